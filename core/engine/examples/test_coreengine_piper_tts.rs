@@ -52,6 +52,7 @@ impl VoiceActivityDetector for StubVad {
         Ok(core_engine::vad::DetectionOutcome {
             is_speech: false,
             confidence: 0.0,
+            speech_event: None,
         })
     }
 }
@@ -44,12 +44,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             text: source_text.to_string(),
             confidence: 1.0,
             is_final: true,
+            stable_prefix: None,
         };
 
         let request = TranslationRequest {
             transcript,
             target_language: "zh".to_string(),
             wait_k: None,
+            decoding: None,
+            committed_prefix: None,
+            source_language: None,
         };
 
         match NmtIncremental::translate(&model, request).await {
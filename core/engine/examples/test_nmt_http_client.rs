@@ -36,9 +36,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             text: "你好，欢迎参加测试。".to_string(),
             confidence: 1.0,
             is_final: true,
+            stable_prefix: None,
         },
         target_language: "en".to_string(),
         wait_k: None,
+        decoding: None,
+        committed_prefix: None,
+        source_language: None,
     };
     
     let response1 = nmt.translate(request1).await?;
@@ -53,9 +57,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             text: "Hello, welcome to the test.".to_string(),
             confidence: 1.0,
             is_final: true,
+            stable_prefix: None,
         },
         target_language: "zh".to_string(),
         wait_k: None,
+        decoding: None,
+        committed_prefix: None,
+        source_language: None,
     };
     
     let response2 = nmt.translate(request2).await?;
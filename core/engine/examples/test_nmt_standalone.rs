@@ -88,9 +88,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 text: text.to_string(),
                 confidence: 1.0,
                 is_final: true,
+                stable_prefix: None,
             },
             target_language: target_lang.to_string(),
             wait_k: None,
+            decoding: None,
+            committed_prefix: None,
+            source_language: None,
         };
 
         let start_time = std::time::Instant::now();
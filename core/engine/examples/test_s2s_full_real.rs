@@ -55,6 +55,7 @@ impl VoiceActivityDetector for StubVad {
             is_boundary: true,  // 标记为边界，触发 ASR 推理
             confidence: 1.0,
             frame,
+            speech_event: None,
         })
     }
 }
@@ -285,12 +286,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         text: source_text.clone(),
         confidence: 1.0,
         is_final: true,
+        stable_prefix: None,
     };
     
     let translation_request = TranslationRequest {
         transcript,
         target_language: "en".to_string(),
         wait_k: None,
+        decoding: None,
+        committed_prefix: None,
+        source_language: None,
     };
     
     let translation_response = NmtIncremental::translate(&*nmt_ref, translation_request).await
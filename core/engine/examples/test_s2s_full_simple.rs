@@ -367,12 +367,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         text: source_text.clone(),
         confidence: 1.0,
         is_final: true,
+        stable_prefix: None,
     };
     
     let translation_request = TranslationRequest {
         transcript,
         target_language: tgt_lang.to_string(),
         wait_k: None,
+        decoding: None,
+        committed_prefix: None,
+        source_language: None,
     };
     
     // 根据翻译方向动态加载 NMT 模型
@@ -177,9 +177,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     text: final_transcript.text.clone(),
                     confidence: 1.0,
                     is_final: true,
+                    stable_prefix: None,
                 },
                 target_language: target_lang.to_string(),
                 wait_k: None,
+                decoding: None,
+                committed_prefix: None,
+                source_language: None,
             };
             
             let translation_result = nmt.translate(translation_request).await?;
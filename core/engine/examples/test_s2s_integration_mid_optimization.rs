@@ -206,9 +206,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         enable_fade: true,
         fade_duration_ms: 20,
         enable_pause: true,
-        pause_duration_ms: 100,
-        sample_rate: 22050,
-        channels: 1,
+        sentence_end_pause_ms: 100,
+        comma_pause_ms: 100,
     };
     
     let engine = CoreEngineBuilder::new()
@@ -226,11 +225,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 7. 启动 Engine
     println!("[6/6] 启动 Engine 并处理音频...");
     engine.boot().await?;
+    let session = engine.create_session(None, None).await;
     
     // 处理音频帧
     let mut asr_results = Vec::new();
     for frame in audio_frames {
-        let result_opt = engine.process_audio_frame(frame, None).await?;
+        let result_opt = engine.process_audio_frame(&session, frame).await?;
         if let Some(result) = result_opt {
             if let Some(ref final_transcript) = result.asr.final_transcript {
                 asr_results.push(final_transcript.text.clone());
@@ -54,6 +54,7 @@ impl VoiceActivityDetector for StubVad {
         Ok(core_engine::vad::DetectionOutcome {
             is_speech: false,
             confidence: 0.0,
+            speech_event: None,
         })
     }
 }
@@ -162,6 +163,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         text: source_text.to_string(),
         source_language: "zh-CN".to_string(),
         target_language: "en-US".to_string(),
+        decoding: None,
+        committed_prefix: None,
     };
     
     let translation_response = engine.nmt().translate(translation_request).await
@@ -70,10 +70,20 @@ class ASRRequest(BaseModel):
     beam_size: int = 5
     vad_filter: bool = False  # VAD is handled by Silero VAD in Rust, disable here to avoid double filtering
     condition_on_previous_text: bool = True  # Use context for better accuracy
+    hotwords: Optional[str] = None  # Comma-separated vocabulary to bias decoding towards (product names, jargon)
+
+class ASRSegment(BaseModel):
+    text: str  # Segment text
+    start: float  # Segment start in seconds
+    end: float  # Segment end in seconds
+    avg_logprob: Optional[float] = None  # Average log probability of the segment's tokens
+    no_speech_prob: Optional[float] = None  # Probability that the segment is not speech
+    compression_ratio: Optional[float] = None  # zlib compression ratio of the segment text
 
 class ASRResponse(BaseModel):
     text: str  # Full transcribed text
     segments: List[str]  # List of segment texts
+    segment_details: List[ASRSegment] = []  # Per-segment timing and decoding statistics (used for confidence filtering)
     language: Optional[str] = None  # Detected language
     duration: float  # Audio duration in seconds
 
@@ -131,6 +141,8 @@ def transcribe(req: ASRRequest):
         logger.info(f"Processing audio: {len(audio)} samples @ {sr}Hz, duration: {len(audio)/sr:.2f}s, dtype: {audio.dtype}")
         if req.prompt:
             logger.info(f"Using context prompt ({len(req.prompt)} chars): \"{req.prompt[:100]}...\"")
+        if req.hotwords:
+            logger.info(f"Using hotwords: \"{req.hotwords[:100]}\"")
         logger.info(f"VAD filter: {req.vad_filter} (disabled because Silero VAD handles boundaries)")
         
         # Run ASR
@@ -142,18 +154,28 @@ def transcribe(req: ASRRequest):
             vad_filter=req.vad_filter,  # False: Silero VAD already handles boundaries
             initial_prompt=req.prompt if req.prompt else None,  # ★ Context support
             condition_on_previous_text=req.condition_on_previous_text,  # ★ Continuous recognition
+            hotwords=req.hotwords or None,  # ★ Vocabulary biasing
         )
         
         asr_elapsed = time.time() - asr_start_time
         
         # Extract text and segments
         segment_texts = []
+        segment_details = []
         full_text_parts = []
         
         for segment in segments:
             segment_text = segment.text.strip()
             if segment_text:
                 segment_texts.append(segment_text)
+                segment_details.append(ASRSegment(
+                    text=segment_text,
+                    start=segment.start,
+                    end=segment.end,
+                    avg_logprob=segment.avg_logprob,
+                    no_speech_prob=segment.no_speech_prob,
+                    compression_ratio=segment.compression_ratio,
+                ))
                 full_text_parts.append(segment_text)
         
         full_text = " ".join(full_text_parts)
@@ -166,6 +188,7 @@ def transcribe(req: ASRRequest):
         return ASRResponse(
             text=full_text,
             segments=segment_texts,
+            segment_details=segment_details,
             language=info.language,
             duration=info.duration,
         )
@@ -6,8 +6,12 @@
 //! - 其他常见的误识别模式
 //! 
 //! 过滤规则从配置文件 `config/asr_filters.json` 加载，在服务启动时初始化。
+//! 
+//! 对提供 token 概率的 ASR 后端（Whisper），`confidence` 模块还根据 token 概率、
+//! 非语音概率和压缩比计算置信度并过滤幻觉。
 
 pub mod config;
+pub mod confidence;
 
 use config::get_config;
 
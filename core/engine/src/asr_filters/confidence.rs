@@ -0,0 +1,187 @@
+//! ASR 置信度计算与幻觉过滤
+//!
+//! Whisper 在静音或噪声上容易产生幻觉（凭空生成的字幕、重复的短语等）。
+//! 这里根据分段的 token 概率、非语音概率和文本压缩比计算置信度：
+//! - 分段置信度 = token 概率的几何平均 × (1 - 非语音概率)，压缩比超过阈值时按比例降低
+//!   （只提供平均对数概率的后端用 exp(平均对数概率)，即同一个几何平均）
+//! - 整句置信度 = 各分段置信度按 token 数加权平均
+//!
+//! 阈值来自 `config/asr_filters.json` 的 `rules.confidence`。
+
+use std::io::Write;
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use super::config::{get_config, ConfidenceRules};
+use crate::types::{StableTranscript, TranscriptSegment};
+
+/// 文本的 zlib 压缩比（原始字节数 / 压缩后字节数），重复越多压缩比越高
+pub fn compression_ratio(text: &str) -> f32 {
+    if text.is_empty() {
+        return 0.0;
+    }
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    if encoder.write_all(text.as_bytes()).is_err() {
+        return 0.0;
+    }
+    match encoder.finish() {
+        Ok(compressed) if !compressed.is_empty() => text.len() as f32 / compressed.len() as f32,
+        _ => 0.0,
+    }
+}
+
+/// 分段 token 概率的几何平均（没有 token 时使用平均对数概率，两者都没有时返回 None）
+pub fn token_probability(segment: &TranscriptSegment) -> Option<f32> {
+    if segment.tokens.is_empty() {
+        return segment.avg_log_probability.map(f32::exp);
+    }
+    let mean_log_prob = segment.tokens.iter()
+        .map(|token| token.probability.max(f32::MIN_POSITIVE).ln())
+        .sum::<f32>() / segment.tokens.len() as f32;
+    Some(mean_log_prob.exp())
+}
+
+/// 计算分段置信度（0.0 ~ 1.0）
+pub fn segment_confidence(segment: &TranscriptSegment) -> f32 {
+    segment_confidence_with_rules(segment, &get_config().rules.confidence)
+}
+
+fn segment_confidence_with_rules(segment: &TranscriptSegment, rules: &ConfidenceRules) -> f32 {
+    let mut confidence = token_probability(segment).unwrap_or(1.0)
+        * (1.0 - segment.no_speech_probability.unwrap_or(0.0));
+    let ratio = compression_ratio(&segment.text);
+    if ratio > rules.compression_ratio_threshold {
+        confidence *= rules.compression_ratio_threshold / ratio;
+    }
+    confidence.clamp(0.0, 1.0)
+}
+
+/// 计算整句置信度（各分段按 token 数加权平均；没有分段时返回 None）
+pub fn transcript_confidence(segments: &[TranscriptSegment]) -> Option<f32> {
+    if segments.is_empty() {
+        return None;
+    }
+    let rules = &get_config().rules.confidence;
+    let (weighted, total_weight) = segments.iter().fold((0.0f32, 0.0f32), |(sum, weight), segment| {
+        let w = segment.tokens.len().max(1) as f32;
+        (sum + segment_confidence_with_rules(segment, rules) * w, weight + w)
+    });
+    Some(weighted / total_weight)
+}
+
+/// 判断分段是否为幻觉
+///
+/// - 文本压缩比超过阈值（重复循环）
+/// - 非语音概率超过阈值，且 token 概率低于最低置信度（静音上生成的文本）
+pub fn is_hallucinated_segment(segment: &TranscriptSegment) -> bool {
+    let rules = &get_config().rules.confidence;
+    if !rules.enabled {
+        return false;
+    }
+    if compression_ratio(&segment.text) > rules.compression_ratio_threshold {
+        return true;
+    }
+    let no_speech = segment.no_speech_probability.unwrap_or(0.0);
+    no_speech > rules.no_speech_threshold
+        && token_probability(segment).unwrap_or(0.0) < rules.min_confidence
+}
+
+/// 丢弃幻觉分段，返回保留的分段
+pub fn suppress_hallucinated_segments(segments: Vec<TranscriptSegment>) -> Vec<TranscriptSegment> {
+    segments.into_iter()
+        .filter(|segment| {
+            let hallucinated = is_hallucinated_segment(segment);
+            if hallucinated {
+                eprintln!("[ASR Filter] 👻 Suppressing hallucinated segment: \"{}\" (no_speech: {:?}, compression ratio: {:.2})",
+                         segment.text, segment.no_speech_probability, compression_ratio(&segment.text));
+            }
+            !hallucinated
+        })
+        .collect()
+}
+
+/// 最终转录的置信度低于阈值时返回该置信度（不提供分段信息的 ASR 后端不过滤，返回 None）
+pub fn low_transcript_confidence(transcript: &StableTranscript) -> Option<f32> {
+    let rules = &get_config().rules.confidence;
+    if !rules.enabled {
+        return None;
+    }
+    transcript_confidence(&transcript.segments).filter(|&confidence| confidence < rules.min_confidence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TranscriptToken;
+
+    fn segment(text: &str, probabilities: &[f32], no_speech: f32) -> TranscriptSegment {
+        TranscriptSegment {
+            text: text.to_string(),
+            start_ms: 0,
+            end_ms: 1000,
+            no_speech_probability: Some(no_speech),
+            avg_log_probability: None,
+            tokens: probabilities.iter().map(|&probability| TranscriptToken {
+                text: "x".to_string(),
+                start_ms: 0,
+                end_ms: 0,
+                probability,
+            }).collect(),
+        }
+    }
+
+    #[test]
+    fn test_compression_ratio_detects_repetition() {
+        assert!(compression_ratio("你好，今天天气怎么样？") < 2.4);
+        assert!(compression_ratio(&"thank you ".repeat(20)) > 2.4);
+    }
+
+    #[test]
+    fn test_confidence_from_token_probabilities() {
+        let confident = segment("hello world", &[0.9, 0.9], 0.01);
+        assert!((segment_confidence(&confident) - 0.9 * 0.99).abs() < 1e-4);
+
+        let uncertain = segment("hello world", &[0.2, 0.3], 0.01);
+        let confidence = transcript_confidence(&[confident, uncertain]).unwrap();
+        assert!(confidence > 0.2 && confidence < 0.9);
+        assert_eq!(transcript_confidence(&[]), None);
+    }
+
+    #[test]
+    fn test_confidence_from_average_log_probability() {
+        let mut averaged = segment("hello world", &[], 0.01);
+        averaged.avg_log_probability = Some(0.9f32.ln());
+        assert!((segment_confidence(&averaged) - 0.9 * 0.99).abs() < 1e-4);
+
+        averaged.avg_log_probability = None;
+        assert_eq!(token_probability(&averaged), None);
+    }
+
+    #[test]
+    fn test_hallucinated_segments_are_suppressed() {
+        let speech = segment("今天开会", &[0.8, 0.9], 0.05);
+        let silence = segment("谢谢观看", &[0.2, 0.1], 0.9);
+        let looping = segment(&"thank you ".repeat(20), &[0.9; 40], 0.05);
+
+        let kept = suppress_hallucinated_segments(vec![speech, silence, looping]);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].text, "今天开会");
+    }
+
+    #[test]
+    fn test_low_confidence_transcript() {
+        let transcript = |segments| StableTranscript {
+            text: "hello".to_string(),
+            speaker_id: None,
+            language: "en".to_string(),
+            segments,
+            language_probabilities: Vec::new(),
+        };
+        let confidence = low_transcript_confidence(&transcript(vec![segment("hello", &[0.1, 0.2], 0.3)]));
+        assert!(confidence.is_some_and(|confidence| confidence < 0.2), "{:?}", confidence);
+        assert_eq!(low_transcript_confidence(&transcript(vec![segment("hello", &[0.9, 0.8], 0.02)])), None);
+        // 没有分段信息时不过滤
+        assert_eq!(low_transcript_confidence(&transcript(Vec::new())), None);
+    }
+}
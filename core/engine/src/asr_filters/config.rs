@@ -58,6 +58,10 @@ pub struct FilterRules {
     /// 字幕志愿者信息的最小长度阈值
     #[serde(default = "default_subtitle_volunteer_min_length")]
     pub subtitle_volunteer_min_length: usize,
+    
+    /// 基于置信度的幻觉过滤规则
+    #[serde(default)]
+    pub confidence: ConfidenceRules,
 }
 
 /// 需要同时包含多个模式的组合
@@ -88,6 +92,26 @@ pub struct ContextAwareThanks {
     pub context_indicators: Vec<String>,
 }
 
+/// 基于置信度的幻觉过滤规则（只对提供 token 概率的 ASR 后端生效，例如 Whisper）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfidenceRules {
+    /// 是否启用置信度过滤
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    
+    /// 最低置信度（整句置信度低于此值时丢弃整句）
+    #[serde(default = "default_min_confidence")]
+    pub min_confidence: f32,
+    
+    /// 非语音概率阈值（分段的非语音概率高于此值且 token 概率低于 `min_confidence` 时丢弃该分段）
+    #[serde(default = "default_no_speech_threshold")]
+    pub no_speech_threshold: f32,
+    
+    /// 压缩比阈值（文本压缩比高于此值说明大量重复，是典型的幻觉，丢弃该分段）
+    #[serde(default = "default_compression_ratio_threshold")]
+    pub compression_ratio_threshold: f32,
+}
+
 fn default_true() -> bool {
     true
 }
@@ -100,6 +124,29 @@ fn default_subtitle_volunteer_min_length() -> usize {
     8
 }
 
+fn default_min_confidence() -> f32 {
+    0.35
+}
+
+fn default_no_speech_threshold() -> f32 {
+    0.6
+}
+
+fn default_compression_ratio_threshold() -> f32 {
+    2.4
+}
+
+impl Default for ConfidenceRules {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_confidence: default_min_confidence(),
+            no_speech_threshold: default_no_speech_threshold(),
+            compression_ratio_threshold: default_compression_ratio_threshold(),
+        }
+    }
+}
+
 impl Default for ContextAwareThanks {
     fn default() -> Self {
         Self {
@@ -124,6 +171,7 @@ impl Default for FilterRules {
             meaningless_patterns: Vec::new(),
             context_aware_thanks: ContextAwareThanks::default(),
             subtitle_volunteer_min_length: 8,
+            confidence: ConfidenceRules::default(),
         }
     }
 }
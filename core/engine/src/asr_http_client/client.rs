@@ -57,6 +57,7 @@ impl AsrHttpClient {
     /// * `audio_data` - Audio data as bytes (WAV format, 16kHz mono)
     /// * `context_prompt` - Context prompt (previous sentences)
     /// * `language` - Language code (optional, None for auto-detect)
+    /// * `hotwords` - Vocabulary to bias decoding towards (empty for no biasing)
     /// 
     /// # Returns
     /// Returns the transcribed text and segments
@@ -65,6 +66,7 @@ impl AsrHttpClient {
         audio_data: Vec<u8>,
         context_prompt: String,
         language: Option<String>,
+        hotwords: &[String],
     ) -> EngineResult<AsrHttpResponse> {
         // Encode audio to base64
         let audio_b64 = BASE64.encode(&audio_data);
@@ -80,6 +82,7 @@ impl AsrHttpClient {
             beam_size: 5,
             vad_filter: false,  // Disable VAD in faster-whisper (we use Silero VAD for boundaries)
             condition_on_previous_text: true,  // 启用条件生成，提高连续识别准确度
+            hotwords: (!hotwords.is_empty()).then(|| hotwords.join(", ")),
         };
         
         // Send request
@@ -90,6 +93,9 @@ impl AsrHttpClient {
         if !request.prompt.is_empty() {
             eprintln!("[ASR] 📚 Context prompt: \"{}\"", request.prompt.chars().take(100).collect::<String>());
         }
+        if let Some(ref hotwords) = request.hotwords {
+            eprintln!("[ASR] 🔤 Hotwords: \"{}\"", hotwords.chars().take(100).collect::<String>());
+        }
         
         let response = self.client
             .post(&url)
@@ -129,5 +135,27 @@ mod tests {
         let client = AsrHttpClient::new("http://127.0.0.1:6006".to_string(), 30);
         assert_eq!(client.service_url, "http://127.0.0.1:6006");
     }
+
+    #[test]
+    fn test_response_segment_details() {
+        let response: AsrHttpResponse = serde_json::from_str(r#"{
+            "text": "你好 世界", "segments": ["你好", "世界"], "language": "zh", "duration": 2.0,
+            "segment_details": [
+                {"text": "你好", "start": 0.0, "end": 1.2, "avg_logprob": -0.1, "no_speech_prob": 0.02, "compression_ratio": 0.5},
+                {"text": "世界", "start": 1.2, "end": 2.0, "avg_logprob": null, "no_speech_prob": null, "compression_ratio": null}
+            ]
+        }"#).unwrap();
+        let segments: Vec<_> = response.segment_details.iter().map(|s| s.to_transcript_segment()).collect();
+        assert_eq!(segments[0].start_ms, 0);
+        assert_eq!(segments[0].end_ms, 1200);
+        assert_eq!(segments[0].avg_log_probability, Some(-0.1));
+        assert_eq!(segments[0].no_speech_probability, Some(0.02));
+        assert_eq!(segments[1].avg_log_probability, None);
+
+        // 旧版服务不返回 segment_details
+        let legacy: AsrHttpResponse = serde_json::from_str(
+            r#"{"text": "你好", "segments": ["你好"], "language": "zh", "duration": 1.0}"#).unwrap();
+        assert!(legacy.segment_details.is_empty());
+    }
 }
 
@@ -5,5 +5,5 @@ pub mod client;
 pub mod types;
 
 pub use client::AsrHttpClient;
-pub use types::{AsrHttpRequest, AsrHttpResponse};
+pub use types::{AsrHttpRequest, AsrHttpResponse, AsrHttpSegment};
 
@@ -3,6 +3,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::types::TranscriptSegment;
+
 /// Request to ASR service
 #[derive(Debug, Clone, Serialize)]
 pub struct AsrHttpRequest {
@@ -20,6 +22,9 @@ pub struct AsrHttpRequest {
     pub vad_filter: bool,
     /// Use context for better accuracy
     pub condition_on_previous_text: bool,
+    /// Comma-separated hotwords (product names, jargon) to bias decoding towards, None for no biasing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hotwords: Option<String>,
 }
 
 impl Default for AsrHttpRequest {
@@ -32,6 +37,7 @@ impl Default for AsrHttpRequest {
             beam_size: 5,
             vad_filter: false,  // VAD is handled by Silero VAD, disable here to avoid double filtering
             condition_on_previous_text: true,
+            hotwords: None,
         }
     }
 }
@@ -43,9 +49,43 @@ pub struct AsrHttpResponse {
     pub text: String,
     /// List of segment texts
     pub segments: Vec<String>,
+    /// Per-segment timing and decoding statistics (empty when talking to an older service)
+    #[serde(default)]
+    pub segment_details: Vec<AsrHttpSegment>,
     /// Detected language
     pub language: Option<String>,
     /// Audio duration in seconds
     pub duration: f32,
 }
 
+
+/// Per-segment details returned by the ASR service
+#[derive(Debug, Clone, Deserialize)]
+pub struct AsrHttpSegment {
+    /// Segment text
+    pub text: String,
+    /// Segment start in seconds
+    pub start: f32,
+    /// Segment end in seconds
+    pub end: f32,
+    /// Average log probability of the segment's tokens
+    pub avg_logprob: Option<f32>,
+    /// Probability that the segment is not speech
+    pub no_speech_prob: Option<f32>,
+    /// zlib compression ratio of the segment text (same formula as `asr_filters::confidence::compression_ratio`)
+    pub compression_ratio: Option<f32>,
+}
+
+impl AsrHttpSegment {
+    /// Convert to a transcript segment so it can go through `asr_filters::confidence`
+    pub fn to_transcript_segment(&self) -> TranscriptSegment {
+        TranscriptSegment {
+            text: self.text.clone(),
+            start_ms: (self.start.max(0.0) * 1000.0).round() as u64,
+            end_ms: (self.end.max(0.0) * 1000.0).round() as u64,
+            no_speech_probability: self.no_speech_prob,
+            avg_log_probability: self.avg_logprob,
+            tokens: Vec::new(),
+        }
+    }
+}
@@ -4,7 +4,15 @@
 use async_trait::async_trait;
 use crate::error::EngineResult;
 use crate::types::AudioFrame;
-use crate::asr_streaming::AsrResult;
+use crate::asr_streaming::{AsrResult, AsrStreamingConfig, AsrStreamingMode};
+use crate::error::EngineError;
+use crate::types::PartialTranscript;
+
+/// Opaque per-session streaming state (partial timing, sliding window, LocalAgreement history)
+///
+/// The ASR instance is shared by all sessions, so the caller keeps this state on the session:
+/// it is restored before each inference and taken back afterwards.
+pub type AsrSessionState = Box<dyn std::any::Any + Send>;
 
 /// Extension trait for ASR streaming implementations that support
 /// frame accumulation and boundary-based inference
@@ -24,8 +32,56 @@ pub trait AsrStreamingExt: Send + Sync {
     
     /// Get the current language setting
     fn get_language(&self) -> EngineResult<Option<String>>;
+
+    /// Restrict automatic language detection to the given languages (empty = no restriction)
+    ///
+    /// Only used when no language is set. Implementations without language identification ignore it.
+    fn set_language_candidates(&self, _candidates: Vec<String>) -> EngineResult<()> {
+        Ok(())
+    }
+
+    /// Bias recognition towards the given vocabulary (product names, jargon; empty = no biasing)
+    ///
+    /// Whisper-based implementations feed the words into the initial prompt.
+    /// Implementations without prompt support ignore it.
+    fn set_hotwords(&self, _hotwords: Vec<String>) -> EngineResult<()> {
+        Ok(())
+    }
     
     /// Infer on boundary (when VAD detects a speech boundary)
     async fn infer_on_boundary(&self) -> EngineResult<AsrResult>;
+
+    /// Whether partial (streaming) inference is enabled
+    fn is_streaming_enabled(&self) -> bool {
+        false
+    }
+
+    /// Infer a partial transcript from the accumulated frames (streaming mode)
+    async fn infer_partial(&self, _current_timestamp_ms: u64) -> EngineResult<Option<PartialTranscript>> {
+        Ok(None)
+    }
+
+    /// Select the streaming mode (off / full / local agreement)
+    ///
+    /// Implementations without partial inference only accept `AsrStreamingMode::Off`.
+    fn set_streaming_config(&self, config: &AsrStreamingConfig) -> EngineResult<()> {
+        match config.mode {
+            AsrStreamingMode::Off => Ok(()),
+            mode => Err(EngineError::new(format!("ASR streaming mode {:?} is not supported", mode))),
+        }
+    }
+
+    /// Take the streaming state of the session that was just processed
+    ///
+    /// Leaves a fresh state behind so that the next session starts clean.
+    /// Stateless implementations return `None`.
+    fn take_session_state(&self) -> EngineResult<Option<AsrSessionState>> {
+        Ok(None)
+    }
+
+    /// Restore a session's streaming state before inference (`None` = start a fresh state)
+    fn restore_session_state(&self, _state: Option<AsrSessionState>) -> EngineResult<()> {
+        Ok(())
+    }
 }
 
@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use crate::error::EngineResult;
 use crate::types::{AudioFrame, PartialTranscript, StableTranscript};
 
-pub use ext::AsrStreamingExt;
+pub use ext::{AsrSessionState, AsrStreamingExt};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AsrRequest {
@@ -20,9 +20,57 @@ pub struct AsrResult {
     pub final_transcript: Option<StableTranscript>,
 }
 
+/// 流式识别模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AsrStreamingMode {
+    /// 只在边界处推理（默认）
+    #[default]
+    Off,
+    /// 定期对本句全部音频推理，输出部分结果
+    Full,
+    /// 滑动窗口 + LocalAgreement：只推理未提交的音频，部分结果带已提交前缀
+    LocalAgreement,
+}
+
+/// 流式识别配置（配置文件 `[asr_streaming]` 段）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AsrStreamingConfig {
+    pub mode: AsrStreamingMode,
+    /// 部分结果更新间隔（秒）
+    pub partial_interval_seconds: f64,
+    /// LocalAgreement：连续多少个假设一致才提交
+    pub agreement_count: usize,
+    /// LocalAgreement：滑动窗口最大长度（秒）
+    pub max_window_seconds: f64,
+    /// LocalAgreement：作为提示词的已提交文本最大字符数
+    pub prompt_max_chars: usize,
+}
+
+impl Default for AsrStreamingConfig {
+    fn default() -> Self {
+        Self {
+            mode: AsrStreamingMode::Off,
+            partial_interval_seconds: 1.0,
+            agreement_count: 2,
+            max_window_seconds: 15.0,
+            prompt_max_chars: 200,
+        }
+    }
+}
+
 #[async_trait]
 pub trait AsrStreaming: Send + Sync {
     async fn initialize(&self) -> EngineResult<()>;
     async fn infer(&self, request: AsrRequest) -> EngineResult<AsrResult>;
     async fn finalize(&self) -> EngineResult<()>;
+
+    /// 获取扩展能力（帧累积、边界推理、语言设置等）
+    ///
+    /// 默认返回 None，表示只支持 `infer`。支持扩展能力的实现应返回 `Some(self)`，
+    /// CoreEngine 会据此选择处理路径，无需对具体类型做任何转换。
+    fn as_ext(&self) -> Option<&dyn AsrStreamingExt> {
+        None
+    }
 }
@@ -5,18 +5,97 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use anyhow::{Result, anyhow};
 use whisper_rs::{
-    WhisperContext, WhisperContextParameters, 
+    WhisperContext, WhisperContextParameters, WhisperState,
     FullParams, SamplingStrategy,
 };
 
+use crate::asr_filters::confidence::{suppress_hallucinated_segments, transcript_confidence};
 use crate::asr_whisper::audio_preprocessing::{preprocess_audio_frame, accumulate_audio_frames};
-use crate::types::AudioFrame;
+use crate::types::{AudioFrame, LanguageProbability, TranscriptSegment, TranscriptToken};
+use crate::language_registry::{self, LanguageBackend};
+
+/// Whisper 转录结果（含分段和 token 级别的时间戳）
+#[derive(Debug, Clone, Default)]
+pub struct WhisperTranscription {
+    /// 各分段文本以空格拼接后的完整文本
+    pub text: String,
+    /// 检测到的语言（指定了语言时即为该语言）
+    pub language: Option<String>,
+    /// 自动检测语言时的候选语言（按概率降序）
+    pub language_probabilities: Vec<LanguageProbability>,
+    /// 已过滤幻觉分段后的分段
+    pub segments: Vec<TranscriptSegment>,
+    /// 整句置信度（由 token 概率、非语音概率和压缩比计算；没有分段时为 None）
+    pub confidence: Option<f32>,
+}
+
+/// 自动检测语言时返回的候选语言数量
+const DETECTED_LANGUAGE_TOP_N: usize = 3;
+
+/// 初始提示词中热词部分的最大字符数（Whisper 提示词最多约 224 个 token）
+const MAX_HOTWORDS_PROMPT_CHARS: usize = 150;
+
+/// whisper.cpp 的时间戳单位是 10ms
+fn centiseconds_to_ms(t: i64) -> u64 {
+    t.max(0) as u64 * 10
+}
+
+/// Whisper 输出的原始 token（文本是 BPE 字节，不一定是完整的 UTF-8）
+struct RawToken {
+    bytes: Vec<u8>,
+    start_ms: u64,
+    end_ms: u64,
+    probability: f32,
+}
+
+/// 合并字节不完整的 token
+///
+/// 一个汉字可能被切分成多个 BPE token（单个 token 不是完整的 UTF-8），
+/// 这里把字节不完整的 token 与后续 token 合并，时间取首尾，概率取乘积
+fn merge_utf8_tokens(raw_tokens: Vec<RawToken>) -> Vec<TranscriptToken> {
+    let mut tokens = Vec::new();
+    let mut pending: Option<(Vec<u8>, TranscriptToken)> = None;
+    for raw in raw_tokens {
+        let (mut buffer, mut merged) = pending.take().unwrap_or_else(|| (Vec::new(), TranscriptToken {
+            text: String::new(),
+            start_ms: raw.start_ms,
+            end_ms: 0,
+            probability: 1.0,
+        }));
+        buffer.extend_from_slice(&raw.bytes);
+        merged.end_ms = raw.end_ms;
+        merged.probability *= raw.probability;
+        match String::from_utf8(buffer) {
+            Ok(text) => {
+                merged.text = text;
+                tokens.push(merged);
+            }
+            Err(e) => pending = Some((e.into_bytes(), merged)),
+        }
+    }
+    if let Some((buffer, mut merged)) = pending {
+        merged.text = String::from_utf8_lossy(&buffer).into_owned();
+        tokens.push(merged);
+    }
+    tokens
+}
+
+/// 推理线程数：使用所有可用的 CPU 核心（留一个给系统）
+fn inference_threads() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get().saturating_sub(1).max(1))
+        .unwrap_or(4)
+}
 
 /// Whisper ASR 推理引擎
 pub struct WhisperAsrEngine {
     ctx: Arc<WhisperContext>,
     model_path: PathBuf,
     language: Option<String>,
+    /// 自动检测语言时的候选范围（Whisper 语言代码，空表示不限制）
+    language_candidates: Vec<String>,
+    /// 热词（写入初始提示词，使识别偏向这些词）
+    hotwords: Vec<String>,
 }
 
 impl WhisperAsrEngine {
@@ -44,6 +123,8 @@ impl WhisperAsrEngine {
             ctx: Arc::new(ctx),
             model_path: model_path.to_path_buf(),
             language: None,
+            language_candidates: Vec::new(),
+            hotwords: Vec::new(),
         })
     }
 
@@ -75,9 +156,10 @@ impl WhisperAsrEngine {
     /// 设置语言
     /// 
     /// # Arguments
-    /// * `language` - 语言代码（如 "en", "zh"），`None` 表示自动检测
+    /// * `language` - 语言代码（如 "en", "zh"），也可以是 BCP-47 标签（如 "zh-Hant"），经语言注册表标准化；`None` 表示自动检测
     pub fn set_language(&mut self, language: Option<String>) {
-        self.language = language;
+        self.language = language
+            .map(|lang| language_registry::global().normalize(&lang, LanguageBackend::Whisper));
     }
 
     /// 获取当前语言设置
@@ -85,6 +167,110 @@ impl WhisperAsrEngine {
         self.language.clone()
     }
 
+    /// 限制自动检测语言的候选范围（例如中英双语会议只在 zh/en 之间选择）
+    /// 
+    /// # Arguments
+    /// * `candidates` - 候选语言（BCP-47 标签，经语言注册表标准化）；空表示不限制
+    pub fn set_language_candidates(&mut self, candidates: Vec<String>) {
+        let registry = language_registry::global();
+        self.language_candidates = candidates.iter()
+            .map(|lang| registry.normalize(lang, LanguageBackend::Whisper))
+            .collect();
+    }
+
+    /// 获取自动检测语言的候选范围
+    pub fn language_candidates(&self) -> &[String] {
+        &self.language_candidates
+    }
+
+    /// 设置热词（产品名、专业术语等 Whisper 容易听错的词）
+    /// 
+    /// 热词会放在初始提示词的开头，Whisper 在解码时会倾向于使用提示词中出现过的写法
+    pub fn set_hotwords(&mut self, hotwords: Vec<String>) {
+        self.hotwords = hotwords.into_iter()
+            .map(|word| word.replace('\0', "").trim().to_string())
+            .filter(|word| !word.is_empty())
+            .collect();
+    }
+
+    /// 获取热词
+    pub fn hotwords(&self) -> &[String] {
+        &self.hotwords
+    }
+
+    /// 拼接初始提示词：热词（总长度受限）+ 上文
+    fn initial_prompt(&self, prompt: Option<&str>) -> Option<String> {
+        let mut hotwords = String::new();
+        for word in &self.hotwords {
+            if hotwords.chars().count() + word.chars().count() > MAX_HOTWORDS_PROMPT_CHARS {
+                break;
+            }
+            if !hotwords.is_empty() {
+                hotwords.push_str(", ");
+            }
+            hotwords.push_str(word);
+        }
+        let prompt = prompt.map(str::trim).filter(|prompt| !prompt.is_empty());
+        match (hotwords.is_empty(), prompt) {
+            (true, None) => None,
+            (true, Some(prompt)) => Some(prompt.to_string()),
+            (false, None) => Some(format!("{}.", hotwords)),
+            (false, Some(prompt)) => Some(format!("{}. {}", hotwords, prompt)),
+        }
+    }
+
+    /// 识别音频的语种
+    /// 
+    /// # Arguments
+    /// * `audio_data` - 预处理后的音频数据（16kHz 单声道 PCM f32）
+    /// * `top_n` - 返回的候选语言数量
+    /// 
+    /// # Returns
+    /// 返回按概率降序排列的候选语言（设置了候选范围时只在范围内选择，并重新归一化概率）
+    pub fn detect_language(&self, audio_data: &[f32], top_n: usize) -> Result<Vec<LanguageProbability>> {
+        let mut state = self.ctx.create_state()
+            .map_err(|e| anyhow!("Failed to create Whisper state: {:?}", e))?;
+        self.detect_language_with_state(&mut state, audio_data, top_n)
+    }
+
+    fn detect_language_with_state(
+        &self,
+        state: &mut WhisperState,
+        audio_data: &[f32],
+        top_n: usize,
+    ) -> Result<Vec<LanguageProbability>> {
+        let num_threads = inference_threads();
+        state.pcm_to_mel(audio_data, num_threads)
+            .map_err(|e| anyhow!("Failed to compute mel spectrogram: {:?}", e))?;
+        let (_, probabilities) = state.lang_detect(0, num_threads)
+            .map_err(|e| anyhow!("Failed to detect language: {:?}", e))?;
+
+        let mut candidates: Vec<LanguageProbability> = probabilities.iter()
+            .enumerate()
+            .filter_map(|(id, &probability)| {
+                let language = whisper_rs::get_lang_str(id as i32)?;
+                let allowed = self.language_candidates.is_empty()
+                    || self.language_candidates.iter().any(|candidate| candidate == language);
+                allowed.then(|| LanguageProbability {
+                    language: language.to_string(),
+                    probability,
+                })
+            })
+            .collect();
+
+        if !self.language_candidates.is_empty() {
+            let total: f32 = candidates.iter().map(|c| c.probability).sum();
+            if total > 0.0 {
+                for candidate in &mut candidates {
+                    candidate.probability /= total;
+                }
+            }
+        }
+        candidates.sort_by(|a, b| b.probability.total_cmp(&a.probability));
+        candidates.truncate(top_n);
+        Ok(candidates)
+    }
+
     /// 对完整音频进行转录
     /// 
     /// # Arguments
@@ -93,23 +279,66 @@ impl WhisperAsrEngine {
     /// # Returns
     /// 返回 (转录文本, 检测到的语言)
     pub fn transcribe_full(&self, audio_data: &[f32]) -> Result<(String, Option<String>)> {
+        let transcription = self.transcribe_detailed(audio_data)?;
+        Ok((transcription.text, transcription.language))
+    }
+
+    /// 对完整音频进行转录，返回分段和 token 级别的时间戳与概率
+    /// 
+    /// # Arguments
+    /// * `audio_data` - 预处理后的音频数据（16kHz 单声道 PCM f32）
+    /// 
+    /// # Returns
+    /// 返回 `WhisperTranscription`（时间戳为相对于 `audio_data` 起点的毫秒数）
+    pub fn transcribe_detailed(&self, audio_data: &[f32]) -> Result<WhisperTranscription> {
+        self.transcribe_with_prompt(audio_data, None)
+    }
+
+    /// 带初始提示词转录（流式识别时用已提交的文本作为上文，保持前后一致；热词总是放在提示词开头）
+    /// 
+    /// # Arguments
+    /// * `audio_data` - 预处理后的音频数据（16kHz 单声道 PCM f32）
+    /// * `prompt` - 初始提示词（None 或空字符串表示不使用）
+    /// 
+    /// # Returns
+    /// 返回 `WhisperTranscription`（时间戳为相对于 `audio_data` 起点的毫秒数）
+    pub fn transcribe_with_prompt(&self, audio_data: &[f32], prompt: Option<&str>) -> Result<WhisperTranscription> {
         // 创建推理状态
         let mut state = self.ctx.create_state()
             .map_err(|e| anyhow!("Failed to create Whisper state: {:?}", e))?;
 
+        // 未指定语言时先识别语种，再用识别出的语言转录
+        // （而不是让 Whisper 在 full() 内部自动检测，这样可以拿到候选语言的概率，并限制候选范围）
+        let mut language_probabilities = Vec::new();
+        let mut detected_lang = match self.language {
+            Some(ref lang) => Some(lang.clone()),
+            None => match self.detect_language_with_state(&mut state, audio_data, DETECTED_LANGUAGE_TOP_N) {
+                Ok(candidates) => {
+                    language_probabilities = candidates;
+                    language_probabilities.first().map(|c| c.language.clone())
+                }
+                Err(e) => {
+                    eprintln!("[ASR] ⚠️ Language detection failed, falling back to Whisper auto-detect: {}", e);
+                    None
+                }
+            },
+        };
+        if let Some(top) = language_probabilities.first() {
+            eprintln!("[ASR] 🌐 Detected language: {} (p={:.2}, candidates: {:?})",
+                     top.language, top.probability,
+                     language_probabilities.iter().map(|c| c.language.as_str()).collect::<Vec<_>>());
+        }
+
         // 配置推理参数
         let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
         
         // 设置语言
-        if let Some(ref lang) = self.language {
+        if let Some(ref lang) = detected_lang {
             params.set_language(Some(lang.as_str()));
         }
         
         // 设置其他参数
-        // 使用所有可用的 CPU 核心（留一个给系统）
-        let num_threads = std::thread::available_parallelism()
-            .map(|n| n.get().saturating_sub(1).max(1))
-            .unwrap_or(4);
+        let num_threads = inference_threads();
         params.set_n_threads(num_threads as i32);
         eprintln!("[ASR] Using {} CPU threads for inference", num_threads);
         params.set_translate(false);
@@ -117,46 +346,86 @@ impl WhisperAsrEngine {
         params.set_print_special(false);
         params.set_print_realtime(false);
         params.set_print_timestamps(false);
+        let initial_prompt = self.initial_prompt(prompt);
+        if let Some(ref initial_prompt) = initial_prompt {
+            params.set_initial_prompt(initial_prompt);
+        }
+        // 启用 token 级别时间戳（用于逐词高亮和字幕对齐）
+        params.set_token_timestamps(true);
 
         // 运行推理
         state.full(params, audio_data)
             .map_err(|e| anyhow!("Failed to run inference: {:?}", e))?;
 
-        // 提取检测到的语言
-        // Whisper 会在推理后设置检测到的语言
-        let detected_lang = if self.language.is_none() {
-            // 如果使用自动检测，尝试从 state 中获取检测到的语言
-            // 注意：whisper_rs 可能不直接提供这个 API，我们需要从 segment 中推断
-            // 或者使用其他方法
-            None  // 暂时返回 None，后续可以从 segment 中提取
-        } else {
-            self.language.clone()
-        };
+        // 语种识别失败时，使用 Whisper 在推理中自动检测的语言
+        if detected_lang.is_none() {
+            detected_lang = whisper_rs::get_lang_str(state.full_lang_id_from_state())
+                .map(|lang| lang.to_string());
+        }
 
         // 提取结果
+        // id >= EOT 的都是特殊 token（SOT、语言、任务、时间戳等），不输出
+        let token_eot = self.ctx.token_eot();
         let num_segments = state.full_n_segments();
-        let mut full_text = String::new();
+        let mut segments = Vec::new();
 
         for i in 0..num_segments {
-            if let Some(segment) = state.get_segment(i) {
-                // 从 Debug 输出中提取文本（因为字段可能是私有的）
-                let segment_debug = format!("{:?}", segment);
-                
-                if let Some(start_idx) = segment_debug.find("text: Ok(\"") {
-                    let text_start = start_idx + 10;
-                    if let Some(end_idx) = segment_debug[text_start..].find("\")") {
-                        let text = &segment_debug[text_start..text_start + end_idx];
-                        let text_trimmed = text.trim();
-                        if !text_trimmed.is_empty() {
-                            full_text.push_str(text_trimmed);
-                            full_text.push(' ');
-                        }
-                    }
+            let Some(segment) = state.get_segment(i) else {
+                continue;
+            };
+            let text = segment.to_str_lossy()
+                .map_err(|e| anyhow!("Failed to read segment {} text: {:?}", i, e))?;
+            let text = text.trim();
+            if text.is_empty() {
+                continue;
+            }
+
+            let mut raw_tokens = Vec::new();
+            for j in 0..segment.n_tokens() {
+                let Some(token) = segment.get_token(j) else {
+                    continue;
+                };
+                if token.token_id() >= token_eot {
+                    continue;
                 }
+                let data = token.token_data();
+                let bytes = token.to_bytes()
+                    .map_err(|e| anyhow!("Failed to read token text: {:?}", e))?;
+                raw_tokens.push(RawToken {
+                    bytes: bytes.to_vec(),
+                    start_ms: centiseconds_to_ms(data.t0),
+                    end_ms: centiseconds_to_ms(data.t1),
+                    probability: data.p,
+                });
             }
+            let tokens = merge_utf8_tokens(raw_tokens);
+
+            segments.push(TranscriptSegment {
+                text: text.to_string(),
+                start_ms: centiseconds_to_ms(segment.start_timestamp()),
+                end_ms: centiseconds_to_ms(segment.end_timestamp()),
+                no_speech_probability: Some(segment.no_speech_probability()),
+                avg_log_probability: None,
+                tokens,
+            });
         }
 
-        Ok((full_text.trim().to_string(), detected_lang))
+        // 丢弃幻觉分段（静音上生成的文本、重复循环）
+        let segments = suppress_hallucinated_segments(segments);
+        let confidence = transcript_confidence(&segments);
+
+        let text = segments.iter()
+            .map(|segment| segment.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Ok(WhisperTranscription {
+            text,
+            language: detected_lang,
+            language_probabilities,
+            segments,
+            confidence,
+        })
     }
 
     /// 从 AudioFrame 转录
@@ -309,5 +578,30 @@ mod tests {
                 result_lower.contains("what you can do for your country"),
                 "转录结果应该包含 JFK 演讲的关键内容");
     }
-}
 
+    #[test]
+    fn test_merge_utf8_tokens_joins_split_characters() {
+        let token = |bytes: &[u8], start_ms, end_ms, probability| RawToken {
+            bytes: bytes.to_vec(),
+            start_ms,
+            end_ms,
+            probability,
+        };
+        // "你" = E4 BD A0，被切成两个 token；"好" 是一个完整的 token
+        let tokens = merge_utf8_tokens(vec![
+            token(&[0xE4, 0xBD], 0, 100, 0.5),
+            token(&[0xA0], 100, 200, 0.8),
+            token("好".as_bytes(), 200, 300, 0.9),
+            token(&[0xE5], 300, 400, 0.5),
+        ]);
+
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(tokens[0].text, "你");
+        assert_eq!((tokens[0].start_ms, tokens[0].end_ms), (0, 200));
+        assert!((tokens[0].probability - 0.4).abs() < 1e-6);
+        assert_eq!(tokens[1].text, "好");
+        // 结尾不完整的字节用替换字符输出，不丢弃
+        assert_eq!(tokens[2].text, "\u{FFFD}");
+        assert_eq!((tokens[2].start_ms, tokens[2].end_ms), (300, 400));
+    }
+}
@@ -6,9 +6,10 @@ use async_trait::async_trait;
 
 use crate::asr_streaming::{AsrRequest, AsrResult, AsrStreaming, AsrStreamingExt};
 use crate::asr_filters::is_meaningless_transcript_with_context;
+use crate::asr_filters::confidence::transcript_confidence;
 use crate::asr_http_client::AsrHttpClient;
 use crate::error::{EngineError, EngineResult};
-use crate::types::{AudioFrame, PartialTranscript, StableTranscript};
+use crate::types::{AudioFrame, PartialTranscript, StableTranscript, TranscriptSegment};
 use crate::asr_whisper::audio_preprocessing::{preprocess_audio_frame, accumulate_audio_frames};
 
 /// 流式推理配置（基于自然停顿）
@@ -43,6 +44,8 @@ pub struct FasterWhisperAsrStreaming {
     context_cache: Arc<Mutex<Vec<String>>>,
     /// 语言设置（可选）
     language: Arc<Mutex<Option<String>>>,
+    /// 热词（随请求发送给 faster-whisper，使识别偏向这些词）
+    hotwords: Arc<Mutex<Vec<String>>>,
 }
 
 impl FasterWhisperAsrStreaming {
@@ -98,6 +101,7 @@ impl FasterWhisperAsrStreaming {
             })),
             context_cache: Arc::new(Mutex::new(Vec::new())),
             language: Arc::new(Mutex::new(None)),
+            hotwords: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
@@ -264,18 +268,25 @@ impl FasterWhisperAsrStreaming {
         let wav_bytes = self.audio_to_wav_bytes(&audio_data)?;
         eprintln!("[ASR] 📦 Converted audio to WAV: {} bytes (sending to Faster-Whisper service)", wav_bytes.len());
         
-        // 4. 获取语言设置
+        // 4. 获取语言和热词设置
         let language = {
             let lang = self.language.lock()
                 .map_err(|e| EngineError::new(format!("Failed to lock language: {}", e)))?;
             lang.clone()
         };
         
+        let hotwords = {
+            let hotwords = self.hotwords.lock()
+                .map_err(|e| EngineError::new(format!("Failed to lock hotwords: {}", e)))?;
+            hotwords.clone()
+        };
+        
         // 5. 调用 HTTP 服务进行转录
         let asr_response = self.http_client.transcribe(
             wav_bytes,
             context_prompt,
             language,
+            &hotwords,
         ).await.map_err(|e| {
             eprintln!("[ASR] ❌ HTTP request failed: {}", e);
             // 注意：缓冲区已经在步骤1中清空了，这里不需要再次清空
@@ -290,6 +301,15 @@ impl FasterWhisperAsrStreaming {
                 eprintln!("[ASR]   Segment {}: \"{}\"", i + 1, seg.chars().take(80).collect::<String>());
             }
         }
+        for detail in &asr_response.segment_details {
+            eprintln!("[ASR]   Segment stats: avg_logprob: {:?}, no_speech: {:?}, compression ratio: {:?}",
+                     detail.avg_logprob, detail.no_speech_prob, detail.compression_ratio);
+        }
+        // 旧版服务不返回分段详情，此时 segments 为空，置信度未知（下游不按置信度过滤）
+        let segments: Vec<TranscriptSegment> = asr_response.segment_details.iter()
+            .map(|detail| detail.to_transcript_segment())
+            .collect();
+        let confidence = transcript_confidence(&segments);
         eprintln!("[ASR] 📝 Final transcript: \"{}\"", transcript_text.chars().take(100).collect::<String>());
         if let Some(ref lang) = asr_response.language {
             eprintln!("[ASR] 🌐 Detected language: {}", lang);
@@ -379,13 +399,16 @@ impl FasterWhisperAsrStreaming {
         let result = AsrResult {
             partial: Some(PartialTranscript {
                 text: transcript_text.clone(),
-                confidence: 0.95,  // faster-whisper 不直接提供置信度，使用默认值
+                confidence: confidence.unwrap_or_default(),
                 is_final: true,
+                stable_prefix: None,
             }),
             final_transcript: Some(StableTranscript {
                 text: transcript_text,
                 speaker_id: None,
                 language: asr_response.language.unwrap_or_else(|| "unknown".to_string()),
+                segments,
+                language_probabilities: Vec::new(),
             }),
         };
 
@@ -417,6 +440,17 @@ impl FasterWhisperAsrStreaming {
         Ok(lang.clone())
     }
 
+    /// 设置热词（空表示不使用）
+    pub fn set_hotwords(&self, hotwords: Vec<String>) -> EngineResult<()> {
+        let mut current = self.hotwords.lock()
+            .map_err(|e| EngineError::new(format!("Failed to lock hotwords: {}", e)))?;
+        *current = hotwords.into_iter()
+            .map(|word| word.trim().to_string())
+            .filter(|word| !word.is_empty())
+            .collect();
+        Ok(())
+    }
+
     /// 累积音频帧到缓冲区（用于 VAD 集成模式）
     /// 
     /// # Arguments
@@ -536,6 +570,10 @@ impl AsrStreaming for FasterWhisperAsrStreaming {
         self.clear_buffer()?;
         Ok(())
     }
+
+    fn as_ext(&self) -> Option<&dyn AsrStreamingExt> {
+        Some(self)
+    }
 }
 
 #[async_trait]
@@ -560,6 +598,10 @@ impl AsrStreamingExt for FasterWhisperAsrStreaming {
         FasterWhisperAsrStreaming::get_language(self)
     }
 
+    fn set_hotwords(&self, hotwords: Vec<String>) -> EngineResult<()> {
+        FasterWhisperAsrStreaming::set_hotwords(self, hotwords)
+    }
+
     async fn infer_on_boundary(&self) -> EngineResult<AsrResult> {
         FasterWhisperAsrStreaming::infer_on_boundary(self).await
     }
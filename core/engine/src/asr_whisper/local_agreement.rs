@@ -0,0 +1,293 @@
+// core/engine/src/asr_whisper/local_agreement.rs
+// LocalAgreement 提交策略（流式 ASR）
+//
+// 滑动窗口流式识别中，每次推理都会得到窗口内音频的一个新假设（hypothesis）。
+// 假设的末尾不稳定（说话还没结束），直接输出会导致部分结果来回跳动。
+// LocalAgreement-n：只有连续 n 个假设都一致的前缀才被提交（不再改变），
+// 其余部分作为易变的尾部输出。
+
+use std::collections::VecDeque;
+
+use crate::types::TranscriptSegment;
+
+/// 去除与已提交文本重叠时最多比较的 n-gram 长度
+const MAX_OVERLAP_NGRAM: usize = 5;
+
+/// 判断重叠时允许的时间误差（毫秒）
+const OVERLAP_TOLERANCE_MS: u64 = 100;
+
+/// 带时间戳的识别单元（拉丁字母文字为单词，中日韩文字为单字）
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimedWord {
+    pub text: String,
+    /// 开始时间（毫秒，相对于本句起点）
+    pub start_ms: u64,
+    /// 结束时间（毫秒，相对于本句起点）
+    pub end_ms: u64,
+}
+
+impl TimedWord {
+    pub fn new(text: impl Into<String>, start_ms: u64, end_ms: u64) -> Self {
+        Self {
+            text: text.into(),
+            start_ms,
+            end_ms,
+        }
+    }
+
+    /// 比较用的文本（忽略大小写和标点；纯标点单元按原文比较）
+    fn key(&self) -> String {
+        let normalized: String = self.text
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect();
+        if normalized.is_empty() {
+            self.text.trim().to_string()
+        } else {
+            normalized
+        }
+    }
+}
+
+/// 是否为中日韩文字或全角标点（这些字符各自成为一个识别单元，拼接时不加空格）
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3000..=0x303F     // CJK 标点
+        | 0x3040..=0x30FF   // 平假名、片假名
+        | 0x3400..=0x4DBF   // CJK 扩展 A
+        | 0x4E00..=0x9FFF   // CJK 统一汉字
+        | 0xAC00..=0xD7AF   // 韩文音节
+        | 0xF900..=0xFAFF   // CJK 兼容汉字
+        | 0xFF00..=0xFFEF   // 全角字符
+    )
+}
+
+/// 把分段中的 token 切分成识别单元（时间戳和偏移量相加后为相对于本句起点的时间）
+pub fn words_from_segments(segments: &[TranscriptSegment], offset_ms: u64) -> Vec<TimedWord> {
+    let mut words: Vec<TimedWord> = Vec::new();
+    for segment in segments {
+        // 当前单词是否还可以继续追加（遇到空白、中日韩文字或分段边界时结束）
+        let mut open = false;
+        for token in &segment.tokens {
+            let start_ms = offset_ms + token.start_ms;
+            let end_ms = offset_ms + token.end_ms.max(token.start_ms);
+            for c in token.text.chars() {
+                if c.is_whitespace() {
+                    open = false;
+                } else if is_cjk(c) {
+                    words.push(TimedWord::new(c.to_string(), start_ms, end_ms));
+                    open = false;
+                } else if open {
+                    let word = words.last_mut().expect("open word exists");
+                    word.text.push(c);
+                    word.end_ms = end_ms;
+                } else {
+                    words.push(TimedWord::new(c.to_string(), start_ms, end_ms));
+                    open = true;
+                }
+            }
+        }
+    }
+    words
+}
+
+/// 拼接识别单元（相邻的拉丁字母单词之间加空格，中日韩文字之间不加）
+pub fn join_words(words: &[TimedWord]) -> String {
+    let mut text = String::new();
+    for word in words {
+        let needs_space = match (text.chars().last(), word.text.chars().next()) {
+            (Some(prev), Some(next)) => !is_cjk(prev) && !is_cjk(next),
+            _ => false,
+        };
+        if needs_space {
+            text.push(' ');
+        }
+        text.push_str(&word.text);
+    }
+    text
+}
+
+/// LocalAgreement 提交策略
+#[derive(Debug, Clone)]
+pub struct LocalAgreement {
+    /// 需要一致的连续假设个数
+    agreement_count: usize,
+    /// 已提交的单元（不再改变）
+    committed: Vec<TimedWord>,
+    /// 最近的假设（已去掉已提交部分），最新的在最后
+    hypotheses: VecDeque<Vec<TimedWord>>,
+}
+
+impl LocalAgreement {
+    /// 创建提交策略
+    ///
+    /// # Arguments
+    /// * `agreement_count` - 连续多少个假设一致才提交（至少为 1，为 1 时每个假设都直接提交）
+    pub fn new(agreement_count: usize) -> Self {
+        Self {
+            agreement_count: agreement_count.max(1),
+            committed: Vec::new(),
+            hypotheses: VecDeque::new(),
+        }
+    }
+
+    /// 已提交的单元
+    pub fn committed(&self) -> &[TimedWord] {
+        &self.committed
+    }
+
+    /// 已提交的文本（稳定前缀）
+    pub fn committed_text(&self) -> String {
+        join_words(&self.committed)
+    }
+
+    /// 已提交部分的结束时间（毫秒）
+    pub fn committed_end_ms(&self) -> u64 {
+        self.committed.last().map(|word| word.end_ms).unwrap_or(0)
+    }
+
+    /// 最新假设中未提交的部分（易变尾部）
+    pub fn tail(&self) -> &[TimedWord] {
+        self.hypotheses.back().map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// 稳定前缀 + 易变尾部
+    pub fn text(&self) -> String {
+        let mut words = self.committed.clone();
+        words.extend_from_slice(self.tail());
+        join_words(&words)
+    }
+
+    /// 输入新的假设，返回本次新提交的单元
+    ///
+    /// 假设的时间戳必须相对于本句起点（滑动窗口的偏移量已加上）。
+    /// 已提交部分（按时间戳和 n-gram 重叠判断）会先从假设中去掉。
+    pub fn insert(&mut self, hypothesis: Vec<TimedWord>) -> Vec<TimedWord> {
+        let hypothesis = self.strip_committed(hypothesis);
+        self.hypotheses.push_back(hypothesis);
+        while self.hypotheses.len() > self.agreement_count {
+            self.hypotheses.pop_front();
+        }
+        if self.hypotheses.len() < self.agreement_count {
+            return Vec::new();
+        }
+
+        // 最近 n 个假设的最长公共前缀
+        let latest = self.hypotheses.back().expect("at least one hypothesis");
+        let agreed = (0..latest.len())
+            .take_while(|&i| {
+                let key = latest[i].key();
+                self.hypotheses.iter().all(|h| h.get(i).is_some_and(|word| word.key() == key))
+            })
+            .count();
+        self.commit_prefix(agreed)
+    }
+
+    /// 强制提交最新假设中在 `until_ms` 之前结束的单元（滑动窗口被迫丢弃未提交的音频时使用）
+    pub fn force_commit_until(&mut self, until_ms: u64) -> Vec<TimedWord> {
+        let count = self.tail().iter().take_while(|word| word.end_ms <= until_ms).count();
+        self.commit_prefix(count)
+    }
+
+    /// 结束本句：提交最新假设的全部内容，返回本句的全部单元并重置状态
+    pub fn finish(&mut self) -> Vec<TimedWord> {
+        let count = self.tail().len();
+        self.commit_prefix(count);
+        self.hypotheses.clear();
+        std::mem::take(&mut self.committed)
+    }
+
+    /// 重置状态
+    pub fn reset(&mut self) {
+        self.committed.clear();
+        self.hypotheses.clear();
+    }
+
+    /// 提交最新假设的前 `count` 个单元，并从所有保留的假设中去掉这部分
+    fn commit_prefix(&mut self, count: usize) -> Vec<TimedWord> {
+        if count == 0 {
+            return Vec::new();
+        }
+        let newly_committed: Vec<TimedWord> = self.hypotheses
+            .back()
+            .map(|latest| latest[..count].to_vec())
+            .unwrap_or_default();
+        for hypothesis in &mut self.hypotheses {
+            hypothesis.drain(..count.min(hypothesis.len()));
+        }
+        self.committed.extend(newly_committed.iter().cloned());
+        newly_committed
+    }
+
+    /// 去掉假设中已提交的部分
+    fn strip_committed(&self, hypothesis: Vec<TimedWord>) -> Vec<TimedWord> {
+        let committed_end_ms = self.committed_end_ms();
+        // 1. 按时间戳：去掉在已提交部分结束之前开始的单元
+        let mut hypothesis: Vec<TimedWord> = hypothesis
+            .into_iter()
+            .filter(|word| word.start_ms + OVERLAP_TOLERANCE_MS > committed_end_ms)
+            .collect();
+
+        // 2. 按 n-gram：时间戳有误差时，假设开头可能还会重复已提交的最后几个单元
+        if let Some(first) = hypothesis.first() {
+            if first.start_ms < committed_end_ms + OVERLAP_TOLERANCE_MS * 10 {
+                let max_n = MAX_OVERLAP_NGRAM.min(self.committed.len()).min(hypothesis.len());
+                for n in (1..=max_n).rev() {
+                    let committed_tail = &self.committed[self.committed.len() - n..];
+                    let overlaps = committed_tail.iter()
+                        .zip(&hypothesis[..n])
+                        .all(|(a, b)| a.key() == b.key());
+                    if overlaps {
+                        hypothesis.drain(..n);
+                        break;
+                    }
+                }
+            }
+        }
+        hypothesis
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TranscriptToken;
+
+    fn token(text: &str, start_ms: u64, end_ms: u64) -> TranscriptToken {
+        TranscriptToken {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            probability: 0.9,
+        }
+    }
+
+    #[test]
+    fn test_words_from_segments_splits_latin_words_and_cjk_chars() {
+        let segments = vec![
+            TranscriptSegment {
+                text: "Hello world.".to_string(),
+                start_ms: 0,
+                end_ms: 800,
+                no_speech_probability: None,
+                avg_log_probability: None,
+                tokens: vec![token(" Hel", 0, 200), token("lo", 200, 300), token(" world", 300, 700), token(".", 700, 800)],
+            },
+            TranscriptSegment {
+                text: "你好，".to_string(),
+                start_ms: 800,
+                end_ms: 1200,
+                no_speech_probability: None,
+                avg_log_probability: None,
+                tokens: vec![token("你好", 800, 1100), token("，", 1100, 1200)],
+            },
+        ];
+
+        let words = words_from_segments(&segments, 1000);
+        let texts: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(texts, vec!["Hello", "world.", "你", "好", "，"]);
+        assert_eq!((words[0].start_ms, words[0].end_ms), (1000, 1300));
+        assert_eq!(join_words(&words), "Hello world.你好，");
+    }
+}
@@ -3,6 +3,7 @@
 pub mod cli;
 pub mod audio_preprocessing;
 pub mod engine;
+pub mod local_agreement;
 pub mod streaming;
 pub mod faster_whisper_streaming;
 
@@ -26,8 +27,11 @@ pub use audio_preprocessing::{
 };
 
 // 导出推理引擎
-pub use engine::WhisperAsrEngine;
+pub use engine::{WhisperAsrEngine, WhisperTranscription};
+
+// 导出 LocalAgreement 提交策略
+pub use local_agreement::{LocalAgreement, TimedWord};
 
 // 导出流式实现
-pub use streaming::WhisperAsrStreaming;
+pub use streaming::{WhisperAsrStreaming, LocalAgreementConfig};
 pub use faster_whisper_streaming::FasterWhisperAsrStreaming;
@@ -5,12 +5,14 @@ use std::sync::{Arc, Mutex};
 use async_trait::async_trait;
 use anyhow::anyhow;
 
-use crate::asr_streaming::{AsrRequest, AsrResult, AsrStreaming};
+use crate::asr_filters::confidence::transcript_confidence;
+use crate::asr_streaming::{AsrRequest, AsrResult, AsrSessionState, AsrStreaming, AsrStreamingConfig, AsrStreamingMode};
 use crate::error::{EngineError, EngineResult};
-use crate::types::{AudioFrame, PartialTranscript, StableTranscript};
+use crate::types::{AudioFrame, LanguageProbability, PartialTranscript, StableTranscript, TranscriptSegment};
 
-use super::engine::WhisperAsrEngine;
-use super::audio_preprocessing::preprocess_audio_frame;
+use super::engine::{WhisperAsrEngine, WhisperTranscription};
+use super::audio_preprocessing::{preprocess_audio_frame, WHISPER_SAMPLE_RATE};
+use super::local_agreement::{join_words, words_from_segments, LocalAgreement};
 
 /// 流式推理配置（基于自然停顿）
 #[derive(Debug, Clone)]
@@ -23,12 +25,196 @@ struct StreamingConfig {
     enabled: bool,
 }
 
+/// 滑动窗口 + LocalAgreement 流式识别配置
+#[derive(Debug, Clone)]
+pub struct LocalAgreementConfig {
+    /// 连续多少个假设一致才提交
+    pub agreement_count: usize,
+    /// 滑动窗口最大长度（秒），超过时强制提交窗口前半部分
+    pub max_window_seconds: f64,
+    /// 作为提示词的已提交文本最大字符数（取末尾）
+    pub prompt_max_chars: usize,
+}
+
+impl Default for LocalAgreementConfig {
+    fn default() -> Self {
+        Self {
+            agreement_count: 2,
+            max_window_seconds: 15.0,
+            prompt_max_chars: 200,
+        }
+    }
+}
+
+/// 滑动窗口状态（一句话内有效，边界处重置）
+#[derive(Debug)]
+struct SlidingWindowState {
+    config: LocalAgreementConfig,
+    agreement: LocalAgreement,
+    /// 窗口内预处理后的音频（16kHz 单声道）
+    samples: Vec<f32>,
+    /// 窗口起点相对于本句起点的时间（毫秒）
+    window_offset_ms: u64,
+    /// 已加入窗口的帧数（音频缓冲区的前缀）
+    processed_frames: usize,
+    /// 最后加入窗口的帧的时间戳（用于检测缓冲区被清空或被其他会话替换）
+    last_frame_timestamp_ms: Option<u64>,
+    /// 移出窗口的分段（时间为相对于本句起点）
+    archived_segments: Vec<TranscriptSegment>,
+    /// 最近一次推理检测到的语言
+    language: Option<String>,
+    language_probabilities: Vec<LanguageProbability>,
+}
+
+impl SlidingWindowState {
+    fn new(config: LocalAgreementConfig) -> Self {
+        Self {
+            agreement: LocalAgreement::new(config.agreement_count),
+            config,
+            samples: Vec::new(),
+            window_offset_ms: 0,
+            processed_frames: 0,
+            last_frame_timestamp_ms: None,
+            archived_segments: Vec::new(),
+            language: None,
+            language_probabilities: Vec::new(),
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.config.clone());
+    }
+
+    fn window_duration_ms(&self) -> u64 {
+        samples_to_ms(self.samples.len())
+    }
+
+    /// 把缓冲区中的新帧加入窗口（缓冲区与窗口状态不一致时重新开始）
+    fn sync_frames(&mut self, frames: &[AudioFrame]) -> EngineResult<()> {
+        let consistent = self.processed_frames <= frames.len()
+            && match self.last_frame_timestamp_ms {
+                Some(timestamp_ms) => self.processed_frames > 0
+                    && frames[self.processed_frames - 1].timestamp_ms == timestamp_ms,
+                None => self.processed_frames == 0,
+            };
+        if !consistent {
+            self.reset();
+        }
+
+        for frame in &frames[self.processed_frames..] {
+            let preprocessed = preprocess_audio_frame(frame)
+                .map_err(|e| EngineError::new(format!("Failed to preprocess audio frame: {}", e)))?;
+            self.samples.extend_from_slice(&preprocessed);
+        }
+        self.processed_frames = frames.len();
+        self.last_frame_timestamp_ms = frames.last().map(|frame| frame.timestamp_ms);
+        Ok(())
+    }
+
+    /// 提示词：已提交文本的末尾
+    fn prompt(&self) -> String {
+        let committed = self.agreement.committed_text();
+        let skip = committed.chars().count().saturating_sub(self.config.prompt_max_chars);
+        committed.chars().skip(skip).collect()
+    }
+
+    /// 记录一次窗口推理结果，更新提交状态并滑动窗口
+    fn apply_transcription(&mut self, transcription: WhisperTranscription) {
+        let offset_ms = self.window_offset_ms;
+        let segments: Vec<TranscriptSegment> = transcription.segments
+            .into_iter()
+            .map(|segment| shift_segment(segment, offset_ms))
+            .collect();
+        if transcription.language.is_some() {
+            self.language = transcription.language;
+            self.language_probabilities = transcription.language_probabilities;
+        }
+        self.agreement.insert(words_from_segments(&segments, 0));
+
+        // 窗口起点移到已提交分段的结束位置（正常情况下只在分段边界处裁剪）
+        let committed_end_ms = self.agreement.committed_end_ms();
+        let mut cut_ms = segments.iter()
+            .take_while(|segment| segment.end_ms <= committed_end_ms)
+            .last()
+            .map(|segment| segment.end_ms)
+            .unwrap_or(self.window_offset_ms);
+
+        // 窗口过长：强制提交窗口前半部分，并在已提交的单词处裁剪
+        let max_window_ms = (self.config.max_window_seconds * 1000.0) as u64;
+        if self.window_duration_ms() > max_window_ms {
+            let until_ms = self.window_offset_ms + self.window_duration_ms() - max_window_ms / 2;
+            let forced = self.agreement.force_commit_until(until_ms);
+            eprintln!("[ASR Streaming] ⏩ Window exceeded {:.1}s, force committed {} words",
+                     self.config.max_window_seconds, forced.len());
+            cut_ms = cut_ms.max(self.agreement.committed_end_ms());
+        }
+
+        if cut_ms > self.window_offset_ms {
+            let cut_samples = ms_to_samples(cut_ms - self.window_offset_ms).min(self.samples.len());
+            self.samples.drain(..cut_samples);
+            self.window_offset_ms = cut_ms;
+            self.archived_segments.extend(segments.into_iter().filter_map(|segment| truncate_segment(segment, cut_ms)));
+        }
+    }
+}
+
+/// 单个会话的流式识别状态
+///
+/// ASR 实例由所有会话共享，调用方通过 `take_session_state` / `restore_session_state`
+/// 把它保存在会话上，避免不同会话互相覆盖部分结果计时和 LocalAgreement 历史。
+#[derive(Debug)]
+struct StreamingSessionState {
+    last_partial_update_ms: u64,
+    sliding_window: Option<SlidingWindowState>,
+}
+
+fn samples_to_ms(samples: usize) -> u64 {
+    samples as u64 * 1000 / WHISPER_SAMPLE_RATE as u64
+}
+
+fn ms_to_samples(ms: u64) -> usize {
+    (ms * WHISPER_SAMPLE_RATE as u64 / 1000) as usize
+}
+
+/// 截取分段在 `cut_ms` 之前的部分（跨越裁剪点的分段只保留在裁剪点之前结束的 token）
+fn truncate_segment(mut segment: TranscriptSegment, cut_ms: u64) -> Option<TranscriptSegment> {
+    if segment.end_ms <= cut_ms {
+        return Some(segment);
+    }
+    segment.tokens.retain(|token| token.end_ms <= cut_ms);
+    if segment.tokens.is_empty() {
+        return None;
+    }
+    segment.text = segment.tokens.iter().map(|token| token.text.as_str()).collect::<String>().trim().to_string();
+    segment.end_ms = cut_ms;
+    Some(segment)
+}
+
+/// 把相对于推理音频起点的分段时间换算为流时间（加上第一帧的 `timestamp_ms`），与 VAD 事件、音频帧对齐
+fn segments_in_stream_time(segments: Vec<TranscriptSegment>, frames: &[AudioFrame]) -> Vec<TranscriptSegment> {
+    let offset_ms = frames.first().map(|frame| frame.timestamp_ms).unwrap_or(0);
+    segments.into_iter().map(|segment| shift_segment(segment, offset_ms)).collect()
+}
+
+/// 把分段时间平移 `offset_ms`
+fn shift_segment(mut segment: TranscriptSegment, offset_ms: u64) -> TranscriptSegment {
+    segment.start_ms += offset_ms;
+    segment.end_ms += offset_ms;
+    for token in &mut segment.tokens {
+        token.start_ms += offset_ms;
+        token.end_ms += offset_ms;
+    }
+    segment
+}
+
 /// Whisper ASR 的流式实现
-/// 
-/// 支持三种模式：
+///
+/// 支持四种模式：
 /// 1. 基础模式：每次 `infer()` 调用时进行完整推理（当前默认）
 /// 2. VAD 集成模式：使用 `accumulate_frame()` 累积帧，在 `infer_on_boundary()` 时推理
-/// 3. 流式模式：使用滑动窗口定期推理，返回部分结果（步骤 3.2）
+/// 3. 流式模式：定期对累积的全部音频推理，返回部分结果（步骤 3.2）
+/// 4. 滑动窗口模式：只对未提交的音频推理，已提交的文本作为提示词，
+///    LocalAgreement 提交稳定前缀（见 `enable_local_agreement_streaming`）
 pub struct WhisperAsrStreaming {
     engine: Arc<Mutex<WhisperAsrEngine>>,  // 使用 Mutex 以支持内部可变性（语言设置）
     /// 音频帧缓冲区（累积所有收到的帧）
@@ -37,6 +223,8 @@ pub struct WhisperAsrStreaming {
     initialized: Arc<Mutex<bool>>,
     /// 流式推理配置
     streaming_config: Arc<Mutex<StreamingConfig>>,
+    /// 滑动窗口状态（None 表示未启用滑动窗口模式）
+    sliding_window: Arc<Mutex<Option<SlidingWindowState>>>,
 }
 
 impl WhisperAsrStreaming {
@@ -56,6 +244,7 @@ impl WhisperAsrStreaming {
                 last_partial_update_ms: 0,
                 enabled: false,  // 默认禁用，需要显式启用
             })),
+            sliding_window: Arc::new(Mutex::new(None)),
         })
     }
 
@@ -75,6 +264,7 @@ impl WhisperAsrStreaming {
                 last_partial_update_ms: 0,
                 enabled: false,  // 默认禁用，需要显式启用
             })),
+            sliding_window: Arc::new(Mutex::new(None)),
         })
     }
 
@@ -96,6 +286,22 @@ impl WhisperAsrStreaming {
         Ok(())
     }
 
+    /// 限制自动检测语言的候选范围（空表示不限制）
+    pub fn set_language_candidates(&self, candidates: Vec<String>) -> EngineResult<()> {
+        let mut engine = self.engine.lock()
+            .map_err(|e| EngineError::new(format!("Failed to lock WhisperAsrEngine: {}", e)))?;
+        engine.set_language_candidates(candidates);
+        Ok(())
+    }
+
+    /// 设置热词（写入 Whisper 初始提示词，空表示不使用）
+    pub fn set_hotwords(&self, hotwords: Vec<String>) -> EngineResult<()> {
+        let mut engine = self.engine.lock()
+            .map_err(|e| EngineError::new(format!("Failed to lock WhisperAsrEngine: {}", e)))?;
+        engine.set_hotwords(hotwords);
+        Ok(())
+    }
+
     /// 启用流式推理模式（部分结果输出）
     /// 
     /// # Arguments
@@ -107,11 +313,34 @@ impl WhisperAsrStreaming {
         }
     }
 
+    /// 启用滑动窗口流式推理模式（LocalAgreement 提交策略）
+    ///
+    /// 每次只对未提交的音频（窗口）推理，已提交的文本作为提示词；
+    /// 连续 `agreement_count` 个假设一致的前缀被提交，部分结果中 `stable_prefix` 为已提交文本。
+    ///
+    /// # Arguments
+    /// * `partial_update_interval_seconds` - 部分结果更新间隔（秒）
+    /// * `config` - 滑动窗口配置
+    pub fn enable_local_agreement_streaming(&self, partial_update_interval_seconds: f64, config: LocalAgreementConfig) {
+        self.enable_streaming(partial_update_interval_seconds);
+        if let Ok(mut sliding_window) = self.sliding_window.lock() {
+            *sliding_window = Some(SlidingWindowState::new(config));
+        }
+    }
+
     /// 禁用流式推理模式
     pub fn disable_streaming(&self) {
         if let Ok(mut config) = self.streaming_config.lock() {
             config.enabled = false;
         }
+        if let Ok(mut sliding_window) = self.sliding_window.lock() {
+            *sliding_window = None;
+        }
+    }
+
+    /// 检查是否启用滑动窗口模式
+    pub fn is_local_agreement_enabled(&self) -> bool {
+        self.sliding_window.lock().map(|sliding_window| sliding_window.is_some()).unwrap_or(false)
     }
 
     /// 检查是否启用流式推理
@@ -123,7 +352,72 @@ impl WhisperAsrStreaming {
         }
     }
 
+    /// 按配置选择流式推理模式
+    pub fn apply_streaming_config(&self, config: &AsrStreamingConfig) {
+        match config.mode {
+            AsrStreamingMode::Off => self.disable_streaming(),
+            AsrStreamingMode::Full => {
+                self.disable_streaming();
+                self.enable_streaming(config.partial_interval_seconds);
+            }
+            AsrStreamingMode::LocalAgreement => self.enable_local_agreement_streaming(
+                config.partial_interval_seconds,
+                LocalAgreementConfig {
+                    agreement_count: config.agreement_count,
+                    max_window_seconds: config.max_window_seconds,
+                    prompt_max_chars: config.prompt_max_chars,
+                },
+            ),
+        }
+    }
+
+    /// 取出当前会话的流式状态，并留下一个新的空状态
+    pub fn take_session_state(&self) -> EngineResult<AsrSessionState> {
+        let last_partial_update_ms = {
+            let mut config = self.streaming_config.lock()
+                .map_err(|e| EngineError::new(format!("Failed to lock streaming config: {}", e)))?;
+            std::mem::take(&mut config.last_partial_update_ms)
+        };
+        let mut sliding_window = self.sliding_window.lock()
+            .map_err(|e| EngineError::new(format!("Failed to lock sliding window: {}", e)))?;
+        let sliding_window = sliding_window.as_mut().map(|state| {
+            let fresh = SlidingWindowState::new(state.config.clone());
+            std::mem::replace(state, fresh)
+        });
+        Ok(Box::new(StreamingSessionState { last_partial_update_ms, sliding_window }))
+    }
+
+    /// 恢复会话的流式状态（None 或状态类型不匹配时使用新的空状态）
+    pub fn restore_session_state(&self, state: Option<AsrSessionState>) -> EngineResult<()> {
+        let state = state.and_then(|state| state.downcast::<StreamingSessionState>().ok());
+        let (last_partial_update_ms, restored_window) = match state {
+            Some(state) => (state.last_partial_update_ms, state.sliding_window),
+            None => (0, None),
+        };
+        {
+            let mut config = self.streaming_config.lock()
+                .map_err(|e| EngineError::new(format!("Failed to lock streaming config: {}", e)))?;
+            config.last_partial_update_ms = last_partial_update_ms;
+        }
+        let mut sliding_window = self.sliding_window.lock()
+            .map_err(|e| EngineError::new(format!("Failed to lock sliding window: {}", e)))?;
+        if let Some(current) = sliding_window.as_mut() {
+            // 配置以当前实例为准（会话保存的状态可能来自修改配置之前）
+            let config = current.config.clone();
+            *current = match restored_window {
+                Some(mut state) if state.config.agreement_count == config.agreement_count => {
+                    state.config = config;
+                    state
+                }
+                _ => SlidingWindowState::new(config),
+            };
+        }
+        Ok(())
+    }
+
     /// 清空音频缓冲区
+    ///
+    /// 不重置滑动窗口：窗口在边界处重置，或在缓冲区与窗口不一致时（`sync_frames`）自动重新开始
     pub fn clear_buffer(&self) {
         if let Ok(mut buffer) = self.audio_buffer.lock() {
             buffer.clear();
@@ -207,6 +501,11 @@ impl WhisperAsrStreaming {
             return Ok(None);
         }
 
+        // 滑动窗口模式：只对窗口内的音频推理
+        if self.is_local_agreement_enabled() {
+            return self.infer_partial_sliding_window(&frames).await;
+        }
+
         // 4. 预处理所有累积的帧（不使用滑动窗口，使用所有累积的音频）
         let mut audio_buffer = Vec::new();
         for frame in &frames {
@@ -219,10 +518,10 @@ impl WhisperAsrStreaming {
         // 5. 运行推理（使用 spawn_blocking 避免阻塞异步运行时）
         let engine_clone = Arc::clone(&self.engine);
         let audio_data_clone = audio_data.clone();
-        let (transcript_text, _detected_lang) = tokio::task::spawn_blocking(move || {
+        let transcription = tokio::task::spawn_blocking(move || {
             let engine = engine_clone.lock()
                 .map_err(|e| anyhow::anyhow!("Failed to lock WhisperAsrEngine: {}", e))?;
-            engine.transcribe_full(&audio_data_clone)
+            engine.transcribe_detailed(&audio_data_clone)
                 .map_err(|e| anyhow::anyhow!("Failed to transcribe: {}", e))
         })
         .await
@@ -230,19 +529,123 @@ impl WhisperAsrStreaming {
         .map_err(|e| EngineError::new(format!("Transcription error: {}", e)))?;
 
         // 6. 构造部分结果
-        if transcript_text.is_empty() {
+        if transcription.text.is_empty() {
             Ok(None)
         } else {
-            let confidence = 0.90;  // 部分结果的置信度稍低
-
             Ok(Some(PartialTranscript {
-                text: transcript_text,
-                confidence,
+                text: transcription.text,
+                confidence: transcription.confidence.unwrap_or_default(),
                 is_final: false,  // 部分结果不是最终的
+                stable_prefix: None,
             }))
         }
     }
 
+    /// 运行推理（使用 spawn_blocking 避免阻塞异步运行时）
+    async fn transcribe_audio(&self, audio_data: Vec<f32>, prompt: Option<String>) -> EngineResult<WhisperTranscription> {
+        let engine_clone = Arc::clone(&self.engine);
+        tokio::task::spawn_blocking(move || {
+            let engine = engine_clone.lock()
+                .map_err(|e| anyhow::anyhow!("Failed to lock WhisperAsrEngine: {}", e))?;
+            engine.transcribe_with_prompt(&audio_data, prompt.as_deref())
+                .map_err(|e| anyhow::anyhow!("Failed to transcribe: {}", e))
+        })
+        .await
+        .map_err(|e| EngineError::new(format!("Task join error: {}", e)))?
+        .map_err(|e| EngineError::new(format!("Transcription error: {}", e)))
+    }
+
+    /// 把缓冲区中的新帧加入滑动窗口，返回窗口音频、提示词和窗口起点
+    fn prepare_sliding_window(&self, frames: &[AudioFrame]) -> EngineResult<Option<(Vec<f32>, String, u64)>> {
+        let mut sliding_window = self.sliding_window.lock()
+            .map_err(|e| EngineError::new(format!("Failed to lock sliding window: {}", e)))?;
+        let Some(state) = sliding_window.as_mut() else {
+            return Ok(None);
+        };
+        state.sync_frames(frames)?;
+        Ok(Some((state.samples.clone(), state.prompt(), state.window_offset_ms)))
+    }
+
+    /// 滑动窗口模式的部分结果：推理窗口音频，LocalAgreement 提交稳定前缀
+    async fn infer_partial_sliding_window(&self, frames: &[AudioFrame]) -> EngineResult<Option<PartialTranscript>> {
+        let Some((audio_data, prompt, window_offset_ms)) = self.prepare_sliding_window(frames)? else {
+            return Ok(None);
+        };
+        if audio_data.is_empty() {
+            return Ok(None);
+        }
+
+        let transcription = self.transcribe_audio(audio_data, Some(prompt)).await?;
+        let confidence = transcription.confidence.unwrap_or_default();
+
+        let mut sliding_window = self.sliding_window.lock()
+            .map_err(|e| EngineError::new(format!("Failed to lock sliding window: {}", e)))?;
+        let Some(state) = sliding_window.as_mut() else {
+            return Ok(None);
+        };
+        if state.window_offset_ms != window_offset_ms {
+            // 推理期间窗口已被重置或滑动（例如到达边界），丢弃过期的结果
+            return Ok(None);
+        }
+        state.apply_transcription(transcription);
+
+        let text = state.agreement.text();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(PartialTranscript {
+            text,
+            confidence,
+            is_final: false,
+            stable_prefix: Some(state.agreement.committed_text()),
+        }))
+    }
+
+    /// 滑动窗口模式的最终结果：推理窗口内剩余的音频，与已提交的文本拼接，并重置窗口
+    async fn finish_sliding_window(&self, frames: &[AudioFrame]) -> EngineResult<WhisperTranscription> {
+        let Some((audio_data, prompt, _)) = self.prepare_sliding_window(frames)? else {
+            return Err(EngineError::new("Sliding window streaming is not enabled"));
+        };
+        let transcription = if audio_data.is_empty() {
+            None
+        } else {
+            Some(self.transcribe_audio(audio_data, Some(prompt)).await?)
+        };
+
+        let mut sliding_window = self.sliding_window.lock()
+            .map_err(|e| EngineError::new(format!("Failed to lock sliding window: {}", e)))?;
+        let state = sliding_window.as_mut()
+            .ok_or_else(|| EngineError::new("Sliding window streaming is not enabled"))?;
+
+        let mut segments = std::mem::take(&mut state.archived_segments);
+        if let Some(transcription) = transcription {
+            let offset_ms = state.window_offset_ms;
+            let window_segments: Vec<TranscriptSegment> = transcription.segments
+                .into_iter()
+                .map(|segment| shift_segment(segment, offset_ms))
+                .collect();
+            state.agreement.insert(words_from_segments(&window_segments, 0));
+            segments.extend(window_segments);
+            if transcription.language.is_some() {
+                state.language = transcription.language;
+                state.language_probabilities = transcription.language_probabilities;
+            }
+        }
+        let text = join_words(&state.agreement.finish());
+        let confidence = transcript_confidence(&segments);
+        let language = state.language.take();
+        let language_probabilities = std::mem::take(&mut state.language_probabilities);
+        state.reset();
+
+        Ok(WhisperTranscription {
+            text,
+            language,
+            language_probabilities,
+            segments,
+            confidence,
+        })
+    }
+
     /// 在检测到语音边界时触发推理
     /// 
     /// # Returns
@@ -263,27 +666,22 @@ impl WhisperAsrStreaming {
             });
         }
 
-        // 3. 预处理所有累积的帧（不使用滑动窗口，使用所有累积的音频）
-        let mut audio_buffer = Vec::new();
-        for frame in &frames {
-            let preprocessed = preprocess_audio_frame(frame)
-                .map_err(|e| EngineError::new(format!("Failed to preprocess audio frame: {}", e)))?;
-            audio_buffer.extend_from_slice(&preprocessed);
-        }
-        let audio_data = audio_buffer;
-
-        // 6. 运行推理（使用 spawn_blocking 避免阻塞异步运行时）
-        let engine_clone = Arc::clone(&self.engine);
-        let audio_data_clone = audio_data.clone();
-        let (transcript_text, _detected_lang) = tokio::task::spawn_blocking(move || {
-            let engine = engine_clone.lock()
-                .map_err(|e| anyhow::anyhow!("Failed to lock WhisperAsrEngine: {}", e))?;
-            engine.transcribe_full(&audio_data_clone)
-                .map_err(|e| anyhow::anyhow!("Failed to transcribe: {}", e))
-        })
-        .await
-        .map_err(|e| EngineError::new(format!("Task join error: {}", e)))?
-        .map_err(|e| EngineError::new(format!("Transcription error: {}", e)))?;
+        // 3. 运行推理：滑动窗口模式只推理窗口内剩余的音频，并与已提交的文本拼接；
+        //    否则预处理所有累积的帧并完整推理
+        let transcription = if self.is_local_agreement_enabled() {
+            self.finish_sliding_window(&frames).await?
+        } else {
+            let mut audio_buffer = Vec::new();
+            for frame in &frames {
+                let preprocessed = preprocess_audio_frame(frame)
+                    .map_err(|e| EngineError::new(format!("Failed to preprocess audio frame: {}", e)))?;
+                audio_buffer.extend_from_slice(&preprocessed);
+            }
+            self.transcribe_audio(audio_buffer, None).await?
+        };
+        let transcript_text = transcription.text;
+        let detected_lang = transcription.language;
+        let confidence = transcription.confidence.unwrap_or_default();
 
         // 7. 清空缓冲区（因为已经推理完成）
         self.clear_buffer();
@@ -307,23 +705,25 @@ impl WhisperAsrStreaming {
                 final_transcript: None,
             }
         } else {
-            let confidence = 0.95;
-
             AsrResult {
                 partial: Some(PartialTranscript {
                     text: transcript_text.clone(),
                     confidence,
                     is_final: true,  // 在边界时，结果应该是最终的
+                    stable_prefix: Some(transcript_text.clone()),
                 }),
                 final_transcript: {
+                    // 使用检测到的语言，如果没有则使用设置的语言，最后使用 "unknown"
                     let engine = self.engine.lock()
                         .map_err(|e| EngineError::new(format!("Failed to lock WhisperAsrEngine: {}", e)))?;
                     Some(StableTranscript {
                         text: transcript_text,
                         speaker_id: None,
-                        language: engine.language()
-                            .unwrap_or("unknown")
-                            .to_string(),
+                        language: detected_lang
+                            .or_else(|| engine.language().map(|s| s.to_string()))
+                            .unwrap_or_else(|| "unknown".to_string()),
+                        segments: segments_in_stream_time(transcription.segments, &frames),
+                        language_probabilities: transcription.language_probabilities,
                     })
                 },
             }
@@ -400,15 +800,18 @@ impl AsrStreaming for WhisperAsrStreaming {
         // 6. 运行推理（使用 spawn_blocking 避免阻塞异步运行时）
         let engine_clone = Arc::clone(&self.engine);
         let audio_data_clone = audio_data.clone();
-        let (transcript_text, detected_lang) = tokio::task::spawn_blocking(move || {
+        let transcription = tokio::task::spawn_blocking(move || {
             let engine = engine_clone.lock()
                 .map_err(|e| anyhow::anyhow!("Failed to lock WhisperAsrEngine: {}", e))?;
-            engine.transcribe_full(&audio_data_clone)
+            engine.transcribe_detailed(&audio_data_clone)
                 .map_err(|e| anyhow::anyhow!("Failed to transcribe: {}", e))
         })
         .await
         .map_err(|e| EngineError::new(format!("Task join error: {}", e)))?
         .map_err(|e| EngineError::new(format!("Transcription error: {}", e)))?;
+        let transcript_text = transcription.text;
+        let detected_lang = transcription.language;
+        let confidence = transcription.confidence.unwrap_or_default();
 
         // 8. 构造结果
         let result = if transcript_text.is_empty() {
@@ -417,8 +820,6 @@ impl AsrStreaming for WhisperAsrStreaming {
                 final_transcript: None,
             }
         } else {
-            let confidence = 0.95;
-            
             // 使用检测到的语言，如果没有则使用设置的语言，最后使用 "unknown"
             let final_language = detected_lang
                 .or_else(|| {
@@ -432,11 +833,14 @@ impl AsrStreaming for WhisperAsrStreaming {
                     text: transcript_text.clone(),
                     confidence,
                     is_final: false,
+                    stable_prefix: None,
                 }),
                 final_transcript: Some(StableTranscript {
                     text: transcript_text,
                     speaker_id: None,
                     language: final_language,
+                    segments: segments_in_stream_time(transcription.segments, &frames),
+                    language_probabilities: transcription.language_probabilities,
                 }),
             }
         };
@@ -455,6 +859,10 @@ impl AsrStreaming for WhisperAsrStreaming {
 
         Ok(())
     }
+
+    fn as_ext(&self) -> Option<&dyn crate::asr_streaming::AsrStreamingExt> {
+        Some(self)
+    }
 }
 
 #[async_trait]
@@ -480,8 +888,37 @@ impl crate::asr_streaming::AsrStreamingExt for WhisperAsrStreaming {
         WhisperAsrStreaming::get_language(self)
     }
 
+    fn set_language_candidates(&self, candidates: Vec<String>) -> EngineResult<()> {
+        WhisperAsrStreaming::set_language_candidates(self, candidates)
+    }
+
+    fn set_hotwords(&self, hotwords: Vec<String>) -> EngineResult<()> {
+        WhisperAsrStreaming::set_hotwords(self, hotwords)
+    }
+
     async fn infer_on_boundary(&self) -> EngineResult<crate::asr_streaming::AsrResult> {
         WhisperAsrStreaming::infer_on_boundary(self).await
     }
+
+    fn is_streaming_enabled(&self) -> bool {
+        WhisperAsrStreaming::is_streaming_enabled(self)
+    }
+
+    async fn infer_partial(&self, current_timestamp_ms: u64) -> EngineResult<Option<PartialTranscript>> {
+        WhisperAsrStreaming::infer_partial(self, current_timestamp_ms).await
+    }
+
+    fn set_streaming_config(&self, config: &AsrStreamingConfig) -> EngineResult<()> {
+        WhisperAsrStreaming::apply_streaming_config(self, config);
+        Ok(())
+    }
+
+    fn take_session_state(&self) -> EngineResult<Option<AsrSessionState>> {
+        WhisperAsrStreaming::take_session_state(self).map(Some)
+    }
+
+    fn restore_session_state(&self, state: Option<AsrSessionState>) -> EngineResult<()> {
+        WhisperAsrStreaming::restore_session_state(self, state)
+    }
 }
 
@@ -12,6 +12,9 @@ use crate::types::AudioFrame;
 /// - 备用缓冲区：接收新的音频输入
 /// 
 /// 当检测到边界时，提交当前缓冲区的内容给 ASR，同时切换到备用缓冲区继续接收新音频。
+///
+/// 配合 VAD 的语音事件使用时（`begin_segment` / `take_segment`），提交给 ASR 的片段只包含
+/// `[start_ms, end_ms)`（已含 pre-roll/post-roll），片段之后的静音帧留在缓冲区，作为下一句的 pre-roll。
 pub struct AudioBufferManager {
     /// 当前缓冲区（正在累积的音频帧）
     current_buffer: Arc<RwLock<VecDeque<AudioFrame>>>,
@@ -27,6 +30,9 @@ pub struct AudioBufferManager {
     
     /// 第一个帧的时间戳（用于计算缓冲时长）
     first_frame_timestamp: Arc<RwLock<Option<u64>>>,
+    
+    /// 是否有已开始、尚未提交的语音片段
+    segment_pending: Arc<RwLock<bool>>,
 }
 
 impl AudioBufferManager {
@@ -42,6 +48,7 @@ impl AudioBufferManager {
             max_buffer_duration_ms: 5000,   // 5秒（符合第二阶段目标：3-5秒）
             min_segment_duration_ms: 200,    // 200ms
             first_frame_timestamp: Arc::new(RwLock::new(None)),
+            segment_pending: Arc::new(RwLock::new(false)),
         }
     }
     
@@ -56,6 +63,7 @@ impl AudioBufferManager {
             max_buffer_duration_ms,
             min_segment_duration_ms,
             first_frame_timestamp: Arc::new(RwLock::new(None)),
+            segment_pending: Arc::new(RwLock::new(false)),
         }
     }
     
@@ -96,6 +104,41 @@ impl AudioBufferManager {
         
         let frames: Vec<AudioFrame> = buffer.drain(..).collect();
         *first_ts = None;  // 重置第一个帧的时间戳
+        *self.segment_pending.write().await = false;
+        
+        frames
+    }
+    
+    /// 语音开始：丢弃片段起点（已含 pre-roll）之前的静音帧
+    /// 
+    /// 如果上一个片段还没有提交（例如因为太短而继续累积），保留已有的帧，与新的语音合并
+    pub async fn begin_segment(&self, start_ms: u64) {
+        let mut buffer = self.current_buffer.write().await;
+        let mut first_ts = self.first_frame_timestamp.write().await;
+        let mut pending = self.segment_pending.write().await;
+        
+        if !*pending {
+            while buffer.front().is_some_and(|frame| frame.end_timestamp_ms() <= start_ms) {
+                buffer.pop_front();
+            }
+            *first_ts = buffer.front().map(|frame| frame.timestamp_ms);
+        }
+        *pending = true;
+    }
+    
+    /// 语音结束：取出片段终点（已含 post-roll）之前的帧用于 ASR 推理
+    /// 
+    /// 之后的帧留在缓冲区（作为下一句的 pre-roll）；`end_ms` 为 None 时取出全部帧
+    pub async fn take_segment(&self, end_ms: Option<u64>) -> Vec<AudioFrame> {
+        let mut buffer = self.current_buffer.write().await;
+        let mut first_ts = self.first_frame_timestamp.write().await;
+        
+        let split = end_ms
+            .and_then(|end_ms| buffer.iter().position(|frame| frame.timestamp_ms >= end_ms))
+            .unwrap_or(buffer.len());
+        let frames: Vec<AudioFrame> = buffer.drain(..split).collect();
+        *first_ts = buffer.front().map(|frame| frame.timestamp_ms);
+        *self.segment_pending.write().await = false;
         
         frames
     }
@@ -162,6 +205,7 @@ impl AudioBufferManager {
         let mut first_ts = self.first_frame_timestamp.write().await;
         buffer.clear();
         *first_ts = None;
+        *self.segment_pending.write().await = false;
     }
     
     /// 检查缓冲区是否为空
@@ -169,6 +213,16 @@ impl AudioBufferManager {
         let buffer = self.current_buffer.read().await;
         buffer.is_empty()
     }
+    
+    /// 获取最大缓冲时长（毫秒）
+    pub fn max_buffer_duration_ms(&self) -> u64 {
+        self.max_buffer_duration_ms
+    }
+    
+    /// 获取最小片段时长（毫秒）
+    pub fn min_segment_duration_ms(&self) -> u64 {
+        self.min_segment_duration_ms
+    }
 }
 
 impl Default for AudioBufferManager {
@@ -254,6 +308,32 @@ mod tests {
         assert!(result.is_err());
     }
     
+    #[tokio::test]
+    async fn test_segment_keeps_pre_roll_and_leaves_trailing_frames() {
+        let manager = AudioBufferManager::with_config(10000, 200);
+        let frame = |timestamp_ms: u64| create_test_frame(timestamp_ms, vec![0.0; 1600]);  // 100ms
+        
+        for timestamp_ms in (0..1000).step_by(100) {
+            manager.push_frame(frame(timestamp_ms)).await.unwrap();
+        }
+        // 语音从 600ms 开始，pre-roll 300ms：保留 300ms 之后的帧
+        manager.begin_segment(300).await;
+        assert_eq!(manager.frame_count().await, 7);
+        assert_eq!(manager.duration_ms().await, 600);
+        
+        // 语音结束于 800ms，post-roll 100ms：900ms 的帧留给下一句
+        let segment = manager.take_segment(Some(900)).await;
+        let timestamps: Vec<u64> = segment.iter().map(|f| f.timestamp_ms).collect();
+        assert_eq!(timestamps, vec![300, 400, 500, 600, 700, 800]);
+        assert_eq!(manager.frame_count().await, 1);
+        
+        // 片段未提交时，下一次语音开始不再丢弃帧
+        manager.begin_segment(900).await;
+        manager.push_frame(frame(1000)).await.unwrap();
+        manager.begin_segment(1000).await;
+        assert_eq!(manager.frame_count().await, 2);
+    }
+    
     #[tokio::test]
     async fn test_merge_frames() {
         let frames = vec![
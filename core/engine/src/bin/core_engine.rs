@@ -3,7 +3,7 @@ use std::sync::Arc;
 use std::io::Cursor;
 use std::time::Instant;
 use axum::{
-    extract::{ws::{WebSocketUpgrade, WebSocket, Message}, State},
+    extract::{ws::{WebSocketUpgrade, WebSocket, Message}, Query, State},
     http::StatusCode,
     response::Response,
     routing::{get, post},
@@ -18,16 +18,22 @@ use base64::{Engine as _, engine::general_purpose};
 use core_engine::bootstrap::{CoreEngine, CoreEngineBuilder, ProcessResult};
 use core_engine::config_manager::{ConfigManager, EngineConfig};
 use core_engine::error::EngineResult;
+use core_engine::nmt_incremental::DecodingOptions;
 use core_engine::types::AudioFrame;
 use core_engine::health_check::HealthChecker;
 use core_engine::emotion_adapter::EmotionStub;
 use core_engine::persona_adapter::PersonaStub;
 use core_engine::event_bus::{EventBus, CoreEvent, EventTopic, EventSubscription, ChannelEventBus};
 use core_engine::vad::{VoiceActivityDetector, DetectionOutcome, SileroVad};
+use core_engine::vad::{EnergyVad, EnergyVadConfig, TimeBasedVad};
 use core_engine::cache_manager::CacheManager;
 use core_engine::telemetry::{TelemetrySink, TelemetryDatum};
 use core_engine::speaker_identifier::{SpeakerIdentifierMode, EmbeddingBasedMode, EmbeddingBasedSpeakerIdentifier};
 use core_engine::tts_streaming::YourTtsHttpConfig;
+use core_engine::tts_streaming::TtsAudioFormat;
+use core_engine::tts_streaming::{AudioCodec, AudioCodecConfig, TtsAudioEncoder, TtsStreamChunk, TtsStreamEncoder};
+use core_engine::asr_streaming::AsrStreamingConfig;
+use futures_util::stream::SplitSink;
 use async_trait::async_trait;
 
 /// 运行时配置（从 TOML 文件加载）
@@ -42,16 +48,32 @@ struct RuntimeConfig {
     #[serde(default)]
     yourtts: Option<YourTtsConfig>,
     engine: EngineRuntimeConfig,
+    #[serde(default)]
+    vad: VadRuntimeConfig,
+    #[serde(default)]
+    audio_codec: AudioCodecConfig,
+    #[serde(default)]
+    asr_streaming: AsrStreamingConfig,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 struct NmtConfig {
+    /// 本地 M2M100 服务 URL（级联的第一个后端）
     url: String,
+    /// 远程翻译 API 的基础 URL（None 表示不使用远程 API）
+    #[serde(default)]
+    remote_url: Option<String>,
+    /// 远程翻译 API 密钥
+    #[serde(default)]
+    remote_api_key: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 struct TtsConfig {
     url: String,
+    /// TTS 输出格式（None 表示保持后端原始格式）
+    #[serde(default)]
+    output_format: Option<TtsAudioFormat>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -74,6 +96,53 @@ struct EngineRuntimeConfig {
     port: u16,
     whisper_model_path: Option<String>,
     silero_vad_model_path: Option<String>,
+    /// 会话词表纠错使用的拼音词典（可选，相对路径从 crate_root 解析，默认 config/pinyin_lexicon.txt）
+    #[serde(default)]
+    pinyin_lexicon_path: Option<String>,
+}
+
+/// VAD 后端
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum VadBackend {
+    /// Silero 模型（模型文件不存在时退回 SimpleVad）
+    #[default]
+    Silero,
+    /// 能量/过零率 VAD（不需要模型文件）
+    Energy,
+    /// 固定时间间隔切分
+    TimeBased,
+}
+
+/// VAD 配置（`[vad]` 段，可省略）
+#[derive(Debug, Clone, Deserialize)]
+struct VadRuntimeConfig {
+    #[serde(default)]
+    backend: VadBackend,
+    /// EnergyVad 参数（`[vad.energy]` 段，省略的字段使用默认值）
+    #[serde(default)]
+    energy: EnergyVadConfig,
+    /// TimeBasedVad 的片段时长（毫秒）
+    #[serde(default = "default_time_based_segment_ms")]
+    time_based_segment_ms: u64,
+    /// 按用户保存 VAD 自适应状态的目录（可选，相对路径从 crate_root 解析）
+    #[serde(default)]
+    state_dir: Option<String>,
+}
+
+fn default_time_based_segment_ms() -> u64 {
+    3000
+}
+
+impl Default for VadRuntimeConfig {
+    fn default() -> Self {
+        Self {
+            backend: VadBackend::default(),
+            energy: EnergyVadConfig::default(),
+            time_based_segment_ms: default_time_based_segment_ms(),
+            state_dir: None,
+        }
+    }
 }
 
 /// S2S 请求（整句翻译）
@@ -82,12 +151,17 @@ struct S2SRequest {
     audio: String, // base64 编码的音频数据
     src_lang: String,
     tgt_lang: String,
+    #[serde(default)]
+    codec: Option<AudioCodec>,  // 输出音频编码（None 表示 TTS 后端的原始音频）
 }
 
 /// S2S 响应
 #[derive(Debug, Serialize)]
 struct S2SResponse {
     audio: String, // base64 编码的音频数据
+    audio_format: Option<TtsAudioFormat>,  // 音频格式（没有音频时为 None）
+    codec: Option<AudioCodec>,  // 音频编码（None 表示未编码的原始音频）
+    mime_type: Option<&'static str>,
     transcript: String,
     translation: String,
 }
@@ -111,10 +185,11 @@ struct ServiceHealth {
 struct AppState {
     engine: Arc<CoreEngine>,
     config: RuntimeConfig,
-    simple_config: Arc<SimpleConfig>,  // 用于动态更新语言配置
     event_bus: Arc<ChannelEventBus>,  // 事件总线（用于 WebSocket 订阅）
     speaker_mode: Arc<RwLock<EmbeddingBasedMode>>,  // 当前说话者识别模式
     speaker_identifier: Option<Arc<EmbeddingBasedSpeakerIdentifier>>,  // 说话者识别器引用（用于动态切换模式）
+    audio_encoder: Arc<TtsAudioEncoder>,  // TTS 输出音频编码器
+    available_codecs: Arc<Vec<AudioCodec>>,  // 启动时检测到的可用输出编码
 }
 
 // 简单的默认实现
@@ -155,6 +230,7 @@ impl VoiceActivityDetector for SimpleVad {
             is_boundary: is_final,
             confidence: 1.0,
             frame: cleaned_frame,
+            speech_event: None,
         })
     }
 }
@@ -173,14 +249,6 @@ impl SimpleConfig {
             target_lang: Arc::new(RwLock::new(target_lang)),
         }
     }
-
-    async fn set_target_language(&self, lang: String) {
-        *self.target_lang.write().await = lang;
-    }
-
-    async fn set_source_language(&self, lang: String) {
-        *self.source_lang.write().await = lang;
-    }
 }
 
 #[async_trait]
@@ -250,7 +318,7 @@ async fn main() -> anyhow::Result<()> {
     let _ = core_engine::asr_filters::config::init_config_from_file();
     eprintln!("[INFO] ASR filter config initialized");
 
-    // 3. 创建 SimpleConfig（用于动态更新语言）
+    // 3. 创建 SimpleConfig（默认语言，每个会话可以单独覆盖）
     let simple_config = Arc::new(SimpleConfig::new("en".to_string(), "zh".to_string()));
     
     // 4. 初始化事件总线（使用 ChannelEventBus 以支持真正的发布/订阅）
@@ -262,14 +330,20 @@ async fn main() -> anyhow::Result<()> {
     let (engine, speaker_identifier) = initialize_engine(&runtime_config, simple_config.clone(), event_bus.clone()).await?;
     eprintln!("[INFO] CoreEngine initialized successfully");
 
+    // 5.5. 检测可用的 TTS 输出编码（Opus / MP3 需要 ffmpeg）
+    let audio_encoder = Arc::new(TtsAudioEncoder::new(runtime_config.audio_codec.clone()));
+    let available_codecs = audio_encoder.available_codecs().await;
+    eprintln!("[INFO] Available TTS output codecs: {:?}", available_codecs);
+
     // 6. 启动 HTTP 服务器
     let app_state = AppState {
         engine: Arc::new(engine),
         config: runtime_config.clone(),
-        simple_config: simple_config.clone(),
         event_bus: event_bus.clone(),
         speaker_mode: Arc::new(RwLock::new(EmbeddingBasedMode::SingleUser)),  // 默认单人模式
         speaker_identifier,  // 说话者识别器引用（用于动态切换模式）
+        audio_encoder,
+        available_codecs: Arc::new(available_codecs),
     };
 
     let app = Router::new()
@@ -299,7 +373,7 @@ async fn initialize_engine(
 ) -> EngineResult<(CoreEngine, Option<Arc<EmbeddingBasedSpeakerIdentifier>>)> {
     let crate_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     
-    // 1. 初始化 SileroVad
+    // 1. 初始化 VAD（默认 SileroVad，可通过 [vad] backend 选择 energy / time_based）
     // 注意：配置文件中的路径可以是绝对路径或相对路径
     // - 绝对路径：直接使用（例如：D:\Programs\github\lingua\core\engine\models\vad\silero\silero_vad_official.onnx）
     // - 相对路径：从 crate_root 解析（例如：models/vad/silero/silero_vad_official.onnx）
@@ -323,7 +397,15 @@ async fn initialize_engine(
               silero_vad_model_path.display(), 
               silero_vad_model_path.exists());
     
-    let vad: Arc<dyn VoiceActivityDetector> = if silero_vad_model_path.exists() {
+    eprintln!("[INFO] VAD backend: {:?}", config.vad.backend);
+    let vad: Arc<dyn VoiceActivityDetector> = if config.vad.backend == VadBackend::Energy {
+        let energy_vad = EnergyVad::new(config.vad.energy.clone());
+        eprintln!("[INFO] Initializing {}", energy_vad.get_info());
+        Arc::new(energy_vad) as Arc<dyn VoiceActivityDetector>
+    } else if config.vad.backend == VadBackend::TimeBased {
+        eprintln!("[INFO] Initializing TimeBasedVad (segment={}ms)", config.vad.time_based_segment_ms);
+        Arc::new(TimeBasedVad::new(config.vad.time_based_segment_ms)) as Arc<dyn VoiceActivityDetector>
+    } else if silero_vad_model_path.exists() {
         eprintln!("[INFO] Initializing SileroVad from: {}", silero_vad_model_path.display());
         Arc::new(SileroVad::new(&silero_vad_model_path)
             .map_err(|e| core_engine::error::EngineError::new(format!("Failed to initialize SileroVad: {}", e)))?) as Arc<dyn VoiceActivityDetector>
@@ -347,9 +429,10 @@ async fn initialize_engine(
         builder = builder.asr_with_default_whisper()
             .map_err(|e| core_engine::error::EngineError::new(format!("Failed to initialize ASR: {}", e)))?;
     }
+    builder = builder.with_asr_streaming(config.asr_streaming.clone());
 
-    // 3. 初始化 NMT
-    builder = builder.nmt_with_m2m100_http_client(Some(&config.nmt.url))
+    // 3. 初始化 NMT（级联：本地 M2M100 服务 → 远程 API → 本地 ONNX Marian）
+    builder = builder.nmt_with_cascade(Some(&config.nmt.url), config.nmt.remote_url.as_deref(), config.nmt.remote_api_key.clone())
         .map_err(|e| core_engine::error::EngineError::new(format!("Failed to initialize NMT: {}", e)))?;
 
     // 4. 初始化 TTS（优先使用 YourTTS，否则使用 Piper TTS）
@@ -391,6 +474,30 @@ async fn initialize_engine(
         None
     };
 
+    // 会话词表纠错：用拼音词典做读音匹配（词典不存在时只按拼写比较）
+    let pinyin_lexicon = config.engine.pinyin_lexicon_path.as_deref()
+        .map(PathBuf::from)
+        .map(|path| if path.is_absolute() { path } else { crate_root.join(path) })
+        .unwrap_or_else(|| crate_root.join("config/pinyin_lexicon.txt"));
+    let pinyin_lexicon = if pinyin_lexicon.exists() {
+        Some(pinyin_lexicon)
+    } else {
+        eprintln!("[WARN] Pinyin lexicon not found at: {}, vocabulary correction will match spelling only", pinyin_lexicon.display());
+        None
+    };
+
+    // VAD 自适应状态按用户持久化（WebSocket 连接携带 user_id 时恢复/保存）
+    if let Some(ref state_dir) = config.vad.state_dir {
+        let state_dir = PathBuf::from(state_dir);
+        let state_dir = if state_dir.is_absolute() { state_dir } else { crate_root.join(state_dir) };
+        builder = builder.with_vad_state_persistence(state_dir);
+    }
+
+    // TTS 输出格式：所有后端的音频统一转换为配置的格式
+    if let Some(output_format) = config.tts.output_format {
+        builder = builder.with_tts_output_format(output_format);
+    }
+
     // 6. 构建 CoreEngine
     let engine = builder
         .emotion(Arc::new(EmotionStub))
@@ -399,6 +506,7 @@ async fn initialize_engine(
         .cache(Arc::new(SimpleCache))
         .telemetry(Arc::new(SimpleTelemetry))
         .with_post_processing(None, true)
+        .with_vocabulary_correction(pinyin_lexicon.as_deref(), core_engine::vocabulary_correction::DEFAULT_MAX_EDIT_RATIO)
         .with_tts_incremental_playback(true, 0, 50)
         .with_audio_enhancement(core_engine::tts_audio_enhancement::AudioEnhancementConfig::default())
         .with_continuous_mode(true, 5000, 200)  // 启用连续模式以支持 WebSocket 流式处理 (max_buffer=5s, min_segment=200ms)
@@ -438,6 +546,13 @@ async fn s2s_handler(
 ) -> Result<Json<S2SResponse>, StatusCode> {
     let s2s_start = Instant::now();
     eprintln!("[S2S] ===== Request started =====");
+
+    if let Some(codec) = request.codec {
+        if !state.available_codecs.contains(&codec) {
+            eprintln!("[ERROR] Requested codec {:?} is not available (available: {:?})", codec, state.available_codecs);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
     
     // 1. 解码 base64 音频
     let audio_data = general_purpose::STANDARD
@@ -474,10 +589,11 @@ async fn s2s_handler(
         request.tgt_lang
     );
 
-    // 3. 根据请求更新目标语言配置
-    state.simple_config.set_target_language(request.tgt_lang.clone()).await;
-    state.simple_config.set_source_language(request.src_lang.clone()).await;
-    eprintln!("[S2S] Updated language config: src={}, tgt={}", request.src_lang, request.tgt_lang);
+    // 3. 为本次请求创建独立会话（语言对只对本次请求生效）
+    let session = state.engine
+        .create_session(Some(request.src_lang.clone()), Some(request.tgt_lang.clone()))
+        .await;
+    eprintln!("[S2S] Session {} created: src={}, tgt={}", session.id(), request.src_lang, request.tgt_lang);
 
     // 4. 处理所有音频帧，累积到 ASR 缓冲区
     // 对于整句翻译，我们需要处理所有帧，最后一帧应该触发边界检测
@@ -485,7 +601,7 @@ async fn s2s_handler(
     
     // 处理所有帧，除了最后一帧
     for frame in audio_frames.iter().take(audio_frames.len().saturating_sub(1)) {
-        match state.engine.process_audio_frame(frame.clone(), Some(request.src_lang.clone())).await {
+        match state.engine.process_audio_frame(&session, frame.clone()).await {
             Ok(Some(result)) => {
                 // 记录最新结果，但继续处理剩余帧，确保音频被完整消耗
                 final_result = Some(result);
@@ -506,7 +622,7 @@ async fn s2s_handler(
         if let Some(last_frame) = audio_frames.last() {
             // 创建一个标记为边界的帧（通过修改 timestamp 或使用特殊处理）
             // 实际上，SimpleVad 总是返回 is_boundary=true，所以最后一帧应该触发推理
-            match state.engine.process_audio_frame(last_frame.clone(), Some(request.src_lang.clone())).await {
+            match state.engine.process_audio_frame(&session, last_frame.clone()).await {
                 Ok(Some(result)) => {
                     final_result = Some(result);
                 }
@@ -551,18 +667,23 @@ async fn s2s_handler(
     }
 
     // 7. 获取 TTS 音频（base64 编码）
-    let audio_base64 = if let Some(tts_chunk) = result.tts {
+    let audio_format = result.tts.as_ref().map(|tts_chunk| tts_chunk.format);
+    let (audio_base64, codec) = if let Some(tts_chunk) = result.tts {
         let audio_size = tts_chunk.audio.len();
         eprintln!("[S2S] TTS audio size: {} bytes", audio_size);
         if audio_size > 0 {
-            general_purpose::STANDARD.encode(&tts_chunk.audio)
+            let (audio, codec) = encode_tts_audio(&state.audio_encoder, tts_chunk, request.codec).await;
+            if codec.is_some() {
+                eprintln!("[S2S] TTS audio encoded as {:?}: {} bytes", codec, audio.len());
+            }
+            (general_purpose::STANDARD.encode(&audio), codec)
         } else {
             eprintln!("[S2S] WARNING: TTS audio is empty!");
-            String::new()
+            (String::new(), None)
         }
     } else {
         eprintln!("[S2S] WARNING: TTS result is None!");
-        String::new()
+        (String::new(), None)
     };
 
     // 8. 计算总时长并返回结果
@@ -574,6 +695,9 @@ async fn s2s_handler(
     
     Ok(Json(S2SResponse {
         audio: audio_base64,
+        audio_format,
+        codec,
+        mime_type: codec.map(|codec| codec.mime_type()),
         transcript,
         translation,
     }))
@@ -643,14 +767,75 @@ fn parse_wav_to_frames(wav_data: &[u8]) -> anyhow::Result<Vec<AudioFrame>> {
 async fn stream_handler(
     ws: WebSocketUpgrade,
     State(state): State<AppState>,
+    Query(params): Query<StreamParams>,
 ) -> Response {
     ws.on_upgrade(move |socket| async move {
-        handle_socket(socket, state).await;
+        handle_socket(socket, state, params.user_id).await;
     })
 }
 
-async fn handle_socket(socket: WebSocket, state: AppState) {
-    eprintln!("[WebSocket] ✅ Client connected");
+/// WebSocket 连接参数（`/stream?user_id=...`）
+#[derive(Debug, Deserialize)]
+struct StreamParams {
+    /// 用户 ID（用于恢复/保存该用户的 VAD 自适应状态）
+    #[serde(default)]
+    user_id: Option<String>,
+}
+
+/// 客户端协商的 TTS 音频输出方式（每个 WebSocket 连接独立）
+#[derive(Debug, Clone, Copy, Default)]
+struct AudioOutputOptions {
+    /// 输出编码（None 表示发送 TTS 后端的原始音频）
+    codec: Option<AudioCodec>,
+    /// 是否以 WebSocket 二进制帧发送音频（否则以 base64 放在 JSON 的 audio 字段中）
+    binary: bool,
+}
+
+/// 按客户端请求的编码编码 TTS 音频，返回 (音频数据, 实际使用的编码)
+/// 编码失败时回退为原始音频（编码为 None），不中断翻译
+async fn encode_tts_audio(
+    encoder: &TtsAudioEncoder,
+    chunk: TtsStreamChunk,
+    codec: Option<AudioCodec>,
+) -> (Vec<u8>, Option<AudioCodec>) {
+    let Some(codec) = codec else {
+        return (chunk.audio, None);
+    };
+    match encoder.encode(&chunk, codec).await {
+        Ok(encoded) => (encoded.data, Some(codec)),
+        Err(e) => {
+            eprintln!("[Codec] ⚠️ Failed to encode TTS audio as {:?}, sending original audio: {}", codec, e);
+            (chunk.audio, None)
+        }
+    }
+}
+
+/// 发送带音频的消息
+/// - 文本模式：音频以 base64 放在 `audio` 字段
+/// - 二进制模式：先发送 JSON（`audio` 为 null，`audio_bytes` 为音频长度），紧接着发送音频二进制帧（持有锁，保证两帧相邻）
+async fn send_with_audio(
+    sender: &tokio::sync::Mutex<SplitSink<WebSocket, Message>>,
+    mut message: serde_json::Value,
+    audio: Option<Vec<u8>>,
+    binary: bool,
+) -> Result<(), axum::Error> {
+    let mut sender_guard = sender.lock().await;
+    match audio {
+        Some(audio) if binary => {
+            message["audio"] = serde_json::Value::Null;
+            message["audio_bytes"] = serde_json::json!(audio.len());
+            sender_guard.send(Message::Text(message.to_string())).await?;
+            sender_guard.send(Message::Binary(audio)).await
+        }
+        audio => {
+            message["audio"] = serde_json::json!(audio.map(|audio| general_purpose::STANDARD.encode(audio)));
+            sender_guard.send(Message::Text(message.to_string())).await
+        }
+    }
+}
+
+async fn handle_socket(socket: WebSocket, state: AppState, user_id: Option<String>) {
+    eprintln!("[WebSocket] ✅ Client connected (user_id: {:?})", user_id);
 
     // 分离 WebSocket 的发送端和接收端
     let (sender, mut receiver) = socket.split();
@@ -661,18 +846,62 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
     let mut src_lang = "en".to_string(); // 默认源语言
     let mut tgt_lang = "zh".to_string(); // 默认目标语言
     let mut frame_count = 0u64;
+    // TTS 音频输出方式（由 config 消息的 codec / binary_audio 设置，TTS 转发任务共享）
+    let output_options = Arc::new(RwLock::new(AudioOutputOptions::default()));
+    
+    // 每个连接拥有独立的会话（语言对、缓冲区、VAD 状态、说话者状态）
+    let session = state.engine
+        .create_session_for_user(Some(src_lang.clone()), Some(tgt_lang.clone()), user_id)
+        .await;
+    let session_id = session.id().to_string();
+    eprintln!("[WebSocket] 🆔 Session {} created", session_id);
+    
+    // 订阅 VAD 语音开始/结束事件，转发给客户端（用于打断 barge-in：用户开口时停止播放 TTS）
+    let mut speech_start_receiver = state.event_bus.subscribe_receiver(EventTopic("VadSpeechStart".to_string()));
+    let mut speech_end_receiver = state.event_bus.subscribe_receiver(EventTopic("VadSpeechEnd".to_string()));
+    let sender_for_vad = Arc::clone(&sender);
+    let session_id_for_vad = session_id.clone();
+    let vad_forwarder = tokio::spawn(async move {
+        loop {
+            let (message_type, event) = tokio::select! {
+                Some(event) = speech_start_receiver.recv() => ("vad_speech_start", event),
+                Some(event) = speech_end_receiver.recv() => ("vad_speech_end", event),
+                else => break,
+            };
+            if event.payload.get("session_id").and_then(|v| v.as_str()) != Some(session_id_for_vad.as_str()) {
+                continue;
+            }
+            let mut response_json = event.payload.clone();
+            response_json["type"] = serde_json::json!(message_type);
+            response_json["timestamp_ms"] = serde_json::json!(event.timestamp_ms);
+            
+            let mut sender_guard = sender_for_vad.lock().await;
+            if let Err(e) = sender_guard.send(Message::Text(response_json.to_string())).await {
+                eprintln!("[WebSocket] ❌ Failed to send VAD event: {}", e);
+                return;
+            }
+        }
+    });
     
     // 订阅 TTS 事件，用于接收增量音频输出
     let mut tts_receiver_from_bus = state.event_bus.subscribe_receiver(EventTopic("Tts".to_string()));
     eprintln!("[WebSocket] 📡 Subscribed to TTS events");
     
-    // 启动任务：从事件总线接收 TTS 事件，按 timestamp_ms 排序后发送到 WebSocket
+    // 启动任务：从事件总线接收本会话的 TTS 事件，按 timestamp_ms 排序后发送到 WebSocket
     let sender_for_tts = Arc::clone(&sender);
-    tokio::spawn(async move {
+    let output_options_for_tts = Arc::clone(&output_options);
+    let encoder_for_tts = Arc::clone(&state.audio_encoder);
+    let tts_forwarder = tokio::spawn(async move {
         let mut pending_events: Vec<CoreEvent> = Vec::new();
         let mut next_expected_timestamp = 0u64;
+        // 流式编码器：同一次合成的音频块编码到同一个容器中（客户端切换编码时重新创建）
+        let mut stream_encoder: Option<TtsStreamEncoder> = None;
         
         while let Some(event) = tts_receiver_from_bus.recv().await {
+            // 只转发属于本会话的音频
+            if event.payload.get("session_id").and_then(|v| v.as_str()) != Some(session_id.as_str()) {
+                continue;
+            }
             pending_events.push(event);
             
             // 按 timestamp_ms 排序
@@ -685,24 +914,56 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                 
                 // 解析事件 payload
                 if let Some(audio_base64) = event.payload.get("audio").and_then(|v| v.as_str()) {
+                    let is_last = event.payload.get("is_last").and_then(|v| v.as_bool()).unwrap_or(false);
+                    let audio = match general_purpose::STANDARD.decode(audio_base64) {
+                        Ok(audio) => audio,
+                        Err(e) => {
+                            eprintln!("[WebSocket] ❌ Failed to decode TTS event audio: {}", e);
+                            continue;
+                        }
+                    };
+                    let options = *output_options_for_tts.read().await;
+                    // 按客户端请求的编码重新编码（需要事件中的音频格式信息）
+                    let format = event.payload.get("format")
+                        .and_then(|format| serde_json::from_value::<TtsAudioFormat>(format.clone()).ok());
+                    let (audio, codec) = match (format, options.codec) {
+                        (Some(format), Some(codec)) => {
+                            let chunk = TtsStreamChunk { audio, format, timestamp_ms: event.timestamp_ms, is_last };
+                            let encoder = match stream_encoder.as_mut() {
+                                Some(encoder) if encoder.codec() == codec => encoder,
+                                _ => stream_encoder.insert(encoder_for_tts.stream_encoder(codec)),
+                            };
+                            match encoder.push(&chunk).await {
+                                Ok(encoded) => (encoded.data, Some(codec)),
+                                Err(e) => {
+                                    eprintln!("[Codec] ⚠️ Failed to encode TTS audio as {:?}, sending original audio: {}", codec, e);
+                                    (chunk.audio, None)
+                                }
+                            }
+                        }
+                        _ => (audio, None),
+                    };
+                    let audio_size = audio.len();
                     let response_json = serde_json::json!({
                         "type": "tts_chunk",
-                        "audio": audio_base64,
+                        "format": event.payload.get("format"),
+                        "codec": codec,
+                        "mime_type": codec.map(|codec| codec.mime_type()),
                         "timestamp_ms": event.timestamp_ms,
-                        "is_last": event.payload.get("is_last").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "is_last": is_last,
                     });
                     
-                    let mut sender_guard = sender_for_tts.lock().await;
-                    if let Err(e) = sender_guard.send(Message::Text(response_json.to_string())).await {
+                    if let Err(e) = send_with_audio(&sender_for_tts, response_json, Some(audio), options.binary).await {
                         eprintln!("[WebSocket] ❌ Failed to send TTS event: {}", e);
                         return;
                     }
-                    drop(sender_guard); // 显式释放锁
                     
-                    eprintln!("[WebSocket] 📤 Sent TTS chunk (timestamp: {}ms, is_last: {}, audio_size: {} chars)", 
+                    eprintln!("[WebSocket] 📤 Sent TTS chunk (timestamp: {}ms, is_last: {}, audio_size: {} bytes, codec: {:?}, binary: {})", 
                         event.timestamp_ms,
-                        event.payload.get("is_last").and_then(|v| v.as_bool()).unwrap_or(false),
-                        audio_base64.len());
+                        is_last,
+                        audio_size,
+                        codec,
+                        options.binary);
                 }
             }
         }
@@ -729,9 +990,105 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                         if let Some(lang) = json_msg["tgt_lang"].as_str() {
                             tgt_lang = lang.to_string();
                         }
-                        state.simple_config.set_source_language(src_lang.clone()).await;
-                        state.simple_config.set_target_language(tgt_lang.clone()).await;
-                        eprintln!("[WebSocket] ⚙️ Config updated: src={}, tgt={}", src_lang, tgt_lang);
+                        session.set_languages(Some(src_lang.clone()), tgt_lang.clone());
+                        eprintln!("[WebSocket] ⚙️ Config updated for session {}: src={}, tgt={}", session.id(), src_lang, tgt_lang);
+                        // 可选：双语会话（例如 {"bilingual": ["zh", "en"]}），按每句检测到的语言自动选择翻译方向；null 表示关闭
+                        if let Some(bilingual) = json_msg.get("bilingual") {
+                            let languages = bilingual.as_array().and_then(|langs| match langs.as_slice() {
+                                [a, b] => Some((a.as_str()?.to_string(), b.as_str()?.to_string())),
+                                _ => None,
+                            });
+                            if languages.is_none() && !bilingual.is_null() {
+                                eprintln!("[WebSocket] ⚠️ Invalid bilingual config for session {}: {}", session.id(), bilingual);
+                            }
+                            session.set_bilingual_languages(languages);
+                            eprintln!("[WebSocket] ⚙️ Bilingual languages updated for session {}: {:?}", session.id(), session.bilingual_languages());
+                        }
+                        // 可选：会话词表（例如 {"vocabulary": ["Lingua", "M2M100"]}），作为 ASR 热词并用于纠正识别文本；null 表示清空
+                        if let Some(vocabulary) = json_msg.get("vocabulary") {
+                            let words = vocabulary.as_array().map(|words| {
+                                words.iter().filter_map(|word| word.as_str().map(str::to_string)).collect::<Vec<_>>()
+                            });
+                            if words.is_none() && !vocabulary.is_null() {
+                                eprintln!("[WebSocket] ⚠️ Invalid vocabulary config for session {}: {}", session.id(), vocabulary);
+                            }
+                            session.set_vocabulary(words.unwrap_or_default());
+                            eprintln!("[WebSocket] ⚙️ Vocabulary updated for session {}: {:?}", session.id(), session.vocabulary());
+                        }
+                        // 可选：NMT 解码参数（例如 {"strategy": {"type": "beam_search", "num_beams": 4}}）
+                        if let Some(decoding) = json_msg.get("decoding") {
+                            match serde_json::from_value::<DecodingOptions>(decoding.clone()) {
+                                Ok(options) => {
+                                    eprintln!("[WebSocket] ⚙️ Decoding options updated for session {}: {:?}", session.id(), options);
+                                    session.set_decoding_options(Some(options));
+                                }
+                                Err(e) => {
+                                    eprintln!("[WebSocket] ⚠️ Invalid decoding options for session {}: {}", session.id(), e);
+                                }
+                            }
+                        }
+                        // 可选：wait-k 同传（例如 {"wait_k": 3}；0 或 null 表示只翻译最终结果）
+                        if let Some(wait_k) = json_msg.get("wait_k") {
+                            let wait_k = wait_k.as_u64().map(|k| k.min(u8::MAX as u64) as u8);
+                            session.set_wait_k(wait_k);
+                            eprintln!("[WebSocket] ⚙️ wait-k updated for session {}: {:?}", session.id(), session.wait_k());
+                        }
+                        // 可选：TTS 输出格式（例如 {"tts_format": {"encoding": "pcm", "sample_rate": 16000, "channels": 1}}；null 表示使用服务端默认格式）
+                        if let Some(tts_format) = json_msg.get("tts_format") {
+                            let format = serde_json::from_value::<Option<TtsAudioFormat>>(tts_format.clone())
+                                .map_err(|e| e.to_string())
+                                .and_then(|format| match format {
+                                    Some(format) => format.validate().map(|_| Some(format)).map_err(|e| e.to_string()),
+                                    None => Ok(None),
+                                });
+                            match format {
+                                Ok(format) => {
+                                    session.set_tts_output_format(format);
+                                    eprintln!("[WebSocket] ⚙️ TTS output format updated for session {}: {:?}", session.id(), format);
+                                }
+                                Err(e) => {
+                                    eprintln!("[WebSocket] ⚠️ Invalid TTS output format for session {}: {}", session.id(), e);
+                                }
+                            }
+                        }
+                        // 可选：TTS 音频输出编码和传输方式（例如 {"codec": "ogg_opus", "binary_audio": true}；codec 为 null 表示原始音频）
+                        if json_msg.get("codec").is_some() || json_msg.get("binary_audio").is_some() {
+                            let options = {
+                                let mut options = output_options.write().await;
+                                if let Some(codec) = json_msg.get("codec") {
+                                    match serde_json::from_value::<Option<AudioCodec>>(codec.clone()) {
+                                        Ok(Some(codec)) if !state.available_codecs.contains(&codec) => {
+                                            eprintln!("[WebSocket] ⚠️ Codec {:?} is not available for session {} (available: {:?})",
+                                                codec, session.id(), state.available_codecs);
+                                        }
+                                        Ok(codec) => options.codec = codec,
+                                        Err(e) => {
+                                            eprintln!("[WebSocket] ⚠️ Invalid codec for session {}: {}", session.id(), e);
+                                        }
+                                    }
+                                }
+                                if let Some(binary) = json_msg.get("binary_audio").and_then(|v| v.as_bool()) {
+                                    options.binary = binary;
+                                }
+                                *options
+                            };
+                            eprintln!("[WebSocket] ⚙️ Audio output updated for session {}: {:?}", session.id(), options);
+                            // 回复实际生效的输出方式（请求的编码不可用时客户端据此回退）
+                            let response_json = serde_json::json!({
+                                "type": "audio_output",
+                                "codec": options.codec,
+                                "mime_type": options.codec.map(|codec| codec.mime_type()),
+                                "binary_audio": options.binary,
+                                "available_codecs": state.available_codecs.as_slice(),
+                            });
+                            let mut sender_guard = sender.lock().await;
+                            if let Err(e) = sender_guard.send(Message::Text(response_json.to_string())).await {
+                                eprintln!("[WebSocket] ❌ Failed to send audio output config: {}", e);
+                                drop(sender_guard);
+                                break;
+                            }
+                            drop(sender_guard); // 显式释放锁
+                        }
                     } else if json_msg["type"] == "audio_frame" {
                         // 处理音频帧
                         if let (Some(base64_audio), Some(timestamp_ms), Some(sample_rate), Some(channels)) = (
@@ -777,39 +1134,42 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                             }
 
                             // 处理音频帧（如果启用了连续模式，会自动使用连续处理逻辑）
-                            match state.engine.process_audio_frame(audio_frame, Some(src_lang.clone())).await {
+                            match state.engine.process_audio_frame(&session, audio_frame).await {
                     Ok(Some(result)) => {
                                     // 发送 ASR 转录、NMT 翻译和 TTS 音频
-                                    let tts_audio_base64 = result.tts.as_ref().and_then(|t| {
-                                        if t.audio.is_empty() {
+                                    let options = *output_options.read().await;
+                                    let tts_audio = match result.tts.clone() {
+                                        Some(t) if t.audio.is_empty() => {
                                             eprintln!("[WebSocket] ⚠️ TTS audio is empty!");
                                             None
-                                        } else {
-                                            eprintln!("[WebSocket] 📤 Sending TTS audio: {} bytes (base64: {} chars)", 
-                                                t.audio.len(), 
-                                                general_purpose::STANDARD.encode(&t.audio).len());
-                                            Some(general_purpose::STANDARD.encode(&t.audio))
                                         }
-                                    });
+                                        Some(t) => {
+                                            let (audio, codec) = encode_tts_audio(&state.audio_encoder, t, options.codec).await;
+                                            eprintln!("[WebSocket] 📤 Sending TTS audio: {} bytes (codec: {:?}, binary: {})", 
+                                                audio.len(), codec, options.binary);
+                                            Some((audio, codec))
+                                        }
+                                        None => None,
+                                    };
+                                    let codec = tts_audio.as_ref().and_then(|(_, codec)| *codec);
                                     
                                     let response_json = serde_json::json!({
                                         "transcript": result.asr.final_transcript.as_ref().map(|t| t.text.clone()),
                                         "translation": result.translation.as_ref().map(|t| t.translated_text.clone()),
-                                        "audio": tts_audio_base64,
+                                        "audio_format": result.tts.as_ref().map(|t| t.format),
+                                        "codec": codec,
+                                        "mime_type": codec.map(|codec| codec.mime_type()),
                                     });
                                     
                                     eprintln!("[WebSocket] 📤 Sending response: transcript={:?}, translation={:?}, audio={}", 
                                         result.asr.final_transcript.as_ref().map(|t| t.text.as_str()),
                                         result.translation.as_ref().map(|t| t.translated_text.as_str()),
-                                        if tts_audio_base64.is_some() { "Yes" } else { "No" });
+                                        if tts_audio.is_some() { "Yes" } else { "No" });
                                     
-                                    let mut sender_guard = sender.lock().await;
-                                    if let Err(e) = sender_guard.send(Message::Text(response_json.to_string())).await {
+                                    if let Err(e) = send_with_audio(&sender, response_json, tts_audio.map(|(audio, _)| audio), options.binary).await {
                                         eprintln!("[WebSocket] ❌ Failed to send response: {}", e);
-                                        drop(sender_guard);
                                         break;
                                     }
-                                    drop(sender_guard); // 显式释放锁
                                     }
                                     Ok(None) => {
                                     // 没有最终结果，继续处理
@@ -853,7 +1213,12 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
             }
         }
     }
-    eprintln!("[WebSocket] 👋 Connection closed (total frames: {})", frame_count);
+    tts_forwarder.abort();
+    vad_forwarder.abort();
+    if let Err(e) = state.engine.save_session_vad_state(&session) {
+        eprintln!("[WebSocket] ⚠️ Failed to save VAD state: {}", e);
+    }
+    eprintln!("[WebSocket] 👋 Connection closed (session: {}, total frames: {})", session.id(), frame_count);
 }
 
 /// 获取当前说话者识别模式
@@ -1,8 +1,9 @@
 ﻿use std::path::PathBuf;
 use std::sync::Arc;
 use std::path::Path;
+use std::time::Duration;
 
-use crate::asr_streaming::AsrStreaming;
+use crate::asr_streaming::{AsrStreaming, AsrStreamingConfig};
 use crate::asr_whisper::{WhisperAsrStreaming, FasterWhisperAsrStreaming};
 use crate::audio_buffer::AudioBufferManager;
 use crate::speaker_voice_mapper::SpeakerVoiceMapper;
@@ -12,17 +13,21 @@ use crate::config_manager::ConfigManager;
 use crate::emotion_adapter::EmotionAdapter;
 use crate::error::{EngineError, EngineResult};
 use crate::event_bus::EventBus;
-use crate::nmt_incremental::{NmtIncremental, MarianNmtOnnx, M2M100NmtOnnx};
-use crate::nmt_client::{LocalM2m100HttpClient, NmtClientAdapter};
+use crate::nmt_incremental::{NmtIncremental, MarianModelRegistry, MarianNmtOnnx, M2M100NmtOnnx};
+use crate::nmt_client::{CascadingNmt, CircuitBreakerConfig, LocalM2m100HttpClient, NmtClientAdapter, RemoteNmtHttpClient};
 use crate::persona_adapter::PersonaAdapter;
 use crate::telemetry::TelemetrySink;
 use crate::tts_streaming::{TtsStreaming, VitsTtsEngine, PiperHttpTts, PiperHttpConfig, YourTtsHttp, YourTtsHttpConfig};
-use crate::vad::VoiceActivityDetector;
+use crate::vad::{AdaptiveStateStore, VoiceActivityDetector};
 use crate::post_processing::TextPostProcessor;
 use crate::performance_logger::PerformanceLogger;
 use crate::text_segmentation::TextSegmenter;
 use crate::translation_quality::TranslationQualityChecker;
 use crate::tts_audio_enhancement::{AudioEnhancer, AudioEnhancementConfig};
+use crate::tts_streaming::TtsAudioFormat;
+use crate::tts_streaming::SsmlTts;
+use crate::tts_streaming::NormalizedTts;
+use crate::vocabulary_correction::{PinyinTable, VocabularyCorrector};
 
 use super::core::CoreEngine;
 
@@ -31,6 +36,7 @@ pub struct CoreEngineBuilder {
     event_bus: Option<Arc<dyn EventBus>>,
     vad: Option<Arc<dyn VoiceActivityDetector>>,
     asr: Option<Arc<dyn AsrStreaming>>,
+    asr_streaming: Option<AsrStreamingConfig>,
     nmt: Option<Arc<dyn NmtIncremental>>,
     emotion: Option<Arc<dyn EmotionAdapter>>,
     persona: Option<Arc<dyn PersonaAdapter>>,
@@ -44,7 +50,10 @@ pub struct CoreEngineBuilder {
     perf_logger: Option<Arc<PerformanceLogger>>,
     text_segmenter: Option<Arc<TextSegmenter>>,
     audio_enhancer: Option<Arc<AudioEnhancer>>,
+    tts_output_format: Option<TtsAudioFormat>,
     quality_checker: Option<Arc<TranslationQualityChecker>>,
+    vocabulary_corrector: Option<Arc<VocabularyCorrector>>,
+    vad_state_store: Option<Arc<AdaptiveStateStore>>,
     // 服务 URL（用于健康检查）
     nmt_service_url: Option<String>,
     tts_service_url: Option<String>,
@@ -66,6 +75,7 @@ impl CoreEngineBuilder {
             event_bus: None,
             vad: None,
             asr: None,
+            asr_streaming: None,
             nmt: None,
             emotion: None,
             persona: None,
@@ -78,7 +88,10 @@ impl CoreEngineBuilder {
             perf_logger: None,
             text_segmenter: None,
             audio_enhancer: None,
+            tts_output_format: None,
             quality_checker: None,
+            vocabulary_corrector: None,
+            vad_state_store: None,
             nmt_service_url: None,
             tts_service_url: None,
             tts_incremental_enabled: false,
@@ -168,6 +181,28 @@ impl CoreEngineBuilder {
         Ok(self)
     }
 
+    /// 使用 Marian 多语言对模型注册表初始化 NMT 模块
+    /// 
+    /// 扫描 `core/engine/models/nmt/` 下所有 `marian-{src}-{tgt}` 目录，按会话请求的语言对路由，
+    /// 模型在第一次使用时加载；没有直接的语言对时经英语中转。
+    /// 
+    /// # Arguments
+    /// * `memory_budget_bytes` - 已加载模型的内存预算（None 使用默认值），超出时按 LRU 淘汰
+    pub fn nmt_with_marian_model_registry(mut self, memory_budget_bytes: Option<u64>) -> EngineResult<Self> {
+        let crate_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let base_dir = crate_root.join("models/nmt");
+
+        let mut registry = MarianModelRegistry::scan(&base_dir)
+            .map_err(|e| EngineError::new(format!("Failed to scan Marian models: {}", e)))?;
+        if let Some(budget) = memory_budget_bytes {
+            registry = registry.with_memory_budget(budget);
+        }
+
+        self.nmt = Some(Arc::new(registry));
+
+        Ok(self)
+    }
+
     /// 使用默认的 M2M100 NMT ONNX 模型初始化 NMT 模块
     /// 
     /// 模型路径：`core/engine/models/nmt/m2m100-en-zh/`
@@ -236,6 +271,51 @@ impl CoreEngineBuilder {
         Ok(self)
     }
 
+    /// 使用级联 NMT 初始化 NMT 模块：本地 M2M100 服务 → 远程 API（可选）→ 本地 ONNX Marian（最后手段）
+    /// 
+    /// 每个后端有独立的超时，连续失败的后端由熔断器暂时跳过；实际提供翻译的后端记录在
+    /// `TranslationResponse::provider` 和性能日志中。
+    /// 
+    /// # Arguments
+    /// * `local_url` - 本地 M2M100 服务 URL，默认为 "http://127.0.0.1:5008"
+    /// * `remote_url` - 远程翻译 API 的基础 URL（None 表示不使用远程 API）
+    /// * `remote_api_key` - 远程 API 密钥（可选）
+    pub fn nmt_with_cascade(
+        mut self,
+        local_url: Option<&str>,
+        remote_url: Option<&str>,
+        remote_api_key: Option<String>,
+    ) -> EngineResult<Self> {
+        let local_url = local_url.unwrap_or("http://127.0.0.1:5008");
+        
+        // 保存本地服务 URL（用于健康检查）
+        self.nmt_service_url = Some(local_url.to_string());
+        
+        let local = NmtClientAdapter::new(Arc::new(LocalM2m100HttpClient::new(local_url)));
+        let mut cascade = CascadingNmt::new(CircuitBreakerConfig::default())
+            .with_backend("local-m2m100", Arc::new(local), Duration::from_secs(5));
+        
+        if let Some(remote_url) = remote_url {
+            let remote = NmtClientAdapter::new(Arc::new(RemoteNmtHttpClient::new(remote_url, remote_api_key)));
+            cascade = cascade.with_backend("remote", Arc::new(remote), Duration::from_secs(10));
+        }
+        
+        // 本地 ONNX Marian 作为最后手段（没有导出模型时跳过）
+        let crate_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        match MarianModelRegistry::scan(&crate_root.join("models/nmt")) {
+            Ok(registry) => {
+                cascade = cascade.with_blocking_backend("marian-onnx", Arc::new(registry), Duration::from_secs(30));
+            }
+            Err(e) => {
+                eprintln!("[NMT Cascade] ⚠️ Marian ONNX fallback unavailable: {}", e);
+            }
+        }
+        
+        self.nmt = Some(Arc::new(cascade));
+        
+        Ok(self)
+    }
+
     /// 使用指定语言对的 M2M100 NMT ONNX 模型初始化 NMT 模块
     /// 
     /// # Arguments
@@ -459,6 +539,58 @@ impl CoreEngineBuilder {
         self
     }
     
+    /// 配置会话词表纠错（不调用时使用不带拼音表的默认纠错器，只按拼写比较）
+    /// 
+    /// # Arguments
+    /// * `pinyin_lexicon` - 拼音词典文件路径（可选，格式见 `PinyinTable::from_lexicon`）；加载失败时只按拼写比较
+    /// * `max_edit_ratio` - 允许的最大编辑距离比例（默认 `DEFAULT_MAX_EDIT_RATIO`）
+    pub fn with_vocabulary_correction(mut self, pinyin_lexicon: Option<&Path>, max_edit_ratio: f32) -> Self {
+        let pinyin = match pinyin_lexicon.map(PinyinTable::from_lexicon) {
+            Some(Ok(table)) => {
+                eprintln!("[CoreEngine] 🔤 Loaded pinyin lexicon ({} characters)", table.len());
+                table
+            }
+            Some(Err(e)) => {
+                eprintln!("[CoreEngine] ⚠️ {}, vocabulary correction will compare spelling only", e);
+                PinyinTable::new()
+            }
+            None => PinyinTable::new(),
+        };
+        self.vocabulary_corrector = Some(Arc::new(VocabularyCorrector::new(pinyin, max_edit_ratio)));
+        self
+    }
+    
+    /// 启用按用户持久化 VAD 自适应状态（停顿阈值、语速历史等，每个用户一个 JSON 文件）
+    /// 
+    /// 使用 `CoreEngine::create_session_for_user` 创建的会话在开始时恢复用户的状态，
+    /// 调用 `CoreEngine::save_session_vad_state` 时保存。目录创建失败时不启用持久化。
+    /// 
+    /// # Arguments
+    /// * `dir` - 状态文件目录
+    pub fn with_vad_state_persistence(mut self, dir: impl Into<PathBuf>) -> Self {
+        match AdaptiveStateStore::new(dir) {
+            Ok(store) => {
+                eprintln!("[CoreEngine] 💾 VAD adaptive state persistence enabled: {}", store.dir().display());
+                self.vad_state_store = Some(Arc::new(store));
+            }
+            Err(e) => {
+                eprintln!("[CoreEngine] ⚠️ {}, VAD adaptive state will not be persisted", e);
+            }
+        }
+        self
+    }
+    
+    /// 设置 ASR 流式识别模式（部分结果、滑动窗口 + LocalAgreement）
+    /// 
+    /// 在 `build()` 时应用到 ASR；ASR 不支持该模式时打印警告并只在边界处推理。
+    /// 
+    /// # Arguments
+    /// * `config` - 流式识别配置
+    pub fn with_asr_streaming(mut self, config: AsrStreamingConfig) -> Self {
+        self.asr_streaming = Some(config);
+        self
+    }
+    
     /// 启用性能日志
     /// 
     /// # Arguments
@@ -502,6 +634,27 @@ impl CoreEngineBuilder {
         self
     }
     
+    /// 设置 TTS 默认输出格式
+    /// 
+    /// 所有 TTS 后端的音频都会转换为该格式（采样率、声道数、封装方式）后再发布；
+    /// 会话可以通过 `Session::set_tts_output_format` 覆盖。未设置时保持后端原始格式。
+    /// 格式无效时不启用转换。
+    /// 
+    /// # Arguments
+    /// * `format` - 输出格式
+    pub fn with_tts_output_format(mut self, format: TtsAudioFormat) -> Self {
+        match format.validate() {
+            Ok(()) => {
+                eprintln!("[CoreEngine] 🔊 TTS output format: {:?} {}Hz {}ch", format.encoding, format.sample_rate, format.channels);
+                self.tts_output_format = Some(format);
+            }
+            Err(e) => {
+                eprintln!("[CoreEngine] ⚠️ {}, TTS audio will keep the backend format", e);
+            }
+        }
+        self
+    }
+    
     /// 启用翻译质量检查
     /// 
     /// # Arguments
@@ -602,15 +755,28 @@ impl CoreEngineBuilder {
     }
 
     pub fn build(self) -> EngineResult<CoreEngine> {
+        let asr = self.asr.ok_or_else(|| EngineError::new("asr is missing"))?;
+        if let Some(ref config) = self.asr_streaming {
+            let applied = match asr.as_ext() {
+                Some(asr_ext) => asr_ext.set_streaming_config(config),
+                None => Err(EngineError::new("ASR does not support streaming extensions")),
+            };
+            match applied {
+                Ok(()) => eprintln!("[CoreEngine] 🎙️ ASR streaming mode: {:?}", config.mode),
+                Err(e) => eprintln!("[CoreEngine] ⚠️ {}, ASR will only infer on boundaries", e),
+            }
+        }
         Ok(CoreEngine {
             event_bus: self.event_bus.ok_or_else(|| EngineError::new("event_bus is missing"))?,
             vad: self.vad.ok_or_else(|| EngineError::new("vad is missing"))?,
-            asr: self.asr.ok_or_else(|| EngineError::new("asr is missing"))?,
+            asr,
             nmt: self.nmt.ok_or_else(|| EngineError::new("nmt is missing"))?,
             emotion: self.emotion.ok_or_else(|| EngineError::new("emotion is missing"))?,
             persona: self.persona.ok_or_else(|| EngineError::new("persona is missing"))?,
-            tts: self.tts.ok_or_else(|| EngineError::new("tts is missing"))?,
-            fallback_tts: self.fallback_tts,
+            // 所有 TTS 后端都通过 SsmlTts 支持 SSML，并在合成前经过 NormalizedTts 文本规范化
+            // （SSML 先降级为普通文本段，再逐段规范化）
+            tts: self.tts.map(|tts| SsmlTts::wrap(NormalizedTts::wrap(tts))).ok_or_else(|| EngineError::new("tts is missing"))?,
+            fallback_tts: self.fallback_tts.map(|tts| SsmlTts::wrap(NormalizedTts::wrap(tts))),
             config: self.config.ok_or_else(|| EngineError::new("config is missing"))?,
             cache: self.cache.ok_or_else(|| EngineError::new("cache is missing"))?,
             telemetry: self.telemetry.ok_or_else(|| EngineError::new("telemetry is missing"))?,
@@ -618,7 +784,10 @@ impl CoreEngineBuilder {
             perf_logger: self.perf_logger,
             text_segmenter: self.text_segmenter,
             audio_enhancer: self.audio_enhancer,
+            tts_output_format: self.tts_output_format,
             quality_checker: self.quality_checker,
+            vocabulary_corrector: self.vocabulary_corrector.unwrap_or_default(),
+            vad_state_store: self.vad_state_store,
             nmt_service_url: self.nmt_service_url,
             tts_service_url: self.tts_service_url,
             tts_incremental_enabled: self.tts_incremental_enabled,
@@ -627,6 +796,7 @@ impl CoreEngineBuilder {
             continuous_mode: self.continuous_mode,
             speaker_voice_mapper: self.speaker_voice_mapper,
             speaker_identifier: self.speaker_identifier,
+            asr_gate: Arc::new(tokio::sync::Mutex::new(())),
         })
     }
 }
@@ -16,8 +16,9 @@ use crate::telemetry::TelemetrySink;
 use crate::text_segmentation::TextSegmenter;
 use crate::translation_quality::TranslationQualityChecker;
 use crate::tts_audio_enhancement::AudioEnhancer;
-use crate::tts_streaming::TtsStreaming;
-use crate::vad::VoiceActivityDetector;
+use crate::tts_streaming::{TtsAudioFormat, TtsStreaming};
+use crate::vad::{AdaptiveStateStore, VoiceActivityDetector};
+use crate::vocabulary_correction::VocabularyCorrector;
 
 pub struct CoreEngine {
     pub(crate) event_bus: Arc<dyn EventBus>,
@@ -36,7 +37,10 @@ pub struct CoreEngine {
     pub(crate) perf_logger: Option<Arc<PerformanceLogger>>,
     pub(crate) text_segmenter: Option<Arc<TextSegmenter>>,
     pub(crate) audio_enhancer: Option<Arc<AudioEnhancer>>,
+    pub(crate) tts_output_format: Option<TtsAudioFormat>,  // TTS 默认输出格式（None 表示保持后端原始格式）
     pub(crate) quality_checker: Option<Arc<TranslationQualityChecker>>,
+    pub(crate) vocabulary_corrector: Arc<VocabularyCorrector>,  // 按会话词表纠正识别文本（翻译之前）
+    pub(crate) vad_state_store: Option<Arc<AdaptiveStateStore>>,  // 按用户持久化 VAD 自适应状态
     // 服务 URL（用于健康检查）
     pub(crate) nmt_service_url: Option<String>,
     pub(crate) tts_service_url: Option<String>,
@@ -50,6 +54,8 @@ pub struct CoreEngine {
     pub(crate) speaker_voice_mapper: Option<Arc<SpeakerVoiceMapper>>,
     // 说话者识别
     pub(crate) speaker_identifier: Option<Arc<dyn SpeakerIdentifier>>,
+    // ASR 串行化锁：ASR 实现内部持有缓冲区和语言设置，多个会话共享时需要串行访问
+    pub(crate) asr_gate: Arc<tokio::sync::Mutex<()>>,
 }
 
 impl Clone for CoreEngine {
@@ -70,7 +76,10 @@ impl Clone for CoreEngine {
             perf_logger: self.perf_logger.as_ref().map(Arc::clone),
             text_segmenter: self.text_segmenter.as_ref().map(Arc::clone),
             audio_enhancer: self.audio_enhancer.as_ref().map(Arc::clone),
+            tts_output_format: self.tts_output_format,
             quality_checker: self.quality_checker.as_ref().map(Arc::clone),
+            vocabulary_corrector: Arc::clone(&self.vocabulary_corrector),
+            vad_state_store: self.vad_state_store.as_ref().map(Arc::clone),
             nmt_service_url: self.nmt_service_url.clone(),
             tts_service_url: self.tts_service_url.clone(),
             tts_incremental_enabled: self.tts_incremental_enabled,
@@ -79,6 +88,7 @@ impl Clone for CoreEngine {
             continuous_mode: self.continuous_mode,
             speaker_voice_mapper: self.speaker_voice_mapper.as_ref().map(Arc::clone),
             speaker_identifier: self.speaker_identifier.as_ref().map(Arc::clone),
+            asr_gate: Arc::clone(&self.asr_gate),
         }
     }
 }
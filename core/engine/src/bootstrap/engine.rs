@@ -1,25 +1,31 @@
 ﻿use std::sync::Arc;
 use std::time::Instant;
 use uuid::Uuid;
-use futures::future::join_all;
+use futures::stream::{FuturesOrdered, StreamExt};
 use serde_json::json;
+use tokio::sync::mpsc;
 
 use crate::asr_streaming::AsrResult;
-use crate::asr_whisper::{WhisperAsrStreaming, FasterWhisperAsrStreaming};
 use crate::asr_streaming::AsrStreamingExt;
 use crate::asr_filters::is_meaningless_transcript as is_meaningless_transcript_filter;
+use crate::asr_filters::confidence::low_transcript_confidence;
 use crate::audio_buffer::merge_frames;
 use crate::emotion_adapter::{EmotionRequest, EmotionResponse};
 use crate::error::{EngineError, EngineResult};
+use crate::language_registry::{self, LanguageBackend};
 use crate::event_bus::{CoreEvent, EventTopic};
-use crate::nmt_incremental::{TranslationRequest, TranslationResponse};
+use crate::nmt_incremental::{TranslationQualityMetrics, TranslationRequest, TranslationResponse};
 use crate::persona_adapter::PersonaContext;
 use crate::telemetry::TelemetryDatum;
-use crate::tts_streaming::{TtsRequest, TtsStreamChunk};
+use crate::text_segmentation::{PauseType, StreamingSegmenter, TextSegment};
+use crate::tts_streaming::{AudioEncoding, TtsAudioConverter, TtsAudioFormat, TtsRequest, TtsStreamChunk, TtsStreamConverter};
+use crate::tts_streaming::is_ssml;
 use crate::types::{PartialTranscript, StableTranscript};
 use crate::health_check::HealthChecker;
 use crate::performance_logger::PerformanceLog;
 use crate::vad::VadFeedbackType;
+use crate::vad::SpeechEvent;
+use crate::session::Session;
 
 
 use super::core::CoreEngine;
@@ -112,154 +118,75 @@ impl CoreEngine {
     /// 5. 发布事件到 EventBus（ASR 部分结果、ASR 最终结果、翻译结果）
     /// 
     /// # Arguments
+    /// * `session` - 当前会话（语言对、缓冲区、VAD 状态、说话者状态均来自会话）
     /// * `frame` - 音频帧
-    /// * `language_hint` - 语言提示（可选）
     /// 
     /// # Returns
     /// 返回处理结果（包含 ASR 和 NMT 结果）
     pub async fn process_audio_frame(
         &self,
+        session: &Session,
         frame: crate::types::AudioFrame,
-        language_hint: Option<String>,
     ) -> EngineResult<Option<ProcessResult>> {
         // 如果启用了连续模式，使用连续处理逻辑
         if self.continuous_mode {
-            return self.process_audio_frame_continuous(frame, language_hint).await;
+            return self.process_audio_frame_continuous(session, frame).await;
         }
         
         // 原有的处理逻辑（非连续模式）
         // 性能日志：记录总耗时
         let total_start = Instant::now();
         let request_id = Uuid::new_v4().to_string();
+        let language_hint = session.asr_language_hint();
         
-        // 1. 通过 VAD 检测语音活动
-        let vad_result = self.vad.detect(frame).await?;
+        // 1. 通过 VAD 检测语音活动（使用会话独立的 VAD 状态）
+        let vad_result = session.vad.detect(frame).await?;
+        if let Some(ref speech_event) = vad_result.speech_event {
+            self.handle_speech_event(session, speech_event, vad_result.frame.timestamp_ms).await?;
+        }
 
         // 2. 累积音频帧到 ASR 缓冲区
-        // 使用 AsrStreamingExt trait 来统一处理不同的 ASR 实现
-        // 尝试将 ASR 转换为支持扩展方法的类型
-        let asr_ptr = Arc::as_ptr(&self.asr);
-        
-        // 尝试转换为 WhisperAsrStreaming
-        let whisper_asr_ptr = asr_ptr as *const WhisperAsrStreaming;
-        let faster_whisper_ptr = asr_ptr as *const crate::asr_whisper::FasterWhisperAsrStreaming;
-        
-        // 检查是否支持扩展方法（FasterWhisperAsrStreaming 或 WhisperAsrStreaming）
-        let supports_ext = unsafe {
-            let faster_whisper_ref = faster_whisper_ptr.as_ref();
-            let whisper_asr_ref = whisper_asr_ptr.as_ref();
-            faster_whisper_ref.is_some() || whisper_asr_ref.is_some()
-        };
-        
-        if supports_ext {
-        unsafe {
-            // 优先尝试 FasterWhisperAsrStreaming
-            let faster_whisper_ref = faster_whisper_ptr.as_ref();
-            let whisper_asr_ref = whisper_asr_ptr.as_ref();
-            
-            if let Some(asr_ext) = faster_whisper_ref {
-                // 使用 FasterWhisperAsrStreaming
-                // 2.1. 如果提供了语言提示，设置 ASR 语言
-                static LAST_LANGUAGE: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
-                if let Some(ref lang_hint) = language_hint {
-                    let normalized_lang = if lang_hint.starts_with("zh") {
-                        Some("zh".to_string())
-                    } else if lang_hint.starts_with("en") {
-                        Some("en".to_string())
-                    } else {
-                        Some(lang_hint.clone())
-                    };
-                    
-                    let mut last_lang = LAST_LANGUAGE.lock().unwrap();
-                    let should_set = last_lang.as_ref() != normalized_lang.as_ref();
-                    if should_set {
-                        if let Err(e) = asr_ext.set_language(normalized_lang.clone()) {
-                            eprintln!("[ASR] Warning: Failed to set language: {}", e);
-                        } else {
-                            *last_lang = normalized_lang;
-                        }
-                    }
-                }
-                
-                asr_ext.accumulate_frame(vad_result.frame.clone())?;
-            } else if let Some(whisper_asr) = whisper_asr_ref {
-                // 使用 WhisperAsrStreaming（向后兼容）
-                // 2.1. 如果提供了语言提示，设置 ASR 语言
-                static LAST_LANGUAGE: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
-                if let Some(ref lang_hint) = language_hint {
-                    // 将语言代码标准化（例如 "zh-CN" -> "zh"）
-                    let normalized_lang = if lang_hint.starts_with("zh") {
-                        Some("zh".to_string())
-                    } else if lang_hint.starts_with("en") {
-                        Some("en".to_string())
-                    } else {
-                        Some(lang_hint.clone())
-                    };
-                    
-                    // 检查语言是否改变
-                    let mut last_lang = LAST_LANGUAGE.lock().unwrap();
-                    let should_set = last_lang.as_ref() != normalized_lang.as_ref();
-                    if should_set {
-                        if let Err(e) = whisper_asr.set_language(normalized_lang.clone()) {
-                        eprintln!("[ASR] Warning: Failed to set language: {}", e);
-                        } else {
-                            *last_lang = normalized_lang;
-                        }
-                    }
-                }
-                
-                // 累积帧
-                whisper_asr.accumulate_frame(vad_result.frame.clone())?;
-            }
+        // 通过 AsrStreaming::as_ext 获取扩展能力，统一处理不同的 ASR 实现
+        if let Some(asr_ext) = self.asr.as_ext() {
+            // 2.1. 累积帧到会话的 ASR 缓冲区
+            // ASR 实例（及其内部缓冲区）由所有会话共享，只在推理前加载本会话的帧
+            session.asr_buffer.lock().unwrap().push(vad_result.frame.clone());
                 
                 // 3. 如果检测到语音边界，触发 ASR 推理（返回最终结果）
             // 注意：边界检测应该在静音达到阈值时立即触发，不应该有延迟
             // 如果用户每个短句之间都停了1秒，VAD应该能检测到边界
                 if vad_result.is_boundary {
-                // 使用统一的扩展方法获取缓冲区大小
-                let buffer_size = if let Some(asr_ext) = faster_whisper_ref {
-                    asr_ext.get_accumulated_frames().map(|f| f.len()).unwrap_or(0)
-                } else if let Some(whisper_asr) = whisper_asr_ref {
-                    whisper_asr.get_accumulated_frames().map(|f| f.len()).unwrap_or(0)
-                } else {
-                    0
+                // 取出会话缓冲区中累积的帧（包含当前边界帧）
+                // VAD 给出了片段终点（含 post-roll）时，之后的静音帧留在缓冲区作为下一句的 pre-roll
+                let session_frames = {
+                    let mut asr_buffer = session.asr_buffer.lock().unwrap();
+                    let split = match vad_result.speech_event {
+                        Some(SpeechEvent::End { end_ms, .. }) => asr_buffer.iter()
+                            .position(|f| f.timestamp_ms >= end_ms)
+                            .unwrap_or(asr_buffer.len()),
+                        _ => asr_buffer.len(),
+                    };
+                    let remaining = asr_buffer.split_off(split);
+                    std::mem::replace(&mut *asr_buffer, remaining)
                 };
+                let buffer_size = session_frames.len();
                 eprintln!("[ASR] 🎯 Boundary detected at {}ms, will process {} accumulated frames", 
                          vad_result.frame.timestamp_ms, buffer_size);
                     // 3.1. 识别说话者（如果启用了说话者识别）
                     // 在非连续模式下，从 ASR 缓冲区获取累积的音频片段
-                    let (speaker_result, speaker_embedding_ms) = if let Some(ref identifier) = self.speaker_identifier {
+                    let (speaker_result, speaker_embedding_ms) = if let Some(ref identifier) = session.speaker_identifier {
                         let speaker_start = Instant::now();
                         eprintln!("[SPEAKER] ===== Speaker Identification Started =====");
                         eprintln!("[SPEAKER] Boundary detected at timestamp: {}ms (confidence: {:.3})", 
                                  vad_result.frame.timestamp_ms, vad_result.confidence);
                         
-                        // 从 ASR 缓冲区获取累积的音频帧（用于说话者识别）
+                        // 从会话缓冲区获取累积的音频帧（用于说话者识别）
                         // 过滤掉静音帧，只使用包含语音的帧
-                        let all_frames = if let Some(asr_ext) = faster_whisper_ref {
-                            asr_ext.get_accumulated_frames()
-                        } else if let Some(whisper_asr) = whisper_asr_ref {
-                            whisper_asr.get_accumulated_frames()
-                        } else {
-                            Ok(vec![vad_result.frame.clone()])
-                        }
-                            .unwrap_or_else(|e| {
-                                eprintln!("[SPEAKER] ⚠ Warning: Failed to get accumulated frames: {}, using current frame only", e);
-                                vec![vad_result.frame.clone()]
-                            });
+                        let all_frames = session_frames.clone();
                         
                         // 尝试从 VAD 获取上一个语音帧的时间戳，用于过滤静音帧
-                        let last_speech_ts = {
-                            let vad_ptr = Arc::as_ptr(&self.vad);
-                            let silero_vad_ptr = vad_ptr as *const crate::vad::SileroVad;
-                            unsafe {
-                                if let Some(silero_vad) = silero_vad_ptr.as_ref() {
-                                    silero_vad.get_last_speech_timestamp()
-                                } else {
-                                    None
-                                }
-                            }
-                        };
+                        let last_speech_ts = session.vad.as_silero()
+                            .and_then(|silero_vad| silero_vad.get_last_speech_timestamp());
                         
                         // 过滤音频帧：只保留包含语音的帧（在最后一个语音帧之前的帧）
                         // 如果无法确定，则使用所有帧（除了明显的静音帧）
@@ -419,19 +346,7 @@ impl CoreEngine {
                     // 这样可以实现流式处理：用户说完话后立即开始翻译，无需等待完整音频
                     // 对于手机端 AEC（声学回响消除）场景，这可以显著减少延迟
                     
-                    // ⚠️ 重要：在调用 infer_on_boundary() 之前先获取音频帧信息
-                    // 因为 infer_on_boundary() 会在推理完成后清空缓冲区
-                    let audio_frames_for_speech_rate = if let Some(asr_ext) = faster_whisper_ref {
-                        asr_ext.get_accumulated_frames()
-                    } else if let Some(whisper_asr) = whisper_asr_ref {
-                        whisper_asr.get_accumulated_frames()
-                    } else {
-                        Ok(vec![])
-                    }
-                        .unwrap_or_else(|e| {
-                            eprintln!("[CoreEngine] ⚠️  get_accumulated_frames failed (before inference): {:?}", e);
-                            vec![]
-                        });
+                    let audio_frames_for_speech_rate = &session_frames;
                     let audio_duration_ms_for_speech_rate = if !audio_frames_for_speech_rate.is_empty() {
                         let total_samples: usize = audio_frames_for_speech_rate.iter().map(|f| f.data.len()).sum();
                         let sample_rate = audio_frames_for_speech_rate[0].sample_rate;
@@ -443,12 +358,16 @@ impl CoreEngine {
                     let asr_start = Instant::now();
                     eprintln!("[ASR] 🚀 Starting transcription immediately after boundary detection...");
                     // 使用统一的扩展方法进行推理
-                    let asr_result = if let Some(asr_ext) = faster_whisper_ref {
-                        asr_ext.infer_on_boundary().await?
-                    } else if let Some(whisper_asr) = whisper_asr_ref {
-                        whisper_asr.infer_on_boundary().await?
-                    } else {
-                        return Err(EngineError::new("ASR implementation does not support boundary inference"));
+                    let mut asr_result = {
+                        // ASR 实例由所有会话共享：持锁期间加载本会话的帧和语言，再推理
+                        let _asr_guard = self.asr_gate.lock().await;
+                        // 加载或推理失败时也要取回会话的流式识别状态，再传播错误
+                        let result = async {
+                            Self::load_session_into_asr(asr_ext, session, &session_frames, language_hint.as_deref())?;
+                            asr_ext.infer_on_boundary().await
+                        }.await;
+                        Self::save_session_from_asr(asr_ext, session);
+                        result?
                     };
                     let asr_ms = asr_start.elapsed().as_millis() as u64;
                     eprintln!("[ASR] ✅ Transcription completed in {}ms", asr_ms);
@@ -462,10 +381,14 @@ impl CoreEngine {
                                  final_transcript.text, final_transcript.language, final_transcript.speaker_id);
                     }
                     
-                    // 3.5. 过滤无意义的 ASR 结果（在进入翻译/TTS 之前）
+                    // 3.4. 按会话词表纠正识别文本（在过滤和翻译之前）
+                    if let Some(ref mut final_transcript) = asr_result.final_transcript {
+                        self.correct_vocabulary(session, final_transcript);
+                    }
+                    
+                    // 3.5. 过滤无意义或低置信度（幻觉）的 ASR 结果（在进入翻译/TTS 之前）
                     if let Some(ref final_transcript) = asr_result.final_transcript {
-                        if is_meaningless_transcript_filter(&final_transcript.text) {
-                            eprintln!("[ASR] ⛔ Filtered meaningless transcript: \"{}\" (skipping translation/TTS)", final_transcript.text);
+                        if Self::should_filter_transcript(final_transcript) {
                             // 直接返回，不进入后续处理
                             return Ok(Some(ProcessResult {
                                 asr: asr_result,
@@ -476,6 +399,11 @@ impl CoreEngine {
                         }
                     }
                     
+                    // 3.6. 记录检测到的语言（双语会话中据此切换翻译方向）
+                    if let Some(ref final_transcript) = asr_result.final_transcript {
+                        Self::apply_detected_language(session, final_transcript);
+                    }
+                    
                     // 4. 发布 ASR 最终结果事件（包含 speaker_id）
                     // 4.1. 更新语速（如果启用了自适应VAD）
                     // 注意：不区分说话者，每个短句都根据上一个短句的语速调整
@@ -500,7 +428,7 @@ impl CoreEngine {
                                      speech_rate);
                             
                             // 更新VAD中的全局语速
-                            Self::update_vad_speech_rate(self, &final_transcript.text, audio_duration_ms);
+                            Self::update_vad_speech_rate(self, session, &final_transcript.text, audio_duration_ms);
                             eprintln!("[CoreEngine] ✅ Speech rate updated successfully");
                         } else {
                             eprintln!("[CoreEngine] ⚠️  Cannot update speech rate: audio_frames is empty (captured before inference)");
@@ -518,13 +446,13 @@ impl CoreEngine {
                     if let Some(ref final_transcript) = asr_result.final_transcript {
                         let mut transcript_with_speaker = final_transcript.clone();
                         transcript_with_speaker.speaker_id = speaker_id.clone();
-                        Self::publish_asr_final_event(self, &transcript_with_speaker, vad_result.frame.timestamp_ms).await?;
+                        Self::publish_asr_final_event(self, session.id(), &transcript_with_speaker, vad_result.frame.timestamp_ms).await?;
                     }
                     
                     // 5. 如果 ASR 返回最终结果，进行 Emotion 分析、Persona 个性化，然后触发 NMT 翻译
                     let (emotion_result, translation_result, tts_result, _nmt_ms, _tts_ms) = if let Some(ref final_transcript) = asr_result.final_transcript {
                         // 5.1. Emotion 情感分析
-                        let emotion_result = self.analyze_emotion(final_transcript, vad_result.frame.timestamp_ms).await.ok();
+                        let emotion_result = self.analyze_emotion(session, final_transcript, vad_result.frame.timestamp_ms).await.ok();
                         
                         // 5.2. 应用 Persona 个性化
                         let personalized_transcript = self.personalize_transcript(final_transcript).await?;
@@ -536,13 +464,7 @@ impl CoreEngine {
                         personalized_with_speaker.speaker_id = speaker_id.clone();
                         // 计算原始音频时长（用于后续计算每个 segment 的语速）
                         let source_audio_duration_ms = if let Some(ref _final_transcript) = asr_result.final_transcript {
-                        let audio_frames = if let Some(asr_ext) = faster_whisper_ref {
-                            asr_ext.get_accumulated_frames().unwrap_or_else(|_| vec![])
-                        } else if let Some(whisper_asr) = whisper_asr_ref {
-                            whisper_asr.get_accumulated_frames().unwrap_or_else(|_| vec![])
-                        } else {
-                            vec![]
-                        };
+                            let audio_frames = &session_frames;
                             if !audio_frames.is_empty() {
                                 let total_samples: usize = audio_frames.iter().map(|f| f.data.len()).sum();
                                 let sample_rate = audio_frames[0].sample_rate;
@@ -581,11 +503,48 @@ impl CoreEngine {
                         let nmt_start = Instant::now();
                     eprintln!("[NMT] 🚀 Starting incremental translation for {} sentences...", sentences.len());
                     
-                    // 如果只有一个句子，使用原有逻辑
+                    // 基于ASR/NMT反馈调整VAD阈值
+                    let apply_vad_feedback = |translation_result: Option<&TranslationResponse>| {
+                        if let Some(ref final_transcript) = asr_result.final_transcript {
+                            let translation_stable: Option<StableTranscript> = translation_result.map(|t| StableTranscript {
+                                text: t.translated_text.clone(),
+                                speaker_id: t.speaker_id.clone(),
+                                language: final_transcript.language.clone(), // 使用ASR检测到的目标语言
+                                segments: Vec::new(),
+                                language_probabilities: Vec::new(),
+                            });
+                            Self::adjust_vad_threshold_by_feedback(
+                                self,
+                                session,
+                                &asr_result,
+                                translation_stable.as_ref(),
+                                translation_result, // 传递完整的 TranslationResponse 以获取质量指标
+                                vad_result.frame.timestamp_ms,
+                                vad_result.frame.timestamp_ms, // 使用边界时间戳作为ASR开始时间
+                            );
+                        }
+                    };
+                    
+                    // 如果只有一个句子，使用原有逻辑（启用增量播放时流式翻译）
                     // 如果有多个句子，逐句翻译和TTS，实现增量处理
-                    let (translation_result, tts_result, nmt_ms, tts_ms, yourtts_ms) = if sentences.len() == 1 {
+                    let (translation_result, tts_result, nmt_ms, tts_ms, yourtts_ms) = if sentences.len() == 1 && self.tts_incremental_enabled {
+                        // 单句 + 增量播放：流式翻译，译文每凑齐一个分句就开始合成
+                        let result = self.translate_and_synthesize_streaming(
+                            session,
+                            &personalized_with_speaker,
+                            vad_result.frame.timestamp_ms,
+                            session.take_committed_translation(),
+                            asr_result.final_transcript.as_ref(),
+                            source_audio_duration_ms,
+                            reference_audio.clone(),
+                            voice_embedding.clone(),
+                            estimated_gender.clone(),
+                        ).await;
+                        apply_vad_feedback(result.0.as_ref());
+                        result
+                    } else if sentences.len() == 1 {
                         // 单句模式：原有逻辑
-                        let mut translation_result = self.translate_and_publish(&personalized_with_speaker, vad_result.frame.timestamp_ms).await.ok();
+                        let mut translation_result = self.translate_and_publish(session, &personalized_with_speaker, vad_result.frame.timestamp_ms, session.take_committed_translation()).await.ok();
                         
                         // 将原始音频信息添加到翻译结果中
                         if let Some(ref mut translation) = translation_result {
@@ -600,27 +559,13 @@ impl CoreEngine {
                         eprintln!("[NMT] ✅ Translation completed in {}ms", nmt_ms);
                         
                         // 基于ASR/NMT反馈调整VAD阈值
-                        if let Some(ref final_transcript) = asr_result.final_transcript {
-                            let translation_stable: Option<StableTranscript> = translation_result.as_ref().map(|t| StableTranscript {
-                                text: t.translated_text.clone(),
-                                speaker_id: t.speaker_id.clone(),
-                                language: final_transcript.language.clone(), // 使用ASR检测到的目标语言
-                            });
-                            Self::adjust_vad_threshold_by_feedback(
-                                self,
-                                &asr_result,
-                                translation_stable.as_ref(),
-                                translation_result.as_ref(), // 传递完整的 TranslationResponse 以获取质量指标
-                                vad_result.frame.timestamp_ms,
-                                vad_result.frame.timestamp_ms, // 使用边界时间戳作为ASR开始时间
-                            );
-                        }
+                        apply_vad_feedback(translation_result.as_ref());
                         
                         // TTS合成
                         let (tts_result, tts_ms, yourtts_ms) = if let Some(ref translation) = translation_result {
                             let tts_start = Instant::now();
                             eprintln!("[TTS] 🚀 Starting synthesis immediately after translation...");
-                            match self.synthesize_and_publish(translation, vad_result.frame.timestamp_ms, reference_audio.clone(), voice_embedding.clone(), estimated_gender.clone()).await {
+                            match self.synthesize_and_publish(session, translation, vad_result.frame.timestamp_ms, reference_audio.clone(), voice_embedding.clone(), estimated_gender.clone()).await {
                                 Ok((result, yt_ms)) => {
                                     let tts_ms = tts_start.elapsed().as_millis() as u64;
                                     eprintln!("[TTS] Synthesis completed in {}ms (audio size: {} bytes)", tts_ms, result.audio.len());
@@ -641,6 +586,7 @@ impl CoreEngine {
                     } else {
                         // 多句模式：增量处理，逐句翻译和TTS
                         self.translate_and_publish_incremental(
+                            session,
                             &sentences,
                             &personalized_with_speaker,
                             vad_result.frame.timestamp_ms,
@@ -669,9 +615,8 @@ impl CoreEngine {
                         eprintln!("[PERF] =====================================");
                         
                         if let Some(ref logger) = self.perf_logger {
-                            let config = self.config.current().await.ok();
                             let src_lang = final_transcript.language.clone();
-                            let tgt_lang = config.as_ref().map(|c| c.target_language.clone()).unwrap_or_else(|| "zh".to_string());
+                            let tgt_lang = session.target_language();
                             
                             let mut perf_log = PerformanceLog::new(
                                 request_id.clone(),
@@ -685,7 +630,12 @@ impl CoreEngine {
                             );
                             
                             if let Some(ref translation) = translation_result {
-                                perf_log.check_suspect_translation(&final_transcript.text, &translation.translated_text);
+                                perf_log.nmt_provider = translation.provider.clone();
+                                perf_log.check_suspect_translation_with_metrics(
+                                    &final_transcript.text,
+                                    &translation.translated_text,
+                                    translation.quality_metrics.as_ref(),
+                                );
                             }
                             
                             logger.log(&perf_log);
@@ -704,12 +654,28 @@ impl CoreEngine {
                     }));
                 } else {
                     // 未检测到边界，检查是否需要输出部分结果（如果启用流式推理）
-                // 注意：仅 WhisperAsrStreaming 支持流式推理
-                if let Some(whisper_asr) = whisper_asr_ref {
-                    if whisper_asr.is_streaming_enabled() {
-                        if let Some(partial) = whisper_asr.infer_partial(vad_result.frame.timestamp_ms).await? {
+                    // 注意：仅启用了流式推理的 ASR 实现（目前为 WhisperAsrStreaming）会输出部分结果
+                    if asr_ext.is_streaming_enabled() {
+                        let partial = {
+                            let _asr_guard = self.asr_gate.lock().await;
+                            let session_frames = session.asr_buffer.lock().unwrap().clone();
+                            let partial = async {
+                                Self::load_session_into_asr(asr_ext, session, &session_frames, language_hint.as_deref())?;
+                                asr_ext.infer_partial(vad_result.frame.timestamp_ms).await
+                            }.await;
+                            Self::save_session_from_asr(asr_ext, session);
+                            partial?
+                        };
+                        if let Some(partial) = partial {
                             // 发布 ASR 部分结果事件
-                            self.publish_asr_partial_event(&partial, vad_result.frame.timestamp_ms).await?;
+                            self.publish_asr_partial_event(session.id(), &partial, vad_result.frame.timestamp_ms).await?;
+                            
+                            // wait-k 同传：说话过程中发布部分翻译
+                            let translation = if session.wait_k().is_some() {
+                                self.translate_partial_and_publish(session, &partial, vad_result.frame.timestamp_ms).await.ok()
+                            } else {
+                                None
+                            };
                             
                             return Ok(Some(ProcessResult {
                                 asr: AsrResult {
@@ -717,26 +683,27 @@ impl CoreEngine {
                                     final_transcript: None,
                                 },
                                 emotion: None,
-                                translation: None,
+                                translation,
                                 tts: None,
                             }));
                         }
-                        }
                     }
                     // 不需要输出部分结果，返回 None
                     return Ok(None);
                 }
-                }
             } else {
             // 如果不是支持扩展方法的 ASR 实现，使用原来的 infer 方法
                 // 在移动 frame 之前保存需要的信息
                 let frame_timestamp = vad_result.frame.timestamp_ms;
                 let frame_data_len = vad_result.frame.data.len();
                 let frame_sample_rate = vad_result.frame.sample_rate;
-                let asr_result = self.asr.infer(crate::asr_streaming::AsrRequest {
-                    frame: vad_result.frame,
-                    language_hint: language_hint.clone(),
-                }).await?;
+                let mut asr_result = {
+                    let _asr_guard = self.asr_gate.lock().await;
+                    self.asr.infer(crate::asr_streaming::AsrRequest {
+                        frame: vad_result.frame,
+                        language_hint: language_hint.clone(),
+                    }).await?
+                };
                 
                 // 打印 ASR 结果
                 if let Some(ref partial) = asr_result.partial {
@@ -747,10 +714,14 @@ impl CoreEngine {
                              final_transcript.text, final_transcript.language, final_transcript.speaker_id);
                 }
                 
-                // 过滤无意义的 ASR 结果（在进入翻译/TTS 之前）
+                // 按会话词表纠正识别文本（在过滤和翻译之前）
+                if let Some(ref mut final_transcript) = asr_result.final_transcript {
+                    self.correct_vocabulary(session, final_transcript);
+                }
+                
+                // 过滤无意义或低置信度（幻觉）的 ASR 结果（在进入翻译/TTS 之前）
                 if let Some(ref final_transcript) = asr_result.final_transcript {
-                if is_meaningless_transcript_filter(&final_transcript.text) {
-                        eprintln!("[ASR] ⛔ Filtered meaningless transcript: \"{}\" (skipping translation/TTS)", final_transcript.text);
+                    if Self::should_filter_transcript(final_transcript) {
                         // 直接返回，不进入后续处理
                         return Ok(Some(ProcessResult {
                             asr: asr_result,
@@ -764,10 +735,11 @@ impl CoreEngine {
                 // 如果检测到边界且有最终结果，进行 Emotion 分析、Persona 个性化，然后触发翻译
                 if vad_result.is_boundary {
                     if let Some(ref final_transcript) = asr_result.final_transcript {
-                    Self::publish_asr_final_event(self, final_transcript, frame_timestamp).await?;
+                        Self::apply_detected_language(session, final_transcript);
+                        Self::publish_asr_final_event(self, session.id(), final_transcript, frame_timestamp).await?;
                         
                         // Emotion 情感分析
-                        let emotion_result = self.analyze_emotion(final_transcript, frame_timestamp).await.ok();
+                        let emotion_result = self.analyze_emotion(session, final_transcript, frame_timestamp).await.ok();
                         
                         // 应用 Persona 个性化
                         let personalized_transcript = self.personalize_transcript(final_transcript).await?;
@@ -778,21 +750,38 @@ impl CoreEngine {
                         };
                         
                         // 使用个性化后的 transcript 进行翻译
-                        let mut translation_result = self.translate_and_publish(&personalized_transcript, frame_timestamp).await.ok();
-                        
-                        // 将原始音频信息添加到翻译结果中（用于计算每个 segment 的语速）
-                        if let Some(ref mut translation) = translation_result {
-                            if let Some(ref final_transcript) = asr_result.final_transcript {
-                                translation.source_audio_duration_ms = source_audio_duration_ms;
-                                translation.source_text = Some(final_transcript.text.clone());
-                            }
-                        }
-                        
-                        // 如果翻译成功，进行 TTS 合成
-                        let tts_result = if let Some(ref translation) = translation_result {
-                        self.synthesize_and_publish(translation, frame_timestamp, None, None, None).await.ok().map(|(chunk, _)| chunk)
+                        let (translation_result, tts_result) = if self.tts_incremental_enabled {
+                            // 增量播放：流式翻译，译文每凑齐一个分句就开始合成
+                            let (translation_result, tts_result, _, _, _) = self.translate_and_synthesize_streaming(
+                                session,
+                                &personalized_transcript,
+                                frame_timestamp,
+                                session.take_committed_translation(),
+                                asr_result.final_transcript.as_ref(),
+                                source_audio_duration_ms,
+                                None,
+                                None,
+                                None,
+                            ).await;
+                            (translation_result, tts_result)
                         } else {
-                            None
+                            let mut translation_result = self.translate_and_publish(session, &personalized_transcript, frame_timestamp, session.take_committed_translation()).await.ok();
+                            
+                            // 将原始音频信息添加到翻译结果中（用于计算每个 segment 的语速）
+                            if let Some(ref mut translation) = translation_result {
+                                if let Some(ref final_transcript) = asr_result.final_transcript {
+                                    translation.source_audio_duration_ms = source_audio_duration_ms;
+                                    translation.source_text = Some(final_transcript.text.clone());
+                                }
+                            }
+                            
+                            // 如果翻译成功，进行 TTS 合成
+                            let tts_result = if let Some(ref translation) = translation_result {
+                                self.synthesize_and_publish(session, translation, frame_timestamp, None, None, None).await.ok().map(|(chunk, _)| chunk)
+                            } else {
+                                None
+                            };
+                            (translation_result, tts_result)
                         };
                         
                         return Ok(Some(ProcessResult {
@@ -805,14 +794,20 @@ impl CoreEngine {
                 }
                 
                 // 如果有部分结果，发布事件
+                let mut translation = None;
                 if let Some(ref partial) = asr_result.partial {
-                Self::publish_asr_partial_event(self, partial, frame_timestamp).await?;
+                    Self::publish_asr_partial_event(self, session.id(), partial, frame_timestamp).await?;
+                    
+                    // wait-k 同传：说话过程中发布部分翻译
+                    if session.wait_k().is_some() {
+                        translation = self.translate_partial_and_publish(session, partial, frame_timestamp).await.ok();
+                    }
                 }
                 
                 return Ok(Some(ProcessResult {
                     asr: asr_result,
                     emotion: None,
-                    translation: None,
+                    translation,
                     tts: None,
                 }));
         }
@@ -827,11 +822,11 @@ impl CoreEngine {
     /// 4. 继续接收新的音频输入
     async fn process_audio_frame_continuous(
         &self,
+        session: &Session,
         frame: crate::types::AudioFrame,
-        language_hint: Option<String>,
     ) -> EngineResult<Option<ProcessResult>> {
-        // 获取音频缓冲管理器
-        let buffer = self.audio_buffer.as_ref()
+        // 获取会话的音频缓冲管理器
+        let buffer = session.audio_buffer.as_ref()
             .ok_or_else(|| EngineError::new("Audio buffer not initialized in continuous mode"))?;
         
         // 保存 frame 的 timestamp（在移动之前）
@@ -853,7 +848,7 @@ impl CoreEngine {
         
         // 2. VAD 检测（仅在非强制边界时执行）
         let vad_result = if !force_boundary {
-            self.vad.detect(frame.clone()).await?
+            session.vad.detect(frame.clone()).await?
         } else {
             // 强制边界时，创建一个假的检测结果
             crate::vad::DetectionOutcome {
@@ -861,8 +856,12 @@ impl CoreEngine {
                 confidence: 1.0,
                 frame: frame.clone(),
                 boundary_type: Some(crate::vad::BoundaryType::ForcedCutoff),
+                speech_event: None,
             }
         };
+        if let Some(ref speech_event) = vad_result.speech_event {
+            self.handle_speech_event(session, speech_event, current_frame_timestamp).await?;
+        }
         
         // 3. 如果检测到边界或强制触发，提交当前缓冲区
         if vad_result.is_boundary || force_boundary {
@@ -873,14 +872,17 @@ impl CoreEngine {
                 return Ok(None);
             }
             
-            // 获取当前缓冲区的所有帧
-            let frames = buffer.take_current_buffer().await;
+            // 获取当前片段的帧（VAD 给出了片段终点时，之后的静音帧留在缓冲区作为下一句的 pre-roll）
+            let segment_end_ms = match vad_result.speech_event {
+                Some(SpeechEvent::End { end_ms, .. }) => Some(end_ms),
+                _ => None,
+            };
+            let frames = buffer.take_segment(segment_end_ms).await;
             
             if frames.is_empty() {
                 // 如果缓冲区为空，但强制触发边界，说明可能是溢出导致的
                 // 在这种情况下，如果当前帧存在，应该将其添加到新缓冲区
                 if force_boundary {
-                    buffer.swap_buffers().await;
                     // 将导致溢出的当前帧添加到新缓冲区
                     if let Err(e) = buffer.push_frame(frame.clone()).await {
                         eprintln!("[VAD] ⚠️ Failed to add overflow frame to new buffer: {}", e);
@@ -889,9 +891,6 @@ impl CoreEngine {
                 return Ok(None);
             }
             
-            // 切换到下一个缓冲区（继续接收新音频）
-            buffer.swap_buffers().await;
-            
             // 如果是强制边界（溢出），将导致溢出的当前帧添加到新缓冲区
             if force_boundary {
                 if let Err(e) = buffer.push_frame(frame.clone()).await {
@@ -912,7 +911,7 @@ impl CoreEngine {
             
             // 识别说话者（如果启用了说话者识别）
             let boundary_timestamp = frames.last().map(|f| f.timestamp_ms).unwrap_or(current_frame_timestamp);
-            let (speaker_result, speaker_embedding_ms) = if let Some(ref identifier) = self.speaker_identifier {
+            let (speaker_result, speaker_embedding_ms) = if let Some(ref identifier) = session.speaker_identifier {
                 let speaker_start = Instant::now();
                 eprintln!("[SPEAKER] ===== Speaker Identification Started =====");
                 eprintln!("[SPEAKER] Boundary timestamp: {}ms", boundary_timestamp);
@@ -1027,8 +1026,8 @@ impl CoreEngine {
             
             // 处理音频片段并等待结果（这样 WebSocket 可以收到结果）
             let result = self.process_audio_segment(
+                session,
                 merged_frame,
-                language_hint,
                 speaker_id,
                 voice_embedding,
                 reference_audio,
@@ -1049,8 +1048,8 @@ impl CoreEngine {
     /// 这是从连续处理模式中分离出来的方法，用于异步处理音频片段
     async fn process_audio_segment(
         &self,
+        session: &Session,
         frame: crate::types::AudioFrame,
-        language_hint: Option<String>,
         speaker_id: Option<String>,
         voice_embedding: Option<Vec<f32>>,
         reference_audio: Option<Vec<f32>>,
@@ -1063,13 +1062,12 @@ impl CoreEngine {
         // 性能日志：记录总耗时
         let total_start = Instant::now();
         let request_id = Uuid::new_v4().to_string();
+        let language_hint = session.asr_language_hint();
         
-        // 对于连续模式，我们需要将整个片段传递给 ASR
-        // 使用 AsrStreamingExt trait 来统一处理不同的 ASR 实现
-        let asr_ptr = Arc::as_ptr(&self.asr);
-        let faster_whisper_ptr = asr_ptr as *const FasterWhisperAsrStreaming;
-        let whisper_asr_ptr = asr_ptr as *const WhisperAsrStreaming;
+        // ASR 实例由所有会话共享：持锁期间设置本会话的语言并推理
+        let asr_guard = self.asr_gate.lock().await;
         
+        // 对于连续模式，我们需要将整个片段传递给 ASR
         // 使用 infer 方法处理整个片段
         let asr_start = Instant::now();
         let segment_duration_ms = frame.data.len() as f32 / frame.sample_rate as f32 * 1000.0;
@@ -1077,33 +1075,26 @@ impl CoreEngine {
                  segment_duration_ms, frame.data.len());
         
         // 设置语言（如果支持）
-        if let Some(ref lang_hint) = language_hint {
-            let normalized_lang = if lang_hint.starts_with("zh") {
-                Some("zh".to_string())
-            } else if lang_hint.starts_with("en") {
-                Some("en".to_string())
-            } else {
-                Some(lang_hint.clone())
-            };
-            
-            unsafe {
-                // 优先尝试 FasterWhisperAsrStreaming
-                let faster_whisper_ref = faster_whisper_ptr.as_ref();
-                if let Some(asr_ext) = faster_whisper_ref {
-                    if let Err(e) = asr_ext.set_language(normalized_lang.clone()) {
-                        eprintln!("[ASR] ⚠️ Warning: Failed to set language on FasterWhisper: {}", e);
-                    }
-                } else if let Some(whisper_asr) = whisper_asr_ptr.as_ref() {
-                    if let Err(e) = whisper_asr.set_language(normalized_lang) {
-                        eprintln!("[ASR] ⚠️ Warning: Failed to set language on Whisper: {}", e);
-                    }
-                }
+        // 自动检测模式下也要设置（None），避免沿用其他会话设置的语言
+        let normalized_lang = language_hint.as_ref()
+            .map(|lang_hint| language_registry::global().normalize(lang_hint, LanguageBackend::Whisper));
+        
+        // 使用 AsrStreamingExt 设置语言（如果支持）
+        if let Some(asr_ext) = self.asr.as_ext() {
+            if let Err(e) = asr_ext.set_language(normalized_lang) {
+                eprintln!("[ASR] ⚠️ Warning: Failed to set language on ASR: {}", e);
+            }
+            if let Err(e) = asr_ext.set_language_candidates(session.asr_language_candidates()) {
+                eprintln!("[ASR] ⚠️ Warning: Failed to set language candidates on ASR: {}", e);
+            }
+            if let Err(e) = asr_ext.set_hotwords(session.vocabulary()) {
+                eprintln!("[ASR] ⚠️ Warning: Failed to set hotwords on ASR: {}", e);
             }
         }
         
-        // 调用 ASR infer 方法（不需要 unsafe，因为使用的是 trait 方法）
+        // 调用 ASR infer 方法
         eprintln!("[ASR] Calling ASR infer method...");
-        let asr_result = match self.asr.infer(crate::asr_streaming::AsrRequest {
+        let mut asr_result = match self.asr.infer(crate::asr_streaming::AsrRequest {
             frame: frame.clone(),
             language_hint: language_hint.clone(),
         }).await {
@@ -1116,6 +1107,7 @@ impl CoreEngine {
                 return Err(e);
             }
         };
+        drop(asr_guard);
                 
         let asr_ms = asr_start.elapsed().as_millis() as u64;
         eprintln!("[ASR] ✅ Transcription completed in {}ms", asr_ms);
@@ -1131,15 +1123,22 @@ impl CoreEngine {
             eprintln!("[ASR] ⚠️ No final transcript received");
         }
         
+        // 按会话词表纠正识别文本（在过滤和翻译之前）
+        if let Some(ref mut final_transcript) = asr_result.final_transcript {
+            self.correct_vocabulary(session, final_transcript);
+        }
+        
         // 发布 ASR 最终结果事件
         if let Some(mut final_transcript) = asr_result.final_transcript.clone() {
-            // 🔍 过滤无意义的识别文本（在继续处理之前）
-            if is_meaningless_transcript_filter(&final_transcript.text) {
-                eprintln!("[ASR] 🚫 Filtered meaningless transcript: \"{}\"", final_transcript.text);
+            // 🔍 过滤无意义或低置信度（幻觉）的识别文本（在继续处理之前）
+            if Self::should_filter_transcript(&final_transcript) {
                 // 返回 None，表示这个结果被过滤掉了
                 return Ok(None);
             }
             
+            // 记录检测到的语言（双语会话中据此切换翻译方向）
+            Self::apply_detected_language(session, &final_transcript);
+            
             // 设置说话者 ID（如果已识别）
             if final_transcript.speaker_id.is_none() {
                 final_transcript.speaker_id = speaker_id.clone();
@@ -1161,14 +1160,14 @@ impl CoreEngine {
                 // 更新VAD中的全局语速
                 // 注意：update_speech_rate 内部会检查语速是否在合理范围内
                 // 如果语速异常（可能是误识别），会被自动过滤
-                Self::update_vad_speech_rate(self, &final_transcript.text, audio_duration_ms);
+                Self::update_vad_speech_rate(self, session, &final_transcript.text, audio_duration_ms);
             }
             
             let timestamp = frame.timestamp_ms;
-            Self::publish_asr_final_event(self, &final_transcript, timestamp).await?;
+            Self::publish_asr_final_event(self, session.id(), &final_transcript, timestamp).await?;
             
             // 继续处理：Emotion → Persona → NMT → TTS
-            let emotion_result = self.analyze_emotion(&final_transcript, timestamp).await.ok();
+            let emotion_result = self.analyze_emotion(session, &final_transcript, timestamp).await.ok();
             let personalized_transcript = self.personalize_transcript(&final_transcript).await?;
             
             // 计算原始音频时长（用于后续计算每个 segment 的语速）
@@ -1178,55 +1177,73 @@ impl CoreEngine {
                 Some((total_samples as f32 / sample_rate as f32 * 1000.0) as u64)
             };
             
-            let nmt_start = Instant::now();
-            eprintln!("[NMT] Starting translation (continuous mode, speaker_id: {:?})...", personalized_transcript.speaker_id);
-            let mut translation_result = self.translate_and_publish(&personalized_transcript, timestamp).await.ok();
+            let (translation_result, tts_result, nmt_ms, tts_ms, yourtts_ms) = if self.tts_incremental_enabled {
+                // 增量播放：流式翻译，译文每凑齐一个分句就开始合成
+                eprintln!("[NMT] Starting streaming translation (continuous mode, speaker_id: {:?})...", personalized_transcript.speaker_id);
+                self.translate_and_synthesize_streaming(
+                    session,
+                    &personalized_transcript,
+                    timestamp,
+                    session.take_committed_translation(),
+                    asr_result.final_transcript.as_ref(),
+                    source_audio_duration_ms,
+                    reference_audio.clone(),
+                    voice_embedding.clone(),
+                    estimated_gender.clone(),
+                ).await
+            } else {
+                let nmt_start = Instant::now();
+                eprintln!("[NMT] Starting translation (continuous mode, speaker_id: {:?})...", personalized_transcript.speaker_id);
+                let mut translation_result = self.translate_and_publish(session, &personalized_transcript, timestamp, session.take_committed_translation()).await.ok();
             
-            // 将原始音频信息添加到翻译结果中（用于计算每个 segment 的语速）
-            if let Some(ref mut translation) = translation_result {
-                if let Some(ref final_transcript) = asr_result.final_transcript {
-                    translation.source_audio_duration_ms = source_audio_duration_ms;
-                    translation.source_text = Some(final_transcript.text.clone());
-                    translation.source_language = Some(final_transcript.language.clone());
+                // 将原始音频信息添加到翻译结果中（用于计算每个 segment 的语速）
+                if let Some(ref mut translation) = translation_result {
+                    if let Some(ref final_transcript) = asr_result.final_transcript {
+                        translation.source_audio_duration_ms = source_audio_duration_ms;
+                        translation.source_text = Some(final_transcript.text.clone());
+                        translation.source_language = Some(final_transcript.language.clone());
+                    }
                 }
-            }
             
-            let nmt_ms = nmt_start.elapsed().as_millis() as u64;
-            eprintln!("[NMT] Translation completed in {}ms", nmt_ms);
+                let nmt_ms = nmt_start.elapsed().as_millis() as u64;
+                eprintln!("[NMT] Translation completed in {}ms", nmt_ms);
             
-            let (tts_result, tts_ms, yourtts_ms) = if let Some(ref translation) = translation_result {
-                let tts_start = Instant::now();
-                eprintln!("[TTS] ===== TTS Synthesis Started =====");
-                eprintln!("[TTS] Text: '{}'", translation.translated_text);
-                eprintln!("[TTS] Speaker ID: {:?}", translation.speaker_id);
-                eprintln!("[TTS] Reference audio: {} (samples: {})", 
-                    if reference_audio.is_some() { "Yes" } else { "No" },
-                    reference_audio.as_ref().map(|a| a.len()).unwrap_or(0));
-                let voice_embedding_for_tts = voice_embedding.clone();
-                match self.synthesize_and_publish(translation, timestamp, reference_audio.clone(), voice_embedding_for_tts, estimated_gender.clone()).await {
-                    Ok((result, yourtts_time)) => {
-                        let tts_ms = tts_start.elapsed().as_millis() as u64;
-                        // 注意：在增量模式下，result 只是一个占位符（第一个 segment）
-                        // 实际所有 segments 已通过事件独立发布，客户端应该通过事件总线接收
-                        if self.tts_incremental_enabled {
-                            eprintln!("[TTS] ✅ Incremental synthesis completed in {}ms (segments published independently, placeholder size: {} bytes)", 
-                                tts_ms, result.audio.len());
-                        } else {
-                            eprintln!("[TTS] ✅ Synthesis completed in {}ms (audio size: {} bytes)", tts_ms, result.audio.len());
+                let (tts_result, tts_ms, yourtts_ms) = if let Some(ref translation) = translation_result {
+                    let tts_start = Instant::now();
+                    eprintln!("[TTS] ===== TTS Synthesis Started =====");
+                    eprintln!("[TTS] Text: '{}'", translation.translated_text);
+                    eprintln!("[TTS] Speaker ID: {:?}", translation.speaker_id);
+                    eprintln!("[TTS] Reference audio: {} (samples: {})", 
+                        if reference_audio.is_some() { "Yes" } else { "No" },
+                        reference_audio.as_ref().map(|a| a.len()).unwrap_or(0));
+                    let voice_embedding_for_tts = voice_embedding.clone();
+                    match self.synthesize_and_publish(session, translation, timestamp, reference_audio.clone(), voice_embedding_for_tts, estimated_gender.clone()).await {
+                        Ok((result, yourtts_time)) => {
+                            let tts_ms = tts_start.elapsed().as_millis() as u64;
+                            // 注意：在增量模式下，result 只是一个占位符（第一个 segment）
+                            // 实际所有 segments 已通过事件独立发布，客户端应该通过事件总线接收
+                            if self.tts_incremental_enabled {
+                                eprintln!("[TTS] ✅ Incremental synthesis completed in {}ms (segments published independently, placeholder size: {} bytes)", 
+                                    tts_ms, result.audio.len());
+                            } else {
+                                eprintln!("[TTS] ✅ Synthesis completed in {}ms (audio size: {} bytes)", tts_ms, result.audio.len());
+                            }
+                            eprintln!("[TTS] ==========================================");
+                            (Some(result), tts_ms, yourtts_time)
+                        }
+                        Err(e) => {
+                            let tts_ms = tts_start.elapsed().as_millis() as u64;
+                            eprintln!("[TTS] ❌ Synthesis failed in {}ms: {}", tts_ms, e);
+                            eprintln!("[TTS] ==========================================");
+                            (None, tts_ms, None)
                         }
-                        eprintln!("[TTS] ==========================================");
-                        (Some(result), tts_ms, yourtts_time)
-                    }
-                    Err(e) => {
-                        let tts_ms = tts_start.elapsed().as_millis() as u64;
-                        eprintln!("[TTS] ❌ Synthesis failed in {}ms: {}", tts_ms, e);
-                        eprintln!("[TTS] ==========================================");
-                        (None, tts_ms, None)
                     }
-                }
-            } else {
-                eprintln!("[TTS] Skipped (no translation result)");
-                (None, 0, None)
+                } else {
+                    eprintln!("[TTS] Skipped (no translation result)");
+                    (None, 0, None)
+                };
+                
+                (translation_result, tts_result, nmt_ms, tts_ms, yourtts_ms)
             };
             
             // 性能日志
@@ -1245,9 +1262,8 @@ impl CoreEngine {
             eprintln!("[PERF] ===========================================");
             
             if let Some(ref logger) = self.perf_logger {
-                let config = self.config.current().await.ok();
                 let src_lang = final_transcript.language.clone();
-                let tgt_lang = config.as_ref().map(|c| c.target_language.clone()).unwrap_or_else(|| "zh".to_string());
+                let tgt_lang = session.target_language();
                 
                 let mut perf_log = PerformanceLog::new(
                     request_id.clone(),
@@ -1261,7 +1277,12 @@ impl CoreEngine {
                 );
                 
                 if let Some(ref translation) = translation_result {
-                    perf_log.check_suspect_translation(&final_transcript.text, &translation.translated_text);
+                    perf_log.nmt_provider = translation.provider.clone();
+                    perf_log.check_suspect_translation_with_metrics(
+                        &final_transcript.text,
+                        &translation.translated_text,
+                        translation.quality_metrics.as_ref(),
+                    );
                 }
                 
                 logger.log(&perf_log);
@@ -1287,6 +1308,7 @@ impl CoreEngine {
     /// 分析情感
     async fn analyze_emotion(
         &self,
+        session: &Session,
         transcript: &StableTranscript,
         timestamp_ms: u64,
     ) -> EngineResult<EmotionResponse> {
@@ -1300,7 +1322,7 @@ impl CoreEngine {
         let response = self.emotion.analyze(request).await?;
         
         // 发布 Emotion 事件
-        Self::publish_emotion_event(self, &response, timestamp_ms).await?;
+        Self::publish_emotion_event(self, session.id(), &response, timestamp_ms).await?;
         
         Ok(response)
     }
@@ -1329,6 +1351,7 @@ impl CoreEngine {
     /// 对每个句子分别进行翻译和TTS，实现实时反馈
     async fn translate_and_publish_incremental(
         &self,
+        session: &Session,
         sentences: &[String],
         original_transcript: &StableTranscript,
         timestamp_ms: u64,
@@ -1364,6 +1387,9 @@ impl CoreEngine {
         // 逐句翻译和TTS（并行处理以提高效率）
         eprintln!("[NMT] ⚡ Starting parallel translation and TTS for {} sentences...", sentences.len());
         
+        // 拆句后已提交的译文前缀无法与单个句子对齐，直接丢弃
+        session.take_committed_translation();
+        
         // 在闭包外部克隆 estimated_gender，确保它在作用域内
         let estimated_gender_clone_for_tasks = estimated_gender.clone();
         
@@ -1384,7 +1410,7 @@ impl CoreEngine {
                 // 翻译单个句子
                 let sentence_nmt_start = Instant::now();
                 eprintln!("[NMT] ⚡ Translating sentence {}/{}: '{}'", idx + 1, sentences.len(), sentence_clone);
-                let translation_result = engine_clone.translate_and_publish(&sentence_transcript, timestamp_ms + (idx as u64 * 100)).await.ok();
+                let translation_result = engine_clone.translate_and_publish(session, &sentence_transcript, timestamp_ms + (idx as u64 * 100), None).await.ok();
                 let sentence_nmt_ms = sentence_nmt_start.elapsed().as_millis() as u64;
                 
                 if let Some(ref translation) = translation_result {
@@ -1400,6 +1426,7 @@ impl CoreEngine {
                     let sentence_tts_start = Instant::now();
                     eprintln!("[TTS] ⚡ Synthesizing sentence {}/{}: '{}'", idx + 1, sentences.len(), translation.translated_text);
                     match engine_clone.synthesize_and_publish(
+                        session,
                         &translation_with_duration,
                         timestamp_ms + (idx as u64 * 100),
                         reference_audio_clone.clone(),
@@ -1463,20 +1490,20 @@ impl CoreEngine {
         };
         
         // 合并所有TTS音频
-        let merged_tts = if !all_tts_chunks.is_empty() {
-            let mut merged_audio = Vec::new();
-            for chunk in &all_tts_chunks {
-                merged_audio.extend_from_slice(&chunk.audio);
+        // 各句的音频可能来自不同后端（例如回退 TTS），先解码再合并，不能直接拼接字节（WAV 头会重复）
+        let merged_tts = all_tts_chunks.first().and_then(|first| {
+            let format = self.output_format_for(session).unwrap_or(first.format);
+            match TtsAudioConverter::new(format).and_then(|converter| converter.merge(&all_tts_chunks)) {
+                Ok(mut merged) => {
+                    merged.is_last = true;
+                    Some(merged)
+                }
+                Err(e) => {
+                    eprintln!("[TTS] ⚠️  Failed to merge {} audio chunks: {}", all_tts_chunks.len(), e);
+                    None
+                }
             }
-            
-            Some(TtsStreamChunk {
-                audio: merged_audio,
-                timestamp_ms: all_tts_chunks[0].timestamp_ms,
-                is_last: true,
-            })
-        } else {
-            None
-        };
+        });
         
         let tts_ms = if let Some(ref tts) = merged_tts {
             // 估算TTS总耗时（实际是并行处理的）
@@ -1496,49 +1523,200 @@ impl CoreEngine {
         (merged_translation, merged_tts, nmt_ms, tts_ms, yourtts_ms)
     }
 
-    /// 翻译并发布事件
-    async fn translate_and_publish(
+    /// 部分翻译（wait-k 同传）并发布事件
+    /// 
+    /// 以会话中已提交的译文前缀作为强制前缀翻译当前的 ASR 部分结果，并更新已提交前缀。
+    /// 质量检查和文本后处理只作用于最终译文，这里不执行。
+    async fn translate_partial_and_publish(
         &self,
-        transcript: &StableTranscript,
+        session: &Session,
+        partial: &PartialTranscript,
         timestamp_ms: u64,
     ) -> EngineResult<TranslationResponse> {
-        // 1. 获取目标语言（从配置中，使用 .ok() 避免阻塞）
-        // ⚠️ 优化：如果配置获取失败，使用默认值而不是阻塞整个流程
-        let target_language = self.config.current().await
-            .map(|c| c.target_language)
-            .unwrap_or_else(|_| {
-                eprintln!("[NMT] ⚠️  Failed to get config, using default target_language: zh");
-                "zh".to_string()
-            });
-        
-        // 2. 构造翻译请求（传递 speaker_id）
         let translation_request = TranslationRequest {
+            transcript: partial.clone(),
+            target_language: session.target_language(),
+            wait_k: session.wait_k(),
+            speaker_id: None,
+            decoding: session.decoding_options(),
+            committed_prefix: session.committed_translation(),
+            // 自动检测模式下使用上一句检测到的语言
+            source_language: session.asr_language_hint().or_else(|| session.detected_language()),
+        };
+        
+        let translation_response = self.nmt.translate(translation_request).await?;
+        if let Some(ref committed) = translation_response.committed_text {
+            *session.committed_translation.lock().unwrap() = Some(committed.clone());
+        }
+        eprintln!("[NMT] 🔄 Partial translation (wait-k): '{}' (committed: {:?}, stable: {})",
+                 translation_response.translated_text, translation_response.committed_text, translation_response.is_stable);
+        
+        Self::publish_translation_event(self, session.id(), &translation_response, timestamp_ms).await?;
+        
+        Ok(translation_response)
+    }
+
+    /// 构造最终转录的翻译请求（传递 speaker_id）
+    fn final_translation_request(
+        session: &Session,
+        transcript: &StableTranscript,
+        committed_prefix: Option<String>,
+    ) -> TranslationRequest {
+        TranslationRequest {
             transcript: PartialTranscript {
                 text: transcript.text.clone(),
                 confidence: 1.0,  // 最终转录的置信度
                 is_final: true,
+                stable_prefix: None,
             },
-            target_language: target_language.clone(),
-            wait_k: None,
+            target_language: session.target_language(),
+            wait_k: session.wait_k(),
             speaker_id: transcript.speaker_id.clone(),  // 传递 speaker_id
+            decoding: session.decoding_options(),
+            committed_prefix,
+            source_language: Some(transcript.language.clone())
+                .filter(|language| !language.is_empty())
+                .or_else(|| session.source_language()),
+        }
+    }
+
+    /// 翻译并发布事件
+    /// 
+    /// `committed_prefix` 为 wait-k 部分翻译阶段已提交的译文前缀，最终译文会沿用该前缀。
+    async fn translate_and_publish(
+        &self,
+        session: &Session,
+        transcript: &StableTranscript,
+        timestamp_ms: u64,
+        committed_prefix: Option<String>,
+    ) -> EngineResult<TranslationResponse> {
+        // 1. 构造翻译请求（目标语言每个会话独立配置）
+        let translation_request = Self::final_translation_request(session, transcript, committed_prefix);
+        
+        // 2. 执行翻译
+        let translation_response = self.nmt.translate(translation_request).await?;
+        
+        // 3. 质量检查、后处理并发布
+        self.finalize_translation(session, transcript, translation_response, timestamp_ms).await
+    }
+
+    /// 流式翻译并发布事件
+    /// 
+    /// NMT 每输出一个 token 发布一次 `NmtPartial` 事件；译文每凑齐一个分句（经质量检查和文本后处理）就送入 `segment_tx`，
+    /// TTS 可以在译文完成前开始合成。最终的 `Translation` 事件使用后端对全部 token 重新解码的完整译文，并再次做质量检查。
+    async fn translate_stream_and_publish(
+        &self,
+        session: &Session,
+        transcript: &StableTranscript,
+        timestamp_ms: u64,
+        committed_prefix: Option<String>,
+        segment_tx: mpsc::UnboundedSender<TextSegment>,
+    ) -> EngineResult<TranslationResponse> {
+        let target_language = session.target_language();
+        let translation_request = Self::final_translation_request(session, transcript, committed_prefix);
+        
+        let send_segment = |segment: TextSegment, log_probs: &[f32]| {
+            // 分句送入 TTS 前先做质量检查（重复序列、可疑字符、低置信度），与最终译文的处理顺序一致
+            let text = match self.quality_checker {
+                Some(ref checker) => checker.check_and_fix_with_metrics(
+                    &transcript.text,
+                    &segment.text,
+                    &target_language,
+                    TranslationQualityMetrics::from_log_probs(log_probs).as_ref(),
+                ),
+                None => segment.text,
+            };
+            let text = match self.post_processor {
+                Some(ref processor) => processor.process(&text, &target_language),
+                None => text,
+            };
+            if !text.trim().is_empty() {
+                eprintln!("[NMT] ✂️  Streamed clause to TTS: '{}' (pause_type: {:?})", text, segment.pause_type);
+                let _ = segment_tx.send(TextSegment { text, pause_type: segment.pause_type });
+            }
         };
         
-        // 3. 执行翻译
-        let mut translation_response = self.nmt.translate(translation_request).await?;
+        // 1. 逐 token 接收译文
+        let mut stream = self.nmt.translate_stream(translation_request).await?;
+        let mut segmenter = self.text_segmenter.as_deref().map(StreamingSegmenter::new);
+        let mut translated_text = String::new();
+        let mut log_probs = Vec::new();
+        let mut provider = None;
+        while let Some(delta) = stream.next().await {
+            let delta = delta?;
+            match delta.full_text {
+                Some(ref full_text) => translated_text.clone_from(full_text),
+                None => translated_text.push_str(&delta.text),
+            }
+            if provider.is_none() {
+                provider = delta.provider.clone();
+            }
+            if let Some(probability) = delta.probability {
+                log_probs.push(probability.ln());
+            }
+            Self::publish_nmt_partial_event(self, session.id(), &delta, &translated_text, timestamp_ms).await?;
+            
+            // 2. 凑齐的分句立即送入 TTS
+            if let Some(ref mut segmenter) = segmenter {
+                for segment in segmenter.push(&delta.text) {
+                    send_segment(segment, &log_probs);
+                }
+            }
+        }
+        
+        // 3. 剩余文本（没有分句器时整句送入 TTS）
+        match segmenter {
+            Some(segmenter) => {
+                for segment in segmenter.finish() {
+                    send_segment(segment, &log_probs);
+                }
+            }
+            None => send_segment(TextSegment {
+                text: translated_text.clone(),
+                pause_type: PauseType::SentenceEnd,
+            }, &log_probs),
+        }
+        
+        let translation_response = TranslationResponse {
+            translated_text: translated_text.clone(),
+            is_stable: true,
+            speaker_id: transcript.speaker_id.clone(),
+            source_text: None,
+            source_audio_duration_ms: None,
+            source_language: None,
+            quality_metrics: TranslationQualityMetrics::from_log_probs(&log_probs),
+            committed_text: Some(translated_text),
+            provider,
+        };
         
-        // 3.1. 确保 speaker_id 被传递到 TranslationResponse
+        // 4. 质量检查、后处理并发布
+        self.finalize_translation(session, transcript, translation_response, timestamp_ms).await
+    }
+
+    /// 对翻译结果做质量检查和文本后处理，并发布翻译事件
+    async fn finalize_translation(
+        &self,
+        session: &Session,
+        transcript: &StableTranscript,
+        mut translation_response: TranslationResponse,
+        timestamp_ms: u64,
+    ) -> EngineResult<TranslationResponse> {
+        let target_language = session.target_language();
+        
+        // 1. 确保 speaker_id 被传递到 TranslationResponse
         if translation_response.speaker_id.is_none() {
             translation_response.speaker_id = transcript.speaker_id.clone();
         }
         eprintln!("[NMT] Raw translation result: '{}'", translation_response.translated_text);
         
-        // 4. 应用翻译质量检查
+        // 2. 应用翻译质量检查
         if let Some(ref checker) = self.quality_checker {
             let before_check = translation_response.translated_text.clone();
-            let checked_text = checker.check_and_fix(
+            let checked_text = checker.check_and_fix_with_metrics(
                 &transcript.text,
                 &translation_response.translated_text,
                 &target_language,
+                translation_response.quality_metrics.as_ref(),
             );
             if before_check != checked_text {
                 eprintln!("[NMT] After quality check: '{}' (was: '{}')", checked_text, before_check);
@@ -1546,7 +1724,7 @@ impl CoreEngine {
             translation_response.translated_text = checked_text;
         }
         
-        // 5. 应用文本后处理
+        // 3. 应用文本后处理
         if let Some(ref processor) = self.post_processor {
             let before_process = translation_response.translated_text.clone();
             let processed_text = processor.process(&translation_response.translated_text, &target_language);
@@ -1558,19 +1736,106 @@ impl CoreEngine {
         
         eprintln!("[NMT] Final translation: '{}'", translation_response.translated_text);
         
-        // 6. 发布翻译事件
-        Self::publish_translation_event(self, &translation_response, timestamp_ms).await?;
+        // 4. 发布翻译事件
+        Self::publish_translation_event(self, session.id(), &translation_response, timestamp_ms).await?;
         
         Ok(translation_response)
     }
 
+    /// 流式翻译的同时逐分句合成 TTS
+    /// 
+    /// 翻译和合成并发进行：译文每凑齐一个分句就开始合成，不等整句翻译完成。
+    /// `source` 为原始 ASR 结果，用于计算语速。
+    /// 
+    /// 返回 (翻译结果, TTS 占位 chunk, NMT耗时, TTS耗时, YourTTS耗时)
+    #[allow(clippy::too_many_arguments)]
+    async fn translate_and_synthesize_streaming(
+        &self,
+        session: &Session,
+        transcript: &StableTranscript,
+        timestamp_ms: u64,
+        committed_prefix: Option<String>,
+        source: Option<&StableTranscript>,
+        source_audio_duration_ms: Option<u64>,
+        reference_audio: Option<Vec<f32>>,
+        voice_embedding: Option<Vec<f32>>,
+        estimated_gender: Option<String>,
+    ) -> (Option<TranslationResponse>, Option<TtsStreamChunk>, u64, u64, Option<u64>) {
+        // 合成所需的说话者和源音频信息在翻译开始前就已确定
+        let translation_meta = TranslationResponse {
+            translated_text: String::new(),
+            is_stable: false,
+            speaker_id: transcript.speaker_id.clone(),
+            source_text: source.map(|s| s.text.clone()),
+            source_audio_duration_ms: source.and(source_audio_duration_ms),
+            source_language: source.map(|s| s.language.clone()),
+            quality_metrics: None,
+            committed_text: None,
+            provider: None,
+        };
+        
+        let (segment_tx, segment_rx) = mpsc::unbounded_channel();
+        let start = Instant::now();
+        eprintln!("[NMT] 🚀 Starting streaming translation with per-clause TTS...");
+        let translate = async {
+            let result = self.translate_stream_and_publish(session, transcript, timestamp_ms, committed_prefix, segment_tx).await;
+            (result, start.elapsed().as_millis() as u64)
+        };
+        let synthesize = async {
+            let result = self.synthesize_segments_and_publish(
+                session,
+                &translation_meta,
+                segment_rx,
+                timestamp_ms,
+                reference_audio,
+                voice_embedding,
+                estimated_gender,
+            ).await;
+            (result, start.elapsed().as_millis() as u64)
+        };
+        let ((translation_result, nmt_ms), (tts_result, tts_ms)) = tokio::join!(translate, synthesize);
+        
+        let translation_result = match translation_result {
+            Ok(mut translation) => {
+                translation.source_text = translation_meta.source_text.clone();
+                translation.source_audio_duration_ms = translation_meta.source_audio_duration_ms;
+                translation.source_language = translation_meta.source_language.clone();
+                eprintln!("[NMT] ✅ Streaming translation completed in {}ms", nmt_ms);
+                Some(translation)
+            }
+            Err(e) => {
+                eprintln!("[NMT] ❌ Streaming translation failed in {}ms: {}", nmt_ms, e);
+                None
+            }
+        };
+        
+        // 合成与翻译重叠进行，TTS 耗时按翻译完成后的剩余时间计
+        let tts_ms = tts_ms.saturating_sub(nmt_ms);
+        let (tts_result, yourtts_ms) = match tts_result {
+            Ok((chunk, yourtts_ms)) => {
+                eprintln!("[TTS] ✅ Streaming synthesis finished {}ms after translation (placeholder size: {} bytes)",
+                    tts_ms, chunk.audio.len());
+                (Some(chunk), yourtts_ms)
+            }
+            Err(e) => {
+                eprintln!("[TTS] ❌ Streaming synthesis failed: {}", e);
+                (None, None)
+            }
+        };
+        
+        (translation_result, tts_result, nmt_ms, tts_ms, yourtts_ms)
+    }
+
     // publish_asr_partial_event, publish_asr_final_event, publish_emotion_event, publish_translation_event 已移至 events.rs 模块
 
     /// TTS 合成并发布事件
     /// 
-    /// 返回 (TtsStreamChunk, YourTTS耗时)
+    /// 使用 `synthesize_stream` 流式合成，每收到一块音频立即转换格式并发布
+    /// 
+    /// 返回 (所有块拼接后的 TtsStreamChunk, YourTTS耗时)
     async fn synthesize_and_publish(
         &self,
+        session: &Session,
         translation: &TranslationResponse,
         timestamp_ms: u64,
         reference_audio: Option<Vec<f32>>,
@@ -1579,25 +1844,15 @@ impl CoreEngine {
     ) -> EngineResult<(TtsStreamChunk, Option<u64>)> {
         // 如果启用增量播放，使用增量合成方法
         if self.tts_incremental_enabled {
-            return self.synthesize_and_publish_incremental(translation, timestamp_ms, reference_audio, voice_embedding, estimated_gender).await;
+            return self.synthesize_and_publish_incremental(session, translation, timestamp_ms, reference_audio, voice_embedding, estimated_gender).await;
         }
 
         // 原有的一次性合成逻辑
-        // 1. 获取目标语言（用于 TTS locale）
-        // ⚠️ 优化：如果配置获取失败，使用默认值而不是阻塞整个流程
-        let target_language = self.config.current().await
-            .map(|c| c.target_language)
-            .unwrap_or_else(|_| {
-                eprintln!("[TTS] ⚠️  Failed to get config, using default target_language: zh");
-                "zh".to_string()
-            });
-        
-        // 2. 对中文文本进行预处理：将小数转换为中文读法
-        let processed_text = if target_language.starts_with("zh") {
-            Self::convert_decimals_to_chinese(&translation.translated_text)
-        } else {
-            translation.translated_text.clone()
-        };
+        // 1. 获取目标语言（用于 TTS locale，每个会话独立配置）
+        let target_language = session.target_language();
+        
+        // 2. 数字、日期、单位等的读法由 TTS 后端外层的 NormalizedTts 按 locale 规范化，这里不再改写文本
+        let processed_text = translation.translated_text.clone();
         
         // 3. 使用传入的 reference_audio（用于 zero-shot TTS）
         eprintln!("[TTS] Reference audio: {} (samples: {})", 
@@ -1611,7 +1866,7 @@ impl CoreEngine {
         // 3. 如果都没有，根据 estimated_gender 选择默认音色（男/女）
         let voice = if reference_audio.is_none() {
             if let Some(ref speaker_id) = translation.speaker_id {
-                if let Some(ref mapper) = self.speaker_voice_mapper {
+                if let Some(ref mapper) = session.speaker_voice_mapper {
                     let assigned_voice = mapper.get_or_assign_voice(speaker_id).await;
                     eprintln!("[TTS] Assigned voice: '{}' for speaker: {}", assigned_voice, speaker_id);
                     assigned_voice
@@ -1646,7 +1901,7 @@ impl CoreEngine {
         
         // 5.1. 获取全局语速（如果启用了自适应VAD）
         // 注意：不区分说话者，使用全局语速历史
-        let speech_rate = Self::get_vad_speech_rate(self);
+        let speech_rate = Self::get_vad_speech_rate(self, session);
         
         if let Some(rate) = speech_rate {
             eprintln!("[TTS] ✅ Using source speech rate: {:.2} chars/s (from user's input, will be applied to translated text)", 
@@ -1755,10 +2010,12 @@ impl CoreEngine {
             eprintln!("[TTS] ⚠️  Using non-YourTTS service (Piper or other), reference audio will NOT be used!");
         }
         
-        // 尝试使用主 TTS 服务，如果失败且是语言不支持的错误，使用 fallback TTS
-        let tts_chunk = match self.tts.synthesize(tts_request.clone()).await {
-            Ok(chunk) => chunk,
-            Err(e) => {
+        // 流式合成：先合成完的部分先发布，不等整句合成完
+        // 尝试使用主 TTS 服务，如果第一块之前失败且是语言不支持的错误，使用 fallback TTS
+        let mut tts_stream = self.tts.synthesize_stream(tts_request.clone());
+        let first_chunk = match tts_stream.next().await {
+            Some(Ok(chunk)) => chunk,
+            Some(Err(e)) => {
                 // 检查是否是语言不支持的错误（YourTTS 不支持中文）
                 let error_msg = e.to_string().to_lowercase();
                 let is_language_error = error_msg.contains("does not support chinese") ||
@@ -1768,28 +2025,78 @@ impl CoreEngine {
                                         error_msg.contains("dict_keys") ||
                                         error_msg.contains("dimension out of range");
                 
-                if is_language_error && self.fallback_tts.is_some() {
-                    eprintln!("[TTS] ⚠️  TTS failed due to unsupported language, trying fallback TTS...");
-                    if let Some(ref fallback_tts) = self.fallback_tts {
+                match self.fallback_tts {
+                    Some(ref fallback_tts) if is_language_error => {
+                        eprintln!("[TTS] ⚠️  TTS failed due to unsupported language, trying fallback TTS...");
                         let mut fallback_request = tts_request.clone();
                         fallback_request.reference_audio = None;  // Piper 不支持 reference_audio
                         fallback_request.speaker_id = None;  // Piper 不支持 speaker_id
                         fallback_request.voice_embedding = None;  // Piper 不支持 voice_embedding
                         eprintln!("[TTS] ⚡ Fallback: Using Piper TTS with speech_rate={:?}", fallback_request.speech_rate);
-                        fallback_tts.synthesize(fallback_request).await?
-                    } else {
-                        return Err(e);
+                        tts_stream = fallback_tts.synthesize_stream(fallback_request);
+                        tts_stream.next().await
+                            .ok_or_else(|| EngineError::new("Fallback TTS returned no audio"))??
                     }
-                } else {
-                    return Err(e);
+                    _ => return Err(e),
                 }
             }
+            None => return Err(EngineError::new("TTS returned no audio")),
         };
+        eprintln!("[TTS] First audio chunk after {}ms", tts_synth_start.elapsed().as_millis());
+
+        // 6. 逐块转换为会话的输出格式并发布 TTS 事件（包含 speaker_id 信息）
+        // 事件时间戳 = 边界时间戳 + 块在合成音频中的时间，保证严格递增（客户端按事件时间戳排序播放）
+        let mut converter = self.output_format_for(session).and_then(|format| {
+            TtsStreamConverter::new(format)
+                .map_err(|e| eprintln!("[TTS] ⚠️  Invalid output format {:?}: {}, using original audio", format, e))
+                .ok()
+        });
+        let mut published: Vec<TtsStreamChunk> = Vec::new();
+        let mut next_event_timestamp = timestamp_ms;
+        let mut next_chunk = Some(first_chunk);
+        while let Some(chunk) = next_chunk.take() {
+            let is_last = chunk.is_last;
+            let chunk = match converter.as_mut() {
+                Some(converter) => converter.push(&chunk).unwrap_or_else(|e| {
+                    eprintln!("[TTS] ⚠️  Failed to convert audio chunk from {:?} to {:?}: {}, using original audio",
+                        chunk.format, converter.output(), e);
+                    chunk
+                }),
+                None => chunk,
+            };
+            let event_timestamp = (timestamp_ms + chunk.timestamp_ms).max(next_event_timestamp);
+            Self::publish_tts_event(self, session.id(), &chunk, event_timestamp).await?;
+            next_event_timestamp = event_timestamp + 1;
+            let last_format = chunk.format;
+            let last_timestamp = chunk.timestamp_ms;
+            published.push(chunk);
+            if is_last {
+                break;
+            }
+            next_chunk = match tts_stream.next().await {
+                Some(chunk) => Some(chunk?),
+                None => {
+                    // 流在 is_last 之前结束：补发一个空的最后一块，让客户端知道本句结束
+                    eprintln!("[TTS] ⚠️  TTS stream ended without a last chunk");
+                    Some(TtsStreamChunk {
+                        audio: Vec::new(),
+                        format: TtsAudioFormat { encoding: AudioEncoding::Pcm, ..last_format },
+                        timestamp_ms: last_timestamp,
+                        is_last: true,
+                    })
+                }
+            };
+        }
         let tts_synth_ms = tts_synth_start.elapsed().as_millis() as u64;
-        eprintln!("[TTS] TTS service call completed in {}ms", tts_synth_ms);
-        
-        // 6. 发布 TTS 事件（包含 speaker_id 信息）
-        Self::publish_tts_event(self, &tts_chunk, timestamp_ms).await?;
+        eprintln!("[TTS] TTS service call completed in {}ms ({} chunks published)", tts_synth_ms, published.len());
+
+        // 所有块按顺序拼接即为完整音频（WAV 只有第一块带文件头）
+        let tts_chunk = TtsStreamChunk {
+            audio: published.iter().flat_map(|chunk| chunk.audio.iter().copied()).collect(),
+            format: published[0].format,
+            timestamp_ms: published[0].timestamp_ms,
+            is_last: true,
+        };
         
         // 如果使用 YourTTS，记录 YourTTS 的耗时（从日志中提取或使用总耗时）
         // 注意：YourTTS 的耗时已经在 yourtts_http.rs 中记录，这里我们使用总耗时作为近似值
@@ -1812,29 +2119,25 @@ impl CoreEngine {
     /// 返回 (TtsStreamChunk, YourTTS耗时)
     async fn synthesize_and_publish_incremental(
         &self,
+        session: &Session,
         translation: &TranslationResponse,
         timestamp_ms: u64,
         reference_audio: Option<Vec<f32>>,
         voice_embedding: Option<Vec<f32>>,
         estimated_gender: Option<String>,
     ) -> EngineResult<(TtsStreamChunk, Option<u64>)> {
-        // 1. 获取目标语言（用于 TTS locale）
-        // ⚠️ 优化：如果配置获取失败，使用默认值而不是阻塞整个流程
-        let target_language = self.config.current().await
-            .map(|c| c.target_language)
-            .unwrap_or_else(|_| {
-                eprintln!("[TTS] ⚠️  Failed to get config, using default target_language: zh");
-                "zh".to_string()
-            });
-        
-        // 2. 分割文本为短句（使用带停顿类型的分段）
+        // 1. 分割文本为短句（使用带停顿类型的分段）
         let segmenter = self.text_segmenter.as_ref()
             .ok_or_else(|| EngineError::new("Text segmenter not initialized".to_string()))?;
         
         eprintln!("[TTS] Input text for segmentation: '{}'", translation.translated_text);
         
         // 尝试使用带停顿类型的分段（如果支持）
-        let segments_with_pause = if segmenter.split_on_comma {
+        // SSML 不按标点分段（会切断标签），整体作为一段交给 TTS，由 SsmlTts 按 SSML 结构分段合成
+        let segments_with_pause = if is_ssml(&translation.translated_text) {
+            eprintln!("[TTS] SSML input, synthesizing as a single segment");
+            vec![TextSegment { text: translation.translated_text.clone(), pause_type: PauseType::SentenceEnd }]
+        } else if segmenter.split_on_comma {
             let segments = segmenter.segment_with_pause_type(&translation.translated_text);
             eprintln!("[TTS] Segmented into {} parts:", segments.len());
             for (i, seg) in segments.iter().enumerate() {
@@ -1870,15 +2173,53 @@ impl CoreEngine {
             return Err(EngineError::new("No segments to synthesize".to_string()));
         }
 
-        // 3. 准备阶段：预先准备所有 TTS 请求参数（包括异步的 voice 获取）
+        // 2. 逐段合成并发布（所有短句已知，一次性送入）
+        let (segment_tx, segment_rx) = mpsc::unbounded_channel();
+        for segment in segments_with_pause {
+            let _ = segment_tx.send(segment);
+        }
+        drop(segment_tx);
+
+        self.synthesize_segments_and_publish(
+            session,
+            translation,
+            segment_rx,
+            timestamp_ms,
+            reference_audio,
+            voice_embedding,
+            estimated_gender,
+        ).await
+    }
+
+    /// TTS 逐段合成并发布事件
+    /// 
+    /// 从 `segments` 按顺序接收短句：每收到一个短句立即开始合成（多个短句并行），合成结果按顺序发布。
+    /// 短句可以在译文完成前陆续到达（流式翻译），也可以一次性全部送入。
+    /// `translation` 只用于获取说话者和源音频信息（音色、语速），不使用其中的译文。
+    /// 
+    /// 返回 (TtsStreamChunk, YourTTS耗时)
+    #[allow(clippy::too_many_arguments)]
+    async fn synthesize_segments_and_publish(
+        &self,
+        session: &Session,
+        translation: &TranslationResponse,
+        mut segments: mpsc::UnboundedReceiver<TextSegment>,
+        timestamp_ms: u64,
+        reference_audio: Option<Vec<f32>>,
+        voice_embedding: Option<Vec<f32>>,
+        estimated_gender: Option<String>,
+    ) -> EngineResult<(TtsStreamChunk, Option<u64>)> {
+        let target_language = session.target_language();
+
+        // 1. 准备阶段：预先准备所有 TTS 请求参数（包括异步的 voice 获取）
         let tts_incremental_start = Instant::now();
         
-        // 3.1. 预先获取 voice（如果需要，且只获取一次）
+        // 1.1. 预先获取 voice（如果需要，且只获取一次）
         let use_reference_audio = reference_audio.clone();
         let use_voice_embedding = voice_embedding.clone();
         let common_voice = if use_reference_audio.is_none() {
             if let Some(ref speaker_id) = translation.speaker_id {
-                if let Some(ref mapper) = self.speaker_voice_mapper {
+                if let Some(ref mapper) = session.speaker_voice_mapper {
                     mapper.get_or_assign_voice(speaker_id).await
                 } else {
                     // 没有 voice mapper，根据性别选择默认音色
@@ -1892,9 +2233,9 @@ impl CoreEngine {
             String::new()  // 使用 zero-shot TTS，voice 可以为空
         };
         
-        // 3.2. 计算每个 segment 的独立语速
-        let segment_speech_rates: Vec<Option<f32>> = if let (Some(source_duration_ms), Some(source_text)) = 
-            (translation.source_audio_duration_ms, translation.source_text.clone()) {
+        // 1.2. 计算源语速（所有 segment 共用）
+        let source_speech_rate: Option<f32> = if let (Some(source_duration_ms), Some(source_text)) = 
+            (translation.source_audio_duration_ms, translation.source_text.as_ref()) {
             let source_text_len = source_text.chars().count() as f32;
             let source_duration_sec = source_duration_ms as f32 / 1000.0;
             let overall_speech_rate = if source_duration_sec > 0.0 {
@@ -1951,52 +2292,46 @@ impl CoreEngine {
                 overall_speech_rate
             };
             
-            // 为每个 segment 计算语速
-            // 注意：语速（chars/s）是说话者的特征，应该保持一致
+            // 注意：语速（chars/s）是说话者的特征，所有 segment 保持一致
             // 但每个 segment 的文本长度不同，所以播放时长会不同
-            segments_with_pause.iter().map(|seg| {
-                if source_duration_sec > 0.0 && adjusted_speech_rate > 0.0 {
-                    let segment_text_len = seg.text.chars().count() as f32;
-                    let expected_duration_sec = segment_text_len / adjusted_speech_rate;
-                    eprintln!("[TTS] 📊 Segment '{}' ({} chars): speech_rate={:.2} chars/s, expected_duration={:.2}s", 
-                             seg.text, segment_text_len, adjusted_speech_rate, expected_duration_sec);
-                    Some(adjusted_speech_rate)
-                } else {
-                    None
-                }
-            }).collect()
+            if source_duration_sec > 0.0 && adjusted_speech_rate > 0.0 {
+                Some(adjusted_speech_rate)
+            } else {
+                None
+            }
         } else {
-            segments_with_pause.iter().map(|_| {
-                // 注意：不区分说话者，使用全局语速历史
-                Self::get_vad_speech_rate(self)
-            }).collect()
+            None
         };
         
-        // 3.3. 创建所有 segment 的并行处理任务
-        eprintln!("[TTS] ⚡ Starting parallel synthesis of {} segments...", segments_with_pause.len());
-        let segment_futures: Vec<_> = segments_with_pause.iter().enumerate().map(|(idx, segment)| {
-            let is_last = idx == segments_with_pause.len() - 1;
-            let segment_text = segment.text.clone();
+        // 1.3. 为单个 segment 创建合成任务
+        let is_yourtts = self.tts_service_url.as_ref()
+            .map(|url| url.contains("5004") || url.contains("yourtts"))
+            .unwrap_or(false);
+        let create_segment_task = |idx: usize, segment: TextSegment| {
+            let segment_text = segment.text;
             let segment_pause_type = segment.pause_type;
             
-            // 预处理文本
-            let processed_text = if target_language.starts_with("zh") {
-                Self::convert_decimals_to_chinese(&segment_text)
-            } else {
-                segment_text.clone()
-            };
+            // 文本规范化由 TTS 后端外层的 NormalizedTts 完成
+            let processed_text = segment_text.clone();
             
             // 获取语速
-            let speech_rate = segment_speech_rates.get(idx)
-                .and_then(|rate| *rate)
-                .or_else(|| {
+            let speech_rate = match source_speech_rate {
+                Some(rate) => {
+                    let segment_text_len = segment_text.chars().count() as f32;
+                    let expected_duration_sec = segment_text_len / rate;
+                    eprintln!("[TTS] 📊 Segment '{}' ({} chars): speech_rate={:.2} chars/s, expected_duration={:.2}s", 
+                             segment_text, segment_text_len, rate, expected_duration_sec);
+                    Some(rate)
+                }
+                None => {
                     // 注意：不区分说话者，使用全局语速历史
-                    let rate = Self::get_vad_speech_rate(self);
+                    let rate = Self::get_vad_speech_rate(self, session);
                     if rate.is_none() {
                         eprintln!("[TTS] ⚠️  No speech rate available for segment {} (VAD adaptive may be disabled or insufficient samples)", idx + 1);
                     }
                     rate
-                });
+                }
+            };
             
             // 构造 TTS 请求
             // 多人模式特殊处理：
@@ -2013,10 +2348,6 @@ impl CoreEngine {
             // 在多人模式下，从 voice 字段获取 speaker 参数
             // 如果使用 YourTTS，需要使用 YourTTS 的预定义 speaker 名称（如 "male-en-5", "female-en-5"）
             // 而不是从 speaker_voice_mapper 获取的 voice（那是给 Piper TTS 用的）
-            let is_yourtts = self.tts_service_url.as_ref()
-                .map(|url| url.contains("5004") || url.contains("yourtts"))
-                .unwrap_or(false);
-            
             let speaker_for_request = if is_multi_user_mode {
                 if is_yourtts {
                     // 多人模式 + YourTTS：使用 YourTTS 的预定义 speaker
@@ -2071,16 +2402,15 @@ impl CoreEngine {
                 eprintln!("[TTS] ⚡ Queueing segment {:2} for parallel synthesis: '{}' (⚠️  NO SPEECH_RATE - will use default)", idx + 1, segment_text);
             }
             
-            // 创建异步任务：合成 + 增强
+            // 创建异步任务：合成（音频增强在发布时进行，届时才知道是否为最后一个短句）
             let tts_clone = Arc::clone(&self.tts);
             let fallback_tts_clone = self.fallback_tts.as_ref().map(Arc::clone);
-            let enhancer_clone = self.audio_enhancer.as_ref().map(Arc::clone);
             
             async move {
                 let segment_tts_start = Instant::now();
                 
                 // 合成音频（带回退机制）
-                let mut chunk = match tts_clone.synthesize(tts_request.clone()).await {
+                let chunk = match tts_clone.synthesize(tts_request.clone()).await {
                     Ok(chunk) => chunk,
                     Err(e) => {
                         // 检查是否是语言不支持的错误（YourTTS 不支持中文）
@@ -2126,101 +2456,89 @@ impl CoreEngine {
                     }
                 };
                 
-                let segment_tts_ms = segment_tts_start.elapsed().as_millis() as u64;
-                
-                // 应用音频增强
-                if let Some(ref enhancer) = enhancer_clone {
-                    let pause_type = if segment_pause_type != crate::text_segmentation::PauseType::None {
-                        Some(segment_pause_type)
-                    } else {
-                        None
-                    };
-                    
-                    match enhancer.enhance_audio_with_pause_type(
-                        &chunk.audio,
-                        idx == 0,  // is_first
-                        is_last,   // is_last
-                        pause_type,
-                    ).await {
-                        Ok(enhanced_audio) => {
-                            chunk.audio = enhanced_audio;
-                            eprintln!("[TTS] ✅ Segment {:2} completed in {}ms: '{}' (audio_size: {} bytes)", 
-                                idx + 1, segment_tts_ms, segment_text, chunk.audio.len());
-                        }
-                        Err(e) => {
-                            eprintln!("[TTS] ⚠️  Segment {:2} enhancement failed: {}, using original audio", idx + 1, e);
-                        }
-                    }
-                } else {
-                    eprintln!("[TTS] ✅ Segment {:2} completed in {}ms: '{}' (audio_size: {} bytes)", 
-                        idx + 1, segment_tts_ms, segment_text, chunk.audio.len());
-                }
-                
-                Ok((idx, chunk, segment_tts_ms, is_last))
+                Ok(SynthesizedSegment {
+                    idx,
+                    text: segment_text,
+                    pause_type: segment_pause_type,
+                    chunk,
+                    tts_ms: segment_tts_start.elapsed().as_millis() as u64,
+                })
             }
-        }).collect();
-        
-        // 3.4. 并行执行所有任务并等待完成
-        eprintln!("[TTS] ⚡ Executing {} segments in parallel...", segment_futures.len());
-        let segment_results = join_all(segment_futures).await;
+        };
         
-        // 3.5. 按顺序处理结果（保持播放顺序）
+        // 2. 接收短句并并行合成，按顺序发布
+        // 最后一个完成的短句先暂存，直到确认没有后续短句时才能标记 is_last
+        eprintln!("[TTS] ⚡ Starting parallel synthesis of streamed segments...");
+        let mut pending = FuturesOrdered::new();
+        let mut next_idx = 0usize;
+        let mut segments_closed = false;
+        let mut held: Option<SynthesizedSegment> = None;
         let mut ordered_chunks = Vec::new();
         let mut total_yourtts_ms = 0u64;
         let mut yourtts_call_count = 0u64;
-        
-        // 收集所有结果（包含索引，用于排序）
-        let mut results_with_idx: Vec<_> = Vec::new();
-        for result in segment_results {
-            results_with_idx.push(result?);
-        }
-        
-        // 按索引排序以确保顺序
-        results_with_idx.sort_by_key(|(idx, _, _, _)| *idx);
-        
-        // 按顺序处理每个结果
         let mut current_timestamp = timestamp_ms;
-        for (idx, mut chunk, segment_tts_ms, is_last) in results_with_idx {
-            // 累计 YourTTS 耗时
-            if self.tts_service_url.as_ref()
-                .map(|url| url.contains("5004") || url.contains("yourtts"))
-                .unwrap_or(false) {
-                total_yourtts_ms += segment_tts_ms;
-                yourtts_call_count += 1;
-            }
-            
-            // 设置时间戳和 is_last 标志
-            chunk.timestamp_ms = current_timestamp;
-            chunk.is_last = is_last;
-            
-            // 立即发布（buffer_sentences == 0）
-            if self.tts_buffer_sentences == 0 {
-                Self::publish_tts_event(self, &chunk, current_timestamp).await?;
-                eprintln!("[TTS] 📤 Published segment {:2} immediately (timestamp: {}ms)", idx + 1, current_timestamp);
+        
+        loop {
+            tokio::select! {
+                segment = segments.recv(), if !segments_closed => {
+                    match segment {
+                        Some(segment) => {
+                            // 还有后续短句，暂存的短句可以发布
+                            if let Some(ready) = held.take() {
+                                ordered_chunks.push(self.publish_synthesized_segment(session, ready, false, current_timestamp).await?);
+                                current_timestamp += 100; // 每个短句间隔 100ms
+                            }
+                            pending.push_back(create_segment_task(next_idx, segment));
+                            next_idx += 1;
+                        }
+                        None => {
+                            segments_closed = true;
+                            if pending.is_empty() {
+                                if let Some(ready) = held.take() {
+                                    ordered_chunks.push(self.publish_synthesized_segment(session, ready, true, current_timestamp).await?);
+                                    current_timestamp += 100;
+                                }
+                            }
+                        }
+                    }
+                }
+                Some(result) = pending.next(), if !pending.is_empty() => {
+                    let ready: SynthesizedSegment = result?;
+                    
+                    // 累计 YourTTS 耗时
+                    if is_yourtts {
+                        total_yourtts_ms += ready.tts_ms;
+                        yourtts_call_count += 1;
+                    }
+                    
+                    if pending.is_empty() && !segments_closed {
+                        // 暂时没有后续短句，等待确认是否为最后一个
+                        held = Some(ready);
+                    } else {
+                        let is_last = pending.is_empty();
+                        ordered_chunks.push(self.publish_synthesized_segment(session, ready, is_last, current_timestamp).await?);
+                        current_timestamp += 100;
+                    }
+                }
+                else => break,
             }
-            
-            // 保存到 ordered_chunks（用于合并和缓冲模式）
-            ordered_chunks.push(chunk);
-            
-            // 更新时间戳
-            current_timestamp += 100; // 每个短句间隔 100ms
         }
         
-        // 3.6. 缓冲模式：发布剩余的短句（如果需要）
+        // 3. 缓冲模式：发布剩余的短句（如果需要）
         if self.tts_buffer_sentences > 0 {
             for (idx, chunk) in ordered_chunks.iter().enumerate() {
-                Self::publish_tts_event(self, chunk, chunk.timestamp_ms).await?;
+                Self::publish_tts_event(self, session.id(), chunk, chunk.timestamp_ms).await?;
                 eprintln!("[TTS] 📤 Published segment {:2} from buffer (timestamp: {}ms)", idx + 1, chunk.timestamp_ms);
             }
         }
         
-        // 3.7. 不合并音频，每个 segment 已经通过 publish_tts_event 独立发布
+        // 4. 不合并音频，每个 segment 已经通过 publish_tts_event 独立发布
         // 这样用户体验更接近连续输出，而不是等好几秒才听到完整的话
         let tts_incremental_total_ms = tts_incremental_start.elapsed().as_millis() as u64;
         eprintln!("[TTS] ⚡ Parallel synthesis completed: {} segments in {}ms (avg: {:.1}ms/segment)", 
             ordered_chunks.len(), 
             tts_incremental_total_ms,
-            if ordered_chunks.len() > 0 { tts_incremental_total_ms as f32 / ordered_chunks.len() as f32 } else { 0.0 });
+            if !ordered_chunks.is_empty() { tts_incremental_total_ms as f32 / ordered_chunks.len() as f32 } else { 0.0 });
         
         // 计算总音频大小（仅用于日志）
         let total_audio_size: usize = ordered_chunks.iter().map(|c| c.audio.len()).sum();
@@ -2228,30 +2546,25 @@ impl CoreEngine {
             total_audio_size, ordered_chunks.len());
         
         // 如果使用 YourTTS，记录 YourTTS 的耗时
-        let yourtts_ms = if self.tts_service_url.as_ref()
-            .map(|url| url.contains("5004") || url.contains("yourtts"))
-            .unwrap_or(false) {
-            if yourtts_call_count > 0 {
-                Some(total_yourtts_ms)
-            } else {
-                None
-            }
+        let yourtts_ms = if is_yourtts && yourtts_call_count > 0 {
+            Some(total_yourtts_ms)
         } else {
             None
         };
         
         if let Some(yt_ms) = yourtts_ms {
             eprintln!("[TTS] YourTTS total time: {}ms ({} calls, avg: {:.1}ms per call)", 
-                yt_ms, yourtts_call_count, if yourtts_call_count > 0 { yt_ms as f64 / yourtts_call_count as f64 } else { 0.0 });
+                yt_ms, yourtts_call_count, yt_ms as f64 / yourtts_call_count as f64);
         }
         
-        // 返回 None，因为每个 segment 已经通过事件独立发布
+        // 每个 segment 已经通过事件独立发布
         // 客户端应该通过事件总线接收并按 timestamp_ms 顺序播放
         // 返回第一个 chunk 作为占位符（仅用于兼容性，实际音频通过事件发布）
         let placeholder_chunk = if let Some(first_chunk) = ordered_chunks.first() {
             // 只返回第一个 chunk 的音频（作为占位符），实际所有 chunks 已通过事件发布
             TtsStreamChunk {
                 audio: first_chunk.audio.clone(),  // 只包含第一个 segment 的音频
+                format: first_chunk.format,
                 timestamp_ms: first_chunk.timestamp_ms,
                 is_last: false,  // 标记为 false，表示还有更多 chunks 通过事件发布
             }
@@ -2266,7 +2579,182 @@ impl CoreEngine {
         Ok((placeholder_chunk, yourtts_ms))
     }
 
-    // convert_decimals_to_chinese 已移至 text_utils.rs 模块
+    /// 对合成完成的短句做音频增强，设置时间戳和 is_last 标志
+    /// 
+    /// 非缓冲模式（`tts_buffer_sentences == 0`）下立即发布；缓冲模式下由调用方统一发布
+    async fn publish_synthesized_segment(
+        &self,
+        session: &Session,
+        segment: SynthesizedSegment,
+        is_last: bool,
+        timestamp_ms: u64,
+    ) -> EngineResult<TtsStreamChunk> {
+        let SynthesizedSegment { idx, text, pause_type, mut chunk, tts_ms } = segment;
+        
+        // 应用音频增强
+        if let Some(ref enhancer) = self.audio_enhancer {
+            let pause_type = if pause_type != PauseType::None {
+                Some(pause_type)
+            } else {
+                None
+            };
+            
+            match enhancer.enhance_audio_with_pause_type(
+                &chunk.audio,
+                &chunk.format,
+                idx == 0,  // is_first
+                is_last,   // is_last
+                pause_type,
+            ).await {
+                Ok(enhanced_audio) => {
+                    chunk.audio = enhanced_audio;
+                    eprintln!("[TTS] ✅ Segment {:2} completed in {}ms: '{}' (audio_size: {} bytes)", 
+                        idx + 1, tts_ms, text, chunk.audio.len());
+                }
+                Err(e) => {
+                    eprintln!("[TTS] ⚠️  Segment {:2} enhancement failed: {}, using original audio", idx + 1, e);
+                }
+            }
+        } else {
+            eprintln!("[TTS] ✅ Segment {:2} completed in {}ms: '{}' (audio_size: {} bytes)", 
+                idx + 1, tts_ms, text, chunk.audio.len());
+        }
+        
+        // 转换为会话的输出格式，设置时间戳和 is_last 标志
+        let mut chunk = self.normalize_tts_chunk(session, chunk);
+        chunk.timestamp_ms = timestamp_ms;
+        chunk.is_last = is_last;
+        
+        // 立即发布（buffer_sentences == 0）
+        if self.tts_buffer_sentences == 0 {
+            Self::publish_tts_event(self, session.id(), &chunk, timestamp_ms).await?;
+            eprintln!("[TTS] 📤 Published segment {:2} immediately (timestamp: {}ms)", idx + 1, timestamp_ms);
+        }
+        
+        Ok(chunk)
+    }
+
+    /// 会话的 TTS 输出格式（会话未指定时使用引擎默认格式，都没有时返回 None 表示保持后端原始格式）
+    fn output_format_for(&self, session: &Session) -> Option<TtsAudioFormat> {
+        session.tts_output_format().or(self.tts_output_format)
+    }
+
+    /// 把 TTS 后端返回的音频转换为会话的输出格式（转换失败时保留原始音频）
+    fn normalize_tts_chunk(&self, session: &Session, chunk: TtsStreamChunk) -> TtsStreamChunk {
+        let Some(format) = self.output_format_for(session) else {
+            return chunk;
+        };
+        match TtsAudioConverter::new(format).and_then(|converter| converter.convert(&chunk)) {
+            Ok(converted) => converted,
+            Err(e) => {
+                eprintln!("[TTS] ⚠️  Failed to convert audio from {:?} to {:?}: {}, using original audio", chunk.format, format, e);
+                chunk
+            }
+        }
+    }
+
+    /// 处理 VAD 语音事件：发布 `VadSpeechStart`/`VadSpeechEnd`，语音开始时丢弃 pre-roll 之前的静音帧
+    async fn handle_speech_event(
+        &self,
+        session: &Session,
+        speech_event: &SpeechEvent,
+        timestamp_ms: u64,
+    ) -> EngineResult<()> {
+        if let SpeechEvent::Start { start_ms, .. } = *speech_event {
+            if let Some(ref buffer) = session.audio_buffer {
+                buffer.begin_segment(start_ms).await;
+            } else {
+                session.asr_buffer.lock().unwrap().retain(|f| f.end_timestamp_ms() > start_ms);
+            }
+        }
+        self.publish_vad_speech_event(session.id(), speech_event, timestamp_ms).await
+    }
+
+    /// 将会话的音频帧、语言、热词和流式识别状态加载到共享的 ASR 实例中
+    /// 
+    /// 调用方必须持有 `asr_gate`，避免其他会话在推理完成前覆盖缓冲区；
+    /// 会话的流式识别状态最先移入 ASR 实例，因此无论加载或推理是否成功，
+    /// 之后都必须调用 `save_session_from_asr` 把状态取回会话
+    fn load_session_into_asr(
+        asr_ext: &dyn AsrStreamingExt,
+        session: &Session,
+        frames: &[crate::types::AudioFrame],
+        language_hint: Option<&str>,
+    ) -> EngineResult<()> {
+        // 将语言代码标准化（例如 "zh-CN" -> "zh"）
+        let normalized_lang = language_hint
+            .map(|lang_hint| language_registry::global().normalize(lang_hint, LanguageBackend::Whisper));
+        if let Err(e) = asr_ext.set_language(normalized_lang) {
+            eprintln!("[ASR] Warning: Failed to set language: {}", e);
+        }
+        if let Err(e) = asr_ext.set_language_candidates(session.asr_language_candidates()) {
+            eprintln!("[ASR] Warning: Failed to set language candidates: {}", e);
+        }
+        if let Err(e) = asr_ext.set_hotwords(session.vocabulary()) {
+            eprintln!("[ASR] Warning: Failed to set hotwords: {}", e);
+        }
+        
+        asr_ext.restore_session_state(session.asr_state.lock().unwrap().take())?;
+        asr_ext.clear_buffer()?;
+        for frame in frames {
+            asr_ext.accumulate_frame(frame.clone())?;
+        }
+        Ok(())
+    }
+
+    /// 推理后把共享 ASR 实例中的流式识别状态（滑动窗口、LocalAgreement 历史）取回会话
+    fn save_session_from_asr(asr_ext: &dyn AsrStreamingExt, session: &Session) {
+        match asr_ext.take_session_state() {
+            Ok(state) => *session.asr_state.lock().unwrap() = state,
+            Err(e) => eprintln!("[ASR] Warning: Failed to save streaming state for session {}: {}", session.id(), e),
+        }
+    }
+
+    /// 是否丢弃最终识别结果（无意义文本或低置信度幻觉），并记录丢弃原因
+    fn should_filter_transcript(transcript: &StableTranscript) -> bool {
+        if is_meaningless_transcript_filter(&transcript.text) {
+            eprintln!("[ASR] ⛔ Filtered meaningless transcript: \"{}\" (skipping translation/TTS)", transcript.text);
+            return true;
+        }
+        if let Some(confidence) = low_transcript_confidence(transcript) {
+            eprintln!("[ASR] ⛔ Filtered low-confidence transcript: \"{}\" (confidence: {:.2}, skipping translation/TTS)",
+                     transcript.text, confidence);
+            return true;
+        }
+        false
+    }
+
+    /// 按会话词表纠正最终识别文本（热词模糊替换）
+    fn correct_vocabulary(&self, session: &Session, transcript: &mut StableTranscript) {
+        let vocabulary = session.vocabulary();
+        if vocabulary.is_empty() {
+            return;
+        }
+        let corrected = self.vocabulary_corrector.correct(&transcript.text, &vocabulary);
+        if corrected != transcript.text {
+            eprintln!("[ASR] 🔤 Vocabulary correction: \"{}\" → \"{}\"", transcript.text, corrected);
+            transcript.text = corrected;
+        }
+    }
+
+    /// 记录 ASR 检测到的语言；双语会话中按检测结果切换翻译方向
+    fn apply_detected_language(session: &Session, transcript: &StableTranscript) {
+        if let Some(target_language) = session.apply_detected_language(&transcript.language) {
+            eprintln!("[ASR] 🌐 Detected language '{}', translating to '{}' (session {})",
+                     transcript.language, target_language, session.id());
+        }
+    }
+
+    // 数字读法等文本规范化由 tts_streaming::NormalizedTts 在合成前完成（各 TTS 后端共用）
     // adjust_vad_threshold_by_feedback, apply_vad_feedback, update_vad_speech_rate, get_vad_speech_rate 已移至 vad_utils.rs 模块
     // publish_tts_event 已移至 events.rs 模块
 }
+
+/// 合成完成、等待发布的短句
+struct SynthesizedSegment {
+    idx: usize,
+    text: String,
+    pause_type: PauseType,
+    chunk: TtsStreamChunk,
+    tts_ms: u64,
+}
@@ -1,6 +1,7 @@
 //! 事件发布相关功能
 //! 
 //! 包含 ASR、NMT、TTS、Emotion 等事件发布方法
+//! 所有事件的 payload 都包含 `session_id`，订阅方据此过滤属于自己会话的事件
 
 use serde_json::json;
 
@@ -9,23 +10,56 @@ use crate::event_bus::{CoreEvent, EventTopic};
 use crate::types::{PartialTranscript, StableTranscript};
 use crate::tts_streaming::TtsStreamChunk;
 use crate::emotion_adapter::EmotionResponse;
-use crate::nmt_incremental::TranslationResponse;
+use crate::nmt_incremental::{TranslationDelta, TranslationResponse};
+use crate::vad::SpeechEvent;
 
 use super::core::CoreEngine;
 
 impl CoreEngine {
+    /// 发布 VAD 语音开始/结束事件（客户端可据此实现打断 barge-in）
+    pub(crate) async fn publish_vad_speech_event(
+        &self,
+        session_id: &str,
+        speech_event: &SpeechEvent,
+        timestamp_ms: u64,
+    ) -> EngineResult<()> {
+        let (topic, payload) = match speech_event {
+            SpeechEvent::Start { onset_ms, start_ms } => ("VadSpeechStart", json!({
+                "session_id": session_id,
+                "onset_ms": onset_ms,
+                "start_ms": start_ms,
+            })),
+            SpeechEvent::End { offset_ms, end_ms } => ("VadSpeechEnd", json!({
+                "session_id": session_id,
+                "offset_ms": offset_ms,
+                "end_ms": end_ms,
+            })),
+        };
+        let event = CoreEvent {
+            topic: EventTopic(topic.to_string()),
+            payload,
+            timestamp_ms,
+        };
+        self.event_bus.publish(event).await?;
+        Ok(())
+    }
+
     /// 发布 ASR 部分结果事件
     pub(crate) async fn publish_asr_partial_event(
         &self,
+        session_id: &str,
         partial: &PartialTranscript,
         timestamp_ms: u64,
     ) -> EngineResult<()> {
         let event = CoreEvent {
             topic: EventTopic("AsrPartial".to_string()),
             payload: json!({
+                "session_id": session_id,
                 "text": partial.text,
                 "confidence": partial.confidence,
                 "is_final": partial.is_final,
+                "stable_prefix": partial.stable_prefix,
+                "volatile_tail": partial.volatile_tail(),
             }),
             timestamp_ms,
         };
@@ -36,15 +70,19 @@ impl CoreEngine {
     /// 发布 ASR 最终结果事件
     pub(crate) async fn publish_asr_final_event(
         &self,
+        session_id: &str,
         transcript: &StableTranscript,
         timestamp_ms: u64,
     ) -> EngineResult<()> {
         let event = CoreEvent {
             topic: EventTopic("AsrFinal".to_string()),
             payload: json!({
+                "session_id": session_id,
                 "text": transcript.text,
                 "speaker_id": transcript.speaker_id,
                 "language": transcript.language,
+                "segments": transcript.segments,
+                "language_probabilities": transcript.language_probabilities,
             }),
             timestamp_ms,
         };
@@ -55,6 +93,7 @@ impl CoreEngine {
     /// 发布 TTS 事件
     pub(crate) async fn publish_tts_event(
         &self,
+        session_id: &str,
         tts_chunk: &TtsStreamChunk,
         timestamp_ms: u64,
     ) -> EngineResult<()> {
@@ -65,8 +104,10 @@ impl CoreEngine {
         let event = CoreEvent {
             topic: EventTopic("Tts".to_string()),
             payload: json!({
+                "session_id": session_id,
                 "audio": audio_base64,  // 包含完整的音频数据（base64 编码）
                 "audio_length": tts_chunk.audio.len(),
+                "format": tts_chunk.format,  // 音频格式（封装方式、采样率、声道数、位深）
                 "timestamp_ms": tts_chunk.timestamp_ms,
                 "is_last": tts_chunk.is_last,
             }),
@@ -79,12 +120,14 @@ impl CoreEngine {
     /// 发布 Emotion 事件
     pub(crate) async fn publish_emotion_event(
         &self,
+        session_id: &str,
         emotion: &EmotionResponse,
         timestamp_ms: u64,
     ) -> EngineResult<()> {
         let event = CoreEvent {
             topic: EventTopic("Emotion".to_string()),
             payload: json!({
+                "session_id": session_id,
                 "primary": emotion.primary,
                 "intensity": emotion.intensity,
                 "confidence": emotion.confidence,
@@ -98,19 +141,45 @@ impl CoreEngine {
     /// 发布翻译事件
     pub(crate) async fn publish_translation_event(
         &self,
+        session_id: &str,
         translation: &TranslationResponse,
         timestamp_ms: u64,
     ) -> EngineResult<()> {
         let event = CoreEvent {
             topic: EventTopic("Translation".to_string()),
             payload: json!({
+                "session_id": session_id,
                 "translated_text": translation.translated_text,
                 "is_stable": translation.is_stable,
+                "committed_text": translation.committed_text,
             }),
             timestamp_ms,
         };
         self.event_bus.publish(event).await?;
         Ok(())
     }
-}
 
+    /// 发布翻译增量事件（流式翻译每输出一个 token 发布一次）
+    pub(crate) async fn publish_nmt_partial_event(
+        &self,
+        session_id: &str,
+        delta: &TranslationDelta,
+        translated_text: &str,
+        timestamp_ms: u64,
+    ) -> EngineResult<()> {
+        let event = CoreEvent {
+            topic: EventTopic("NmtPartial".to_string()),
+            payload: json!({
+                "session_id": session_id,
+                "text": delta.text,
+                "token_id": delta.token_id,
+                "probability": delta.probability,
+                "is_last": delta.is_last,
+                "translated_text": translated_text,
+            }),
+            timestamp_ms,
+        };
+        self.event_bus.publish(event).await?;
+        Ok(())
+    }
+}
@@ -6,6 +6,7 @@ pub mod lifecycle;
 pub mod text_utils;
 pub mod vad_utils;
 pub mod events;
+pub mod session;
 
 #[cfg(test)]
 mod vad_feedback_test;
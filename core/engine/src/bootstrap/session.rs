@@ -0,0 +1,111 @@
+//! 会话创建
+//!
+//! 为每个连接创建独立的 `Session`，共享模型，隔离状态；
+//! 带用户 ID 的会话可以恢复和保存该用户的 VAD 自适应状态
+
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::audio_buffer::AudioBufferManager;
+use crate::error::EngineResult;
+use crate::session::{parse_source_language, Session, SessionLanguages};
+use crate::speaker_voice_mapper::SpeakerVoiceMapper;
+
+use super::core::CoreEngine;
+
+impl CoreEngine {
+    /// 创建新的会话
+    ///
+    /// 未指定的语言从 `ConfigManager` 的当前配置中获取（作为默认值）。
+    ///
+    /// # Arguments
+    /// * `source_language` - 源语言（None 表示使用配置中的源语言，"auto" 表示由 ASR 自动检测）
+    /// * `target_language` - 目标语言（None 表示使用配置中的目标语言）
+    pub async fn create_session(
+        &self,
+        source_language: Option<String>,
+        target_language: Option<String>,
+    ) -> Arc<Session> {
+        self.create_session_for_user(source_language, target_language, None).await
+    }
+
+    /// 为指定用户创建会话
+    ///
+    /// 启用了 VAD 状态持久化（`CoreEngineBuilder::with_vad_state_persistence`）时，
+    /// 会话的 VAD 从该用户上次保存的自适应状态开始，而不是从默认阈值重新学习。
+    ///
+    /// # Arguments
+    /// * `user_id` - 用户 ID（None 或空字符串表示匿名会话，不恢复也不保存状态）
+    pub async fn create_session_for_user(
+        &self,
+        source_language: Option<String>,
+        target_language: Option<String>,
+        user_id: Option<String>,
+    ) -> Arc<Session> {
+        let user_id = user_id.filter(|id| !id.is_empty());
+        let config = self.config.current().await.ok();
+        let languages = SessionLanguages {
+            source_language: parse_source_language(
+                source_language.or_else(|| config.as_ref().map(|c| c.source_language.clone())),
+            ),
+            target_language: target_language
+                .or_else(|| config.as_ref().map(|c| c.target_language.clone()))
+                .unwrap_or_else(|| "zh".to_string()),
+        };
+
+        let vad = self.vad.fork_for_session().unwrap_or_else(|| Arc::clone(&self.vad));
+        let audio_buffer = self.audio_buffer.as_ref().map(|template| {
+            Arc::new(AudioBufferManager::with_config(
+                template.max_buffer_duration_ms(),
+                template.min_segment_duration_ms(),
+            ))
+        });
+        let speaker_identifier = self.speaker_identifier.as_ref().map(|identifier| {
+            identifier.fork_for_session().unwrap_or_else(|| Arc::clone(identifier))
+        });
+        let speaker_voice_mapper = self.speaker_voice_mapper.as_ref().map(|mapper| {
+            Arc::new(SpeakerVoiceMapper::new(mapper.available_voices().to_vec()))
+        });
+
+        let session_id = Uuid::new_v4().to_string();
+        eprintln!("[SESSION] Created session {} (user={:?}, src={:?}, tgt={})",
+                 session_id, user_id, languages.source_language, languages.target_language);
+
+        if let (Some(store), Some(user_id), Some(silero_vad)) =
+            (self.vad_state_store.as_ref(), user_id.as_deref(), vad.as_silero())
+        {
+            match store.load(user_id) {
+                Ok(Some(state)) => silero_vad.restore_adaptive_state(state),
+                Ok(None) => eprintln!("[SESSION] No saved VAD state for user {}, starting from defaults", user_id),
+                Err(e) => eprintln!("[SESSION] ⚠️ {}, starting from defaults", e),
+            }
+        }
+
+        Arc::new(Session::new(
+            session_id,
+            user_id,
+            languages,
+            vad,
+            audio_buffer,
+            speaker_identifier,
+            speaker_voice_mapper,
+        ))
+    }
+
+    /// 保存会话的 VAD 自适应状态（会话结束时调用）
+    ///
+    /// 匿名会话、未启用持久化或 VAD 不是 SileroVad 时不做任何事
+    pub fn save_session_vad_state(&self, session: &Session) -> EngineResult<()> {
+        let (Some(store), Some(user_id), Some(silero_vad)) =
+            (self.vad_state_store.as_ref(), session.user_id(), session.vad.as_silero())
+        else {
+            return Ok(());
+        };
+        let state = silero_vad.adaptive_state();
+        store.save(user_id, &state)?;
+        eprintln!("[SESSION] 💾 Saved VAD state for user {} (session {}, samples={})",
+                 user_id, session.id(), state.sample_count());
+        Ok(())
+    }
+}
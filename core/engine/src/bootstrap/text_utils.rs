@@ -198,107 +198,6 @@ impl super::core::CoreEngine {
         sentences
     }
 
-    /// 将数字转换为中文（用于TTS输出）
-    /// 
-    /// 例如：123 -> "一百二十三"
-    pub(crate) fn convert_decimals_to_chinese(text: &str) -> String {
-        let mut result = String::new();
-        let mut chars = text.chars().peekable();
-        
-        while let Some(ch) = chars.next() {
-            if ch.is_ascii_digit() {
-                // 开始读取数字
-                let mut num_str = String::new();
-                num_str.push(ch);
-                
-                // 检查下一个字符是否是小数点
-                let mut has_decimal = false;
-                if let Some(&'.') = chars.peek() {
-                    chars.next(); // 跳过小数点
-                    has_decimal = true;
-                    num_str.push('.');
-                    
-                    // 读取小数点后的数字
-                    while let Some(&next_ch) = chars.peek() {
-                        if next_ch.is_ascii_digit() {
-                            num_str.push(chars.next().unwrap());
-                        } else {
-                            break;
-                        }
-                    }
-                } else {
-                    // 继续读取整数部分
-                    while let Some(&next_ch) = chars.peek() {
-                        if next_ch.is_ascii_digit() {
-                            num_str.push(chars.next().unwrap());
-                        } else {
-                            break;
-                        }
-                    }
-                }
-                
-                // 如果是小数，转换为中文读法
-                if has_decimal && num_str.contains('.') {
-                    let parts: Vec<&str> = num_str.split('.').collect();
-                    if parts.len() == 2 {
-                        // 转换整数部分
-                        let int_part = parts[0];
-                        let mut chinese_num = String::new();
-                        for digit_char in int_part.chars() {
-                            if let Some(digit) = digit_char.to_digit(10) {
-                                chinese_num.push_str(match digit {
-                                    0 => "零",
-                                    1 => "一",
-                                    2 => "二",
-                                    3 => "三",
-                                    4 => "四",
-                                    5 => "五",
-                                    6 => "六",
-                                    7 => "七",
-                                    8 => "八",
-                                    9 => "九",
-                                    _ => "",
-                                });
-                            }
-                        }
-                        
-                        // 添加"点"
-                        chinese_num.push_str("点");
-                        
-                        // 转换小数部分
-                        for digit_char in parts[1].chars() {
-                            if let Some(digit) = digit_char.to_digit(10) {
-                                chinese_num.push_str(match digit {
-                                    0 => "零",
-                                    1 => "一",
-                                    2 => "二",
-                                    3 => "三",
-                                    4 => "四",
-                                    5 => "五",
-                                    6 => "六",
-                                    7 => "七",
-                                    8 => "八",
-                                    9 => "九",
-                                    _ => "",
-                                });
-                            }
-                        }
-                        
-                        result.push_str(&chinese_num);
-                        continue;
-                    }
-                }
-                
-                // 如果不是小数，保持原样
-                result.push_str(&num_str);
-            } else {
-                result.push(ch);
-            }
-        }
-        
-        result
-    }
-
     /// 根据估计的性别获取默认音色名称（用于 TTS voice 参数）
     /// 
     /// # Arguments
@@ -17,6 +17,8 @@ mod tests {
             final_transcript: Some(StableTranscript {
                 text: text.to_string(),
                 language: "zh".to_string(),
+                segments: Vec::new(),
+                language_probabilities: Vec::new(),
                 speaker_id: Some("speaker1".to_string()),
             }),
         }
@@ -35,6 +37,8 @@ mod tests {
             source_text: None,
             source_language: None,  // 测试中不需要源语言信息
             quality_metrics,
+            committed_text: None,
+            provider: None,
         }
     }
     
@@ -2,12 +2,11 @@
 //! 
 //! 包含 VAD 阈值调整、语速更新等功能
 
-use std::sync::Arc;
-
 use crate::asr_streaming::AsrResult;
 use crate::asr_filters::is_meaningless_transcript as is_meaningless_transcript_filter;
 use crate::error::EngineError;
-use crate::nmt_incremental::TranslationResponse;
+use crate::nmt_incremental::{TranslationQualityMetrics, TranslationResponse};
+use crate::session::Session;
 use crate::types::StableTranscript;
 use crate::vad::VadFeedbackType;
 
@@ -17,6 +16,7 @@ impl CoreEngine {
     /// 基于ASR/NMT反馈调整VAD阈值（修订版）
     /// 
     /// # Arguments
+    /// * `session` - 当前会话（反馈只作用于该会话的 VAD）
     /// * `asr_result` - ASR识别结果
     /// * `translation_result` - NMT翻译结果（可选，StableTranscript 格式）
     /// * `translation_response` - NMT翻译响应（可选，包含质量指标）
@@ -29,6 +29,7 @@ impl CoreEngine {
     /// 3. **去重逻辑**：TooLong 优先，BadBoundary 只执行一次
     pub(crate) fn adjust_vad_threshold_by_feedback(
         &self,
+        session: &Session,
         asr_result: &AsrResult,
         translation_result: Option<&StableTranscript>,
         translation_response: Option<&TranslationResponse>,
@@ -72,7 +73,7 @@ impl CoreEngine {
                     if let Some(ref metrics) = translation_resp.quality_metrics {
                         // 困惑度过高
                         if let Some(perplexity) = metrics.perplexity {
-                            if perplexity > 100.0 {
+                            if perplexity > TranslationQualityMetrics::MAX_PERPLEXITY {
                                 eprintln!("[VAD Feedback] ⚠️  High perplexity ({:.2}) detected", perplexity);
                                 has_quality_issues = true;
                             }
@@ -80,7 +81,7 @@ impl CoreEngine {
                         
                         // 平均概率过低
                         if let Some(avg_prob) = metrics.avg_probability {
-                            if avg_prob < 0.05 {
+                            if avg_prob < TranslationQualityMetrics::MIN_AVG_PROBABILITY {
                                 eprintln!("[VAD Feedback] ⚠️  Low average probability ({:.4}) detected", avg_prob);
                                 has_quality_issues = true;
                             }
@@ -88,7 +89,7 @@ impl CoreEngine {
                         
                         // 最小概率过低
                         if let Some(min_prob) = metrics.min_probability {
-                            if min_prob < 0.001 {
+                            if min_prob < TranslationQualityMetrics::MIN_TOKEN_PROBABILITY {
                                 eprintln!("[VAD Feedback] ⚠️  Very low min probability ({:.6}) detected", min_prob);
                                 has_quality_issues = true;
                             }
@@ -125,11 +126,11 @@ impl CoreEngine {
             if is_too_long {
                 // BoundaryTooLong → delta -= 150ms
                 eprintln!("[VAD Feedback] ✅ Applying BoundaryTooLong feedback: delta -= 150ms");
-                self.apply_vad_feedback(VadFeedbackType::BoundaryTooLong, 150);
+                self.apply_vad_feedback(session, VadFeedbackType::BoundaryTooLong, 150);
             } else if is_boundary_too_short {
                 // BoundaryTooShort → delta += 150ms（只在明确是边界问题时才调整）
                 eprintln!("[VAD Feedback] 🔧 Applying BoundaryTooShort feedback (short text + quality issues, likely boundary too short): delta += 150ms");
-                self.apply_vad_feedback(VadFeedbackType::BoundaryTooShort, 150);
+                self.apply_vad_feedback(session, VadFeedbackType::BoundaryTooShort, 150);
             } else {
                 eprintln!("[VAD Feedback] ℹ️  No feedback adjustment needed (text_len={}, filtered={})", text_len, is_filtered);
             }
@@ -137,53 +138,31 @@ impl CoreEngine {
     }
     
     /// 应用 VAD 反馈调整
-    pub(crate) fn apply_vad_feedback(&self, feedback_type: VadFeedbackType, adjustment_ms: i64) {
-        // 尝试将VAD转换为SileroVad（使用与update_vad_speech_rate相同的方法）
-        let vad_ptr = Arc::as_ptr(&self.vad);
-        let silero_vad_ptr = vad_ptr as *const crate::vad::SileroVad;
-        
-        unsafe {
-            if let Some(silero_vad) = silero_vad_ptr.as_ref() {
-                silero_vad.adjust_delta_by_feedback(feedback_type, adjustment_ms);
-            } else {
-                eprintln!("[VAD Feedback] ⚠️  VAD is not SileroVad, cannot apply feedback adjustment");
-            }
+    pub(crate) fn apply_vad_feedback(&self, session: &Session, feedback_type: VadFeedbackType, adjustment_ms: i64) {
+        if let Some(silero_vad) = session.vad.as_silero() {
+            silero_vad.adjust_delta_by_feedback(feedback_type, adjustment_ms);
+        } else {
+            eprintln!("[VAD Feedback] ⚠️  VAD is not SileroVad, cannot apply feedback adjustment");
         }
     }
     
-    /// 更新VAD中的全局语速（用于自适应调整）
+    /// 更新会话 VAD 中的语速（用于自适应调整）
     /// 
     /// 不区分说话者，每个短句都根据上一个短句的语速调整。
-    pub(crate) fn update_vad_speech_rate(&self, text: &str, audio_duration_ms: u64) {
+    pub(crate) fn update_vad_speech_rate(&self, session: &Session, text: &str, audio_duration_ms: u64) {
         eprintln!("[CoreEngine] 📝 update_vad_speech_rate called: text='{}' ({} chars), duration={}ms", 
                  text.chars().take(30).collect::<String>(), text.chars().count(), audio_duration_ms);
         
-        // 尝试将 VAD 转换为 SileroVad
-        let vad_ptr = Arc::as_ptr(&self.vad);
-        let silero_vad_ptr = vad_ptr as *const crate::vad::SileroVad;
-        
-        unsafe {
-            if let Some(silero_vad) = silero_vad_ptr.as_ref() {
-                silero_vad.update_speech_rate(text, audio_duration_ms);
-            } else {
-                eprintln!("[CoreEngine] ⚠️  update_vad_speech_rate: VAD is not SileroVad, cannot update speech rate");
-            }
+        if let Some(silero_vad) = session.vad.as_silero() {
+            silero_vad.update_speech_rate(text, audio_duration_ms);
+        } else {
+            eprintln!("[CoreEngine] ⚠️  update_vad_speech_rate: VAD is not SileroVad, cannot update speech rate");
         }
     }
     
-    /// 获取全局语速（用于传递给TTS）
-    pub(crate) fn get_vad_speech_rate(&self) -> Option<f32> {
-        // 尝试将 VAD 转换为 SileroVad
-        let vad_ptr = Arc::as_ptr(&self.vad);
-        let silero_vad_ptr = vad_ptr as *const crate::vad::SileroVad;
-        
-        unsafe {
-            if let Some(silero_vad) = silero_vad_ptr.as_ref() {
-                silero_vad.get_speech_rate()
-            } else {
-                None
-            }
-        }
+    /// 获取会话的语速（用于传递给TTS）
+    pub(crate) fn get_vad_speech_rate(&self, session: &Session) -> Option<f32> {
+        session.vad.as_silero().and_then(|silero_vad| silero_vad.get_speech_rate())
     }
 }
 
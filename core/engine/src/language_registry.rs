@@ -0,0 +1,391 @@
+//! 语言注册表
+//!
+//! 统一管理引擎支持的语言，所有后端（Marian、M2M100、Whisper 语言提示、Piper 语音 locale）
+//! 都通过注册表解析语言标签，而不是各自硬编码语言列表：
+//! - BCP-47 标签解析（如 "zh-Hant-TW"、"en_US"），大小写和 `_` / `-` 分隔符均可
+//! - 别名（如 "eng"、"english"、"中文"）
+//! - 文字变体（如 zh-Hans / zh-Hant），未显式指定时按地区推断
+//!
+//! 语言表从配置文件 `config/languages.json` 加载，新增语言只需修改配置文件。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::error::{EngineError, EngineResult};
+
+/// 内置语言表（找不到配置文件时使用）
+const BUILTIN_LANGUAGES: &str = include_str!("../config/languages.json");
+
+/// 使用语言代码的后端
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LanguageBackend {
+    /// Marian NMT（模型目录名 marian-{src}-{tgt}）
+    Marian,
+    /// M2M100 NMT（语言 token __{code}__）
+    M2M100,
+    /// Whisper ASR 语言提示
+    Whisper,
+    /// Piper TTS 语音 locale（如 zh_CN）
+    Piper,
+}
+
+impl LanguageBackend {
+    /// 后端名称，同时用作模型目录名前缀（如 "marian"、"m2m100"）
+    pub fn name(&self) -> &'static str {
+        match self {
+            LanguageBackend::Marian => "marian",
+            LanguageBackend::M2M100 => "m2m100",
+            LanguageBackend::Whisper => "whisper",
+            LanguageBackend::Piper => "piper",
+        }
+    }
+}
+
+/// 语言表中的一种语言
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageEntry {
+    /// 规范语言代码（ISO 639-1，如 "zh"）
+    pub code: String,
+    #[serde(default)]
+    pub name: String,
+    /// 别名（ISO 639-2/3 代码、语言名称等，不区分大小写）
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// 未指定文字和地区时使用的文字（如 "Hans"）
+    #[serde(default)]
+    pub default_script: Option<String>,
+    /// 文字变体 -> 使用该文字的地区（如 "Hant" -> ["TW", "HK"]），第一个地区为默认地区
+    #[serde(default)]
+    pub scripts: HashMap<String, Vec<String>>,
+    /// Marian 模型目录中的语言代码（缺省为 code）
+    #[serde(default)]
+    pub marian: Option<String>,
+    /// M2M100 语言 token 中的语言代码（缺省为 code）
+    #[serde(default)]
+    pub m2m100: Option<String>,
+    /// Whisper 语言提示（缺省为 code）
+    #[serde(default)]
+    pub whisper: Option<String>,
+    /// Piper 语音 locale（如 "zh_CN"）
+    #[serde(default)]
+    pub piper_locale: Option<String>,
+    /// Piper 默认语音（如 "zh_CN-huayan-medium"）
+    #[serde(default)]
+    pub piper_voice: Option<String>,
+}
+
+impl LanguageEntry {
+    /// 获取后端使用的语言代码
+    pub fn backend_code(&self, backend: LanguageBackend) -> &str {
+        let code = match backend {
+            LanguageBackend::Marian => self.marian.as_deref(),
+            LanguageBackend::M2M100 => self.m2m100.as_deref(),
+            LanguageBackend::Whisper => self.whisper.as_deref(),
+            LanguageBackend::Piper => self.piper_locale.as_deref(),
+        };
+        code.unwrap_or(&self.code)
+    }
+}
+
+/// 语言注册表配置文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageRegistryConfig {
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    pub languages: Vec<LanguageEntry>,
+}
+
+/// 解析后的 BCP-47 语言标签（只保留 language / script / region 子标签）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageTag {
+    /// 主语言子标签（小写，如 "zh"）
+    pub language: String,
+    /// 文字子标签（首字母大写，如 "Hant"）
+    pub script: Option<String>,
+    /// 地区子标签（大写，如 "TW"）
+    pub region: Option<String>,
+}
+
+impl LanguageTag {
+    /// 解析 BCP-47 标签（如 "zh-Hant-TW"、"en_us"）
+    ///
+    /// 变体、扩展等其余子标签会被忽略；主语言子标签不是 2~8 位字母时返回 `None`。
+    pub fn parse(tag: &str) -> Option<Self> {
+        let mut subtags = tag.trim().split(|c| c == '-' || c == '_').filter(|s| !s.is_empty());
+        let language = subtags.next()?;
+        if !(2..=8).contains(&language.len()) || !language.chars().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+
+        let mut script = None;
+        let mut region = None;
+        for subtag in subtags {
+            let is_alpha = subtag.chars().all(|c| c.is_ascii_alphabetic());
+            let is_digit = subtag.chars().all(|c| c.is_ascii_digit());
+            if script.is_none() && region.is_none() && subtag.len() == 4 && is_alpha {
+                let mut chars = subtag.chars();
+                let first = chars.next().map(|c| c.to_ascii_uppercase()).unwrap_or_default();
+                script = Some(std::iter::once(first).chain(chars.map(|c| c.to_ascii_lowercase())).collect());
+            } else if region.is_none() && ((subtag.len() == 2 && is_alpha) || (subtag.len() == 3 && is_digit)) {
+                region = Some(subtag.to_ascii_uppercase());
+            } else {
+                break;
+            }
+        }
+
+        Some(Self {
+            language: language.to_ascii_lowercase(),
+            script,
+            region,
+        })
+    }
+}
+
+/// 语言注册表
+#[derive(Debug, Clone)]
+pub struct LanguageRegistry {
+    entries: Vec<LanguageEntry>,
+    /// 小写的代码 / 别名 / 后端代码 -> entries 下标
+    index: HashMap<String, usize>,
+}
+
+impl LanguageRegistry {
+    /// 从配置构建注册表（后出现的条目覆盖先出现的同名代码或别名）
+    pub fn from_config(config: LanguageRegistryConfig) -> Self {
+        let mut index = HashMap::new();
+        for (idx, entry) in config.languages.iter().enumerate() {
+            let backend_codes = [&entry.marian, &entry.m2m100, &entry.whisper]
+                .into_iter()
+                .flatten();
+            for key in std::iter::once(&entry.code).chain(&entry.aliases).chain(backend_codes) {
+                index.insert(key.to_lowercase(), idx);
+            }
+        }
+        Self {
+            entries: config.languages,
+            index,
+        }
+    }
+
+    /// 从 JSON 字符串加载
+    pub fn from_json_str(content: &str) -> EngineResult<Self> {
+        let config: LanguageRegistryConfig = serde_json::from_str(content)
+            .map_err(|e| EngineError::new(format!("Failed to parse language registry: {}", e)))?;
+        Ok(Self::from_config(config))
+    }
+
+    /// 从文件加载
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> EngineResult<Self> {
+        let content = fs::read_to_string(path.as_ref())
+            .map_err(|e| EngineError::new(format!("Failed to read language registry: {}", e)))?;
+        Self::from_json_str(&content)
+    }
+
+    /// 从默认路径加载，找不到配置文件时使用内置语言表
+    pub fn load_default() -> EngineResult<Self> {
+        let possible_paths = vec![
+            "config/languages.json",
+            "core/engine/config/languages.json",
+            "../config/languages.json",
+            "../../config/languages.json",
+        ];
+
+        for path in &possible_paths {
+            if Path::new(path).exists() {
+                eprintln!("[Language Registry] Loading languages from: {}", path);
+                return Self::load_from_file(path);
+            }
+        }
+
+        eprintln!("[Language Registry] ⚠️  Config file not found, using builtin languages");
+        Ok(Self::builtin())
+    }
+
+    /// 内置语言表
+    pub fn builtin() -> Self {
+        Self::from_json_str(BUILTIN_LANGUAGES).expect("builtin language registry should be valid")
+    }
+
+    /// 所有已注册语言
+    pub fn languages(&self) -> &[LanguageEntry] {
+        &self.entries
+    }
+
+    /// 解析语言标签（别名、BCP-47 标签均可），未注册的语言返回 `None`
+    pub fn resolve(&self, tag: &str) -> Option<&LanguageEntry> {
+        let key = tag.trim().to_lowercase();
+        if let Some(&idx) = self.index.get(&key) {
+            return Some(&self.entries[idx]);
+        }
+        let parsed = LanguageTag::parse(&key)?;
+        self.index.get(&parsed.language).map(|&idx| &self.entries[idx])
+    }
+
+    /// 是否支持该语言
+    pub fn is_supported(&self, tag: &str) -> bool {
+        self.resolve(tag).is_some()
+    }
+
+    /// 获取规范语言代码（如 "zh-Hant-TW" -> "zh"）
+    pub fn canonical_code(&self, tag: &str) -> Option<&str> {
+        self.resolve(tag).map(|entry| entry.code.as_str())
+    }
+
+    /// 获取后端使用的语言代码
+    ///
+    /// Piper 的 locale 优先使用标签中的地区（或由文字变体推断的地区），如 "zh-Hant" -> "zh_TW"。
+    pub fn backend_code(&self, tag: &str, backend: LanguageBackend) -> Option<String> {
+        let entry = self.resolve(tag)?;
+        if backend == LanguageBackend::Piper {
+            if let Some(region) = self.region(tag) {
+                let language = entry.piper_locale.as_deref().and_then(|l| l.split('_').next()).unwrap_or(&entry.code);
+                return Some(format!("{}_{}", language, region));
+            }
+        }
+        Some(entry.backend_code(backend).to_string())
+    }
+
+    /// 标准化为后端使用的语言代码，未注册的语言原样返回
+    pub fn normalize(&self, tag: &str, backend: LanguageBackend) -> String {
+        self.backend_code(tag, backend).unwrap_or_else(|| tag.to_string())
+    }
+
+    /// 获取文字变体（显式指定 > 按地区推断 > 默认文字）
+    pub fn script(&self, tag: &str) -> Option<String> {
+        let entry = self.resolve(tag)?;
+        let parsed = LanguageTag::parse(tag);
+        if let Some(script) = parsed.as_ref().and_then(|p| p.script.clone()) {
+            return Some(script);
+        }
+        if let Some(region) = parsed.as_ref().and_then(|p| p.region.as_deref()) {
+            let by_region = entry
+                .scripts
+                .iter()
+                .find(|(_, regions)| regions.iter().any(|r| r.eq_ignore_ascii_case(region)))
+                .map(|(script, _)| script.clone());
+            if by_region.is_some() {
+                return by_region;
+            }
+        }
+        entry.default_script.clone()
+    }
+
+    /// 获取地区（显式指定 > 文字变体的默认地区）
+    fn region(&self, tag: &str) -> Option<String> {
+        let entry = self.resolve(tag)?;
+        let parsed = LanguageTag::parse(tag)?;
+        if parsed.region.is_some() {
+            return parsed.region;
+        }
+        let script = parsed.script?;
+        entry.scripts.get(&script).and_then(|regions| regions.first()).cloned()
+    }
+
+    /// 获取 Piper 默认语音
+    pub fn piper_voice(&self, tag: &str) -> Option<&str> {
+        self.resolve(tag).and_then(|entry| entry.piper_voice.as_deref())
+    }
+}
+
+/// 全局注册表实例
+static GLOBAL_REGISTRY: RwLock<Option<Arc<LanguageRegistry>>> = RwLock::new(None);
+
+/// 初始化（或替换）全局注册表
+pub fn init_registry(registry: LanguageRegistry) {
+    let mut global = GLOBAL_REGISTRY.write().unwrap_or_else(|e| e.into_inner());
+    *global = Some(Arc::new(registry));
+}
+
+/// 获取全局注册表（首次使用时从默认路径加载）
+pub fn global() -> Arc<LanguageRegistry> {
+    if let Some(registry) = GLOBAL_REGISTRY.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return Arc::clone(registry);
+    }
+
+    let mut global = GLOBAL_REGISTRY.write().unwrap_or_else(|e| e.into_inner());
+    let registry = global.get_or_insert_with(|| {
+        let registry = LanguageRegistry::load_default().unwrap_or_else(|e| {
+            eprintln!("[Language Registry] ⚠️  Failed to load languages: {}, using builtin", e);
+            LanguageRegistry::builtin()
+        });
+        Arc::new(registry)
+    });
+    Arc::clone(registry)
+}
+
+/// 驻留语言代码字符串（语言数量有限，驻留后可以作为 `&'static str` 使用）
+pub(crate) fn intern(code: &str) -> &'static str {
+    static INTERNED: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+    let mut interned = INTERNED.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(existing) = interned.iter().find(|s| **s == code) {
+        return existing;
+    }
+    let leaked: &'static str = Box::leak(code.to_string().into_boxed_str());
+    interned.push(leaked);
+    leaked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bcp47_tag() {
+        let tag = LanguageTag::parse("zh-hant-tw").unwrap();
+        assert_eq!(tag.language, "zh");
+        assert_eq!(tag.script.as_deref(), Some("Hant"));
+        assert_eq!(tag.region.as_deref(), Some("TW"));
+
+        let tag = LanguageTag::parse("en_US").unwrap();
+        assert_eq!(tag.script, None);
+        assert_eq!(tag.region.as_deref(), Some("US"));
+
+        assert_eq!(LanguageTag::parse("es-419").unwrap().region.as_deref(), Some("419"));
+        assert!(LanguageTag::parse("").is_none());
+        assert!(LanguageTag::parse("1x").is_none());
+    }
+
+    #[test]
+    fn test_resolve_aliases_and_variants() {
+        let registry = LanguageRegistry::builtin();
+        for tag in ["zh", "ZH-CN", "zh-Hans", "zh_Hant_HK", "cmn", "中文", "Chinese"] {
+            assert_eq!(registry.canonical_code(tag), Some("zh"), "tag: {}", tag);
+        }
+        assert_eq!(registry.canonical_code("english"), Some("en"));
+        assert_eq!(registry.canonical_code("iw"), Some("he"));
+        assert_eq!(registry.canonical_code("xx-YY"), None);
+    }
+
+    #[test]
+    fn test_script_variants() {
+        let registry = LanguageRegistry::builtin();
+        assert_eq!(registry.script("zh").as_deref(), Some("Hans"));
+        assert_eq!(registry.script("zh-TW").as_deref(), Some("Hant"));
+        assert_eq!(registry.script("zh-Hant").as_deref(), Some("Hant"));
+        assert_eq!(registry.script("en"), None);
+    }
+
+    #[test]
+    fn test_backend_codes() {
+        let registry = LanguageRegistry::builtin();
+        assert_eq!(registry.backend_code("zh-Hant-TW", LanguageBackend::Whisper).as_deref(), Some("zh"));
+        assert_eq!(registry.backend_code("zh-Hans", LanguageBackend::M2M100).as_deref(), Some("zh"));
+        assert_eq!(registry.backend_code("zh", LanguageBackend::Piper).as_deref(), Some("zh_CN"));
+        assert_eq!(registry.backend_code("zh-Hant", LanguageBackend::Piper).as_deref(), Some("zh_TW"));
+        assert_eq!(registry.backend_code("en-GB", LanguageBackend::Piper).as_deref(), Some("en_GB"));
+        assert_eq!(registry.piper_voice("en-US"), Some("en_US-lessac-medium"));
+        assert_eq!(registry.normalize("xx", LanguageBackend::Whisper), "xx");
+    }
+
+    #[test]
+    fn test_intern() {
+        let a = intern("sw");
+        let b = intern(&String::from("sw"));
+        assert!(std::ptr::eq(a, b));
+    }
+}
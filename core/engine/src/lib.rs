@@ -16,6 +16,7 @@ pub mod asr_streaming;
 pub mod onnx_utils;
 pub mod health_check;
 pub mod post_processing;
+pub mod vocabulary_correction;
 pub mod performance_logger;
 pub mod text_segmentation;
 pub mod translation_quality;
@@ -25,6 +26,8 @@ pub mod speaker_identifier;
 pub mod speaker_voice_mapper;
 pub mod asr_filters;
 pub mod asr_http_client;
+pub mod session;
+pub mod language_registry;
 
 pub use bootstrap::{CoreEngine, CoreEngineBuilder, ProcessResult};
 pub use cache_manager::CacheManager;
@@ -33,17 +36,21 @@ pub use emotion_adapter::{EmotionAdapter, EmotionRequest, EmotionResponse, XlmRE
 pub use error::{EngineError, EngineResult};
 pub use event_bus::{CoreEvent, EventBus, EventSubscription, EventTopic};
 pub use nmt_incremental::{
-    LanguageCode, LanguagePair, MarianNmtOnnx, MarianTokenizer, NmtIncremental, TranslationRequest, TranslationResponse,
+    LanguageCode, LanguagePair, MarianModelRegistry, MarianNmtOnnx, MarianTokenizer, NmtIncremental, TranslationDelta,
+    TranslationQualityMetrics, TranslationRequest, TranslationResponse, TranslationStream,
 };
-pub use nmt_client::{LocalM2m100HttpClient, RemoteNmtHttpClient, NmtClientAdapter};
+pub use nmt_client::{CascadingNmt, CircuitBreakerConfig, LocalM2m100HttpClient, RemoteNmtHttpClient, NmtClientAdapter};
 pub use persona_adapter::{PersonaAdapter, PersonaContext, RuleBasedPersonaAdapter, PersonaStub};
 pub use telemetry::{TelemetryDatum, TelemetrySink};
-pub use tts_streaming::{TtsRequest, TtsStreamChunk, TtsStreaming, FastSpeech2TtsEngine, TtsStub};
-pub use types::{AudioFrame, PartialTranscript, StableTranscript};
+pub use tts_streaming::{TtsAudioFormat, TtsRequest, TtsStreamChunk, TtsStreaming, FastSpeech2TtsEngine, TtsStub};
+pub use types::{AudioFrame, LanguageProbability, PartialTranscript, StableTranscript, TranscriptSegment, TranscriptToken};
 pub use vad::{DetectionOutcome, VoiceActivityDetector};
 pub use asr_streaming::{AsrRequest, AsrResult, AsrStreaming};
 pub use health_check::{HealthChecker, ServiceHealth};
 pub use post_processing::TextPostProcessor;
+pub use vocabulary_correction::{PinyinTable, VocabularyCorrector};
 pub use performance_logger::{PerformanceLog, PerformanceLogger};
 pub use tts_audio_enhancement::{AudioEnhancer, AudioEnhancementConfig};
 pub use translation_quality::TranslationQualityChecker;
+pub use session::{Session, SessionLanguages};
+pub use language_registry::{LanguageBackend, LanguageRegistry, LanguageTag};
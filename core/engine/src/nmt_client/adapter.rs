@@ -5,12 +5,15 @@
 use async_trait::async_trait;
 use std::sync::Arc;
 use crate::error::EngineResult;
+use crate::language_registry::{self, LanguageBackend};
 use crate::nmt_incremental::{NmtIncremental, TranslationRequest, TranslationResponse};
 use super::{NmtClient, NmtTranslateRequest};
 
 /// NMT 客户端适配器
 ///
 /// 将 NmtClient 包装为 NmtIncremental trait 的实现
+/// 
+/// HTTP 服务一次性返回完整译文，流式翻译（`translate_stream`）使用 trait 默认实现，只产出一个增量。
 pub struct NmtClientAdapter {
     client: Arc<dyn NmtClient>,
 }
@@ -35,22 +38,20 @@ impl NmtIncremental for NmtClientAdapter {
         let target_lang = request.target_language.clone();
 
         // 确定源语言
-        // 注意：TranslationRequest 中的 transcript 是 PartialTranscript，不包含 language 字段
-        // 所以我们需要从 target_language 推断源语言
-        // 这是一个简化处理：如果目标是 en，源是 zh；如果目标是 zh，源是 en
-        // 实际应用中，应该从 ASR 结果（StableTranscript）中获取源语言
-        let src_lang = if target_lang == "en" {
-            "zh"
-        } else if target_lang == "zh" {
-            "en"
-        } else {
+        // 优先使用请求中的源语言；未提供时从 target_language 推断（简化处理：目标是 en 则源是 zh，目标是 zh 则源是 en）
+        let src_lang = match request.source_language.as_deref() {
+            Some(source_language) => {
+                language_registry::global().normalize(source_language, LanguageBackend::M2M100)
+            }
+            None if target_lang == "en" => "zh".to_string(),
+            None if target_lang == "zh" => "en".to_string(),
             // 默认使用 zh -> en
-            "zh"
+            None => "zh".to_string(),
         };
 
         // 构造 NmtTranslateRequest
         let req = NmtTranslateRequest {
-            src_lang: src_lang.to_string(),
+            src_lang,
             tgt_lang: target_lang,
             text: source_text,
         };
@@ -79,6 +80,8 @@ impl NmtIncremental for NmtClientAdapter {
             source_audio_duration_ms: None,
             source_language: None,  // HTTP 服务可能无法提供源语言信息
             quality_metrics: None,
+            committed_text: None,
+            provider: response.provider,
             is_stable: true, // HTTP 服务总是返回稳定结果
         })
     }
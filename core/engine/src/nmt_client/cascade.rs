@@ -0,0 +1,356 @@
+//! 级联 NMT 后端
+//!
+//! 按顺序尝试多个翻译后端（例如本地 M2M100 服务 → 远程 API → 本地 ONNX Marian），
+//! 每个后端有独立的超时；连续失败的后端由熔断器暂时跳过，避免反复请求已经故障的服务。
+//! 同步推理的后端（如本地 ONNX Marian）在 `spawn_blocking` 中运行，超时才能生效，也不会阻塞异步运行时。
+
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+use crate::error::{EngineError, EngineResult};
+use crate::nmt_incremental::{
+    NmtIncremental, TranslationDelta, TranslationRequest, TranslationResponse, TranslationStream,
+};
+
+/// 熔断器配置
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerConfig {
+    /// 连续失败多少次后熔断
+    pub failure_threshold: u32,
+    /// 熔断持续时间，之后放行一次试探请求
+    pub open_duration: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 3,
+            open_duration: Duration::from_secs(30),
+        }
+    }
+}
+
+/// 熔断器状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// 正常放行
+    Closed,
+    /// 熔断中，跳过该后端
+    Open,
+    /// 熔断时间已过，放行一次试探请求（成功则恢复，失败则重新熔断）
+    HalfOpen,
+}
+
+/// 单个后端的熔断器
+#[derive(Debug)]
+struct CircuitBreaker {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    /// 半开状态下试探请求的开始时间（试探请求被取消时，超过熔断持续时间后允许新的试探）
+    probing_since: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    fn new() -> Self {
+        Self {
+            consecutive_failures: 0,
+            opened_at: None,
+            probing_since: None,
+        }
+    }
+
+    fn state(&self, config: &CircuitBreakerConfig) -> CircuitState {
+        match self.opened_at {
+            None => CircuitState::Closed,
+            Some(opened_at) if opened_at.elapsed() < config.open_duration => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// 是否放行请求（半开状态只放行一个试探请求）
+    fn try_acquire(&mut self, config: &CircuitBreakerConfig) -> bool {
+        match self.state(config) {
+            CircuitState::Closed => true,
+            CircuitState::Open => false,
+            CircuitState::HalfOpen
+                if self.probing_since.is_some_and(|since| since.elapsed() < config.open_duration) => false,
+            CircuitState::HalfOpen => {
+                self.probing_since = Some(Instant::now());
+                true
+            }
+        }
+    }
+
+    fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.opened_at = None;
+        self.probing_since = None;
+    }
+
+    fn record_failure(&mut self, config: &CircuitBreakerConfig) {
+        self.consecutive_failures += 1;
+        if self.probing_since.is_some() || self.consecutive_failures >= config.failure_threshold {
+            self.opened_at = Some(Instant::now());
+        }
+        self.probing_since = None;
+    }
+}
+
+/// 级联中的一个后端
+struct CascadeBackend {
+    name: String,
+    nmt: Arc<dyn NmtIncremental>,
+    timeout: Duration,
+    /// 翻译是同步的 CPU 推理，需要在阻塞线程中运行
+    blocking: bool,
+    breaker: Mutex<CircuitBreaker>,
+}
+
+impl CascadeBackend {
+    async fn translate(&self, request: TranslationRequest) -> EngineResult<TranslationResponse> {
+        if !self.blocking {
+            return self.nmt.translate(request).await;
+        }
+        let nmt = Arc::clone(&self.nmt);
+        let runtime = tokio::runtime::Handle::current();
+        tokio::task::spawn_blocking(move || runtime.block_on(nmt.translate(request)))
+            .await
+            .map_err(|e| EngineError::new(format!("translation task failed: {}", e)))?
+    }
+
+    /// 建立翻译流；同步推理的后端在阻塞线程中逐个产出增量，通过通道转发（容量 1，按消费方拉取进度解码）
+    async fn translate_stream(&self, request: TranslationRequest) -> EngineResult<TranslationStream<'_>> {
+        if !self.blocking {
+            return self.nmt.translate_stream(request).await;
+        }
+        let nmt = Arc::clone(&self.nmt);
+        let runtime = tokio::runtime::Handle::current();
+        let (delta_tx, delta_rx) = mpsc::channel::<EngineResult<TranslationDelta>>(1);
+        tokio::task::spawn_blocking(move || {
+            runtime.block_on(async move {
+                let mut deltas = match nmt.translate_stream(request).await {
+                    Ok(deltas) => deltas,
+                    Err(e) => {
+                        let _ = delta_tx.send(Err(e)).await;
+                        return;
+                    }
+                };
+                while let Some(delta) = deltas.next().await {
+                    if delta_tx.send(delta).await.is_err() {
+                        break;
+                    }
+                }
+            })
+        });
+        Ok(Box::pin(stream::unfold(delta_rx, |mut delta_rx| async move {
+            delta_rx.recv().await.map(|delta| (delta, delta_rx))
+        })))
+    }
+
+    fn try_acquire(&self, config: &CircuitBreakerConfig) -> bool {
+        self.breaker.lock().unwrap().try_acquire(config)
+    }
+
+    fn record_success(&self) {
+        self.breaker.lock().unwrap().record_success();
+    }
+
+    fn record_failure(&self, config: &CircuitBreakerConfig, error: &EngineError) {
+        let mut breaker = self.breaker.lock().unwrap();
+        breaker.record_failure(config);
+        eprintln!(
+            "[NMT Cascade] ⚠️ Backend '{}' failed ({} consecutive): {}",
+            self.name, breaker.consecutive_failures, error
+        );
+        if breaker.state(config) == CircuitState::Open {
+            eprintln!(
+                "[NMT Cascade] 🔌 Circuit opened for backend '{}' ({}s)",
+                self.name,
+                config.open_duration.as_secs_f32()
+            );
+        }
+    }
+}
+
+/// 级联 NMT：按顺序尝试各后端，返回第一个成功的结果，并在响应中记录提供翻译的后端
+pub struct CascadingNmt {
+    backends: Vec<CascadeBackend>,
+    breaker_config: CircuitBreakerConfig,
+}
+
+impl CascadingNmt {
+    /// 创建空的级联（使用 `with_backend` 按优先级添加后端）
+    pub fn new(breaker_config: CircuitBreakerConfig) -> Self {
+        Self {
+            backends: Vec::new(),
+            breaker_config,
+        }
+    }
+
+    /// 添加后端（先添加的优先）
+    ///
+    /// # Arguments
+    /// * `name` - 后端名称（记录在 `TranslationResponse::provider` 和性能日志中）
+    /// * `nmt` - 翻译后端
+    /// * `timeout` - 单次请求超时
+    pub fn with_backend(mut self, name: impl Into<String>, nmt: Arc<dyn NmtIncremental>, timeout: Duration) -> Self {
+        self.backends.push(CascadeBackend {
+            name: name.into(),
+            nmt,
+            timeout,
+            blocking: false,
+            breaker: Mutex::new(CircuitBreaker::new()),
+        });
+        self
+    }
+
+    /// 添加同步推理的后端（如本地 ONNX Marian），参数同 `with_backend`
+    ///
+    /// 翻译在 `spawn_blocking` 中运行：超时后立即切换到下一个后端（推理在后台线程中继续直到结束），
+    /// 推理期间不占用异步运行时的工作线程。
+    pub fn with_blocking_backend(mut self, name: impl Into<String>, nmt: Arc<dyn NmtIncremental>, timeout: Duration) -> Self {
+        self = self.with_backend(name, nmt, timeout);
+        if let Some(backend) = self.backends.last_mut() {
+            backend.blocking = true;
+        }
+        self
+    }
+
+    /// 后端名称（按优先级排序）
+    pub fn backend_names(&self) -> Vec<&str> {
+        self.backends.iter().map(|backend| backend.name.as_str()).collect()
+    }
+
+    /// 查询后端的熔断器状态
+    pub fn circuit_state(&self, name: &str) -> Option<CircuitState> {
+        self.backends
+            .iter()
+            .find(|backend| backend.name == name)
+            .map(|backend| backend.breaker.lock().unwrap().state(&self.breaker_config))
+    }
+
+    fn all_failed(errors: Vec<String>) -> EngineError {
+        if errors.is_empty() {
+            EngineError::new("All NMT backends are unavailable (circuit open)")
+        } else {
+            EngineError::new(format!("All NMT backends failed: {}", errors.join("; ")))
+        }
+    }
+}
+
+#[async_trait]
+impl NmtIncremental for CascadingNmt {
+    async fn initialize(&self) -> EngineResult<()> {
+        // 单个后端初始化失败不影响整体，只要有一个后端可用
+        let mut errors = Vec::new();
+        for backend in &self.backends {
+            if let Err(e) = backend.nmt.initialize().await {
+                backend.record_failure(&self.breaker_config, &e);
+                errors.push(format!("{}: {}", backend.name, e));
+            }
+        }
+        if !self.backends.is_empty() && errors.len() == self.backends.len() {
+            return Err(Self::all_failed(errors));
+        }
+        Ok(())
+    }
+
+    async fn translate(&self, request: TranslationRequest) -> EngineResult<TranslationResponse> {
+        let mut errors = Vec::new();
+        for backend in &self.backends {
+            if !backend.try_acquire(&self.breaker_config) {
+                eprintln!("[NMT Cascade] ⏭️ Skipping backend '{}' (circuit open)", backend.name);
+                continue;
+            }
+
+            let result = match tokio::time::timeout(backend.timeout, backend.translate(request.clone())).await {
+                Ok(result) => result,
+                Err(_) => Err(EngineError::new(format!("timed out after {}ms", backend.timeout.as_millis()))),
+            };
+            match result {
+                Ok(mut response) => {
+                    backend.record_success();
+                    response.provider = Some(backend.name.clone());
+                    return Ok(response);
+                }
+                Err(e) => {
+                    backend.record_failure(&self.breaker_config, &e);
+                    errors.push(format!("{}: {}", backend.name, e));
+                }
+            }
+        }
+        Err(Self::all_failed(errors))
+    }
+
+    /// 流式翻译：建立流或输出第一个增量之前失败（含超时）时切换后端，
+    /// 流开始输出后的错误直接返回给调用方
+    ///
+    /// 熔断器在流结束时记录结果：最后一个增量（`is_last`）或流正常结束记为成功，中途出错记为失败。
+    async fn translate_stream<'a>(&'a self, request: TranslationRequest) -> EngineResult<TranslationStream<'a>> {
+        let mut errors = Vec::new();
+        for backend in &self.backends {
+            if !backend.try_acquire(&self.breaker_config) {
+                eprintln!("[NMT Cascade] ⏭️ Skipping backend '{}' (circuit open)", backend.name);
+                continue;
+            }
+
+            // 建立流和等待第一个增量共用一个超时
+            let deadline = tokio::time::Instant::now() + backend.timeout;
+            let first = tokio::time::timeout_at(deadline, async {
+                let mut deltas = backend.translate_stream(request.clone()).await?;
+                match deltas.next().await {
+                    Some(Ok(delta)) => Ok((delta, deltas)),
+                    Some(Err(e)) => Err(e),
+                    None => Err(EngineError::new("translation stream ended without output")),
+                }
+            }).await;
+            let result = match first {
+                Ok(result) => result,
+                Err(_) => Err(EngineError::new(format!("timed out after {}ms", backend.timeout.as_millis()))),
+            };
+            let (first, deltas) = match result {
+                Ok(first) => first,
+                Err(e) => {
+                    backend.record_failure(&self.breaker_config, &e);
+                    errors.push(format!("{}: {}", backend.name, e));
+                    continue;
+                }
+            };
+
+            let config = &self.breaker_config;
+            let deltas = stream::iter([Ok(first)]).chain(deltas);
+            return Ok(Box::pin(stream::unfold(Some(deltas), move |deltas| async move {
+                let mut deltas = deltas?;
+                match deltas.next().await {
+                    Some(Ok(mut delta)) => {
+                        delta.provider = Some(backend.name.clone());
+                        if delta.is_last {
+                            backend.record_success();
+                            return Some((Ok(delta), None));
+                        }
+                        Some((Ok(delta), Some(deltas)))
+                    }
+                    Some(Err(e)) => {
+                        backend.record_failure(config, &e);
+                        Some((Err(e), None))
+                    }
+                    None => {
+                        backend.record_success();
+                        None
+                    }
+                }
+            })));
+        }
+        Err(Self::all_failed(errors))
+    }
+
+    async fn finalize(&self) -> EngineResult<()> {
+        for backend in &self.backends {
+            backend.nmt.finalize().await?;
+        }
+        Ok(())
+    }
+}
@@ -1,14 +1,16 @@
 //! NMT 客户端模块
 //!
-//! 提供统一的 NMT 客户端接口，支持本地 Python 服务和远程 API。
+//! 提供统一的 NMT 客户端接口，支持本地 Python 服务和远程 API，以及按顺序回退的级联后端。
 
 mod local_m2m100;
 mod remote;
 mod types;
 mod adapter;
+mod cascade;
 
 pub use local_m2m100::LocalM2m100HttpClient;
 pub use remote::RemoteNmtHttpClient;
 pub use types::{NmtClient, NmtTranslateRequest, NmtTranslateResponse};
 pub use adapter::NmtClientAdapter;
+pub use cascade::{CascadingNmt, CircuitBreakerConfig, CircuitState};
 
@@ -0,0 +1,579 @@
+// 解码策略实现（贪婪 / Beam Search / 采样）
+//
+// 与具体模型无关：模型只需提供一个单步函数（给定当前前缀和该假设的缓存，返回下一个 token 的 logits），
+// 由 `generate` 负责 logits 处理（重复惩罚、n-gram 屏蔽）和假设管理。
+
+use anyhow::{Result, anyhow};
+use ndarray::Array1;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// 解码策略
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DecodingStrategy {
+    /// 贪婪解码（argmax），延迟最低
+    Greedy,
+    /// Beam Search：保留 `num_beams` 个候选假设，质量更好但耗时约为 `num_beams` 倍
+    BeamSearch {
+        num_beams: usize,
+    },
+    /// 随机采样（温度 + top-k + top-p）
+    Sampling {
+        temperature: f32,
+        top_k: Option<usize>,
+        top_p: Option<f32>,
+        /// 随机种子（None 表示每次随机）
+        seed: Option<u64>,
+    },
+}
+
+/// 解码参数（随 `TranslationRequest` 传入，可按会话配置）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DecodingOptions {
+    /// 解码策略
+    pub strategy: DecodingStrategy,
+    /// 长度惩罚（Beam Search 中 score = sum(log_prob) / len^length_penalty）
+    /// > 1.0 鼓励更长的输出，< 1.0 鼓励更短的输出
+    pub length_penalty: f32,
+    /// 禁止重复的 n-gram 大小（0 表示不限制）
+    pub no_repeat_ngram_size: usize,
+    /// 重复惩罚（1.0 表示不惩罚；> 1.0 降低已生成 token 的概率）
+    pub repetition_penalty: f32,
+    /// 最大输出长度与源序列长度之比（None 表示只受模型最大长度限制）
+    pub max_length_ratio: Option<f32>,
+}
+
+impl Default for DecodingOptions {
+    fn default() -> Self {
+        Self {
+            strategy: DecodingStrategy::Greedy,
+            length_penalty: 1.0,
+            no_repeat_ngram_size: 0,
+            repetition_penalty: 1.0,
+            max_length_ratio: None,
+        }
+    }
+}
+
+impl DecodingOptions {
+    /// 贪婪解码（默认参数）
+    pub fn greedy() -> Self {
+        Self::default()
+    }
+
+    /// Beam Search（默认屏蔽重复的 3-gram）
+    pub fn beam_search(num_beams: usize) -> Self {
+        Self {
+            strategy: DecodingStrategy::BeamSearch { num_beams },
+            no_repeat_ngram_size: 3,
+            ..Self::default()
+        }
+    }
+
+    /// 是否使用 Beam Search（多个候选假设）
+    pub(crate) fn is_beam_search(&self) -> bool {
+        matches!(self.strategy, DecodingStrategy::BeamSearch { num_beams } if num_beams > 1)
+    }
+
+    /// 计算最多生成的 token 数
+    ///
+    /// # Arguments
+    /// * `source_len` - 源序列长度（token 数）
+    /// * `model_max_new_tokens` - 模型允许的最大生成长度
+    pub fn max_new_tokens(&self, source_len: usize, model_max_new_tokens: usize) -> usize {
+        match self.max_length_ratio {
+            Some(ratio) if ratio > 0.0 => {
+                let limit = (source_len as f32 * ratio).ceil() as usize;
+                limit.clamp(1, model_max_new_tokens.max(1))
+            }
+            _ => model_max_new_tokens,
+        }
+    }
+}
+
+/// 单个候选假设
+struct Hypothesis<S> {
+    tokens: Vec<i64>,
+    /// 已生成 token 在模型原始分布下的 log 概率
+    log_probs: Vec<f32>,
+    score: f32,
+    state: Option<S>,
+}
+
+/// 解码结果
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Generated {
+    /// 生成的 token（不包含起始序列和 EOS）
+    pub tokens: Vec<i64>,
+    /// 每个生成 token 的 log 概率（与 `tokens` 一一对应；按模型原始 logits 计算，不受重复惩罚等处理影响）
+    pub log_probs: Vec<f32>,
+}
+
+/// 运行解码
+///
+/// # Arguments
+/// * `options` - 解码参数
+/// * `prefix` - 起始序列（如 BOS / 目标语言 token），不计入输出
+/// * `eos_token_id` - 结束 token
+/// * `max_new_tokens` - 最多生成的 token 数
+/// * `step` - 单步函数：输入完整前缀和该假设的缓存（None 表示需要从完整前缀重新计算），返回下一个 token 的 logits
+/// * `fork` - 复制缓存（Beam Search 中一个假设扩展出多个分支时使用；返回 None 表示缓存无法复制，分支将从完整前缀重新计算）
+///
+/// # Returns
+/// 生成的 token 序列（不包含 `prefix` 和 EOS）及其 log 概率
+pub(crate) fn generate<S, F, C>(
+    options: &DecodingOptions,
+    prefix: &[i64],
+    eos_token_id: i64,
+    max_new_tokens: usize,
+    mut step: F,
+    fork: C,
+) -> Result<Generated>
+where
+    F: FnMut(&[i64], &mut Option<S>) -> Result<Array1<f32>>,
+    C: Fn(&S) -> Option<S>,
+{
+    match options.strategy {
+        DecodingStrategy::BeamSearch { num_beams } if num_beams > 1 => {
+            beam_search(options, num_beams, prefix, eos_token_id, max_new_tokens, step, fork)
+        }
+        _ => {
+            let mut decoder = StepDecoder::new(options, prefix, eos_token_id, max_new_tokens);
+            let mut log_probs = Vec::new();
+            while let Some((_, probability)) = decoder.next_token(&mut step)? {
+                log_probs.push(probability.ln());
+            }
+            Ok(Generated {
+                tokens: decoder.generated().to_vec(),
+                log_probs,
+            })
+        }
+    }
+}
+
+/// 逐步解码器（贪婪 / 采样）
+///
+/// 每次调用 `next_token` 只解码一个 token，供流式输出使用；`generate` 的贪婪 / 采样分支也基于它实现。
+/// Beam Search 需要完整解码后才能确定结果，不支持逐步输出。
+pub(crate) struct StepDecoder<S> {
+    options: DecodingOptions,
+    tokens: Vec<i64>,
+    prefix_len: usize,
+    eos_token_id: i64,
+    max_new_tokens: usize,
+    state: Option<S>,
+    rng: StdRng,
+    finished: bool,
+}
+
+impl<S> StepDecoder<S> {
+    pub(crate) fn new(options: &DecodingOptions, prefix: &[i64], eos_token_id: i64, max_new_tokens: usize) -> Self {
+        let rng = match options.strategy {
+            DecodingStrategy::Sampling { seed: Some(seed), .. } => StdRng::seed_from_u64(seed),
+            _ => StdRng::from_entropy(),
+        };
+        Self {
+            options: options.clone(),
+            tokens: prefix.to_vec(),
+            prefix_len: prefix.len(),
+            eos_token_id,
+            max_new_tokens,
+            state: None,
+            rng,
+            finished: false,
+        }
+    }
+
+    /// 已生成的 token（不包含起始序列）
+    pub(crate) fn generated(&self) -> &[i64] {
+        &self.tokens[self.prefix_len..]
+    }
+
+    /// 解码下一个 token
+    ///
+    /// # Returns
+    /// (token, 概率)；None 表示解码已结束（EOS、达到最大长度或检测到重复循环）。
+    /// 概率按模型原始 logits 计算，不受重复惩罚等处理影响
+    pub(crate) fn next_token<F>(&mut self, step: F) -> Result<Option<(i64, f32)>>
+    where
+        F: FnOnce(&[i64], &mut Option<S>) -> Result<Array1<f32>>,
+    {
+        let step_idx = self.tokens.len() - self.prefix_len;
+        if self.finished || step_idx >= self.max_new_tokens {
+            self.finished = true;
+            return Ok(None);
+        }
+
+        let mut logits = step(&self.tokens, &mut self.state)?;
+        let log_probs = log_softmax(&logits);
+        process_logits(&mut logits, &self.tokens[self.prefix_len..], &self.options);
+
+        let next_token_id = match self.options.strategy {
+            DecodingStrategy::Sampling { temperature, top_k, top_p, .. } => {
+                sample(&logits, temperature, top_k, top_p, &mut self.rng)?
+            }
+            _ => argmax(&logits)?,
+        };
+
+        if next_token_id == self.eos_token_id {
+            println!("[Decoding] Generated EOS token at step {}", step_idx);
+            self.finished = true;
+            return Ok(None);
+        }
+        let probability = log_probs
+            .get(next_token_id as usize)
+            .map(|log_prob| log_prob.exp())
+            .unwrap_or(0.0);
+        self.tokens.push(next_token_id);
+
+        // ⚠️ 贪婪解码的安全阀：陷入 2-token 重复循环时停止（配置 no_repeat_ngram_size 可从根本上避免）
+        if self.options.strategy == DecodingStrategy::Greedy && has_two_token_loop(&self.tokens[self.prefix_len..]) {
+            println!("[Decoding] ⚠️  检测到 2-token 重复模式, 停止解码 (step {})", step_idx);
+            self.finished = true;
+        }
+
+        Ok(Some((next_token_id, probability)))
+    }
+}
+
+fn beam_search<S, F, C>(
+    options: &DecodingOptions,
+    num_beams: usize,
+    prefix: &[i64],
+    eos_token_id: i64,
+    max_new_tokens: usize,
+    mut step: F,
+    fork: C,
+) -> Result<Generated>
+where
+    F: FnMut(&[i64], &mut Option<S>) -> Result<Array1<f32>>,
+    C: Fn(&S) -> Option<S>,
+{
+    let prefix_len = prefix.len();
+    let mut beams = vec![Hypothesis { tokens: prefix.to_vec(), log_probs: Vec::new(), score: 0.0, state: None }];
+    // 已结束的假设：(解码结果, 归一化后的分数)
+    let mut finished: Vec<(Generated, f32)> = Vec::new();
+
+    for _ in 0..max_new_tokens {
+        // 1. 每个假设取 top 2 * num_beams 个候选（保证去掉 EOS 后仍有足够的候选）
+        // 候选：(父假设, token, 累计分数, 该 token 在原始分布下的 log 概率)
+        let mut candidates: Vec<(usize, i64, f32, f32)> = Vec::new();
+        for (beam_idx, beam) in beams.iter_mut().enumerate() {
+            let mut logits = step(&beam.tokens, &mut beam.state)?;
+            let raw_log_probs = log_softmax(&logits);
+            process_logits(&mut logits, &beam.tokens[prefix_len..], options);
+            let log_probs = log_softmax(&logits);
+            for (token_id, log_prob) in top_k_indices(&log_probs, 2 * num_beams) {
+                candidates.push((beam_idx, token_id as i64, beam.score + log_prob, raw_log_probs[token_id]));
+            }
+        }
+        candidates.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+
+        // 2. 选出下一轮的假设；排名在前 num_beams 内的 EOS 候选加入已结束列表
+        let mut selected: Vec<(usize, i64, f32, f32)> = Vec::with_capacity(num_beams);
+        for (rank, (beam_idx, token_id, score, raw_log_prob)) in candidates.into_iter().enumerate() {
+            if token_id == eos_token_id {
+                if rank < num_beams {
+                    let beam = &beams[beam_idx];
+                    let generated = Generated {
+                        tokens: beam.tokens[prefix_len..].to_vec(),
+                        log_probs: beam.log_probs.clone(),
+                    };
+                    let length = generated.tokens.len() + 1;  // 包含 EOS
+                    finished.push((generated, normalize_score(score, length, options.length_penalty)));
+                }
+                continue;
+            }
+            if score == f32::NEG_INFINITY {
+                continue;
+            }
+            selected.push((beam_idx, token_id, score, raw_log_prob));
+            if selected.len() == num_beams {
+                break;
+            }
+        }
+
+        if finished.len() >= num_beams || selected.is_empty() {
+            beams.clear();
+            break;
+        }
+
+        // 3. 分配缓存：父假设的最后一个子分支直接接管缓存，其余分支复制
+        let mut remaining_children = vec![0usize; beams.len()];
+        for &(beam_idx, _, _, _) in &selected {
+            remaining_children[beam_idx] += 1;
+        }
+        let mut next_beams = Vec::with_capacity(selected.len());
+        for (beam_idx, token_id, score, raw_log_prob) in selected {
+            remaining_children[beam_idx] -= 1;
+            let parent = &mut beams[beam_idx];
+            let state = if remaining_children[beam_idx] == 0 {
+                parent.state.take()
+            } else {
+                parent.state.as_ref().and_then(&fork)
+            };
+            let mut tokens = parent.tokens.clone();
+            tokens.push(token_id);
+            let mut log_probs = parent.log_probs.clone();
+            log_probs.push(raw_log_prob);
+            next_beams.push(Hypothesis { tokens, log_probs, score, state });
+        }
+        beams = next_beams;
+    }
+
+    // 4. 达到最大长度仍未结束的假设也参与最终比较
+    for beam in beams {
+        let generated = Generated {
+            tokens: beam.tokens[prefix_len..].to_vec(),
+            log_probs: beam.log_probs,
+        };
+        let length = generated.tokens.len().max(1);
+        finished.push((generated, normalize_score(beam.score, length, options.length_penalty)));
+    }
+
+    finished
+        .into_iter()
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(generated, _)| generated)
+        .ok_or_else(|| anyhow!("beam search produced no hypothesis"))
+}
+
+/// 应用重复惩罚和 n-gram 屏蔽
+///
+/// `generated` 只包含已生成的 token（不包含起始序列）
+pub(crate) fn process_logits(logits: &mut Array1<f32>, generated: &[i64], options: &DecodingOptions) {
+    // 重复惩罚（CTRL 风格）：正 logit 除以惩罚系数，负 logit 乘以惩罚系数
+    if options.repetition_penalty > 0.0 && (options.repetition_penalty - 1.0).abs() > f32::EPSILON {
+        let mut seen = std::collections::HashSet::new();
+        for &token_id in generated {
+            if !seen.insert(token_id) {
+                continue;
+            }
+            if let Some(logit) = logits.get_mut(token_id as usize) {
+                if *logit > 0.0 {
+                    *logit /= options.repetition_penalty;
+                } else {
+                    *logit *= options.repetition_penalty;
+                }
+            }
+        }
+    }
+
+    // n-gram 屏蔽：如果当前末尾的 (n-1)-gram 之前出现过，禁止生成当时紧随其后的 token
+    let n = options.no_repeat_ngram_size;
+    if n > 0 && generated.len() + 1 >= n {
+        let tail = &generated[generated.len() + 1 - n..];
+        for window in generated.windows(n) {
+            if window[..n - 1] == *tail {
+                if let Some(logit) = logits.get_mut(window[n - 1] as usize) {
+                    *logit = f32::NEG_INFINITY;
+                }
+            }
+        }
+    }
+}
+
+/// 长度归一化后的分数
+fn normalize_score(score: f32, length: usize, length_penalty: f32) -> f32 {
+    score / (length.max(1) as f32).powf(length_penalty)
+}
+
+fn argmax(logits: &Array1<f32>) -> Result<i64> {
+    logits
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(idx, _)| idx as i64)
+        .ok_or_else(|| anyhow!("failed to find next token"))
+}
+
+fn log_softmax(logits: &Array1<f32>) -> Array1<f32> {
+    let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    if max == f32::NEG_INFINITY {
+        return logits.clone();
+    }
+    let log_sum_exp = logits.iter().map(|&x| (x - max).exp()).sum::<f32>().ln() + max;
+    logits.mapv(|x| x - log_sum_exp)
+}
+
+/// 返回分数最高的 k 个 (索引, 分数)，按分数降序
+fn top_k_indices(values: &Array1<f32>, k: usize) -> Vec<(usize, f32)> {
+    let mut indexed: Vec<(usize, f32)> = values.iter().copied().enumerate().collect();
+    let k = k.min(indexed.len());
+    if k == 0 {
+        return Vec::new();
+    }
+    indexed.select_nth_unstable_by(k - 1, |a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    indexed.truncate(k);
+    indexed.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    indexed
+}
+
+/// 温度 + top-k + top-p 采样
+fn sample(
+    logits: &Array1<f32>,
+    temperature: f32,
+    top_k: Option<usize>,
+    top_p: Option<f32>,
+    rng: &mut StdRng,
+) -> Result<i64> {
+    if temperature <= 0.0 {
+        return argmax(logits);
+    }
+
+    let scaled = logits.mapv(|x| x / temperature);
+    let k = top_k.filter(|&k| k > 0).unwrap_or(scaled.len());
+    let candidates = top_k_indices(&scaled, k);
+
+    let max = candidates.first().map(|&(_, v)| v).unwrap_or(f32::NEG_INFINITY);
+    if max == f32::NEG_INFINITY {
+        return argmax(logits);
+    }
+    let mut probs: Vec<(usize, f32)> = candidates
+        .into_iter()
+        .map(|(idx, v)| (idx, (v - max).exp()))
+        .collect();
+    let total: f32 = probs.iter().map(|&(_, p)| p).sum();
+    for entry in probs.iter_mut() {
+        entry.1 /= total;
+    }
+
+    // top-p：保留累计概率达到 p 的最小候选集合
+    if let Some(p) = top_p.filter(|&p| p > 0.0 && p < 1.0) {
+        let mut cumulative = 0.0;
+        let mut keep = probs.len();
+        for (i, &(_, prob)) in probs.iter().enumerate() {
+            cumulative += prob;
+            if cumulative >= p {
+                keep = i + 1;
+                break;
+            }
+        }
+        probs.truncate(keep);
+    }
+
+    let total: f32 = probs.iter().map(|&(_, p)| p).sum();
+    let mut target = rng.gen::<f32>() * total;
+    for &(idx, prob) in &probs {
+        if target < prob {
+            return Ok(idx as i64);
+        }
+        target -= prob;
+    }
+    probs
+        .last()
+        .map(|&(idx, _)| idx as i64)
+        .ok_or_else(|| anyhow!("no token to sample"))
+}
+
+/// 检测 2-token 重复循环（如 A B A B）
+fn has_two_token_loop(generated: &[i64]) -> bool {
+    let n = generated.len();
+    n >= 4 && generated[n - 1] == generated[n - 3] && generated[n - 2] == generated[n - 4]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 简单的“模型”：根据前缀长度返回固定的 logits 表
+    fn scripted_step(table: Vec<Vec<f32>>) -> impl FnMut(&[i64], &mut Option<()>) -> Result<Array1<f32>> {
+        move |tokens: &[i64], _state: &mut Option<()>| {
+            let idx = (tokens.len() - 1).min(table.len() - 1);
+            Ok(Array1::from_vec(table[idx].clone()))
+        }
+    }
+
+    #[test]
+    fn test_greedy_picks_argmax_until_eos() {
+        // vocab: 0 = EOS, 1, 2, 3
+        let step = scripted_step(vec![
+            vec![0.0, 0.1, 2.0, 0.3],
+            vec![0.0, 3.0, 0.1, 0.2],
+            vec![5.0, 0.1, 0.1, 0.1],
+        ]);
+        let output = generate(&DecodingOptions::greedy(), &[9], 0, 10, step, |_: &()| None).unwrap();
+        assert_eq!(output.tokens, vec![2, 1]);
+        let expected = log_softmax(&Array1::from_vec(vec![0.0, 0.1, 2.0, 0.3]))[2];
+        assert!((output.log_probs[0] - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_beam_search_finds_higher_probability_sequence() {
+        // 贪婪第一步选 1（0.55），但 1 之后概率分散；选 2（0.45）之后几乎确定结束
+        // 序列概率：1 → EOS = 0.55 * 0.4 = 0.22；2 → EOS = 0.45 * 0.95 ≈ 0.43
+        let step = |tokens: &[i64], _state: &mut Option<()>| -> Result<Array1<f32>> {
+            let probs = match tokens {
+                [_] => vec![0.0, 0.55, 0.45],
+                [_, 1] => vec![0.4, 0.3, 0.3],
+                [_, 2] => vec![0.95, 0.025, 0.025],
+                _ => vec![1.0, 0.0, 0.0],
+            };
+            Ok(Array1::from_vec(probs.into_iter().map(|p: f32| p.max(1e-9).ln()).collect()))
+        };
+
+        let greedy = generate(&DecodingOptions::greedy(), &[9], 0, 5, step, |_: &()| None).unwrap();
+        assert_eq!(greedy.tokens, vec![1]);
+
+        let options = DecodingOptions {
+            strategy: DecodingStrategy::BeamSearch { num_beams: 2 },
+            length_penalty: 0.0,
+            ..DecodingOptions::default()
+        };
+        let beam = generate(&options, &[9], 0, 5, step, |_: &()| Some(())).unwrap();
+        assert_eq!(beam.tokens, vec![2]);
+        assert!((beam.log_probs[0].exp() - 0.45).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_no_repeat_ngram_blocks_repeated_bigram() {
+        let options = DecodingOptions {
+            no_repeat_ngram_size: 2,
+            ..DecodingOptions::default()
+        };
+        let mut logits = Array1::from_vec(vec![0.0, 1.0, 2.0, 3.0]);
+        // 已生成 [3, 2, 3]：bigram (3, 2) 已出现，末尾是 3，应屏蔽 2
+        process_logits(&mut logits, &[3, 2, 3], &options);
+        assert_eq!(logits[2], f32::NEG_INFINITY);
+        assert_eq!(logits[1], 1.0);
+    }
+
+    #[test]
+    fn test_repetition_penalty() {
+        let options = DecodingOptions {
+            repetition_penalty: 2.0,
+            ..DecodingOptions::default()
+        };
+        let mut logits = Array1::from_vec(vec![4.0, -1.0, 3.0]);
+        process_logits(&mut logits, &[0, 1, 0], &options);
+        assert_eq!(logits.to_vec(), vec![2.0, -2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_max_new_tokens_ratio() {
+        let options = DecodingOptions {
+            max_length_ratio: Some(1.5),
+            ..DecodingOptions::default()
+        };
+        assert_eq!(options.max_new_tokens(10, 128), 15);
+        assert_eq!(options.max_new_tokens(200, 128), 128);
+        assert_eq!(DecodingOptions::default().max_new_tokens(10, 128), 128);
+    }
+
+    #[test]
+    fn test_seeded_sampling_is_reproducible() {
+        let options = DecodingOptions {
+            strategy: DecodingStrategy::Sampling { temperature: 1.0, top_k: Some(3), top_p: Some(0.9), seed: Some(42) },
+            ..DecodingOptions::default()
+        };
+        let step = |_tokens: &[i64], _state: &mut Option<()>| -> Result<Array1<f32>> {
+            Ok(Array1::from_vec(vec![0.5, 1.0, 1.0, 1.0]))
+        };
+        let first = generate(&options, &[9], 0, 8, step, |_: &()| None).unwrap();
+        let second = generate(&options, &[9], 0, 8, step, |_: &()| None).unwrap();
+        assert_eq!(first.tokens, second.tokens);
+    }
+}
@@ -1,37 +1,56 @@
+use std::fmt;
 use std::path::{Path, PathBuf};
 use anyhow::{Result, anyhow};
 
-/// Marian NMT 支持的语言代码
-/// 对应模型目录命名：marian-{source}-{target}
+use crate::language_registry::{self, LanguageBackend};
+
+/// 语言代码（规范代码，如 "en", "zh"）
+///
+/// 支持的语言由语言注册表（`config/languages.json`）决定，
+/// 常用语言提供关联常量（如 `LanguageCode::En`）。
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum LanguageCode {
-    En,  // English
-    Zh,  // Chinese
-    Es,  // Spanish
-    Ja,  // Japanese
-    // 可以继续添加其他语言
+pub struct LanguageCode(&'static str);
+
+#[allow(non_upper_case_globals)]
+impl LanguageCode {
+    pub const En: LanguageCode = LanguageCode("en");  // English
+    pub const Zh: LanguageCode = LanguageCode("zh");  // Chinese
+    pub const Es: LanguageCode = LanguageCode("es");  // Spanish
+    pub const Ja: LanguageCode = LanguageCode("ja");  // Japanese
 }
 
 impl LanguageCode {
-    /// 从字符串转换为语言代码
+    /// 从字符串转换为语言代码（支持别名和 BCP-47 标签，如 "english"、"zh-Hant-TW"）
     pub fn from_str(s: &str) -> Result<Self> {
-        match s.to_lowercase().as_str() {
-            "en" | "eng" | "english" => Ok(LanguageCode::En),
-            "zh" | "zho" | "chinese" | "中文" => Ok(LanguageCode::Zh),
-            "es" | "spa" | "spanish" | "español" => Ok(LanguageCode::Es),
-            "ja" | "jpn" | "japanese" | "日本語" => Ok(LanguageCode::Ja),
-            _ => Err(anyhow!("Unsupported language code: {}", s)),
+        let registry = language_registry::global();
+        let code = registry
+            .canonical_code(s)
+            .ok_or_else(|| anyhow!("Unsupported language code: {}", s))?;
+        Ok(Self(language_registry::intern(code)))
+    }
+
+    /// 规范语言代码
+    pub fn as_str(&self) -> &'static str {
+        self.0
+    }
+
+    /// 获取后端使用的语言代码
+    pub fn backend_code(&self, backend: LanguageBackend) -> &'static str {
+        match language_registry::global().backend_code(self.0, backend) {
+            Some(code) => language_registry::intern(&code),
+            None => self.0,
         }
     }
 
     /// 转换为目录名格式（如 "en", "zh"）
     pub fn to_dir_name(&self) -> &'static str {
-        match self {
-            LanguageCode::En => "en",
-            LanguageCode::Zh => "zh",
-            LanguageCode::Es => "es",
-            LanguageCode::Ja => "ja",
-        }
+        self.backend_code(LanguageBackend::Marian)
+    }
+}
+
+impl fmt::Display for LanguageCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
     }
 }
 
@@ -61,31 +80,47 @@ impl LanguagePair {
 
     /// 转换为模型目录名（如 "marian-en-zh"）
     pub fn to_model_dir_name(&self) -> String {
-        format!("marian-{}-{}", self.source.to_dir_name(), self.target.to_dir_name())
+        self.model_dir_name(LanguageBackend::Marian)
+    }
+
+    /// 转换为指定后端的模型目录名（如 M2M100 -> "m2m100-en-zh"）
+    pub fn model_dir_name(&self, backend: LanguageBackend) -> String {
+        format!(
+            "{}-{}-{}",
+            backend.name(),
+            self.source.backend_code(backend),
+            self.target.backend_code(backend)
+        )
     }
 
     /// 从模型目录名解析语言对（如 "marian-en-zh" -> LanguagePair { source: En, target: Zh }）
     pub fn from_model_dir_name(dir_name: &str) -> Result<Self> {
-        // 移除 "marian-" 前缀
-        let name = dir_name.strip_prefix("marian-")
+        Self::from_backend_model_dir_name(dir_name, LanguageBackend::Marian)
+    }
+
+    /// 从指定后端的模型目录名解析语言对（如 "m2m100-zh-en"）
+    pub fn from_backend_model_dir_name(dir_name: &str, backend: LanguageBackend) -> Result<Self> {
+        // 移除 "{backend}-" 前缀
+        let name = dir_name.strip_prefix(backend.name())
+            .and_then(|name| name.strip_prefix('-'))
             .ok_or_else(|| anyhow!("Invalid model directory name: {}", dir_name))?;
-        
+
         // 分割源语言和目标语言
         let parts: Vec<&str> = name.split('-').collect();
         if parts.len() != 2 {
             return Err(anyhow!("Invalid model directory name format: {}", dir_name));
         }
-        
+
         let source = LanguageCode::from_str(parts[0])?;
         let target = LanguageCode::from_str(parts[1])?;
         Ok(Self { source, target })
     }
 
     /// 根据语言对查找模型目录
-    /// 
+    ///
     /// # Arguments
     /// * `base_dir` - 模型基础目录（如 `core/engine/models/nmt/`）
-    /// 
+    ///
     /// # Returns
     /// 完整的模型目录路径（如 `core/engine/models/nmt/marian-en-zh/`）
     pub fn find_model_dir(&self, base_dir: &Path) -> PathBuf {
@@ -93,17 +128,17 @@ impl LanguagePair {
     }
 
     /// 从模型目录路径自动识别语言对
-    /// 
+    ///
     /// # Arguments
     /// * `model_dir` - 模型目录路径（如 `core/engine/models/nmt/marian-en-zh/`）
-    /// 
+    ///
     /// # Returns
     /// 解析出的语言对
     pub fn from_model_dir(model_dir: &Path) -> Result<Self> {
         let dir_name = model_dir.file_name()
             .and_then(|n| n.to_str())
             .ok_or_else(|| anyhow!("Invalid model directory path: {}", model_dir.display()))?;
-        
+
         Self::from_model_dir_name(dir_name)
     }
 }
@@ -131,5 +166,20 @@ mod tests {
         assert_eq!(pair.source, LanguageCode::En);
         assert_eq!(pair.target, LanguageCode::Zh);
     }
-}
 
+    #[test]
+    fn test_language_pair_backend_model_dir_name() {
+        let pair = LanguagePair::from_backend_model_dir_name("m2m100-fr-de", LanguageBackend::M2M100).unwrap();
+        assert_eq!(pair.source.as_str(), "fr");
+        assert_eq!(pair.target.as_str(), "de");
+        assert_eq!(pair.model_dir_name(LanguageBackend::M2M100), "m2m100-fr-de");
+        assert!(LanguagePair::from_backend_model_dir_name("marian-fr-de", LanguageBackend::M2M100).is_err());
+    }
+
+    #[test]
+    fn test_language_code_from_bcp47_tag() {
+        assert_eq!(LanguageCode::from_str("zh-Hant-TW").unwrap(), LanguageCode::Zh);
+        assert_eq!(LanguageCode::from_str("English").unwrap(), LanguageCode::En);
+        assert!(LanguageCode::from_str("klingon").is_err());
+    }
+}
@@ -4,11 +4,22 @@
 use anyhow::Result;
 use ort::tensor::OrtOwnedTensor;
 use ort::value::Value;
-use ndarray::{Array1, Array2, Array3, Array4, IxDyn};
+use ndarray::{Array1, Array2, Array3, Array4, ArrayD, IxDyn};
 use super::m2m100_onnx::M2M100NmtOnnx;
-// ✅ 工程版实时翻译改造：decoder_step 不再使用 DecoderState
-// 但保留导入，因为 decoder_step 函数仍然存在（已废弃，仅用于参考）
-use super::decoder_state::DecoderState;
+
+/// M2M100 增量解码的 KV cache
+/// 
+/// 以 ndarray 形式保存（ONNX Value 在 `session.run` 时会被消耗，每步需要重新构造），
+/// 因此可以直接 clone（Beam Search 分支时使用）
+#[derive(Clone)]
+pub(crate) struct M2M100KvCache {
+    /// 每层的 decoder self-attention KV（present.*.decoder.key/value）
+    /// 形状 [1, 16, tgt_seq_len, 64]，每步增加一个位置
+    decoder_kv: Vec<(ArrayD<f32>, ArrayD<f32>)>,
+    /// 每层的 encoder cross-attention KV（present.*.encoder.key/value）
+    /// 形状 [1, 16, encoder_seq_len, 64]，只在第一步计算一次
+    encoder_kv: Vec<(ArrayD<f32>, ArrayD<f32>)>,
+}
 
 impl M2M100NmtOnnx {
     /// 构造静态 encoder KV 占位符
@@ -211,273 +222,353 @@ impl M2M100NmtOnnx {
         Ok(result)
     }
 
-    /// 构造第一步用的 decoder KV 占位符（用于增量解码，固定长度为 1）
-    pub(crate) fn build_zero_decoder_kv(
-        &self,
-    ) -> anyhow::Result<Vec<(Value<'static>, Value<'static>)>> {
-        self.build_zero_decoder_kv_for_seq_len(1)
-    }
-
-    /// 执行 decoder 的单次步进（增量解码版本）
+    /// 增量解码：解码下一个 token（复用 KV cache）
+    /// 
+    /// - 第一步（prefill，`kv_cache` 为 None）：传入完整的 `generated_ids` 和长度为 0 的 past decoder KV
+    ///   （`use_cache_branch = false`），从输出中保存 decoder self-attention KV 和
+    ///   encoder cross-attention KV。cross-attention KV 只在这一步计算一次。
+    /// - 之后每一步：只传入最后一个 token，上一步的 `present.*.decoder.*` 作为 `past_key_values`，
+    ///   缓存的 encoder KV 原样传入（`use_cache_branch = true`）。
     /// 
-    /// 执行 decoder 的单次步进（增量解码版本）
+    /// 避免每一步都对完整序列重新运行 decoder（非增量解码的总耗时随输出长度平方增长）。
+    /// 
+    /// # Arguments
+    /// * `generated_ids` - 当前已生成的目标 token 序列（包含起始 token）
+    /// * `encoder_hidden_states` - Encoder 输出的隐藏状态
+    /// * `encoder_attention_mask` - Encoder 的 attention mask
+    /// * `encoder_seq_len` - Encoder 序列长度
+    /// * `use_new_format` - 是否使用新格式（没有 use_cache_branch）
+    /// * `kv_cache` - KV cache（第一步传入 None，函数返回后会被填充/更新）
     /// 
-    /// 新格式：51 个输入（3 base + 48 KV，没有 use_cache_branch）
-    /// 旧格式：52 个输入（3 base + 48 KV + 1 flag）
-    pub(crate) fn decoder_step(
+    /// # Returns
+    /// 返回下一个 token 的 logits（vocab_size 维度）
+    pub(crate) fn decode_next_token_incremental(
         &self,
+        generated_ids: &[i64],
         encoder_hidden_states: &Array3<f32>,
         encoder_attention_mask: &Array2<i64>,
-        mut state: DecoderState,
         encoder_seq_len: usize,
         use_new_format: bool,
-    ) -> anyhow::Result<(Array1<f32>, DecoderState)> {
+        kv_cache: &mut Option<M2M100KvCache>,
+    ) -> anyhow::Result<Array1<f32>> {
+        let runner = OnnxDecoderRunner {
+            model: self,
+            encoder_hidden_states,
+            encoder_attention_mask,
+            use_new_format,
+        };
+        decode_step_with_cache(&runner, generated_ids, encoder_seq_len, kv_cache)
+    }
+
+    /// 从 decoder 输出中提取 f32 张量
+    fn extract_f32_output(value: Option<Value<'static>>, name: &str) -> anyhow::Result<ArrayD<f32>> {
+        use anyhow::anyhow;
+
+        let value = value.ok_or_else(|| anyhow!("missing {name} output"))?;
+        let tensor: OrtOwnedTensor<f32, IxDyn> = value
+            .try_extract::<f32>()
+            .map_err(|e| anyhow!("failed to extract {name}: {e}"))?;
+        let array = tensor.view().to_owned();
+        Ok(array)
+    }
+}
+
+/// decoder 单次运行的输入（past KV 以 ndarray 形式传入）
+pub(crate) struct DecoderStepInputs {
+    /// 本步输入的 token，形状 [1, step_len]
+    pub input_ids: Array2<i64>,
+    /// 每层的 past decoder KV，形状 [1, 16, past_len, 64]（prefill 时 past_len = 0）
+    pub past_decoder_kv: Vec<(ArrayD<f32>, ArrayD<f32>)>,
+    /// 每层的 past encoder KV，形状 [1, 16, encoder_seq_len, 64]
+    pub past_encoder_kv: Vec<(ArrayD<f32>, ArrayD<f32>)>,
+    /// 是否使用 past KV（prefill 为 false）
+    pub use_cache_branch: bool,
+}
+
+/// decoder 单次运行的输出
+pub(crate) struct DecoderStepOutputs {
+    /// 形状 [1, step_len, vocab_size]
+    pub logits: ArrayD<f32>,
+    /// 每层的 present decoder KV（past 与本步 token 拼接），形状 [1, 16, past_len + step_len, 64]
+    pub present_decoder_kv: Vec<(ArrayD<f32>, ArrayD<f32>)>,
+    /// 每层的 present encoder KV
+    pub present_encoder_kv: Vec<(ArrayD<f32>, ArrayD<f32>)>,
+}
+
+/// 运行一次 M2M100 decoder（ONNX 会话，测试中可替换为模拟实现）
+pub(crate) trait M2M100DecoderRunner {
+    fn run_decoder_step(&self, inputs: DecoderStepInputs) -> Result<DecoderStepOutputs>;
+}
+
+/// 基于 ONNX 会话的 decoder
+struct OnnxDecoderRunner<'a> {
+    model: &'a M2M100NmtOnnx,
+    encoder_hidden_states: &'a Array3<f32>,
+    encoder_attention_mask: &'a Array2<i64>,
+    use_new_format: bool,
+}
+
+impl M2M100DecoderRunner for OnnxDecoderRunner<'_> {
+    fn run_decoder_step(&self, inputs: DecoderStepInputs) -> Result<DecoderStepOutputs> {
         use std::ptr;
         use ndarray::CowArray;
         use anyhow::anyhow;
 
-        let batch_size = 1usize;
-        let cur_len = state.input_ids.len();
-        let decoder_input_ids = Array2::<i64>::from_shape_vec(
-            (batch_size, cur_len),
-            state.input_ids.clone(),
-        )?;
-
         macro_rules! array_to_value {
-            ($arr:expr, $ty:ty) => {{
+            ($arr:expr) => {{
                 let arr_dyn = $arr.into_dyn();
                 let arr_owned = arr_dyn.to_owned();
                 let cow_arr = CowArray::from(arr_owned);
                 let value = Value::from_array(ptr::null_mut(), &cow_arr)
-                    .map_err(|e| anyhow!("failed to convert array to Value: {:?}", e))?;
+                    .map_err(|e| anyhow!("failed to convert array to Value: {e}"))?;
                 Ok::<ort::Value<'static>, anyhow::Error>(unsafe { std::mem::transmute::<ort::Value, ort::Value<'static>>(value) })
             }};
         }
 
-        let input_ids_value = array_to_value!(decoder_input_ids.clone(), i64)?;
-        let encoder_states_value = array_to_value!(encoder_hidden_states.clone(), f32)?;
-        let encoder_mask_value = array_to_value!(encoder_attention_mask.clone(), i64)?;
-        
-        // 如果是旧格式，准备 use_cache_branch 值（但先不添加，等 KV cache 添加后再添加）
-        let use_cache_value = if !use_new_format {
-            let use_cache_array = Array1::<bool>::from_vec(vec![state.use_cache_branch]);
-            Some(array_to_value!(use_cache_array, bool)?)
-        } else {
-            None
-        };
-
-        // 组织输入顺序：encoder_attention_mask, input_ids, encoder_hidden_states, past_key_values.*, use_cache_branch
-        let mut input_values: Vec<Value<'static>> = Vec::new();
+        // 组织 ONNX 输入：encoder_attention_mask, input_ids, encoder_hidden_states, past_key_values.*, use_cache_branch
+        let num_layers = M2M100NmtOnnx::NUM_LAYERS;
+        let mut input_values: Vec<Value<'static>> = Vec::with_capacity(4 + num_layers * 4);
+        input_values.push(array_to_value!(self.encoder_attention_mask.clone())?);
+        input_values.push(array_to_value!(inputs.input_ids)?);
+        input_values.push(array_to_value!(self.encoder_hidden_states.clone())?);
+        for ((dec_k, dec_v), (enc_k, enc_v)) in inputs.past_decoder_kv.into_iter().zip(inputs.past_encoder_kv) {
+            input_values.push(array_to_value!(dec_k)?);
+            input_values.push(array_to_value!(dec_v)?);
+            input_values.push(array_to_value!(enc_k)?);
+            input_values.push(array_to_value!(enc_v)?);
+        }
+        if !self.use_new_format {
+            let use_cache_array = Array1::<bool>::from_vec(vec![inputs.use_cache_branch]);
+            input_values.push(array_to_value!(use_cache_array)?);
+        }
 
-        input_values.push(encoder_mask_value);
-        input_values.push(input_ids_value);
-        input_values.push(encoder_states_value);
+        let decoder_session = self.model.decoder_session.lock().unwrap();
+        let outputs: Vec<Value<'static>> = decoder_session.run(input_values)
+            .map_err(|e| anyhow!("failed to run decoder model: {e}"))?;
+        let mut iter = outputs.into_iter();
 
-        // KV cache：准备输入 KV cache
-        let decoder_kv = if state.use_cache_branch && state.decoder_kv_cache.is_some() {
-            let kv = state.decoder_kv_cache.take().unwrap();
-            // 调试：检查 KV cache 的形状
-            if let Some((first_k, _)) = kv.first() {
-                if let Ok(tensor) = first_k.try_extract::<f32>() {
-                    let view = tensor.view();
-                    let shape = view.shape();
-                    println!("[decoder_step] 使用现有 KV cache, shape: {:?}", shape);
-                }
-            }
-            kv
-        } else {
-            println!("[decoder_step] 使用零 KV cache (首次或非缓存模式)");
-            self.build_zero_decoder_kv()?
-        };
-        
-        // 准备 encoder KV cache
-        // ✅ 优先使用从 decoder 输出中提取的真实 encoder KV cache
-        // 如果还没有（第一步），则使用全零占位符
-        // 注意：不能直接 clone Value，需要重新创建或使用 take
-        // 但是，我们需要保留 encoder_kv_cache 以便后续步骤使用，所以先检查但不 take
-        let encoder_kv = if state.encoder_kv_cache.is_some() {
-            println!("[decoder_step] 使用缓存的 encoder KV cache（从 decoder 输出中提取）");
-            // 临时取出，使用后会在最后保存回去
-            state.encoder_kv_cache.take().unwrap()
-        } else {
-            println!("[decoder_step] 使用全零 encoder KV cache 占位符（第一步）");
-            // 先解包 Result
-            self.build_static_encoder_kv(encoder_seq_len)?
-        };
-        
-        // 调试：检查 encoder KV cache 的形状
-        if let Some((first_enc_k, _)) = encoder_kv.first() {
-            if let Ok(tensor) = first_enc_k.try_extract::<f32>() {
-                let view = tensor.view();
-                let shape = view.shape();
-                println!("[Encoder KV Cache] Static encoder KV cache shape: {:?}", shape);
-            }
-        }
-        
-        let mut decoder_kv_iter = decoder_kv.into_iter();
-        let mut encoder_kv_iter = encoder_kv.into_iter();
-        
-        for _layer_idx in 0..Self::NUM_LAYERS {
-            // Decoder KV
-            let (dec_k, dec_v) = decoder_kv_iter.next()
-                .ok_or_else(|| anyhow!("insufficient decoder KV cache"))?;
-            input_values.push(dec_k);
-            input_values.push(dec_v);
-            
-            // Encoder KV: 使用全零占位符
-            let (enc_k, enc_v) = encoder_kv_iter.next()
-                .ok_or_else(|| anyhow!("insufficient encoder KV cache"))?;
-            input_values.push(enc_k);
-            input_values.push(enc_v);
+        // 输出：logits，然后每层依次为 decoder.key, decoder.value, encoder.key, encoder.value
+        let logits = M2M100NmtOnnx::extract_f32_output(iter.next(), "logits")?;
+        let mut present_decoder_kv = Vec::with_capacity(num_layers);
+        let mut present_encoder_kv = Vec::with_capacity(num_layers);
+        for _ in 0..num_layers {
+            let dec_k = M2M100NmtOnnx::extract_f32_output(iter.next(), "present.*.decoder.key")?;
+            let dec_v = M2M100NmtOnnx::extract_f32_output(iter.next(), "present.*.decoder.value")?;
+            present_decoder_kv.push((dec_k, dec_v));
+            let enc_k = M2M100NmtOnnx::extract_f32_output(iter.next(), "present.*.encoder.key")?;
+            let enc_v = M2M100NmtOnnx::extract_f32_output(iter.next(), "present.*.encoder.value")?;
+            present_encoder_kv.push((enc_k, enc_v));
         }
 
-        // 如果是旧格式，添加 use_cache_branch
-        if let Some(use_cache_val) = use_cache_value {
-            input_values.push(use_cache_val);
+        Ok(DecoderStepOutputs { logits, present_decoder_kv, present_encoder_kv })
+    }
+}
+
+/// 增量解码一步：维护 KV cache，返回下一个 token 的 logits
+///
+/// prefill 的 past decoder KV 长度为 0，present decoder KV 只包含真实 token 的位置；
+/// 每步检查 present decoder KV 的长度等于已生成的 token 数，避免占位行混入缓存。
+pub(crate) fn decode_step_with_cache<R: M2M100DecoderRunner + ?Sized>(
+    runner: &R,
+    generated_ids: &[i64],
+    encoder_seq_len: usize,
+    kv_cache: &mut Option<M2M100KvCache>,
+) -> Result<Array1<f32>> {
+    use anyhow::anyhow;
+
+    let num_layers = M2M100NmtOnnx::NUM_LAYERS;
+    let (step_ids, past_decoder_kv, past_encoder_kv, use_cache_branch) = match kv_cache.as_ref() {
+        None => {
+            // prefill：完整序列 + 空的 past decoder KV；encoder KV 由本步计算（输入只是占位符）
+            let empty_decoder = Array4::<f32>::zeros((1, M2M100NmtOnnx::NUM_HEADS, 0, M2M100NmtOnnx::HEAD_DIM)).into_dyn();
+            let placeholder_encoder = Array4::<f32>::zeros((1, M2M100NmtOnnx::NUM_HEADS, encoder_seq_len, M2M100NmtOnnx::HEAD_DIM)).into_dyn();
+            (
+                generated_ids.to_vec(),
+                vec![(empty_decoder.clone(), empty_decoder); num_layers],
+                vec![(placeholder_encoder.clone(), placeholder_encoder); num_layers],
+                false,
+            )
+        }
+        Some(cache) => (
+            vec![*generated_ids.last().ok_or_else(|| anyhow!("generated_ids is empty"))?],
+            cache.decoder_kv.clone(),
+            cache.encoder_kv.clone(),
+            true,
+        ),
+    };
+    let input_ids = Array2::<i64>::from_shape_vec((1, step_ids.len()), step_ids)?;
+
+    let outputs = runner.run_decoder_step(DecoderStepInputs {
+        input_ids,
+        past_decoder_kv,
+        past_encoder_kv,
+        use_cache_branch,
+    })?;
+
+    // 取最后一个 step 的 logits
+    let seq_len = outputs.logits.shape()[1];
+    let last_step_logits = outputs.logits
+        .slice(ndarray::s![0, seq_len - 1, ..])
+        .to_owned();
+
+    // present decoder KV 必须正好覆盖已生成的 token（多出的行说明 past 中的占位符被拼接进来了）
+    for (dec_k, dec_v) in &outputs.present_decoder_kv {
+        let (key_len, value_len) = (dec_k.shape()[2], dec_v.shape()[2]);
+        if key_len != generated_ids.len() || value_len != generated_ids.len() {
+            return Err(anyhow!(
+                "present decoder KV length {}/{} does not match generated length {}",
+                key_len, value_len, generated_ids.len()
+            ));
         }
+    }
 
-        // 调用 session.run
-        let decoder_session = self.decoder_session.lock().unwrap();
-        let outputs: Vec<Value<'static>> = decoder_session.run(input_values)
-            .map_err(|e| anyhow!("failed to run decoder model: {e}"))?;
+    // decoder KV 每步更新；encoder KV 只在 prefill 时保存，之后保持不变
+    match kv_cache {
+        Some(cache) => cache.decoder_kv = outputs.present_decoder_kv,
+        None => *kv_cache = Some(M2M100KvCache {
+            decoder_kv: outputs.present_decoder_kv,
+            encoder_kv: outputs.present_encoder_kv,
+        }),
+    }
 
-        // 从输出中提取 logits + 新 KV
-        let mut iter = outputs.into_iter();
-        let logits_value = iter.next().expect("missing logits output");
+    Ok(last_step_logits)
+}
 
-        let logits_tensor: OrtOwnedTensor<f32, IxDyn> = logits_value
-            .try_extract::<f32>()
-            .map_err(|e| anyhow!("failed to extract logits: {e}"))?;
-        let logits_view = logits_tensor.view();
-        let logits_array = logits_view.to_owned();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::{concatenate, Axis};
+    use std::sync::Mutex;
+
+    const VOCAB_SIZE: usize = 8;
+
+    /// 模拟 decoder：每个 token 的 KV 行填充为 token id，present = past 拼接本步 token；
+    /// 下一个 token 由所有 KV 行之和与行数决定，因此混入的占位行会改变输出
+    struct MockDecoder {
+        /// 记录每次运行的 (past decoder KV 长度, use_cache_branch)
+        calls: Mutex<Vec<(usize, bool)>>,
+        /// 模拟 prefill 时拼接 past 的模型（验证占位行不会混入）
+        concat_past_on_prefill: bool,
+    }
 
-        // 取最后一个 step 的 logits
-        let shape = logits_array.shape();
-        let seq_len = shape[1];
-        let last_step_logits = logits_array
-            .slice(ndarray::s![0, seq_len - 1, ..])
-            .to_owned();
+    impl MockDecoder {
+        fn new() -> Self {
+            Self { calls: Mutex::new(Vec::new()), concat_past_on_prefill: true }
+        }
 
-        // 提取 decoder KV cache
-        // ✅ 修复：直接使用整个 present KV，不截断
-        // 根据 Python 测试，past_seq 维度是动态的，可以接受任意长度
-        // 直接将上一步的 present KV 作为下一步的 past KV，保留完整的历史信息
-        let mut next_decoder_kv: Vec<(Value<'static>, Value<'static>)> = Vec::with_capacity(Self::NUM_LAYERS);
-        
-        // ✅ 提取 encoder KV cache（从 decoder 输出中获取真实的 encoder KV）
-        // 第一次调用后，decoder 会输出 encoder KV cache，形状应该是 [1, 16, encoder_seq_len, 64]
-        // 后续步骤中复用这个 encoder KV cache，而不是使用全零占位符
-        let mut next_encoder_kv: Vec<(Value<'static>, Value<'static>)> = Vec::with_capacity(Self::NUM_LAYERS);
-        
-        for layer_idx in 0..Self::NUM_LAYERS {
-            let dec_k_value = iter.next().expect("missing present.*.decoder.key");
-            let dec_v_value = iter.next().expect("missing present.*.decoder.value");
-            let enc_k_value = iter.next().expect("missing present.*.encoder.key");
-            let enc_v_value = iter.next().expect("missing present.*.encoder.value");
-            
-            // ✅ 直接使用整个 present KV，不截断
-            // 提取 tensor 以获取形状信息（用于调试）并重新创建 Value
-            let dec_k_tensor: OrtOwnedTensor<f32, IxDyn> = dec_k_value
-                .try_extract::<f32>()
-                .map_err(|e| anyhow!("failed to extract decoder key tensor: {e}"))?;
-            let dec_k_view = dec_k_tensor.view();
-            let kv_shape = dec_k_view.shape();
-            let kv_seq_len = kv_shape[2];
-            
-            // 调试日志（前几步）
-            if layer_idx == 0 {
-                println!("[KV Cache] Layer 0, present KV shape: {:?}, seq_len: {}", kv_shape, kv_seq_len);
-                // 检查 KV cache 的内容（检查最后一个位置的 K 值，这应该是新添加的）
-                if kv_seq_len >= 2 {
-                    let last_k = dec_k_view.slice(ndarray::s![0, 0, kv_seq_len - 1, 0..5.min(Self::HEAD_DIM)]);
-                    let first_k = dec_k_view.slice(ndarray::s![0, 0, 0, 0..5.min(Self::HEAD_DIM)]);
-                    println!("[KV Cache] Layer 0, first K values (seq=0): {:?}", first_k);
-                    println!("[KV Cache] Layer 0, last K values (seq={}): {:?}", kv_seq_len - 1, last_k);
-                    // 检查是否有非零值
-                    let has_nonzero = dec_k_view.iter().any(|&v| v.abs() > 1e-6);
-                    println!("[KV Cache] Layer 0, has non-zero values: {}", has_nonzero);
-                }
+        fn token_rows(ids: &[i64]) -> ArrayD<f32> {
+            let mut rows = Array4::<f32>::zeros((1, M2M100NmtOnnx::NUM_HEADS, ids.len(), M2M100NmtOnnx::HEAD_DIM));
+            for (position, &id) in ids.iter().enumerate() {
+                rows.slice_mut(ndarray::s![.., .., position, ..]).fill(id as f32 + 1.0);
             }
-            
-            // 重新创建 Value（因为需要 'static 生命周期）
-            // 但保持完整的序列长度，不截断
-            let dec_k_array = dec_k_view.to_owned();
-            let dec_k_dyn = dec_k_array.into_dyn();
-            let dec_k_cow = ndarray::CowArray::from(dec_k_dyn);
-            
-            let dec_v_tensor: OrtOwnedTensor<f32, IxDyn> = dec_v_value
-                .try_extract::<f32>()
-                .map_err(|e| anyhow!("failed to extract decoder value tensor: {e}"))?;
-            let dec_v_view = dec_v_tensor.view();
-            let dec_v_array = dec_v_view.to_owned();
-            let dec_v_dyn = dec_v_array.into_dyn();
-            let dec_v_cow = ndarray::CowArray::from(dec_v_dyn);
-            
-            let dec_k_new = Value::from_array(ptr::null_mut(), &dec_k_cow)
-                .map_err(|e| anyhow!("failed to convert decoder key array to Value: {e}"))?;
-            let dec_v_new = Value::from_array(ptr::null_mut(), &dec_v_cow)
-                .map_err(|e| anyhow!("failed to convert decoder value array to Value: {e}"))?;
-            
-            next_decoder_kv.push((
-                unsafe { std::mem::transmute::<ort::Value, ort::Value<'static>>(dec_k_new) },
-                unsafe { std::mem::transmute::<ort::Value, ort::Value<'static>>(dec_v_new) },
-            ));
-            
-            // 提取 encoder KV cache
-            let enc_k_tensor: OrtOwnedTensor<f32, IxDyn> = enc_k_value
-                .try_extract::<f32>()
-                .map_err(|e| anyhow!("failed to extract encoder key tensor: {e}"))?;
-            let enc_k_view = enc_k_tensor.view();
-            let enc_k_shape = enc_k_view.shape();
-            
-            // 调试日志（第一层）
-            if layer_idx == 0 {
-                println!("[Encoder KV Cache] Layer 0, present encoder KV shape: {:?}", enc_k_shape);
-                // 检查是否有非零值
-                let has_nonzero = enc_k_view.iter().any(|&v| v.abs() > 1e-6);
-                println!("[Encoder KV Cache] Layer 0, has non-zero values: {}", has_nonzero);
-                if has_nonzero && enc_k_shape.len() >= 3 {
-                    let seq_len = enc_k_shape[2];
-                    if seq_len > 0 {
-                        let first_k = enc_k_view.slice(ndarray::s![0, 0, 0, 0..5.min(Self::HEAD_DIM)]);
-                        let last_k = enc_k_view.slice(ndarray::s![0, 0, seq_len - 1, 0..5.min(Self::HEAD_DIM)]);
-                        println!("[Encoder KV Cache] Layer 0, first K values (seq=0): {:?}", first_k);
-                        println!("[Encoder KV Cache] Layer 0, last K values (seq={}): {:?}", seq_len - 1, last_k);
+            rows.into_dyn()
+        }
+
+        /// 参考实现：根据完整序列计算下一个 token
+        fn next_token(ids: &[i64]) -> i64 {
+            let sum: i64 = ids.iter().map(|id| id + 1).sum();
+            (sum + ids.len() as i64) % VOCAB_SIZE as i64
+        }
+    }
+
+    impl M2M100DecoderRunner for MockDecoder {
+        fn run_decoder_step(&self, inputs: DecoderStepInputs) -> Result<DecoderStepOutputs> {
+            let past_len = inputs.past_decoder_kv[0].0.shape()[2];
+            self.calls.lock().unwrap().push((past_len, inputs.use_cache_branch));
+
+            let step_ids: Vec<i64> = inputs.input_ids.iter().copied().collect();
+            let new_rows = Self::token_rows(&step_ids);
+            let present_decoder_kv: Vec<(ArrayD<f32>, ArrayD<f32>)> = inputs.past_decoder_kv
+                .iter()
+                .map(|(past_k, past_v)| {
+                    if inputs.use_cache_branch || self.concat_past_on_prefill {
+                        (
+                            concatenate(Axis(2), &[past_k.view(), new_rows.view()]).unwrap(),
+                            concatenate(Axis(2), &[past_v.view(), new_rows.view()]).unwrap(),
+                        )
+                    } else {
+                        (new_rows.clone(), new_rows.clone())
                     }
-                }
+                })
+                .collect();
+
+            // 由 present KV 还原完整序列，计算每个位置的下一个 token
+            let keys = &present_decoder_kv[0].0;
+            let total_len = keys.shape()[2];
+            let sequence: Vec<i64> = (0..total_len).map(|position| keys[[0, 0, position, 0]] as i64 - 1).collect();
+            let mut logits = Array3::<f32>::zeros((1, step_ids.len(), VOCAB_SIZE));
+            for step in 0..step_ids.len() {
+                let prefix = &sequence[..total_len - step_ids.len() + step + 1];
+                logits[[0, step, Self::next_token(prefix) as usize]] = 1.0;
             }
-            
-            let enc_k_array = enc_k_view.to_owned();
-            let enc_k_dyn = enc_k_array.into_dyn();
-            let enc_k_cow = ndarray::CowArray::from(enc_k_dyn);
-            
-            let enc_v_tensor: OrtOwnedTensor<f32, IxDyn> = enc_v_value
-                .try_extract::<f32>()
-                .map_err(|e| anyhow!("failed to extract encoder value tensor: {e}"))?;
-            let enc_v_view = enc_v_tensor.view();
-            let enc_v_array = enc_v_view.to_owned();
-            let enc_v_dyn = enc_v_array.into_dyn();
-            let enc_v_cow = ndarray::CowArray::from(enc_v_dyn);
-            
-            let enc_k_new = Value::from_array(ptr::null_mut(), &enc_k_cow)
-                .map_err(|e| anyhow!("failed to convert encoder key array to Value: {e}"))?;
-            let enc_v_new = Value::from_array(ptr::null_mut(), &enc_v_cow)
-                .map_err(|e| anyhow!("failed to convert encoder value array to Value: {e}"))?;
-            
-            next_encoder_kv.push((
-                unsafe { std::mem::transmute::<ort::Value, ort::Value<'static>>(enc_k_new) },
-                unsafe { std::mem::transmute::<ort::Value, ort::Value<'static>>(enc_v_new) },
-            ));
+
+            let present_encoder_kv = if inputs.use_cache_branch {
+                inputs.past_encoder_kv
+            } else {
+                let encoder = Array4::<f32>::ones((1, M2M100NmtOnnx::NUM_HEADS, 3, M2M100NmtOnnx::HEAD_DIM)).into_dyn();
+                vec![(encoder.clone(), encoder); M2M100NmtOnnx::NUM_LAYERS]
+            };
+            Ok(DecoderStepOutputs { logits: logits.into_dyn(), present_decoder_kv, present_encoder_kv })
         }
-        
-        state.decoder_kv_cache = Some(next_decoder_kv);
-        // ✅ 保存 encoder KV cache，后续步骤中复用
-        state.encoder_kv_cache = Some(next_encoder_kv);
-        if !state.use_cache_branch {
-            state.use_cache_branch = true;
+    }
+
+    fn argmax(logits: &Array1<f32>) -> i64 {
+        logits.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)).map(|(index, _)| index as i64).unwrap()
+    }
+
+    #[test]
+    fn test_incremental_decoding_matches_full_sequence_reference() {
+        let decoder = MockDecoder::new();
+        let mut generated = vec![5i64, 3];
+        let mut kv_cache = None;
+        for _ in 0..6 {
+            let logits = decode_step_with_cache(&decoder, &generated, 3, &mut kv_cache).unwrap();
+            let next = argmax(&logits);
+            assert_eq!(next, MockDecoder::next_token(&generated), "generated: {:?}", generated);
+            generated.push(next);
+        }
+
+        // prefill 的 past 长度为 0，之后每步的 past 长度等于之前生成的 token 数
+        let calls = decoder.calls.lock().unwrap();
+        assert_eq!(calls[0], (0, false));
+        for (step, &(past_len, use_cache_branch)) in calls.iter().enumerate().skip(1) {
+            assert_eq!(past_len, step + 1);
+            assert!(use_cache_branch);
         }
 
-        Ok((last_step_logits, state))
+        // encoder KV 来自 prefill 的输出，而不是占位符
+        let cache = kv_cache.unwrap();
+        assert!(cache.encoder_kv.iter().all(|(k, _)| k.iter().all(|&value| value == 1.0)));
+    }
+
+    #[test]
+    fn test_prefill_without_concatenation_keeps_same_cache() {
+        let decoder = MockDecoder { concat_past_on_prefill: false, ..MockDecoder::new() };
+        let mut kv_cache = None;
+        decode_step_with_cache(&decoder, &[5, 3], 3, &mut kv_cache).unwrap();
+        let cache = kv_cache.unwrap();
+        assert!(cache.decoder_kv.iter().all(|(k, v)| k.shape()[2] == 2 && v.shape()[2] == 2));
+    }
+
+    /// 把占位行拼接进 present 的 decoder 会被检测出来
+    struct PaddingDecoder;
+
+    impl M2M100DecoderRunner for PaddingDecoder {
+        fn run_decoder_step(&self, inputs: DecoderStepInputs) -> Result<DecoderStepOutputs> {
+            let len = inputs.past_decoder_kv[0].0.shape()[2] + inputs.input_ids.len() + 1;
+            let rows = Array4::<f32>::zeros((1, M2M100NmtOnnx::NUM_HEADS, len, M2M100NmtOnnx::HEAD_DIM)).into_dyn();
+            Ok(DecoderStepOutputs {
+                logits: Array3::<f32>::zeros((1, inputs.input_ids.len(), VOCAB_SIZE)).into_dyn(),
+                present_decoder_kv: vec![(rows.clone(), rows); M2M100NmtOnnx::NUM_LAYERS],
+                present_encoder_kv: inputs.past_encoder_kv,
+            })
+        }
     }
-}
 
+    #[test]
+    fn test_rejects_present_kv_with_extra_rows() {
+        let mut kv_cache = None;
+        assert!(decode_step_with_cache(&PaddingDecoder, &[5, 3], 3, &mut kv_cache).is_err());
+        assert!(kv_cache.is_none());
+    }
+}
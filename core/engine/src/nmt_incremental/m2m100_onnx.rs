@@ -3,6 +3,8 @@ use std::fs;
 use std::path::Path;
 use ort::session::Session;
 use super::m2m100_tokenizer::M2M100Tokenizer;
+use super::language_pair::LanguagePair;
+use crate::language_registry::LanguageBackend;
 
 pub struct M2M100NmtOnnx {
     pub encoder_session: std::sync::Mutex<Session>,
@@ -41,22 +43,20 @@ impl M2M100NmtOnnx {
         // 1. 先初始化 ORT 环境
         crate::onnx_utils::init_onnx_runtime()?;
 
-        // 2. 从目录名识别语言对（m2m100-en-zh 或 m2m100-zh-en）
+        // 2. 从目录名识别语言对（m2m100-{src}-{tgt}，语言代码由语言注册表解析）
         let dir_name = model_dir
             .file_name()
             .and_then(|n| n.to_str())
             .ok_or_else(|| anyhow!("Invalid model directory name"))?;
         
-        let (src_lang, tgt_lang) = if dir_name.contains("en-zh") {
-            ("en", "zh")
-        } else if dir_name.contains("zh-en") {
-            ("zh", "en")
-        } else {
-            return Err(anyhow!(
-                "Cannot determine language pair from directory name: {}. Expected 'm2m100-en-zh' or 'm2m100-zh-en'",
-                dir_name
-            ));
-        };
+        let language_pair = LanguagePair::from_backend_model_dir_name(dir_name, LanguageBackend::M2M100)
+            .map_err(|e| anyhow!(
+                "Cannot determine language pair from directory name: {} ({}). Expected 'm2m100-{{src}}-{{tgt}}', e.g. 'm2m100-en-zh'",
+                dir_name,
+                e
+            ))?;
+        let src_lang = language_pair.source.backend_code(LanguageBackend::M2M100);
+        let tgt_lang = language_pair.target.backend_code(LanguageBackend::M2M100);
 
         // 3. 加载 tokenizer
         let tokenizer = M2M100Tokenizer::from_model_dir(model_dir)?;
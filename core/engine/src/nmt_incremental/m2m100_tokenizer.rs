@@ -4,6 +4,8 @@ use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 
+use crate::language_registry::{self, LanguageBackend};
+
 /// M2M100 Tokenizer 实现
 /// 
 /// 基于 vocab.json 的最长匹配分词，支持语言 token 和解码
@@ -271,7 +273,7 @@ impl M2M100Tokenizer {
     /// 获取语言 token ID
     /// 
     /// # Arguments
-    /// * `lang` - 语言代码（如 "en" 或 "zh"）
+    /// * `lang` - 语言代码（如 "en" 或 "zh"），也可以是别名或 BCP-47 标签（如 "zh-Hant"），经语言注册表标准化
     /// 
     /// # Returns
     /// 语言 token 的 ID
@@ -279,8 +281,9 @@ impl M2M100Tokenizer {
     /// # Panics
     /// 如果语言代码无效，会 panic（fail-fast 设计）
     pub fn get_lang_id(&self, lang: &str) -> i64 {
+        let code = language_registry::global().normalize(lang, LanguageBackend::M2M100);
         self.lang_id_map
-            .get(lang)
+            .get(&code)
             .copied()
             .expect(&format!("Invalid M2M100 language code: {}. Available languages: {:?}", 
                 lang, 
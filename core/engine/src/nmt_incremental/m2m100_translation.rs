@@ -1,16 +1,28 @@
 // M2M100 Translation 实现
 
-use anyhow::{Result, anyhow};
+use anyhow::Result;
 use async_trait::async_trait;
 
 use crate::error::{EngineError, EngineResult};
 use super::nmt_trait::NmtIncremental;
-use super::types::{TranslationRequest, TranslationResponse};
+use super::types::{TranslationQualityMetrics, TranslationRequest, TranslationResponse, TranslationStream};
 use super::m2m100_onnx::M2M100NmtOnnx;
-// ✅ 使用非增量解码，每次传入完整序列，使用全零 KV cache，不维护状态
+use super::m2m100_decoder::M2M100KvCache;
+use super::decoding::{generate, DecodingOptions, StepDecoder};
+use super::streaming::{delta_stream, precomputed_stream, TokenSource};
+use super::wait_k::{committed_prefix, PrefixHypothesis};
+
+/// M2M100 解码模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum M2M100DecodeMode {
+    /// 增量解码：复用 KV cache，每步只输入最后一个 token（默认）
+    Incremental,
+    /// 非增量解码：每步输入完整序列，使用全零 KV 占位符（用于对照验证）
+    NonIncremental,
+}
 
 impl M2M100NmtOnnx {
-    /// 执行完整的翻译流程
+    /// 执行完整的翻译流程（增量解码）
     /// 
     /// # Arguments
     /// * `source_text` - 源文本（需要翻译的文本）
@@ -18,6 +30,49 @@ impl M2M100NmtOnnx {
     /// # Returns
     /// 翻译后的文本
     pub fn translate(&self, source_text: &str) -> Result<String> {
+        self.translate_with_mode(source_text, M2M100DecodeMode::Incremental)
+    }
+
+    /// 使用指定的解码模式执行完整的翻译流程（贪婪解码）
+    /// 
+    /// 两种模式的贪婪解码结果应完全一致，区别只在于性能。
+    pub fn translate_with_mode(&self, source_text: &str, mode: M2M100DecodeMode) -> Result<String> {
+        self.translate_with_options(source_text, mode, &DecodingOptions::default())
+    }
+
+    /// 使用指定的解码模式和解码参数执行完整的翻译流程
+    /// 
+    /// # Arguments
+    /// * `source_text` - 源文本（需要翻译的文本）
+    /// * `mode` - 解码模式（增量 / 非增量）
+    /// * `options` - 解码参数（贪婪 / Beam Search / 采样、重复惩罚等）
+    pub fn translate_with_options(
+        &self,
+        source_text: &str,
+        mode: M2M100DecodeMode,
+        options: &DecodingOptions,
+    ) -> Result<String> {
+        let hypothesis = self.translate_prefix(source_text, None, mode, options)?;
+        let translated_text = self.tokenizer.decode(&hypothesis.target_ids, true)?;
+        println!("[NMT][translate] Translated text: '{}'", translated_text);
+
+        Ok(translated_text)
+    }
+
+    /// 前缀翻译：以已提交的译文前缀作为强制解码前缀，续写译文
+    /// 
+    /// # Arguments
+    /// * `source_text` - 源文本（可以是仍在增长的部分转录）
+    /// * `committed_prefix` - 已提交的译文前缀（None 表示从头解码）
+    /// * `mode` - 解码模式（增量 / 非增量）
+    /// * `options` - 解码参数
+    pub(crate) fn translate_prefix(
+        &self,
+        source_text: &str,
+        committed_prefix: Option<&str>,
+        mode: M2M100DecodeMode,
+        options: &DecodingOptions,
+    ) -> Result<PrefixHypothesis> {
         // 1. 使用 tokenizer 编码源文本（包含源语言 token）
         let source_ids = self.tokenizer.encode(source_text, &self.src_lang, true)?;
         println!("Source text: '{}'", source_text);
@@ -28,7 +83,6 @@ impl M2M100NmtOnnx {
         println!("Encoder output shape: {:?}", encoder_hidden_states.shape());
 
         // 3. 获取配置信息
-        // ✅ 工程版实时翻译改造：使用简单的 Vec<i64> 管理生成序列，不再需要 DecoderState
         let tgt_lang_id = self.tokenizer.get_lang_id(&self.tgt_lang);
         let eos_token_id = self.tokenizer.eos_token_id();
 
@@ -36,111 +90,131 @@ impl M2M100NmtOnnx {
         println!("[Config] src_lang: {}, tgt_lang: {}", self.src_lang, self.tgt_lang);
         println!("[Config] tgt_lang_id: {}, eos_token_id: {} (from tokenizer), pad_token_id: {}", 
             tgt_lang_id, eos_token_id, self.pad_token_id);
+        println!("[Config] decode mode: {:?}, decoding options: {:?}", mode, options);
 
-        // 4. 进入解码循环（非增量解码版本，不维护 KV cache）
-        let max_steps = self.max_length.min(128);
-        let encoder_seq_len = encoder_hidden_states.shape()[1];
+        // 4. 已提交的译文前缀
+        let forced_ids = self.encode_committed_prefix(committed_prefix)?;
 
-        // 初始化生成序列（包含目标语言 token）
-        let mut generated_ids = vec![tgt_lang_id];
+        // 5. 解码（生成序列以目标语言 token + 已提交前缀开头）
+        // ⚠️ 长度限制：防止无限循环（生成序列总长度不超过 128）
+        let max_new_tokens = options
+            .max_new_tokens(source_ids.len(), self.max_length.min(128) - 1)
+            .saturating_sub(forced_ids.len());
+        let encoder_seq_len = encoder_hidden_states.shape()[1];
+        let mut prefix = vec![tgt_lang_id];
+        prefix.extend_from_slice(&forced_ids);
 
-        for step in 0..max_steps {
-            println!("[Step {}] 非增量解码: generated_ids = {:?} (长度: {})", 
-                step, &generated_ids[..generated_ids.len().min(10)], generated_ids.len());
+        let generated = match mode {
+            // ✅ 增量解码：复用上一步的 present KV，每步只输入最后一个 token
+            M2M100DecodeMode::Incremental => generate(
+                options,
+                &prefix,
+                eos_token_id,
+                max_new_tokens,
+                |generated_ids, kv_cache| self.decode_next_token_incremental(
+                    generated_ids,
+                    &encoder_hidden_states,
+                    &encoder_attention_mask,
+                    encoder_seq_len,
+                    self.use_new_format,
+                    kv_cache,
+                ),
+                |kv_cache: &M2M100KvCache| Some(kv_cache.clone()),
+            )?,
+            // 非增量解码：每次传入完整的 generated_ids，每次都使用全零 KV cache
+            M2M100DecodeMode::NonIncremental => generate(
+                options,
+                &prefix,
+                eos_token_id,
+                max_new_tokens,
+                |generated_ids, _state: &mut Option<()>| self.decode_next_token_non_incremental(
+                    generated_ids,
+                    &encoder_hidden_states,
+                    &encoder_attention_mask,
+                    encoder_seq_len,
+                    self.use_new_format,
+                ),
+                |_: &()| None,
+            )?,
+        };
 
-            // ✅ 使用非增量解码：每次传入完整的 generated_ids，每次都使用全零 KV cache
-            let logits = self.decode_next_token_non_incremental(
-                &generated_ids,
-                &encoder_hidden_states,
-                &encoder_attention_mask,
-                encoder_seq_len,
-                self.use_new_format,
-            )?;
-
-            // 打印 logits 信息（前几步）
-            if step < 3 {
-                println!("[Step {}] logits shape: {:?}", step, logits.shape());
-                // 获取 top 5 logits
-                let mut logits_with_idx: Vec<(usize, f32)> = logits.iter()
-                    .enumerate()
-                    .map(|(i, &v)| (i, v))
-                    .collect();
-                logits_with_idx.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-                println!("[Step {}] top 5 logits: {:?}", step, &logits_with_idx[..5.min(logits_with_idx.len())]);
-                if (self.eos_token_id as usize) < logits.len() {
-                    println!("[Step {}] logits[eos_token_id={}]: {}", step, self.eos_token_id, logits[self.eos_token_id as usize]);
-                }
+        // ✅ 生成结果已去掉目标语言 token 和 EOS token
+        let forced_len = forced_ids.len();
+        let mut target_ids = forced_ids;
+        target_ids.extend(generated.tokens);
+        println!("[NMT][translate] Translated IDs: {:?} (length: {})", target_ids, target_ids.len());
+        for &id in &target_ids {
+            if let Some(piece) = self.tokenizer.id_to_piece(id) {
+                println!("  Token {} -> '{}'", id, piece);
             }
+        }
 
-            // ✅ 使用 argmax 选择概率最高的 token（贪婪解码）
-            let next_token_id = logits
-                .iter()
-                .enumerate()
-                .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
-                .map(|(idx, _)| idx as i64)
-                .ok_or_else(|| anyhow!("failed to find next token"))?;
-
-            // 打印调试日志
-            if step < 10 {
-                let mut logits_with_idx: Vec<(usize, f32)> = logits.iter()
-                    .enumerate()
-                    .map(|(i, &v)| (i, v))
-                    .collect();
-                logits_with_idx.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-                let top5: Vec<(usize, f32)> = logits_with_idx.iter().take(5).copied().collect();
-                
-                println!("  logits(top5)={:?}", top5);
-                println!("  next_id={}", next_token_id);
-            }
+        Ok(PrefixHypothesis {
+            // 源序列去掉语言 token 和 EOS token
+            source_len: source_ids.len().saturating_sub(2),
+            target_ids,
+            forced_len,
+            log_probs: generated.log_probs,
+        })
+    }
 
-            // ✅ EOS 检查
-            if next_token_id == eos_token_id {
-                println!("[Step {}] Generated EOS token ({}), stopping", step, eos_token_id);
-                break;
-            }
-            
-            // ✅ 加入生成序列
-            generated_ids.push(next_token_id);
-            
-            // ⚠️ 重复检测：检查是否陷入重复模式
-            if generated_ids.len() >= 4 {
-                let last_four: Vec<i64> = generated_ids.iter().rev().take(4).copied().collect();
-                if last_four.len() >= 4 && last_four[0] == last_four[2] && last_four[1] == last_four[3] {
-                    println!("[Step {}] ⚠️  检测到 2-token 重复模式: {:?}, 停止解码", step, last_four);
-                    println!("[WARNING] 解码陷入重复循环，可能是模型或 KV cache 处理有问题");
-                    break;
-                }
-            }
-            
-            // ⚠️ 长度限制：防止无限循环
-            if generated_ids.len() >= 128 {
-                println!("[Step {}] ⚠️  达到最大长度限制 (128), 强制停止", step);
-                break;
-            }
+    /// 流式翻译：逐 token 输出译文增量
+    /// 
+    /// 贪婪 / 采样解码在消费方拉取时才逐 token 解码（增量 KV cache）；
+    /// Beam Search 需要完整解码后才能确定结果，解码结束后再逐 token 输出。
+    /// 
+    /// # Arguments
+    /// * `source_text` - 源文本
+    /// * `committed_prefix` - 已提交的译文前缀（None 表示从头解码）
+    /// * `options` - 解码参数
+    pub fn translate_stream_with_options<'a>(
+        &'a self,
+        source_text: &str,
+        committed_prefix: Option<&str>,
+        options: &DecodingOptions,
+    ) -> Result<TranslationStream<'a>> {
+        let detokenize = move |ids: &[i64]| self.tokenizer.decode(ids, true);
+
+        if options.is_beam_search() {
+            let hypothesis = self.translate_prefix(source_text, committed_prefix, M2M100DecodeMode::Incremental, options)?;
+            return Ok(precomputed_stream(hypothesis, detokenize));
         }
 
-        println!("[NMT][translate] Generated IDs: {:?} (length: {})", generated_ids, generated_ids.len());
+        let source_ids = self.tokenizer.encode(source_text, &self.src_lang, true)?;
+        let (encoder_hidden_states, encoder_attention_mask) = self.run_encoder(&source_ids)?;
+        let forced_ids = self.encode_committed_prefix(committed_prefix)?;
 
-        // ✅ 生成的序列格式: [tgt_lang_id, ...tokens..., eos_token_id]
-        // 需要跳过第一个目标语言 token，只保留实际的翻译 token
-        let translated_ids: Vec<i64> = generated_ids.iter()
-            .skip(1)  // ✅ 跳过第一个目标语言 token
-            .filter(|&&id| id != eos_token_id)  // ✅ 过滤掉 EOS token
-            .copied()
-            .collect();
-        
-        // 调试：打印每个 token 对应的文本
-        println!("[NMT][translate] Translated IDs (after filtering): {:?}", translated_ids);
-        for &id in &translated_ids {
-            if let Some(piece) = self.tokenizer.id_to_piece(id) {
-                println!("  Token {} -> '{}'", id, piece);
-            }
-        }
-        
-        let translated_text = self.tokenizer.decode(&translated_ids, true)?;
-        println!("[NMT][translate] Translated text: '{}'", translated_text);
+        let max_new_tokens = options
+            .max_new_tokens(source_ids.len(), self.max_length.min(128) - 1)
+            .saturating_sub(forced_ids.len());
+        let encoder_seq_len = encoder_hidden_states.shape()[1];
+        let mut prefix = vec![self.tokenizer.get_lang_id(&self.tgt_lang)];
+        prefix.extend_from_slice(&forced_ids);
 
-        Ok(translated_text)
+        let decoder = StepDecoder::new(options, &prefix, self.tokenizer.eos_token_id(), max_new_tokens);
+        let step = move |generated_ids: &[i64], kv_cache: &mut Option<M2M100KvCache>| {
+            self.decode_next_token_incremental(
+                generated_ids,
+                &encoder_hidden_states,
+                &encoder_attention_mask,
+                encoder_seq_len,
+                self.use_new_format,
+                kv_cache,
+            )
+        };
+        Ok(delta_stream(TokenSource::Step { decoder, step }, forced_ids, detokenize))
+    }
+
+    /// 编码已提交的译文前缀（去掉 encode 添加的语言 token）
+    fn encode_committed_prefix(&self, committed_prefix: Option<&str>) -> Result<Vec<i64>> {
+        let forced_ids: Vec<i64> = match committed_prefix {
+            Some(text) => self.tokenizer.encode(text, &self.tgt_lang, false)?.into_iter().skip(1).collect(),
+            None => Vec::new(),
+        };
+        if !forced_ids.is_empty() {
+            println!("[NMT][translate] Forced prefix IDs: {:?} (length: {})", forced_ids, forced_ids.len());
+        }
+        Ok(forced_ids)
     }
 }
 
@@ -154,7 +228,21 @@ impl NmtIncremental for M2M100NmtOnnx {
     async fn translate(&self, request: TranslationRequest) -> EngineResult<TranslationResponse> {
         let source_text = request.transcript.text.clone();
         
-        let translated = self.translate(&source_text)
+        let options = request.decoding.clone().unwrap_or_default();
+        let translate = || -> Result<(String, String, bool, Option<TranslationQualityMetrics>)> {
+            let hypothesis = self.translate_prefix(
+                &source_text,
+                committed_prefix(&request),
+                M2M100DecodeMode::Incremental,
+                &options,
+            )?;
+            // wait-k：只提交源前缀已“读够”的目标 token，其余为不稳定的尾部
+            let (committed_len, is_stable) = hypothesis.commit(&request);
+            let translated = self.tokenizer.decode(&hypothesis.target_ids, true)?;
+            let committed = self.tokenizer.decode(&hypothesis.target_ids[..committed_len], true)?;
+            Ok((translated, committed, is_stable, hypothesis.quality_metrics()))
+        };
+        let (translated, committed, is_stable, quality_metrics) = translate()
             .map_err(|e| {
                 let error_msg = format!("Translation failed: {}", e);
                 EngineError::new(error_msg)
@@ -166,11 +254,19 @@ impl NmtIncremental for M2M100NmtOnnx {
             source_text: None,
             source_audio_duration_ms: None,
             source_language: None,  // M2M100 可能无法提供源语言信息
-            quality_metrics: None,
-            is_stable: request.wait_k.is_none() || request.wait_k == Some(0),
+            quality_metrics,
+            committed_text: Some(committed),
+            provider: None,
+            is_stable,
         })
     }
 
+    async fn translate_stream<'a>(&'a self, request: TranslationRequest) -> EngineResult<TranslationStream<'a>> {
+        let options = request.decoding.clone().unwrap_or_default();
+        self.translate_stream_with_options(&request.transcript.text, committed_prefix(&request), &options)
+            .map_err(|e| EngineError::new(format!("Translation failed: {}", e)))
+    }
+
     async fn finalize(&self) -> EngineResult<()> {
         Ok(())
     }
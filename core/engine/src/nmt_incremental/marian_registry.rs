@@ -0,0 +1,389 @@
+// Marian 多语言对模型注册表
+//
+// 扫描模型基础目录下的 marian-{src}-{tgt} 目录，按会话请求的语言对路由：
+// - 模型在第一次使用时才加载（在阻塞线程中加载，不持有注册表的锁），按 LRU 在内存预算内淘汰
+// - 没有直接的语言对时经英语中转（如 ja→zh 使用 ja→en→zh）
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, OnceCell};
+
+use crate::error::{EngineError, EngineResult};
+use crate::types::PartialTranscript;
+use super::language_pair::{LanguageCode, LanguagePair};
+use super::marian_onnx::MarianNmtOnnx;
+use super::nmt_trait::NmtIncremental;
+use super::types::{TranslationQualityMetrics, TranslationRequest, TranslationResponse, TranslationStream};
+
+/// 模型加载函数：(模型目录, 语言对) -> 翻译模型
+pub type MarianModelLoader = Arc<dyn Fn(&Path, LanguagePair) -> Result<Arc<dyn NmtIncremental>> + Send + Sync>;
+
+/// 已加载的模型
+struct LoadedModel {
+    model: Arc<dyn NmtIncremental>,
+    /// 估算的内存占用（模型文件大小）
+    size_bytes: u64,
+}
+
+/// 正在加载的模型：同一语言对的并发请求等待同一次加载
+type LoadingModel = Arc<OnceCell<Arc<dyn NmtIncremental>>>;
+
+/// LRU 状态（`order` 队首为最久未使用）
+#[derive(Default)]
+struct LruState {
+    models: HashMap<LanguagePair, LoadedModel>,
+    order: VecDeque<LanguagePair>,
+    used_bytes: u64,
+    loading: HashMap<LanguagePair, LoadingModel>,
+}
+
+impl LruState {
+    fn touch(&mut self, pair: LanguagePair) {
+        self.order.retain(|p| *p != pair);
+        self.order.push_back(pair);
+    }
+}
+
+/// Marian 多语言对模型注册表
+pub struct MarianModelRegistry {
+    base_dir: PathBuf,
+    /// 可用的语言对 -> 模型目录
+    available: HashMap<LanguagePair, PathBuf>,
+    /// 已加载模型的内存预算（字节），超出时淘汰最久未使用的模型
+    memory_budget_bytes: u64,
+    loader: MarianModelLoader,
+    state: Mutex<LruState>,
+}
+
+impl MarianModelRegistry {
+    /// 默认内存预算：2 GiB
+    pub const DEFAULT_MEMORY_BUDGET_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+    /// 扫描模型基础目录（如 `core/engine/models/nmt/`），识别所有 marian-{src}-{tgt} 模型目录
+    ///
+    /// 只扫描目录，不加载模型。
+    pub fn scan(base_dir: &Path) -> Result<Self> {
+        let entries = fs::read_dir(base_dir)
+            .map_err(|e| anyhow!("failed to read model directory {}: {e}", base_dir.display()))?;
+
+        let mut available = HashMap::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            // 非 Marian 目录（如 m2m100-en-zh）或未注册的语言直接跳过
+            if let Ok(pair) = LanguagePair::from_model_dir(&path) {
+                println!("[NMT Registry] Found model {} -> {}: {}", pair.source, pair.target, path.display());
+                available.insert(pair, path);
+            }
+        }
+
+        if available.is_empty() {
+            return Err(anyhow!("no Marian model directory found in {}", base_dir.display()));
+        }
+
+        let loader: MarianModelLoader = Arc::new(|model_dir: &Path, _pair: LanguagePair| {
+            let model: Arc<dyn NmtIncremental> = Arc::new(MarianNmtOnnx::new_from_dir(model_dir)?);
+            Ok(model)
+        });
+
+        Ok(Self {
+            base_dir: base_dir.to_path_buf(),
+            available,
+            memory_budget_bytes: Self::DEFAULT_MEMORY_BUDGET_BYTES,
+            loader,
+            state: Mutex::new(LruState::default()),
+        })
+    }
+
+    /// 设置已加载模型的内存预算（字节）
+    pub fn with_memory_budget(mut self, memory_budget_bytes: u64) -> Self {
+        self.memory_budget_bytes = memory_budget_bytes;
+        self
+    }
+
+    /// 替换模型加载函数（默认加载 `MarianNmtOnnx`）
+    pub fn with_loader(mut self, loader: MarianModelLoader) -> Self {
+        self.loader = loader;
+        self
+    }
+
+    /// 模型基础目录
+    pub fn base_dir(&self) -> &Path {
+        &self.base_dir
+    }
+
+    /// 所有可用的语言对
+    pub fn available_pairs(&self) -> Vec<LanguagePair> {
+        let mut pairs: Vec<LanguagePair> = self.available.keys().copied().collect();
+        pairs.sort_by_key(|pair| (pair.source.as_str(), pair.target.as_str()));
+        pairs
+    }
+
+    /// 当前已加载的语言对（按最近使用排序，最后一个为最近使用）
+    pub fn loaded_pairs(&self) -> Vec<LanguagePair> {
+        self.state.lock().unwrap().order.iter().copied().collect()
+    }
+
+    /// 规划翻译路径：直接语言对，或经英语中转的两段语言对
+    ///
+    /// 没有可用路径时返回 `None`。
+    pub fn route(&self, source: LanguageCode, target: LanguageCode) -> Option<Vec<LanguagePair>> {
+        let direct = LanguagePair::new(source, target);
+        if self.available.contains_key(&direct) {
+            return Some(vec![direct]);
+        }
+        if source == LanguageCode::En || target == LanguageCode::En {
+            return None;
+        }
+        let to_pivot = LanguagePair::new(source, LanguageCode::En);
+        let from_pivot = LanguagePair::new(LanguageCode::En, target);
+        if self.available.contains_key(&to_pivot) && self.available.contains_key(&from_pivot) {
+            Some(vec![to_pivot, from_pivot])
+        } else {
+            None
+        }
+    }
+
+    /// 获取语言对的模型（未加载时加载，并按 LRU 淘汰超出预算的模型）
+    ///
+    /// 加载 ONNX 模型需要数秒，在阻塞线程中进行且不持有锁，其他语言对的请求不受影响；
+    /// 同一语言对的并发请求共享同一次加载。
+    async fn model(&self, pair: LanguagePair) -> EngineResult<Arc<dyn NmtIncremental>> {
+        let model_dir = self.available.get(&pair).ok_or_else(|| {
+            EngineError::new(format!("No Marian model for {} -> {}", pair.source, pair.target))
+        })?;
+        let loading = {
+            let mut state = self.state.lock().unwrap();
+            if let Some(loaded) = state.models.get(&pair) {
+                let model = Arc::clone(&loaded.model);
+                state.touch(pair);
+                return Ok(model);
+            }
+            Arc::clone(state.loading.entry(pair).or_default())
+        };
+
+        let loaded = loading
+            .get_or_try_init(|| async {
+                println!("[NMT Registry] Loading model {} -> {} from {}", pair.source, pair.target, model_dir.display());
+                let loader = Arc::clone(&self.loader);
+                let dir = model_dir.clone();
+                tokio::task::spawn_blocking(move || loader(&dir, pair))
+                    .await
+                    .map_err(|e| EngineError::new(format!("Marian model loading task failed: {}", e)))?
+                    .map_err(|e| EngineError::new(format!("Failed to load Marian model {}: {}", model_dir.display(), e)))
+            })
+            .await
+            .map(Arc::clone);
+
+        let mut state = self.state.lock().unwrap();
+        // 只有第一个拿到锁的等待者把模型移入 LRU（加载失败时移除，下次请求重新加载）
+        let owns_loading = state.loading.get(&pair).is_some_and(|cell| Arc::ptr_eq(cell, &loading));
+        if owns_loading {
+            state.loading.remove(&pair);
+        }
+        let model = loaded?;
+        if !owns_loading {
+            if state.models.contains_key(&pair) {
+                state.touch(pair);
+            }
+            return Ok(model);
+        }
+
+        let size_bytes = model_size_bytes(model_dir);
+        state.models.insert(pair, LoadedModel { model: Arc::clone(&model), size_bytes });
+        state.used_bytes += size_bytes;
+        state.touch(pair);
+
+        // 淘汰最久未使用的模型（刚加载的模型始终保留）
+        while state.used_bytes > self.memory_budget_bytes && state.order.len() > 1 {
+            let Some(evicted) = state.order.pop_front() else { break };
+            if let Some(loaded) = state.models.remove(&evicted) {
+                state.used_bytes -= loaded.size_bytes;
+                println!(
+                    "[NMT Registry] Evicted model {} -> {} ({} bytes, budget {} bytes)",
+                    evicted.source, evicted.target, loaded.size_bytes, self.memory_budget_bytes
+                );
+            }
+        }
+
+        Ok(model)
+    }
+
+    /// 解析请求的语言对，返回翻译路径上各段的模型
+    async fn resolve_models(&self, request: &TranslationRequest) -> EngineResult<(LanguageCode, Vec<Arc<dyn NmtIncremental>>)> {
+        let target = LanguageCode::from_str(&request.target_language)
+            .map_err(|e| EngineError::new(format!("Invalid target language: {}", e)))?;
+        let source = match request.source_language.as_deref() {
+            Some(source_language) => LanguageCode::from_str(source_language)
+                .map_err(|e| EngineError::new(format!("Invalid source language: {}", e)))?,
+            None => self.infer_source(target)?,
+        };
+
+        let route = self.route(source, target).ok_or_else(|| {
+            EngineError::new(format!(
+                "No Marian model route for {} -> {} (available: {:?})",
+                source,
+                target,
+                self.available_pairs().iter().map(|pair| pair.to_model_dir_name()).collect::<Vec<_>>()
+            ))
+        })?;
+        if route.len() > 1 {
+            println!("[NMT Registry] Pivoting {} -> {} via en", source, target);
+        }
+        let mut models = Vec::with_capacity(route.len());
+        for pair in route {
+            models.push(self.model(pair).await?);
+        }
+        Ok((source, models))
+    }
+
+    /// 请求未提供源语言时，只有唯一一个以 `target` 为目标的语言对时才能推断
+    fn infer_source(&self, target: LanguageCode) -> EngineResult<LanguageCode> {
+        let mut sources = self.available.keys().filter(|pair| pair.target == target).map(|pair| pair.source);
+        match (sources.next(), sources.next()) {
+            (Some(source), None) => Ok(source),
+            _ => Err(EngineError::new(format!(
+                "Source language is required to route translation to {}",
+                target
+            ))),
+        }
+    }
+}
+
+/// 估算模型内存占用：模型目录下 ONNX 文件（含外部数据文件）的总大小
+fn model_size_bytes(model_dir: &Path) -> u64 {
+    fs::read_dir(model_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.file_name().to_string_lossy().contains(".onnx"))
+                .filter_map(|entry| entry.metadata().ok())
+                .map(|metadata| metadata.len())
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// 中转翻译的第一段请求：只翻译到英语，wait-k 和已提交前缀只作用于最终目标语言
+fn pivot_request(request: &TranslationRequest) -> TranslationRequest {
+    TranslationRequest {
+        target_language: LanguageCode::En.to_string(),
+        wait_k: None,
+        committed_prefix: None,
+        ..request.clone()
+    }
+}
+
+/// 中转翻译的第二段请求：以英语译文作为源文本
+fn from_pivot_request(request: &TranslationRequest, pivot_text: String) -> TranslationRequest {
+    TranslationRequest {
+        transcript: PartialTranscript {
+            text: pivot_text,
+            ..request.transcript.clone()
+        },
+        source_language: Some(LanguageCode::En.to_string()),
+        ..request.clone()
+    }
+}
+
+/// 合并两段翻译的质量指标（取较差的一段）
+fn combine_metrics(
+    first: Option<TranslationQualityMetrics>,
+    second: Option<TranslationQualityMetrics>,
+) -> Option<TranslationQualityMetrics> {
+    let worse = |a: Option<f32>, b: Option<f32>, pick: fn(f32, f32) -> f32| match (a, b) {
+        (Some(a), Some(b)) => Some(pick(a, b)),
+        (a, b) => a.or(b),
+    };
+    match (first, second) {
+        (Some(first), Some(second)) => Some(TranslationQualityMetrics {
+            perplexity: worse(first.perplexity, second.perplexity, f32::max),
+            avg_probability: worse(first.avg_probability, second.avg_probability, f32::min),
+            min_probability: worse(first.min_probability, second.min_probability, f32::min),
+        }),
+        (first, second) => first.or(second),
+    }
+}
+
+#[async_trait]
+impl NmtIncremental for MarianModelRegistry {
+    async fn initialize(&self) -> EngineResult<()> {
+        // 模型在第一次使用时才加载
+        Ok(())
+    }
+
+    async fn translate(&self, request: TranslationRequest) -> EngineResult<TranslationResponse> {
+        let (source, models) = self.resolve_models(&request).await?;
+        let source_text = request.transcript.text.clone();
+
+        let mut response = match models.as_slice() {
+            [model] => model.translate(request).await?,
+            [to_pivot, from_pivot] => {
+                let pivot = to_pivot.translate(pivot_request(&request)).await?;
+                let mut response = from_pivot.translate(from_pivot_request(&request, pivot.translated_text)).await?;
+                response.quality_metrics = combine_metrics(pivot.quality_metrics, response.quality_metrics);
+                response
+            }
+            _ => return Err(EngineError::new("Invalid Marian model route")),
+        };
+        response.source_text = Some(source_text);
+        response.source_language = Some(source.to_string());
+        Ok(response)
+    }
+
+    async fn translate_stream<'a>(&'a self, request: TranslationRequest) -> EngineResult<TranslationStream<'a>> {
+        let (_, mut models) = self.resolve_models(&request).await?;
+        let model = models.pop().ok_or_else(|| EngineError::new("Invalid Marian model route"))?;
+        // 中转翻译：第一段完整翻译到英语，只对第二段流式输出
+        let request = match models.pop() {
+            Some(to_pivot) => {
+                let pivot = to_pivot.translate(pivot_request(&request)).await?;
+                from_pivot_request(&request, pivot.translated_text)
+            }
+            None => request,
+        };
+
+        // 模型可能在翻译过程中被 LRU 淘汰，因此在独立任务中持有模型并转发增量（容量 1，按消费方拉取进度解码）；
+        // 解码是同步的 CPU 推理，放在阻塞线程中运行
+        let (delta_tx, delta_rx) = mpsc::channel(1);
+        let runtime = tokio::runtime::Handle::current();
+        tokio::task::spawn_blocking(move || runtime.block_on(async move {
+            let mut deltas = match model.translate_stream(request).await {
+                Ok(deltas) => deltas,
+                Err(e) => {
+                    let _ = delta_tx.send(Err(e)).await;
+                    return;
+                }
+            };
+            while let Some(delta) = deltas.next().await {
+                if delta_tx.send(delta).await.is_err() {
+                    break;
+                }
+            }
+        }));
+
+        Ok(Box::pin(stream::unfold(delta_rx, |mut delta_rx| async move {
+            delta_rx.recv().await.map(|delta| (delta, delta_rx))
+        })))
+    }
+
+    async fn finalize(&self) -> EngineResult<()> {
+        let models: Vec<Arc<dyn NmtIncremental>> = {
+            let mut state = self.state.lock().unwrap();
+            state.order.clear();
+            state.used_bytes = 0;
+            state.models.drain().map(|(_, loaded)| loaded.model).collect()
+        };
+        for model in models {
+            model.finalize().await?;
+        }
+        Ok(())
+    }
+}
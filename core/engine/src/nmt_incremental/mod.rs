@@ -6,10 +6,14 @@ mod nmt_trait;
 mod utils;
 mod stub;
 mod decoder_state;
+mod decoding;
+mod wait_k;
+mod streaming;
 mod encoder;
 mod decoder;
 mod translation;
 mod marian_onnx;
+mod marian_registry;
 mod m2m100_onnx;
 mod m2m100_decoder;
 mod m2m100_translation;
@@ -17,10 +21,14 @@ mod m2m100_translation;
 pub use tokenizer::MarianTokenizer;
 pub use m2m100_tokenizer::M2M100Tokenizer;
 pub use language_pair::{LanguageCode, LanguagePair};
-pub use types::{TranslationRequest, TranslationResponse};
+pub use types::{TranslationDelta, TranslationQualityMetrics, TranslationRequest, TranslationResponse, TranslationStream};
+pub use decoding::{DecodingOptions, DecodingStrategy};
+pub use wait_k::WaitKPolicy;
 pub use crate::types::PartialTranscript;
 pub use nmt_trait::NmtIncremental;
 pub use utils::{load_marian_onnx_for_smoke_test, translate_full_sentence_stub};
 pub use stub::MarianNmtStub;
 pub use marian_onnx::MarianNmtOnnx;
+pub use marian_registry::{MarianModelLoader, MarianModelRegistry};
 pub use m2m100_onnx::M2M100NmtOnnx;
+pub use m2m100_translation::M2M100DecodeMode;
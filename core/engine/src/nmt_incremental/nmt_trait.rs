@@ -1,11 +1,26 @@
 use async_trait::async_trait;
+use futures::stream;
 use crate::error::EngineResult;
-use super::types::{TranslationRequest, TranslationResponse};
+use super::types::{TranslationDelta, TranslationRequest, TranslationResponse, TranslationStream};
 
 #[async_trait]
 pub trait NmtIncremental: Send + Sync {
     async fn initialize(&self) -> EngineResult<()>;
     async fn translate(&self, request: TranslationRequest) -> EngineResult<TranslationResponse>;
     async fn finalize(&self) -> EngineResult<()>;
-}
 
+    /// 流式翻译：逐 token 产出译文增量
+    /// 
+    /// 默认实现调用 `translate`，将完整译文作为唯一的增量返回（适用于不支持流式输出的后端）。
+    async fn translate_stream<'a>(&'a self, request: TranslationRequest) -> EngineResult<TranslationStream<'a>> {
+        let response = self.translate(request).await?;
+        Ok(Box::pin(stream::iter([Ok(TranslationDelta {
+            full_text: Some(response.translated_text.clone()),
+            text: response.translated_text,
+            token_id: None,
+            probability: None,
+            is_last: true,
+            provider: response.provider,
+        })])))
+    }
+}
@@ -0,0 +1,242 @@
+// 流式翻译输出
+//
+// 把逐步解码器包装为译文增量流：每次轮询只解码一个 token，解码在消费方拉取时才进行，
+// 因此消费方可以在译文尚未完成时就开始处理已输出的部分（例如送入 TTS）。
+
+use anyhow::Result;
+use futures::stream;
+use ndarray::Array1;
+
+use crate::error::EngineError;
+use super::decoding::StepDecoder;
+use super::types::{TranslationDelta, TranslationStream};
+use super::wait_k::PrefixHypothesis;
+
+/// 译文 token 的来源
+pub(crate) enum TokenSource<S, F> {
+    /// 逐步解码（贪婪 / 采样）
+    Step { decoder: StepDecoder<S>, step: F },
+    /// 已完成解码的 (token, 概率)（Beam Search 需要完整解码后才能确定结果）
+    Precomputed(std::vec::IntoIter<(i64, f32)>),
+}
+
+struct DeltaStreamState<S, F, D> {
+    source: TokenSource<S, F>,
+    detokenize: D,
+    /// 强制前缀（之前已提交的译文），作为第一个增量输出
+    forced_ids: Option<Vec<i64>>,
+    target_ids: Vec<i64>,
+    emitted_text: String,
+    done: bool,
+}
+
+/// 构建译文增量流
+///
+/// # Arguments
+/// * `source` - 译文 token 来源
+/// * `forced_ids` - 强制前缀 token（已包含在解码器的起始序列中，这里只用于输出）
+/// * `detokenize` - 将 token 序列解码为文本
+pub(crate) fn delta_stream<'a, S, F, D>(
+    source: TokenSource<S, F>,
+    forced_ids: Vec<i64>,
+    detokenize: D,
+) -> TranslationStream<'a>
+where
+    S: Send + 'a,
+    F: FnMut(&[i64], &mut Option<S>) -> Result<Array1<f32>> + Send + 'a,
+    D: Fn(&[i64]) -> Result<String> + Send + 'a,
+{
+    let state = DeltaStreamState {
+        source,
+        detokenize,
+        forced_ids: Some(forced_ids).filter(|ids| !ids.is_empty()),
+        target_ids: Vec::new(),
+        emitted_text: String::new(),
+        done: false,
+    };
+
+    Box::pin(stream::unfold(state, |mut state| async move {
+        if state.done {
+            return None;
+        }
+        // 每个 token 之前让出执行权，使同一任务中并发的消费方（例如 TTS 合成）可以推进
+        tokio::task::yield_now().await;
+        match next_delta(&mut state) {
+            Ok(delta) => {
+                state.done = delta.is_last;
+                Some((Ok(delta), state))
+            }
+            Err(e) => {
+                state.done = true;
+                Some((Err(EngineError::new(format!("Streaming translation failed: {}", e))), state))
+            }
+        }
+    }))
+}
+
+/// 构建已完成解码的译文增量流（逐 token 输出）
+///
+/// # Arguments
+/// * `hypothesis` - 完整的解码结果（包含强制前缀）
+/// * `detokenize` - 将 token 序列解码为文本
+pub(crate) fn precomputed_stream<'a, D>(hypothesis: PrefixHypothesis, detokenize: D) -> TranslationStream<'a>
+where
+    D: Fn(&[i64]) -> Result<String> + Send + 'a,
+{
+    let PrefixHypothesis { mut target_ids, forced_len, log_probs, .. } = hypothesis;
+    let generated = target_ids.split_off(forced_len.min(target_ids.len()));
+    let tokens: Vec<(i64, f32)> = generated
+        .into_iter()
+        .zip(log_probs.iter().map(|log_prob| log_prob.exp()))
+        .collect();
+    let source: TokenSource<(), fn(&[i64], &mut Option<()>) -> Result<Array1<f32>>> =
+        TokenSource::Precomputed(tokens.into_iter());
+    delta_stream(source, target_ids, detokenize)
+}
+
+fn next_delta<S, F, D>(state: &mut DeltaStreamState<S, F, D>) -> Result<TranslationDelta>
+where
+    F: FnMut(&[i64], &mut Option<S>) -> Result<Array1<f32>>,
+    D: Fn(&[i64]) -> Result<String>,
+{
+    let (token, probability) = if let Some(forced_ids) = state.forced_ids.take() {
+        state.target_ids.extend(forced_ids);
+        (None, None)
+    } else {
+        let next = match &mut state.source {
+            TokenSource::Step { decoder, step } => decoder.next_token(&mut *step)?,
+            TokenSource::Precomputed(tokens) => tokens.next(),
+        };
+        match next {
+            Some((id, probability)) => {
+                state.target_ids.push(id);
+                (Some(id), Some(probability))
+            }
+            None => {
+                // 解码结束：输出暂缓的末尾
+                let text = (state.detokenize)(&state.target_ids)?;
+                let delta = text.strip_prefix(state.emitted_text.as_str()).unwrap_or_default().to_string();
+                state.emitted_text.push_str(&delta);
+                return Ok(TranslationDelta {
+                    text: delta,
+                    token_id: None,
+                    probability: None,
+                    is_last: true,
+                    full_text: Some(text),
+                    provider: None,
+                });
+            }
+        }
+    };
+
+    // 每步对完整的 token 序列重新解码（SentencePiece 的空格、跨 token 合并的字符与一次性解码一致）
+    let text = (state.detokenize)(&state.target_ids)?;
+    let delta = stable_delta(&state.emitted_text, &text).to_string();
+    state.emitted_text.push_str(&delta);
+    Ok(TranslationDelta {
+        text: delta,
+        token_id: token,
+        probability,
+        is_last: false,
+        full_text: Some(text),
+        provider: None,
+    })
+}
+
+/// 计算可以输出的新增文本
+///
+/// 只输出在已输出文本之后追加的部分；末尾尚未组成完整字符的字节（解码为 U+FFFD）暂缓输出。
+/// 重新解码后前文发生变化时暂不输出，等待后续 token（最终译文以 `full_text` 为准）。
+fn stable_delta<'a>(emitted: &str, current: &'a str) -> &'a str {
+    let Some(appended) = current.strip_prefix(emitted) else {
+        return "";
+    };
+    appended.trim_end_matches(char::REPLACEMENT_CHARACTER)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    fn detokenize(ids: &[i64]) -> Result<String> {
+        Ok(ids.iter().map(|id| format!("w{}", id)).collect::<Vec<_>>().join(" "))
+    }
+
+    /// SentencePiece 风格的解码：片段拼接后 '▁' 替换为空格并去掉首尾空白
+    fn sentencepiece_detokenize(ids: &[i64]) -> Result<String> {
+        const PIECES: [&str; 4] = ["▁Hello", "▁world", ",", "▁again"];
+        let text: String = ids.iter().map(|&id| PIECES[id as usize]).collect();
+        Ok(text.replace('▁', " ").trim().to_string())
+    }
+
+    /// 字节回退解码：每个 token 是一个 UTF-8 字节，不完整的字符解码为 U+FFFD
+    fn byte_detokenize(ids: &[i64]) -> Result<String> {
+        let bytes: Vec<u8> = ids.iter().map(|&id| id as u8).collect();
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    async fn collect_texts(hypothesis: PrefixHypothesis, detokenize: fn(&[i64]) -> Result<String>) -> (Vec<String>, String) {
+        let deltas: Vec<TranslationDelta> = precomputed_stream(hypothesis, detokenize)
+            .map(|delta| delta.unwrap())
+            .collect()
+            .await;
+        let full_text = deltas.last().unwrap().full_text.clone().unwrap();
+        (deltas.into_iter().map(|delta| delta.text).collect(), full_text)
+    }
+
+    fn hypothesis(target_ids: Vec<i64>) -> PrefixHypothesis {
+        let log_probs = vec![0.0; target_ids.len()];
+        PrefixHypothesis { source_len: 4, target_ids, forced_len: 0, log_probs }
+    }
+
+    #[test]
+    fn test_stable_delta() {
+        assert_eq!(stable_delta("", "Hello"), "Hello");
+        assert_eq!(stable_delta("Hello", "Hello world"), " world");
+        assert_eq!(stable_delta("你好", "你好世界"), "世界");
+        assert_eq!(stable_delta("Hello", "Hello"), "");
+        assert_eq!(stable_delta("你", "你\u{FFFD}"), "");
+        assert_eq!(stable_delta("Hello world", "Hello, world"), "");
+    }
+
+    #[tokio::test]
+    async fn test_deltas_concatenate_to_full_detokenization() {
+        let (texts, full_text) = collect_texts(hypothesis(vec![0, 1, 2, 3]), sentencepiece_detokenize).await;
+        assert_eq!(texts, vec!["Hello", " world", ",", " again", ""]);
+        assert_eq!(full_text, "Hello world, again");
+        assert_eq!(texts.concat(), full_text);
+    }
+
+    #[tokio::test]
+    async fn test_incomplete_utf8_is_held_back() {
+        // "你好" 的 UTF-8 字节逐个作为 token
+        let ids: Vec<i64> = "你好".bytes().map(i64::from).collect();
+        let (texts, full_text) = collect_texts(hypothesis(ids), byte_detokenize).await;
+        assert_eq!(texts, vec!["", "", "你", "", "", "好", ""]);
+        assert_eq!(texts.concat(), full_text);
+        assert_eq!(full_text, "你好");
+    }
+
+    #[tokio::test]
+    async fn test_precomputed_stream_concatenates_to_full_text() {
+        let hypothesis = PrefixHypothesis {
+            source_len: 4,
+            target_ids: vec![1, 2, 3, 4],
+            forced_len: 2,
+            log_probs: vec![0.5f32.ln(), 0.25f32.ln()],
+        };
+        let deltas: Vec<TranslationDelta> = precomputed_stream(hypothesis, detokenize)
+            .map(|delta| delta.unwrap())
+            .collect()
+            .await;
+
+        let texts: Vec<&str> = deltas.iter().map(|d| d.text.as_str()).collect();
+        assert_eq!(texts, vec!["w1 w2", " w3", " w4", ""]);
+        assert_eq!(deltas[0].token_id, None);
+        assert_eq!(deltas[1].token_id, Some(3));
+        assert!((deltas[2].probability.unwrap() - 0.25).abs() < 1e-6);
+        assert!(deltas.last().unwrap().is_last);
+        assert_eq!(texts.concat(), "w1 w2 w3 w4");
+    }
+}
@@ -52,6 +52,8 @@ impl NmtIncremental for MarianNmtStub {
             source_audio_duration_ms: None,
             source_language: None,  // Stub 无法提供源语言信息
             quality_metrics: None,
+            committed_text: None,
+            provider: None,
         })
     }
 
@@ -1,12 +1,15 @@
-use anyhow::{Result, anyhow};
-use ort::value::Value;
+use anyhow::Result;
 use async_trait::async_trait;
+use ndarray::{Array1, Array2, Array3};
 
 use crate::error::{EngineError, EngineResult};
 use super::nmt_trait::NmtIncremental;
-use super::types::{TranslationRequest, TranslationResponse};
+use super::types::{TranslationRequest, TranslationResponse, TranslationStream};
 use super::marian_onnx::MarianNmtOnnx;
 use super::decoder_state::DecoderState;
+use super::decoding::{generate, DecodingOptions, StepDecoder};
+use super::streaming::{delta_stream, precomputed_stream, TokenSource};
+use super::wait_k::{committed_prefix, PrefixHypothesis};
 
 impl MarianNmtOnnx {
     /// 执行完整的翻译流程
@@ -21,6 +24,36 @@ impl MarianNmtOnnx {
     /// 这是一个简化版本，假设 encoder_hidden_states 已经准备好。
     /// 完整的实现需要先运行 encoder 模型。
     pub fn translate(&self, source_text: &str) -> Result<String> {
+        self.translate_with_options(source_text, &DecodingOptions::default())
+    }
+
+    /// 使用指定的解码参数执行完整的翻译流程
+    /// 
+    /// # Note
+    /// Marian 的 KV cache 以 ONNX Value 形式保存，无法复制：Beam Search 中同一假设扩展出的
+    /// 多个分支只有一个能沿用缓存，其余分支会从完整前缀重新计算。
+    pub fn translate_with_options(&self, source_text: &str, options: &DecodingOptions) -> Result<String> {
+        let hypothesis = self.translate_prefix(source_text, None, options)?;
+
+        // 使用 tokenizer 解码（生成结果已去掉 BOS token）
+        let translated_text = self.tokenizer.decode(&hypothesis.target_ids);
+        println!("[NMT][translate] Translated text: '{}'", translated_text);
+
+        Ok(translated_text)
+    }
+
+    /// 前缀翻译：以已提交的译文前缀作为强制解码前缀，续写译文
+    /// 
+    /// # Arguments
+    /// * `source_text` - 源文本（可以是仍在增长的部分转录）
+    /// * `committed_prefix` - 已提交的译文前缀（None 表示从头解码）
+    /// * `options` - 解码参数
+    pub(crate) fn translate_prefix(
+        &self,
+        source_text: &str,
+        committed_prefix: Option<&str>,
+        options: &DecodingOptions,
+    ) -> Result<PrefixHypothesis> {
         // 1. 使用 tokenizer 编码源文本
         let source_ids = self.tokenizer.encode(source_text, true);
         println!("Source text: '{}'", source_text);
@@ -30,100 +63,146 @@ impl MarianNmtOnnx {
         let (encoder_hidden_states, encoder_attention_mask) = self.run_encoder(&source_ids)?;
         println!("Encoder output shape: {:?}", encoder_hidden_states.shape());
 
-        // 3. 初始化 DecoderState（根据 marian_nmt_interface_spec.md：只维护 decoder KV cache）
-        // 第一步：不使用 KV cache，input_ids 只包含 BOS token
-        let mut state = DecoderState {
-            input_ids: vec![self.decoder_start_token_id],
-            generated_ids: vec![self.decoder_start_token_id],  // 一开始就包含 BOS
-            decoder_kv_cache: None,
-            encoder_kv_cache: None,  // 第一步使用全零占位符
-            use_cache_branch: false,  // 第一步：禁用 KV 分支
-        };
+        // 3. 已提交的译文前缀
+        let forced_ids = committed_prefix
+            .map(|text| self.tokenizer.encode(text, false))
+            .unwrap_or_default();
 
-        // 4. 进入解码循环
-        let max_steps = self.max_length.min(128); // 限制最大步数
+        // 4. 进入解码循环（生成序列以 BOS + 已提交前缀开头）
+        let max_new_tokens = options
+            .max_new_tokens(source_ids.len(), self.max_length.min(128)) // 限制最大步数
+            .saturating_sub(forced_ids.len());
         let encoder_seq_len = encoder_hidden_states.shape()[1];  // 用于创建静态 encoder KV
+        let mut prefix = vec![self.decoder_start_token_id];
+        prefix.extend_from_slice(&forced_ids);
 
-        for step in 0..max_steps {
-            // 准备当前步骤的 state（根据 marian_nmt_interface_spec.md：只维护 decoder KV cache）
-            // 关键：如果使用 KV cache，input_ids 应该只包含新 token（单个 token）
-            let current_state = if state.use_cache_branch && state.decoder_kv_cache.is_some() {
-                // 正常模式（使用 KV cache）：只输入新 token
-                // 注意：这里应该使用上一步生成的最后一个 token
-                let last_token = state.generated_ids.last().copied().unwrap_or(self.decoder_start_token_id);
-                DecoderState {
-                    input_ids: vec![last_token],  // 关键：只包含新 token
-                    generated_ids: state.generated_ids.clone(),
-                    decoder_kv_cache: state.decoder_kv_cache.take(),  // 使用历史 decoder KV cache
-                    encoder_kv_cache: state.encoder_kv_cache.take(),  // 使用历史 encoder KV cache
-                    use_cache_branch: true,  // 启用 KV 分支
-                }
-            } else {
-                // 第一步：使用完整历史序列
-                let current_generated_ids = state.generated_ids.clone();
-                DecoderState {
-                    input_ids: current_generated_ids.clone(),  // 使用完整历史序列
-                    generated_ids: current_generated_ids.clone(),
-                    decoder_kv_cache: None,
-                    encoder_kv_cache: None,  // 使用全零占位符
-                    use_cache_branch: false,  // 禁用 KV 分支
-                }
-            };
-            
-            println!("[DEBUG] Step {}: decoder_input_ids={:?} (length: {}), use_cache_branch={}, has_decoder_kv={}", 
-                step, current_state.input_ids, current_state.input_ids.len(), 
-                current_state.use_cache_branch, 
-                current_state.decoder_kv_cache.is_some());
-            
-            // 创建静态 encoder KV 占位符（根据 marian_nmt_interface_spec.md）
-            // 注意：由于 Value 不支持 Clone，我们需要在每次步骤中重新创建
-            // 但由于 encoder KV 是静态的（全零），每次创建相同的值
-            let static_encoder_kv = self.build_static_encoder_kv(encoder_seq_len)?;
-            let (logits, next_state) = self.decoder_step(
+        let generated = generate(
+            options,
+            &prefix,
+            self.eos_token_id,
+            max_new_tokens,
+            |generated_ids, state: &mut Option<DecoderState>| self.decode_next_token(
+                generated_ids,
                 &encoder_hidden_states,
                 &encoder_attention_mask,
-                current_state,
-                &static_encoder_kv,  // 传递静态 encoder KV 占位符
-            )?;
-
-            // decoder_step 返回的 logits 已经是最后一个位置的 Array1<f32>
-            // 所以直接使用即可
-            
-            // 选择概率最高的 token（贪婪解码）
-            let next_token_id = logits
-                .iter()
-                .enumerate()
-                .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
-                .map(|(idx, _)| idx as i64)
-                .ok_or_else(|| anyhow!("failed to find next token"))?;
-
-            // 检查是否生成 EOS
-            if next_token_id == self.eos_token_id {
-                println!("Generated EOS token at step {}", step);
-                break;
+                encoder_seq_len,
+                state,
+            ),
+            // ONNX Value 无法复制，分支从完整前缀重新计算
+            |_: &DecoderState| None,
+        )?;
+
+        let forced_len = forced_ids.len();
+        let mut target_ids = forced_ids;
+        target_ids.extend(generated.tokens);
+        println!("[NMT][translate] Generated IDs: {:?} (length: {})", target_ids, target_ids.len());
+
+        Ok(PrefixHypothesis {
+            // 源序列去掉 BOS 和 EOS token
+            source_len: source_ids.len().saturating_sub(2),
+            target_ids,
+            forced_len,
+            log_probs: generated.log_probs,
+        })
+    }
+
+    /// 单步解码：返回下一个 token 的 logits
+    /// 
+    /// `state` 为 None 时从完整前缀计算（禁用 KV 分支），否则复用上一步的 decoder KV cache，只输入最后一个 token。
+    fn decode_next_token(
+        &self,
+        generated_ids: &[i64],
+        encoder_hidden_states: &Array3<f32>,
+        encoder_attention_mask: &Array2<i64>,
+        encoder_seq_len: usize,
+        state: &mut Option<DecoderState>,
+    ) -> Result<Array1<f32>> {
+        // 准备当前步骤的 state（根据 marian_nmt_interface_spec.md：只维护 decoder KV cache）
+        let current_state = match state.take() {
+            // 正常模式（使用 KV cache）：只输入新 token
+            Some(mut cached) if cached.use_cache_branch && cached.decoder_kv_cache.is_some() => {
+                let last_token = generated_ids.last().copied().unwrap_or(self.decoder_start_token_id);
+                cached.input_ids = vec![last_token];  // 关键：只包含新 token
+                cached.generated_ids = generated_ids.to_vec();
+                cached
             }
+            // 第一步（或缓存不可用）：使用完整历史序列，禁用 KV 分支
+            _ => DecoderState {
+                input_ids: generated_ids.to_vec(),
+                generated_ids: generated_ids.to_vec(),
+                decoder_kv_cache: None,
+                encoder_kv_cache: None,  // 使用全零占位符
+                use_cache_branch: false,
+            },
+        };
+
+        println!("[DEBUG] decoder_input_ids={:?} (length: {}), use_cache_branch={}, has_decoder_kv={}, has_encoder_kv={}", 
+            current_state.input_ids, current_state.input_ids.len(), 
+            current_state.use_cache_branch, 
+            current_state.decoder_kv_cache.is_some(),
+            current_state.encoder_kv_cache.is_some());
+
+        // 创建静态 encoder KV 占位符（根据 marian_nmt_interface_spec.md）
+        // 注意：由于 Value 不支持 Clone，我们需要在每次步骤中重新创建
+        let static_encoder_kv = self.build_static_encoder_kv(encoder_seq_len)?;
+        let (logits, next_state) = self.decoder_step(
+            encoder_hidden_states,
+            encoder_attention_mask,
+            current_state,
+            &static_encoder_kv,
+        )?;
+
+        // 保存 decoder KV cache 供下一步使用
+        *state = Some(next_state);
+        Ok(logits)
+    }
 
-            // 更新 state：添加新 token，并保存 KV cache（根据 marian_nmt_interface_spec.md：只维护 decoder KV cache）
-            state.generated_ids.push(next_token_id);
-            state.decoder_kv_cache = next_state.decoder_kv_cache;  // 保存 decoder KV cache 供下一步使用
-            state.use_cache_branch = next_state.use_cache_branch;  // 更新 use_cache_branch 状态
-            
-            println!("[DEBUG] After Step {}: use_cache_branch={}, has_decoder_kv={}", 
-                step, state.use_cache_branch, 
-                state.decoder_kv_cache.is_some());
+    /// 流式翻译：逐 token 输出译文增量
+    /// 
+    /// 贪婪 / 采样解码在消费方拉取时才逐 token 解码；
+    /// Beam Search 需要完整解码后才能确定结果，解码结束后再逐 token 输出。
+    /// 
+    /// # Arguments
+    /// * `source_text` - 源文本
+    /// * `committed_prefix` - 已提交的译文前缀（None 表示从头解码）
+    /// * `options` - 解码参数
+    pub fn translate_stream_with_options<'a>(
+        &'a self,
+        source_text: &str,
+        committed_prefix: Option<&str>,
+        options: &DecodingOptions,
+    ) -> Result<TranslationStream<'a>> {
+        let detokenize = move |ids: &[i64]| Ok(self.tokenizer.decode(ids));
+
+        if options.is_beam_search() {
+            let hypothesis = self.translate_prefix(source_text, committed_prefix, options)?;
+            return Ok(precomputed_stream(hypothesis, detokenize));
         }
 
-        println!("[NMT][translate] Generated IDs: {:?} (length: {})", state.generated_ids, state.generated_ids.len());
+        let source_ids = self.tokenizer.encode(source_text, true);
+        let (encoder_hidden_states, encoder_attention_mask) = self.run_encoder(&source_ids)?;
+        let forced_ids = committed_prefix
+            .map(|text| self.tokenizer.encode(text, false))
+            .unwrap_or_default();
 
-        // 5. 使用 tokenizer 解码（跳过 BOS token）
-        let translated_ids: Vec<i64> = state.generated_ids.iter()
-            .skip(1)  // 跳过 BOS token
-            .copied()
-            .collect();
-        let translated_text = self.tokenizer.decode(&translated_ids);
-        println!("[NMT][translate] Translated text: '{}'", translated_text);
+        let max_new_tokens = options
+            .max_new_tokens(source_ids.len(), self.max_length.min(128))
+            .saturating_sub(forced_ids.len());
+        let encoder_seq_len = encoder_hidden_states.shape()[1];
+        let mut prefix = vec![self.decoder_start_token_id];
+        prefix.extend_from_slice(&forced_ids);
 
-        Ok(translated_text)
+        let decoder = StepDecoder::new(options, &prefix, self.eos_token_id, max_new_tokens);
+        let step = move |generated_ids: &[i64], state: &mut Option<DecoderState>| {
+            self.decode_next_token(
+                generated_ids,
+                &encoder_hidden_states,
+                &encoder_attention_mask,
+                encoder_seq_len,
+                state,
+            )
+        };
+        Ok(delta_stream(TokenSource::Step { decoder, step }, forced_ids, detokenize))
     }
 }
 
@@ -142,7 +221,8 @@ impl NmtIncremental for MarianNmtOnnx {
         
         // 由于 self.translate() 是同步方法，但 trait 要求是 async，
         // 我们直接调用同步方法（虽然会阻塞当前任务，但对于翻译这种 CPU 密集型操作是合理的）
-        let translated = self.translate(&source_text)
+        let options = request.decoding.clone().unwrap_or_default();
+        let hypothesis = self.translate_prefix(&source_text, committed_prefix(&request), &options)
             .map_err(|e| {
                 // 将 anyhow::Error 转换为 EngineError
                 // String 可以转换为 Cow<'static, str>
@@ -150,17 +230,31 @@ impl NmtIncremental for MarianNmtOnnx {
                 EngineError::new(error_msg)
             })?;
 
+        // wait-k：只提交源前缀已“读够”的目标 token，其余为不稳定的尾部
+        let (committed_len, is_stable) = hypothesis.commit(&request);
+        let translated = self.tokenizer.decode(&hypothesis.target_ids);
+        let committed = self.tokenizer.decode(&hypothesis.target_ids[..committed_len]);
+        let quality_metrics = hypothesis.quality_metrics();
+
         Ok(TranslationResponse {
             translated_text: translated,
             speaker_id: None,
             source_text: None,
             source_audio_duration_ms: None,
             source_language: None,  // Marian ONNX 可能无法提供源语言信息
-            quality_metrics: None,
-            is_stable: request.wait_k.is_none() || request.wait_k == Some(0),
+            quality_metrics,
+            committed_text: Some(committed),
+            provider: None,
+            is_stable,
         })
     }
 
+    async fn translate_stream<'a>(&'a self, request: TranslationRequest) -> EngineResult<TranslationStream<'a>> {
+        let options = request.decoding.clone().unwrap_or_default();
+        self.translate_stream_with_options(&request.transcript.text, committed_prefix(&request), &options)
+            .map_err(|e| EngineError::new(format!("Translation failed: {}", e)))
+    }
+
     async fn finalize(&self) -> EngineResult<()> {
         // ONNX 会话会在对象销毁时自动清理
         Ok(())
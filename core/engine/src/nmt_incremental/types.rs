@@ -1,5 +1,8 @@
+use futures::stream::BoxStream;
 use serde::{Deserialize, Serialize};
+use crate::error::EngineResult;
 use crate::types::PartialTranscript;
+use super::decoding::DecodingOptions;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranslationRequest {
@@ -7,6 +10,16 @@ pub struct TranslationRequest {
     pub target_language: String,
     pub wait_k: Option<u8>,
     pub speaker_id: Option<String>,
+    /// 解码参数（None 表示使用后端默认的贪婪解码）
+    #[serde(default)]
+    pub decoding: Option<DecodingOptions>,
+    /// 已提交的译文前缀（wait-k 同传时由调用方回传上一次响应的 `committed_text`，
+    /// 后端将其作为强制解码前缀，保证已提交部分不会改变）
+    #[serde(default)]
+    pub committed_prefix: Option<String>,
+    /// 源语言（None 表示未知，由后端自行推断）
+    #[serde(default)]
+    pub source_language: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,8 +31,40 @@ pub struct TranslationResponse {
     pub source_audio_duration_ms: Option<u64>,
     pub source_language: Option<String>,  // 源语言（用于语速换算）
     pub quality_metrics: Option<TranslationQualityMetrics>,
+    /// 已提交的译文前缀（wait-k 模式下 `is_stable` 为 false 时，`translated_text` 中只有这部分不会再改变；
+    /// None 表示后端不支持前缀翻译）
+    #[serde(default)]
+    pub committed_text: Option<String>,
+    /// 提供本次翻译的后端（如 "local-m2m100"；None 表示未记录）
+    #[serde(default)]
+    pub provider: Option<String>,
 }
 
+/// 流式翻译的译文增量
+/// 
+/// 按顺序拼接所有增量的 `text` 即为完整译文（增量只追加、不回退；可能变化的末尾会暂缓输出）。
+/// 后端提供 `full_text` 时以它为准。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslationDelta {
+    /// 新增的译文文本（可能为空，例如子词尚未组成完整字符，或最后的结束标记）
+    pub text: String,
+    /// 对应的目标 token（None 表示该增量不是单个 token，例如强制前缀或不支持流式的后端一次性返回的译文）
+    pub token_id: Option<i64>,
+    /// 该 token 的概率（None 表示后端无法提供）
+    pub probability: Option<f32>,
+    /// 是否为最后一个增量
+    pub is_last: bool,
+    /// 到本增量为止的完整译文（对全部 token 重新解码的结果，None 表示后端未提供）
+    #[serde(default)]
+    pub full_text: Option<String>,
+    /// 提供本次翻译的后端（None 表示未记录）
+    #[serde(default)]
+    pub provider: Option<String>,
+}
+
+/// 流式翻译结果：按顺序产出译文增量
+pub type TranslationStream<'a> = BoxStream<'a, EngineResult<TranslationDelta>>;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranslationQualityMetrics {
     pub perplexity: Option<f32>,
@@ -27,3 +72,38 @@ pub struct TranslationQualityMetrics {
     pub min_probability: Option<f32>,
 }
 
+impl TranslationQualityMetrics {
+    /// 困惑度超过该值视为低置信度
+    pub const MAX_PERPLEXITY: f32 = 100.0;
+    /// 平均 token 概率低于该值视为低置信度
+    pub const MIN_AVG_PROBABILITY: f32 = 0.05;
+    /// 最小 token 概率低于该值视为低置信度
+    pub const MIN_TOKEN_PROBABILITY: f32 = 0.001;
+
+    /// 根据生成 token 的 log 概率计算质量指标（没有生成任何 token 时返回 None）
+    pub fn from_log_probs(log_probs: &[f32]) -> Option<Self> {
+        if log_probs.is_empty() {
+            return None;
+        }
+        let count = log_probs.len() as f32;
+        let mean_log_prob = log_probs.iter().sum::<f32>() / count;
+        let avg_probability = log_probs.iter().map(|log_prob| log_prob.exp()).sum::<f32>() / count;
+        let min_probability = log_probs
+            .iter()
+            .map(|log_prob| log_prob.exp())
+            .fold(f32::INFINITY, f32::min);
+        Some(Self {
+            perplexity: Some((-mean_log_prob).exp()),
+            avg_probability: Some(avg_probability),
+            min_probability: Some(min_probability),
+        })
+    }
+
+    /// 是否为低置信度翻译（任一指标超出阈值）
+    pub fn is_low_confidence(&self) -> bool {
+        self.perplexity.is_some_and(|p| p > Self::MAX_PERPLEXITY)
+            || self.avg_probability.is_some_and(|p| p < Self::MIN_AVG_PROBABILITY)
+            || self.min_probability.is_some_and(|p| p < Self::MIN_TOKEN_PROBABILITY)
+    }
+}
+
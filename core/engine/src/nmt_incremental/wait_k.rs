@@ -0,0 +1,131 @@
+// wait-k 同传策略（prefix-to-prefix 翻译）
+//
+// 读入 `s` 个源 token 后，最多提交 `s - k + 1` 个目标 token；源句结束（`is_final`）后全部提交。
+// 已提交的目标前缀由调用方通过 `TranslationRequest::committed_prefix` 回传，
+// 后端将其作为强制解码前缀，保证已提交部分在后续请求中不会被改写。
+
+use super::types::{TranslationQualityMetrics, TranslationRequest};
+
+/// wait-k 策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WaitKPolicy {
+    k: usize,
+}
+
+impl WaitKPolicy {
+    /// 创建 wait-k 策略（k 必须大于 0）
+    pub fn new(k: usize) -> Option<Self> {
+        if k == 0 {
+            None
+        } else {
+            Some(Self { k })
+        }
+    }
+
+    /// 从请求参数创建（None 或 0 表示不启用 wait-k，整句翻译）
+    pub fn from_request(wait_k: Option<u8>) -> Option<Self> {
+        wait_k.and_then(|k| Self::new(k as usize))
+    }
+
+    /// 等待的源 token 数
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// 计算可提交的目标 token 数
+    ///
+    /// # Arguments
+    /// * `source_len` - 已读入的源 token 数（不含特殊 token）
+    /// * `hypothesis_len` - 当前译文假设的 token 数（包含强制前缀）
+    /// * `forced_len` - 强制前缀（之前已提交）的 token 数，已提交的 token 不会被撤回
+    /// * `is_final` - 源句是否已结束
+    pub fn committed_len(&self, source_len: usize, hypothesis_len: usize, forced_len: usize, is_final: bool) -> usize {
+        if is_final {
+            return hypothesis_len;
+        }
+        let allowed = (source_len + 1).saturating_sub(self.k);
+        allowed.max(forced_len).min(hypothesis_len)
+    }
+}
+
+/// 前缀翻译的 token 级结果
+pub(crate) struct PrefixHypothesis {
+    /// 已读入的源 token 数（不含特殊 token）
+    pub source_len: usize,
+    /// 译文 token（包含强制前缀，不含特殊 token）
+    pub target_ids: Vec<i64>,
+    /// 强制前缀的 token 数
+    pub forced_len: usize,
+    /// 新生成 token（`target_ids[forced_len..]`）的 log 概率
+    pub log_probs: Vec<f32>,
+}
+
+impl PrefixHypothesis {
+    /// 按请求中的 wait-k 参数划分已提交部分
+    ///
+    /// # Returns
+    /// (已提交的 token 数, 是否全部提交)
+    pub(crate) fn commit(&self, request: &TranslationRequest) -> (usize, bool) {
+        match WaitKPolicy::from_request(request.wait_k) {
+            Some(policy) => {
+                let committed = policy.committed_len(
+                    self.source_len,
+                    self.target_ids.len(),
+                    self.forced_len,
+                    request.transcript.is_final,
+                );
+                (committed, committed == self.target_ids.len())
+            }
+            None => (self.target_ids.len(), true),
+        }
+    }
+
+    /// 新生成部分的质量指标（强制前缀不参与计算）
+    pub(crate) fn quality_metrics(&self) -> Option<TranslationQualityMetrics> {
+        TranslationQualityMetrics::from_log_probs(&self.log_probs)
+    }
+}
+
+/// 取出请求中非空的已提交前缀
+pub(crate) fn committed_prefix(request: &TranslationRequest) -> Option<&str> {
+    request
+        .committed_prefix
+        .as_deref()
+        .map(str::trim)
+        .filter(|prefix| !prefix.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_disables_wait_k() {
+        assert_eq!(WaitKPolicy::from_request(None), None);
+        assert_eq!(WaitKPolicy::from_request(Some(0)), None);
+        assert_eq!(WaitKPolicy::from_request(Some(3)).map(|p| p.k()), Some(3));
+    }
+
+    #[test]
+    fn test_commits_one_target_token_per_source_token_after_k() {
+        let policy = WaitKPolicy::new(3).unwrap();
+        assert_eq!(policy.committed_len(2, 10, 0, false), 0);
+        assert_eq!(policy.committed_len(3, 10, 0, false), 1);
+        assert_eq!(policy.committed_len(5, 10, 0, false), 3);
+        // 不超过当前假设长度
+        assert_eq!(policy.committed_len(20, 4, 0, false), 4);
+    }
+
+    #[test]
+    fn test_committed_tokens_are_never_retracted() {
+        let policy = WaitKPolicy::new(3).unwrap();
+        // ASR 修正导致源前缀变短时，之前已提交的 3 个 token 仍然保留
+        assert_eq!(policy.committed_len(2, 6, 3, false), 3);
+    }
+
+    #[test]
+    fn test_final_source_commits_everything() {
+        let policy = WaitKPolicy::new(5).unwrap();
+        assert_eq!(policy.committed_len(1, 7, 0, true), 7);
+    }
+}
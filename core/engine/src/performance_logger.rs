@@ -5,6 +5,8 @@
 use serde::{Deserialize, Serialize};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::nmt_incremental::TranslationQualityMetrics;
+
 /// 性能日志条目
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PerformanceLog {
@@ -35,6 +37,18 @@ pub struct PerformanceLog {
     /// 译文长度
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tgt_text_len: Option<usize>,
+    /// 解码困惑度
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub perplexity: Option<f32>,
+    /// 平均 token 概率
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_probability: Option<f32>,
+    /// 最小 token 概率
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_probability: Option<f32>,
+    /// 提供翻译的 NMT 后端
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nmt_provider: Option<String>,
 }
 
 impl PerformanceLog {
@@ -69,14 +83,42 @@ impl PerformanceLog {
             suspect_translation: None,
             src_text_len: None,
             tgt_text_len: None,
+            perplexity: None,
+            avg_probability: None,
+            min_probability: None,
+            nmt_provider: None,
         }
     }
 
     /// 检查是否为可疑翻译
     pub fn check_suspect_translation(&mut self, src_text: &str, tgt_text: &str) {
+        self.check_suspect_translation_with_metrics(src_text, tgt_text, None);
+    }
+
+    /// 检查是否为可疑翻译（结合解码器的质量指标）
+    /// 
+    /// 除长度和字符比例规则外，解码器置信度低（见 `TranslationQualityMetrics::is_low_confidence`）也判定为可疑
+    pub fn check_suspect_translation_with_metrics(
+        &mut self,
+        src_text: &str,
+        tgt_text: &str,
+        metrics: Option<&TranslationQualityMetrics>,
+    ) {
         self.src_text_len = Some(src_text.len());
         self.tgt_text_len = Some(tgt_text.len());
 
+        if let Some(metrics) = metrics {
+            self.perplexity = metrics.perplexity;
+            self.avg_probability = metrics.avg_probability;
+            self.min_probability = metrics.min_probability;
+
+            // 规则 0: 解码器置信度低
+            if metrics.is_low_confidence() {
+                self.suspect_translation = Some(true);
+                return;
+            }
+        }
+
         // 规则 1: 原文长度 > 20 字符，而译文长度 < 3 字符
         if src_text.len() > 20 && tgt_text.len() < 3 {
             self.suspect_translation = Some(true);
@@ -186,10 +228,12 @@ impl PerformanceLogger {
         if self.log_suspect {
             if let Some(true) = log.suspect_translation {
                 eprintln!(
-                    "[WARN] Suspect translation detected: id={}, src_len={}, tgt_len={}",
+                    "[WARN] Suspect translation detected: id={}, src_len={}, tgt_len={}, perplexity={:?}, avg_prob={:?}",
                     log.id,
                     log.src_text_len.unwrap_or(0),
-                    log.tgt_text_len.unwrap_or(0)
+                    log.tgt_text_len.unwrap_or(0),
+                    log.perplexity,
+                    log.avg_probability
                 );
             }
         }
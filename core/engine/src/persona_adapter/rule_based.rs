@@ -143,6 +143,8 @@ impl PersonaAdapter for RuleBasedPersonaAdapter {
             text: personalized_text,
             speaker_id: transcript.speaker_id,
             language: transcript.language,
+            segments: transcript.segments,
+            language_probabilities: transcript.language_probabilities,
         })
     }
 }
@@ -7,6 +7,8 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
+use crate::tts_streaming::is_ssml;
+
 /// 文本后处理器
 pub struct TextPostProcessor {
     terms_map: HashMap<String, String>,
@@ -60,8 +62,10 @@ impl TextPostProcessor {
         // 2. 术语替换
         result = self.replace_terms(&result);
 
-        // 3. 句号兜底
-        result = self.add_punctuation_if_needed(&result, target_lang);
+        // 3. 句号兜底（SSML 末尾是 </speak>，补在后面会变成根元素之外的文字）
+        if !is_ssml(&result) {
+            result = self.add_punctuation_if_needed(&result, target_lang);
+        }
 
         result
     }
@@ -155,5 +159,14 @@ mod tests {
         assert_eq!(processor.add_punctuation_if_needed("hello.", "en"), "hello.");
         assert_eq!(processor.add_punctuation_if_needed("测试。", "zh"), "测试。");
     }
+
+    #[test]
+    fn test_ssml_is_not_punctuated() {
+        let processor = TextPostProcessor::default();
+        let ssml = r#"<speak>你好<break time="500ms"/>世界</speak>"#;
+        assert_eq!(processor.process(ssml, "zh"), ssml);
+        assert!(crate::tts_streaming::parse_ssml(&processor.process(ssml, "zh"), "zh").is_ok());
+        assert_eq!(processor.process("<speak>hello</speak>", "en"), "<speak>hello</speak>");
+    }
 }
 
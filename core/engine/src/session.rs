@@ -0,0 +1,288 @@
+//! 会话（Session）模块
+//!
+//! 每个 WebSocket 连接 / S2S 请求对应一个独立的会话。
+//! 会话持有所有与连接相关的可变状态，避免多个客户端之间互相干扰：
+//! - 语言对（源语言 / 目标语言），以及双语会话中按每句检测到的语言自动选择的翻译方向
+//! - NMT 解码参数（延迟 / 质量权衡）
+//! - wait-k 同传参数和当前句已提交的译文前缀
+//! - TTS 输出格式（采样率、声道数、封装方式）
+//! - 音频缓冲区（连续模式）
+//! - VAD 状态（静音计数、自适应语速等）
+//! - ASR 帧缓冲区和流式识别状态（部分结果计时、滑动窗口、LocalAgreement 历史）
+//! - 说话者识别状态和说话者 → 音色映射
+//!
+//! 模型、HTTP 客户端等无状态（或只读）的组件仍由 `CoreEngine` 共享。
+
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::asr_streaming::AsrSessionState;
+use crate::audio_buffer::AudioBufferManager;
+use crate::error::EngineResult;
+use crate::language_registry;
+use crate::nmt_incremental::DecodingOptions;
+use crate::speaker_identifier::SpeakerIdentifier;
+use crate::speaker_voice_mapper::SpeakerVoiceMapper;
+use crate::tts_streaming::TtsAudioFormat;
+use crate::types::AudioFrame;
+use crate::vad::VoiceActivityDetector;
+
+/// 客户端用于表示"自动检测源语言"的语言代码
+pub const AUTO_LANGUAGE: &str = "auto";
+
+/// 将客户端传入的源语言转换为会话的源语言（"auto" 或空字符串表示自动检测）
+pub fn parse_source_language(source_language: Option<String>) -> Option<String> {
+    source_language.filter(|lang| !lang.is_empty() && !lang.eq_ignore_ascii_case(AUTO_LANGUAGE))
+}
+
+/// 会话语言对
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionLanguages {
+    /// 源语言（None 表示由 ASR 自动检测）
+    pub source_language: Option<String>,
+    /// 目标语言
+    pub target_language: String,
+}
+
+/// 单个连接的会话状态
+///
+/// 通过 `CoreEngine::create_session`（或 `create_session_for_user`）创建，并传递给 `CoreEngine::process_audio_frame`。
+pub struct Session {
+    id: String,
+    /// 用户 ID（用于按用户恢复/保存 VAD 自适应状态，None 表示匿名会话）
+    user_id: Option<String>,
+    languages: RwLock<SessionLanguages>,
+    /// 双语会话的两种语言（例如中英会议，双方都可能发言）
+    bilingual_languages: RwLock<Option<(String, String)>>,
+    /// 最近一句话检测到的语言（自动检测模式）
+    detected_language: RwLock<Option<String>>,
+    /// NMT 解码参数（None 表示使用 NMT 后端默认值）
+    decoding_options: RwLock<Option<DecodingOptions>>,
+    /// wait-k 同传参数（None 表示只在句子结束时翻译）
+    wait_k: RwLock<Option<u8>>,
+    /// 会话词表（产品名、专业术语等）：作为 ASR 热词，并用于纠正识别文本
+    vocabulary: RwLock<Vec<String>>,
+    /// 客户端请求的 TTS 输出格式（None 表示使用引擎默认格式）
+    tts_output_format: RwLock<Option<TtsAudioFormat>>,
+    /// 当前句已提交的译文前缀（wait-k 模式，句子结束时清空）
+    pub(crate) committed_translation: Mutex<Option<String>>,
+    /// 会话独立的 VAD（无状态的 VAD 实现会与引擎共享同一实例）
+    pub(crate) vad: Arc<dyn VoiceActivityDetector>,
+    /// 会话独立的音频缓冲区（仅连续模式）
+    pub(crate) audio_buffer: Option<Arc<AudioBufferManager>>,
+    /// 会话独立的 ASR 帧缓冲区（非连续模式）
+    pub(crate) asr_buffer: Mutex<Vec<AudioFrame>>,
+    /// 会话独立的 ASR 流式识别状态（推理前恢复到共享的 ASR 实例，推理后取回）
+    pub(crate) asr_state: Mutex<Option<AsrSessionState>>,
+    /// 会话独立的说话者识别器
+    pub(crate) speaker_identifier: Option<Arc<dyn SpeakerIdentifier>>,
+    /// 会话独立的说话者 → 音色映射
+    pub(crate) speaker_voice_mapper: Option<Arc<SpeakerVoiceMapper>>,
+}
+
+impl Session {
+    pub(crate) fn new(
+        id: String,
+        user_id: Option<String>,
+        languages: SessionLanguages,
+        vad: Arc<dyn VoiceActivityDetector>,
+        audio_buffer: Option<Arc<AudioBufferManager>>,
+        speaker_identifier: Option<Arc<dyn SpeakerIdentifier>>,
+        speaker_voice_mapper: Option<Arc<SpeakerVoiceMapper>>,
+    ) -> Self {
+        Self {
+            id,
+            user_id,
+            languages: RwLock::new(languages),
+            bilingual_languages: RwLock::new(None),
+            detected_language: RwLock::new(None),
+            decoding_options: RwLock::new(None),
+            wait_k: RwLock::new(None),
+            vocabulary: RwLock::new(Vec::new()),
+            tts_output_format: RwLock::new(None),
+            committed_translation: Mutex::new(None),
+            vad,
+            audio_buffer,
+            asr_buffer: Mutex::new(Vec::new()),
+            asr_state: Mutex::new(None),
+            speaker_identifier,
+            speaker_voice_mapper,
+        }
+    }
+
+    /// 会话 ID（用于在 EventBus 上区分不同会话的事件）
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// 用户 ID（匿名会话返回 None）
+    pub fn user_id(&self) -> Option<&str> {
+        self.user_id.as_deref()
+    }
+
+    /// 获取当前语言对
+    pub fn languages(&self) -> SessionLanguages {
+        self.languages.read().unwrap().clone()
+    }
+
+    /// 获取源语言
+    pub fn source_language(&self) -> Option<String> {
+        self.languages.read().unwrap().source_language.clone()
+    }
+
+    /// 获取目标语言
+    pub fn target_language(&self) -> String {
+        self.languages.read().unwrap().target_language.clone()
+    }
+
+    /// 更新源语言（None 或 "auto" 表示由 ASR 自动检测）
+    pub fn set_source_language(&self, source_language: Option<String>) {
+        self.languages.write().unwrap().source_language = parse_source_language(source_language);
+    }
+
+    /// 更新目标语言
+    pub fn set_target_language(&self, target_language: String) {
+        self.languages.write().unwrap().target_language = target_language;
+    }
+
+    /// 同时更新源语言和目标语言
+    pub fn set_languages(&self, source_language: Option<String>, target_language: String) {
+        let mut languages = self.languages.write().unwrap();
+        languages.source_language = parse_source_language(source_language);
+        languages.target_language = target_language;
+    }
+
+    /// 获取双语会话的两种语言
+    pub fn bilingual_languages(&self) -> Option<(String, String)> {
+        self.bilingual_languages.read().unwrap().clone()
+    }
+
+    /// 设置双语会话（None 表示关闭）
+    ///
+    /// 双语会话中 ASR 在这两种语言之间自动检测每句话的语言，并把目标语言切换为另一种语言，
+    /// 例如中英会议中说中文时译为英文，说英文时译为中文。
+    pub fn set_bilingual_languages(&self, languages: Option<(String, String)>) {
+        *self.bilingual_languages.write().unwrap() = languages;
+    }
+
+    /// 最近一句话检测到的语言
+    pub fn detected_language(&self) -> Option<String> {
+        self.detected_language.read().unwrap().clone()
+    }
+
+    /// ASR 使用的语言（双语会话和自动检测模式下为 None）
+    pub(crate) fn asr_language_hint(&self) -> Option<String> {
+        if self.bilingual_languages().is_some() {
+            None
+        } else {
+            self.source_language()
+        }
+    }
+
+    /// ASR 自动检测语言时的候选范围（仅双语会话限制为这两种语言）
+    pub(crate) fn asr_language_candidates(&self) -> Vec<String> {
+        self.bilingual_languages()
+            .map(|(a, b)| vec![a, b])
+            .unwrap_or_default()
+    }
+
+    /// 记录 ASR 检测到的语言；双语会话中据此切换目标语言
+    ///
+    /// # Returns
+    /// 目标语言被切换时返回新的目标语言
+    pub(crate) fn apply_detected_language(&self, language: &str) -> Option<String> {
+        if language.is_empty() || language == "unknown" {
+            return None;
+        }
+        let registry = language_registry::global();
+        let detected = registry.canonical_code(language).unwrap_or(language).to_string();
+        *self.detected_language.write().unwrap() = Some(detected.clone());
+
+        let (a, b) = self.bilingual_languages()?;
+        let target = if registry.canonical_code(&a) == Some(detected.as_str()) {
+            b
+        } else if registry.canonical_code(&b) == Some(detected.as_str()) {
+            a
+        } else {
+            return None;
+        };
+        let mut languages = self.languages.write().unwrap();
+        if languages.target_language == target {
+            return None;
+        }
+        languages.target_language = target.clone();
+        Some(target)
+    }
+
+    /// 获取 NMT 解码参数
+    pub fn decoding_options(&self) -> Option<DecodingOptions> {
+        self.decoding_options.read().unwrap().clone()
+    }
+
+    /// 更新 NMT 解码参数（例如对质量要求高的会话使用 Beam Search）
+    pub fn set_decoding_options(&self, decoding_options: Option<DecodingOptions>) {
+        *self.decoding_options.write().unwrap() = decoding_options;
+    }
+
+    /// 获取 wait-k 同传参数
+    pub fn wait_k(&self) -> Option<u8> {
+        *self.wait_k.read().unwrap()
+    }
+
+    /// 更新 wait-k 同传参数（Some(k) 且 k > 0 时，说话过程中会根据 ASR 部分结果发布部分翻译）
+    pub fn set_wait_k(&self, wait_k: Option<u8>) {
+        *self.wait_k.write().unwrap() = wait_k.filter(|&k| k > 0);
+    }
+
+    /// 获取会话词表
+    pub fn vocabulary(&self) -> Vec<String> {
+        self.vocabulary.read().unwrap().clone()
+    }
+
+    /// 更新会话词表（去除空白和重复项；空列表表示关闭热词和纠错）
+    pub fn set_vocabulary(&self, vocabulary: Vec<String>) {
+        let mut words: Vec<String> = Vec::new();
+        for word in vocabulary {
+            let word = word.trim().to_string();
+            if !word.is_empty() && !words.contains(&word) {
+                words.push(word);
+            }
+        }
+        *self.vocabulary.write().unwrap() = words;
+    }
+
+    /// 获取客户端请求的 TTS 输出格式
+    pub fn tts_output_format(&self) -> Option<TtsAudioFormat> {
+        *self.tts_output_format.read().unwrap()
+    }
+
+    /// 更新 TTS 输出格式（None 表示使用引擎默认格式）
+    pub fn set_tts_output_format(&self, format: Option<TtsAudioFormat>) {
+        *self.tts_output_format.write().unwrap() = format;
+    }
+
+    /// 获取当前句已提交的译文前缀
+    pub fn committed_translation(&self) -> Option<String> {
+        self.committed_translation.lock().unwrap().clone()
+    }
+
+    /// 取出当前句已提交的译文前缀（句子结束时调用，下一句从头开始）
+    pub(crate) fn take_committed_translation(&self) -> Option<String> {
+        self.committed_translation.lock().unwrap().take()
+    }
+
+    /// 重置会话状态（清空缓冲区、重置 VAD 和说话者识别），语言对保持不变
+    pub async fn reset(&self) -> EngineResult<()> {
+        self.asr_buffer.lock().unwrap().clear();
+        *self.asr_state.lock().unwrap() = None;
+        *self.committed_translation.lock().unwrap() = None;
+        *self.detected_language.write().unwrap() = None;
+        if let Some(ref buffer) = self.audio_buffer {
+            buffer.clear().await;
+        }
+        self.vad.reset().await?;
+        if let Some(ref identifier) = self.speaker_identifier {
+            identifier.reset().await?;
+        }
+        Ok(())
+    }
+}
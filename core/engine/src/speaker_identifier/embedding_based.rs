@@ -422,6 +422,20 @@ impl SpeakerIdentifier for EmbeddingBasedSpeakerIdentifier {
             self.similarity_threshold
         )
     }
+    
+    fn fork_for_session(&self) -> Option<Arc<dyn SpeakerIdentifier>> {
+        // HTTP 客户端和识别模式共享（模式切换对所有会话生效），说话者库按会话独立
+        Some(Arc::new(Self {
+            embedding_client: self.embedding_client.clone(),
+            similarity_threshold: self.similarity_threshold,
+            mode: Arc::clone(&self.mode),
+            speaker_embeddings: Arc::new(RwLock::new(HashMap::new())),
+            next_speaker_id: Arc::new(RwLock::new(1)),
+            speaker_reference_audio_segments: Arc::new(RwLock::new(HashMap::new())),
+            min_merged_audio_samples: self.min_merged_audio_samples,
+            single_user_speaker_id: Arc::new(RwLock::new(None)),
+        }))
+    }
 }
 
 #[cfg(test)]
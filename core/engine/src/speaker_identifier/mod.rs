@@ -8,6 +8,8 @@ mod vad_based;
 mod embedding_based;
 mod speaker_embedding_client;
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
@@ -109,5 +111,12 @@ pub trait SpeakerIdentifier: Send + Sync {
     
     /// 获取识别器信息
     fn get_info(&self) -> String;
+    
+    /// 为新的会话创建独立的识别器实例（说话者库不在会话之间共享）
+    /// 
+    /// 默认返回 None，表示所有会话共享同一个识别器
+    fn fork_for_session(&self) -> Option<Arc<dyn SpeakerIdentifier>> {
+        None
+    }
 }
 
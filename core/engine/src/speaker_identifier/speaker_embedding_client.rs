@@ -25,6 +25,7 @@ impl Default for SpeakerEmbeddingClientConfig {
 }
 
 /// Speaker Embedding HTTP 客户端
+#[derive(Clone)]
 pub struct SpeakerEmbeddingClient {
     client: reqwest::Client,
     config: SpeakerEmbeddingClientConfig,
@@ -156,6 +156,13 @@ impl SpeakerIdentifier for VadBasedSpeakerIdentifier {
             self.max_same_speaker_interval_ms
         )
     }
+    
+    fn fork_for_session(&self) -> Option<Arc<dyn SpeakerIdentifier>> {
+        Some(Arc::new(Self::new(
+            self.min_switch_interval_ms,
+            self.max_same_speaker_interval_ms,
+        )))
+    }
 }
 
 #[cfg(test)]
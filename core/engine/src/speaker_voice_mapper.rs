@@ -195,6 +195,11 @@ impl SpeakerVoiceMapper {
         let mapping = self.mapping.read().await;
         mapping.len()
     }
+    
+    /// 获取可用 voice 列表
+    pub fn available_voices(&self) -> &[String] {
+        &self.available_voices
+    }
 }
 
 #[cfg(test)]
@@ -212,6 +212,47 @@ impl TextSegmenter {
     }
 }
 
+/// 流式分句器
+/// 
+/// 译文逐段到达时（例如 NMT 逐 token 输出），每凑齐一个完整分句就输出，
+/// 分句规则与 `TextSegmenter::segment_with_pause_type` 一致。
+/// 末尾的分句要等后续文本到达（或调用 `finish`）才能确定已经完整（例如 "1." 之后可能还有 "5"）。
+pub struct StreamingSegmenter<'a> {
+    segmenter: &'a TextSegmenter,
+    buffer: String,
+}
+
+impl<'a> StreamingSegmenter<'a> {
+    pub fn new(segmenter: &'a TextSegmenter) -> Self {
+        Self {
+            segmenter,
+            buffer: String::new(),
+        }
+    }
+
+    /// 追加文本，返回新凑齐的完整分句
+    pub fn push(&mut self, text: &str) -> Vec<TextSegment> {
+        self.buffer.push_str(text);
+        let mut segments = self.segmenter.segment_with_pause_type(&self.buffer);
+        if segments.len() < 2 {
+            return Vec::new();
+        }
+        // 最后一个分句可能还不完整，保留到下次（保留原始文本，末尾空白决定与后续文本如何拼接）
+        if let Some(tail) = segments.pop() {
+            self.buffer = match self.buffer.rfind(&tail.text) {
+                Some(pos) => self.buffer[pos..].to_string(),
+                None => tail.text,
+            };
+        }
+        segments
+    }
+
+    /// 文本结束，返回剩余的分句
+    pub fn finish(self) -> Vec<TextSegment> {
+        self.segmenter.segment_with_pause_type(&self.buffer)
+    }
+}
+
 impl Default for TextSegmenter {
     fn default() -> Self {
         Self {
@@ -331,4 +372,43 @@ mod tests {
         assert_eq!(segments[1].text, "It works well.");
         assert_eq!(segments[1].pause_type, PauseType::SentenceEnd);
     }
+
+    #[test]
+    fn test_streaming_segmenter_keeps_word_boundaries() {
+        let segmenter = TextSegmenter::new_with_comma_splitting(50);
+        let mut streaming = StreamingSegmenter::new(&segmenter);
+
+        let mut streamed: Vec<String> = Vec::new();
+        for delta in ["Hello,", " how ", "are", " you?"] {
+            streamed.extend(streaming.push(delta).into_iter().map(|s| s.text));
+        }
+        streamed.extend(streaming.finish().into_iter().map(|s| s.text));
+        assert_eq!(streamed, vec!["Hello,", "how are you?"]);
+    }
+
+    #[test]
+    fn test_streaming_segmenter_matches_batch_segmentation() {
+        let segmenter = TextSegmenter::new_with_comma_splitting(50);
+        let text = "Hello, world. The price is 3.14 dollars. It's cheap!";
+        let mut streaming = StreamingSegmenter::new(&segmenter);
+
+        let mut streamed = Vec::new();
+        for ch in text.chars() {
+            for segment in streaming.push(&ch.to_string()) {
+                streamed.push((segment.text, segment.pause_type));
+            }
+        }
+        // 逐字符输入时，完整分句应在文本结束前就输出
+        assert_eq!(streamed.len(), 3);
+        for segment in streaming.finish() {
+            streamed.push((segment.text, segment.pause_type));
+        }
+
+        let batch: Vec<(String, PauseType)> = segmenter
+            .segment_with_pause_type(text)
+            .into_iter()
+            .map(|s| (s.text, s.pause_type))
+            .collect();
+        assert_eq!(streamed, batch);
+    }
 }
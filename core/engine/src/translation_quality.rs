@@ -3,6 +3,7 @@
 //! 用于检测和修复翻译质量问题
 
 use crate::error::EngineResult;
+use crate::nmt_incremental::TranslationQualityMetrics;
 
 /// 翻译质量检查器
 pub struct TranslationQualityChecker {
@@ -26,6 +27,28 @@ impl TranslationQualityChecker {
     /// # Returns
     /// 修复后的译文
     pub fn check_and_fix(&self, src_text: &str, tgt_text: &str, target_lang: &str) -> String {
+        self.check_and_fix_with_metrics(src_text, tgt_text, target_lang, None)
+    }
+
+    /// 检查并修复翻译质量（结合解码器的质量指标）
+    /// 
+    /// 解码器置信度低（困惑度过高、token 概率过低）时，即使文本本身没有异常也按可疑翻译处理
+    /// 
+    /// # Arguments
+    /// * `src_text` - 原文
+    /// * `tgt_text` - 译文
+    /// * `target_lang` - 目标语言
+    /// * `metrics` - 解码器给出的质量指标（None 表示后端无法提供，只做启发式检查）
+    /// 
+    /// # Returns
+    /// 修复后的译文
+    pub fn check_and_fix_with_metrics(
+        &self,
+        src_text: &str,
+        tgt_text: &str,
+        target_lang: &str,
+        metrics: Option<&TranslationQualityMetrics>,
+    ) -> String {
         if !self.enabled {
             return tgt_text.to_string();
         }
@@ -35,8 +58,12 @@ impl TranslationQualityChecker {
         // 1. 删除重复序列
         result = self.remove_repetitive_sequences(&result);
 
-        // 2. 检查可疑字符比例
-        if self.is_suspicious_quality(&result, target_lang) {
+        // 2. 检查可疑字符比例和解码器置信度
+        let low_confidence = metrics.is_some_and(TranslationQualityMetrics::is_low_confidence);
+        if low_confidence {
+            eprintln!("[NMT] ⚠️  Low-confidence translation flagged by decoder metrics: {:?}", metrics);
+        }
+        if low_confidence || self.is_suspicious_quality(&result, target_lang) {
             // 如果质量可疑，尝试简单修复
             result = self.attempt_fix(&result, target_lang);
         }
@@ -197,6 +224,19 @@ mod tests {
         assert!(!checker.is_suspicious_quality("你好世界", "zh"));
     }
 
+    #[test]
+    fn test_low_confidence_metrics_trigger_fix() {
+        let checker = TranslationQualityChecker::new(true);
+        let confident = TranslationQualityMetrics::from_log_probs(&[0.9f32.ln(), 0.8f32.ln()]).unwrap();
+        let unsure = TranslationQualityMetrics::from_log_probs(&[0.01f32.ln(), 0.02f32.ln()]).unwrap();
+        assert!(!confident.is_low_confidence());
+        assert!(unsure.is_low_confidence());
+
+        // 文本本身正常，置信度高时原样保留，置信度低时清理多余标点
+        assert_eq!(checker.check_and_fix_with_metrics("Hi", "Hello!!!", "en", Some(&confident)), "Hello!!!");
+        assert_eq!(checker.check_and_fix_with_metrics("Hi", "Hello!!!", "en", Some(&unsure)), "Hello!");
+    }
+
     #[test]
     fn test_check_and_fix() {
         let checker = TranslationQualityChecker::new(true);
@@ -1,8 +1,11 @@
 //! TTS 音频增强模块
 //! 
 //! 用于改善增量播放的听感：fade in/out、停顿插入等
+//!
+//! 采样率和声道数取自音频块的格式信息（`TtsAudioFormat`），处理后按原格式重新编码
 
 use crate::error::EngineResult;
+use crate::tts_streaming::{PcmAudio, TtsAudioFormat};
 
 /// 音频增强配置
 #[derive(Debug, Clone)]
@@ -17,10 +20,6 @@ pub struct AudioEnhancementConfig {
     pub sentence_end_pause_ms: u32,
     /// 逗号停顿时长（毫秒）
     pub comma_pause_ms: u32,
-    /// 采样率（用于计算样本数）
-    pub sample_rate: u32,
-    /// 声道数
-    pub channels: u16,
 }
 
 impl Default for AudioEnhancementConfig {
@@ -31,8 +30,6 @@ impl Default for AudioEnhancementConfig {
             enable_pause: true,
             sentence_end_pause_ms: 250,  // 句子结束：250ms 停顿
             comma_pause_ms: 150,         // 逗号：150ms 停顿
-            sample_rate: 22050,  // Piper TTS 默认采样率
-            channels: 1,
         }
     }
 }
@@ -51,19 +48,22 @@ impl AudioEnhancer {
     /// 处理音频数据（添加 fade in/out 和停顿）
     /// 
     /// # Arguments
-    /// * `audio_data` - WAV 格式的音频数据
+    /// * `audio_data` - 音频数据
+    /// * `format` - 音频数据的格式（输出保持相同的格式）
     /// * `is_first` - 是否为第一段（决定是否添加 fade in）
     /// * `is_last` - 是否为最后一段（决定是否添加 fade out 和停顿）
     /// * `has_sentence_end` - 是否包含句子结束标点（决定是否添加停顿）
     pub async fn enhance_audio(
         &self,
         audio_data: &[u8],
+        format: &TtsAudioFormat,
         is_first: bool,
         is_last: bool,
         has_sentence_end: bool,
     ) -> EngineResult<Vec<u8>> {
         self.enhance_audio_with_pause_type(
             audio_data,
+            format,
             is_first,
             is_last,
             if has_sentence_end {
@@ -77,13 +77,15 @@ impl AudioEnhancer {
     /// 处理音频数据（添加 fade in/out 和停顿，支持不同停顿类型）
     /// 
     /// # Arguments
-    /// * `audio_data` - WAV 格式的音频数据
+    /// * `audio_data` - 音频数据
+    /// * `format` - 音频数据的格式（输出保持相同的格式）
     /// * `is_first` - 是否为第一段（决定是否添加 fade in）
     /// * `is_last` - 是否为最后一段（决定是否添加 fade out 和停顿）
     /// * `pause_type` - 停顿类型（None 表示不添加停顿）
     pub async fn enhance_audio_with_pause_type(
         &self,
         audio_data: &[u8],
+        format: &TtsAudioFormat,
         is_first: bool,
         is_last: bool,
         pause_type: Option<crate::text_segmentation::PauseType>,
@@ -92,8 +94,8 @@ impl AudioEnhancer {
             return Ok(audio_data.to_vec());
         }
 
-        // 解析 WAV 文件
-        let (mut samples, sample_rate, channels) = self.parse_wav(audio_data)?;
+        // 按格式解码为 PCM 样本
+        let PcmAudio { mut samples, sample_rate, channels } = PcmAudio::decode(audio_data, format)?;
         
         // 应用 fade in/out
         if self.config.enable_fade {
@@ -117,88 +119,8 @@ impl AudioEnhancer {
             }
         }
 
-        // 重新编码为 WAV
-        self.encode_wav(&samples, sample_rate, channels)
-    }
-
-    /// 解析 WAV 文件，提取 PCM 样本
-    fn parse_wav(&self, wav_data: &[u8]) -> EngineResult<(Vec<i16>, u32, u16)> {
-        // 简单的 WAV 解析（假设标准 PCM WAV 格式）
-        if wav_data.len() < 44 {
-            return Err(crate::error::EngineError::new("Invalid WAV file: too short".to_string()));
-        }
-
-        // 检查 RIFF 头
-        if &wav_data[0..4] != b"RIFF" {
-            return Err(crate::error::EngineError::new("Invalid WAV file: missing RIFF header".to_string()));
-        }
-
-        // 检查 WAVE 标识
-        if &wav_data[8..12] != b"WAVE" {
-            return Err(crate::error::EngineError::new("Invalid WAV file: missing WAVE identifier".to_string()));
-        }
-
-        // 查找 fmt chunk
-        let mut offset = 12;
-        let mut sample_rate = 22050u32;
-        let mut channels = 1u16;
-        let mut bits_per_sample = 16u16;
-        let mut data_offset = 0usize;
-
-        while offset < wav_data.len() - 8 {
-            let chunk_id = &wav_data[offset..offset + 4];
-            let chunk_size = u32::from_le_bytes([
-                wav_data[offset + 4],
-                wav_data[offset + 5],
-                wav_data[offset + 6],
-                wav_data[offset + 7],
-            ]) as usize;
-
-            if chunk_id == b"fmt " {
-                // 解析 fmt chunk
-                let audio_format = u16::from_le_bytes([wav_data[offset + 8], wav_data[offset + 9]]);
-                if audio_format != 1 {
-                    return Err(crate::error::EngineError::new(
-                        format!("Unsupported audio format: {}", audio_format)
-                    ));
-                }
-                channels = u16::from_le_bytes([wav_data[offset + 10], wav_data[offset + 11]]);
-                sample_rate = u32::from_le_bytes([
-                    wav_data[offset + 12],
-                    wav_data[offset + 13],
-                    wav_data[offset + 14],
-                    wav_data[offset + 15],
-                ]);
-                bits_per_sample = u16::from_le_bytes([wav_data[offset + 22], wav_data[offset + 23]]);
-            } else if chunk_id == b"data" {
-                data_offset = offset + 8;
-                break;
-            }
-
-            offset += 8 + chunk_size;
-        }
-
-        if data_offset == 0 {
-            return Err(crate::error::EngineError::new("Invalid WAV file: missing data chunk".to_string()));
-        }
-
-        // 读取 PCM 数据
-        let pcm_data = &wav_data[data_offset..];
-        let num_samples = pcm_data.len() / (bits_per_sample as usize / 8) / channels as usize;
-        let mut samples = Vec::with_capacity(num_samples * channels as usize);
-
-        if bits_per_sample == 16 {
-            for i in 0..num_samples * channels as usize {
-                let sample = i16::from_le_bytes([pcm_data[i * 2], pcm_data[i * 2 + 1]]);
-                samples.push(sample);
-            }
-        } else {
-            return Err(crate::error::EngineError::new(
-                format!("Unsupported bits per sample: {}", bits_per_sample)
-            ));
-        }
-
-        Ok((samples, sample_rate, channels))
+        // 按原格式重新编码
+        Ok(PcmAudio { samples, sample_rate, channels }.encode(format.encoding))
     }
 
     /// 应用 fade in/out
@@ -262,39 +184,6 @@ impl AudioEnhancer {
         
         Ok(())
     }
-
-    /// 将 PCM 样本编码为 WAV 格式
-    fn encode_wav(&self, samples: &[i16], sample_rate: u32, channels: u16) -> EngineResult<Vec<u8>> {
-        let mut wav_data = Vec::new();
-        
-        // RIFF header
-        wav_data.extend_from_slice(b"RIFF");
-        let file_size = 36 + samples.len() * 2;
-        wav_data.extend_from_slice(&(file_size as u32).to_le_bytes());
-        wav_data.extend_from_slice(b"WAVE");
-        
-        // fmt chunk
-        wav_data.extend_from_slice(b"fmt ");
-        wav_data.extend_from_slice(&16u32.to_le_bytes());  // fmt chunk size
-        wav_data.extend_from_slice(&1u16.to_le_bytes());   // audio format (PCM)
-        wav_data.extend_from_slice(&channels.to_le_bytes());
-        wav_data.extend_from_slice(&sample_rate.to_le_bytes());
-        let byte_rate = sample_rate * channels as u32 * 2;
-        wav_data.extend_from_slice(&byte_rate.to_le_bytes());
-        wav_data.extend_from_slice(&(channels * 2).to_le_bytes());  // block align
-        wav_data.extend_from_slice(&16u16.to_le_bytes());  // bits per sample
-        
-        // data chunk
-        wav_data.extend_from_slice(b"data");
-        wav_data.extend_from_slice(&(samples.len() * 2).to_le_bytes());
-        
-        // PCM data
-        for &sample in samples {
-            wav_data.extend_from_slice(&sample.to_le_bytes());
-        }
-        
-        Ok(wav_data)
-    }
 }
 
 impl Default for AudioEnhancer {
@@ -0,0 +1,422 @@
+//! TTS 音频格式与格式转换
+//!
+//! 各个 TTS 后端返回的音频格式并不相同：Piper、YourTTS 返回完整的 WAV 文件，
+//! ONNX 引擎（FastSpeech2、VITS）返回裸 PCM，采样率也各不相同（16kHz / 22.05kHz）。
+//! `TtsStreamChunk` 通过 `TtsAudioFormat` 显式说明 `audio` 字节的格式，
+//! `TtsAudioConverter` 再把任意后端的音频统一转换为客户端请求的输出格式：
+//! - 声道数不同时先混成单声道（多声道取平均值），需要多声道时再复制到每个声道
+//! - 采样率不同时用 `StreamingResampler` 做带限重采样
+//! - 按输出编码重新打包（裸 PCM 或 WAV）
+//!
+//! `synthesize_stream` 的分块用 `TtsStreamConverter` 逐块转换（重采样器跨块保留状态）。
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{EngineError, EngineResult};
+use crate::tts_streaming::TtsStreamChunk;
+use crate::vad::StreamingResampler;
+
+/// 目前支持的位深（16-bit 有符号整数）
+const SUPPORTED_BITS_PER_SAMPLE: u16 = 16;
+
+/// 音频字节的封装方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioEncoding {
+    /// 裸 PCM（小端有符号整数，多声道交错排列），没有文件头
+    Pcm,
+    /// 带 RIFF 头的 WAV 文件（PCM 数据）
+    Wav,
+}
+
+/// 音频块的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TtsAudioFormat {
+    pub encoding: AudioEncoding,
+    pub sample_rate: u32,
+    pub channels: u16,
+    #[serde(default = "default_bits_per_sample")]
+    pub bits_per_sample: u16,
+}
+
+fn default_bits_per_sample() -> u16 {
+    SUPPORTED_BITS_PER_SAMPLE
+}
+
+impl TtsAudioFormat {
+    /// 16-bit 裸 PCM
+    pub fn pcm16(sample_rate: u32, channels: u16) -> Self {
+        Self {
+            encoding: AudioEncoding::Pcm,
+            sample_rate,
+            channels,
+            bits_per_sample: SUPPORTED_BITS_PER_SAMPLE,
+        }
+    }
+
+    /// 16-bit PCM 的 WAV 文件
+    pub fn wav16(sample_rate: u32, channels: u16) -> Self {
+        Self {
+            encoding: AudioEncoding::Wav,
+            ..Self::pcm16(sample_rate, channels)
+        }
+    }
+
+    /// 从 WAV 文件头读取格式（用于返回完整 WAV 文件的 HTTP 后端）
+    pub fn from_wav_header(wav_data: &[u8]) -> EngineResult<Self> {
+        parse_wav(wav_data).map(|(format, _)| format)
+    }
+
+    /// 检查格式是否能够解码/编码
+    pub fn validate(&self) -> EngineResult<()> {
+        if self.sample_rate == 0 || self.channels == 0 {
+            return Err(EngineError::new(format!(
+                "Invalid audio format: sample_rate={}, channels={}", self.sample_rate, self.channels
+            )));
+        }
+        if self.bits_per_sample != SUPPORTED_BITS_PER_SAMPLE {
+            return Err(EngineError::new(format!(
+                "Unsupported bits per sample: {}", self.bits_per_sample
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// 解码后的 16-bit PCM 样本（多声道交错排列）
+#[derive(Debug, Clone, PartialEq)]
+pub struct PcmAudio {
+    pub samples: Vec<i16>,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+impl PcmAudio {
+    /// 按格式解码音频字节
+    ///
+    /// WAV 以文件头中的格式为准（流式 WAV 的 data 长度可能是 0xFFFFFFFF，读取到文件末尾为止）；
+    /// 裸 PCM 末尾不足一帧的字节会被丢弃。
+    pub fn decode(audio: &[u8], format: &TtsAudioFormat) -> EngineResult<Self> {
+        let (format, pcm_data) = match format.encoding {
+            AudioEncoding::Pcm => (*format, audio),
+            AudioEncoding::Wav => {
+                let (header_format, pcm_data) = parse_wav(audio)?;
+                if header_format.sample_rate != format.sample_rate || header_format.channels != format.channels {
+                    eprintln!("[TTS Audio] ⚠️  WAV header ({}Hz, {}ch) does not match chunk format ({}Hz, {}ch), using header",
+                        header_format.sample_rate, header_format.channels, format.sample_rate, format.channels);
+                }
+                (header_format, pcm_data)
+            }
+        };
+        format.validate()?;
+
+        let frame_bytes = format.channels as usize * 2;
+        let usable = pcm_data.len() - pcm_data.len() % frame_bytes;
+        let samples = pcm_data[..usable]
+            .chunks_exact(2)
+            .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+            .collect();
+        Ok(Self {
+            samples,
+            sample_rate: format.sample_rate,
+            channels: format.channels,
+        })
+    }
+
+    /// 编码为指定的封装方式
+    pub fn encode(&self, encoding: AudioEncoding) -> Vec<u8> {
+        let data_len = self.samples.len() * 2;
+        let mut bytes = Vec::with_capacity(data_len + if encoding == AudioEncoding::Wav { 44 } else { 0 });
+        if encoding == AudioEncoding::Wav {
+            bytes.extend_from_slice(b"RIFF");
+            bytes.extend_from_slice(&(36 + data_len as u32).to_le_bytes());
+            bytes.extend_from_slice(b"WAVE");
+            bytes.extend_from_slice(b"fmt ");
+            bytes.extend_from_slice(&16u32.to_le_bytes());  // fmt chunk size
+            bytes.extend_from_slice(&1u16.to_le_bytes());   // audio format (PCM)
+            bytes.extend_from_slice(&self.channels.to_le_bytes());
+            bytes.extend_from_slice(&self.sample_rate.to_le_bytes());
+            bytes.extend_from_slice(&(self.sample_rate * self.channels as u32 * 2).to_le_bytes());  // byte rate
+            bytes.extend_from_slice(&(self.channels * 2).to_le_bytes());  // block align
+            bytes.extend_from_slice(&SUPPORTED_BITS_PER_SAMPLE.to_le_bytes());
+            bytes.extend_from_slice(b"data");
+            bytes.extend_from_slice(&(data_len as u32).to_le_bytes());
+        }
+        for sample in &self.samples {
+            bytes.extend_from_slice(&sample.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// 每个声道的样本数
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.channels.max(1) as usize
+    }
+
+    /// 时长（毫秒）
+    pub fn duration_ms(&self) -> u64 {
+        self.frames() as u64 * 1000 / self.sample_rate.max(1) as u64
+    }
+
+    /// 转换为指定的采样率和声道数
+    pub fn convert(self, sample_rate: u32, channels: u16) -> EngineResult<Self> {
+        if self.sample_rate == sample_rate && self.channels == channels {
+            return Ok(self);
+        }
+        if channels == 0 || self.channels == 0 {
+            return Err(EngineError::new(format!(
+                "Invalid channel conversion: {} -> {}", self.channels, channels
+            )));
+        }
+
+        // 混成单声道后重采样，再复制到目标声道数
+        let mono = self.to_mono();
+        let resampled = if self.sample_rate == sample_rate {
+            mono
+        } else {
+            let mut resampler = StreamingResampler::new(self.sample_rate, sample_rate)?;
+            let mut output = resampler.process(&mono);
+            output.extend(resampler.flush());
+            output
+        };
+        Ok(Self::from_mono(&resampled, sample_rate, channels))
+    }
+
+    /// 混成单声道（多声道取平均值），归一化到 [-1, 1]
+    fn to_mono(&self) -> Vec<f32> {
+        let source_channels = self.channels.max(1) as usize;
+        self.samples
+            .chunks_exact(source_channels)
+            .map(|frame| frame.iter().map(|&s| s as f32).sum::<f32>() / source_channels as f32 / 32768.0)
+            .collect()
+    }
+
+    /// 把单声道样本复制到每个声道
+    fn from_mono(mono: &[f32], sample_rate: u32, channels: u16) -> Self {
+        let samples = mono
+            .iter()
+            .map(|&x| (x * 32768.0).round().clamp(i16::MIN as f32, i16::MAX as f32) as i16)
+            .flat_map(|sample| std::iter::repeat_n(sample, channels as usize))
+            .collect();
+        Self { samples, sample_rate, channels }
+    }
+}
+
+/// 把任意后端的音频块转换为统一的输出格式
+///
+/// 每块独立转换（重采样器不跨块保留状态），适用于完整的合成结果；
+/// `synthesize_stream` 的分块使用 `TtsStreamConverter`。
+#[derive(Debug, Clone, Copy)]
+pub struct TtsAudioConverter {
+    output: TtsAudioFormat,
+}
+
+impl TtsAudioConverter {
+    pub fn new(output: TtsAudioFormat) -> EngineResult<Self> {
+        output.validate()?;
+        Ok(Self { output })
+    }
+
+    /// 输出格式
+    pub fn output(&self) -> TtsAudioFormat {
+        self.output
+    }
+
+    /// 转换单个音频块（格式已经一致时直接复制）
+    pub fn convert(&self, chunk: &TtsStreamChunk) -> EngineResult<TtsStreamChunk> {
+        if chunk.format == self.output {
+            return Ok(chunk.clone());
+        }
+        let audio = if chunk.audio.is_empty() {
+            Vec::new()
+        } else {
+            PcmAudio::decode(&chunk.audio, &chunk.format)?
+                .convert(self.output.sample_rate, self.output.channels)?
+                .encode(self.output.encoding)
+        };
+        Ok(TtsStreamChunk {
+            audio,
+            format: self.output,
+            timestamp_ms: chunk.timestamp_ms,
+            is_last: chunk.is_last,
+        })
+    }
+
+    /// 把多个音频块（可以来自不同后端、格式不同）合并为一个输出格式的音频块
+    ///
+    /// 时间戳取第一块，`is_last` 取最后一块
+    pub fn merge(&self, chunks: &[TtsStreamChunk]) -> EngineResult<TtsStreamChunk> {
+        let (Some(first), Some(last)) = (chunks.first(), chunks.last()) else {
+            return Err(EngineError::new("No audio chunks to merge"));
+        };
+        let mut merged = PcmAudio {
+            samples: Vec::new(),
+            sample_rate: self.output.sample_rate,
+            channels: self.output.channels,
+        };
+        for chunk in chunks.iter().filter(|chunk| !chunk.audio.is_empty()) {
+            let audio = PcmAudio::decode(&chunk.audio, &chunk.format)?
+                .convert(self.output.sample_rate, self.output.channels)?;
+            merged.samples.extend(audio.samples);
+        }
+        Ok(TtsStreamChunk {
+            audio: merged.encode(self.output.encoding),
+            format: self.output,
+            timestamp_ms: first.timestamp_ms,
+            is_last: last.is_last,
+        })
+    }
+}
+
+/// 把 `synthesize_stream` 的音频块逐块转换为统一的输出格式
+///
+/// 与 `TtsAudioConverter` 不同，重采样器跨块保留状态，块边界处没有不连续，
+/// 最后一块（`is_last`）输出重采样器中剩余的样本。输出 WAV 时只有第一块带文件头
+/// （只有一块时写入实际长度，否则长度写为 0xFFFFFFFF），后续块是裸 PCM，
+/// 所有输出块按顺序拼接后是一个完整的输出格式音频。
+#[derive(Debug)]
+pub struct TtsStreamConverter {
+    output: TtsAudioFormat,
+    resampler: Option<StreamingResampler>,
+    started: bool,
+}
+
+impl TtsStreamConverter {
+    pub fn new(output: TtsAudioFormat) -> EngineResult<Self> {
+        output.validate()?;
+        Ok(Self {
+            output,
+            resampler: None,
+            started: false,
+        })
+    }
+
+    /// 输出格式（第一块的格式；输出 WAV 时后续块是同样采样率和声道数的裸 PCM）
+    pub fn output(&self) -> TtsAudioFormat {
+        self.output
+    }
+
+    /// 转换流中的下一块
+    pub fn push(&mut self, chunk: &TtsStreamChunk) -> EngineResult<TtsStreamChunk> {
+        let audio = if chunk.audio.is_empty() {
+            PcmAudio { samples: Vec::new(), sample_rate: chunk.format.sample_rate, channels: chunk.format.channels }
+        } else {
+            PcmAudio::decode(&chunk.audio, &chunk.format)?
+        };
+
+        let converted = if audio.sample_rate == self.output.sample_rate {
+            if audio.channels == self.output.channels {
+                audio
+            } else {
+                PcmAudio::from_mono(&audio.to_mono(), self.output.sample_rate, self.output.channels)
+            }
+        } else {
+            let resampler = match self.resampler.as_mut() {
+                Some(resampler) if resampler.from_rate() == audio.sample_rate => resampler,
+                _ => self.resampler.insert(StreamingResampler::new(audio.sample_rate, self.output.sample_rate)?),
+            };
+            let mut resampled = resampler.process(&audio.to_mono());
+            if chunk.is_last {
+                resampled.extend(resampler.flush());
+            }
+            PcmAudio::from_mono(&resampled, self.output.sample_rate, self.output.channels)
+        };
+
+        let first = !self.started;
+        self.started = true;
+        let (bytes, format) = if first && self.output.encoding == AudioEncoding::Wav {
+            let mut bytes = converted.encode(AudioEncoding::Wav);
+            if !chunk.is_last {
+                // 流式 WAV：总长度未知
+                bytes[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+                bytes[40..44].copy_from_slice(&u32::MAX.to_le_bytes());
+            }
+            (bytes, self.output)
+        } else {
+            (
+                converted.encode(AudioEncoding::Pcm),
+                TtsAudioFormat { encoding: AudioEncoding::Pcm, ..self.output },
+            )
+        };
+        Ok(TtsStreamChunk {
+            audio: bytes,
+            format,
+            timestamp_ms: chunk.timestamp_ms,
+            is_last: chunk.is_last,
+        })
+    }
+}
+
+/// 解析 WAV 文件，返回 (格式, PCM 数据)
+fn parse_wav(wav_data: &[u8]) -> EngineResult<(TtsAudioFormat, &[u8])> {
+    if wav_data.len() < 12 {
+        return Err(EngineError::new("Invalid WAV file: too short"));
+    }
+    if &wav_data[0..4] != b"RIFF" {
+        return Err(EngineError::new("Invalid WAV file: missing RIFF header"));
+    }
+    if &wav_data[8..12] != b"WAVE" {
+        return Err(EngineError::new("Invalid WAV file: missing WAVE identifier"));
+    }
+
+    let mut format = None;
+    let mut offset = 12;
+    while offset + 8 <= wav_data.len() {
+        let id = &wav_data[offset..offset + 4];
+        let size = u32::from_le_bytes([
+            wav_data[offset + 4],
+            wav_data[offset + 5],
+            wav_data[offset + 6],
+            wav_data[offset + 7],
+        ]) as usize;
+        let body = offset + 8;
+
+        if id == b"data" {
+            let format = format.ok_or_else(|| EngineError::new("Invalid WAV file: data chunk before fmt chunk"))?;
+            // 流式 WAV 的 data 长度未知（0xFFFFFFFF），读取到文件末尾为止
+            let end = body.saturating_add(size).min(wav_data.len());
+            return Ok((format, &wav_data[body..end]));
+        }
+        if id == b"fmt " {
+            if size < 16 || body + 16 > wav_data.len() {
+                return Err(EngineError::new("Invalid WAV file: truncated fmt chunk"));
+            }
+            let audio_format = u16::from_le_bytes([wav_data[body], wav_data[body + 1]]);
+            if audio_format != 1 {
+                return Err(EngineError::new(format!("Unsupported audio format: {}", audio_format)));
+            }
+            format = Some(TtsAudioFormat {
+                encoding: AudioEncoding::Wav,
+                channels: u16::from_le_bytes([wav_data[body + 2], wav_data[body + 3]]),
+                sample_rate: u32::from_le_bytes([
+                    wav_data[body + 4],
+                    wav_data[body + 5],
+                    wav_data[body + 6],
+                    wav_data[body + 7],
+                ]),
+                bits_per_sample: u16::from_le_bytes([wav_data[body + 14], wav_data[body + 15]]),
+            });
+        }
+        // RIFF 块按 2 字节对齐
+        offset = body.saturating_add(size).saturating_add(size & 1);
+    }
+    Err(EngineError::new("Invalid WAV file: missing data chunk"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wav_round_trip_keeps_samples() {
+        let audio = PcmAudio {
+            samples: vec![0, 1000, -1000, i16::MAX, i16::MIN, 7],
+            sample_rate: 22050,
+            channels: 2,
+        };
+        let wav = audio.encode(AudioEncoding::Wav);
+        assert_eq!(TtsAudioFormat::from_wav_header(&wav).unwrap(), TtsAudioFormat::wav16(22050, 2));
+        assert_eq!(PcmAudio::decode(&wav, &TtsAudioFormat::wav16(22050, 2)).unwrap(), audio);
+        assert_eq!(audio.frames(), 3);
+    }
+}
@@ -0,0 +1,446 @@
+//! TTS 输出音频的编码（传输压缩）
+//!
+//! WebSocket / S2S 接口默认以 base64 发送未压缩的音频，移动端流量较大。客户端可以协商输出编码：
+//! - `pcm16` / `wav`：在进程内完成，不需要外部依赖
+//! - `ogg_opus` / `webm_opus` / `mp3`：通过 ffmpeg 子进程编码
+//!
+//! `TtsAudioEncoder::encode` 把一段完整的音频编码为一个完整的文件（S2S 接口、整句结果）。
+//! 流式输出的音频块使用 `TtsStreamEncoder`：一次合成（直到 `is_last`）的所有块送入同一个 ffmpeg
+//! 进程，输出是同一个容器的连续字节，按顺序拼接后才是一个完整的文件。逐块独立编码会让每块
+//! 各有容器头和编码器延迟，块边界处播放不连续。
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::error::{EngineError, EngineResult};
+use crate::tts_streaming::{AudioEncoding, PcmAudio, TtsAudioFormat, TtsStreamChunk, TtsStreamConverter};
+
+/// Opus 的解码采样率固定为 48kHz（编码前统一重采样）
+const OPUS_SAMPLE_RATE: u32 = 48000;
+
+/// 客户端可以请求的输出编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioCodec {
+    /// 16-bit 小端裸 PCM（单声道，采样率见音频格式）
+    Pcm16,
+    /// 16-bit PCM 的 WAV 文件
+    Wav,
+    /// Ogg 封装的 Opus
+    OggOpus,
+    /// WebM 封装的 Opus
+    WebmOpus,
+    /// MP3
+    Mp3,
+}
+
+impl AudioCodec {
+    /// MIME 类型（客户端据此选择解码方式）
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            AudioCodec::Pcm16 => "audio/L16",
+            AudioCodec::Wav => "audio/wav",
+            AudioCodec::OggOpus => "audio/ogg; codecs=opus",
+            AudioCodec::WebmOpus => "audio/webm; codecs=opus",
+            AudioCodec::Mp3 => "audio/mpeg",
+        }
+    }
+
+    /// 是否需要 ffmpeg 编码
+    pub fn requires_ffmpeg(&self) -> bool {
+        matches!(self, AudioCodec::OggOpus | AudioCodec::WebmOpus | AudioCodec::Mp3)
+    }
+}
+
+/// 编码器配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioCodecConfig {
+    /// ffmpeg 可执行文件路径（Opus / MP3 编码使用，需要 libopus / libmp3lame）
+    pub ffmpeg_path: String,
+    /// Opus 码率（kbps，语音 24kbps 已经足够清晰）
+    pub opus_bitrate_kbps: u32,
+    /// MP3 码率（kbps）
+    pub mp3_bitrate_kbps: u32,
+    /// 单个音频块的编码超时（毫秒）
+    pub timeout_ms: u64,
+}
+
+impl Default for AudioCodecConfig {
+    fn default() -> Self {
+        Self {
+            ffmpeg_path: "ffmpeg".to_string(),
+            opus_bitrate_kbps: 24,
+            mp3_bitrate_kbps: 64,
+            timeout_ms: 5000,
+        }
+    }
+}
+
+/// 编码后的音频
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodedAudio {
+    pub codec: AudioCodec,
+    pub data: Vec<u8>,
+    /// 解码后的采样率
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+/// 把 TTS 音频块编码为客户端请求的输出编码
+#[derive(Debug, Clone, Default)]
+pub struct TtsAudioEncoder {
+    config: AudioCodecConfig,
+}
+
+impl TtsAudioEncoder {
+    pub fn new(config: AudioCodecConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &AudioCodecConfig {
+        &self.config
+    }
+
+    /// 创建流式编码器（每个输出流一个，例如每个 WebSocket 连接）
+    pub fn stream_encoder(&self, codec: AudioCodec) -> TtsStreamEncoder {
+        TtsStreamEncoder::new(self.config.clone(), codec)
+    }
+
+    /// 检测可用的输出编码
+    ///
+    /// `pcm16` / `wav` 始终可用；Opus / MP3 取决于 ffmpeg 是否安装，以及是否带有 libopus / libmp3lame 编码器
+    pub async fn available_codecs(&self) -> Vec<AudioCodec> {
+        let mut codecs = vec![AudioCodec::Pcm16, AudioCodec::Wav];
+        let probe = Command::new(&self.config.ffmpeg_path)
+            .args(["-hide_banner", "-encoders"])
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output();
+        match tokio::time::timeout(Duration::from_millis(self.config.timeout_ms), probe).await {
+            Ok(Ok(output)) if output.status.success() => {
+                let encoders = String::from_utf8_lossy(&output.stdout);
+                if encoders.contains("libopus") {
+                    codecs.extend([AudioCodec::OggOpus, AudioCodec::WebmOpus]);
+                }
+                if encoders.contains("libmp3lame") {
+                    codecs.push(AudioCodec::Mp3);
+                }
+            }
+            Ok(Ok(output)) => {
+                eprintln!("[Codec] ⚠️ ffmpeg -encoders exited with {}, Opus/MP3 output disabled", output.status);
+            }
+            Ok(Err(e)) => {
+                eprintln!("[Codec] ⚠️ ffmpeg not available ({}: {}), Opus/MP3 output disabled", self.config.ffmpeg_path, e);
+            }
+            Err(_) => {
+                eprintln!("[Codec] ⚠️ ffmpeg probe timed out, Opus/MP3 output disabled");
+            }
+        }
+        codecs
+    }
+
+    /// 编码一个音频块（按块的格式信息解码，空音频返回空数据）
+    pub async fn encode(&self, chunk: &TtsStreamChunk, codec: AudioCodec) -> EngineResult<EncodedAudio> {
+        let pcm = decode_chunk(chunk)?;
+        let data = match codec {
+            _ if pcm.samples.is_empty() => Vec::new(),
+            AudioCodec::Pcm16 => pcm.encode(AudioEncoding::Pcm),
+            AudioCodec::Wav => pcm.encode(AudioEncoding::Wav),
+            AudioCodec::OggOpus | AudioCodec::WebmOpus | AudioCodec::Mp3 => {
+                self.run_ffmpeg(&pcm, &ffmpeg_output_args(&self.config, codec)).await?
+            }
+        };
+        Ok(EncodedAudio {
+            codec,
+            data,
+            sample_rate: encoded_sample_rate(codec, pcm.sample_rate),
+            channels: pcm.channels,
+        })
+    }
+
+    /// 通过 ffmpeg 子进程编码：标准输入写入 WAV，从标准输出读取编码结果
+    async fn run_ffmpeg(&self, pcm: &PcmAudio, output_args: &[String]) -> EngineResult<Vec<u8>> {
+        let mut child = Command::new(&self.config.ffmpeg_path)
+            .args(["-hide_banner", "-loglevel", "error", "-f", "wav", "-i", "pipe:0"])
+            .args(output_args)
+            .arg("pipe:1")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| EngineError::new(format!("Failed to start ffmpeg ({}): {}", self.config.ffmpeg_path, e)))?;
+
+        // 写入和读取同时进行，避免管道缓冲区写满后互相等待
+        let mut stdin = child.stdin.take().expect("ffmpeg stdin is piped");
+        let wav = pcm.encode(AudioEncoding::Wav);
+        let writer = tokio::spawn(async move {
+            stdin.write_all(&wav).await?;
+            stdin.shutdown().await
+        });
+
+        let output = tokio::time::timeout(Duration::from_millis(self.config.timeout_ms), child.wait_with_output())
+            .await
+            .map_err(|_| EngineError::new(format!("ffmpeg encoding timed out after {}ms", self.config.timeout_ms)))?
+            .map_err(|e| EngineError::new(format!("Failed to run ffmpeg: {}", e)))?;
+        // ffmpeg 提前退出时写入会失败（Broken pipe），错误原因以退出状态和 stderr 为准
+        let _ = writer.await;
+
+        if !output.status.success() {
+            return Err(EngineError::new(format!(
+                "ffmpeg exited with {}: {}", output.status, String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        if output.stdout.is_empty() {
+            return Err(EngineError::new("ffmpeg produced no output"));
+        }
+        Ok(output.stdout)
+    }
+}
+
+/// 流式编码器：同一次合成的音频块编码为同一个容器的连续字节
+///
+/// - `pcm16`：逐块输出裸 PCM
+/// - `wav`：每次合成的第一块带流式 WAV 头（长度未知），后续块是裸 PCM
+/// - Opus / MP3：每次合成启动一个 ffmpeg 进程，块的 PCM 依次写入标准输入，标准输出由后台任务持续读取。
+///   编码输出比输入晚（编码器延迟、容器分页），每块返回此前已经产生的字节，
+///   最后一块（`is_last`）关闭标准输入并返回剩余的全部输出，下一次合成重新启动进程
+#[derive(Debug)]
+pub struct TtsStreamEncoder {
+    config: AudioCodecConfig,
+    codec: AudioCodec,
+    wav: Option<TtsStreamConverter>,
+    process: Option<EncoderProcess>,
+}
+
+impl TtsStreamEncoder {
+    pub fn new(config: AudioCodecConfig, codec: AudioCodec) -> Self {
+        Self {
+            config,
+            codec,
+            wav: None,
+            process: None,
+        }
+    }
+
+    pub fn codec(&self) -> AudioCodec {
+        self.codec
+    }
+
+    /// 编码流中的下一块（出错后丢弃当前进程，下一块重新开始一个容器）
+    pub async fn push(&mut self, chunk: &TtsStreamChunk) -> EngineResult<EncodedAudio> {
+        let pcm = decode_chunk(chunk)?;
+        let mut channels = pcm.channels;
+        let (data, sample_rate) = match self.codec {
+            AudioCodec::Pcm16 => (pcm.encode(AudioEncoding::Pcm), pcm.sample_rate),
+            AudioCodec::Wav => {
+                let converter = match self.wav.as_mut() {
+                    Some(converter) => converter,
+                    None => self.wav.insert(TtsStreamConverter::new(TtsAudioFormat::wav16(pcm.sample_rate, pcm.channels))?),
+                };
+                let output = converter.output();
+                channels = output.channels;
+                let converted = converter.push(&TtsStreamChunk {
+                    audio: pcm.encode(AudioEncoding::Pcm),
+                    format: TtsAudioFormat::pcm16(pcm.sample_rate, pcm.channels),
+                    timestamp_ms: chunk.timestamp_ms,
+                    is_last: chunk.is_last,
+                })?;
+                if chunk.is_last {
+                    self.wav = None;
+                }
+                (converted.audio, output.sample_rate)
+            }
+            AudioCodec::OggOpus | AudioCodec::WebmOpus | AudioCodec::Mp3 => {
+                let sample_rate = encoded_sample_rate(self.codec, pcm.sample_rate);
+                let result = self.encode_with_ffmpeg(pcm, chunk.is_last).await;
+                if result.is_err() {
+                    self.process = None;
+                }
+                (result?, sample_rate)
+            }
+        };
+        Ok(EncodedAudio {
+            codec: self.codec,
+            data,
+            sample_rate,
+            channels,
+        })
+    }
+
+    async fn encode_with_ffmpeg(&mut self, pcm: PcmAudio, is_last: bool) -> EngineResult<Vec<u8>> {
+        let timeout = Duration::from_millis(self.config.timeout_ms);
+        let process = match self.process.as_mut() {
+            Some(process) => process,
+            None => self.process.insert(EncoderProcess::spawn(&self.config, self.codec, pcm.sample_rate, pcm.channels)?),
+        };
+        // 同一次合成的块格式相同，这里只是兜底
+        let pcm = pcm.convert(process.sample_rate, process.channels)?;
+        if !pcm.samples.is_empty() {
+            let written = tokio::time::timeout(timeout, process.stdin.write_all(&pcm.encode(AudioEncoding::Pcm))).await;
+            match written {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => {
+                    // ffmpeg 提前退出时写入会失败（Broken pipe），错误原因以退出状态和 stderr 为准
+                    let process = self.process.take().expect("encoder process is running");
+                    process.finish(timeout).await?;
+                    return Err(EngineError::new(format!("Failed to write to ffmpeg: {}", e)));
+                }
+                Err(_) => {
+                    return Err(EngineError::new(format!("ffmpeg encoding timed out after {}ms", self.config.timeout_ms)));
+                }
+            }
+        }
+        if !is_last {
+            return Ok(process.take_output());
+        }
+        let process = self.process.take().expect("encoder process is running");
+        process.finish(timeout).await
+    }
+}
+
+/// 一次流式合成的 ffmpeg 编码进程（输入为裸 PCM）
+#[derive(Debug)]
+struct EncoderProcess {
+    child: Child,
+    stdin: ChildStdin,
+    /// 标准输出读取任务转发的编码数据
+    output: mpsc::UnboundedReceiver<Vec<u8>>,
+    stderr: JoinHandle<String>,
+    /// 输入 PCM 的采样率和声道数
+    sample_rate: u32,
+    channels: u16,
+}
+
+impl EncoderProcess {
+    fn spawn(config: &AudioCodecConfig, codec: AudioCodec, sample_rate: u32, channels: u16) -> EngineResult<Self> {
+        let rate = sample_rate.to_string();
+        let channel_count = channels.to_string();
+        let mut output_args = ffmpeg_output_args(config, codec);
+        // 编码出的数据立即写出，不在 ffmpeg 内部攒缓冲区；Ogg 默认每页 1 秒，改为 20ms 一页
+        output_args.extend(["-flush_packets".to_string(), "1".to_string()]);
+        if codec == AudioCodec::OggOpus {
+            output_args.extend(["-page_duration".to_string(), "20000".to_string()]);
+        }
+        let mut child = Command::new(&config.ffmpeg_path)
+            .args(["-hide_banner", "-loglevel", "error", "-f", "s16le", "-ar", &rate, "-ac", &channel_count, "-i", "pipe:0"])
+            .args(output_args)
+            .arg("pipe:1")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| EngineError::new(format!("Failed to start ffmpeg ({}): {}", config.ffmpeg_path, e)))?;
+
+        let stdin = child.stdin.take().expect("ffmpeg stdin is piped");
+        let mut stdout = child.stdout.take().expect("ffmpeg stdout is piped");
+        let mut stderr = child.stderr.take().expect("ffmpeg stderr is piped");
+
+        // 持续读取标准输出，避免管道写满后 ffmpeg 阻塞、进而阻塞标准输入的写入
+        let (sender, output) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut buffer = vec![0u8; 8192];
+            while let Ok(read) = stdout.read(&mut buffer).await {
+                if read == 0 || sender.send(buffer[..read].to_vec()).is_err() {
+                    break;
+                }
+            }
+        });
+        let stderr = tokio::spawn(async move {
+            let mut message = String::new();
+            let _ = stderr.read_to_string(&mut message).await;
+            message
+        });
+
+        Ok(Self {
+            child,
+            stdin,
+            output,
+            stderr,
+            sample_rate,
+            channels,
+        })
+    }
+
+    /// 取出目前已经编码出的数据（不等待）
+    fn take_output(&mut self) -> Vec<u8> {
+        let mut data = Vec::new();
+        while let Ok(bytes) = self.output.try_recv() {
+            data.extend_from_slice(&bytes);
+        }
+        data
+    }
+
+    /// 关闭标准输入，等待 ffmpeg 写出剩余数据和容器结尾后退出
+    async fn finish(self, timeout: Duration) -> EngineResult<Vec<u8>> {
+        let EncoderProcess { mut child, stdin, mut output, stderr, .. } = self;
+        drop(stdin);
+        let finished = tokio::time::timeout(timeout, async move {
+            let mut data = Vec::new();
+            while let Some(bytes) = output.recv().await {
+                data.extend_from_slice(&bytes);
+            }
+            let status = child.wait().await;
+            (data, status, stderr.await.unwrap_or_default())
+        })
+        .await
+        .map_err(|_| EngineError::new(format!("ffmpeg encoding timed out after {}ms", timeout.as_millis())))?;
+
+        let (data, status, stderr) = finished;
+        let status = status.map_err(|e| EngineError::new(format!("Failed to run ffmpeg: {}", e)))?;
+        if !status.success() {
+            return Err(EngineError::new(format!("ffmpeg exited with {}: {}", status, stderr.trim())));
+        }
+        Ok(data)
+    }
+}
+
+/// 按块的格式信息解码（空音频返回空样本）
+fn decode_chunk(chunk: &TtsStreamChunk) -> EngineResult<PcmAudio> {
+    if chunk.audio.is_empty() {
+        return Ok(PcmAudio {
+            samples: Vec::new(),
+            sample_rate: chunk.format.sample_rate,
+            channels: chunk.format.channels,
+        });
+    }
+    PcmAudio::decode(&chunk.audio, &chunk.format)
+}
+
+/// 编码后音频的采样率
+fn encoded_sample_rate(codec: AudioCodec, sample_rate: u32) -> u32 {
+    if matches!(codec, AudioCodec::OggOpus | AudioCodec::WebmOpus) {
+        OPUS_SAMPLE_RATE
+    } else {
+        sample_rate
+    }
+}
+
+/// ffmpeg 的输出参数（编码器、码率、容器）
+fn ffmpeg_output_args(config: &AudioCodecConfig, codec: AudioCodec) -> Vec<String> {
+    match codec {
+        AudioCodec::OggOpus | AudioCodec::WebmOpus => {
+            let container = if codec == AudioCodec::OggOpus { "ogg" } else { "webm" };
+            vec![
+                "-c:a".into(), "libopus".into(),
+                "-b:a".into(), format!("{}k", config.opus_bitrate_kbps),
+                "-ar".into(), OPUS_SAMPLE_RATE.to_string(),
+                "-f".into(), container.into(),
+            ]
+        }
+        AudioCodec::Mp3 => vec![
+            "-c:a".into(), "libmp3lame".into(),
+            "-b:a".into(), format!("{}k", config.mp3_bitrate_kbps),
+            "-f".into(), "mp3".into(),
+        ],
+        AudioCodec::Pcm16 | AudioCodec::Wav => Vec::new(),
+    }
+}
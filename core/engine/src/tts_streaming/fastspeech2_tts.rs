@@ -7,14 +7,49 @@ use ort::tensor::OrtOwnedTensor;
 use ort::value::Value;
 use std::sync::Arc;
 use async_trait::async_trait;
-use ndarray::{Array1, Array2, Array3, IxDyn, Ix2, Ix3};
+use ndarray::{s, Array1, Array2, Array3, IxDyn, Ix2, Ix3};
 use std::ptr;
 use ndarray::CowArray;
 
 use crate::error::{EngineError, EngineResult};
-use super::{TtsRequest, TtsStreamChunk, TtsStreaming};
+use super::{TtsAudioFormat, TtsChunkStream, TtsRequest, TtsStreamChunk, TtsStreaming};
 use super::text_processor::TextProcessor;
 
+/// FastSpeech2 + HiFiGAN 输出采样率
+const FASTSPEECH2_SAMPLE_RATE: u32 = 16000;
+
+/// 流式合成时每个声码器窗口的 mel 帧数
+const VOCODER_WINDOW_FRAMES: usize = 32;
+
+/// 流式合成时声码器窗口两侧的上下文帧数（对应的输出样本被丢弃）
+const VOCODER_CONTEXT_FRAMES: usize = 8;
+
+/// 流式合成时输出的峰值上限（留出余量，避免转换为 PCM 时削波）
+const STREAM_PEAK_LIMIT: f32 = 0.95;
+
+/// 流式合成的峰值限幅器
+///
+/// 整段波形未知，不能像一次性合成那样按整段的取值范围归一化。增益为固定的 1.0，
+/// 当出现超过上限的峰值时按目前为止的最大峰值降低增益（只降不升），
+/// 输出不超过 ±`STREAM_PEAK_LIMIT`，也不会因为每个窗口的增益不同而产生音量跳变。
+#[derive(Debug, Clone, Copy)]
+struct StreamingPeakLimiter {
+    peak: f32,
+}
+
+impl StreamingPeakLimiter {
+    fn new() -> Self {
+        Self { peak: STREAM_PEAK_LIMIT }
+    }
+
+    fn process(&mut self, waveform: Array1<f32>) -> Array1<f32> {
+        let window_peak = waveform.iter().filter(|x| x.is_finite()).fold(0.0f32, |a, &b| a.max(b.abs()));
+        self.peak = self.peak.max(window_peak);
+        let gain = STREAM_PEAK_LIMIT / self.peak;
+        waveform.mapv(|x| if x.is_finite() { x * gain } else { 0.0 })
+    }
+}
+
 /// FastSpeech2 + HiFiGAN TTS 引擎
 pub struct FastSpeech2TtsEngine {
     /// FastSpeech2 模型会话（中文）
@@ -423,6 +458,72 @@ impl FastSpeech2TtsEngine {
         Ok(audio)
     }
 
+    /// 文本预处理和 FastSpeech2 推理：文本 → 音素 ID → Mel-spectrogram
+    ///
+    /// 文本或音素为空时返回 None（调用方返回空音频）
+    fn text_to_mel(&self, request: &TtsRequest) -> EngineResult<Option<Array3<f32>>> {
+        // 输入验证
+        if request.text.trim().is_empty() {
+            return Ok(None);
+        }
+
+        // 1. 文本预处理：文本 → 音素 ID
+        let text_processor = self.get_text_processor(&request.locale)
+            .map_err(|e| EngineError::new(format!("unsupported locale: {e}")))?;
+        
+        let phone_ids = text_processor.text_to_phone_ids(&request.text)
+            .map_err(|e| EngineError::new(format!("text preprocessing failed: {e}")))?;
+
+        println!("[DEBUG TTS] Text: '{}', Phone IDs: {:?} (length: {})", 
+            request.text, phone_ids, phone_ids.len());
+
+        if phone_ids.is_empty() {
+            println!("[DEBUG TTS] Phone IDs is empty, returning empty audio");
+            return Ok(None);
+        }
+
+        // 2. FastSpeech2 推理：音素 ID → Mel-spectrogram
+        let mel = self.run_fastspeech2(&phone_ids, &request.locale)
+            .map_err(|e| EngineError::new(format!("FastSpeech2 inference failed: {e}")))?;
+
+        // 验证 mel-spectrogram 形状
+        let mel_shape = mel.shape();
+        println!("[DEBUG TTS] Mel-spectrogram shape: {:?}", mel_shape);
+        if mel_shape.len() != 3 || mel_shape[0] != 1 {
+            return Err(EngineError::new(format!(
+                "Invalid mel-spectrogram shape: {:?}, expected [1, mel_dim, time_steps]",
+                mel_shape
+            )));
+        }
+        Ok(Some(mel))
+    }
+
+    /// 对 mel-spectrogram 的一个窗口运行 HiFiGAN（流式合成）
+    ///
+    /// 窗口 `[frame, frame + VOCODER_WINDOW_FRAMES)` 两侧各带 `VOCODER_CONTEXT_FRAMES` 帧上下文一起送入声码器，
+    /// 输出中上下文对应的样本被丢弃，避免窗口边界处的卷积边缘效应（overlap-save）。
+    fn vocode_window(&self, mel: &Array3<f32>, frame: usize, locale: &str) -> EngineResult<Array1<f32>> {
+        let time_steps = mel.shape()[1];
+        let start = frame.saturating_sub(VOCODER_CONTEXT_FRAMES);
+        let window_end = (frame + VOCODER_WINDOW_FRAMES).min(time_steps);
+        let end = (window_end + VOCODER_CONTEXT_FRAMES).min(time_steps);
+
+        let window = mel.slice(s![.., start..end, ..]).to_owned();
+        let audio = self.run_hifigan(&window, locale)
+            .map_err(|e| EngineError::new(format!("HiFiGAN inference failed: {e}")))?;
+
+        // 每个 mel 帧对应的样本数（hop size）
+        let samples_per_frame = audio.len() / (end - start);
+        if samples_per_frame == 0 {
+            return Err(EngineError::new(format!(
+                "HiFiGAN produced {} samples for {} mel frames", audio.len(), end - start
+            )));
+        }
+        let keep_from = (frame - start) * samples_per_frame;
+        let keep_to = (window_end - start) * samples_per_frame;
+        Ok(audio.slice(s![keep_from..keep_to]).to_owned())
+    }
+
     /// 将 f32 音频波形转换为 PCM 16-bit 字节
     /// 
     /// 输入：f32 音频波形（范围通常是 [-1.0, 1.0]）
@@ -498,47 +599,15 @@ impl FastSpeech2TtsEngine {
 #[async_trait]
 impl TtsStreaming for FastSpeech2TtsEngine {
     async fn synthesize(&self, request: TtsRequest) -> EngineResult<TtsStreamChunk> {
-        // 输入验证
-        if request.text.trim().is_empty() {
+        // 1-2. 文本 → 音素 ID → Mel-spectrogram
+        let Some(mel) = self.text_to_mel(&request)? else {
             return Ok(TtsStreamChunk {
                 audio: vec![],
+                format: TtsAudioFormat::pcm16(FASTSPEECH2_SAMPLE_RATE, 1),
                 timestamp_ms: 0,
                 is_last: true,
             });
-        }
-
-        // 1. 文本预处理：文本 → 音素 ID
-        let text_processor = self.get_text_processor(&request.locale)
-            .map_err(|e| EngineError::new(format!("unsupported locale: {e}")))?;
-        
-        let phone_ids = text_processor.text_to_phone_ids(&request.text)
-            .map_err(|e| EngineError::new(format!("text preprocessing failed: {e}")))?;
-
-        println!("[DEBUG TTS] Text: '{}', Phone IDs: {:?} (length: {})", 
-            request.text, phone_ids, phone_ids.len());
-
-        if phone_ids.is_empty() {
-            println!("[DEBUG TTS] Phone IDs is empty, returning empty audio");
-            return Ok(TtsStreamChunk {
-                audio: vec![],
-                timestamp_ms: 0,
-                is_last: true,
-            });
-        }
-
-        // 2. FastSpeech2 推理：音素 ID → Mel-spectrogram
-        let mel = self.run_fastspeech2(&phone_ids, &request.locale)
-            .map_err(|e| EngineError::new(format!("FastSpeech2 inference failed: {e}")))?;
-
-        // 验证 mel-spectrogram 形状
-        let mel_shape = mel.shape();
-        println!("[DEBUG TTS] Mel-spectrogram shape: {:?}", mel_shape);
-        if mel_shape.len() != 3 || mel_shape[0] != 1 {
-            return Err(EngineError::new(format!(
-                "Invalid mel-spectrogram shape: {:?}, expected [1, mel_dim, time_steps]",
-                mel_shape
-            )));
-        }
+        };
 
         // 3. HiFiGAN 推理：Mel-spectrogram → 音频波形
         let audio_waveform = self.run_hifigan(&mel, &request.locale)
@@ -621,13 +690,99 @@ impl TtsStreaming for FastSpeech2TtsEngine {
         // 当前：返回完整音频作为单个 chunk
         Ok(TtsStreamChunk {
             audio: pcm_audio,
+            format: TtsAudioFormat::pcm16(FASTSPEECH2_SAMPLE_RATE, 1),
             timestamp_ms: 0,  // 时间戳从 0 开始
             is_last: true,    // 单个 chunk，标记为最后一个
         })
     }
 
+    /// 流式合成：FastSpeech2 一次生成整句的 mel-spectrogram，HiFiGAN 按窗口逐段生成波形
+    ///
+    /// 整段波形未知，不做一次性合成的 min-max 归一化，而是用 `StreamingPeakLimiter` 限制峰值
+    fn synthesize_stream(&self, request: TtsRequest) -> TtsChunkStream<'_> {
+        enum State {
+            Start(TtsRequest),
+            Vocoding {
+                mel: Array3<f32>,
+                locale: String,
+                next_frame: usize,
+                emitted_samples: u64,
+                limiter: StreamingPeakLimiter,
+            },
+            Done,
+        }
+
+        Box::pin(futures::stream::unfold(State::Start(request), move |state| async move {
+            let (mel, locale, frame, emitted_samples, mut limiter) = match state {
+                State::Start(request) => match self.text_to_mel(&request) {
+                    Ok(Some(mel)) => (mel, request.locale, 0, 0, StreamingPeakLimiter::new()),
+                    Ok(None) => {
+                        let empty = TtsStreamChunk {
+                            audio: vec![],
+                            format: TtsAudioFormat::pcm16(FASTSPEECH2_SAMPLE_RATE, 1),
+                            timestamp_ms: 0,
+                            is_last: true,
+                        };
+                        return Some((Ok(empty), State::Done));
+                    }
+                    Err(e) => return Some((Err(e), State::Done)),
+                },
+                State::Vocoding { mel, locale, next_frame, emitted_samples, limiter } => {
+                    (mel, locale, next_frame, emitted_samples, limiter)
+                }
+                State::Done => return None,
+            };
+
+            let waveform = match self.vocode_window(&mel, frame, &locale) {
+                Ok(waveform) => waveform,
+                Err(e) => return Some((Err(e), State::Done)),
+            };
+            let normalized = limiter.process(waveform);
+
+            let next_frame = frame + VOCODER_WINDOW_FRAMES;
+            let is_last = next_frame >= mel.shape()[1];
+            let chunk = TtsStreamChunk {
+                audio: self.audio_to_pcm16(&normalized),
+                format: TtsAudioFormat::pcm16(FASTSPEECH2_SAMPLE_RATE, 1),
+                timestamp_ms: emitted_samples * 1000 / FASTSPEECH2_SAMPLE_RATE as u64,
+                is_last,
+            };
+            let emitted_samples = emitted_samples + normalized.len() as u64;
+            let next_state = if is_last {
+                State::Done
+            } else {
+                State::Vocoding { mel, locale, next_frame, emitted_samples, limiter }
+            };
+            Some((Ok(chunk), next_state))
+        }))
+    }
+
     async fn close(&self) -> EngineResult<()> {
         Ok(())
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peak_limiter_keeps_quiet_audio_unchanged() {
+        let mut limiter = StreamingPeakLimiter::new();
+        let waveform = Array1::from(vec![0.1, -0.5, 0.9]);
+        assert_eq!(limiter.process(waveform.clone()), waveform);
+    }
+
+    #[test]
+    fn test_peak_limiter_never_exceeds_limit_and_never_raises_gain() {
+        let mut limiter = StreamingPeakLimiter::new();
+        let loud = limiter.process(Array1::from(vec![0.5, -1.9, 1.2]));
+        assert!(loud.iter().all(|x| x.abs() <= STREAM_PEAK_LIMIT));
+        assert!((loud[1] + STREAM_PEAK_LIMIT).abs() < 1e-6);
+
+        // 之后较小的窗口沿用已经降低的增益，音量不会跳回去
+        let quiet = limiter.process(Array1::from(vec![0.5, f32::NAN]));
+        assert!((quiet[0] - 0.5 * STREAM_PEAK_LIMIT / 1.9).abs() < 1e-6);
+        assert_eq!(quiet[1], 0.0);
+    }
+}
@@ -3,11 +3,18 @@ mod vits_tts;
 mod vits_zh_aishell3_tokenizer;
 mod text_processor;
 mod audio_utils;
+mod audio_format;
+mod codec;
+mod ssml;
+mod text_normalization;
 mod stub;
 mod piper_http;
 pub mod yourtts_http;
 
+use std::pin::Pin;
+
 use async_trait::async_trait;
+use futures::Stream;
 use serde::{Deserialize, Serialize};
 
 use crate::error::EngineResult;
@@ -17,6 +24,10 @@ pub use vits_tts::VitsTtsEngine;
 pub use stub::TtsStub;
 pub use text_processor::TextProcessor;
 pub use audio_utils::{save_pcm_to_wav, validate_pcm_audio};
+pub use audio_format::{AudioEncoding, PcmAudio, TtsAudioConverter, TtsAudioFormat, TtsStreamConverter};
+pub use codec::{AudioCodec, AudioCodecConfig, EncodedAudio, TtsAudioEncoder, TtsStreamEncoder};
+pub use ssml::{is_ssml, lower_ssml, parse_ssml, Prosody, SsmlPart, SsmlPhoneme, SsmlSegment, SsmlTts};
+pub use text_normalization::{normalize_for_tts, NormalizationLanguage, NormalizedTts, TextNormalizer};
 pub use piper_http::{PiperHttpTts, PiperHttpConfig};
 pub use yourtts_http::{YourTtsHttp, YourTtsHttpConfig};
 
@@ -35,12 +46,44 @@ pub struct TtsRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TtsStreamChunk {
     pub audio: Vec<u8>,
+    /// `audio` 字节本身的格式（流式 WAV 只有第一块带文件头，后续块是裸 PCM）
+    pub format: TtsAudioFormat,
+    /// 本块第一个样本在整段合成音频中的时间（毫秒）
     pub timestamp_ms: u64,
+    /// 是否为本次合成的最后一块
     pub is_last: bool,
 }
 
+/// 流式合成的音频块序列
+///
+/// 按顺序产出音频块，最后一块 `is_last` 为 true；中途出错时产出 `Err` 并结束。
+/// 所有块的 `audio` 按顺序拼接，与 `synthesize` 一次性返回的音频格式相同。
+pub type TtsChunkStream<'a> = Pin<Box<dyn Stream<Item = EngineResult<TtsStreamChunk>> + Send + 'a>>;
+
 #[async_trait]
 pub trait TtsStreaming: Send + Sync {
     async fn synthesize(&self, request: TtsRequest) -> EngineResult<TtsStreamChunk>;
+
+    /// 流式合成：先合成完的部分先返回，不必等整句合成完才能开始播放
+    ///
+    /// 默认实现包装 `synthesize`，整段音频作为唯一的一块返回
+    fn synthesize_stream(&self, request: TtsRequest) -> TtsChunkStream<'_> {
+        Box::pin(futures::stream::once(self.synthesize(request)))
+    }
+
+    /// 是否接受指定音标体系（SSML `<phoneme alphabet>`，例如 "ipa"、"x-sampa"、"pinyin"）的音素输入
+    ///
+    /// 默认不接受：`SsmlTts` 改读 `<phoneme>` 元素的内容文字
+    fn accepts_phonemes(&self, _alphabet: &str) -> bool {
+        false
+    }
+
+    /// 按 `<phoneme>` 指定的发音合成 `request.text`（只在 `accepts_phonemes` 返回 true 时调用）
+    ///
+    /// 默认实现忽略音素，按文字合成
+    async fn synthesize_phonemes(&self, request: TtsRequest, _phoneme: SsmlPhoneme) -> EngineResult<TtsStreamChunk> {
+        self.synthesize(request).await
+    }
+
     async fn close(&self) -> EngineResult<()>;
 }
@@ -5,10 +5,11 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::error::{EngineError, EngineResult};
-use crate::tts_streaming::{TtsRequest, TtsStreamChunk, TtsStreaming};
+use crate::language_registry::{self, LanguageBackend};
+use crate::tts_streaming::{AudioEncoding, TtsAudioFormat, TtsChunkStream, TtsRequest, TtsStreamChunk, TtsStreaming};
 
 /// Piper HTTP 服务配置
 #[derive(Debug, Clone)]
@@ -67,44 +68,43 @@ struct PiperHttpRequest {
 /// Piper HTTP 服务响应（WAV 音频数据）
 /// 注意：实际响应是二进制 WAV 数据，不是 JSON
 
-#[async_trait]
-impl TtsStreaming for PiperHttpTts {
-    async fn synthesize(&self, request: TtsRequest) -> EngineResult<TtsStreamChunk> {
-        use std::time::Instant;
-        let tts_start = Instant::now();
-        eprintln!("[Piper TTS] ===== TTS Request Started =====");
-        eprintln!("[Piper TTS] Text: '{}' (voice={}, locale={})", 
-                  if request.text.len() > 50 { &request.text[..50] } else { &request.text },
-                  request.voice, request.locale);
-        
-        // 确定使用的语音
-        let voice = if request.voice.is_empty() {
-            // 根据 locale 选择默认 voice
-            let locale_lower = request.locale.to_lowercase();
-            eprintln!("[Piper TTS] Request locale: '{}' (lowercase: '{}')", request.locale, locale_lower);
-            if locale_lower.starts_with("en") {
-                // 英文语音
-                let en_voice = "en_US-lessac-medium";  // 或者根据实际可用的英文 voice 调整
-                eprintln!("[Piper TTS] Using English voice: {}", en_voice);
-                en_voice
-            } else if locale_lower.starts_with("zh") {
-                // 中文语音
-                eprintln!("[Piper TTS] Using Chinese voice: {}", self.config.default_voice);
-                &self.config.default_voice
-            } else {
-                // 默认使用配置的 voice
-                eprintln!("[Piper TTS] Using default voice: {}", self.config.default_voice);
-                &self.config.default_voice
+impl PiperHttpTts {
+    /// 确定使用的语音（请求未指定时按 locale 选择）
+    fn resolve_voice(&self, request: &TtsRequest) -> String {
+        if request.voice.is_empty() {
+            // 根据 locale 选择默认 voice（由语言注册表提供）
+            let registry = language_registry::global();
+            let language = registry.canonical_code(&request.locale);
+            eprintln!("[Piper TTS] Request locale: '{}' (piper locale: {:?})",
+                      request.locale, registry.backend_code(&request.locale, LanguageBackend::Piper));
+            // 配置的 voice 与请求语言一致时优先使用配置的 voice
+            let default_voice_language = self.config.default_voice.split(|c| c == '_' || c == '-').next();
+            match registry.piper_voice(&request.locale) {
+                Some(locale_voice) if language != default_voice_language => {
+                    eprintln!("[Piper TTS] Using locale voice: {}", locale_voice);
+                    locale_voice.to_string()
+                }
+                _ => {
+                    eprintln!("[Piper TTS] Using default voice: {}", self.config.default_voice);
+                    self.config.default_voice.clone()
+                }
             }
         } else {
             eprintln!("[Piper TTS] Using specified voice: {}", request.voice);
-            &request.voice
-        };
+            request.voice.clone()
+        }
+    }
+
+    /// 发送合成请求，返回（状态码已检查的）响应，响应体尚未读取
+    async fn send_request(&self, request: &TtsRequest) -> EngineResult<reqwest::Response> {
+        eprintln!("[Piper TTS] Text: '{}' (voice={}, locale={})", 
+                  if request.text.len() > 50 { &request.text[..50] } else { &request.text },
+                  request.voice, request.locale);
 
         // 构造请求体
         let http_request = PiperHttpRequest {
             text: request.text.clone(),
-            voice: voice.to_string(),
+            voice: self.resolve_voice(request),
             language: if request.locale.is_empty() {
                 None
             } else {
@@ -113,7 +113,6 @@ impl TtsStreaming for PiperHttpTts {
         };
 
         // 发送 HTTP POST 请求
-        let http_start = Instant::now();
         let response = self
             .client
             .post(&self.config.endpoint)
@@ -126,8 +125,6 @@ impl TtsStreaming for PiperHttpTts {
                     e
                 ))
             })?;
-        let http_elapsed = http_start.elapsed().as_millis();
-        eprintln!("[Piper TTS] [HTTP Request] Completed in {}ms", http_elapsed);
 
         // 检查 HTTP 状态码
         if !response.status().is_success() {
@@ -139,6 +136,21 @@ impl TtsStreaming for PiperHttpTts {
             )));
         }
 
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl TtsStreaming for PiperHttpTts {
+    async fn synthesize(&self, request: TtsRequest) -> EngineResult<TtsStreamChunk> {
+        let tts_start = Instant::now();
+        eprintln!("[Piper TTS] ===== TTS Request Started =====");
+
+        let http_start = Instant::now();
+        let response = self.send_request(&request).await?;
+        let http_elapsed = http_start.elapsed().as_millis();
+        eprintln!("[Piper TTS] [HTTP Request] Completed in {}ms", http_elapsed);
+
         // 读取音频数据（WAV 格式）
         let audio_data = response
             .bytes()
@@ -165,18 +177,204 @@ impl TtsStreaming for PiperHttpTts {
 
         // 返回音频块
         Ok(TtsStreamChunk {
+            format: TtsAudioFormat::from_wav_header(&audio_data)?,
             audio: audio_data,
-            timestamp_ms: 0, // Piper HTTP 服务不提供时间戳，设为 0
+            timestamp_ms: 0, // 整段音频从 0 开始
             is_last: true,   // HTTP 请求返回完整音频，标记为最后一块
         })
     }
 
+    /// 流式合成：按 HTTP 响应体（chunked transfer）到达的顺序返回音频块
+    ///
+    /// 第一块包含 WAV 头，之后每块只包含按样本对齐的 PCM 数据；时间戳根据 WAV 头中的格式计算
+    fn synthesize_stream(&self, request: TtsRequest) -> TtsChunkStream<'_> {
+        enum State {
+            Request(TtsRequest),
+            Body(reqwest::Response, WavChunker, Instant),
+            Done,
+        }
+
+        Box::pin(futures::stream::unfold(State::Request(request), move |state| async move {
+            let (mut response, mut chunker, started) = match state {
+                State::Request(request) => {
+                    eprintln!("[Piper TTS] ===== Streaming TTS Request Started =====");
+                    let started = Instant::now();
+                    match self.send_request(&request).await {
+                        Ok(response) => (response, WavChunker::default(), started),
+                        Err(e) => return Some((Err(e), State::Done)),
+                    }
+                }
+                State::Body(response, chunker, started) => (response, chunker, started),
+                State::Done => return None,
+            };
+
+            loop {
+                match response.chunk().await {
+                    Ok(Some(bytes)) => match chunker.push(&bytes) {
+                        Ok(Some(chunk)) => {
+                            if chunk.timestamp_ms == 0 {
+                                eprintln!("[Piper TTS] First audio chunk after {}ms", started.elapsed().as_millis());
+                            }
+                            return Some((Ok(chunk), State::Body(response, chunker, started)));
+                        }
+                        Ok(None) => continue,
+                        Err(e) => return Some((Err(e), State::Done)),
+                    },
+                    Ok(None) => {
+                        let last = chunker.finish();
+                        eprintln!("[Piper TTS] ===== Streaming TTS Request Completed in {}ms =====",
+                                  started.elapsed().as_millis());
+                        return Some((last, State::Done));
+                    }
+                    Err(e) => {
+                        return Some((
+                            Err(EngineError::new(format!("Failed to read audio stream from Piper service: {}", e))),
+                            State::Done,
+                        ));
+                    }
+                }
+            }
+        }))
+    }
+
     async fn close(&self) -> EngineResult<()> {
         // HTTP 客户端无需特殊清理
         Ok(())
     }
 }
 
+/// WAV 流的 PCM 格式（从 WAV 头解析）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WavFormat {
+    sample_rate: u32,
+    channels: u16,
+    bits_per_sample: u16,
+    /// 每个样本帧的字节数（声道数 × 每样本字节数）
+    block_align: usize,
+}
+
+/// 把 WAV 字节流切分为音频块
+///
+/// 解析到 `data` 块之前不输出；之后每次输出已到达的、按样本帧对齐的数据（不完整的样本帧留到下一块），
+/// 并根据已输出的 PCM 数据量计算时间戳。所有块拼接后与原始字节流相同。
+#[derive(Debug, Default)]
+struct WavChunker {
+    format: Option<WavFormat>,
+    /// 尚未输出的字节
+    pending: Vec<u8>,
+    /// WAV 头的长度（尚未输出 WAV 头时为 Some）
+    header_len: Option<usize>,
+    /// 已输出的 PCM 数据字节数
+    emitted_data_bytes: u64,
+    /// 已输出的总字节数
+    emitted_bytes: usize,
+}
+
+impl WavChunker {
+    /// 解析 WAV 头，返回 (格式, 头长度)；数据不足时返回 None
+    fn parse_header(bytes: &[u8]) -> EngineResult<Option<(WavFormat, usize)>> {
+        if bytes.len() < 12 {
+            return Ok(None);
+        }
+        if &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+            return Err(EngineError::new("Piper service returned non-WAV audio stream"));
+        }
+        let mut format = None;
+        let mut offset = 12;
+        while offset + 8 <= bytes.len() {
+            let id = &bytes[offset..offset + 4];
+            let size = u32::from_le_bytes([bytes[offset + 4], bytes[offset + 5], bytes[offset + 6], bytes[offset + 7]]) as usize;
+            let body = offset + 8;
+            if id == b"data" {
+                let format = format.ok_or_else(|| EngineError::new("WAV stream has no fmt chunk before data"))?;
+                return Ok(Some((format, body)));
+            }
+            if body + size > bytes.len() {
+                return Ok(None);
+            }
+            if id == b"fmt " && size >= 16 {
+                let channels = u16::from_le_bytes([bytes[body + 2], bytes[body + 3]]) as usize;
+                let sample_rate = u32::from_le_bytes([bytes[body + 4], bytes[body + 5], bytes[body + 6], bytes[body + 7]]);
+                let bits_per_sample = u16::from_le_bytes([bytes[body + 14], bytes[body + 15]]) as usize;
+                let block_align = channels * bits_per_sample.div_ceil(8);
+                if sample_rate == 0 || block_align == 0 {
+                    return Err(EngineError::new(format!(
+                        "Invalid WAV format: sample_rate={}, channels={}, bits={}", sample_rate, channels, bits_per_sample
+                    )));
+                }
+                format = Some(WavFormat {
+                    sample_rate,
+                    channels: channels as u16,
+                    bits_per_sample: bits_per_sample as u16,
+                    block_align,
+                });
+            }
+            // RIFF 块按 2 字节对齐
+            offset = body + size + (size & 1);
+        }
+        Ok(None)
+    }
+
+    /// 输入新到达的字节，有可输出的数据时返回一个音频块
+    fn push(&mut self, bytes: &[u8]) -> EngineResult<Option<TtsStreamChunk>> {
+        self.pending.extend_from_slice(bytes);
+        let format = match self.format {
+            Some(format) => format,
+            None => match Self::parse_header(&self.pending)? {
+                Some((format, header_len)) => {
+                    self.format = Some(format);
+                    self.header_len = Some(header_len);
+                    format
+                }
+                None => return Ok(None),
+            },
+        };
+
+        let header_len = self.header_len.unwrap_or(0);
+        let data_len = self.pending.len() - header_len;
+        let aligned = data_len - data_len % format.block_align;
+        if aligned == 0 {
+            return Ok(None);
+        }
+        Ok(Some(self.emit(header_len + aligned, false)))
+    }
+
+    /// 响应体读取完毕：输出剩余的全部字节作为最后一块
+    fn finish(&mut self) -> EngineResult<TtsStreamChunk> {
+        if self.emitted_bytes == 0 && self.pending.is_empty() {
+            return Err(EngineError::new("Piper service returned empty audio data"));
+        }
+        if self.format.is_none() {
+            return Err(EngineError::new(format!(
+                "Piper audio stream ended before WAV header was complete ({} bytes)", self.pending.len()
+            )));
+        }
+        Ok(self.emit(self.pending.len(), true))
+    }
+
+    fn emit(&mut self, len: usize, is_last: bool) -> TtsStreamChunk {
+        let format = self.format.expect("format parsed before emitting");
+        let header_len = self.header_len.take().unwrap_or(0);
+        let timestamp_ms = self.emitted_data_bytes / format.block_align as u64 * 1000 / format.sample_rate as u64;
+        let audio: Vec<u8> = self.pending.drain(..len).collect();
+        self.emitted_data_bytes += (len - header_len) as u64;
+        self.emitted_bytes += len;
+        // 第一块带 WAV 头，后续块是裸 PCM
+        let encoding = if header_len > 0 { AudioEncoding::Wav } else { AudioEncoding::Pcm };
+        TtsStreamChunk {
+            audio,
+            format: TtsAudioFormat {
+                encoding,
+                sample_rate: format.sample_rate,
+                channels: format.channels,
+                bits_per_sample: format.bits_per_sample,
+            },
+            timestamp_ms,
+            is_last,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -214,6 +412,65 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    /// 16kHz 单声道 16-bit WAV（data 块长度按流式响应的惯例写为 0xFFFFFFFF）
+    fn streaming_wav(samples: usize) -> Vec<u8> {
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&u32::MAX.to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());  // PCM
+        wav.extend_from_slice(&1u16.to_le_bytes());  // 单声道
+        wav.extend_from_slice(&16000u32.to_le_bytes());
+        wav.extend_from_slice(&32000u32.to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&u32::MAX.to_le_bytes());
+        wav.extend((0..samples).flat_map(|i| (i as i16).to_le_bytes()));
+        wav
+    }
+
+    #[test]
+    fn test_wav_chunker_splits_on_sample_boundaries() {
+        let wav = streaming_wav(16000);
+        let mut chunker = WavChunker::default();
+        let mut chunks = Vec::new();
+        // 网络分块大小与样本边界无关（奇数字节，且第一块不足以包含完整的 WAV 头）
+        for piece in wav.chunks(4001) {
+            if let Some(chunk) = chunker.push(piece).unwrap() {
+                chunks.push(chunk);
+            }
+        }
+        chunks.push(chunker.finish().unwrap());
+
+        assert_eq!(&chunks[0].audio[0..4], b"RIFF");
+        assert_eq!(chunks[0].format, TtsAudioFormat::wav16(16000, 1));
+        assert_eq!(chunks[0].timestamp_ms, 0);
+        assert!(chunks[1..].iter().all(|chunk| chunk.audio.len() % 2 == 0));
+        assert!(chunks[1..].iter().all(|chunk| chunk.format == TtsAudioFormat::pcm16(16000, 1)));
+        assert!(chunks.windows(2).all(|pair| pair[0].timestamp_ms < pair[1].timestamp_ms));
+        // 第二块之前输出了 4001 - 44 = 3957 字节的 PCM，对齐到 3956 字节 = 1978 个样本 ≈ 123ms
+        assert_eq!(chunks[1].timestamp_ms, 123);
+        assert!(chunks.last().unwrap().is_last);
+        assert!(chunks[..chunks.len() - 1].iter().all(|chunk| !chunk.is_last));
+        let joined: Vec<u8> = chunks.into_iter().flat_map(|chunk| chunk.audio).collect();
+        assert_eq!(joined, wav);
+    }
+
+    #[test]
+    fn test_wav_chunker_rejects_invalid_streams() {
+        let mut chunker = WavChunker::default();
+        assert!(chunker.finish().is_err());
+
+        let mut chunker = WavChunker::default();
+        assert!(chunker.push(b"ID3\x03 not a wav file").is_err());
+
+        let mut chunker = WavChunker::default();
+        assert!(chunker.push(&streaming_wav(0)[..20]).unwrap().is_none());
+        assert!(chunker.finish().is_err());
+    }
+
     #[tokio::test]
     #[ignore] // 需要 WSL2 中运行 Piper 服务
     async fn test_piper_http_synthesize() {
@@ -0,0 +1,814 @@
+//! SSML 子集
+//!
+//! NMT 后处理和 persona 规则可以用 SSML 给 TTS 附加韵律提示。`TtsRequest::text` 以 `<speak>` 开头时
+//! 按 SSML 解析，支持的标签：
+//! - `<break time="500ms"/>` / `<break strength="strong"/>`：插入静音
+//! - `<prosody rate pitch volume>`：语速、音高、音量（可嵌套，逐层叠加）
+//! - `<say-as interpret-as="characters|cardinal|date|telephone">`：按读法展开为文字
+//! - `<sub alias="...">`：用 alias 替换内容
+//! - `<phoneme alphabet ph>`：后端通过 `TtsStreaming::accepts_phonemes` 声明接受该音标体系时按音素合成，
+//!   否则只读内容文字（第一次降级时打印警告）；自闭合的 `<phoneme/>` 没有内容，不发音
+//!
+//! 其余标签（`<p>`、`<s>`、`<emphasis>` 等）只保留其中的文字。
+//!
+//! SSML 被降级为逐段的 TTS 请求和静音段（`lower_ssml`）。`SsmlTts` 包装任意 `TtsStreaming` 后端：
+//! 各段分别合成，韵律在 PCM 上处理（与后端无关），再和静音一起按顺序拼接为一段音频。
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::error::{EngineError, EngineResult};
+use crate::tts_streaming::{PcmAudio, TtsAudioFormat, TtsChunkStream, TtsRequest, TtsStreamChunk, TtsStreaming};
+use crate::tts_streaming::text_normalization::{digit_name, read_date, read_number, read_telephone};
+use crate::vad::StreamingResampler;
+
+/// 单个 `<break>` 的最长静音（毫秒）
+const MAX_BREAK_MS: u64 = 10_000;
+
+/// 韵律调整的范围
+const MIN_RATE: f32 = 0.25;
+const MAX_RATE: f32 = 4.0;
+const MAX_PITCH_SEMITONES: f32 = 12.0;
+const MAX_VOLUME_DB: f32 = 20.0;
+
+/// 变速（WSOLA）的帧长和相似度搜索范围（毫秒）
+const WSOLA_FRAME_MS: f32 = 20.0;
+const WSOLA_TOLERANCE_MS: f32 = 5.0;
+
+/// 文本是否为 SSML（以 `<speak` 开头，允许前置 XML 声明）
+pub fn is_ssml(text: &str) -> bool {
+    let text = text.trim_start();
+    let text = match text.strip_prefix("<?xml") {
+        Some(rest) => rest.find("?>").map(|end| rest[end + 2..].trim_start()).unwrap_or(rest),
+        None => text,
+    };
+    text.starts_with("<speak")
+}
+
+/// 韵律（相对于后端默认值）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Prosody {
+    /// 语速倍数（2.0 表示时长减半）
+    pub rate: f32,
+    /// 音高偏移（半音）
+    pub pitch_semitones: f32,
+    /// 音量偏移（dB，`silent` 为负无穷）
+    pub volume_db: f32,
+}
+
+impl Default for Prosody {
+    fn default() -> Self {
+        Self { rate: 1.0, pitch_semitones: 0.0, volume_db: 0.0 }
+    }
+}
+
+impl Prosody {
+    pub fn is_neutral(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 线性增益
+    pub fn gain(&self) -> f32 {
+        10f32.powf(self.volume_db / 20.0)
+    }
+
+    /// 嵌套的 `<prosody>`：在外层的基础上叠加
+    fn compose(&self, attrs: &HashMap<String, String>) -> Self {
+        let mut prosody = *self;
+        if let Some(rate) = attrs.get("rate") {
+            match parse_rate(rate) {
+                Some(value) => prosody.rate = (prosody.rate * value).clamp(MIN_RATE, MAX_RATE),
+                None => eprintln!("[SSML] ⚠️ Ignoring invalid prosody rate: '{}'", rate),
+            }
+        }
+        if let Some(pitch) = attrs.get("pitch") {
+            match parse_pitch(pitch) {
+                Some(value) => {
+                    prosody.pitch_semitones = (prosody.pitch_semitones + value).clamp(-MAX_PITCH_SEMITONES, MAX_PITCH_SEMITONES)
+                }
+                None => eprintln!("[SSML] ⚠️ Ignoring invalid prosody pitch: '{}'", pitch),
+            }
+        }
+        if let Some(volume) = attrs.get("volume") {
+            match parse_volume(volume) {
+                Some(value) => prosody.volume_db = (prosody.volume_db + value).min(MAX_VOLUME_DB),
+                None => eprintln!("[SSML] ⚠️ Ignoring invalid prosody volume: '{}'", volume),
+            }
+        }
+        prosody
+    }
+}
+
+/// `<phoneme>` 指定的发音
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SsmlPhoneme {
+    /// 音标体系（`alphabet` 属性，省略时为 "ipa"）
+    pub alphabet: String,
+    /// 音素串（`ph` 属性）
+    pub ph: String,
+}
+
+/// SSML 解析结果：带韵律的文字、带指定发音的文字或静音，按朗读顺序排列
+#[derive(Debug, Clone, PartialEq)]
+pub enum SsmlPart {
+    Text { text: String, prosody: Prosody },
+    Phoneme { text: String, phoneme: SsmlPhoneme, prosody: Prosody },
+    Break { duration_ms: u64 },
+}
+
+/// SSML 降级后的一段：一个 TTS 请求（合成后应用韵律）、一个按音素合成的请求或一段静音
+#[derive(Debug, Clone)]
+pub enum SsmlSegment {
+    Speech { request: TtsRequest, prosody: Prosody },
+    Phoneme { request: TtsRequest, phoneme: SsmlPhoneme, prosody: Prosody },
+    Silence { duration_ms: u64 },
+}
+
+/// 解析 SSML（`locale` 决定 `<say-as>` 的读法）
+///
+/// XML 结构错误（标签不匹配、未闭合、缺少 `<speak>` 根元素）返回错误；
+/// 无法识别的属性值只打印警告并忽略，避免上游生成的提示导致整句无法合成。
+/// `<phoneme>` 保留为 `SsmlPart::Phoneme`。
+pub fn parse_ssml(input: &str, locale: &str) -> EngineResult<Vec<SsmlPart>> {
+    parse_ssml_with(input, locale, &|_| true)
+}
+
+/// 解析 SSML，`accepts_phonemes` 返回 false 的 `<phoneme>` 降级为内容文字（与相邻文字合并）
+fn parse_ssml_with(input: &str, locale: &str, accepts_phonemes: &dyn Fn(&str) -> bool) -> EngineResult<Vec<SsmlPart>> {
+    let zh = locale.starts_with("zh");
+    let mut parts = PartsBuilder::default();
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root_closed = false;
+    let mut rest = input;
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("<!--") {
+            let end = after.find("-->").ok_or_else(|| EngineError::new("SSML: unterminated comment"))?;
+            rest = &after[end + 3..];
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix("<?") {
+            let end = after.find("?>").ok_or_else(|| EngineError::new("SSML: unterminated processing instruction"))?;
+            rest = &after[end + 2..];
+            continue;
+        }
+        if rest.starts_with('<') {
+            let end = find_tag_end(rest).ok_or_else(|| EngineError::new("SSML: unterminated tag"))?;
+            let tag = parse_tag(&rest[1..end])?;
+            rest = &rest[end + 1..];
+            if root_closed {
+                return Err(EngineError::new(format!("SSML: <{}> after </speak>", tag.name)));
+            }
+            match tag.kind {
+                TagKind::Close => {
+                    let element = stack.pop().ok_or_else(|| {
+                        EngineError::new(format!("SSML: unexpected </{}>", tag.name))
+                    })?;
+                    if element.name != tag.name {
+                        return Err(EngineError::new(format!(
+                            "SSML: mismatched tags <{}> and </{}>", element.name, tag.name
+                        )));
+                    }
+                    match element.capture {
+                        Some(Capture::Phoneme { phoneme, text }) if accepts_phonemes(&phoneme.alphabet) => {
+                            parts.push_phoneme(&text, phoneme, element.prosody);
+                        }
+                        Some(capture) => parts.push_text(&capture.expand(zh), element.prosody),
+                        None => {}
+                    }
+                    root_closed = stack.is_empty();
+                }
+                TagKind::Open | TagKind::SelfClosing => {
+                    let parent = match stack.last() {
+                        Some(parent) => parent,
+                        None if tag.name == "speak" => {
+                            if tag.kind == TagKind::Open {
+                                stack.push(Element { name: tag.name, prosody: Prosody::default(), capture: None });
+                            } else {
+                                root_closed = true;
+                            }
+                            continue;
+                        }
+                        None => return Err(EngineError::new(format!("SSML: expected <speak>, found <{}>", tag.name))),
+                    };
+                    if parent.capture.is_some() {
+                        return Err(EngineError::new(format!("SSML: <{}> may only contain text", parent.name)));
+                    }
+                    let prosody = parent.prosody;
+                    let element = match tag.name.as_str() {
+                        "speak" => return Err(EngineError::new("SSML: nested <speak>")),
+                        "break" => {
+                            parts.push_break(break_duration_ms(&tag.attrs));
+                            Element { name: tag.name.clone(), prosody, capture: None }
+                        }
+                        "prosody" => Element { prosody: prosody.compose(&tag.attrs), name: tag.name.clone(), capture: None },
+                        "say-as" => {
+                            let interpret_as = tag.attrs.get("interpret-as").cloned().ok_or_else(|| {
+                                EngineError::new("SSML: <say-as> requires interpret-as")
+                            })?;
+                            let capture = Capture::SayAs { interpret_as, format: tag.attrs.get("format").cloned(), text: String::new() };
+                            Element { name: tag.name.clone(), prosody, capture: Some(capture) }
+                        }
+                        "sub" => {
+                            let alias = tag.attrs.get("alias").cloned().ok_or_else(|| {
+                                EngineError::new("SSML: <sub> requires alias")
+                            })?;
+                            Element { name: tag.name.clone(), prosody, capture: Some(Capture::Sub { alias }) }
+                        }
+                        "phoneme" => match tag.attrs.get("ph") {
+                            Some(ph) => {
+                                let phoneme = SsmlPhoneme {
+                                    alphabet: tag.attrs.get("alphabet").cloned().unwrap_or_else(|| "ipa".to_string()),
+                                    ph: ph.clone(),
+                                };
+                                let capture = Capture::Phoneme { phoneme, text: String::new() };
+                                Element { name: tag.name.clone(), prosody, capture: Some(capture) }
+                            }
+                            None => {
+                                eprintln!("[SSML] ⚠️ <phoneme> without ph, keeping its text");
+                                Element { name: tag.name.clone(), prosody, capture: None }
+                            }
+                        },
+                        "p" | "s" | "emphasis" | "voice" | "lang" | "mark" => {
+                            Element { name: tag.name.clone(), prosody, capture: None }
+                        }
+                        other => {
+                            eprintln!("[SSML] ⚠️ Unsupported tag <{}>, keeping its text", other);
+                            Element { name: tag.name.clone(), prosody, capture: None }
+                        }
+                    };
+                    if tag.kind == TagKind::SelfClosing {
+                        // 自闭合的 <say-as/> / <sub/> / <phoneme/> 没有内容，按空文本展开
+                        if let Some(capture) = element.capture {
+                            parts.push_text(&capture.expand(zh), element.prosody);
+                        }
+                    } else {
+                        stack.push(element);
+                    }
+                }
+            }
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = decode_entities(&rest[..end])?;
+            rest = &rest[end..];
+            match stack.last_mut() {
+                Some(Element { capture: Some(capture), .. }) => capture.push_text(&text),
+                Some(element) => parts.push_text(&text, element.prosody),
+                None if text.trim().is_empty() => {}
+                None => return Err(EngineError::new("SSML: text outside <speak>")),
+            }
+        }
+    }
+
+    if let Some(element) = stack.last() {
+        return Err(EngineError::new(format!("SSML: unclosed <{}>", element.name)));
+    }
+    if !root_closed {
+        return Err(EngineError::new("SSML: missing <speak> root element"));
+    }
+    Ok(parts.finish())
+}
+
+/// 把 SSML 请求降级为逐段的 TTS 请求和静音（每段沿用原请求的音色、语速等参数）
+pub fn lower_ssml(request: &TtsRequest) -> EngineResult<Vec<SsmlSegment>> {
+    Ok(lower_parts(request, parse_ssml(&request.text, &request.locale)?))
+}
+
+fn lower_parts(request: &TtsRequest, parts: Vec<SsmlPart>) -> Vec<SsmlSegment> {
+    parts
+        .into_iter()
+        .map(|part| match part {
+            SsmlPart::Text { text, prosody } => SsmlSegment::Speech {
+                request: TtsRequest { text, ..request.clone() },
+                prosody,
+            },
+            SsmlPart::Phoneme { text, phoneme, prosody } => SsmlSegment::Phoneme {
+                request: TtsRequest { text, ..request.clone() },
+                phoneme,
+                prosody,
+            },
+            SsmlPart::Break { duration_ms } => SsmlSegment::Silence { duration_ms },
+        })
+        .collect()
+}
+
+/// 为任意 TTS 后端增加 SSML 支持
+///
+/// 普通文本直接交给内部后端（包括流式合成）；SSML 按段合成后拼接为一块音频，
+/// 格式与第一个语音段相同（没有语音段时为 16kHz 单声道裸 PCM）。
+pub struct SsmlTts {
+    inner: Arc<dyn TtsStreaming>,
+    /// 是否已经警告过后端不接受 `<phoneme>`（只警告一次）
+    phoneme_warned: AtomicBool,
+}
+
+impl SsmlTts {
+    pub fn new(inner: Arc<dyn TtsStreaming>) -> Self {
+        Self { inner, phoneme_warned: AtomicBool::new(false) }
+    }
+
+    /// 包装为 `Arc<dyn TtsStreaming>`（用于 `CoreEngineBuilder::build`）
+    pub fn wrap(inner: Arc<dyn TtsStreaming>) -> Arc<dyn TtsStreaming> {
+        Arc::new(Self::new(inner))
+    }
+
+    /// 后端是否接受该音标体系的音素（不接受时第一次打印警告）
+    fn accepts_phonemes(&self, alphabet: &str) -> bool {
+        let accepted = self.inner.accepts_phonemes(alphabet);
+        if !accepted && !self.phoneme_warned.swap(true, Ordering::Relaxed) {
+            eprintln!("[SSML] ⚠️ TTS backend does not accept <phoneme alphabet=\"{}\">, speaking the element text instead", alphabet);
+        }
+        accepted
+    }
+}
+
+#[async_trait]
+impl TtsStreaming for SsmlTts {
+    async fn synthesize(&self, request: TtsRequest) -> EngineResult<TtsStreamChunk> {
+        if !is_ssml(&request.text) {
+            return self.inner.synthesize(request).await;
+        }
+        let parts = parse_ssml_with(&request.text, &request.locale, &|alphabet| self.accepts_phonemes(alphabet))?;
+        let segments = lower_parts(&request, parts);
+        eprintln!("[SSML] Lowered SSML into {} segments", segments.len());
+
+        // 语音段并行合成，结果按顺序拼接
+        let synthesized = futures::future::try_join_all(segments.iter().map(|segment| async move {
+            match segment {
+                SsmlSegment::Speech { request, .. } => self.inner.synthesize(request.clone()).await.map(Some),
+                SsmlSegment::Phoneme { request, phoneme, .. } => {
+                    self.inner.synthesize_phonemes(request.clone(), phoneme.clone()).await.map(Some)
+                }
+                SsmlSegment::Silence { .. } => Ok(None),
+            }
+        }))
+        .await?;
+
+        let output = synthesized
+            .iter()
+            .flatten()
+            .map(|chunk| chunk.format)
+            .next()
+            .unwrap_or_else(|| TtsAudioFormat::pcm16(16000, 1));
+        let mut samples = Vec::new();
+        for (segment, chunk) in segments.iter().zip(synthesized) {
+            match (segment, chunk) {
+                (SsmlSegment::Speech { prosody, .. } | SsmlSegment::Phoneme { prosody, .. }, Some(chunk))
+                    if !chunk.audio.is_empty() =>
+                {
+                    let pcm = PcmAudio::decode(&chunk.audio, &chunk.format)?;
+                    let pcm = apply_prosody(pcm, *prosody)?.convert(output.sample_rate, output.channels)?;
+                    samples.extend(pcm.samples);
+                }
+                (SsmlSegment::Silence { duration_ms }, _) => {
+                    let frames = (*duration_ms * output.sample_rate as u64 / 1000) as usize;
+                    samples.extend(std::iter::repeat_n(0i16, frames * output.channels as usize));
+                }
+                _ => {}
+            }
+        }
+
+        let audio = PcmAudio { samples, sample_rate: output.sample_rate, channels: output.channels };
+        Ok(TtsStreamChunk {
+            audio: audio.encode(output.encoding),
+            format: TtsAudioFormat { encoding: output.encoding, ..TtsAudioFormat::pcm16(output.sample_rate, output.channels) },
+            timestamp_ms: 0,
+            is_last: true,
+        })
+    }
+
+    fn synthesize_stream(&self, request: TtsRequest) -> TtsChunkStream<'_> {
+        if is_ssml(&request.text) {
+            Box::pin(futures::stream::once(self.synthesize(request)))
+        } else {
+            self.inner.synthesize_stream(request)
+        }
+    }
+
+    async fn close(&self) -> EngineResult<()> {
+        self.inner.close().await
+    }
+}
+
+// ---------------------------------------------------------------------------
+// XML 解析
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TagKind {
+    Open,
+    Close,
+    SelfClosing,
+}
+
+#[derive(Debug)]
+struct Tag {
+    name: String,
+    kind: TagKind,
+    attrs: HashMap<String, String>,
+}
+
+/// 打开的元素
+struct Element {
+    name: String,
+    prosody: Prosody,
+    /// 需要收集内容后整体展开的元素（`<say-as>`、`<sub>`、`<phoneme>`）
+    capture: Option<Capture>,
+}
+
+enum Capture {
+    SayAs { interpret_as: String, format: Option<String>, text: String },
+    Sub { alias: String },
+    Phoneme { phoneme: SsmlPhoneme, text: String },
+}
+
+impl Capture {
+    fn push_text(&mut self, content: &str) {
+        if let Capture::SayAs { text, .. } | Capture::Phoneme { text, .. } = self {
+            text.push_str(content);
+        }
+    }
+
+    /// 展开为朗读的文字（`<phoneme>` 降级为内容文字）
+    fn expand(self, zh: bool) -> String {
+        match self {
+            Capture::Sub { alias } => alias,
+            Capture::Phoneme { text, .. } => text,
+            Capture::SayAs { interpret_as, format, text } => {
+                let text = text.trim();
+                let expanded = match interpret_as.as_str() {
+                    "characters" | "spell-out" => Some(read_characters(text, zh)),
+                    "cardinal" | "number" => read_number(text, zh),
+                    "date" => read_date(text, format.as_deref().unwrap_or("ymd"), zh),
+                    "telephone" => Some(read_telephone(text, zh)),
+                    other => {
+                        eprintln!("[SSML] ⚠️ Unsupported say-as interpret-as=\"{}\", keeping text", other);
+                        None
+                    }
+                };
+                expanded.unwrap_or_else(|| {
+                    eprintln!("[SSML] ⚠️ Cannot read '{}' as {}, keeping text", text, interpret_as);
+                    text.to_string()
+                })
+            }
+        }
+    }
+}
+
+/// 按顺序累积文字和静音：相同韵律的相邻文字合并，相邻静音时长相加，空白折叠为一个空格
+#[derive(Default)]
+struct PartsBuilder {
+    parts: Vec<SsmlPart>,
+}
+
+impl PartsBuilder {
+    fn push_text(&mut self, content: &str, prosody: Prosody) {
+        if content.is_empty() {
+            return;
+        }
+        let mut collapsed = String::with_capacity(content.len());
+        for ch in content.chars() {
+            if ch.is_whitespace() {
+                if !collapsed.ends_with(' ') {
+                    collapsed.push(' ');
+                }
+            } else {
+                collapsed.push(ch);
+            }
+        }
+        match self.parts.last_mut() {
+            Some(SsmlPart::Text { text, prosody: last }) if *last == prosody => {
+                if text.ends_with(' ') && collapsed.starts_with(' ') {
+                    collapsed.remove(0);
+                }
+                text.push_str(&collapsed);
+            }
+            _ => self.parts.push(SsmlPart::Text { text: collapsed, prosody }),
+        }
+    }
+
+    /// 带指定发音的文字单独成段，不与相邻文字合并
+    fn push_phoneme(&mut self, content: &str, phoneme: SsmlPhoneme, prosody: Prosody) {
+        let text = content.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !text.is_empty() {
+            self.parts.push(SsmlPart::Phoneme { text, phoneme, prosody });
+        }
+    }
+
+    fn push_break(&mut self, duration_ms: u64) {
+        if duration_ms == 0 {
+            return;
+        }
+        match self.parts.last_mut() {
+            Some(SsmlPart::Break { duration_ms: last }) => *last = (*last + duration_ms).min(MAX_BREAK_MS),
+            _ => self.parts.push(SsmlPart::Break { duration_ms }),
+        }
+    }
+
+    fn finish(self) -> Vec<SsmlPart> {
+        let mut parts: Vec<SsmlPart> = Vec::with_capacity(self.parts.len());
+        for part in self.parts {
+            match part {
+                SsmlPart::Text { text, prosody } => {
+                    let text = text.trim();
+                    if !text.is_empty() {
+                        parts.push(SsmlPart::Text { text: text.to_string(), prosody });
+                    }
+                }
+                SsmlPart::Phoneme { .. } => parts.push(part),
+                SsmlPart::Break { duration_ms } => match parts.last_mut() {
+                    // 去掉空白文字后两段静音可能相邻
+                    Some(SsmlPart::Break { duration_ms: last }) => *last = (*last + duration_ms).min(MAX_BREAK_MS),
+                    _ => parts.push(SsmlPart::Break { duration_ms }),
+                },
+            }
+        }
+        parts
+    }
+}
+
+/// 找到标签结束的 `>`（跳过属性值中的引号内容）
+fn find_tag_end(input: &str) -> Option<usize> {
+    let mut quote = None;
+    for (idx, ch) in input.char_indices().skip(1) {
+        match (quote, ch) {
+            (None, '"' | '\'') => quote = Some(ch),
+            (Some(q), _) if q == ch => quote = None,
+            (None, '>') => return Some(idx),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// 解析 `<` 和 `>` 之间的内容
+fn parse_tag(content: &str) -> EngineResult<Tag> {
+    let (kind, body) = if let Some(body) = content.strip_prefix('/') {
+        (TagKind::Close, body)
+    } else if let Some(body) = content.strip_suffix('/') {
+        (TagKind::SelfClosing, body)
+    } else {
+        (TagKind::Open, content)
+    };
+    let body = body.trim();
+    let name_end = body.find(char::is_whitespace).unwrap_or(body.len());
+    let name = &body[..name_end];
+    if name.is_empty() {
+        return Err(EngineError::new(format!("SSML: invalid tag <{}>", content)));
+    }
+
+    let mut attrs = HashMap::new();
+    let mut rest = body[name_end..].trim_start();
+    while !rest.is_empty() {
+        let invalid = || EngineError::new(format!("SSML: invalid attributes in <{}>", content));
+        let eq = rest.find('=').ok_or_else(invalid)?;
+        let key = rest[..eq].trim();
+        let value = rest[eq + 1..].trim_start();
+        let quote = value.chars().next().filter(|ch| matches!(ch, '"' | '\'')).ok_or_else(invalid)?;
+        let close = value[1..].find(quote).ok_or_else(invalid)?;
+        attrs.insert(key.to_string(), decode_entities(&value[1..1 + close])?);
+        rest = value[close + 2..].trim_start();
+    }
+    Ok(Tag { name: name.to_string(), kind, attrs })
+}
+
+/// 解码 XML 实体
+fn decode_entities(text: &str) -> EngineResult<String> {
+    if !text.contains('&') {
+        return Ok(text.to_string());
+    }
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find(';')
+            .ok_or_else(|| EngineError::new(format!("SSML: unterminated entity in '{}'", text)))?;
+        let entity = &rest[start + 1..start + end];
+        let ch = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16).ok())
+                .unwrap_or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        decoded.push(ch.ok_or_else(|| EngineError::new(format!("SSML: unknown entity &{};", entity)))?);
+        rest = &rest[start + end + 1..];
+    }
+    decoded.push_str(rest);
+    Ok(decoded)
+}
+
+// ---------------------------------------------------------------------------
+// 属性值
+// ---------------------------------------------------------------------------
+
+/// `<break>` 的静音时长：`time` 优先，其次 `strength`（默认 medium）
+fn break_duration_ms(attrs: &HashMap<String, String>) -> u64 {
+    if let Some(time) = attrs.get("time") {
+        match parse_time_ms(time) {
+            Some(ms) => return ms.min(MAX_BREAK_MS),
+            None => eprintln!("[SSML] ⚠️ Ignoring invalid break time: '{}'", time),
+        }
+    }
+    match attrs.get("strength").map(String::as_str) {
+        Some("none") => 0,
+        Some("x-weak") => 100,
+        Some("weak") => 250,
+        Some("strong") => 700,
+        Some("x-strong") => 1000,
+        _ => 400,
+    }
+}
+
+/// "500ms" / "1.5s"
+fn parse_time_ms(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let ms = if let Some(ms) = value.strip_suffix("ms") {
+        ms.trim().parse::<f32>().ok()?
+    } else {
+        value.strip_suffix('s')?.trim().parse::<f32>().ok()? * 1000.0
+    };
+    (ms.is_finite() && ms >= 0.0).then(|| ms.round() as u64)
+}
+
+/// 语速倍数："slow" / "120%"（相对默认语速）/ "+20%" / "-10%" / "1.2"
+fn parse_rate(value: &str) -> Option<f32> {
+    let value = value.trim();
+    let rate = match value {
+        "x-slow" => 0.5,
+        "slow" => 0.75,
+        "medium" | "default" => 1.0,
+        "fast" => 1.25,
+        "x-fast" => 1.5,
+        _ => match value.strip_suffix('%') {
+            Some(percent) if percent.starts_with(['+', '-']) => 1.0 + percent.parse::<f32>().ok()? / 100.0,
+            Some(percent) => percent.parse::<f32>().ok()? / 100.0,
+            None => value.parse::<f32>().ok()?,
+        },
+    };
+    (rate.is_finite() && rate > 0.0).then_some(rate)
+}
+
+/// 音高偏移（半音）："high" / "+2st" / "-10%"
+fn parse_pitch(value: &str) -> Option<f32> {
+    let value = value.trim();
+    let semitones = match value {
+        "x-low" => -6.0,
+        "low" => -3.0,
+        "medium" | "default" => 0.0,
+        "high" => 3.0,
+        "x-high" => 6.0,
+        _ => {
+            if let Some(st) = value.strip_suffix("st") {
+                st.parse::<f32>().ok()?
+            } else {
+                let factor = 1.0 + value.strip_suffix('%')?.parse::<f32>().ok()? / 100.0;
+                if factor <= 0.0 {
+                    return None;
+                }
+                12.0 * factor.log2()
+            }
+        }
+    };
+    semitones.is_finite().then_some(semitones)
+}
+
+/// 音量偏移（dB）："loud" / "+6dB" / "silent"
+fn parse_volume(value: &str) -> Option<f32> {
+    let value = value.trim();
+    match value {
+        "silent" => Some(f32::NEG_INFINITY),
+        "x-soft" => Some(-12.0),
+        "soft" => Some(-6.0),
+        "medium" | "default" => Some(0.0),
+        "loud" => Some(6.0),
+        "x-loud" => Some(12.0),
+        _ => value.strip_suffix("dB")?.trim().parse::<f32>().ok().filter(|db| db.is_finite()),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// <say-as> 读法
+// ---------------------------------------------------------------------------
+
+/// 逐字读："ABC" -> "A B C"，数字读作数字名
+fn read_characters(text: &str, zh: bool) -> String {
+    text.chars()
+        .filter(|ch| !ch.is_whitespace())
+        .map(|ch| match ch.to_digit(10) {
+            Some(digit) => digit_name(digit, zh).to_string(),
+            None => ch.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// ---------------------------------------------------------------------------
+// 韵律处理
+// ---------------------------------------------------------------------------
+
+/// 在 PCM 上应用韵律（输出为单声道）
+///
+/// 音高：重采样到 `sample_rate / pitch` 后按原采样率播放（音高升高 `pitch` 倍，时长缩短同样倍数），
+/// 再用 WSOLA 变速补偿，最终时长只由 `rate` 决定。
+fn apply_prosody(pcm: PcmAudio, prosody: Prosody) -> EngineResult<PcmAudio> {
+    if prosody.is_neutral() {
+        return Ok(pcm);
+    }
+    let sample_rate = pcm.sample_rate;
+    let mono = pcm.convert(sample_rate, 1)?;
+    let mut samples: Vec<f32> = mono.samples.iter().map(|&s| s as f32 / 32768.0).collect();
+
+    let pitch = 2f32.powf(prosody.pitch_semitones / 12.0);
+    if (pitch - 1.0).abs() > 1e-3 {
+        let shifted_rate = ((sample_rate as f32 / pitch).round() as u32).max(1);
+        let mut resampler = StreamingResampler::new(sample_rate, shifted_rate)?;
+        let mut shifted = resampler.process(&samples);
+        shifted.extend(resampler.flush());
+        samples = shifted;
+    }
+    let samples = time_stretch(&samples, sample_rate, prosody.rate / pitch);
+
+    let gain = prosody.gain();
+    Ok(PcmAudio {
+        samples: samples
+            .iter()
+            .map(|&x| (x * gain * 32768.0).round().clamp(i16::MIN as f32, i16::MAX as f32) as i16)
+            .collect(),
+        sample_rate,
+        channels: 1,
+    })
+}
+
+/// WSOLA 变速不变调：输出时长为输入的 `1 / rate`
+///
+/// 按固定输出步长叠加加窗帧，每帧在名义输入位置附近搜索与上一帧自然延续最相似的起点，避免相位不连续。
+fn time_stretch(input: &[f32], sample_rate: u32, rate: f32) -> Vec<f32> {
+    if input.is_empty() || (rate - 1.0).abs() < 1e-3 {
+        return input.to_vec();
+    }
+    let frame = ((sample_rate as f32 * WSOLA_FRAME_MS / 1000.0) as usize).max(16) & !1;
+    let hop = frame / 2;
+    let tolerance = (sample_rate as f32 * WSOLA_TOLERANCE_MS / 1000.0) as isize;
+    let output_len = (input.len() as f32 / rate).round() as usize;
+    let window: Vec<f32> = (0..frame)
+        .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / frame as f32).cos())
+        .collect();
+    let sample = |idx: isize| if idx >= 0 { input.get(idx as usize).copied().unwrap_or(0.0) } else { 0.0 };
+
+    let mut output = vec![0.0f32; output_len + frame];
+    let mut weight = vec![0.0f32; output_len + frame];
+    let mut previous = 0isize;
+    let mut k = 0usize;
+    while k * hop < output_len {
+        let nominal = (k as f32 * hop as f32 * rate).round() as isize;
+        let position = if k == 0 {
+            0
+        } else {
+            let natural = previous + hop as isize;
+            let mut best = (nominal, f32::MIN);
+            for candidate in (nominal - tolerance).max(0)..=nominal + tolerance {
+                let score: f32 = (0..hop as isize).map(|i| sample(candidate + i) * sample(natural + i)).sum();
+                if score > best.1 {
+                    best = (candidate, score);
+                }
+            }
+            best.0
+        };
+        for (i, &w) in window.iter().enumerate() {
+            output[k * hop + i] += w * sample(position + i as isize);
+            weight[k * hop + i] += w;
+        }
+        previous = position;
+        k += 1;
+    }
+
+    output.truncate(output_len);
+    for (value, &w) in output.iter_mut().zip(&weight) {
+        if w > 1e-3 {
+            *value /= w;
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_stretch_duration() {
+        let tone: Vec<f32> = (0..16000).map(|i| (i as f32 * 0.1).sin() * 0.5).collect();
+        assert_eq!(time_stretch(&tone, 16000, 2.0).len(), 8000);
+        assert_eq!(time_stretch(&tone, 16000, 0.5).len(), 32000);
+    }
+}
@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 
 use crate::error::EngineResult;
-use super::{TtsRequest, TtsStreamChunk, TtsStreaming};
+use super::{TtsAudioFormat, TtsRequest, TtsStreamChunk, TtsStreaming};
 
 /// TTS 适配器的 stub 实现（用于测试和开发）
 pub struct TtsStub;
@@ -18,6 +18,7 @@ impl TtsStreaming for TtsStub {
         // Stub 实现：返回空音频
         Ok(TtsStreamChunk {
             audio: vec![],
+            format: TtsAudioFormat::pcm16(16000, 1),
             timestamp_ms: 0,
             is_last: true,
         })
@@ -0,0 +1,1064 @@
+//! TTS 文本规范化（中文 / 英文）
+//!
+//! 各 TTS 后端只能可靠地朗读文字，数字、符号和缩写的读法取决于语境（"2025年" 读作"二零二五年"，
+//! "10:30" 读作"十点三十分"）。`TextNormalizer` 在合成前把它们展开为文字：
+//! - 基数（千分位、小数、负数）、范围（"3-5"）、英文序数（"21st"）
+//! - 百分数、货币（"$3.5M"、"¥12.50"）
+//! - 日期（"2025-03-05"、"3/21/2025"、"2025年"）、时间（"10:30"、"9:05 pm"）
+//! - 电话号码（"138-1234-5678"、"+1 555-123-4567"、中文语境下的 11 位手机号）
+//! - 单位（"5km"、"36.5℃"、"500 g"）、年代（"1990s"、"90s"）、常见缩写（"Dr."、"e.g."）和 "&"
+//!
+//! 单字母单位（"s"、"m"、"h"、"g"、"L"）与数字之间必须有空格，紧跟数字的字母可能是别的意思
+//! （"1990s" 是年代，"4g" 是网络制式），不按单位读。
+//!
+//! 展开结果只含文字，规范化是幂等的。`NormalizedTts` 包装任意 `TtsStreaming` 后端，在合成前规范化请求文本；
+//! SSML 请求原样透传（由外层的 `SsmlTts` 先降级为普通文本段，再逐段经过规范化）。
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::error::EngineResult;
+use crate::language_registry;
+use crate::tts_streaming::{is_ssml, SsmlPhoneme, TtsChunkStream, TtsRequest, TtsStreamChunk, TtsStreaming};
+
+const ZH_DIGITS: [&str; 10] = ["零", "一", "二", "三", "四", "五", "六", "七", "八", "九"];
+const EN_ONES: [&str; 20] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+];
+const EN_TENS: [&str; 10] = ["", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+const EN_MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+
+/// 超过该位数的整数逐位读（卡号、订单号等）
+const MAX_CARDINAL_DIGITS: usize = 16;
+
+/// 单位：符号、中文读法、英文单数、英文复数（中文读法为空时保留符号）
+const UNITS: &[(&str, &str, &str, &str)] = &[
+    ("km/h", "公里每小时", "kilometer per hour", "kilometers per hour"),
+    ("m/s", "米每秒", "meter per second", "meters per second"),
+    ("mph", "英里每小时", "mile per hour", "miles per hour"),
+    ("km", "公里", "kilometer", "kilometers"),
+    ("cm", "厘米", "centimeter", "centimeters"),
+    ("mm", "毫米", "millimeter", "millimeters"),
+    ("m", "米", "meter", "meters"),
+    ("ft", "英尺", "foot", "feet"),
+    ("kg", "公斤", "kilogram", "kilograms"),
+    ("mg", "毫克", "milligram", "milligrams"),
+    ("g", "克", "gram", "grams"),
+    ("lbs", "磅", "pound", "pounds"),
+    ("lb", "磅", "pound", "pounds"),
+    ("ml", "毫升", "milliliter", "milliliters"),
+    ("mL", "毫升", "milliliter", "milliliters"),
+    ("L", "升", "liter", "liters"),
+    ("ms", "毫秒", "millisecond", "milliseconds"),
+    ("min", "分钟", "minute", "minutes"),
+    ("h", "小时", "hour", "hours"),
+    ("s", "秒", "second", "seconds"),
+    ("°C", "摄氏度", "degree Celsius", "degrees Celsius"),
+    ("℃", "摄氏度", "degree Celsius", "degrees Celsius"),
+    ("°F", "华氏度", "degree Fahrenheit", "degrees Fahrenheit"),
+    ("kHz", "千赫", "kilohertz", "kilohertz"),
+    ("Hz", "赫兹", "hertz", "hertz"),
+    ("kW", "千瓦", "kilowatt", "kilowatts"),
+    ("KB", "", "kilobyte", "kilobytes"),
+    ("MB", "", "megabyte", "megabytes"),
+    ("GB", "", "gigabyte", "gigabytes"),
+    ("TB", "", "terabyte", "terabytes"),
+];
+
+/// 货币：符号、中文名、英文单数、英文复数、英文辅币（单数 / 复数）
+type Currency = (char, &'static str, &'static str, &'static str, Option<(&'static str, &'static str)>);
+
+const CURRENCIES: &[Currency] = &[
+    ('$', "美元", "dollar", "dollars", Some(("cent", "cents"))),
+    ('€', "欧元", "euro", "euros", Some(("cent", "cents"))),
+    ('£', "英镑", "pound", "pounds", Some(("penny", "pence"))),
+    ('¥', "元", "yuan", "yuan", None),
+    ('￥', "元", "yuan", "yuan", None),
+];
+
+/// 金额的数量级后缀：后缀、中文、英文
+const SCALES: &[(&str, &str, &str)] = &[
+    ("K", "千", "thousand"),
+    ("k", "千", "thousand"),
+    ("M", "百万", "million"),
+    ("B", "十亿", "billion"),
+    ("bn", "十亿", "billion"),
+    ("万", "万", "ten thousand"),
+    ("亿", "亿", "hundred million"),
+];
+
+/// 英文缩写
+const EN_ABBREVIATIONS: &[(&str, &str)] = &[
+    ("Mr.", "Mister"),
+    ("Mrs.", "Missus"),
+    ("Ms.", "Miz"),
+    ("Dr.", "Doctor"),
+    ("Prof.", "Professor"),
+    ("Jr.", "Junior"),
+    ("Sr.", "Senior"),
+    ("vs.", "versus"),
+    ("etc.", "et cetera"),
+    ("e.g.", "for example"),
+    ("i.e.", "that is"),
+    ("approx.", "approximately"),
+    ("Inc.", "Incorporated"),
+    ("Ltd.", "Limited"),
+    ("Dept.", "Department"),
+    ("Jan.", "January"),
+    ("Feb.", "February"),
+    ("Aug.", "August"),
+    ("Sept.", "September"),
+    ("Oct.", "October"),
+    ("Nov.", "November"),
+    ("Dec.", "December"),
+];
+
+/// 中文语境中出现的英文缩写
+const ZH_ABBREVIATIONS: &[(&str, &str)] = &[
+    ("vs.", "对"),
+    ("etc.", "等"),
+    ("e.g.", "例如"),
+    ("i.e.", "即"),
+];
+
+/// 规范化语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalizationLanguage {
+    Chinese,
+    English,
+}
+
+impl NormalizationLanguage {
+    /// 由 locale 判断语言（通过语言注册表解析 BCP-47 标签和别名，如 "zh-Hant-TW"、"english"），其他语言返回 None
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        match language_registry::global().canonical_code(locale)? {
+            "zh" => Some(Self::Chinese),
+            "en" => Some(Self::English),
+            _ => None,
+        }
+    }
+}
+
+/// TTS 文本规范化器
+#[derive(Debug, Clone, Copy)]
+pub struct TextNormalizer {
+    language: NormalizationLanguage,
+}
+
+/// 按 locale 规范化文本；不支持的语言原样返回
+pub fn normalize_for_tts(text: &str, locale: &str) -> String {
+    match TextNormalizer::for_locale(locale) {
+        Some(normalizer) => normalizer.normalize(text),
+        None => text.to_string(),
+    }
+}
+
+/// 扫描到的数字（不含符号）
+struct ScannedNumber {
+    /// 整数部分（已去掉千分位逗号）
+    integer: String,
+    fraction: Option<String>,
+    has_commas: bool,
+    /// 消耗的字符数
+    len: usize,
+}
+
+impl ScannedNumber {
+    fn is_one(&self) -> bool {
+        self.integer == "1" && self.fraction.is_none()
+    }
+
+    fn is_two(&self) -> bool {
+        self.integer == "2" && self.fraction.is_none()
+    }
+}
+
+impl TextNormalizer {
+    pub fn new(language: NormalizationLanguage) -> Self {
+        Self { language }
+    }
+
+    pub fn for_locale(locale: &str) -> Option<Self> {
+        NormalizationLanguage::from_locale(locale).map(Self::new)
+    }
+
+    pub fn language(&self) -> NormalizationLanguage {
+        self.language
+    }
+
+    fn zh(&self) -> bool {
+        self.language == NormalizationLanguage::Chinese
+    }
+
+    /// 规范化文本：从左到右扫描，数字、符号和缩写替换为读法，其余字符原样保留
+    pub fn normalize(&self, text: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let mut out = String::with_capacity(text.len());
+        let mut i = 0;
+        while i < chars.len() {
+            match self.match_at(&chars, i) {
+                Some((len, words)) => {
+                    self.emit(&mut out, &words, chars.get(i + len).copied());
+                    i += len;
+                }
+                None => {
+                    out.push(chars[i]);
+                    i += 1;
+                }
+            }
+        }
+        out
+    }
+
+    /// 写入展开的文字（英文与前后的字母数字之间补空格）
+    fn emit(&self, out: &mut String, words: &str, next: Option<char>) {
+        if !self.zh() && out.chars().last().is_some_and(|ch| ch.is_alphanumeric()) {
+            out.push(' ');
+        }
+        out.push_str(words);
+        if !self.zh() && next.is_some_and(|ch| ch.is_alphanumeric()) {
+            out.push(' ');
+        }
+    }
+
+    /// 在位置 `i` 尝试各条规则，返回（消耗的字符数，读法）
+    fn match_at(&self, chars: &[char], i: usize) -> Option<(usize, String)> {
+        let ch = chars[i];
+        let prev = i.checked_sub(1).map(|p| chars[p]);
+        let next_is_digit = chars.get(i + 1).is_some_and(|c| c.is_ascii_digit());
+        match ch {
+            '0'..='9' => self
+                .match_date(chars, i)
+                .or_else(|| self.match_time(chars, i))
+                .or_else(|| self.match_phone(chars, i))
+                .or_else(|| self.match_dotted(chars, i))
+                .or_else(|| self.match_number(chars, i, false)),
+            '+' | '(' if next_is_digit => self.match_phone(chars, i),
+            '-' if next_is_digit && !prev.is_some_and(|c| c.is_ascii_alphanumeric()) => {
+                self.match_number(chars, i + 1, true).map(|(len, words)| (len + 1, words))
+            }
+            '&' => Some((1, if self.zh() { "和" } else { "and" }.to_string())),
+            _ if CURRENCIES.iter().any(|&(symbol, ..)| symbol == ch) && next_is_digit => self.match_currency(chars, i),
+            _ if ch.is_ascii_alphabetic() && !prev.is_some_and(|c| c.is_ascii_alphabetic()) => self.match_abbreviation(chars, i),
+            _ => None,
+        }
+    }
+
+    /// 日期："2025-03-05" / "2025/3/5"，英文另支持 "3/21/2025"
+    fn match_date(&self, chars: &[char], i: usize) -> Option<(usize, String)> {
+        let (fields, len) = scan_digit_groups(chars, i, &['-', '/'], 3)?;
+        if chars.get(i + len).is_some_and(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        let separators: Vec<char> = chars[i..i + len].iter().copied().filter(|c| !c.is_ascii_digit()).collect();
+        if separators[0] != separators[1] {
+            return None;
+        }
+        let lengths: Vec<usize> = fields.iter().map(String::len).collect();
+        let format = match lengths.as_slice() {
+            [4, 1..=2, 1..=2] => "ymd",
+            [1..=2, 1..=2, 4] if !self.zh() && separators[0] == '/' => "mdy",
+            _ => return None,
+        };
+        read_date(&fields.join("-"), format, self.zh()).map(|words| (len, words))
+    }
+
+    /// 时间："10:30" / "10:30:15"，英文可带 am / pm
+    fn match_time(&self, chars: &[char], i: usize) -> Option<(usize, String)> {
+        let (fields, mut len) = scan_digit_groups(chars, i, &[':'], 3)
+            .or_else(|| scan_digit_groups(chars, i, &[':'], 2))?;
+        if fields[0].len() > 2 || fields[1..].iter().any(|field| field.len() != 2) {
+            return None;
+        }
+        let values: Vec<u64> = fields.iter().filter_map(|field| field.parse().ok()).collect();
+        let (hour, minute, second) = (values[0], values[1], values.get(2).copied().unwrap_or(0));
+        if hour > 24 || minute > 59 || second > 59 {
+            return None;
+        }
+        // 英文允许紧跟 am / pm（"10:30am"），其他字母说明不是时间
+        let meridiem = if self.zh() { None } else { match_meridiem(chars, i + len) };
+        if meridiem.is_none() && chars.get(i + len).is_some_and(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+
+        if self.zh() {
+            let mut words = if hour == 2 { "两".to_string() } else { zh_cardinal(hour) };
+            words.push('点');
+            if minute == 0 && second == 0 {
+                words.push('整');
+            } else {
+                if (1..10).contains(&minute) {
+                    words.push('零');
+                }
+                words.push_str(&zh_cardinal(minute));
+                words.push('分');
+                if second > 0 {
+                    words.push_str(&zh_cardinal(second));
+                    words.push('秒');
+                }
+            }
+            return Some((len, words));
+        }
+
+        let mut words = en_cardinal(hour);
+        match minute {
+            0 if meridiem.is_none() => words.push_str(" o'clock"),
+            0 => {}
+            1..=9 => {
+                words.push_str(" oh ");
+                words.push_str(EN_ONES[minute as usize]);
+            }
+            _ => {
+                words.push(' ');
+                words.push_str(&en_cardinal(minute));
+            }
+        }
+        if second > 0 {
+            words.push_str(&format!(" and {} second{}", en_cardinal(second), if second == 1 { "" } else { "s" }));
+        }
+        if let Some((meridiem_len, meridiem)) = meridiem {
+            words.push(' ');
+            words.push_str(meridiem);
+            len += meridiem_len;
+        }
+        Some((len, words))
+    }
+
+    /// 电话号码：以 "+" / "(" 开头、"-" 分隔的号码（如 "138-1234-5678"、"555-0100"），中文另支持 11 位手机号
+    fn match_phone(&self, chars: &[char], i: usize) -> Option<(usize, String)> {
+        let international = matches!(chars[i], '+' | '(');
+        let mut j = i;
+        let mut groups: Vec<usize> = Vec::new();
+        let mut current = 0;
+        let mut separators = 0;
+        if chars[j] == '+' {
+            j += 1;
+        }
+        while j < chars.len() {
+            let ch = chars[j];
+            let next_is_digit = chars.get(j + 1).is_some_and(|c| c.is_ascii_digit());
+            if ch.is_ascii_digit() {
+                current += 1;
+                j += 1;
+                continue;
+            }
+            if current > 0 {
+                groups.push(current);
+                current = 0;
+            }
+            match ch {
+                '-' if next_is_digit => separators += 1,
+                ' ' if international && next_is_digit && !groups.is_empty() => separators += 1,
+                '(' if j == i => {}
+                ')' if international && groups.len() == 1 => {
+                    if chars.get(j + 1) == Some(&' ') && chars.get(j + 2).is_some_and(|c| c.is_ascii_digit()) {
+                        j += 1;
+                    }
+                    separators += 1;
+                }
+                _ => break,
+            }
+            j += 1;
+        }
+        if current > 0 {
+            groups.push(current);
+        }
+        if chars.get(j).is_some_and(|c| c.is_ascii_alphanumeric()) || (chars[j - 1] != ')' && !chars[j - 1].is_ascii_digit()) {
+            return None;
+        }
+        let digits: usize = groups.iter().sum();
+        let first_is_zero = chars[i] == '0';
+        let is_phone = if international {
+            digits >= 7 && separators > 0
+        } else {
+            match groups.as_slice() {
+                [11] => self.zh() && chars[i] == '1',
+                [_, _, _, ..] => digits >= 7,
+                [first, second] => (*first == 3 && *second == 4) || (first_is_zero && first + second >= 9),
+                _ => false,
+            }
+        };
+        if !is_phone {
+            return None;
+        }
+        let text: String = chars[i..j].iter().collect();
+        Some((j - i, read_telephone(&text, self.zh())))
+    }
+
+    /// 点分数字（版本号、IP 地址）："2.0.1" -> "二点零点一" / "two point zero point one"
+    fn match_dotted(&self, chars: &[char], i: usize) -> Option<(usize, String)> {
+        let mut groups = Vec::new();
+        let mut j = i;
+        loop {
+            let start = j;
+            while chars.get(j).is_some_and(|c| c.is_ascii_digit()) {
+                j += 1;
+            }
+            groups.push(ScannedNumber {
+                integer: chars[start..j].iter().collect(),
+                fraction: None,
+                has_commas: false,
+                len: j - start,
+            });
+            if chars.get(j) == Some(&'.') && chars.get(j + 1).is_some_and(|c| c.is_ascii_digit()) {
+                j += 1;
+            } else {
+                break;
+            }
+        }
+        if groups.len() < 3 {
+            return None;
+        }
+        let words: Vec<String> = groups.iter().map(|group| self.number_words(group, false)).collect();
+        Some((j - i, words.join(if self.zh() { "点" } else { " point " })))
+    }
+
+    /// 货币："$3.5M" -> "三点五百万美元" / "three point five million dollars"，"$3.50" -> "three dollars and fifty cents"
+    fn match_currency(&self, chars: &[char], i: usize) -> Option<(usize, String)> {
+        let &(_, zh_name, singular, plural, subunit) = CURRENCIES.iter().find(|&&(symbol, ..)| symbol == chars[i])?;
+        let number = scan_number(chars, i + 1)?;
+        let mut len = 1 + number.len;
+        let scale = SCALES.iter().find(|&&(suffix, ..)| {
+            starts_with_at(chars, len + i, suffix) && !chars.get(i + len + suffix.chars().count()).is_some_and(|c| c.is_ascii_alphabetic())
+        });
+        if let Some((suffix, ..)) = scale {
+            len += suffix.chars().count();
+        }
+
+        let words = if self.zh() {
+            match (scale, number.fraction.as_deref()) {
+                (None, Some(fraction)) if zh_name == "元" && fraction.len() <= 2 => {
+                    zh_yuan(&number.integer, fraction)
+                }
+                _ => format!(
+                    "{}{}{}",
+                    if scale.is_some() && number.is_two() { "两".to_string() } else { self.number_words(&number, false) },
+                    scale.map(|&(_, zh, _)| zh).unwrap_or(""),
+                    zh_name
+                ),
+            }
+        } else {
+            match (scale, number.fraction.as_deref(), subunit) {
+                (Some(&(_, _, en)), ..) => format!("{} {} {}", self.number_words(&number, false), en, plural),
+                (None, Some(fraction), Some((sub_singular, sub_plural))) if fraction.len() <= 2 => {
+                    let cents: u64 = format!("{:0<2}", fraction).parse().ok()?;
+                    let integer = ScannedNumber { fraction: None, ..number };
+                    let mut words =
+                        format!("{} {}", self.number_words(&integer, false), if integer.is_one() { singular } else { plural });
+                    if cents > 0 {
+                        words.push_str(&format!(" and {} {}", en_cardinal(cents), if cents == 1 { sub_singular } else { sub_plural }));
+                    }
+                    words
+                }
+                _ => format!("{} {}", self.number_words(&number, false), if number.is_one() { singular } else { plural }),
+            }
+        };
+        Some((len, words))
+    }
+
+    /// 数字及其后缀：百分号、英文序数、单位、范围、中文年份，否则读作基数
+    fn match_number(&self, chars: &[char], i: usize, negative: bool) -> Option<(usize, String)> {
+        let number = scan_number(chars, i)?;
+        let mut j = i + number.len;
+        let zh = self.zh();
+
+        // 百分数
+        if matches!(chars.get(j), Some('%' | '％')) {
+            let value = self.number_words(&number, negative);
+            let words = if zh { format!("百分之{}", value) } else { format!("{} percent", value) };
+            return Some((number.len + 1, words));
+        }
+
+        // 英文序数："1st" / "22nd" / "3rd" / "11th"
+        if !negative && number.fraction.is_none() && !number.has_commas {
+            for suffix in ["st", "nd", "rd", "th"] {
+                if starts_with_at_ignore_case(chars, j, suffix) && !chars.get(j + 2).is_some_and(|c| c.is_ascii_alphanumeric()) {
+                    let n: u64 = number.integer.parse().ok()?;
+                    let words = if zh { format!("第{}", zh_cardinal(n)) } else { en_ordinal(n) };
+                    return Some((number.len + 2, words));
+                }
+            }
+        }
+
+        // 中文年份："2025年" 逐位读
+        if zh && !negative && chars.get(j) == Some(&'年') && number.integer.len() == 4 && number.fraction.is_none() && !number.has_commas {
+            return Some((number.len, read_digits(&number.integer, true)));
+        }
+
+        // 范围："3-5" / "3~5"（右侧可带单位、百分号），"2020-2025年" 两侧都按年份读
+        if matches!(chars.get(j), Some('-' | '~' | '～')) && chars.get(j + 1).is_some_and(|c| c.is_ascii_digit()) {
+            if let Some((right_len, right)) = self.match_number(chars, j + 1, false) {
+                let left = if zh && chars.get(j + 1 + right_len) == Some(&'年') && number.integer.len() == 4 {
+                    read_digits(&number.integer, true)
+                } else {
+                    self.plain_words(&number, negative)
+                };
+                let words = if zh { format!("{}到{}", left, right) } else { format!("{} to {}", left, right) };
+                return Some((number.len + 1 + right_len, words));
+            }
+        }
+
+        // 年代："1990s" / "1990's" / "90s"（不是 90 秒）
+        if !negative && number.fraction.is_none() && !number.has_commas && !number.integer.starts_with('0')
+            && matches!(number.integer.len(), 2 | 4) && number.integer.ends_with('0')
+        {
+            let suffix_len = ["s", "'s", "’s"].iter().find(|suffix| starts_with_at(chars, j, suffix)).map(|suffix| suffix.chars().count());
+            if let Some(suffix_len) = suffix_len.filter(|&len| !chars.get(j + len).is_some_and(|c| c.is_ascii_alphanumeric())) {
+                return Some((number.len + suffix_len, self.decade_words(&number.integer)));
+            }
+        }
+
+        // 单位（数字与单位之间允许一个空格，单字母单位必须有空格）
+        let spaced = chars.get(j) == Some(&' ');
+        if spaced {
+            j += 1;
+        }
+        let unit = UNITS
+            .iter()
+            .filter(|&&(symbol, ..)| spaced || symbol.chars().count() > 1 || !symbol.chars().all(|c| c.is_ascii_alphabetic()))
+            .filter(|&&(symbol, ..)| {
+                starts_with_at(chars, j, symbol) && !chars.get(j + symbol.chars().count()).is_some_and(|c| c.is_ascii_alphanumeric())
+            })
+            .max_by_key(|&&(symbol, ..)| symbol.chars().count());
+        if let Some(&(symbol, zh_name, singular, plural)) = unit {
+            if !(zh && zh_name.is_empty()) {
+                let len = j - i + symbol.chars().count();
+                let words = if zh {
+                    if negative && zh_name.ends_with("氏度") {
+                        format!("零下{}{}", self.number_words(&number, false), zh_name)
+                    } else {
+                        format!("{}{}", self.number_words(&number, negative), zh_name)
+                    }
+                } else {
+                    format!("{} {}", self.number_words(&number, negative), if number.is_one() { singular } else { plural })
+                };
+                return Some((len, words));
+            }
+        }
+
+        // 中文量级前的 2 读作"两"："2万" -> "两万"
+        if zh && !negative && number.is_two() && matches!(chars.get(j), Some('千' | '万' | '亿')) {
+            return Some((number.len, "两".to_string()));
+        }
+
+        Some((number.len, self.plain_words(&number, negative)))
+    }
+
+    /// 年代读法："1990" -> "nineteen nineties" / "一九九零年代"，"90" -> "nineties" / "九十年代"
+    fn decade_words(&self, integer: &str) -> String {
+        let n: u64 = integer.parse().unwrap_or_default();
+        if self.zh() {
+            let value = if integer.len() == 4 { read_digits(integer, true) } else { zh_cardinal(n) };
+            return format!("{}年代", value);
+        }
+        let words = if integer.len() == 4 { en_year(n) } else { en_cardinal(n) };
+        match words.strip_suffix('y') {
+            Some(stem) => format!("{}ies", stem),
+            None => format!("{}s", words),
+        }
+    }
+
+    /// 不带后缀的数字：英文中 1900..2100 的四位整数按年份读（"in 2025" -> "in twenty twenty-five"）
+    fn plain_words(&self, number: &ScannedNumber, negative: bool) -> String {
+        match number.integer.parse::<u64>() {
+            Ok(year) if !self.zh() && !negative && (1900..2100).contains(&year) && number.fraction.is_none() && !number.has_commas => {
+                en_year(year)
+            }
+            _ => self.number_words(number, negative),
+        }
+    }
+
+    /// 数值读法："1,234.5" -> "一千二百三十四点五"，以 0 开头或过长的整数逐位读
+    fn number_words(&self, number: &ScannedNumber, negative: bool) -> String {
+        let zh = self.zh();
+        let integer = &number.integer;
+        let mut words = match integer.parse::<u64>() {
+            Ok(n) if integer.len() <= MAX_CARDINAL_DIGITS && !(integer.len() > 1 && integer.starts_with('0')) => {
+                if zh {
+                    zh_cardinal(n)
+                } else {
+                    en_cardinal(n)
+                }
+            }
+            _ => read_digits(integer, zh),
+        };
+        if let Some(fraction) = &number.fraction {
+            words.push_str(if zh { "点" } else { " point " });
+            words.push_str(&read_digits(fraction, zh));
+        }
+        if negative {
+            words.insert_str(0, if zh { "负" } else { "minus " });
+        }
+        words
+    }
+
+    /// 缩写（区分大小写，前后为词边界）
+    fn match_abbreviation(&self, chars: &[char], i: usize) -> Option<(usize, String)> {
+        let table = if self.zh() { ZH_ABBREVIATIONS } else { EN_ABBREVIATIONS };
+        let &(abbreviation, expansion) = table.iter().find(|&&(abbreviation, _)| {
+            starts_with_at(chars, i, abbreviation) && !chars.get(i + abbreviation.len()).is_some_and(|c| c.is_ascii_alphanumeric())
+        })?;
+        let len = abbreviation.len();
+        // 缩写位于文本末尾时保留句号，避免丢失句末停顿
+        let words = if i + len == chars.len() { format!("{}.", expansion) } else { expansion.to_string() };
+        Some((len, words))
+    }
+}
+
+/// `chars[i..]` 是否以 `pattern` 开头
+fn starts_with_at(chars: &[char], i: usize, pattern: &str) -> bool {
+    pattern.chars().enumerate().all(|(offset, expected)| chars.get(i + offset) == Some(&expected))
+}
+
+fn starts_with_at_ignore_case(chars: &[char], i: usize, pattern: &str) -> bool {
+    pattern.chars().enumerate().all(|(offset, expected)| chars.get(i + offset).is_some_and(|c| c.eq_ignore_ascii_case(&expected)))
+}
+
+/// 扫描数字：整数部分（允许每组 3 位的千分位逗号）和可选的小数部分
+fn scan_number(chars: &[char], i: usize) -> Option<ScannedNumber> {
+    let mut j = i;
+    let mut integer = String::new();
+    while let Some(&ch) = chars.get(j).filter(|c| c.is_ascii_digit()) {
+        integer.push(ch);
+        j += 1;
+    }
+    if integer.is_empty() {
+        return None;
+    }
+
+    let mut has_commas = false;
+    if integer.len() <= 3 {
+        while chars.get(j) == Some(&',')
+            && (1..=3).all(|offset| chars.get(j + offset).is_some_and(|c| c.is_ascii_digit()))
+            && !chars.get(j + 4).is_some_and(|c| c.is_ascii_digit())
+        {
+            integer.extend(&chars[j + 1..j + 4]);
+            has_commas = true;
+            j += 4;
+        }
+    }
+
+    let mut fraction = None;
+    if chars.get(j) == Some(&'.') && chars.get(j + 1).is_some_and(|c| c.is_ascii_digit()) {
+        let mut digits = String::new();
+        j += 1;
+        while let Some(&ch) = chars.get(j).filter(|c| c.is_ascii_digit()) {
+            digits.push(ch);
+            j += 1;
+        }
+        fraction = Some(digits);
+    }
+
+    Some(ScannedNumber { integer, fraction, has_commas, len: j - i })
+}
+
+/// 扫描恰好 `count` 组以 `separators` 分隔的数字（其后不能紧跟数字或同类分隔符）
+fn scan_digit_groups(chars: &[char], i: usize, separators: &[char], count: usize) -> Option<(Vec<String>, usize)> {
+    let mut fields = Vec::with_capacity(count);
+    let mut j = i;
+    for idx in 0..count {
+        if idx > 0 {
+            if !chars.get(j).is_some_and(|c| separators.contains(c)) {
+                return None;
+            }
+            j += 1;
+        }
+        let mut field = String::new();
+        while let Some(&ch) = chars.get(j).filter(|c| c.is_ascii_digit()) {
+            field.push(ch);
+            j += 1;
+        }
+        if field.is_empty() {
+            return None;
+        }
+        fields.push(field);
+    }
+    let followed_by_group = chars.get(j).is_some_and(|c| separators.contains(c)) && chars.get(j + 1).is_some_and(|c| c.is_ascii_digit());
+    if followed_by_group || chars.get(j).is_some_and(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some((fields, j - i))
+}
+
+/// 时间后的 am / pm（允许一个空格）："am" / "PM" / "a.m."
+fn match_meridiem(chars: &[char], i: usize) -> Option<(usize, &'static str)> {
+    let start = if chars.get(i) == Some(&' ') { i + 1 } else { i };
+    for (pattern, words) in [("a.m.", "a m"), ("p.m.", "p m"), ("am", "a m"), ("pm", "p m")] {
+        let end = start + pattern.len();
+        if starts_with_at_ignore_case(chars, start, pattern) && !chars.get(end).is_some_and(|c| c.is_ascii_alphanumeric()) {
+            return Some((end - i, words));
+        }
+    }
+    None
+}
+
+/// 人民币金额："12" + "50" -> "十二元五角"
+fn zh_yuan(integer: &str, fraction: &str) -> String {
+    let mut words = format!("{}元", integer.parse().map(zh_cardinal).unwrap_or_else(|_| read_digits(integer, true)));
+    let mut subunits = fraction.chars().filter_map(|ch| ch.to_digit(10));
+    let (jiao, fen) = (subunits.next().unwrap_or(0), subunits.next().unwrap_or(0));
+    if jiao > 0 {
+        words.push_str(ZH_DIGITS[jiao as usize]);
+        words.push('角');
+    }
+    if fen > 0 {
+        if jiao == 0 {
+            words.push('零');
+        }
+        words.push_str(ZH_DIGITS[fen as usize]);
+        words.push('分');
+    }
+    words
+}
+
+/// 逐位读数字串："2025" -> "二零二五" / "two zero two five"
+fn read_digits(digits: &str, zh: bool) -> String {
+    digits
+        .chars()
+        .filter_map(|ch| ch.to_digit(10))
+        .map(|digit| digit_name(digit, zh))
+        .collect::<Vec<_>>()
+        .join(if zh { "" } else { " " })
+}
+
+pub(crate) fn digit_name(digit: u32, zh: bool) -> &'static str {
+    if zh {
+        ZH_DIGITS[digit as usize]
+    } else {
+        EN_ONES[digit as usize]
+    }
+}
+
+/// 基数词（允许千分位逗号、负号和小数）："1,234" -> "一千二百三十四" / "one thousand two hundred thirty-four"
+pub(crate) fn read_number(text: &str, zh: bool) -> Option<String> {
+    let text: String = text.chars().filter(|&ch| ch != ',').collect();
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.as_str()),
+    };
+    let (integer, fraction) = match text.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (text, None),
+    };
+    if integer.is_empty() || !integer.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let mut words = if zh { zh_cardinal(integer.parse().ok()?) } else { en_cardinal(integer.parse().ok()?) };
+    if let Some(fraction) = fraction {
+        if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        words.push_str(if zh { "点" } else { " point " });
+        words.push_str(&read_digits(fraction, zh));
+    }
+    if negative {
+        words.insert_str(0, if zh { "负" } else { "minus " });
+    }
+    Some(words)
+}
+
+/// 日期（`format` 为 y/m/d 的排列，如 "ymd"、"mdy"、"md"）："2025-03-05" -> "二零二五年三月五日" / "March fifth, twenty twenty-five"
+pub(crate) fn read_date(text: &str, format: &str, zh: bool) -> Option<String> {
+    let fields: Vec<u64> = text
+        .split(|ch: char| !ch.is_ascii_digit())
+        .filter(|field| !field.is_empty())
+        .map(|field| field.parse().ok())
+        .collect::<Option<_>>()?;
+    if fields.len() != format.len() {
+        return None;
+    }
+    let (mut year, mut month, mut day) = (None, None, None);
+    for (field, value) in format.chars().zip(fields) {
+        match field {
+            'y' => year = Some(value),
+            'm' if (1..=12).contains(&value) => month = Some(value),
+            'd' if (1..=31).contains(&value) => day = Some(value),
+            _ => return None,
+        }
+    }
+
+    if zh {
+        let mut words = String::new();
+        if let Some(year) = year {
+            words.push_str(&read_digits(&year.to_string(), true));
+            words.push('年');
+        }
+        if let Some(month) = month {
+            words.push_str(&zh_cardinal(month));
+            words.push('月');
+        }
+        if let Some(day) = day {
+            words.push_str(&zh_cardinal(day));
+            words.push('日');
+        }
+        Some(words)
+    } else {
+        let mut words = Vec::new();
+        if let Some(month) = month {
+            words.push(EN_MONTHS[month as usize - 1].to_string());
+        }
+        if let Some(day) = day {
+            words.push(en_ordinal(day));
+        }
+        let mut words = words.join(" ");
+        if let Some(year) = year {
+            if !words.is_empty() {
+                words.push_str(", ");
+            }
+            words.push_str(&en_year(year));
+        }
+        Some(words)
+    }
+}
+
+/// 电话号码：逐位读（中文 1 读作"幺"），分隔符处停顿
+pub(crate) fn read_telephone(text: &str, zh: bool) -> String {
+    let mut groups: Vec<Vec<&str>> = vec![Vec::new()];
+    for ch in text.chars() {
+        match ch {
+            '0'..='9' => {
+                let digit = ch.to_digit(10).unwrap_or_default();
+                groups.last_mut().unwrap().push(if zh && digit == 1 { "幺" } else { digit_name(digit, zh) });
+            }
+            '+' => groups.last_mut().unwrap().push(if zh { "加" } else { "plus" }),
+            _ => {
+                if !groups.last().unwrap().is_empty() {
+                    groups.push(Vec::new());
+                }
+            }
+        }
+    }
+    groups
+        .into_iter()
+        .filter(|group| !group.is_empty())
+        .map(|group| group.join(if zh { "" } else { " " }))
+        .collect::<Vec<_>>()
+        .join(if zh { "，" } else { ", " })
+}
+
+/// 中文基数词：10 -> "十"，10001 -> "一万零一"，20000 -> "两万"
+fn zh_cardinal(n: u64) -> String {
+    if n == 0 {
+        return ZH_DIGITS[0].to_string();
+    }
+    const SECTION_UNITS: [&str; 5] = ["", "万", "亿", "万亿", "亿亿"];
+    let mut sections = Vec::new();
+    let mut rest = n;
+    while rest > 0 {
+        sections.push((rest % 10_000) as u32);
+        rest /= 10_000;
+    }
+
+    let mut words = String::new();
+    let mut pending_zero = false;
+    for (idx, &section) in sections.iter().enumerate().rev() {
+        if section == 0 {
+            pending_zero = !words.is_empty();
+            continue;
+        }
+        if !words.is_empty() && (pending_zero || section < 1000) {
+            words.push('零');
+        }
+        if section == 2 && idx > 0 {
+            words.push('两');
+        } else {
+            words.push_str(&zh_section(section));
+        }
+        words.push_str(SECTION_UNITS[idx]);
+        pending_zero = false;
+    }
+    match words.strip_prefix("一十") {
+        Some(rest) => format!("十{}", rest),
+        None => words,
+    }
+}
+
+/// 中文四位以内的数（1..=9999），千位的 2 读作"两"
+fn zh_section(section: u32) -> String {
+    const UNITS: [&str; 4] = ["千", "百", "十", ""];
+    let digits = [section / 1000, section / 100 % 10, section / 10 % 10, section % 10];
+    let mut words = String::new();
+    let mut pending_zero = false;
+    for (digit, unit) in digits.into_iter().zip(UNITS) {
+        if digit == 0 {
+            pending_zero = !words.is_empty();
+            continue;
+        }
+        if pending_zero {
+            words.push('零');
+            pending_zero = false;
+        }
+        words.push_str(if digit == 2 && unit == "千" { "两" } else { ZH_DIGITS[digit as usize] });
+        words.push_str(unit);
+    }
+    words
+}
+
+/// 英文基数词：1234 -> "one thousand two hundred thirty-four"
+fn en_cardinal(n: u64) -> String {
+    const SCALES: [(u64, &str); 6] = [
+        (1_000_000_000_000_000_000, "quintillion"),
+        (1_000_000_000_000_000, "quadrillion"),
+        (1_000_000_000_000, "trillion"),
+        (1_000_000_000, "billion"),
+        (1_000_000, "million"),
+        (1_000, "thousand"),
+    ];
+    if n == 0 {
+        return EN_ONES[0].to_string();
+    }
+    let mut words = Vec::new();
+    let mut rest = n;
+    for (scale, name) in SCALES {
+        if rest >= scale {
+            words.push(format!("{} {}", en_below_thousand(rest / scale), name));
+            rest %= scale;
+        }
+    }
+    if rest > 0 {
+        words.push(en_below_thousand(rest));
+    }
+    words.join(" ")
+}
+
+/// 英文 1..=999
+fn en_below_thousand(n: u64) -> String {
+    let mut words = Vec::new();
+    if n >= 100 {
+        words.push(format!("{} hundred", EN_ONES[(n / 100) as usize]));
+    }
+    let rest = n % 100;
+    if rest >= 20 {
+        let tens = EN_TENS[(rest / 10) as usize];
+        words.push(if rest.is_multiple_of(10) { tens.to_string() } else { format!("{}-{}", tens, EN_ONES[(rest % 10) as usize]) });
+    } else if rest > 0 {
+        words.push(EN_ONES[rest as usize].to_string());
+    }
+    words.join(" ")
+}
+
+/// 英文序数词：5 -> "fifth"，21 -> "twenty-first"
+fn en_ordinal(n: u64) -> String {
+    let cardinal = en_cardinal(n);
+    let split = cardinal.rfind([' ', '-']).map(|idx| idx + 1).unwrap_or(0);
+    let (head, last) = cardinal.split_at(split);
+    let last = match last {
+        "one" => "first".to_string(),
+        "two" => "second".to_string(),
+        "three" => "third".to_string(),
+        "five" => "fifth".to_string(),
+        "eight" => "eighth".to_string(),
+        "nine" => "ninth".to_string(),
+        "twelve" => "twelfth".to_string(),
+        word if word.ends_with('y') => format!("{}ieth", &word[..word.len() - 1]),
+        word => format!("{}th", word),
+    };
+    format!("{}{}", head, last)
+}
+
+/// 英文年份读法：1999 -> "nineteen ninety-nine"，2005 -> "two thousand five"，2025 -> "twenty twenty-five"
+fn en_year(year: u64) -> String {
+    if !(1100..10_000).contains(&year) || (2000..2010).contains(&year) || year.is_multiple_of(1000) {
+        return en_cardinal(year);
+    }
+    let (high, low) = (year / 100, year % 100);
+    match low {
+        0 => format!("{} hundred", en_cardinal(high)),
+        1..=9 => format!("{} oh {}", en_cardinal(high), EN_ONES[low as usize]),
+        _ => format!("{} {}", en_cardinal(high), en_cardinal(low)),
+    }
+}
+
+/// 在合成前规范化请求文本的 TTS 包装
+pub struct NormalizedTts {
+    inner: Arc<dyn TtsStreaming>,
+}
+
+impl NormalizedTts {
+    pub fn new(inner: Arc<dyn TtsStreaming>) -> Self {
+        Self { inner }
+    }
+
+    /// 包装后端，返回可直接替换原后端的 `Arc<dyn TtsStreaming>`
+    pub fn wrap(inner: Arc<dyn TtsStreaming>) -> Arc<dyn TtsStreaming> {
+        Arc::new(Self::new(inner))
+    }
+
+    fn normalize_request(mut request: TtsRequest) -> TtsRequest {
+        if is_ssml(&request.text) {
+            return request;
+        }
+        let normalized = normalize_for_tts(&request.text, &request.locale);
+        if normalized != request.text {
+            eprintln!("[TTS] 🔤 Text normalized ({}): '{}' -> '{}'", request.locale, request.text, normalized);
+            request.text = normalized;
+        }
+        request
+    }
+}
+
+#[async_trait]
+impl TtsStreaming for NormalizedTts {
+    async fn synthesize(&self, request: TtsRequest) -> EngineResult<TtsStreamChunk> {
+        self.inner.synthesize(Self::normalize_request(request)).await
+    }
+
+    fn synthesize_stream(&self, request: TtsRequest) -> TtsChunkStream<'_> {
+        self.inner.synthesize_stream(Self::normalize_request(request))
+    }
+
+    fn accepts_phonemes(&self, alphabet: &str) -> bool {
+        self.inner.accepts_phonemes(alphabet)
+    }
+
+    /// 音素决定发音，内容文字不做规范化
+    async fn synthesize_phonemes(&self, request: TtsRequest, phoneme: SsmlPhoneme) -> EngineResult<TtsStreamChunk> {
+        self.inner.synthesize_phonemes(request, phoneme).await
+    }
+
+    async fn close(&self) -> EngineResult<()> {
+        self.inner.close().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_readings() {
+        assert_eq!(zh_cardinal(10), "十");
+        assert_eq!(zh_cardinal(105), "一百零五");
+        assert_eq!(zh_cardinal(2_345), "两千三百四十五");
+        assert_eq!(zh_cardinal(10_001), "一万零一");
+        assert_eq!(zh_cardinal(20_000), "两万");
+        assert_eq!(zh_cardinal(100_000_001), "一亿零一");
+        assert_eq!(zh_cardinal(10_000_100), "一千万零一百");
+        assert_eq!(en_cardinal(1_234), "one thousand two hundred thirty-four");
+        assert_eq!(en_ordinal(21), "twenty-first");
+        assert_eq!(en_ordinal(30), "thirtieth");
+        assert_eq!(en_year(1999), "nineteen ninety-nine");
+        assert_eq!(en_year(2005), "two thousand five");
+        assert_eq!(en_year(1905), "nineteen oh five");
+    }
+
+    #[test]
+    fn test_decades_and_single_letter_units() {
+        let en = TextNormalizer::new(NormalizationLanguage::English);
+        assert_eq!(en.normalize("the 2000s and 1900s"), "the two thousands and nineteen hundreds");
+        assert_eq!(en.normalize("the 1980's"), "the nineteen eighties");
+        assert_eq!(en.normalize("in his 20s"), "in his twenties");
+        assert_eq!(en.normalize("wait 30 s"), "wait thirty seconds");
+        assert_eq!(en.normalize("2 h"), "two hours");
+        let zh = TextNormalizer::new(NormalizationLanguage::Chinese);
+        assert_eq!(zh.normalize("90年代"), "九十年代");
+        assert_eq!(zh.normalize("1990s"), "一九九零年代");
+        assert_eq!(zh.normalize("4g网络"), "四g网络");
+        assert_eq!(zh.normalize("500 g"), "五百克");
+    }
+}
@@ -3,6 +3,8 @@ use std::collections::HashMap;
 use std::path::Path;
 use std::fs;
 
+use super::text_normalization::normalize_for_tts;
+
 /// 文本预处理器
 /// 负责文本规范化、音素转换和音素 ID 映射
 #[derive(Clone)]
@@ -55,9 +57,9 @@ impl TextProcessor {
         })
     }
 
-    /// 规范化文本（简化版：目前只做基本处理）
+    /// 规范化文本：数字、日期、单位、缩写等展开为文字（见 `TextNormalizer`），再清理空格和特殊字符
     pub fn normalize_text(&self, text: &str) -> String {
-        let mut result = text.trim().to_string();
+        let mut result = normalize_for_tts(text.trim(), &self.locale);
         
         if result.is_empty() {
             return result;
@@ -73,12 +75,6 @@ impl TextProcessor {
                        matches!(c, ' ' | '.' | ',' | '!' | '?' | ';' | ':' | '-' | '\'' | '"'))
             .collect();
         
-        // TODO: 实现更复杂的规范化
-        // - 数字转文字（"123" → "一百二十三" 或 "one hundred twenty three"）
-        // - 日期时间处理
-        // - 缩写展开（"Dr." → "Doctor"）
-        // - 货币、单位转换
-        
         result
     }
 
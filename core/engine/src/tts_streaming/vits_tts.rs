@@ -3,6 +3,7 @@ use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use std::sync::Arc;
 use async_trait::async_trait;
+use futures::StreamExt;
 use ort::session::Session;
 use ort::{SessionBuilder, Environment};
 use ort::tensor::OrtOwnedTensor;
@@ -12,9 +13,16 @@ use std::ptr;
 use ndarray::CowArray;
 
 use crate::error::{EngineError, EngineResult};
-use super::{TtsRequest, TtsStreamChunk, TtsStreaming};
+use crate::text_segmentation::TextSegmenter;
+use super::{TtsAudioFormat, TtsChunkStream, TtsRequest, TtsStreamChunk, TtsStreaming};
 use super::vits_zh_aishell3_tokenizer::VitsZhAishell3Tokenizer;
 
+/// MMS TTS 的采样率
+const MMS_SAMPLE_RATE: u32 = 16000;
+
+/// 流式合成时每个分句的最大长度（字符）
+const STREAM_SEGMENT_MAX_CHARS: usize = 50;
+
 /// VITS TTS 引擎（使用 MMS TTS ONNX 模型）
 /// 
 /// 支持多语言，根据 locale 选择对应的模型
@@ -275,7 +283,7 @@ impl VitsTtsEngine {
         let sample_rate = if is_zh_aishell3 {
             22050u32  // vits-zh-aishell3 通常使用 22050 Hz
         } else {
-            16000u32  // MMS TTS 使用 16000 Hz
+            MMS_SAMPLE_RATE  // MMS TTS 使用 16000 Hz
         };
 
         Ok(Self {
@@ -639,6 +647,14 @@ impl VitsTtsEngine {
         Ok(audio)
     }
 
+    /// 输出采样率（中文可能是 vits-zh-aishell3，其他语言使用 MMS TTS）
+    fn output_sample_rate(&self, locale: &str) -> u32 {
+        match locale {
+            "zh" | "zh-CN" | "zh-TW" | "cmn" => self.sample_rate,
+            _ => MMS_SAMPLE_RATE,
+        }
+    }
+
     /// 将 f32 音频波形转换为 PCM 16-bit 字节
     /// 
     /// 输入：f32 音频波形（范围通常是 [-1.0, 1.0]）
@@ -686,11 +702,54 @@ impl TtsStreaming for VitsTtsEngine {
         // 3. 创建 chunk（当前实现：一次性返回完整音频）
         Ok(TtsStreamChunk {
             audio: pcm_audio,
+            format: TtsAudioFormat::pcm16(self.output_sample_rate(&request.locale), 1),
             timestamp_ms: 0,  // 时间戳从 0 开始
             is_last: true,    // 单个 chunk，标记为最后一个
         })
     }
 
+    /// 流式合成：按分句（句号、逗号等）逐句推理，每句合成完立即返回
+    ///
+    /// VITS 的 ONNX 模型把声学模型和声码器导出为一个整体（文本 → 波形），无法单独按窗口运行声码器，
+    /// 因此流式的粒度是分句：首块延迟取决于第一个分句的长度，而不是整段文本。
+    fn synthesize_stream(&self, request: TtsRequest) -> TtsChunkStream<'_> {
+        let segments: Vec<String> = TextSegmenter::new_with_comma_splitting(STREAM_SEGMENT_MAX_CHARS)
+            .segment(&request.text)
+            .into_iter()
+            .filter(|segment| !segment.trim().is_empty())
+            .collect();
+        if segments.len() <= 1 {
+            return Box::pin(futures::stream::once(self.synthesize(request)));
+        }
+
+        let format = TtsAudioFormat::pcm16(self.output_sample_rate(&request.locale), 1);
+        let sample_rate = format.sample_rate as u64;
+        let locale = request.locale;
+        let total = segments.len();
+        Box::pin(futures::stream::iter(segments.into_iter().enumerate()).scan(
+            (0u64, false),
+            move |(emitted_samples, failed), (index, segment)| {
+                if *failed {
+                    return futures::future::ready(None);
+                }
+                let result = self.run_inference(&segment, &locale)
+                    .map_err(|e| EngineError::new(format!("VITS inference failed for segment {}/{}: {e}", index + 1, total)))
+                    .map(|waveform| {
+                        let chunk = TtsStreamChunk {
+                            audio: self.audio_to_pcm16(&waveform),
+                            format,
+                            timestamp_ms: *emitted_samples * 1000 / sample_rate,
+                            is_last: index + 1 == total,
+                        };
+                        *emitted_samples += waveform.len() as u64;
+                        chunk
+                    });
+                *failed = result.is_err();
+                futures::future::ready(Some(result))
+            },
+        ))
+    }
+
     async fn close(&self) -> EngineResult<()> {
         Ok(())
     }
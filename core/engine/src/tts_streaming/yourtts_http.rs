@@ -11,7 +11,7 @@ use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 use crate::error::{EngineError, EngineResult};
-use crate::tts_streaming::{TtsRequest, TtsStreamChunk, TtsStreaming};
+use crate::tts_streaming::{TtsAudioFormat, TtsRequest, TtsStreamChunk, TtsStreaming};
 
 /// YourTTS HTTP 服务配置
 #[derive(Debug, Clone)]
@@ -418,6 +418,7 @@ impl TtsStreaming for YourTtsHttp {
 
         Ok(TtsStreamChunk {
             audio: wav_audio,
+            format: TtsAudioFormat::wav16(result.sample_rate, 1),
             timestamp_ms: 0,  // TODO: 使用实际时间戳
             is_last: true,
         })
@@ -8,11 +8,40 @@ pub struct AudioFrame {
     pub timestamp_ms: u64,
 }
 
+impl AudioFrame {
+    /// 帧时长（毫秒，按每个声道的样本数计算）
+    pub fn duration_ms(&self) -> u64 {
+        if self.sample_rate == 0 || self.channels == 0 {
+            return 0;
+        }
+        let samples_per_channel = (self.data.len() / self.channels as usize) as u64;
+        samples_per_channel * 1000 / self.sample_rate as u64
+    }
+
+    /// 帧结束时间（毫秒）
+    pub fn end_timestamp_ms(&self) -> u64 {
+        self.timestamp_ms + self.duration_ms()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PartialTranscript {
     pub text: String,
     pub confidence: f32,
     pub is_final: bool,
+    /// 已提交的稳定前缀（流式识别时不再改变的部分，`text` 总是以它开头）；None 表示整段文本都可能改变
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stable_prefix: Option<String>,
+}
+
+impl PartialTranscript {
+    /// 易变尾部（`text` 去掉稳定前缀后的部分，之后的部分结果中可能改变）
+    pub fn volatile_tail(&self) -> &str {
+        self.stable_prefix
+            .as_deref()
+            .and_then(|prefix| self.text.strip_prefix(prefix))
+            .unwrap_or(&self.text)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,4 +49,42 @@ pub struct StableTranscript {
     pub text: String,
     pub speaker_id: Option<String>,
     pub language: String,
+    /// 分段时间信息（毫秒，相对于本次推理音频的起点）；后端不提供时为空
+    #[serde(default)]
+    pub segments: Vec<TranscriptSegment>,
+    /// 自动检测语言时的候选语言（按概率降序）；指定了语言时为空
+    #[serde(default)]
+    pub language_probabilities: Vec<LanguageProbability>,
+}
+
+/// 语言识别结果中的一个候选语言
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LanguageProbability {
+    pub language: String,
+    pub probability: f32,
+}
+
+/// 转录分段（Whisper segment）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    pub text: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// 该分段为非语音的概率
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_speech_probability: Option<f32>,
+    /// 分段 token 的平均对数概率（后端不提供逐 token 概率时使用，如 faster-whisper）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_log_probability: Option<f32>,
+    #[serde(default)]
+    pub tokens: Vec<TranscriptToken>,
+}
+
+/// 分段内的 token（不含特殊 token），用于逐词高亮和字幕对齐
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptToken {
+    pub text: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub probability: f32,
 }
@@ -0,0 +1,99 @@
+//! VAD 自适应状态的持久化
+//!
+//! 按用户 ID 把 `SpeakerAdaptiveState` 保存为 JSON 文件（每个用户一个文件），
+//! 会话开始时恢复，老用户可以直接使用上次调好的停顿阈值。
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::error::{EngineError, EngineResult};
+use crate::vad::SpeakerAdaptiveState;
+
+/// 临时文件序号（与进程 ID 一起保证同一用户的并发保存不会写同一个临时文件）
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// 按用户保存 VAD 自适应状态的目录
+#[derive(Debug, Clone)]
+pub struct AdaptiveStateStore {
+    dir: PathBuf,
+}
+
+impl AdaptiveStateStore {
+    /// 创建存储（目录不存在时自动创建）
+    pub fn new(dir: impl Into<PathBuf>) -> EngineResult<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(|e| {
+            EngineError::new(format!("Failed to create VAD state directory {}: {}", dir.display(), e))
+        })?;
+        Ok(Self { dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 用户状态文件路径
+    ///
+    /// 用户 ID 来自客户端，除字母、数字、`-`、`_` 以外的字符按字节转义为 `%XX`，避免路径穿越
+    pub fn path_for(&self, user_id: &str) -> PathBuf {
+        let mut file_name = String::with_capacity(user_id.len() + 5);
+        for byte in user_id.bytes() {
+            if byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' {
+                file_name.push(byte as char);
+            } else {
+                file_name.push_str(&format!("%{:02X}", byte));
+            }
+        }
+        file_name.push_str(".json");
+        self.dir.join(file_name)
+    }
+
+    /// 读取用户的状态（没有保存过时返回 None）
+    pub fn load(&self, user_id: &str) -> EngineResult<Option<SpeakerAdaptiveState>> {
+        let path = self.path_for(user_id);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(EngineError::new(format!("Failed to read VAD state {}: {}", path.display(), e)));
+            }
+        };
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| EngineError::new(format!("Failed to parse VAD state {}: {}", path.display(), e)))
+    }
+
+    /// 保存用户的状态（先写临时文件再重命名，避免中断时留下不完整的文件）
+    ///
+    /// 临时文件名带进程 ID 和序号：同一用户的多个会话同时结束时各写各的临时文件，
+    /// 重命名是原子的，最终文件总是其中一次完整的保存
+    pub fn save(&self, user_id: &str, state: &SpeakerAdaptiveState) -> EngineResult<()> {
+        let path = self.path_for(user_id);
+        let json = serde_json::to_string_pretty(state)
+            .map_err(|e| EngineError::new(format!("Failed to serialize VAD state: {}", e)))?;
+        let tmp_path = path.with_extension(format!(
+            "json.{}.{}.tmp",
+            std::process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&tmp_path, json)
+            .and_then(|_| std::fs::rename(&tmp_path, &path))
+            .map_err(|e| {
+                let _ = std::fs::remove_file(&tmp_path);
+                EngineError::new(format!("Failed to write VAD state {}: {}", path.display(), e))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_id_is_escaped_in_file_name() {
+        let store = AdaptiveStateStore { dir: PathBuf::from("states") };
+        assert_eq!(store.path_for("alice_01"), PathBuf::from("states/alice_01.json"));
+        assert_eq!(store.path_for("../etc/passwd"), PathBuf::from("states/%2E%2E%2Fetc%2Fpasswd.json"));
+        assert_eq!(store.path_for("张"), PathBuf::from("states/%E5%BC%A0.json"));
+    }
+}
@@ -0,0 +1,404 @@
+//! 基于能量的轻量 VAD 实现
+//!
+//! 不需要模型文件，适用于嵌入式部署和测试环境。每 20ms 分析一次：
+//! - 语音频段能量：300-3400Hz 带通滤波后的能量（dBFS）
+//! - 频段能量占比：带通能量 / 总能量，过滤低频嗡嗡声
+//! - 过零率：过滤白噪声等高过零率的噪声
+//! - 自适应噪声底：非语音帧时跟踪背景噪声（下降快、上升慢），语音判定使用相对噪声底的信噪比
+//!
+//! 边界语义与 `SileroVad` 相同：检测到语音后，连续静音达到 `min_silence_duration_ms` 时报告
+//! `BoundaryType::NaturalPause`，并发出语音开始/结束事件。
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::EngineResult;
+use crate::types::AudioFrame;
+use crate::vad::{BoundaryType, DetectionOutcome, SpeechPadding, VadInputAdapter, VoiceActivityDetector};
+
+/// 分析采样率
+const ANALYSIS_SAMPLE_RATE: u32 = 16000;
+
+/// 语音频段（Hz）
+const SPEECH_BAND_LOW_HZ: f32 = 300.0;
+const SPEECH_BAND_HIGH_HZ: f32 = 3400.0;
+
+/// 能量下限（避免对数运算溢出，dBFS）
+const ENERGY_FLOOR_DB: f32 = -100.0;
+
+/// EnergyVad 配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnergyVadConfig {
+    /// 分析帧时长（毫秒）
+    pub frame_ms: u64,
+    /// 语音判定阈值：语音频段能量高于噪声底的分贝数
+    pub speech_threshold_db: f32,
+    /// 语音频段能量的绝对下限（dBFS），低于此值一律视为静音
+    pub min_energy_db: f32,
+    /// 语音频段能量占总能量的最小比例（0.0-1.0）
+    pub min_speech_band_ratio: f32,
+    /// 最大过零率（每个样本的过零次数，0.0-1.0），高于此值视为噪声
+    pub max_zero_crossing_rate: f32,
+    /// 连续多少个语音分析帧才判定语音开始（防止瞬时噪声触发）
+    pub onset_frames: usize,
+    /// 噪声底下降速率（当前能量低于噪声底时，0.0-1.0）
+    pub noise_floor_fall_rate: f32,
+    /// 噪声底上升速率（当前能量高于噪声底时，0.0-1.0）
+    pub noise_floor_rise_rate: f32,
+    /// 最小静音时长（毫秒），检测到语音后连续静音达到此时长判定为自然停顿
+    pub min_silence_duration_ms: u64,
+    /// 最小话语时长（毫秒，累计语音时长），不足时不报告边界（继续累积到下一句）
+    pub min_utterance_ms: u64,
+    /// 语音开始/结束事件的 pre-roll/post-roll
+    pub speech_padding: SpeechPadding,
+}
+
+impl Default for EnergyVadConfig {
+    fn default() -> Self {
+        Self {
+            frame_ms: 20,
+            speech_threshold_db: 9.0,
+            min_energy_db: -50.0,
+            min_speech_band_ratio: 0.3,
+            max_zero_crossing_rate: 0.35,
+            onset_frames: 3,  // 60ms
+            noise_floor_fall_rate: 0.2,
+            noise_floor_rise_rate: 0.02,
+            min_silence_duration_ms: 500,
+            min_utterance_ms: 200,
+            speech_padding: SpeechPadding::new(300, 150),
+        }
+    }
+}
+
+/// 二阶 IIR 滤波器（RBJ Audio EQ Cookbook，Q = 0.707）
+#[derive(Debug, Clone)]
+struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    z1: f32,
+    z2: f32,
+}
+
+impl Biquad {
+    fn new(b: [f32; 3], a: [f32; 3]) -> Self {
+        Self {
+            b0: b[0] / a[0],
+            b1: b[1] / a[0],
+            b2: b[2] / a[0],
+            a1: a[1] / a[0],
+            a2: a[2] / a[0],
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    fn coefficients(sample_rate: u32, cutoff_hz: f32) -> (f32, f32) {
+        let omega = 2.0 * std::f32::consts::PI * cutoff_hz / sample_rate as f32;
+        let alpha = omega.sin() / (2.0 * std::f32::consts::FRAC_1_SQRT_2);
+        (omega.cos(), alpha)
+    }
+
+    fn highpass(sample_rate: u32, cutoff_hz: f32) -> Self {
+        let (cos, alpha) = Self::coefficients(sample_rate, cutoff_hz);
+        Self::new(
+            [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    fn lowpass(sample_rate: u32, cutoff_hz: f32) -> Self {
+        let (cos, alpha) = Self::coefficients(sample_rate, cutoff_hz);
+        Self::new(
+            [(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    /// 转置直接 II 型
+    fn process(&mut self, x: f32) -> f32 {
+        let y = self.b0 * x + self.z1;
+        self.z1 = self.b1 * x - self.a1 * y + self.z2;
+        self.z2 = self.b2 * x - self.a2 * y;
+        y
+    }
+}
+
+/// 一个分析帧的特征
+#[derive(Debug, Clone, Copy)]
+struct FrameFeatures {
+    /// 语音频段能量（dBFS）
+    band_energy_db: f32,
+    /// 语音频段能量占比
+    band_ratio: f32,
+    /// 过零率
+    zero_crossing_rate: f32,
+}
+
+fn energy_db(mean_square: f32) -> f32 {
+    (10.0 * mean_square.max(1e-10).log10()).max(ENERGY_FLOOR_DB)
+}
+
+/// 每个会话独立的检测状态
+#[derive(Debug, Clone)]
+struct EnergyVadState {
+    /// 尚未分析的样本（16kHz 单声道）
+    pending: Vec<f32>,
+    highpass: Biquad,
+    lowpass: Biquad,
+    /// 自适应噪声底（dBFS），None 表示尚未初始化
+    noise_floor_db: Option<f32>,
+    /// 连续语音分析帧数
+    speech_run: usize,
+    /// 当前是否处于语音状态
+    in_speech: bool,
+    /// 本句累计语音时长（毫秒）
+    speech_ms: u64,
+    /// 语音之后的连续静音时长（毫秒）
+    silence_ms: u64,
+    /// 是否已经发出 Start、尚未发出 End
+    segment_active: bool,
+    /// 最近一个语音帧的结束时间
+    last_speech_end_ms: Option<u64>,
+    /// 最近一个分析帧的置信度
+    confidence: f32,
+}
+
+impl EnergyVadState {
+    fn new() -> Self {
+        Self {
+            pending: Vec::new(),
+            highpass: Biquad::highpass(ANALYSIS_SAMPLE_RATE, SPEECH_BAND_LOW_HZ),
+            lowpass: Biquad::lowpass(ANALYSIS_SAMPLE_RATE, SPEECH_BAND_HIGH_HZ),
+            noise_floor_db: None,
+            speech_run: 0,
+            in_speech: false,
+            speech_ms: 0,
+            silence_ms: 0,
+            segment_active: false,
+            last_speech_end_ms: None,
+            confidence: 0.0,
+        }
+    }
+
+    fn features(&mut self, samples: &[f32]) -> FrameFeatures {
+        let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+        let mut total = 0.0f32;
+        let mut band = 0.0f32;
+        let mut crossings = 0usize;
+        let mut previous: Option<f32> = None;
+        for &sample in samples {
+            let centered = sample - mean;
+            total += centered * centered;
+            let filtered = self.lowpass.process(self.highpass.process(sample));
+            band += filtered * filtered;
+            if let Some(prev) = previous {
+                if (prev >= 0.0) != (centered >= 0.0) {
+                    crossings += 1;
+                }
+            }
+            previous = Some(centered);
+        }
+        let n = samples.len() as f32;
+        FrameFeatures {
+            band_energy_db: energy_db(band / n),
+            band_ratio: if total > 0.0 { (band / total).min(1.0) } else { 0.0 },
+            zero_crossing_rate: crossings as f32 / (n - 1.0).max(1.0),
+        }
+    }
+
+    /// 判定一个分析帧是否为语音，并更新噪声底；返回 (是否为语音, 信噪比)
+    fn classify(&mut self, features: FrameFeatures, config: &EnergyVadConfig) -> (bool, f32) {
+        let floor = *self.noise_floor_db.get_or_insert(features.band_energy_db);
+        let snr_db = features.band_energy_db - floor;
+        let is_speech = snr_db >= config.speech_threshold_db
+            && features.band_energy_db >= config.min_energy_db
+            && features.band_ratio >= config.min_speech_band_ratio
+            && features.zero_crossing_rate <= config.max_zero_crossing_rate;
+
+        // 噪声底：非语音帧时跟踪能量（下降快、上升慢）；语音帧时以更慢的速度上升，
+        // 避免背景噪声持续变大后一直被判定为语音
+        let rate = if features.band_energy_db < floor {
+            config.noise_floor_fall_rate
+        } else if is_speech {
+            config.noise_floor_rise_rate * 0.1
+        } else {
+            config.noise_floor_rise_rate
+        };
+        self.noise_floor_db = Some(floor + rate.clamp(0.0, 1.0) * (features.band_energy_db - floor));
+        (is_speech, snr_db)
+    }
+}
+
+/// 基于能量、过零率和自适应噪声底的 VAD（不需要模型文件）
+pub struct EnergyVad {
+    config: EnergyVadConfig,
+    state: Mutex<EnergyVadState>,
+    /// 输入格式适配（混成单声道并重采样到 16kHz）
+    input_adapter: VadInputAdapter,
+}
+
+impl EnergyVad {
+    pub fn new(config: EnergyVadConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(EnergyVadState::new()),
+            input_adapter: VadInputAdapter::new(ANALYSIS_SAMPLE_RATE),
+        }
+    }
+
+    pub fn config(&self) -> &EnergyVadConfig {
+        &self.config
+    }
+
+    /// 当前的噪声底估计（dBFS）
+    pub fn noise_floor_db(&self) -> Option<f32> {
+        self.state.lock().unwrap().noise_floor_db
+    }
+
+    fn analysis_frame_len(&self) -> usize {
+        (ANALYSIS_SAMPLE_RATE as u64 * self.config.frame_ms.max(1) / 1000) as usize
+    }
+}
+
+impl Default for EnergyVad {
+    fn default() -> Self {
+        Self::new(EnergyVadConfig::default())
+    }
+}
+
+#[async_trait]
+impl VoiceActivityDetector for EnergyVad {
+    async fn detect(&self, frame: AudioFrame) -> EngineResult<DetectionOutcome> {
+        // 清理 FINAL_FRAME_FLAG（与 SileroVad 一致）
+        const FINAL_FRAME_FLAG: u64 = 1u64 << 63;
+        let mut frame = frame;
+        frame.timestamp_ms &= !FINAL_FRAME_FLAG;
+
+        let converted = self.input_adapter.convert(&frame)?;
+        let frame_len = self.analysis_frame_len();
+        let config = &self.config;
+
+        let mut state = self.state.lock().unwrap();
+        state.pending.extend_from_slice(&converted.data);
+
+        let mut is_boundary = false;
+        let mut speech_event = None;
+        let mut offset = 0;
+        // 逐个分析帧处理；产生事件后停止，剩余样本留到下一次调用（每次调用最多一个事件）
+        while state.pending.len() - offset >= frame_len && speech_event.is_none() {
+            let samples = state.pending[offset..offset + frame_len].to_vec();
+            offset += frame_len;
+
+            let features = state.features(&samples);
+            let (is_speech, snr_db) = state.classify(features, config);
+            state.confidence = (snr_db / (2.0 * config.speech_threshold_db.max(1.0))).clamp(0.0, 1.0);
+
+            state.speech_run = if is_speech { state.speech_run + 1 } else { 0 };
+            if !state.in_speech && state.speech_run >= config.onset_frames.max(1) {
+                state.in_speech = true;
+                state.silence_ms = 0;
+                if !state.segment_active {
+                    state.segment_active = true;
+                    speech_event = Some(config.speech_padding.start(frame.timestamp_ms));
+                    eprintln!("[EnergyVad] 🗣️  Speech started at {}ms (snr={:.1}dB, zcr={:.2})",
+                             frame.timestamp_ms, snr_db, features.zero_crossing_rate);
+                }
+            }
+
+            if state.in_speech {
+                if is_speech {
+                    state.silence_ms = 0;
+                    state.speech_ms += config.frame_ms;
+                    state.last_speech_end_ms = Some(frame.end_timestamp_ms());
+                } else {
+                    state.silence_ms += config.frame_ms;
+                    if state.silence_ms >= config.min_silence_duration_ms {
+                        state.in_speech = false;
+                        if state.speech_ms >= config.min_utterance_ms {
+                            // 自然停顿：与 SileroVad 相同的边界语义
+                            is_boundary = true;
+                            state.segment_active = false;
+                            state.speech_ms = 0;
+                            let offset_ms = state.last_speech_end_ms.unwrap_or(frame.timestamp_ms);
+                            speech_event = Some(config.speech_padding.end(offset_ms));
+                            eprintln!("[EnergyVad] ✅ Boundary detected: silence={}ms, timestamp={}ms, noise_floor={:.1}dB",
+                                     state.silence_ms, frame.timestamp_ms, state.noise_floor_db.unwrap_or(ENERGY_FLOOR_DB));
+                        }
+                    }
+                }
+            }
+        }
+        state.pending.drain(..offset);
+        let confidence = state.confidence;
+        drop(state);
+
+        Ok(DetectionOutcome {
+            is_boundary,
+            confidence,
+            frame,
+            boundary_type: if is_boundary {
+                Some(BoundaryType::NaturalPause)
+            } else {
+                None
+            },
+            speech_event,
+        })
+    }
+
+    async fn reset(&self) -> EngineResult<()> {
+        *self.state.lock().unwrap() = EnergyVadState::new();
+        self.input_adapter.reset();
+        Ok(())
+    }
+
+    fn get_info(&self) -> String {
+        format!(
+            "EnergyVad(threshold={}dB, min_energy={}dB, max_zcr={}, min_silence={}ms)",
+            self.config.speech_threshold_db,
+            self.config.min_energy_db,
+            self.config.max_zero_crossing_rate,
+            self.config.min_silence_duration_ms
+        )
+    }
+
+    fn speech_padding(&self) -> SpeechPadding {
+        self.config.speech_padding
+    }
+
+    fn fork_for_session(&self) -> Option<Arc<dyn VoiceActivityDetector>> {
+        Some(Arc::new(Self::new(self.config.clone())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(frequency: f32, amplitude: f32, samples: usize) -> Vec<f32> {
+        (0..samples)
+            .map(|i| (2.0 * std::f32::consts::PI * frequency * i as f32 / ANALYSIS_SAMPLE_RATE as f32).sin() * amplitude)
+            .collect()
+    }
+
+    #[test]
+    fn test_features_separate_speech_band_from_hum() {
+        let mut state = EnergyVadState::new();
+        // 预热滤波器
+        state.features(&tone(1000.0, 0.3, 3200));
+        let speech_band = state.features(&tone(1000.0, 0.3, 320));
+        assert!(speech_band.band_ratio > 0.8, "{:?}", speech_band);
+        assert!((speech_band.zero_crossing_rate - 0.125).abs() < 0.01);
+
+        let mut state = EnergyVadState::new();
+        state.features(&tone(50.0, 0.3, 3200));
+        let hum = state.features(&tone(50.0, 0.3, 320));
+        assert!(hum.band_ratio < 0.1, "{:?}", hum);
+    }
+}
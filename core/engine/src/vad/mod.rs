@@ -1,9 +1,14 @@
 mod time_based_vad;
 mod silero_vad;
+mod resampler;
+mod energy_vad;
+mod adaptive_store;
 
 #[cfg(test)]
 mod vad_feedback_test;
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
@@ -11,7 +16,10 @@ use crate::error::EngineResult;
 use crate::types::AudioFrame;
 
 pub use time_based_vad::TimeBasedVad;
-pub use silero_vad::{SileroVad, SileroVadConfig, VadFeedbackType};
+pub use silero_vad::{SileroVad, SileroVadConfig, SpeakerAdaptiveState, VadFeedbackType};
+pub use resampler::{StreamingResampler, VadInputAdapter};
+pub use energy_vad::{EnergyVad, EnergyVadConfig};
+pub use adaptive_store::AdaptiveStateStore;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DetectionOutcome {
@@ -27,6 +35,62 @@ pub struct DetectionOutcome {
     /// Some(BoundaryType::ForcedCutoff) = 强制截断（超过最大缓冲区）
     /// Some(BoundaryType::TimeBased) = 基于时间的截断（TimeBasedVad）
     pub boundary_type: Option<BoundaryType>,
+    /// 语音开始/结束事件（一句话开始时为 Start，该句的边界处为 End）
+    #[serde(default)]
+    pub speech_event: Option<SpeechEvent>,
+}
+
+/// 语音开始/结束事件
+///
+/// 时间戳与输入帧的 `timestamp_ms` 一致；`start_ms`/`end_ms` 已经加上了 pre-roll/post-roll，
+/// 送给 ASR 的片段应覆盖 `[start_ms, end_ms)`，避免开头的第一个音节被截掉
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SpeechEvent {
+    /// 语音开始
+    Start {
+        /// 检测到语音的时间
+        onset_ms: u64,
+        /// 片段起点（onset 减去 pre-roll）
+        start_ms: u64,
+    },
+    /// 语音结束
+    End {
+        /// 最后一段语音的结束时间
+        offset_ms: u64,
+        /// 片段终点（offset 加上 post-roll）
+        end_ms: u64,
+    },
+}
+
+/// 语音片段前后的填充时长
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpeechPadding {
+    /// 语音开始前保留的音频（毫秒）
+    pub pre_roll_ms: u64,
+    /// 语音结束后保留的音频（毫秒）
+    pub post_roll_ms: u64,
+}
+
+impl SpeechPadding {
+    pub fn new(pre_roll_ms: u64, post_roll_ms: u64) -> Self {
+        Self { pre_roll_ms, post_roll_ms }
+    }
+
+    /// 语音开始事件
+    pub fn start(&self, onset_ms: u64) -> SpeechEvent {
+        SpeechEvent::Start {
+            onset_ms,
+            start_ms: onset_ms.saturating_sub(self.pre_roll_ms),
+        }
+    }
+
+    /// 语音结束事件
+    pub fn end(&self, offset_ms: u64) -> SpeechEvent {
+        SpeechEvent::End {
+            offset_ms,
+            end_ms: offset_ms.saturating_add(self.post_roll_ms),
+        }
+    }
 }
 
 /// 边界类型（为自然停顿识别优化预留）
@@ -66,4 +130,25 @@ pub trait VoiceActivityDetector: Send + Sync {
     fn get_info(&self) -> String {
         "Unknown VAD".to_string()
     }
+
+    /// 语音开始/结束事件使用的 pre-roll/post-roll
+    ///
+    /// 默认不填充；不产生语音事件的检测器无需覆盖
+    fn speech_padding(&self) -> SpeechPadding {
+        SpeechPadding::default()
+    }
+
+    /// 为新的会话创建独立的检测器实例（共享模型，不共享状态）
+    ///
+    /// 默认返回 None，表示检测器无状态，可以在多个会话之间直接共享
+    fn fork_for_session(&self) -> Option<Arc<dyn VoiceActivityDetector>> {
+        None
+    }
+
+    /// 获取 SileroVad 特有的能力（自适应语速、反馈调整等）
+    ///
+    /// 默认返回 None；只有 SileroVad 返回 `Some(self)`
+    fn as_silero(&self) -> Option<&SileroVad> {
+        None
+    }
 }
@@ -0,0 +1,275 @@
+//! VAD 输入格式适配
+//!
+//! 浏览器客户端通常发送 44.1/48kHz 立体声，而 VAD 模型只接受 16kHz（或 8kHz）单声道。
+//! 这里把任意采样率/声道数的帧转换为检测器需要的格式：
+//! - 多声道取平均值混成单声道
+//! - 用加窗 sinc 低通插值重采样（带限，降采样时不会产生混叠）
+//! - 重采样器在帧之间保留状态，逐帧转换与整段转换的结果一致
+//!
+//! 时间戳不做换算：转换后的帧沿用原始帧的 `timestamp_ms`，与客户端的音频流保持一致。
+
+use std::sync::Mutex;
+
+use crate::asr_whisper::audio_preprocessing::convert_to_mono;
+use crate::error::{EngineError, EngineResult};
+use crate::types::AudioFrame;
+
+/// 低通滤波器单侧的过零点数（越大过渡带越窄，计算量越大）
+const SINC_ZERO_CROSSINGS: f64 = 16.0;
+
+/// 降采样时的截止频率（相对于目标采样率的奈奎斯特频率），留出过渡带避免混叠
+const DOWNSAMPLE_CUTOFF: f64 = 0.95;
+
+/// 流式带限重采样器（加窗 sinc 插值）
+///
+/// 每个输出样本对应输入中的一个（分数）位置，取该位置两侧 `span` 个输入样本做卷积。
+/// 为了保证右侧有足够的样本，输出会比输入滞后约 `span` 个输入样本（16kHz → 48kHz 时不到 1ms）。
+#[derive(Debug, Clone)]
+pub struct StreamingResampler {
+    from_rate: u32,
+    to_rate: u32,
+    /// 低通截止频率（相对于输入采样率的奈奎斯特频率）
+    cutoff: f64,
+    /// 卷积窗口单侧宽度（输入样本数）
+    span: f64,
+    /// 尚未丢弃的输入样本
+    history: Vec<f32>,
+    /// 已丢弃的输入样本数（`history[0]` 在整个流中的下标）
+    consumed: u64,
+    /// 下一个输出样本的序号
+    next_output: u64,
+}
+
+impl StreamingResampler {
+    pub fn new(from_rate: u32, to_rate: u32) -> EngineResult<Self> {
+        if from_rate == 0 || to_rate == 0 {
+            return Err(EngineError::new(format!(
+                "Invalid resample rates: {} -> {}", from_rate, to_rate
+            )));
+        }
+        let cutoff = if to_rate < from_rate {
+            to_rate as f64 / from_rate as f64 * DOWNSAMPLE_CUTOFF
+        } else {
+            1.0
+        };
+        Ok(Self {
+            from_rate,
+            to_rate,
+            cutoff,
+            span: SINC_ZERO_CROSSINGS / cutoff,
+            history: Vec::new(),
+            consumed: 0,
+            next_output: 0,
+        })
+    }
+
+    pub fn from_rate(&self) -> u32 {
+        self.from_rate
+    }
+
+    pub fn to_rate(&self) -> u32 {
+        self.to_rate
+    }
+
+    /// 输入一段样本，返回目前能够计算的全部输出样本
+    pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        if self.from_rate == self.to_rate {
+            return input.to_vec();
+        }
+        self.history.extend_from_slice(input);
+
+        let available = self.consumed + self.history.len() as u64;
+        let mut output = Vec::with_capacity(
+            (input.len() as u64 * self.to_rate as u64 / self.from_rate as u64 + 1) as usize
+        );
+        loop {
+            let position = self.output_position();
+            if position + self.span >= available as f64 {
+                break;
+            }
+            output.push(self.interpolate(position));
+            self.next_output += 1;
+        }
+
+        // 丢弃之后不会再用到的输入样本
+        let numerator = self.next_output * self.from_rate as u64;
+        let next_position = numerator as f64 / self.to_rate as f64;
+        let keep_from = ((next_position - self.span).floor().max(0.0) as u64).max(self.consumed);
+        let drop_count = ((keep_from - self.consumed) as usize).min(self.history.len());
+        self.history.drain(..drop_count);
+        self.consumed += drop_count as u64;
+
+        output
+    }
+
+    /// 输入结束：输出剩余的全部样本（流结束之后的样本视为 0），然后清空状态
+    ///
+    /// 与 `process` 的输出拼接后，总长度为 `输入样本数 * to / from`（向上取整）
+    pub fn flush(&mut self) -> Vec<f32> {
+        if self.from_rate == self.to_rate {
+            return Vec::new();
+        }
+        let total_input = self.consumed + self.history.len() as u64;
+        let total_output = (total_input * self.to_rate as u64).div_ceil(self.from_rate as u64);
+        let mut output = Vec::with_capacity(total_output.saturating_sub(self.next_output) as usize);
+        while self.next_output < total_output {
+            output.push(self.interpolate(self.output_position()));
+            self.next_output += 1;
+        }
+        self.reset();
+        output
+    }
+
+    /// 下一个输出样本在输入流中的位置 = next_output * from / to（用整数运算避免长时间累积误差）
+    fn output_position(&self) -> f64 {
+        let numerator = self.next_output * self.from_rate as u64;
+        let whole = numerator / self.to_rate as u64;
+        whole as f64 + (numerator % self.to_rate as u64) as f64 / self.to_rate as f64
+    }
+
+    /// 计算输入流中 `position` 处的带限插值（流开始之前的样本视为 0）
+    fn interpolate(&self, position: f64) -> f32 {
+        let first = (position - self.span).ceil().max(self.consumed as f64) as u64;
+        let last = (position + self.span).floor() as u64;
+        let mut sum = 0.0f64;
+        for index in first..=last {
+            let Some(&sample) = self.history.get((index - self.consumed) as usize) else {
+                break;
+            };
+            let t = position - index as f64;
+            sum += sample as f64 * self.kernel(t);
+        }
+        sum as f32
+    }
+
+    /// 低通滤波核：cutoff * sinc(cutoff * t)，乘以 Blackman 窗
+    fn kernel(&self, t: f64) -> f64 {
+        let x = t / self.span;
+        if x.abs() >= 1.0 {
+            return 0.0;
+        }
+        let arg = std::f64::consts::PI * self.cutoff * t;
+        let sinc = if arg.abs() < 1e-9 { 1.0 } else { arg.sin() / arg };
+        let phase = std::f64::consts::PI * (x + 1.0);
+        let window = 0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos();
+        self.cutoff * sinc * window
+    }
+
+    /// 清空内部状态（新的音频流）
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.consumed = 0;
+        self.next_output = 0;
+    }
+}
+
+/// VAD 输入适配器：把任意格式的帧转换为检测器需要的单声道目标采样率
+///
+/// 重采样器按输入采样率创建，输入格式变化时重新创建（并打印日志）
+#[derive(Debug)]
+pub struct VadInputAdapter {
+    target_rate: u32,
+    resampler: Mutex<Option<StreamingResampler>>,
+}
+
+impl VadInputAdapter {
+    pub fn new(target_rate: u32) -> Self {
+        Self {
+            target_rate,
+            resampler: Mutex::new(None),
+        }
+    }
+
+    pub fn target_rate(&self) -> u32 {
+        self.target_rate
+    }
+
+    /// 转换一帧音频（保留原始时间戳）
+    pub fn convert(&self, frame: &AudioFrame) -> EngineResult<AudioFrame> {
+        if frame.channels == 0 || frame.sample_rate == 0 {
+            return Err(EngineError::new(format!(
+                "Invalid audio frame format: sample_rate={}, channels={}", frame.sample_rate, frame.channels
+            )));
+        }
+
+        let mono = if frame.channels > 1 {
+            convert_to_mono(&frame.data, frame.channels as usize)
+        } else {
+            frame.data.clone()
+        };
+
+        let data = if frame.sample_rate == self.target_rate {
+            mono
+        } else {
+            let mut resampler = self.resampler.lock().unwrap();
+            if resampler.as_ref().is_none_or(|r| r.from_rate() != frame.sample_rate) {
+                eprintln!("[VAD] 🔄 Resampling input {}Hz/{}ch → {}Hz/1ch",
+                         frame.sample_rate, frame.channels, self.target_rate);
+                *resampler = Some(StreamingResampler::new(frame.sample_rate, self.target_rate)?);
+            }
+            resampler.as_mut().expect("resampler initialized").process(&mono)
+        };
+
+        Ok(AudioFrame {
+            sample_rate: self.target_rate,
+            channels: 1,
+            data,
+            timestamp_ms: frame.timestamp_ms,
+        })
+    }
+
+    /// 清空重采样状态
+    pub fn reset(&self) {
+        *self.resampler.lock().unwrap() = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency: f64, rate: u32, samples: usize) -> Vec<f32> {
+        (0..samples)
+            .map(|i| (2.0 * std::f64::consts::PI * frequency * i as f64 / rate as f64).sin() as f32 * 0.5)
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|x| x * x).sum::<f32>() / samples.len().max(1) as f32).sqrt()
+    }
+
+    #[test]
+    fn test_chunked_matches_one_shot() {
+        let input = sine(440.0, 48000, 4800);
+        let mut one_shot = StreamingResampler::new(48000, 16000).unwrap();
+        let expected = one_shot.process(&input);
+
+        let mut chunked = StreamingResampler::new(48000, 16000).unwrap();
+        let actual: Vec<f32> = input.chunks(441).flat_map(|chunk| chunked.process(chunk)).collect();
+
+        assert_eq!(actual.len(), expected.len());
+        assert!(actual.iter().zip(&expected).all(|(a, b)| (a - b).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_downsampling_rejects_aliases() {
+        // 440Hz 在通带内，幅度保持；12kHz 高于 8kHz 奈奎斯特频率，应被滤掉而不是折叠成 4kHz
+        let mut resampler = StreamingResampler::new(48000, 16000).unwrap();
+        let passband = resampler.process(&sine(440.0, 48000, 48000));
+        assert!((rms(&passband[1000..]) - 0.5 / 2f32.sqrt()).abs() < 0.01);
+
+        let mut resampler = StreamingResampler::new(48000, 16000).unwrap();
+        let stopband = resampler.process(&sine(12000.0, 48000, 48000));
+        assert!(rms(&stopband[1000..]) < 0.005);
+    }
+
+    #[test]
+    fn test_flush_outputs_remaining_samples() {
+        let mut resampler = StreamingResampler::new(22050, 16000).unwrap();
+        let mut output = resampler.process(&sine(440.0, 22050, 22050));
+        output.extend(resampler.flush());
+        assert_eq!(output.len(), 16000);
+        assert!((rms(&output[1000..15000]) - 0.5 / 2f32.sqrt()).abs() < 0.01);
+        assert!(resampler.flush().is_empty());
+    }
+}
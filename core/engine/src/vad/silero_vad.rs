@@ -10,22 +10,28 @@ use std::collections::VecDeque;
 use ort::{Environment, Session, SessionBuilder, Value};
 use ndarray::{Array1, Array2, Array3, Ix2, Ix3};
 use ndarray::CowArray;
+use serde::{Deserialize, Serialize};
 
 use crate::error::EngineResult;
 use crate::types::AudioFrame;
-use crate::vad::{DetectionOutcome, VoiceActivityDetector, BoundaryType};
+use crate::vad::{DetectionOutcome, VoiceActivityDetector, BoundaryType, SpeechEvent, SpeechPadding, VadInputAdapter};
 
 /// Silero VAD 配置
 #[derive(Clone)]
 pub struct SileroVadConfig {
     /// 模型文件路径
     pub model_path: String,
-    /// 采样率（Silero VAD 要求 16kHz）
+    /// 模型采样率（16kHz，电话音频可用 8kHz）；其他采样率和多声道的输入会在检测前自动转换
     pub sample_rate: u32,
-    /// 帧大小（512 samples @ 16kHz = 32ms）
+    /// 帧大小（512 samples @ 16kHz = 32ms，256 samples @ 8kHz = 32ms）
     pub frame_size: usize,
-    /// 静音阈值（0.0-1.0），低于此值认为是静音
+    /// 静音阈值（0.0-1.0，离开语音状态的阈值）：处于语音状态时，平滑后的语音概率低于此值才认为是静音
     pub silence_threshold: f32,
+    /// 语音起始阈值（0.0-1.0，进入语音状态的阈值）：平滑后的语音概率达到此值才认为语音开始，
+    /// 应不小于 `silence_threshold`，两者之间的区间用于滞回，避免在阈值附近频繁切换
+    pub speech_onset_threshold: f32,
+    /// 语音概率平滑系数（0.0-1.0，指数移动平均中上一帧的权重，0.0 表示不平滑）
+    pub probability_smoothing: f32,
     /// 最小静音时长（毫秒），超过此时长才判定为自然停顿
     pub min_silence_duration_ms: u64,
     /// 是否启用自适应调整（按用户）
@@ -58,6 +64,8 @@ pub struct SileroVadConfig {
     /// 最小话语时长（防止半句话被切掉，毫秒）
     #[allow(dead_code)]
     pub min_utterance_ms: u64,
+    /// 语音开始/结束事件的 pre-roll/post-roll（片段向前、向后多保留的音频）
+    pub speech_padding: SpeechPadding,
 }
 
 impl Default for SileroVadConfig {
@@ -67,6 +75,8 @@ impl Default for SileroVadConfig {
             sample_rate: 16000,
             frame_size: 512,  // 32ms @ 16kHz
             silence_threshold: 0.2,  // 降低阈值，提高语音检测灵敏度（从 0.5 降到 0.2）
+            speech_onset_threshold: 0.3,  // 进入语音需要更高的概率，滞回区间 0.2-0.3
+            probability_smoothing: 0.3,
             min_silence_duration_ms: 300,  // 基础阈值（从500ms降低到300ms以更快响应）
             adaptive_enabled: true,  // 默认启用自适应
             adaptive_min_samples: 1,  // 至少1个样本（降低以更快开始调整）
@@ -78,12 +88,149 @@ impl Default for SileroVadConfig {
             final_threshold_min_ms: 200,  // 最终阈值范围：200-800ms（从300-1000ms降低，更快响应）
             final_threshold_max_ms: 800,
             min_utterance_ms: 1000,  // 最小话语时长：1000ms（降低以防止过度等待）
+            speech_padding: SpeechPadding::new(300, 150),  // 保留语音开始前 300ms，避免第一个音节被截掉
         }
     }
 }
 
+impl SileroVadConfig {
+    /// 电话音频配置（8kHz 模型输入，256 samples = 32ms）
+    pub fn telephony(model_path: impl Into<String>) -> Self {
+        Self {
+            model_path: model_path.into(),
+            sample_rate: 8000,
+            frame_size: 256,
+            ..Default::default()
+        }
+    }
+}
+
+/// 模型的循环状态输入布局（加载模型时根据输入名检测）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StateLayout {
+    /// v5：单个 `state` 张量 [2, 1, 128]，输入前需要拼接上一块末尾的上下文样本
+    Combined,
+    /// v4：分开的 `h`、`c` 张量（各 [2, 1, 64]）
+    Split,
+}
+
+impl StateLayout {
+    fn detect(input_names: &[String]) -> Self {
+        let has = |name: &str| input_names.iter().any(|input| input == name);
+        if has("h") && has("c") {
+            StateLayout::Split
+        } else {
+            StateLayout::Combined
+        }
+    }
+
+    /// 上下文样本数（v5 模型：16kHz 为 64，8kHz 为 32；v4 模型不需要）
+    fn context_size(self, sample_rate: u32) -> usize {
+        match self {
+            StateLayout::Combined if sample_rate == 8000 => 32,
+            StateLayout::Combined => 64,
+            StateLayout::Split => 0,
+        }
+    }
+}
+
+/// 是否为循环状态输入
+fn is_state_input(name: &str) -> bool {
+    matches!(name, "state" | "h" | "c")
+}
+
+/// 跨帧传递的模型状态（每个会话独立，reset 时清空）
+#[derive(Debug, Clone)]
+struct RecurrentState {
+    /// 循环状态张量（按模型输入顺序：Combined 布局为 [state]，Split 布局为 [h, c]）
+    tensors: Vec<Array3<f32>>,
+    /// 上一块音频末尾的样本（拼接在下一块之前）
+    context: Vec<f32>,
+}
+
+impl RecurrentState {
+    fn new(layout: StateLayout, context_size: usize) -> Self {
+        let tensors = match layout {
+            StateLayout::Combined => vec![Array3::<f32>::zeros((2, 1, 128))],
+            StateLayout::Split => vec![Array3::<f32>::zeros((2, 1, 64)); 2],
+        };
+        Self {
+            tensors,
+            context: vec![0.0; context_size],
+        }
+    }
+}
+
+/// 语音概率平滑 + 滞回判定（每个会话独立）
+#[derive(Debug, Clone, Default)]
+struct SpeechHysteresis {
+    /// 平滑后的语音概率
+    smoothed: Option<f32>,
+    /// 当前是否处于语音状态
+    in_speech: bool,
+}
+
+impl SpeechHysteresis {
+    /// 输入一帧的语音概率，返回平滑后的概率和当前是否处于语音状态
+    fn update(&mut self, speech_prob: f32, config: &SileroVadConfig) -> (f32, bool) {
+        let alpha = config.probability_smoothing.clamp(0.0, 0.99);
+        let smoothed = match self.smoothed {
+            Some(previous) => alpha * previous + (1.0 - alpha) * speech_prob,
+            None => speech_prob,
+        };
+        self.smoothed = Some(smoothed);
+
+        let onset = config.speech_onset_threshold.max(config.silence_threshold);
+        if self.in_speech {
+            if smoothed < config.silence_threshold {
+                self.in_speech = false;
+            }
+        } else if smoothed >= onset {
+            self.in_speech = true;
+        }
+        (smoothed, self.in_speech)
+    }
+}
+
+/// 当前话语的语音事件状态（每个会话独立）
+#[derive(Debug, Clone, Default)]
+struct SpeechSegmentState {
+    /// 是否已经发出 Start、尚未发出 End
+    active: bool,
+    /// 最近一个语音帧的结束时间
+    last_speech_end_ms: Option<u64>,
+}
+
+impl SpeechSegmentState {
+    /// 根据当前帧的判定更新状态，返回需要发出的语音事件
+    fn update(
+        &mut self,
+        in_speech: bool,
+        is_boundary: bool,
+        frame: &AudioFrame,
+        padding: &SpeechPadding,
+    ) -> Option<SpeechEvent> {
+        if in_speech {
+            self.last_speech_end_ms = Some(frame.end_timestamp_ms());
+            if !self.active {
+                self.active = true;
+                return Some(padding.start(frame.timestamp_ms));
+            }
+        } else if is_boundary && self.active {
+            self.active = false;
+            let offset_ms = self.last_speech_end_ms.unwrap_or(frame.timestamp_ms);
+            return Some(padding.end(offset_ms));
+        }
+        None
+    }
+}
+
 /// 每个说话者的自适应状态
-struct SpeakerAdaptiveState {
+///
+/// 可以序列化为 JSON（见 `AdaptiveStateStore`），在会话开始时恢复，
+/// 老用户不需要每次连接都从默认阈值重新学习。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeakerAdaptiveState {
     /// 语速历史（字符/秒）
     speech_rate_history: VecDeque<f32>,
     /// 基础阈值（由语速自适应生成，毫秒）
@@ -94,11 +241,14 @@ struct SpeakerAdaptiveState {
     sample_count: usize,
 }
 
+/// 语速历史保留的样本数
+const SPEECH_RATE_HISTORY_LEN: usize = 20;
+
 impl SpeakerAdaptiveState {
     fn new(base_duration_ms: u64) -> Self {
         eprintln!("[SileroVad] 🆕 Initialized SpeakerAdaptiveState with base_duration_ms={}ms", base_duration_ms);
         Self {
-            speech_rate_history: VecDeque::with_capacity(20),  // 保留最近20个样本
+            speech_rate_history: VecDeque::with_capacity(SPEECH_RATE_HISTORY_LEN),  // 保留最近20个样本
             base_threshold_ms: base_duration_ms,
             delta_ms: 0,  // 初始 delta 为 0
             sample_count: 0,
@@ -113,7 +263,7 @@ impl SpeakerAdaptiveState {
     /// - 慢语速 → 更长的阈值（说话者可能在句子中间思考停顿）
     fn update_speech_rate(&mut self, speech_rate: f32, config: &SileroVadConfig) {
         self.speech_rate_history.push_back(speech_rate);
-        if self.speech_rate_history.len() > 20 {
+        if self.speech_rate_history.len() > SPEECH_RATE_HISTORY_LEN {
             self.speech_rate_history.pop_front();
         }
         self.sample_count += 1;
@@ -212,6 +362,33 @@ impl SpeakerAdaptiveState {
             Some(self.speech_rate_history.iter().sum::<f32>() / self.speech_rate_history.len() as f32)
         }
     }
+    
+    /// 基础阈值（毫秒）
+    pub fn base_threshold_ms(&self) -> u64 {
+        self.base_threshold_ms
+    }
+    
+    /// 反馈偏移量（毫秒）
+    pub fn delta_ms(&self) -> i64 {
+        self.delta_ms
+    }
+    
+    /// 已学习的样本数量
+    pub fn sample_count(&self) -> usize {
+        self.sample_count
+    }
+    
+    /// 把从磁盘读取的状态限制在当前配置的范围内（配置可能已经修改，文件也可能被手动编辑过）
+    fn sanitized(mut self, config: &SileroVadConfig) -> Self {
+        self.speech_rate_history.retain(|rate| rate.is_finite() && *rate > 0.0);
+        while self.speech_rate_history.len() > SPEECH_RATE_HISTORY_LEN {
+            self.speech_rate_history.pop_front();
+        }
+        self.base_threshold_ms = self.base_threshold_ms
+            .clamp(config.base_threshold_min_ms, config.base_threshold_max_ms);
+        self.delta_ms = self.delta_ms.clamp(config.delta_min_ms, config.delta_max_ms);
+        self
+    }
 }
 
 /// Silero VAD 实现
@@ -222,14 +399,24 @@ pub struct SileroVad {
     silence_frame_count: Arc<Mutex<usize>>,
     /// 上一个检测到语音的帧的时间戳
     last_speech_timestamp: Arc<Mutex<Option<u64>>>,
-    /// 隐藏状态（用于 VAD 模型的状态传递）
-    hidden_state: Arc<Mutex<Option<Array2<f32>>>>,
-    /// 全局自适应状态（不按说话者区分，每个短句都根据上一个短句的语速调整）
+    /// 模型输入名（按模型输入顺序）
+    input_names: Arc<Vec<String>>,
+    /// 循环状态布局
+    state_layout: StateLayout,
+    /// 循环状态（跨帧传递，None 表示下一帧从零状态开始）
+    recurrent_state: Arc<Mutex<Option<RecurrentState>>>,
+    /// 语音概率平滑和滞回状态
+    hysteresis: Arc<Mutex<SpeechHysteresis>>,
+    /// 语音开始/结束事件状态
+    speech_segment: Arc<Mutex<SpeechSegmentState>>,
+    /// 会话的自适应状态（每个短句都根据上一个短句的语速调整；可按用户保存和恢复）
     adaptive_state: Arc<Mutex<SpeakerAdaptiveState>>,
     /// 上一次边界检测的时间戳（用于冷却期）
     last_boundary_timestamp: Arc<Mutex<Option<u64>>>,
     /// 帧缓冲区（用于累积小帧，直到达到 frame_size）
     frame_buffer: Arc<Mutex<Vec<f32>>>,
+    /// 输入格式适配（混成单声道并重采样到模型采样率）
+    input_adapter: Arc<VadInputAdapter>,
 }
 
 impl SileroVad {
@@ -246,6 +433,15 @@ impl SileroVad {
     
     /// 使用自定义配置创建 SileroVad
     pub fn with_config(config: SileroVadConfig) -> EngineResult<Self> {
+        if config.sample_rate != 8000 && config.sample_rate != 16000 {
+            return Err(crate::error::EngineError::new(
+                format!("Unsupported Silero VAD sample rate {} (expected 8000 or 16000)", config.sample_rate)
+            ));
+        }
+        if config.frame_size == 0 {
+            return Err(crate::error::EngineError::new("Silero VAD frame size must be positive"));
+        }
+        
         // 初始化 ONNX Runtime 环境
         crate::onnx_utils::init_onnx_runtime()
             .map_err(|e| crate::error::EngineError::new(format!("Failed to init ONNX runtime: {}", e)))?;
@@ -270,18 +466,27 @@ impl SileroVad {
             eprintln!("  Input[{}]: name='{}', dimensions={:?}, input_type={:?}", 
                      i, input.name, input.dimensions, input.input_type);
         }
+        let input_names: Vec<String> = session.inputs.iter().map(|input| input.name.clone()).collect();
+        let state_layout = StateLayout::detect(&input_names);
+        eprintln!("[SileroVad] State layout: {:?}, sample_rate={}Hz, frame_size={}, context={}",
+                 state_layout, config.sample_rate, config.frame_size, state_layout.context_size(config.sample_rate));
         
         Ok(Self {
             session: Arc::new(Mutex::new(session)),
             config: config.clone(),
             silence_frame_count: Arc::new(Mutex::new(0)),
             last_speech_timestamp: Arc::new(Mutex::new(None)),
-            hidden_state: Arc::new(Mutex::new(None)),
+            input_names: Arc::new(input_names),
+            state_layout,
+            recurrent_state: Arc::new(Mutex::new(None)),
+            hysteresis: Arc::new(Mutex::new(SpeechHysteresis::default())),
+            speech_segment: Arc::new(Mutex::new(SpeechSegmentState::default())),
             adaptive_state: Arc::new(Mutex::new(SpeakerAdaptiveState::new(
                 (config.base_threshold_min_ms + config.base_threshold_max_ms) / 2
             ))),
             last_boundary_timestamp: Arc::new(Mutex::new(None)),
             frame_buffer: Arc::new(Mutex::new(Vec::new())),
+            input_adapter: Arc::new(VadInputAdapter::new(config.sample_rate)),
         })
     }
     
@@ -305,74 +510,57 @@ impl SileroVad {
             .map(|&x| x.clamp(-1.0, 1.0))
             .collect();
         
-        // 创建音频输入数组（形状：[1, frame_size]）
-        let input_array = Array2::from_shape_vec((1, normalized.len()), normalized)
+        // 取出上一帧留下的状态（第一帧或 reset 之后从零状态开始）
+        let context_size = self.state_layout.context_size(self.config.sample_rate);
+        let mut state = self.recurrent_state.lock().unwrap()
+            .take()
+            .unwrap_or_else(|| RecurrentState::new(self.state_layout, context_size));
+        
+        // 模型输入 = 上下文 + 当前块（形状：[1, context + frame_size]）
+        let mut model_input = state.context.clone();
+        model_input.extend_from_slice(&normalized);
+        let new_context = model_input[model_input.len() - context_size..].to_vec();
+        let input_array = Array2::from_shape_vec((1, model_input.len()), model_input)
             .map_err(|e| crate::error::EngineError::new(format!("Failed to create input array: {}", e)))?;
+        let cow_arr = CowArray::from(input_array.into_dyn());
         
-        // 获取或初始化隐藏状态（形状：[2, 1, 128]）
-        let state_array = {
-            let mut state_guard = self.hidden_state.lock().unwrap();
-            if let Some(ref state_2d) = *state_guard {
-                // 状态存储为 [2, 128]，需要扩展为 [2, 1, 128]
-                let state_3d = state_2d.clone().into_shape((2, 1, 128))
-                    .map_err(|e| crate::error::EngineError::new(format!("Failed to reshape state: {}", e)))?;
-                state_3d
-            } else {
-                // 初始化隐藏状态为零 [2, 1, 128]
-                let new_state = Array3::<f32>::zeros((2, 1, 128));
-                // 存储为 [2, 128] 以便下次使用
-                *state_guard = Some(new_state.clone().into_shape((2, 128))
-                    .map_err(|e| crate::error::EngineError::new(format!("Failed to reshape new state: {}", e)))?);
-                new_state
-            }
-        };
-        
-        // 转换为动态维度
-        let arr_dyn = input_array.into_dyn();
-        let arr_owned = arr_dyn.to_owned();
-        let cow_arr = CowArray::from(arr_owned);
-        
-        let state_dyn = state_array.into_dyn();
-        let state_owned = state_dyn.to_owned();
-        let state_cow = CowArray::from(state_owned);
+        let state_cows: Vec<CowArray<f32, ndarray::IxDyn>> = state.tensors.iter()
+            .map(|tensor| CowArray::from(tensor.clone().into_dyn()))
+            .collect();
         
         // 创建采样率输入（Int64 标量，形状：[]）
         // 注意：Silero VAD 的 sr 输入是 Int64，不是 Float32
         let sr_array = Array1::from_vec(vec![self.config.sample_rate as i64]);
-        let sr_dyn = sr_array.into_dyn();
-        let sr_owned = sr_dyn.to_owned();
-        let sr_cow = CowArray::from(sr_owned);
+        let sr_cow = CowArray::from(sr_array.into_dyn());
         
-        // 创建 ONNX 输入（需要在同一个作用域内创建，确保生命周期正确）
+        // 按模型输入名创建 ONNX 输入（v5：input, state, sr；v4：input, sr, h, c）
         use std::ptr;
-        let audio_input = {
-            let audio_val = Value::from_array(ptr::null_mut(), &cow_arr)
-                .map_err(|e| crate::error::EngineError::new(format!("Failed to create audio input: {}", e)))?;
-            unsafe { std::mem::transmute::<Value, Value<'static>>(audio_val) }
-        };
-        
-        let state_input = {
-            let state_val = Value::from_array(ptr::null_mut(), &state_cow)
-                .map_err(|e| crate::error::EngineError::new(format!("Failed to create state input: {}", e)))?;
-            unsafe { std::mem::transmute::<Value, Value<'static>>(state_val) }
-        };
-        
-        let sr_input = {
-            let sr_val = Value::from_array(ptr::null_mut(), &sr_cow)
-                .map_err(|e| crate::error::EngineError::new(format!("Failed to create sr input: {}", e)))?;
-            unsafe { std::mem::transmute::<Value, Value<'static>>(sr_val) }
-        };
+        let mut inputs = Vec::with_capacity(self.input_names.len());
+        let mut state_inputs = state_cows.iter();
+        for name in self.input_names.iter() {
+            let value = if name == "sr" {
+                Value::from_array(ptr::null_mut(), &sr_cow)
+            } else if is_state_input(name) {
+                let state_cow = state_inputs.next()
+                    .ok_or_else(|| crate::error::EngineError::new(format!("Unexpected state input '{}'", name)))?;
+                Value::from_array(ptr::null_mut(), state_cow)
+            } else {
+                Value::from_array(ptr::null_mut(), &cow_arr)
+            }
+            .map_err(|e| crate::error::EngineError::new(format!("Failed to create input '{}': {}", name, e)))?;
+            inputs.push(unsafe { std::mem::transmute::<Value, Value<'static>>(value) });
+        }
         
-        // 推理（按模型输入顺序传递：input, state, sr）
+        // 推理
         let session_guard = self.session.lock().unwrap();
         let outputs = session_guard
-            .run(vec![audio_input, state_input, sr_input])
+            .run(inputs)
             .map_err(|e| crate::error::EngineError::new(format!("ONNX inference failed: {}", e)))?;
         
         // 提取输出
-        // Silero VAD 输出：[output, new_state]
-        // output 形状：[1, 2]，第一列是静音概率，第二列是语音概率
-        // new_state 形状：[2, 1, 128]，新的隐藏状态
+        // Silero VAD 输出：[output, 新的循环状态...]
+        // output 形状：[1, 1] 或 [1, 2]（第二列是语音概率）
+        // 新状态的顺序与状态输入相同（v5：state；v4：h, c）
         use ort::tensor::OrtOwnedTensor;
         use ndarray::IxDyn;
         
@@ -381,26 +569,18 @@ impl SileroVad {
             .try_extract()
             .map_err(|e| crate::error::EngineError::new(format!("Failed to extract output: {}", e)))?;
         
-        // 提取 new_state（第二个输出）并更新隐藏状态
-        if outputs.len() > 1 {
-            let state_tensor: OrtOwnedTensor<f32, IxDyn> = outputs[1]
+        // 提取新的循环状态；模型没有输出某个状态时保留旧值
+        for (tensor, output) in state.tensors.iter_mut().zip(outputs.iter().skip(1)) {
+            let state_tensor: OrtOwnedTensor<f32, IxDyn> = output
                 .try_extract()
                 .map_err(|e| crate::error::EngineError::new(format!("Failed to extract state: {}", e)))?;
-            
-            let state_view = state_tensor.view();
-            let new_state_3d: Array3<f32> = state_view
+            *tensor = state_tensor.view()
                 .to_owned()
                 .into_dimensionality::<Ix3>()
                 .map_err(|e| crate::error::EngineError::new(format!("Failed to reshape state: {}", e)))?;
-            
-            // 将状态从 [2, 1, 128] 转换为 [2, 128] 存储
-            let new_state_2d = new_state_3d.into_shape((2, 128))
-                .map_err(|e| crate::error::EngineError::new(format!("Failed to reshape state for storage: {}", e)))?;
-            
-            // 更新隐藏状态
-            let mut state_guard = self.hidden_state.lock().unwrap();
-            *state_guard = Some(new_state_2d);
         }
+        state.context = new_context;
+        *self.recurrent_state.lock().unwrap() = Some(state);
         
         // 提取输出值
         // 根据实际输出形状处理：
@@ -514,13 +694,6 @@ impl SileroVad {
 #[async_trait]
 impl VoiceActivityDetector for SileroVad {
     async fn detect(&self, frame: AudioFrame) -> EngineResult<DetectionOutcome> {
-        // 检查采样率是否匹配
-        if frame.sample_rate != self.config.sample_rate {
-            return Err(crate::error::EngineError::new(
-                format!("Sample rate mismatch: expected {}, got {}", self.config.sample_rate, frame.sample_rate)
-            ));
-        }
-        
         // 清理 FINAL_FRAME_FLAG（如果设置了的话）
         // FINAL_FRAME_FLAG = 1u64 << 63，用于标记最后一帧
         const FINAL_FRAME_FLAG: u64 = 1u64 << 63;
@@ -528,9 +701,12 @@ impl VoiceActivityDetector for SileroVad {
         let mut cleaned_frame = frame.clone();
         cleaned_frame.timestamp_ms = cleaned_timestamp;
         
+        // 转换为模型需要的格式（单声道、模型采样率）；返回给调用方的仍是原始帧
+        let model_frame = self.input_adapter.convert(&cleaned_frame)?;
+        
         // 累积帧到缓冲区，直到达到 frame_size
         let mut buffer = self.frame_buffer.lock().unwrap();
-        buffer.extend_from_slice(&cleaned_frame.data);
+        buffer.extend_from_slice(&model_frame.data);
         
         // 如果缓冲区还没有达到 frame_size，返回一个"非边界"的结果
         // 注意：我们需要至少累积到 frame_size 才能进行 VAD 检测
@@ -542,6 +718,7 @@ impl VoiceActivityDetector for SileroVad {
                 confidence: 0.5,
                 frame: cleaned_frame.clone(),
                 boundary_type: None,
+                speech_event: None,
             });
         }
         
@@ -567,8 +744,9 @@ impl VoiceActivityDetector for SileroVad {
         // 检测语音活动
         let speech_prob = self.detect_voice_activity(&audio_data)?;
         
-        // 判断是否为静音
-        let is_silence = speech_prob < self.config.silence_threshold;
+        // 判断是否为静音（平滑后的概率 + 起始/结束双阈值滞回）
+        let (_, in_speech) = self.hysteresis.lock().unwrap().update(speech_prob, &self.config);
+        let is_silence = !in_speech;
         
         // 更新静音帧计数
         let mut silence_count = self.silence_frame_count.lock().unwrap();
@@ -622,6 +800,7 @@ impl VoiceActivityDetector for SileroVad {
                 confidence: 0.5,
                 frame: cleaned_frame.clone(),
                 boundary_type: None,
+                speech_event: None,
             });
         }
         
@@ -697,6 +876,19 @@ impl VoiceActivityDetector for SileroVad {
             *last_boundary_ts = None;
         }
         
+        // 一句话的第一个语音帧发出 Start，该句的边界处发出 End
+        let speech_event = self.speech_segment.lock().unwrap()
+            .update(in_speech, is_boundary, &cleaned_frame, &self.config.speech_padding);
+        match &speech_event {
+            Some(SpeechEvent::Start { onset_ms, start_ms }) => {
+                eprintln!("[SileroVad] 🗣️  Speech started at {}ms (segment starts at {}ms with pre-roll)", onset_ms, start_ms);
+            }
+            Some(SpeechEvent::End { offset_ms, end_ms }) => {
+                eprintln!("[SileroVad] 🤫 Speech ended at {}ms (segment ends at {}ms with post-roll)", offset_ms, end_ms);
+            }
+            None => {}
+        }
+        
         Ok(DetectionOutcome {
             is_boundary,
             confidence: speech_prob,
@@ -706,23 +898,29 @@ impl VoiceActivityDetector for SileroVad {
             } else {
                 None
             },
+            speech_event,
         })
     }
     
     async fn reset(&self) -> EngineResult<()> {
         let mut silence_count = self.silence_frame_count.lock().unwrap();
         let mut last_speech = self.last_speech_timestamp.lock().unwrap();
-        let mut hidden_state = self.hidden_state.lock().unwrap();
+        let mut recurrent_state = self.recurrent_state.lock().unwrap();
+        let mut hysteresis = self.hysteresis.lock().unwrap();
+        let mut speech_segment = self.speech_segment.lock().unwrap();
         let mut adaptive_state = self.adaptive_state.lock().unwrap();
         let mut last_boundary_ts = self.last_boundary_timestamp.lock().unwrap();
         let mut frame_buffer = self.frame_buffer.lock().unwrap();
         *silence_count = 0;
         *last_speech = None;
-        *hidden_state = None;  // 重置隐藏状态
+        *recurrent_state = None;  // 重置模型循环状态和上下文
+        *hysteresis = SpeechHysteresis::default();  // 重置概率平滑和滞回状态
+        *speech_segment = SpeechSegmentState::default();  // 重置语音事件状态
         *adaptive_state = SpeakerAdaptiveState::new(
             (self.config.base_threshold_min_ms + self.config.base_threshold_max_ms) / 2
         );  // 重置自适应状态
         frame_buffer.clear();  // 清空帧缓冲区
+        self.input_adapter.reset();  // 清空重采样状态
         *last_boundary_ts = None;  // 重置边界冷却期
         Ok(())
     }
@@ -736,6 +934,35 @@ impl VoiceActivityDetector for SileroVad {
             self.config.adaptive_enabled
         )
     }
+    
+    fn speech_padding(&self) -> SpeechPadding {
+        self.config.speech_padding
+    }
+    
+    fn fork_for_session(&self) -> Option<Arc<dyn VoiceActivityDetector>> {
+        // 共享 ONNX 会话（模型只加载一次），但每个会话拥有独立的静音计数、隐藏状态和自适应状态
+        Some(Arc::new(Self {
+            session: Arc::clone(&self.session),
+            config: self.config.clone(),
+            silence_frame_count: Arc::new(Mutex::new(0)),
+            last_speech_timestamp: Arc::new(Mutex::new(None)),
+            input_names: Arc::clone(&self.input_names),
+            state_layout: self.state_layout,
+            recurrent_state: Arc::new(Mutex::new(None)),
+            hysteresis: Arc::new(Mutex::new(SpeechHysteresis::default())),
+            speech_segment: Arc::new(Mutex::new(SpeechSegmentState::default())),
+            adaptive_state: Arc::new(Mutex::new(SpeakerAdaptiveState::new(
+                (self.config.base_threshold_min_ms + self.config.base_threshold_max_ms) / 2
+            ))),
+            last_boundary_timestamp: Arc::new(Mutex::new(None)),
+            frame_buffer: Arc::new(Mutex::new(Vec::new())),
+            input_adapter: Arc::new(VadInputAdapter::new(self.config.sample_rate)),
+        }))
+    }
+    
+    fn as_silero(&self) -> Option<&SileroVad> {
+        Some(self)
+    }
 }
 
 // 为 SileroVad 添加自适应相关方法
@@ -905,6 +1132,21 @@ impl SileroVad {
                  old_delta, state.delta_ms, feedback_type, delta_adjustment, old_base, old_effective, new_effective);
     }
     
+    /// 导出当前的自适应状态（用于按用户持久化）
+    pub fn adaptive_state(&self) -> SpeakerAdaptiveState {
+        self.adaptive_state.lock().unwrap().clone()
+    }
+    
+    /// 恢复之前保存的自适应状态（会话开始时调用）
+    ///
+    /// 状态会被限制在当前配置的阈值范围内
+    pub fn restore_adaptive_state(&self, state: SpeakerAdaptiveState) {
+        let state = state.sanitized(&self.config);
+        eprintln!("[SileroVad] ♻️  Restored adaptive state: effective_threshold={}ms (base={}ms, delta={:+}ms, samples={})",
+                 state.get_adjusted_duration(&self.config), state.base_threshold_ms, state.delta_ms, state.sample_count);
+        *self.adaptive_state.lock().unwrap() = state;
+    }
+    
     /// 基于反馈调整阈值（兼容旧接口，已废弃）
     #[deprecated(note = "Use adjust_delta_by_feedback instead")]
     pub fn adjust_threshold_by_feedback(&self, feedback_type: VadFeedbackType, _adjustment_factor: f32) {
@@ -985,12 +1227,43 @@ mod tests {
         assert!(adjusted <= config.final_threshold_max_ms);
     }
     
+    #[test]
+    fn test_speaker_adaptive_state_json_roundtrip() {
+        let config = SileroVadConfig::default();
+        let mut state = SpeakerAdaptiveState::new(400);
+        state.update_speech_rate(10.0, &config);
+        state.update_speech_rate(9.0, &config);
+        state.delta_ms = -150;
+        
+        let json = serde_json::to_string(&state).unwrap();
+        let restored: SpeakerAdaptiveState = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, state);
+        assert_eq!(restored.get_adjusted_duration(&config), state.get_adjusted_duration(&config));
+    }
+    
+    #[test]
+    fn test_speaker_adaptive_state_sanitized() {
+        let config = SileroVadConfig::default();
+        let mut state = SpeakerAdaptiveState::new(5000);
+        state.delta_ms = 10_000;
+        state.speech_rate_history = (0..30).map(|i| i as f32).collect();
+        state.speech_rate_history.push_back(f32::NAN);
+        
+        let state = state.sanitized(&config);
+        assert_eq!(state.base_threshold_ms, config.base_threshold_max_ms);
+        assert_eq!(state.delta_ms, config.delta_max_ms);
+        assert_eq!(state.speech_rate_history.len(), SPEECH_RATE_HISTORY_LEN);
+        assert!(state.speech_rate_history.iter().all(|rate| rate.is_finite() && *rate > 0.0));
+    }
+    
     #[test]
     fn test_silero_vad_config_default() {
         let config = SileroVadConfig::default();
         assert_eq!(config.sample_rate, 16000);
         assert_eq!(config.frame_size, 512);
         assert_eq!(config.silence_threshold, 0.2);  // 更新为新的默认值
+        assert_eq!(config.speech_onset_threshold, 0.3);
+        assert_eq!(config.probability_smoothing, 0.3);
         assert_eq!(config.min_silence_duration_ms, 300);
         assert!(config.adaptive_enabled);
         assert_eq!(config.adaptive_min_samples, 1);
@@ -1001,6 +1274,117 @@ mod tests {
         assert_eq!(config.final_threshold_max_ms, 800);
     }
     
+    #[test]
+    fn test_telephony_config() {
+        let config = SileroVadConfig::telephony("models/vad/silero/silero_vad.onnx");
+        assert_eq!(config.sample_rate, 8000);
+        assert_eq!(config.frame_size, 256);
+        assert_eq!(config.silence_threshold, SileroVadConfig::default().silence_threshold);
+    }
+    
+    #[test]
+    fn test_state_layout_detection() {
+        let names = |list: &[&str]| list.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        assert_eq!(StateLayout::detect(&names(&["input", "state", "sr"])), StateLayout::Combined);
+        assert_eq!(StateLayout::detect(&names(&["input", "sr", "h", "c"])), StateLayout::Split);
+        assert_eq!(StateLayout::Combined.context_size(16000), 64);
+        assert_eq!(StateLayout::Combined.context_size(8000), 32);
+        assert_eq!(StateLayout::Split.context_size(16000), 0);
+        
+        let state = RecurrentState::new(StateLayout::Split, 0);
+        assert_eq!(state.tensors.len(), 2);
+        assert_eq!(state.tensors[0].shape(), &[2, 1, 64]);
+    }
+    
+    #[test]
+    fn test_speech_hysteresis() {
+        let config = SileroVadConfig {
+            silence_threshold: 0.3,
+            speech_onset_threshold: 0.6,
+            probability_smoothing: 0.0,
+            ..Default::default()
+        };
+        let mut hysteresis = SpeechHysteresis::default();
+        
+        // 介于两个阈值之间的概率不会进入语音状态
+        assert!(!hysteresis.update(0.5, &config).1);
+        assert!(hysteresis.update(0.7, &config).1);
+        // 进入语音后，介于两个阈值之间的概率保持语音状态
+        assert!(hysteresis.update(0.4, &config).1);
+        assert!(!hysteresis.update(0.2, &config).1);
+        assert!(!hysteresis.update(0.5, &config).1);
+    }
+    
+    #[test]
+    fn test_speech_probability_smoothing() {
+        let config = SileroVadConfig {
+            silence_threshold: 0.3,
+            speech_onset_threshold: 0.6,
+            probability_smoothing: 0.5,
+            ..Default::default()
+        };
+        let mut hysteresis = SpeechHysteresis::default();
+        
+        assert!(hysteresis.update(0.9, &config).1);
+        // 单帧的低概率被平滑：0.5 * 0.9 + 0.5 * 0.0 = 0.45，仍在语音状态
+        let (smoothed, in_speech) = hysteresis.update(0.0, &config);
+        assert!((smoothed - 0.45).abs() < 1e-6);
+        assert!(in_speech);
+        // 持续的低概率最终结束语音
+        assert!(!hysteresis.update(0.0, &config).1);
+    }
+    
+    #[test]
+    fn test_speech_segment_events_with_padding() {
+        let padding = SpeechPadding::new(300, 100);
+        let mut segment = SpeechSegmentState::default();
+        let frame = |timestamp_ms: u64| create_test_frame(timestamp_ms, vec![0.0; 512]);  // 32ms
+        
+        assert_eq!(segment.update(false, false, &frame(0), &padding), None);
+        assert_eq!(segment.update(true, false, &frame(1000), &padding),
+                   Some(SpeechEvent::Start { onset_ms: 1000, start_ms: 700 }));
+        // 同一句话内的语音帧和短暂静音不再发出事件
+        assert_eq!(segment.update(true, false, &frame(1032), &padding), None);
+        assert_eq!(segment.update(false, false, &frame(1064), &padding), None);
+        // 边界处结束：最后一个语音帧结束于 1064ms，加上 100ms post-roll
+        assert_eq!(segment.update(false, true, &frame(1600), &padding),
+                   Some(SpeechEvent::End { offset_ms: 1064, end_ms: 1164 }));
+        assert_eq!(segment.update(false, true, &frame(2000), &padding), None);
+    }
+    
+    #[tokio::test]
+    async fn test_reset_clears_recurrent_state() {
+        let model_path = "models/vad/silero/silero_vad.onnx";
+        if !Path::new(model_path).exists() {
+            eprintln!("⚠️  Skipping test: model file not found at {}", model_path);
+            return;
+        }
+        
+        for config in [SileroVadConfig::default(), SileroVadConfig::telephony(model_path)] {
+            let vad = SileroVad::with_config(SileroVadConfig { model_path: model_path.to_string(), ..config.clone() }).unwrap();
+            let chunk: Vec<f32> = (0..config.frame_size)
+                .map(|i| (2.0 * std::f32::consts::PI * 220.0 * i as f32 / config.sample_rate as f32).sin() * 0.5)
+                .collect();
+            
+            let first = vad.detect_voice_activity(&chunk).unwrap();
+            for _ in 0..5 {
+                vad.detect_voice_activity(&chunk).unwrap();
+            }
+            assert!(vad.recurrent_state.lock().unwrap().is_some());
+            
+            // reset 之后从零状态开始，相同输入得到相同结果
+            vad.reset().await.unwrap();
+            assert!(vad.recurrent_state.lock().unwrap().is_none());
+            let after_reset = vad.detect_voice_activity(&chunk).unwrap();
+            assert!((first - after_reset).abs() < 1e-6, "{}Hz: {} vs {}", config.sample_rate, first, after_reset);
+            
+            // 独立会话不共享状态
+            let forked = vad.fork_for_session().unwrap();
+            let forked_result = forked.as_silero().unwrap().detect_voice_activity(&chunk).unwrap();
+            assert!((first - forked_result).abs() < 1e-6);
+        }
+    }
+    
     /// 创建测试用的语音音频帧
     fn create_speech_frame(timestamp_ms: u64) -> AudioFrame {
         // 创建 512 样本的音频帧（32ms @ 16kHz）
@@ -9,16 +9,20 @@ use tokio::sync::RwLock;
 
 use crate::error::EngineResult;
 use crate::types::AudioFrame;
-use crate::vad::{DetectionOutcome, VoiceActivityDetector, BoundaryType};
+use crate::vad::{DetectionOutcome, VoiceActivityDetector, BoundaryType, SpeechEvent};
 
 /// 基于时间的 VAD
 /// 
 /// 在固定时间间隔（例如每 3 秒）检测一次边界，不考虑实际的语音活动。
+/// 只使用帧的时间戳，因此接受任意采样率和声道数的帧（原样返回）。
+/// 所有音频都视为语音：每个片段的第一帧发出 Start，边界帧发出 End（不做 pre-roll/post-roll 填充）。
 pub struct TimeBasedVad {
     /// 片段时长（毫秒）
     segment_duration_ms: u64,
     /// 上一个边界的时间戳（毫秒）
     last_boundary_time: Arc<RwLock<u64>>,
+    /// 当前片段是否已经发出 Start
+    segment_active: Arc<RwLock<bool>>,
 }
 
 impl TimeBasedVad {
@@ -31,6 +35,7 @@ impl TimeBasedVad {
             segment_duration_ms,
             // 使用 u64::MAX 作为未初始化标记，避免与时间戳 0 冲突
             last_boundary_time: Arc::new(RwLock::new(u64::MAX)),
+            segment_active: Arc::new(RwLock::new(false)),
         }
     }
     
@@ -45,15 +50,22 @@ impl TimeBasedVad {
 impl VoiceActivityDetector for TimeBasedVad {
     async fn detect(&self, frame: AudioFrame) -> EngineResult<DetectionOutcome> {
         let mut last = self.last_boundary_time.write().await;
+        let mut segment_active = self.segment_active.write().await;
         
         // 如果是第一帧（last == u64::MAX 表示未初始化），初始化边界时间
         if *last == u64::MAX {
             *last = frame.timestamp_ms;
+            *segment_active = true;
+            let speech_event = Some(SpeechEvent::Start {
+                onset_ms: frame.timestamp_ms,
+                start_ms: frame.timestamp_ms,
+            });
             return Ok(DetectionOutcome {
                 is_boundary: false,
                 confidence: 1.0,
                 frame,
                 boundary_type: None,
+                speech_event,
             });
         }
         
@@ -68,6 +80,23 @@ impl VoiceActivityDetector for TimeBasedVad {
             *last = frame.timestamp_ms;
         }
         
+        // 片段的第一帧发出 Start，边界帧（属于当前片段）发出 End
+        let speech_event = if is_boundary {
+            *segment_active = false;
+            Some(SpeechEvent::End {
+                offset_ms: frame.end_timestamp_ms(),
+                end_ms: frame.end_timestamp_ms(),
+            })
+        } else if !*segment_active {
+            *segment_active = true;
+            Some(SpeechEvent::Start {
+                onset_ms: frame.timestamp_ms,
+                start_ms: frame.timestamp_ms,
+            })
+        } else {
+            None
+        };
+        
         Ok(DetectionOutcome {
             is_boundary,
             confidence: 1.0,
@@ -77,6 +106,7 @@ impl VoiceActivityDetector for TimeBasedVad {
             } else {
                 None
             },
+            speech_event,
         })
     }
     
@@ -84,12 +114,17 @@ impl VoiceActivityDetector for TimeBasedVad {
         let mut last = self.last_boundary_time.write().await;
         // 重置为未初始化状态
         *last = u64::MAX;
+        *self.segment_active.write().await = false;
         Ok(())
     }
     
     fn get_info(&self) -> String {
         format!("TimeBasedVad(segment_duration={}ms)", self.segment_duration_ms)
     }
+    
+    fn fork_for_session(&self) -> Option<Arc<dyn VoiceActivityDetector>> {
+        Some(Arc::new(Self::new(self.segment_duration_ms)))
+    }
 }
 
 #[cfg(test)]
@@ -136,6 +171,24 @@ mod tests {
         assert!(result.is_boundary, "应该在6000ms时检测到边界（从3000ms开始经过3000ms）");
     }
     
+    #[tokio::test]
+    async fn test_speech_events_per_segment() {
+        let vad = TimeBasedVad::new(1000);
+        let mut events = Vec::new();
+        for timestamp_ms in (0..=2000).step_by(500) {
+            if let Some(event) = vad.detect(create_test_frame(timestamp_ms)).await.unwrap().speech_event {
+                events.push(event);
+            }
+        }
+        // 512 samples @ 16kHz = 32ms，边界帧属于当前片段
+        assert_eq!(events, vec![
+            SpeechEvent::Start { onset_ms: 0, start_ms: 0 },
+            SpeechEvent::End { offset_ms: 1032, end_ms: 1032 },
+            SpeechEvent::Start { onset_ms: 1500, start_ms: 1500 },
+            SpeechEvent::End { offset_ms: 2032, end_ms: 2032 },
+        ]);
+    }
+    
     #[tokio::test]
     async fn test_reset() {
         let vad = TimeBasedVad::new(3000);
@@ -13,6 +13,8 @@ mod tests {
             sample_rate: 16000,
             frame_size: 512,
             silence_threshold: 0.5,
+            speech_onset_threshold: 0.5,
+            probability_smoothing: 0.0,
             min_silence_duration_ms: 400,
             adaptive_enabled: true,
             adaptive_min_samples: 1,
@@ -24,6 +26,7 @@ mod tests {
             final_threshold_min_ms: 400,
             final_threshold_max_ms: 800,
             min_utterance_ms: 1000,
+            speech_padding: Default::default(),
         }
     }
     
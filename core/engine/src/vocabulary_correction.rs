@@ -0,0 +1,281 @@
+//! 词表纠错模块
+//!
+//! 用会话词表（产品名、专业术语等）纠正 ASR 识别文本（源语言侧，在翻译之前）。
+//! Whisper 经常把领域词听成读音相近的词，这里按读音和拼写做模糊匹配并替换：
+//! - 中文字符按拼音比较（需要拼音表，没有拼音的字按字本身比较）
+//! - 拉丁字母单词按小写拼写比较
+//! - 比较时去掉单元之间的空格，因此也能纠正跨文字的误识别（例如 "灵瓜" → "Lingua"）
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::error::{EngineError, EngineResult};
+
+/// 默认允许的最大编辑距离比例（编辑距离 / 读音长度）
+pub const DEFAULT_MAX_EDIT_RATIO: f32 = 0.34;
+
+/// 读音长度小于该值时只纠正同音字（读音完全相同的中文），避免误改 "it" → "IT" 之类的短词
+const MIN_FUZZY_KEY_CHARS: usize = 5;
+
+/// 匹配窗口最多包含的单元数
+const MAX_WINDOW_UNITS: usize = 8;
+
+/// 汉字 → 拼音（不带声调）
+#[derive(Debug, Clone, Default)]
+pub struct PinyinTable {
+    map: HashMap<char, String>,
+}
+
+impl PinyinTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 从词典文件加载拼音表
+    ///
+    /// 支持两种格式（每行一个汉字，多音字取第一个读音，`#` 开头的行是注释）：
+    /// - `汉字 拼音`（例如 `好 hao3`，随引擎发布的 `config/pinyin_lexicon.txt` 使用这种格式）
+    /// - `汉字 声母 韵母 音调`（`^` 表示零声母）
+    pub fn from_lexicon(path: &Path) -> EngineResult<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| EngineError::new(format!("Failed to read pinyin lexicon {}: {}", path.display(), e)))?;
+
+        let mut table = Self::new();
+        for line in content.lines().filter(|line| !line.starts_with('#')) {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let mut chars = parts.first().map(|word| word.chars()).into_iter().flatten();
+            let (Some(ch), None) = (chars.next(), chars.next()) else {
+                continue;
+            };
+            let pinyin = match parts.as_slice() {
+                [_, initial, final_part, ..] if !final_part.starts_with('#') => {
+                    format!("{}{}", initial.trim_start_matches('^'), final_part)
+                }
+                [_, syllable, ..] => syllable.to_string(),
+                _ => continue,
+            };
+            let pinyin: String = pinyin.chars()
+                .filter(|c| c.is_ascii_alphabetic())
+                .map(|c| c.to_ascii_lowercase())
+                .collect();
+            if !pinyin.is_empty() {
+                table.map.entry(ch).or_insert(pinyin);
+            }
+        }
+        Ok(table)
+    }
+
+    /// 添加一个汉字的拼音（已存在时覆盖）
+    pub fn insert(&mut self, ch: char, pinyin: impl Into<String>) {
+        self.map.insert(ch, pinyin.into().to_lowercase());
+    }
+
+    pub fn get(&self, ch: char) -> Option<&str> {
+        self.map.get(&ch).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+/// 文本中的一个识别单元（一个汉字或一个拉丁字母单词）
+#[derive(Debug, Clone)]
+struct Unit {
+    /// 读音（汉字为拼音，单词为小写拼写）
+    key: String,
+    /// 在原文中的字节范围
+    start: usize,
+    end: usize,
+    /// 与前一个单元之间只有空白（没有标点）
+    joined_to_prev: bool,
+    /// 是否为汉字
+    cjk: bool,
+}
+
+/// 是否为中日韩文字
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32, 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF)
+}
+
+/// 编辑距离（Levenshtein）
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// 词表纠错器
+#[derive(Debug, Clone)]
+pub struct VocabularyCorrector {
+    pinyin: PinyinTable,
+    max_edit_ratio: f32,
+}
+
+impl Default for VocabularyCorrector {
+    fn default() -> Self {
+        Self::new(PinyinTable::new(), DEFAULT_MAX_EDIT_RATIO)
+    }
+}
+
+impl VocabularyCorrector {
+    /// 创建纠错器
+    ///
+    /// # Arguments
+    /// * `pinyin` - 拼音表（为空时汉字只按字本身比较，无法识别同音字）
+    /// * `max_edit_ratio` - 允许的最大编辑距离比例（0.0 表示只纠正读音完全相同的词）
+    pub fn new(pinyin: PinyinTable, max_edit_ratio: f32) -> Self {
+        Self {
+            pinyin,
+            max_edit_ratio: max_edit_ratio.max(0.0),
+        }
+    }
+
+    /// 把文本切分成识别单元
+    fn units(&self, text: &str) -> Vec<Unit> {
+        let mut units: Vec<Unit> = Vec::new();
+        // 当前单词是否还可以继续追加；上一个单元之后是否只出现过空白
+        let mut open = false;
+        let mut joined = true;
+        for (i, c) in text.char_indices() {
+            let end = i + c.len_utf8();
+            if is_cjk(c) {
+                let key = self.pinyin.get(c).map(str::to_string).unwrap_or_else(|| c.to_string());
+                units.push(Unit { key, start: i, end, joined_to_prev: joined, cjk: true });
+                open = false;
+                joined = true;
+            } else if c.is_alphanumeric() {
+                if open {
+                    let unit = units.last_mut().expect("open word exists");
+                    unit.key.extend(c.to_lowercase());
+                    unit.end = end;
+                } else {
+                    units.push(Unit { key: c.to_lowercase().collect(), start: i, end, joined_to_prev: joined, cjk: false });
+                    open = true;
+                    joined = true;
+                }
+            } else {
+                open = false;
+                if !c.is_whitespace() {
+                    joined = false;
+                }
+            }
+        }
+        units
+    }
+
+    /// 读音（各单元读音直接拼接，不含空格）
+    fn key_of(units: &[Unit]) -> Vec<char> {
+        units.iter().flat_map(|unit| unit.key.chars()).collect()
+    }
+
+    /// 用词表纠正文本
+    ///
+    /// 先找出所有与某个词读音足够接近的窗口（不跨标点），再按编辑距离比例从小到大（相同时取较长的窗口）
+    /// 选出互不重叠的窗口，替换为词表中的写法；已经与词表写法完全相同的部分保持不变
+    pub fn correct(&self, text: &str, vocabulary: &[String]) -> String {
+        if vocabulary.is_empty() || text.is_empty() {
+            return text.to_string();
+        }
+
+        let terms: Vec<(&str, Vec<char>)> = vocabulary.iter()
+            .map(|term| term.trim())
+            .filter(|term| !term.is_empty())
+            .map(|term| (term, Self::key_of(&self.units(term))))
+            .filter(|(_, key)| !key.is_empty())
+            .collect();
+        let units = self.units(text);
+
+        // (编辑距离比例, 起始单元, 窗口单元数, 词)
+        let mut candidates: Vec<(f32, usize, usize, &str)> = Vec::new();
+        for i in 0..units.len() {
+            for (term, term_key) in &terms {
+                for len in 1..=MAX_WINDOW_UNITS.min(units.len() - i) {
+                    let window = &units[i..i + len];
+                    if window.iter().skip(1).any(|unit| !unit.joined_to_prev) {
+                        break;
+                    }
+                    let window_key = Self::key_of(window);
+                    if window_key.len() > term_key.len() * 2 {
+                        break;
+                    }
+                    let distance = edit_distance(&window_key, term_key);
+                    let ratio = distance as f32 / window_key.len().max(term_key.len()) as f32;
+                    let accepted = if term_key.len() >= MIN_FUZZY_KEY_CHARS {
+                        ratio <= self.max_edit_ratio
+                    } else {
+                        distance == 0 && window.iter().any(|unit| unit.cjk)
+                    };
+                    if accepted {
+                        candidates.push((ratio, i, len, term));
+                    }
+                }
+            }
+        }
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0).then(b.2.cmp(&a.2)).then(a.1.cmp(&b.1)));
+
+        let mut taken = vec![false; units.len()];
+        let mut selected: Vec<(usize, usize, &str)> = Vec::new();
+        for (_, i, len, term) in candidates {
+            if taken[i..i + len].iter().any(|&t| t) {
+                continue;
+            }
+            taken[i..i + len].iter_mut().for_each(|t| *t = true);
+            selected.push((i, len, term));
+        }
+        selected.sort_by_key(|&(i, _, _)| i);
+
+        let mut result = String::with_capacity(text.len());
+        let mut copied_until = 0;
+        for (i, len, term) in selected {
+            let start = units[i].start;
+            let end = units[i + len - 1].end;
+            let original = &text[start..end];
+            result.push_str(&text[copied_until..start]);
+            result.push_str(term);
+            if original != term {
+                eprintln!("[Vocabulary] 🔤 Corrected \"{}\" → \"{}\"", original, term);
+            }
+            copied_until = end;
+        }
+        result.push_str(&text[copied_until..]);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        let chars = |s: &str| s.chars().collect::<Vec<_>>();
+        assert_eq!(edit_distance(&chars("kitten"), &chars("sitting")), 3);
+        assert_eq!(edit_distance(&chars(""), &chars("abc")), 3);
+        assert_eq!(edit_distance(&chars("lingua"), &chars("lingua")), 0);
+    }
+
+    #[test]
+    fn test_units_track_punctuation_breaks() {
+        let corrector = VocabularyCorrector::default();
+        let units = corrector.units("Hello world, 你好");
+        let keys: Vec<&str> = units.iter().map(|unit| unit.key.as_str()).collect();
+        assert_eq!(keys, vec!["hello", "world", "你", "好"]);
+        assert!(units[1].joined_to_prev);
+        assert!(!units[2].joined_to_prev);
+        assert!(units[3].joined_to_prev);
+    }
+}
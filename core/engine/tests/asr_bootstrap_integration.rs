@@ -38,6 +38,7 @@ impl VoiceActivityDetector for DummyVad {
             is_boundary: true,
             confidence: 1.0,
             frame,
+            speech_event: None,
         })
     }
 }
@@ -95,6 +96,7 @@ impl TtsStreaming for DummyTts {
     async fn synthesize(&self, _request: TtsRequest) -> EngineResult<TtsStreamChunk> {
         Ok(TtsStreamChunk {
             audio: vec![],
+            format: TtsAudioFormat::pcm16(16000, 1),
             timestamp_ms: 0,
             is_last: true,
         })
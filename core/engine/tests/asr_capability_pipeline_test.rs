@@ -0,0 +1,527 @@
+//! 自定义 ASR 实现的完整业务流程测试
+//!
+//! 验证通过 `CoreEngineBuilder::asr` 注入的任意 `AsrStreaming` 实现都能安全地跑通
+//! VAD → ASR → NMT → TTS 流程：
+//! - 只实现 `AsrStreaming` 的 ASR 走 `infer` 路径
+//! - 通过 `as_ext()` 暴露 `AsrStreamingExt` 的 ASR 走帧累积 + 边界推理路径
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use core_engine::asr_streaming::AsrStreamingExt;
+use core_engine::event_bus::{ChannelEventBus, EventTopic};
+use core_engine::vad::{BoundaryType, SpeechPadding};
+use core_engine::*;
+
+const FRAME_MS: u64 = 100;
+const FRAMES_PER_UTTERANCE: u64 = 3;
+
+/// 每 3 帧报告一次边界的 VAD
+struct EveryThirdFrameVad;
+
+#[async_trait]
+impl VoiceActivityDetector for EveryThirdFrameVad {
+    async fn detect(&self, frame: AudioFrame) -> EngineResult<DetectionOutcome> {
+        let index = frame.timestamp_ms / FRAME_MS;
+        let is_boundary = index % FRAMES_PER_UTTERANCE == FRAMES_PER_UTTERANCE - 1;
+        Ok(DetectionOutcome {
+            is_boundary,
+            confidence: 1.0,
+            frame,
+            boundary_type: if is_boundary { Some(BoundaryType::NaturalPause) } else { None },
+            speech_event: None,
+        })
+    }
+}
+
+/// 前 3 帧为静音，第 4 帧开始说话，第 6 帧为边界的 VAD（pre-roll 一帧，无 post-roll）
+struct ScriptedSpeechVad;
+
+#[async_trait]
+impl VoiceActivityDetector for ScriptedSpeechVad {
+    async fn detect(&self, frame: AudioFrame) -> EngineResult<DetectionOutcome> {
+        let padding = self.speech_padding();
+        let is_boundary = frame.timestamp_ms == 5 * FRAME_MS;
+        let speech_event = match frame.timestamp_ms / FRAME_MS {
+            3 => Some(padding.start(frame.timestamp_ms)),
+            5 => Some(padding.end(frame.timestamp_ms)),
+            _ => None,
+        };
+        Ok(DetectionOutcome {
+            is_boundary,
+            confidence: 1.0,
+            frame,
+            boundary_type: if is_boundary { Some(BoundaryType::NaturalPause) } else { None },
+            speech_event,
+        })
+    }
+
+    fn speech_padding(&self) -> SpeechPadding {
+        SpeechPadding::new(FRAME_MS, 0)
+    }
+}
+
+fn final_transcript(language: &str) -> StableTranscript {
+    StableTranscript {
+        text: "hello world".to_string(),
+        speaker_id: None,
+        language: language.to_string(),
+        segments: vec![TranscriptSegment {
+            text: "hello world".to_string(),
+            start_ms: 0,
+            end_ms: 300,
+            no_speech_probability: Some(0.01),
+            avg_log_probability: None,
+            tokens: vec![
+                TranscriptToken { text: " hello".to_string(), start_ms: 0, end_ms: 140, probability: 0.98 },
+                TranscriptToken { text: " world".to_string(), start_ms: 140, end_ms: 300, probability: 0.91 },
+            ],
+        }],
+        language_probabilities: Vec::new(),
+    }
+}
+
+/// 只实现 `AsrStreaming` 的 ASR（不提供扩展能力）
+struct PlainMockAsr {
+    infer_calls: Mutex<usize>,
+}
+
+#[async_trait]
+impl AsrStreaming for PlainMockAsr {
+    async fn initialize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn infer(&self, request: AsrRequest) -> EngineResult<AsrResult> {
+        *self.infer_calls.lock().unwrap() += 1;
+        Ok(AsrResult {
+            partial: None,
+            final_transcript: Some(final_transcript(
+                request.language_hint.as_deref().unwrap_or("en"),
+            )),
+        })
+    }
+
+    async fn finalize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+/// 通过 `as_ext()` 提供帧累积和边界推理能力的 ASR
+struct ExtMockAsr {
+    buffer: Mutex<Vec<AudioFrame>>,
+    language: Mutex<Option<String>>,
+    boundary_frame_counts: Mutex<Vec<usize>>,
+    hotwords: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl AsrStreaming for ExtMockAsr {
+    async fn initialize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn infer(&self, _request: AsrRequest) -> EngineResult<AsrResult> {
+        Err(EngineError::new("ExtMockAsr only supports boundary inference"))
+    }
+
+    async fn finalize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    fn as_ext(&self) -> Option<&dyn AsrStreamingExt> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl AsrStreamingExt for ExtMockAsr {
+    fn accumulate_frame(&self, frame: AudioFrame) -> EngineResult<()> {
+        self.buffer.lock().unwrap().push(frame);
+        Ok(())
+    }
+
+    fn get_accumulated_frames(&self) -> EngineResult<Vec<AudioFrame>> {
+        Ok(self.buffer.lock().unwrap().clone())
+    }
+
+    fn clear_buffer(&self) -> EngineResult<()> {
+        self.buffer.lock().unwrap().clear();
+        Ok(())
+    }
+
+    fn set_language(&self, language: Option<String>) -> EngineResult<()> {
+        *self.language.lock().unwrap() = language;
+        Ok(())
+    }
+
+    fn get_language(&self) -> EngineResult<Option<String>> {
+        Ok(self.language.lock().unwrap().clone())
+    }
+
+    fn set_hotwords(&self, hotwords: Vec<String>) -> EngineResult<()> {
+        *self.hotwords.lock().unwrap() = hotwords;
+        Ok(())
+    }
+
+    async fn infer_on_boundary(&self) -> EngineResult<AsrResult> {
+        let frame_count = std::mem::take(&mut *self.buffer.lock().unwrap()).len();
+        self.boundary_frame_counts.lock().unwrap().push(frame_count);
+        let language = self.language.lock().unwrap().clone().unwrap_or_else(|| "en".to_string());
+        Ok(AsrResult {
+            partial: None,
+            final_transcript: Some(final_transcript(&language)),
+        })
+    }
+}
+
+/// 按脚本依次"检测"出指定语言的 ASR（模拟自动语种识别），并记录引擎设置的语言和候选范围
+struct DetectingMockAsr {
+    detected: Mutex<Vec<&'static str>>,
+    language: Mutex<Option<String>>,
+    candidates: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl AsrStreaming for DetectingMockAsr {
+    async fn initialize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn infer(&self, _request: AsrRequest) -> EngineResult<AsrResult> {
+        Err(EngineError::new("DetectingMockAsr only supports boundary inference"))
+    }
+
+    async fn finalize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    fn as_ext(&self) -> Option<&dyn AsrStreamingExt> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl AsrStreamingExt for DetectingMockAsr {
+    fn accumulate_frame(&self, _frame: AudioFrame) -> EngineResult<()> {
+        Ok(())
+    }
+
+    fn get_accumulated_frames(&self) -> EngineResult<Vec<AudioFrame>> {
+        Ok(Vec::new())
+    }
+
+    fn clear_buffer(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    fn set_language(&self, language: Option<String>) -> EngineResult<()> {
+        *self.language.lock().unwrap() = language;
+        Ok(())
+    }
+
+    fn get_language(&self) -> EngineResult<Option<String>> {
+        Ok(self.language.lock().unwrap().clone())
+    }
+
+    fn set_language_candidates(&self, candidates: Vec<String>) -> EngineResult<()> {
+        *self.candidates.lock().unwrap() = candidates;
+        Ok(())
+    }
+
+    async fn infer_on_boundary(&self) -> EngineResult<AsrResult> {
+        let language = self.detected.lock().unwrap().remove(0);
+        let mut transcript = final_transcript(language);
+        transcript.language_probabilities = vec![
+            LanguageProbability { language: language.to_string(), probability: 0.9 },
+        ];
+        Ok(AsrResult {
+            partial: None,
+            final_transcript: Some(transcript),
+        })
+    }
+}
+
+struct TaggingNmt;
+
+#[async_trait]
+impl NmtIncremental for TaggingNmt {
+    async fn initialize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn translate(&self, request: TranslationRequest) -> EngineResult<TranslationResponse> {
+        Ok(TranslationResponse {
+            translated_text: format!("[{}] {}", request.target_language, request.transcript.text),
+            is_stable: true,
+            speaker_id: request.speaker_id,
+            source_text: Some(request.transcript.text),
+            source_audio_duration_ms: None,
+            source_language: None,
+            quality_metrics: None,
+            committed_text: None,
+            provider: None,
+        })
+    }
+
+    async fn finalize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+struct DummyConfig;
+
+#[async_trait]
+impl ConfigManager for DummyConfig {
+    async fn load(&self) -> EngineResult<EngineConfig> {
+        Ok(EngineConfig {
+            mode: "fast".to_string(),
+            source_language: "en".to_string(),
+            target_language: "zh".to_string(),
+        })
+    }
+
+    async fn current(&self) -> EngineResult<EngineConfig> {
+        self.load().await
+    }
+}
+
+struct DummyCache;
+
+#[async_trait]
+impl CacheManager for DummyCache {
+    async fn warm_up(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn purge(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+struct DummyTelemetry;
+
+#[async_trait]
+impl TelemetrySink for DummyTelemetry {
+    async fn record(&self, _datum: TelemetryDatum) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+fn builder_with_asr(asr: Arc<dyn AsrStreaming>) -> CoreEngineBuilder {
+    builder_with_asr_and_bus(asr, Arc::new(ChannelEventBus::new()))
+}
+
+fn builder_with_asr_and_bus(asr: Arc<dyn AsrStreaming>, event_bus: Arc<ChannelEventBus>) -> CoreEngineBuilder {
+    CoreEngineBuilder::new()
+        .event_bus(event_bus)
+        .vad(Arc::new(EveryThirdFrameVad))
+        .asr(asr)
+        .nmt(Arc::new(TaggingNmt))
+        .emotion(Arc::new(EmotionStub::new()))
+        .persona(Arc::new(PersonaStub::new()))
+        .tts(Arc::new(TtsStub::new()))
+        .config(Arc::new(DummyConfig))
+        .cache(Arc::new(DummyCache))
+        .telemetry(Arc::new(DummyTelemetry))
+}
+
+fn speech_frame(index: u64) -> AudioFrame {
+    AudioFrame {
+        sample_rate: 16000,
+        channels: 1,
+        data: vec![0.1; (16000 * FRAME_MS / 1000) as usize],
+        timestamp_ms: index * FRAME_MS,
+    }
+}
+
+/// 送入一句话的所有帧，返回边界帧的处理结果
+async fn feed_utterance(engine: &CoreEngine, session: &Session) -> Option<ProcessResult> {
+    let mut last = None;
+    for index in 0..FRAMES_PER_UTTERANCE {
+        last = engine
+            .process_audio_frame(session, speech_frame(index))
+            .await
+            .expect("process_audio_frame should succeed");
+    }
+    last
+}
+
+#[tokio::test]
+async fn test_plain_asr_runs_full_pipeline() {
+    let asr = Arc::new(PlainMockAsr {
+        infer_calls: Mutex::new(0),
+    });
+    let engine = builder_with_asr(asr.clone()).build().expect("builder should succeed");
+    engine.boot().await.expect("boot should succeed");
+
+    let session = engine.create_session(Some("en".to_string()), Some("zh".to_string())).await;
+    let result = feed_utterance(&engine, &session).await.expect("boundary should produce a result");
+
+    assert_eq!(result.asr.final_transcript.unwrap().text, "hello world");
+    assert_eq!(result.translation.unwrap().translated_text, "[zh] hello world");
+    assert!(*asr.infer_calls.lock().unwrap() > 0);
+}
+
+#[tokio::test]
+async fn test_ext_asr_uses_boundary_inference() {
+    let asr = Arc::new(ExtMockAsr {
+        buffer: Mutex::new(Vec::new()),
+        language: Mutex::new(None),
+        boundary_frame_counts: Mutex::new(Vec::new()),
+        hotwords: Mutex::new(Vec::new()),
+    });
+    let engine = builder_with_asr(asr.clone()).build().expect("builder should succeed");
+    engine.boot().await.expect("boot should succeed");
+
+    let session = engine.create_session(Some("en-US".to_string()), Some("zh".to_string())).await;
+    let result = feed_utterance(&engine, &session).await.expect("boundary should produce a result");
+
+    assert_eq!(result.asr.final_transcript.unwrap().language, "en");
+    assert_eq!(result.translation.unwrap().translated_text, "[zh] hello world");
+    // 边界推理应看到本句累积的全部帧
+    assert_eq!(*asr.boundary_frame_counts.lock().unwrap(), vec![FRAMES_PER_UTTERANCE as usize]);
+    assert_eq!(asr.get_language().unwrap(), Some("en".to_string()));
+}
+
+#[tokio::test]
+async fn test_plain_asr_in_continuous_mode_with_language_hint() {
+    let asr = Arc::new(PlainMockAsr {
+        infer_calls: Mutex::new(0),
+    });
+    let engine = builder_with_asr(asr.clone())
+        .with_continuous_mode(true, 5000, 200)
+        .build()
+        .expect("builder should succeed");
+    engine.boot().await.expect("boot should succeed");
+
+    let session = engine.create_session(Some("zh".to_string()), Some("en".to_string())).await;
+    let result = feed_utterance(&engine, &session).await.expect("boundary should produce a result");
+
+    assert_eq!(result.asr.final_transcript.unwrap().language, "zh");
+    assert_eq!(result.translation.unwrap().translated_text, "[en] hello world");
+    assert_eq!(*asr.infer_calls.lock().unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_asr_final_event_carries_timestamps() {
+    let asr = Arc::new(ExtMockAsr {
+        buffer: Mutex::new(Vec::new()),
+        language: Mutex::new(None),
+        boundary_frame_counts: Mutex::new(Vec::new()),
+        hotwords: Mutex::new(Vec::new()),
+    });
+    let event_bus = Arc::new(ChannelEventBus::new());
+    let mut asr_finals = event_bus.subscribe_receiver(EventTopic("AsrFinal".to_string()));
+    // 订阅在后台任务中注册
+    tokio::task::yield_now().await;
+
+    let engine = builder_with_asr_and_bus(asr, event_bus).build().expect("builder should succeed");
+    engine.boot().await.expect("boot should succeed");
+
+    let session = engine.create_session(Some("en".to_string()), Some("zh".to_string())).await;
+    let result = feed_utterance(&engine, &session).await.expect("boundary should produce a result");
+
+    let segments = result.asr.final_transcript.unwrap().segments;
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].tokens[1].start_ms, 140);
+
+    let event = asr_finals.recv().await.expect("AsrFinal event should be published");
+    assert_eq!(event.payload["session_id"], session.id());
+    let segment = &event.payload["segments"][0];
+    assert_eq!(segment["end_ms"], 300);
+    assert_eq!(segment["tokens"][0]["text"], " hello");
+    assert_eq!(segment["tokens"][1]["end_ms"], 300);
+    let probability = segment["tokens"][1]["probability"].as_f64().unwrap();
+    assert!((probability - 0.91).abs() < 1e-6);
+}
+
+#[tokio::test]
+async fn test_bilingual_session_picks_direction_per_utterance() {
+    let asr = Arc::new(DetectingMockAsr {
+        detected: Mutex::new(vec!["zh", "en", "zh"]),
+        language: Mutex::new(Some("ja".to_string())),
+        candidates: Mutex::new(Vec::new()),
+    });
+    let engine = builder_with_asr(asr.clone()).build().expect("builder should succeed");
+    engine.boot().await.expect("boot should succeed");
+
+    let session = engine.create_session(Some("auto".to_string()), Some("en".to_string())).await;
+    assert_eq!(session.source_language(), None);
+    session.set_bilingual_languages(Some(("zh-CN".to_string(), "en".to_string())));
+
+    let mut targets = Vec::new();
+    for _ in 0..3 {
+        let result = feed_utterance(&engine, &session).await.expect("boundary should produce a result");
+        let transcript = result.asr.final_transcript.unwrap();
+        assert_eq!(transcript.language_probabilities[0].language, transcript.language);
+        targets.push(result.translation.unwrap().translated_text);
+    }
+    assert_eq!(targets, vec!["[en] hello world", "[zh-CN] hello world", "[en] hello world"]);
+    assert_eq!(session.detected_language().as_deref(), Some("zh"));
+
+    // 双语会话由 ASR 自动检测语言，且只在这两种语言之间选择
+    assert_eq!(asr.get_language().unwrap(), None);
+    assert_eq!(*asr.candidates.lock().unwrap(), vec!["zh-CN".to_string(), "en".to_string()]);
+}
+
+#[tokio::test]
+async fn test_session_vocabulary_biases_asr_and_corrects_source_text() {
+    let asr = Arc::new(ExtMockAsr {
+        buffer: Mutex::new(Vec::new()),
+        language: Mutex::new(None),
+        boundary_frame_counts: Mutex::new(Vec::new()),
+        hotwords: Mutex::new(Vec::new()),
+    });
+    let engine = builder_with_asr(asr.clone()).build().expect("builder should succeed");
+    engine.boot().await.expect("boot should succeed");
+
+    let session = engine.create_session(Some("en".to_string()), Some("zh".to_string())).await;
+    session.set_vocabulary(vec![" HelloWorld ".to_string(), "HelloWorld".to_string(), String::new()]);
+    assert_eq!(session.vocabulary(), vec!["HelloWorld".to_string()]);
+
+    let result = feed_utterance(&engine, &session).await.expect("boundary should produce a result");
+
+    // 词表作为热词传给 ASR，识别结果在翻译之前按词表纠正
+    assert_eq!(*asr.hotwords.lock().unwrap(), vec!["HelloWorld".to_string()]);
+    assert_eq!(result.asr.final_transcript.unwrap().text, "HelloWorld");
+    assert_eq!(result.translation.unwrap().translated_text, "[zh] HelloWorld");
+}
+
+#[tokio::test]
+async fn test_vad_speech_events_trim_segment_to_pre_roll() {
+    let asr = Arc::new(ExtMockAsr {
+        buffer: Mutex::new(Vec::new()),
+        language: Mutex::new(None),
+        boundary_frame_counts: Mutex::new(Vec::new()),
+        hotwords: Mutex::new(Vec::new()),
+    });
+    let event_bus = Arc::new(ChannelEventBus::new());
+    let mut speech_starts = event_bus.subscribe_receiver(EventTopic("VadSpeechStart".to_string()));
+    let mut speech_ends = event_bus.subscribe_receiver(EventTopic("VadSpeechEnd".to_string()));
+    tokio::task::yield_now().await;
+
+    let engine = builder_with_asr_and_bus(asr.clone(), event_bus)
+        .vad(Arc::new(ScriptedSpeechVad))
+        .build()
+        .expect("builder should succeed");
+    engine.boot().await.expect("boot should succeed");
+
+    let session = engine.create_session(Some("en".to_string()), Some("zh".to_string())).await;
+    let mut last = None;
+    for index in 0..6 {
+        last = engine.process_audio_frame(&session, speech_frame(index)).await.expect("process_audio_frame should succeed");
+    }
+    assert!(last.is_some(), "boundary should produce a result");
+
+    // 只把 pre-roll（第 3 帧）和语音帧（第 4、5 帧）交给 ASR，边界帧留给下一句
+    assert_eq!(*asr.boundary_frame_counts.lock().unwrap(), vec![3]);
+
+    let start = speech_starts.recv().await.expect("VadSpeechStart should be published");
+    assert_eq!(start.payload["session_id"], session.id());
+    assert_eq!(start.payload["onset_ms"], 300);
+    assert_eq!(start.payload["start_ms"], 200);
+    let end = speech_ends.recv().await.expect("VadSpeechEnd should be published");
+    assert_eq!(end.payload["end_ms"], 500);
+    assert_eq!(end.timestamp_ms, 500);
+}
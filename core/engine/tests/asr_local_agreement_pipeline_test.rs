@@ -0,0 +1,395 @@
+//! LocalAgreement 流式识别流程测试（CoreEngine 级别）
+//!
+//! 验证：
+//! - 共享的 ASR 实例在每次推理前恢复会话的流式状态、推理后取回，
+//!   部分结果的已提交前缀随着说话逐步增长，不会因为每次重新加载帧而被重置
+//! - 两个会话交替输入时，各自的 LocalAgreement 历史互不干扰
+//! - 向 ASR 加载会话失败时，会话的 LocalAgreement 历史不会丢失
+//! - `[asr_streaming]` 配置可以被解析，不支持流式的 ASR 拒绝非 off 模式
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use core_engine::asr_streaming::{AsrSessionState, AsrStreamingConfig, AsrStreamingExt, AsrStreamingMode};
+use core_engine::asr_whisper::{LocalAgreement, TimedWord};
+use core_engine::event_bus::ChannelEventBus;
+use core_engine::vad::BoundaryType;
+use core_engine::*;
+
+const FRAME_MS: u64 = 100;
+const FRAMES_PER_UTTERANCE: u64 = 6;
+
+/// 每 6 帧报告一次边界的 VAD
+struct EverySixthFrameVad;
+
+#[async_trait]
+impl VoiceActivityDetector for EverySixthFrameVad {
+    async fn detect(&self, frame: AudioFrame) -> EngineResult<DetectionOutcome> {
+        let index = frame.timestamp_ms / FRAME_MS;
+        let is_boundary = index % FRAMES_PER_UTTERANCE == FRAMES_PER_UTTERANCE - 1;
+        Ok(DetectionOutcome {
+            is_boundary,
+            confidence: 1.0,
+            frame,
+            boundary_type: if is_boundary { Some(BoundaryType::NaturalPause) } else { None },
+            speech_event: None,
+        })
+    }
+}
+
+/// 用 LocalAgreement 提交假设的流式 ASR
+///
+/// 第 n 帧的假设为 "<lang>1 ... <lang>(n-1) x<n>"：最后一个词每次都会变化（不稳定的尾部），
+/// 单词前缀为会话设置的语言，用于区分不同会话的假设。
+/// 与 `WhisperAsrStreaming` 一样，`clear_buffer` 只清空音频，LocalAgreement 状态通过会话状态保存/恢复。
+/// `fail_accumulate` 置位时 `accumulate_frame` 返回错误，用于模拟加载会话失败。
+struct AgreementAsr {
+    buffer: Mutex<Vec<AudioFrame>>,
+    language: Mutex<Option<String>>,
+    agreement: Mutex<LocalAgreement>,
+    fail_accumulate: AtomicBool,
+}
+
+impl AgreementAsr {
+    fn new() -> Self {
+        Self {
+            buffer: Mutex::new(Vec::new()),
+            language: Mutex::new(None),
+            agreement: Mutex::new(LocalAgreement::new(2)),
+            fail_accumulate: AtomicBool::new(false),
+        }
+    }
+
+    fn hypothesis(&self) -> Vec<TimedWord> {
+        let count = self.buffer.lock().unwrap().len() as u64;
+        let prefix = self.language.lock().unwrap().clone().unwrap_or_default();
+        let mut words: Vec<TimedWord> = (1..count)
+            .map(|i| TimedWord::new(format!("{}{}", prefix, i), (i - 1) * FRAME_MS, i * FRAME_MS))
+            .collect();
+        words.push(TimedWord::new(format!("x{}", count), (count - 1) * FRAME_MS, count * FRAME_MS));
+        words
+    }
+}
+
+#[async_trait]
+impl AsrStreaming for AgreementAsr {
+    async fn initialize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn infer(&self, _request: AsrRequest) -> EngineResult<AsrResult> {
+        Err(EngineError::new("AgreementAsr only supports boundary inference"))
+    }
+
+    async fn finalize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    fn as_ext(&self) -> Option<&dyn AsrStreamingExt> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl AsrStreamingExt for AgreementAsr {
+    fn accumulate_frame(&self, frame: AudioFrame) -> EngineResult<()> {
+        if self.fail_accumulate.load(Ordering::SeqCst) {
+            return Err(EngineError::new("AgreementAsr: accumulate_frame failed"));
+        }
+        self.buffer.lock().unwrap().push(frame);
+        Ok(())
+    }
+
+    fn get_accumulated_frames(&self) -> EngineResult<Vec<AudioFrame>> {
+        Ok(self.buffer.lock().unwrap().clone())
+    }
+
+    fn clear_buffer(&self) -> EngineResult<()> {
+        self.buffer.lock().unwrap().clear();
+        Ok(())
+    }
+
+    fn set_language(&self, language: Option<String>) -> EngineResult<()> {
+        *self.language.lock().unwrap() = language;
+        Ok(())
+    }
+
+    fn get_language(&self) -> EngineResult<Option<String>> {
+        Ok(self.language.lock().unwrap().clone())
+    }
+
+    async fn infer_on_boundary(&self) -> EngineResult<AsrResult> {
+        let hypothesis = self.hypothesis();
+        let mut agreement = self.agreement.lock().unwrap();
+        agreement.insert(hypothesis);
+        let text = core_engine::asr_whisper::local_agreement::join_words(&agreement.finish());
+        self.buffer.lock().unwrap().clear();
+        Ok(AsrResult {
+            partial: None,
+            final_transcript: Some(StableTranscript {
+                text,
+                speaker_id: None,
+                language: self.language.lock().unwrap().clone().unwrap_or_default(),
+                segments: Vec::new(),
+                language_probabilities: Vec::new(),
+            }),
+        })
+    }
+
+    fn is_streaming_enabled(&self) -> bool {
+        true
+    }
+
+    async fn infer_partial(&self, _current_timestamp_ms: u64) -> EngineResult<Option<PartialTranscript>> {
+        let hypothesis = self.hypothesis();
+        let mut agreement = self.agreement.lock().unwrap();
+        agreement.insert(hypothesis);
+        Ok(Some(PartialTranscript {
+            text: agreement.text(),
+            confidence: 0.9,
+            is_final: false,
+            stable_prefix: Some(agreement.committed_text()),
+        }))
+    }
+
+    fn take_session_state(&self) -> EngineResult<Option<AsrSessionState>> {
+        let state = std::mem::replace(&mut *self.agreement.lock().unwrap(), LocalAgreement::new(2));
+        Ok(Some(Box::new(state)))
+    }
+
+    fn restore_session_state(&self, state: Option<AsrSessionState>) -> EngineResult<()> {
+        *self.agreement.lock().unwrap() = state
+            .and_then(|state| state.downcast::<LocalAgreement>().ok())
+            .map(|state| *state)
+            .unwrap_or_else(|| LocalAgreement::new(2));
+        Ok(())
+    }
+}
+
+/// 原样返回的 NMT
+struct EchoNmt;
+
+#[async_trait]
+impl NmtIncremental for EchoNmt {
+    async fn initialize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn translate(&self, request: TranslationRequest) -> EngineResult<TranslationResponse> {
+        Ok(TranslationResponse {
+            translated_text: request.transcript.text.clone(),
+            is_stable: true,
+            speaker_id: request.speaker_id,
+            source_text: Some(request.transcript.text),
+            source_audio_duration_ms: None,
+            source_language: None,
+            quality_metrics: None,
+            committed_text: None,
+            provider: None,
+        })
+    }
+
+    async fn finalize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+struct DummyConfig;
+
+#[async_trait]
+impl ConfigManager for DummyConfig {
+    async fn load(&self) -> EngineResult<EngineConfig> {
+        Ok(EngineConfig {
+            mode: "fast".to_string(),
+            source_language: "en".to_string(),
+            target_language: "zh".to_string(),
+        })
+    }
+
+    async fn current(&self) -> EngineResult<EngineConfig> {
+        self.load().await
+    }
+}
+
+struct DummyCache;
+
+#[async_trait]
+impl CacheManager for DummyCache {
+    async fn warm_up(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn purge(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+struct DummyTelemetry;
+
+#[async_trait]
+impl TelemetrySink for DummyTelemetry {
+    async fn record(&self, _datum: TelemetryDatum) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+fn build_engine() -> CoreEngine {
+    build_engine_with_asr(Arc::new(AgreementAsr::new()))
+}
+
+fn build_engine_with_asr(asr: Arc<AgreementAsr>) -> CoreEngine {
+    CoreEngineBuilder::new()
+        .event_bus(Arc::new(ChannelEventBus::new()))
+        .vad(Arc::new(EverySixthFrameVad))
+        .asr(asr)
+        .nmt(Arc::new(EchoNmt))
+        .emotion(Arc::new(EmotionStub::new()))
+        .persona(Arc::new(PersonaStub::new()))
+        .tts(Arc::new(TtsStub::new()))
+        .config(Arc::new(DummyConfig))
+        .cache(Arc::new(DummyCache))
+        .telemetry(Arc::new(DummyTelemetry))
+        .build()
+        .expect("builder should succeed")
+}
+
+fn speech_frame(index: u64) -> AudioFrame {
+    AudioFrame {
+        sample_rate: 16000,
+        channels: 1,
+        data: vec![0.1; (16000 * FRAME_MS / 1000) as usize],
+        timestamp_ms: index * FRAME_MS,
+    }
+}
+
+async fn stable_prefix(engine: &CoreEngine, session: &Session, index: u64) -> String {
+    let result = engine
+        .process_audio_frame(session, speech_frame(index))
+        .await
+        .expect("process_audio_frame should succeed")
+        .expect("streaming ASR should produce a partial result");
+    let partial = result.asr.partial.expect("partial transcript");
+    partial.stable_prefix.expect("LocalAgreement partials carry a stable prefix")
+}
+
+#[tokio::test]
+async fn test_committed_prefix_grows_across_partials() {
+    let engine = build_engine();
+    engine.boot().await.expect("boot should succeed");
+    let session = engine.create_session(Some("en".to_string()), Some("zh".to_string())).await;
+
+    let mut prefixes = Vec::new();
+    for index in 0..FRAMES_PER_UTTERANCE - 1 {
+        prefixes.push(stable_prefix(&engine, &session, index).await);
+    }
+    assert_eq!(prefixes, vec!["", "", "en1", "en1 en2", "en1 en2 en3"]);
+
+    // 句子结束：最终结果以已提交前缀开头，会话的流式状态随之重置
+    let result = engine
+        .process_audio_frame(&session, speech_frame(FRAMES_PER_UTTERANCE - 1))
+        .await
+        .expect("process_audio_frame should succeed")
+        .expect("boundary should produce a result");
+    let final_transcript = result.asr.final_transcript.expect("final transcript");
+    assert_eq!(final_transcript.text, "en1 en2 en3 en4 en5 x6");
+
+    let prefix = stable_prefix(&engine, &session, FRAMES_PER_UTTERANCE).await;
+    assert_eq!(prefix, "");
+}
+
+#[tokio::test]
+async fn test_interleaved_sessions_keep_separate_agreement_history() {
+    let engine = build_engine();
+    engine.boot().await.expect("boot should succeed");
+    let english = engine.create_session(Some("en".to_string()), Some("zh".to_string())).await;
+    let french = engine.create_session(Some("fr".to_string()), Some("zh".to_string())).await;
+
+    let mut english_prefixes = Vec::new();
+    let mut french_prefixes = Vec::new();
+    for index in 0..FRAMES_PER_UTTERANCE - 1 {
+        english_prefixes.push(stable_prefix(&engine, &english, index).await);
+        french_prefixes.push(stable_prefix(&engine, &french, index).await);
+    }
+    assert_eq!(english_prefixes, vec!["", "", "en1", "en1 en2", "en1 en2 en3"]);
+    assert_eq!(french_prefixes, vec!["", "", "fr1", "fr1 fr2", "fr1 fr2 fr3"]);
+}
+
+#[tokio::test]
+async fn test_failed_load_keeps_agreement_history() {
+    let asr = Arc::new(AgreementAsr::new());
+    let engine = build_engine_with_asr(Arc::clone(&asr));
+    engine.boot().await.expect("boot should succeed");
+    let session = engine.create_session(Some("en".to_string()), Some("zh".to_string())).await;
+
+    let mut prefixes = Vec::new();
+    for index in 0..3 {
+        prefixes.push(stable_prefix(&engine, &session, index).await);
+    }
+    assert_eq!(prefixes, vec!["", "", "en1"]);
+
+    asr.fail_accumulate.store(true, Ordering::SeqCst);
+    assert!(engine.process_audio_frame(&session, speech_frame(3)).await.is_err());
+    asr.fail_accumulate.store(false, Ordering::SeqCst);
+
+    // 历史保留时，第 3 帧（索引 2）的假设 "en1 en2 x3" 与本次假设一起提交 "en1 en2"；
+    // 历史丢失时会从空状态重新开始，已提交前缀回到 ""
+    assert_eq!(stable_prefix(&engine, &session, 4).await, "en1 en2");
+}
+
+#[test]
+fn test_asr_streaming_config_defaults_and_parsing() {
+    let config: AsrStreamingConfig = serde_json::from_str("{}").unwrap();
+    assert_eq!(config, AsrStreamingConfig::default());
+    assert_eq!(config.mode, AsrStreamingMode::Off);
+
+    let config: AsrStreamingConfig =
+        serde_json::from_str(r#"{"mode": "local_agreement", "agreement_count": 3}"#).unwrap();
+    assert_eq!(config.mode, AsrStreamingMode::LocalAgreement);
+    assert_eq!(config.agreement_count, 3);
+    assert_eq!(config.partial_interval_seconds, 1.0);
+}
+
+#[test]
+fn test_default_ext_rejects_streaming_modes() {
+    struct BoundaryOnlyAsr;
+
+    #[async_trait]
+    impl AsrStreamingExt for BoundaryOnlyAsr {
+        fn accumulate_frame(&self, _frame: AudioFrame) -> EngineResult<()> {
+            Ok(())
+        }
+
+        fn get_accumulated_frames(&self) -> EngineResult<Vec<AudioFrame>> {
+            Ok(Vec::new())
+        }
+
+        fn clear_buffer(&self) -> EngineResult<()> {
+            Ok(())
+        }
+
+        fn set_language(&self, _language: Option<String>) -> EngineResult<()> {
+            Ok(())
+        }
+
+        fn get_language(&self) -> EngineResult<Option<String>> {
+            Ok(None)
+        }
+
+        async fn infer_on_boundary(&self) -> EngineResult<AsrResult> {
+            Ok(AsrResult {
+                partial: None,
+                final_transcript: None,
+            })
+        }
+    }
+
+    let asr = BoundaryOnlyAsr;
+    assert!(asr.set_streaming_config(&AsrStreamingConfig::default()).is_ok());
+    let local_agreement = AsrStreamingConfig {
+        mode: AsrStreamingMode::LocalAgreement,
+        ..AsrStreamingConfig::default()
+    };
+    assert!(asr.set_streaming_config(&local_agreement).is_err());
+    assert!(asr.take_session_state().unwrap().is_none());
+}
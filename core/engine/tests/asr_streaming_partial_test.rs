@@ -56,6 +56,7 @@ impl VoiceActivityDetector for TestVad {
             is_boundary,
             confidence: if is_boundary { 1.0 } else { 0.5 },
             frame,
+            speech_event: None,
         })
     }
 }
@@ -113,6 +114,7 @@ impl TtsStreaming for DummyTts {
     async fn synthesize(&self, _request: TtsRequest) -> EngineResult<TtsStreamChunk> {
         Ok(TtsStreamChunk {
             audio: vec![],
+            format: TtsAudioFormat::pcm16(16000, 1),
             timestamp_ms: 0,
             is_last: true,
         })
@@ -191,6 +193,7 @@ async fn test_streaming_partial_results() {
 
     // 初始化
     engine.boot().await.expect("Failed to boot");
+    let session = engine.create_session(Some("en".to_string()), None).await;
 
     // 注意：由于 engine.asr 是私有的，我们无法直接访问
     // 流式推理的启用应该在创建 engine 之前完成
@@ -213,7 +216,7 @@ async fn test_streaming_partial_results() {
         };
 
         // 处理音频帧
-        let result = engine.process_audio_frame(frame, Some("en".to_string()))
+        let result = engine.process_audio_frame(&session, frame)
             .await
             .expect("Failed to process audio frame");
 
@@ -55,6 +55,7 @@ impl VoiceActivityDetector for TestVad {
             is_boundary,
             confidence: if is_boundary { 1.0 } else { 0.5 },
             frame,
+            speech_event: None,
         })
     }
 }
@@ -112,6 +113,7 @@ impl TtsStreaming for DummyTts {
     async fn synthesize(&self, _request: TtsRequest) -> EngineResult<TtsStreamChunk> {
         Ok(TtsStreamChunk {
             audio: vec![],
+            format: TtsAudioFormat::pcm16(16000, 1),
             timestamp_ms: 0,
             is_last: true,
         })
@@ -190,6 +192,7 @@ async fn test_vad_integration_boundary_trigger() {
 
     // 初始化
     engine.boot().await.expect("Failed to boot");
+    let session = engine.create_session(Some("en".to_string()), None).await;
 
     println!("\n开始测试 VAD 集成...");
     println!("VAD 配置：每 10 帧检测一次边界");
@@ -206,7 +209,7 @@ async fn test_vad_integration_boundary_trigger() {
         };
 
         // 处理音频帧
-        let result = engine.process_audio_frame(frame, Some("en".to_string()))
+        let result = engine.process_audio_frame(&session, frame)
             .await
             .expect("Failed to process audio frame");
 
@@ -265,6 +268,7 @@ async fn test_vad_integration_accumulation_only() {
         .expect("Failed to build CoreEngine");
 
     engine.boot().await.expect("Failed to boot");
+    let session = engine.create_session(Some("en".to_string()), None).await;
 
     println!("\n测试：非边界帧只累积，不推理");
 
@@ -277,7 +281,7 @@ async fn test_vad_integration_accumulation_only() {
             timestamp_ms: (i * 100) as u64,
         };
 
-        let result = engine.process_audio_frame(frame, Some("en".to_string()))
+        let result = engine.process_audio_frame(&session, frame)
             .await
             .expect("Failed to process audio frame");
 
@@ -295,7 +299,7 @@ async fn test_vad_integration_accumulation_only() {
         timestamp_ms: 900,
     };
 
-    let result = engine.process_audio_frame(frame, Some("en".to_string()))
+    let result = engine.process_audio_frame(&session, frame)
         .await
         .expect("Failed to process audio frame");
 
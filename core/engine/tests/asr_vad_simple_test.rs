@@ -46,6 +46,8 @@ impl NmtIncremental for DummyNmt {
             source_audio_duration_ms: None,
             source_text: None,
             quality_metrics: None,
+            committed_text: None,
+            provider: None,
         })
     }
 
@@ -87,6 +89,7 @@ impl TtsStreaming for DummyTts {
     async fn synthesize(&self, _request: TtsRequest) -> EngineResult<TtsStreamChunk> {
         Ok(TtsStreamChunk {
             audio: vec![],
+            format: TtsAudioFormat::pcm16(16000, 1),
             timestamp_ms: 0,
             is_last: true,
         })
@@ -282,6 +285,7 @@ async fn test_asr_vad_simple() {
         .expect("Failed to build CoreEngine");
     
     engine.boot().await.expect("Failed to boot");
+    let session = engine.create_session(Some("zh".to_string()), None).await;
     println!("✓ CoreEngine 初始化成功");
     
     // 5. 加载音频文件
@@ -301,7 +305,7 @@ async fn test_asr_vad_simple() {
         total_frames += 1;
         
         // 处理音频帧
-        let result = engine.process_audio_frame(frame.clone(), Some("zh".to_string()))
+        let result = engine.process_audio_frame(&session, frame.clone())
             .await
             .expect("Failed to process audio frame");
         
@@ -0,0 +1,171 @@
+//! 滑动窗口 + LocalAgreement 流式识别测试
+//!
+//! 验证：
+//! - 连续 n 个假设一致的前缀才被提交，已提交部分不再改变
+//! - 新假设中与已提交部分重叠的单元被去掉
+//! - 逐帧输入 test_output 中的音频时，部分结果的稳定前缀只增不改，且最终结果以它开头
+
+use std::path::{Path, PathBuf};
+
+use core_engine::asr_streaming::{AsrStreaming, AsrStreamingExt};
+use core_engine::asr_whisper::{LocalAgreement, LocalAgreementConfig, TimedWord, WhisperAsrStreaming};
+use core_engine::types::AudioFrame;
+
+fn words(items: &[(&str, u64)]) -> Vec<TimedWord> {
+    items.iter().map(|&(text, start_ms)| TimedWord::new(text, start_ms, start_ms + 200)).collect()
+}
+
+fn texts(words: &[TimedWord]) -> Vec<&str> {
+    words.iter().map(|word| word.text.as_str()).collect()
+}
+
+#[test]
+fn test_commits_prefix_agreed_by_consecutive_hypotheses() {
+    let mut agreement = LocalAgreement::new(2);
+
+    // 第一个假设：还没有可比较的假设，不提交
+    assert!(agreement.insert(words(&[("I", 0), ("scream", 300)])).is_empty());
+    assert_eq!(agreement.text(), "I scream");
+
+    // 第二个假设只有第一个词一致（忽略大小写和标点）
+    let committed = agreement.insert(words(&[("i", 0), ("scream,", 300), ("for", 600)]));
+    assert_eq!(texts(&committed), vec!["i"]);
+    assert_eq!(agreement.committed_text(), "i");
+    assert_eq!(agreement.text(), "i scream, for");
+
+    // 第三个假设：重复已提交的 "i"（时间戳有误差），重叠部分被去掉
+    let committed = agreement.insert(words(&[("i", 250), ("scream", 300), ("for", 600), ("ice", 900)]));
+    assert_eq!(texts(&committed), vec!["scream", "for"]);
+    assert_eq!(agreement.committed_text(), "i scream for");
+    assert_eq!(texts(agreement.tail()), vec!["ice"]);
+
+    // 结束时提交最新假设的剩余部分
+    let all = agreement.finish();
+    assert_eq!(texts(&all), vec!["i", "scream", "for", "ice"]);
+    assert_eq!(agreement.committed_text(), "");
+}
+
+#[test]
+fn test_committed_prefix_never_changes() {
+    let mut agreement = LocalAgreement::new(2);
+    let hypotheses = [
+        vec![("今", 0), ("天", 200)],
+        vec![("今", 0), ("天", 200), ("天", 400)],
+        vec![("金", 0), ("天", 200), ("天", 400), ("气", 600)],
+        vec![("今", 0), ("天", 200), ("天", 400), ("气", 600), ("好", 800)],
+    ];
+
+    let mut previous = String::new();
+    for hypothesis in &hypotheses {
+        agreement.insert(words(hypothesis));
+        let committed = agreement.committed_text();
+        assert!(committed.starts_with(&previous), "{:?} -> {:?}", previous, committed);
+        assert!(agreement.text().starts_with(&committed));
+        previous = committed;
+    }
+    // 第三个假设把 "今" 识别成了 "金"，但 "今" 已经提交，不会被改掉
+    assert_eq!(previous, "今天天气");
+}
+
+#[test]
+fn test_force_commit_until() {
+    let mut agreement = LocalAgreement::new(3);
+    agreement.insert(words(&[("hello", 0), ("world", 300), ("again", 600)]));
+    assert!(agreement.committed().is_empty());
+
+    let forced = agreement.force_commit_until(650);
+    assert_eq!(texts(&forced), vec!["hello", "world"]);
+    assert_eq!(agreement.committed_end_ms(), 500);
+    assert_eq!(texts(agreement.tail()), vec!["again"]);
+}
+
+/// 读取 WAV 文件并切分成约 100ms 的帧
+fn load_frames(wav_path: &Path) -> Vec<AudioFrame> {
+    let mut reader = hound::WavReader::open(wav_path).expect("Failed to open WAV file");
+    let spec = reader.spec();
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>()
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Failed to read samples"),
+        hound::SampleFormat::Int => {
+            let max_val = (1i32 << (spec.bits_per_sample - 1)) as f32;
+            reader.samples::<i32>()
+                .map(|s| s.map(|sample| sample as f32 / max_val))
+                .collect::<Result<Vec<_>, _>>()
+                .expect("Failed to read samples")
+        }
+    };
+
+    let chunk_size = (spec.sample_rate as usize / 10) * spec.channels as usize;
+    samples.chunks(chunk_size)
+        .enumerate()
+        .map(|(i, chunk)| AudioFrame {
+            sample_rate: spec.sample_rate,
+            channels: spec.channels as u8,
+            data: chunk.to_vec(),
+            timestamp_ms: i as u64 * 100,
+        })
+        .collect()
+}
+
+async fn stream_wav(asr: &WhisperAsrStreaming, wav_path: &Path, language: &str) {
+    asr.set_language(Some(language.to_string())).expect("Failed to set language");
+    asr.clear_buffer();
+
+    let mut stable_prefix = String::new();
+    let mut partial_count = 0;
+    for frame in load_frames(wav_path) {
+        let timestamp_ms = frame.timestamp_ms;
+        AsrStreamingExt::accumulate_frame(asr, frame).expect("Failed to accumulate frame");
+        let Some(partial) = asr.infer_partial(timestamp_ms).await.expect("Failed to infer partial") else {
+            continue;
+        };
+        partial_count += 1;
+
+        let prefix = partial.stable_prefix.clone().expect("sliding window partials carry a stable prefix");
+        println!("  [{:>5}ms] {} | {}", timestamp_ms, prefix, partial.volatile_tail());
+        assert!(prefix.starts_with(&stable_prefix), "stable prefix changed: {:?} -> {:?}", stable_prefix, prefix);
+        assert!(partial.text.starts_with(&prefix));
+        stable_prefix = prefix;
+    }
+    assert!(partial_count > 0, "no partial results for {}", wav_path.display());
+
+    let result = asr.infer_on_boundary().await.expect("Failed to infer on boundary");
+    let final_transcript = result.final_transcript.expect("no final transcript");
+    println!("  final: {}", final_transcript.text);
+    assert!(final_transcript.text.starts_with(&stable_prefix),
+            "final text {:?} does not start with stable prefix {:?}", final_transcript.text, stable_prefix);
+    assert!(!final_transcript.text.is_empty());
+    assert!(final_transcript.segments.windows(2).all(|pair| pair[0].start_ms <= pair[1].start_ms));
+}
+
+#[tokio::test]
+async fn test_sliding_window_streaming_on_test_wavs() {
+    let crate_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let project_root = crate_root
+        .parent()
+        .and_then(|p| p.parent())
+        .expect("failed to resolve project root");
+
+    let model_path = crate_root.join("models/asr/whisper-base/ggml-base.bin");
+    let chinese_wav = project_root.join("test_output/chinese.wav");
+    let english_wav = project_root.join("test_output/english.wav");
+
+    if !model_path.exists() || !chinese_wav.exists() || !english_wav.exists() {
+        println!("⚠ 跳过测试: 模型或音频文件不存在");
+        return;
+    }
+
+    let asr = WhisperAsrStreaming::new_from_model_path(&model_path)
+        .expect("Failed to create WhisperAsrStreaming");
+    asr.initialize().await.expect("Failed to initialize");
+    asr.enable_local_agreement_streaming(0.5, LocalAgreementConfig::default());
+    assert!(asr.is_local_agreement_enabled());
+
+    println!("\n中文音频:");
+    stream_wav(&asr, &chinese_wav, "zh").await;
+    println!("\n英文音频:");
+    stream_wav(&asr, &english_wav, "en").await;
+
+    asr.finalize().await.expect("Failed to finalize");
+}
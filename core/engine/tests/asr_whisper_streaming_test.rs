@@ -271,3 +271,60 @@ async fn test_whisper_streaming_full_lifecycle() {
     println!("✓ 清理后推理完成（缓冲区已重置）");
 }
 
+
+/// 测试 5: 最终结果的分段和 token 时间戳是流时间（加上第一帧的时间戳），而不是相对于推理缓冲区
+#[tokio::test]
+async fn test_whisper_streaming_final_timestamps_use_stream_time() {
+    let crate_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let project_root = crate_root
+        .parent()
+        .and_then(|p| p.parent())
+        .expect("failed to resolve project root");
+
+    let model_path = crate_root.join("models/asr/whisper-base/ggml-base.bin");
+    let wav_path = project_root.join("third_party/whisper.cpp/samples/jfk.wav");
+
+    if !model_path.exists() || !wav_path.exists() {
+        println!("⚠ 跳过测试: 模型或音频文件不存在");
+        return;
+    }
+
+    let asr = WhisperAsrStreaming::new_from_model_path(&model_path)
+        .expect("Failed to create WhisperAsrStreaming");
+    asr.initialize().await.expect("Failed to initialize");
+
+    let mut reader = hound::WavReader::open(&wav_path)
+        .expect("Failed to open WAV file");
+    let spec = reader.spec();
+    let samples: Vec<f32> = reader.samples::<i16>()
+        .map(|s| s.map(|sample| sample as f32 / 32768.0))
+        .collect::<Result<Vec<_>, _>>()
+        .expect("Failed to read samples");
+
+    // 这句话从会话开始后第 60 秒开始
+    const START_MS: u64 = 60_000;
+    let frame = AudioFrame {
+        sample_rate: spec.sample_rate,
+        channels: spec.channels as u8,
+        data: samples,
+        timestamp_ms: START_MS,
+    };
+    let end_ms = frame.end_timestamp_ms();
+
+    let result = asr.infer(AsrRequest {
+        frame,
+        language_hint: Some("en".to_string()),
+    }).await.expect("Failed to infer");
+    let final_transcript = result.final_transcript.expect("应该返回最终结果");
+
+    assert!(!final_transcript.segments.is_empty());
+    for segment in &final_transcript.segments {
+        assert!(segment.start_ms >= START_MS && segment.end_ms <= end_ms + 100,
+                "分段时间 {}-{}ms 应在 {}-{}ms 之内", segment.start_ms, segment.end_ms, START_MS, end_ms);
+        for token in &segment.tokens {
+            assert!(token.start_ms >= START_MS, "token 时间 {}ms 应在 {}ms 之后", token.start_ms, START_MS);
+        }
+    }
+
+    asr.finalize().await.expect("Failed to finalize");
+}
@@ -79,6 +79,7 @@ impl VoiceActivityDetector for TestVad {
             is_boundary,
             confidence: if is_boundary { 1.0 } else { 0.5 },
             frame,
+            speech_event: None,
         })
     }
 }
@@ -116,6 +117,7 @@ impl TtsStreaming for DummyTts {
     async fn synthesize(&self, _request: TtsRequest) -> EngineResult<TtsStreamChunk> {
         Ok(TtsStreamChunk {
             audio: vec![],
+            format: TtsAudioFormat::pcm16(16000, 1),
             timestamp_ms: 0,
             is_last: true,
         })
@@ -207,6 +209,7 @@ async fn test_full_business_flow() {
 
     // 初始化
     engine.boot().await.expect("Failed to boot");
+    let session = engine.create_session(Some("en".to_string()), None).await;
     event_bus_clone.clear_events();
 
     println!("\n========== 开始端到端业务流程测试 ==========");
@@ -229,7 +232,7 @@ async fn test_full_business_flow() {
         // 处理音频帧（添加超时：每帧最多等待 10 秒）
         let process_result = timeout(
             Duration::from_secs(10),
-            engine.process_audio_frame(frame, Some("en".to_string()))
+            engine.process_audio_frame(&session, frame)
         ).await;
 
         let result = match process_result {
@@ -79,6 +79,7 @@ impl VoiceActivityDetector for TestVad {
             is_boundary,
             confidence: if is_boundary { 1.0 } else { 0.5 },
             frame,
+            speech_event: None,
         })
     }
 }
@@ -116,6 +117,7 @@ impl TtsStreaming for DummyTts {
     async fn synthesize(&self, _request: TtsRequest) -> EngineResult<TtsStreamChunk> {
         Ok(TtsStreamChunk {
             audio: vec![],
+            format: TtsAudioFormat::pcm16(16000, 1),
             timestamp_ms: 0,
             is_last: true,
         })
@@ -341,6 +343,7 @@ async fn test_step6_process_single_frame() {
         .expect("Failed to build CoreEngine");
     
     engine.boot().await.expect("Failed to boot");
+    let session = engine.create_session(Some("en".to_string()), None).await;
     
     // 处理 1 个音频帧（不会触发边界，所以不会推理）
     let frame = AudioFrame {
@@ -353,7 +356,7 @@ async fn test_step6_process_single_frame() {
     use tokio::time::{timeout, Duration};
     let result = timeout(
         Duration::from_secs(5),
-        engine.process_audio_frame(frame, Some("en".to_string()))
+        engine.process_audio_frame(&session, frame)
     ).await;
     
     match result {
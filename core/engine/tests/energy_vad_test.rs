@@ -0,0 +1,154 @@
+//! EnergyVad 测试（不需要模型文件）
+//!
+//! 验证：
+//! - 静音 → 语音 → 静音：发出 Start/End 事件，静音达到阈值后报告一次 NaturalPause 边界
+//! - 高过零率的强噪声（白噪声）不会被判定为语音
+//! - 噪声底跟踪背景噪声
+//! - 48kHz 立体声输入经过格式适配后检测结果一致
+//! - fork_for_session 的检测状态相互独立
+
+use core_engine::types::AudioFrame;
+use core_engine::vad::{BoundaryType, DetectionOutcome, EnergyVad, EnergyVadConfig, SpeechEvent, VoiceActivityDetector};
+
+const FRAME_MS: u64 = 20;
+
+/// 确定性的伪随机噪声（LCG），范围 [-amplitude, amplitude]
+struct Noise(u64);
+
+impl Noise {
+    fn next(&mut self, amplitude: f32) -> f32 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((self.0 >> 40) as f32 / (1u64 << 24) as f32 * 2.0 - 1.0) * amplitude
+    }
+}
+
+/// 浊音的近似：150Hz 基频加 20 个谐波（幅度按 1/√k 衰减）
+fn voiced(t: f32) -> f32 {
+    (1..=20)
+        .map(|k| (2.0 * std::f32::consts::PI * 150.0 * k as f32 * t).sin() / (k as f32).sqrt())
+        .sum::<f32>()
+        * 0.12
+}
+
+/// 按时间段生成单声道信号：`speech` 内为浊音，全程叠加低幅背景噪声
+fn signal(sample_rate: u32, total_ms: u64, speech: (u64, u64), noise_amplitude: f32) -> Vec<f32> {
+    let mut noise = Noise(42);
+    let samples = (sample_rate as u64 * total_ms / 1000) as usize;
+    (0..samples)
+        .map(|i| {
+            let t = i as f32 / sample_rate as f32;
+            let ms = i as u64 * 1000 / sample_rate as u64;
+            let voice = if ms >= speech.0 && ms < speech.1 { voiced(t) } else { 0.0 };
+            voice + noise.next(noise_amplitude)
+        })
+        .collect()
+}
+
+fn frames(samples: &[f32], sample_rate: u32, channels: u8) -> Vec<AudioFrame> {
+    let per_frame = (sample_rate as u64 * FRAME_MS / 1000) as usize;
+    samples
+        .chunks(per_frame)
+        .enumerate()
+        .map(|(index, chunk)| AudioFrame {
+            sample_rate,
+            channels,
+            data: chunk.iter().flat_map(|&x| std::iter::repeat(x).take(channels as usize)).collect(),
+            timestamp_ms: index as u64 * FRAME_MS,
+        })
+        .collect()
+}
+
+async fn run(vad: &dyn VoiceActivityDetector, frames: Vec<AudioFrame>) -> Vec<DetectionOutcome> {
+    let mut outcomes = Vec::new();
+    for frame in frames {
+        outcomes.push(vad.detect(frame).await.unwrap());
+    }
+    outcomes
+}
+
+fn events(outcomes: &[DetectionOutcome]) -> Vec<SpeechEvent> {
+    outcomes.iter().filter_map(|o| o.speech_event.clone()).collect()
+}
+
+#[tokio::test]
+async fn test_utterance_produces_events_and_natural_pause() {
+    let vad = EnergyVad::default();
+    let samples = signal(16000, 2500, (500, 1500), 0.003);
+    let outcomes = run(&vad, frames(&samples, 16000, 1)).await;
+
+    let boundaries: Vec<&DetectionOutcome> = outcomes.iter().filter(|o| o.is_boundary).collect();
+    assert_eq!(boundaries.len(), 1, "exactly one boundary expected");
+    assert_eq!(boundaries[0].boundary_type, Some(BoundaryType::NaturalPause));
+    // 语音在 1500ms 结束，静音 500ms 后报告边界
+    let boundary_ms = boundaries[0].frame.timestamp_ms;
+    assert!((1950..=2100).contains(&boundary_ms), "boundary at {}ms", boundary_ms);
+
+    match events(&outcomes).as_slice() {
+        [SpeechEvent::Start { onset_ms, start_ms }, SpeechEvent::End { offset_ms, end_ms }] => {
+            assert!((500..=600).contains(onset_ms), "onset at {}ms", onset_ms);
+            assert_eq!(*start_ms, onset_ms - 300);
+            assert!((1480..=1540).contains(offset_ms), "offset at {}ms", offset_ms);
+            assert_eq!(*end_ms, offset_ms + 150);
+        }
+        other => panic!("unexpected events: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_white_noise_is_not_speech() {
+    let vad = EnergyVad::default();
+    // 先给安静的背景建立噪声底，然后是强白噪声
+    let mut samples = signal(16000, 500, (0, 0), 0.003);
+    samples.extend(signal(16000, 2000, (0, 0), 0.3));
+    let outcomes = run(&vad, frames(&samples, 16000, 1)).await;
+
+    assert!(events(&outcomes).is_empty());
+    assert!(outcomes.iter().all(|o| !o.is_boundary));
+}
+
+#[tokio::test]
+async fn test_noise_floor_tracks_background() {
+    let vad = EnergyVad::default();
+    assert_eq!(vad.noise_floor_db(), None);
+
+    run(&vad, frames(&signal(16000, 1000, (0, 0), 0.003), 16000, 1)).await;
+    let quiet = vad.noise_floor_db().unwrap();
+    assert!((-70.0..-50.0).contains(&quiet), "noise floor {}dB", quiet);
+
+    vad.reset().await.unwrap();
+    assert_eq!(vad.noise_floor_db(), None);
+}
+
+#[tokio::test]
+async fn test_browser_audio_matches_16k_detection() {
+    let vad = EnergyVad::default();
+    let samples = signal(48000, 2500, (500, 1500), 0.003);
+    let outcomes = run(&vad, frames(&samples, 48000, 2)).await;
+
+    assert_eq!(outcomes.iter().filter(|o| o.is_boundary).count(), 1);
+    assert!(outcomes.iter().all(|o| o.frame.sample_rate == 48000 && o.frame.channels == 2));
+    let events = events(&outcomes);
+    assert_eq!(events.len(), 2);
+    assert!(matches!(events[0], SpeechEvent::Start { onset_ms, .. } if (500..=620).contains(&onset_ms)));
+}
+
+#[tokio::test]
+async fn test_forked_sessions_are_independent() {
+    let config = EnergyVadConfig {
+        min_silence_duration_ms: 300,
+        ..EnergyVadConfig::default()
+    };
+    let vad = EnergyVad::new(config);
+    let first = vad.fork_for_session().unwrap();
+    let second = vad.fork_for_session().unwrap();
+
+    let speech = frames(&signal(16000, 1000, (200, 500), 0.003), 16000, 1);
+    let silence = frames(&signal(16000, 1000, (0, 0), 0.003), 16000, 1);
+
+    let first_outcomes = run(first.as_ref(), speech).await;
+    let second_outcomes = run(second.as_ref(), silence).await;
+
+    assert!(first_outcomes.iter().any(|o| o.is_boundary));
+    assert!(events(&second_outcomes).is_empty());
+    assert!(vad.noise_floor_db().is_none(), "template must not be touched by sessions");
+}
@@ -0,0 +1,44 @@
+//! 语言注册表测试
+//!
+//! 验证新增语言只需修改语言表配置：
+//! - 配置中新增的语言可以被 LanguageCode / LanguagePair 解析
+//! - 各后端（Marian、M2M100、Whisper、Piper）按配置得到各自的语言代码
+
+use core_engine::language_registry::{self, LanguageRegistry};
+use core_engine::{LanguageBackend, LanguageCode, LanguagePair};
+
+const EXTRA_LANGUAGES: &str = r#"{
+  "version": "test",
+  "languages": [
+    { "code": "en", "aliases": ["english"], "piper_locale": "en_US", "piper_voice": "en_US-lessac-medium" },
+    { "code": "zh", "aliases": ["中文"], "default_script": "Hans",
+      "scripts": { "Hans": ["CN"], "Hant": ["TW", "HK"] }, "piper_locale": "zh_CN" },
+    { "code": "sw", "name": "Swahili", "aliases": ["swa", "kiswahili"],
+      "marian": "swc", "piper_locale": "sw_CD", "piper_voice": "sw_CD-lanfrica-medium" }
+  ]
+}"#;
+
+#[test]
+fn test_language_added_through_config() {
+    let registry = LanguageRegistry::from_json_str(EXTRA_LANGUAGES).expect("config should parse");
+    language_registry::init_registry(registry);
+
+    let swahili = LanguageCode::from_str("Kiswahili").expect("configured language should resolve");
+    assert_eq!(swahili.as_str(), "sw");
+    assert_eq!(swahili.to_dir_name(), "swc");
+
+    // Marian 目录名使用 Marian 的语言代码，并能反向解析
+    let pair = LanguagePair::new(LanguageCode::En, swahili);
+    assert_eq!(pair.to_model_dir_name(), "marian-en-swc");
+    assert_eq!(LanguagePair::from_model_dir_name("marian-en-swc").unwrap(), pair);
+    assert_eq!(pair.model_dir_name(LanguageBackend::M2M100), "m2m100-en-sw");
+
+    let registry = language_registry::global();
+    assert_eq!(registry.normalize("sw-KE", LanguageBackend::Whisper), "sw");
+    assert_eq!(registry.backend_code("sw", LanguageBackend::Piper).as_deref(), Some("sw_CD"));
+    assert_eq!(registry.backend_code("zh-HK", LanguageBackend::Piper).as_deref(), Some("zh_HK"));
+    assert_eq!(registry.piper_voice("sw"), Some("sw_CD-lanfrica-medium"));
+
+    // 未配置的语言仍然报错
+    assert!(LanguageCode::from_str("ja").is_err());
+}
@@ -39,6 +39,7 @@ impl VoiceActivityDetector for DummyVad {
             is_boundary: true,
             confidence: 1.0,
             frame,
+            speech_event: None,
         })
     }
 }
@@ -96,6 +97,7 @@ impl TtsStreaming for DummyTts {
     async fn synthesize(&self, _request: TtsRequest) -> EngineResult<TtsStreamChunk> {
         Ok(TtsStreamChunk {
             audio: vec![],
+            format: TtsAudioFormat::pcm16(16000, 1),
             timestamp_ms: 0,
             is_last: true,
         })
@@ -219,9 +221,13 @@ async fn test_marian_nmt_onnx_trait_implementation() {
                 text: source_text.to_string(),
                 confidence: 1.0,
                 is_final: true,
+                stable_prefix: None,
             },
             target_language: "zh".to_string(),
             wait_k: None,
+            decoding: None,
+            committed_prefix: None,
+            source_language: None,
         };
 
         match nmt.translate(request).await {
@@ -273,6 +279,7 @@ async fn test_nmt_integration_full_pipeline() {
         text: "Hello world".to_string(),
         confidence: 0.95,
         is_final: true,
+        stable_prefix: None,
     };
 
     println!("\n--- Step 1: ASR Output ---");
@@ -285,6 +292,9 @@ async fn test_nmt_integration_full_pipeline() {
         transcript: asr_transcript.clone(),
         target_language: "zh".to_string(),
         wait_k: None,
+        decoding: None,
+        committed_prefix: None,
+        source_language: None,
     };
 
     let translation_response = nmt.translate(translation_request).await
@@ -323,6 +333,8 @@ async fn test_nmt_integration_full_pipeline() {
             text: translation_response.translated_text.clone(),
             speaker_id: None,
             language: "zh".to_string(),
+            segments: Vec::new(),
+            language_probabilities: Vec::new(),
         },
         persona_context,
     ).await.expect("Failed to personalize");
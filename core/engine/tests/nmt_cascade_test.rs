@@ -0,0 +1,318 @@
+//! 级联 NMT 后端测试
+//!
+//! 验证：
+//! - 按顺序回退，并在响应中记录提供翻译的后端
+//! - 单个后端超时后回退到下一个后端
+//! - 连续失败的后端被熔断器跳过，熔断时间过后放行试探请求并恢复
+//! - 流式翻译在第一个增量之前出错时回退；流中途出错计入熔断器，成功在流结束时才记录
+//! - 同步推理的后端在阻塞线程中运行，超时后立即回退
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::{stream, StreamExt};
+use core_engine::nmt_client::CircuitState;
+use core_engine::*;
+
+/// 可切换成功/失败、可设置延迟的假后端
+struct FakeBackend {
+    name: &'static str,
+    failing: AtomicBool,
+    delay: Duration,
+    calls: AtomicUsize,
+}
+
+impl FakeBackend {
+    fn new(name: &'static str, failing: bool, delay: Duration) -> Arc<Self> {
+        Arc::new(Self {
+            name,
+            failing: AtomicBool::new(failing),
+            delay,
+            calls: AtomicUsize::new(0),
+        })
+    }
+
+    fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl NmtIncremental for FakeBackend {
+    async fn initialize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn translate(&self, request: TranslationRequest) -> EngineResult<TranslationResponse> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(self.delay).await;
+        if self.failing.load(Ordering::SeqCst) {
+            return Err(EngineError::new(format!("{} unavailable", self.name)));
+        }
+        Ok(TranslationResponse {
+            translated_text: format!("{}:{}", self.name, request.transcript.text),
+            is_stable: true,
+            speaker_id: None,
+            source_text: None,
+            source_audio_duration_ms: None,
+            source_language: None,
+            quality_metrics: None,
+            committed_text: None,
+            provider: None,
+        })
+    }
+
+    async fn finalize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+/// 流式后端：先输出 `ok_deltas` 个增量，`failing` 时随后出错，否则输出结束增量
+struct FakeStreamBackend {
+    ok_deltas: usize,
+    failing: AtomicBool,
+}
+
+fn delta(text: &str, is_last: bool) -> TranslationDelta {
+    TranslationDelta {
+        text: text.to_string(),
+        token_id: None,
+        probability: None,
+        is_last,
+        full_text: None,
+        provider: None,
+    }
+}
+
+#[async_trait]
+impl NmtIncremental for FakeStreamBackend {
+    async fn initialize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn translate(&self, _request: TranslationRequest) -> EngineResult<TranslationResponse> {
+        Err(EngineError::new("FakeStreamBackend only supports streaming translation"))
+    }
+
+    async fn translate_stream<'a>(&'a self, _request: TranslationRequest) -> EngineResult<TranslationStream<'a>> {
+        let mut deltas: Vec<EngineResult<TranslationDelta>> = (0..self.ok_deltas).map(|i| Ok(delta(&format!("t{}", i), false))).collect();
+        if self.failing.load(Ordering::SeqCst) {
+            deltas.push(Err(EngineError::new("decoder crashed")));
+        } else {
+            deltas.push(Ok(delta("", true)));
+        }
+        Ok(Box::pin(stream::iter(deltas)))
+    }
+
+    async fn finalize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+/// 同步推理的后端（阻塞当前线程）
+struct BlockingBackend {
+    delay: Duration,
+}
+
+#[async_trait]
+impl NmtIncremental for BlockingBackend {
+    async fn initialize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn translate(&self, request: TranslationRequest) -> EngineResult<TranslationResponse> {
+        std::thread::sleep(self.delay);
+        Ok(TranslationResponse {
+            translated_text: format!("blocking:{}", request.transcript.text),
+            is_stable: true,
+            speaker_id: None,
+            source_text: None,
+            source_audio_duration_ms: None,
+            source_language: None,
+            quality_metrics: None,
+            committed_text: None,
+            provider: None,
+        })
+    }
+
+    async fn finalize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+fn request(text: &str) -> TranslationRequest {
+    TranslationRequest {
+        transcript: PartialTranscript {
+            text: text.to_string(),
+            confidence: 1.0,
+            is_final: true,
+            stable_prefix: None,
+        },
+        target_language: "en".to_string(),
+        wait_k: None,
+        speaker_id: None,
+        decoding: None,
+        committed_prefix: None,
+        source_language: Some("zh".to_string()),
+    }
+}
+
+const TIMEOUT: Duration = Duration::from_millis(200);
+
+#[tokio::test]
+async fn test_falls_back_in_order_and_records_provider() {
+    let local = FakeBackend::new("local", true, Duration::ZERO);
+    let remote = FakeBackend::new("remote", false, Duration::ZERO);
+    let cascade = CascadingNmt::new(CircuitBreakerConfig::default())
+        .with_backend("local-m2m100", local.clone(), TIMEOUT)
+        .with_backend("remote", remote.clone(), TIMEOUT);
+
+    let response = cascade.translate(request("你好")).await.unwrap();
+    assert_eq!(response.translated_text, "remote:你好");
+    assert_eq!(response.provider.as_deref(), Some("remote"));
+    assert_eq!((local.calls(), remote.calls()), (1, 1));
+
+    // 流式翻译同样回退，并在每个增量上记录后端
+    let deltas: Vec<TranslationDelta> = cascade
+        .translate_stream(request("世界"))
+        .await
+        .unwrap()
+        .map(|delta| delta.unwrap())
+        .collect()
+        .await;
+    assert_eq!(deltas.len(), 1);
+    assert_eq!(deltas[0].text, "remote:世界");
+    assert_eq!(deltas[0].provider.as_deref(), Some("remote"));
+
+    // 所有后端都失败时返回错误
+    remote.failing.store(true, Ordering::SeqCst);
+    let error = cascade.translate(request("再见")).await.unwrap_err();
+    assert!(error.to_string().contains("All NMT backends failed"), "{}", error);
+}
+
+#[tokio::test]
+async fn test_timeout_falls_back_to_next_backend() {
+    let slow = FakeBackend::new("slow", false, Duration::from_secs(5));
+    let marian = FakeBackend::new("marian", false, Duration::ZERO);
+    let cascade = CascadingNmt::new(CircuitBreakerConfig::default())
+        .with_backend("slow", slow.clone(), TIMEOUT)
+        .with_backend("marian-onnx", marian.clone(), TIMEOUT);
+
+    let response = cascade.translate(request("你好")).await.unwrap();
+    assert_eq!(response.provider.as_deref(), Some("marian-onnx"));
+    assert_eq!(slow.calls(), 1);
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_skips_failing_backend_and_recovers() {
+    let local = FakeBackend::new("local", true, Duration::ZERO);
+    let remote = FakeBackend::new("remote", false, Duration::ZERO);
+    let config = CircuitBreakerConfig {
+        failure_threshold: 2,
+        open_duration: Duration::from_millis(100),
+    };
+    let cascade = CascadingNmt::new(config)
+        .with_backend("local-m2m100", local.clone(), TIMEOUT)
+        .with_backend("remote", remote.clone(), TIMEOUT);
+
+    for _ in 0..2 {
+        cascade.translate(request("你好")).await.unwrap();
+    }
+    assert_eq!(cascade.circuit_state("local-m2m100"), Some(CircuitState::Open));
+
+    // 熔断期间不再请求故障后端
+    for _ in 0..3 {
+        let response = cascade.translate(request("你好")).await.unwrap();
+        assert_eq!(response.provider.as_deref(), Some("remote"));
+    }
+    assert_eq!(local.calls(), 2);
+
+    // 熔断时间过后放行试探请求，成功则恢复
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(cascade.circuit_state("local-m2m100"), Some(CircuitState::HalfOpen));
+    local.failing.store(false, Ordering::SeqCst);
+    let response = cascade.translate(request("你好")).await.unwrap();
+    assert_eq!(response.provider.as_deref(), Some("local-m2m100"));
+    assert_eq!(cascade.circuit_state("local-m2m100"), Some(CircuitState::Closed));
+    assert_eq!(local.calls(), 3);
+}
+
+#[tokio::test]
+async fn test_stream_error_before_first_delta_falls_back() {
+    let local = Arc::new(FakeStreamBackend { ok_deltas: 0, failing: AtomicBool::new(true) });
+    let remote = FakeBackend::new("remote", false, Duration::ZERO);
+    let cascade = CascadingNmt::new(CircuitBreakerConfig::default())
+        .with_backend("local-m2m100", local, TIMEOUT)
+        .with_backend("remote", remote.clone(), TIMEOUT);
+
+    let deltas: Vec<TranslationDelta> = cascade
+        .translate_stream(request("你好"))
+        .await
+        .unwrap()
+        .map(|delta| delta.unwrap())
+        .collect()
+        .await;
+    assert_eq!(deltas.len(), 1);
+    assert_eq!(deltas[0].text, "remote:你好");
+    assert_eq!(deltas[0].provider.as_deref(), Some("remote"));
+    assert_eq!(remote.calls(), 1);
+}
+
+#[tokio::test]
+async fn test_mid_stream_errors_trip_circuit_breaker() {
+    let local = Arc::new(FakeStreamBackend { ok_deltas: 2, failing: AtomicBool::new(true) });
+    let config = CircuitBreakerConfig {
+        failure_threshold: 2,
+        open_duration: Duration::from_millis(100),
+    };
+    let cascade = CascadingNmt::new(config).with_backend("local-m2m100", local.clone(), TIMEOUT);
+
+    for _ in 0..2 {
+        let results: Vec<EngineResult<TranslationDelta>> =
+            cascade.translate_stream(request("你好")).await.unwrap().collect().await;
+        // 已经输出过增量，错误直接返回给调用方
+        assert_eq!(results.len(), 3);
+        assert!(results[2].is_err());
+    }
+    assert_eq!(cascade.circuit_state("local-m2m100"), Some(CircuitState::Open));
+    assert!(cascade.translate_stream(request("你好")).await.is_err());
+
+    // 试探请求的流完整结束后才恢复
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    local.failing.store(false, Ordering::SeqCst);
+    let mut deltas = cascade.translate_stream(request("你好")).await.unwrap();
+    deltas.next().await.unwrap().unwrap();
+    assert_eq!(cascade.circuit_state("local-m2m100"), Some(CircuitState::HalfOpen));
+    while deltas.next().await.is_some() {}
+    assert_eq!(cascade.circuit_state("local-m2m100"), Some(CircuitState::Closed));
+}
+
+#[tokio::test]
+async fn test_blocking_backend_times_out() {
+    let blocking = Arc::new(BlockingBackend { delay: Duration::from_secs(2) });
+    let marian = FakeBackend::new("marian", false, Duration::ZERO);
+    let cascade = CascadingNmt::new(CircuitBreakerConfig::default())
+        .with_blocking_backend("blocking", blocking, TIMEOUT)
+        .with_backend("marian-onnx", marian.clone(), TIMEOUT);
+
+    let started = std::time::Instant::now();
+    let response = cascade.translate(request("你好")).await.unwrap();
+    assert_eq!(response.provider.as_deref(), Some("marian-onnx"));
+    assert!(started.elapsed() < Duration::from_secs(1), "timeout should not wait for blocking inference");
+
+    // 流式翻译同样在阻塞线程中运行
+    let cascade = CascadingNmt::new(CircuitBreakerConfig::default())
+        .with_blocking_backend("blocking", Arc::new(BlockingBackend { delay: Duration::from_millis(10) }), TIMEOUT);
+    let deltas: Vec<TranslationDelta> = cascade
+        .translate_stream(request("世界"))
+        .await
+        .unwrap()
+        .map(|delta| delta.unwrap())
+        .collect()
+        .await;
+    assert_eq!(deltas.len(), 1);
+    assert_eq!(deltas[0].text, "blocking:世界");
+    assert_eq!(deltas[0].provider.as_deref(), Some("blocking"));
+}
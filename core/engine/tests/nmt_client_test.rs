@@ -74,9 +74,13 @@ async fn test_adapter_translate() {
             text: "你好".to_string(),
             confidence: 1.0,
             is_final: true,
+            stable_prefix: None,
         },
         target_language: "en".to_string(),
         wait_k: None,
+        decoding: None,
+        committed_prefix: None,
+        source_language: None,
     };
 
     let response = adapter.translate(request).await;
@@ -94,9 +98,13 @@ async fn test_adapter_translate() {
             text: "你好".to_string(),
             confidence: 1.0,
             is_final: true,
+            stable_prefix: None,
         },
         target_language: "en".to_string(),
         wait_k: None,
+        decoding: None,
+        committed_prefix: None,
+        source_language: None,
     };
 
     let response = adapter.translate(request).await;
@@ -0,0 +1,58 @@
+//! M2M100 增量 KV-cache 解码一致性测试
+//!
+//! 验证增量解码（复用 present KV）与非增量解码（每步完整序列）的贪婪解码输出完全一致。
+//! 模型目录不存在时跳过。
+
+use std::path::PathBuf;
+use std::time::Instant;
+
+use core_engine::nmt_incremental::{M2M100DecodeMode, M2M100NmtOnnx};
+
+fn assert_parity(model_dir: &str, test_cases: &[&str]) {
+    let model_dir = PathBuf::from(model_dir);
+    if !model_dir.exists() {
+        eprintln!("[SKIP] 模型目录不存在: {}", model_dir.display());
+        return;
+    }
+
+    let nmt = M2M100NmtOnnx::new_from_dir(&model_dir)
+        .expect("Failed to load M2M100 NMT");
+
+    for source_text in test_cases {
+        let start = Instant::now();
+        let non_incremental = nmt
+            .translate_with_mode(source_text, M2M100DecodeMode::NonIncremental)
+            .expect("Non-incremental translation failed");
+        let non_incremental_ms = start.elapsed().as_millis();
+
+        let start = Instant::now();
+        let incremental = nmt
+            .translate_with_mode(source_text, M2M100DecodeMode::Incremental)
+            .expect("Incremental translation failed");
+        let incremental_ms = start.elapsed().as_millis();
+
+        println!(
+            "'{}' -> '{}' (non-incremental: {}ms, incremental: {}ms)",
+            source_text, incremental, non_incremental_ms, incremental_ms
+        );
+        assert_eq!(incremental, non_incremental, "增量解码结果应与非增量解码一致: '{}'", source_text);
+    }
+}
+
+#[test]
+#[ignore] // 需要本地 ONNX 模型
+fn test_kv_cache_parity_zh_to_en() {
+    assert_parity(
+        "models/nmt/m2m100-zh-en",
+        &["你好，世界", "欢迎来到我们的系统", "这是一个测试"],
+    );
+}
+
+#[test]
+#[ignore] // 需要本地 ONNX 模型
+fn test_kv_cache_parity_en_to_zh() {
+    assert_parity(
+        "models/nmt/m2m100-en-zh",
+        &["Hello, world", "Welcome to our system", "This is a test"],
+    );
+}
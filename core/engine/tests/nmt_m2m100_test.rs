@@ -43,12 +43,16 @@ async fn test_nmt_zh_to_en() {
             text: source_text.to_string(),
             confidence: 1.0,
             is_final: true,
+            stable_prefix: None,
         };
         
         let request = TranslationRequest {
             transcript,
             target_language: "en".to_string(),
             wait_k: None,
+            decoding: None,
+            committed_prefix: None,
+            source_language: None,
         };
         
         let response = NmtIncremental::translate(&nmt, request).await
@@ -121,12 +125,16 @@ async fn test_nmt_en_to_zh() {
             text: source_text.to_string(),
             confidence: 1.0,
             is_final: true,
+            stable_prefix: None,
         };
         
         let request = TranslationRequest {
             transcript,
             target_language: "zh".to_string(),
             wait_k: None,
+            decoding: None,
+            committed_prefix: None,
+            source_language: None,
         };
         
         let response = NmtIncremental::translate(&nmt, request).await
@@ -0,0 +1,205 @@
+//! Marian 多语言对模型注册表测试
+//!
+//! 使用假模型目录和记录加载次数的假模型验证：
+//! - 扫描基础目录识别语言对，不在扫描时加载模型
+//! - 按请求的语言对路由，没有直接语言对时经英语中转
+//! - 超出内存预算时按 LRU 淘汰模型
+//! - 加载模型时不阻塞其他语言对的请求，同一语言对的并发请求只加载一次
+
+use std::fs;
+use std::path::Path;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use tempfile::TempDir;
+use core_engine::nmt_incremental::{MarianModelLoader, MarianModelRegistry};
+use core_engine::*;
+
+/// 把文本标记为 "[src-tgt]text" 的假模型
+struct TaggingModel {
+    pair: LanguagePair,
+}
+
+#[async_trait]
+impl NmtIncremental for TaggingModel {
+    async fn initialize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn translate(&self, request: TranslationRequest) -> EngineResult<TranslationResponse> {
+        Ok(TranslationResponse {
+            translated_text: format!("[{}-{}]{}", self.pair.source, self.pair.target, request.transcript.text),
+            is_stable: true,
+            speaker_id: None,
+            source_text: None,
+            source_audio_duration_ms: None,
+            source_language: None,
+            quality_metrics: None,
+            committed_text: None,
+            provider: None,
+        })
+    }
+
+    async fn finalize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+/// 创建假模型目录（每个目录一个指定大小的 model.onnx）
+fn model_base_dir(dirs: &[&str], model_bytes: usize) -> TempDir {
+    let base_dir = TempDir::new().unwrap();
+    for dir in dirs {
+        let model_dir = base_dir.path().join(dir);
+        fs::create_dir(&model_dir).unwrap();
+        fs::write(model_dir.join("model.onnx"), vec![0u8; model_bytes]).unwrap();
+    }
+    base_dir
+}
+
+fn registry(base_dir: &Path, loads: Arc<Mutex<Vec<String>>>) -> MarianModelRegistry {
+    let loader: MarianModelLoader = Arc::new(move |_dir: &Path, pair: LanguagePair| {
+        loads.lock().unwrap().push(pair.to_model_dir_name());
+        let model: Arc<dyn NmtIncremental> = Arc::new(TaggingModel { pair });
+        Ok(model)
+    });
+    MarianModelRegistry::scan(base_dir).unwrap().with_loader(loader)
+}
+
+fn request(text: &str, source: Option<&str>, target: &str) -> TranslationRequest {
+    TranslationRequest {
+        transcript: PartialTranscript {
+            text: text.to_string(),
+            confidence: 1.0,
+            is_final: true,
+            stable_prefix: None,
+        },
+        target_language: target.to_string(),
+        wait_k: None,
+        speaker_id: None,
+        decoding: None,
+        committed_prefix: None,
+        source_language: source.map(str::to_string),
+    }
+}
+
+#[tokio::test]
+async fn test_scan_and_lazy_load() {
+    let base_dir = model_base_dir(&["marian-en-zh", "marian-zh-en", "m2m100-en-zh", "not-a-model"], 10);
+    let loads = Arc::new(Mutex::new(Vec::new()));
+    let registry = registry(base_dir.path(), Arc::clone(&loads));
+
+    let pairs: Vec<String> = registry.available_pairs().iter().map(|p| p.to_model_dir_name()).collect();
+    assert_eq!(pairs, vec!["marian-en-zh", "marian-zh-en"]);
+    assert!(loads.lock().unwrap().is_empty(), "scan should not load models");
+
+    let response = registry.translate(request("hello", Some("en-US"), "zh")).await.unwrap();
+    assert_eq!(response.translated_text, "[en-zh]hello");
+    assert_eq!(response.source_language.as_deref(), Some("en"));
+
+    // 已加载的模型直接复用
+    registry.translate(request("world", Some("en"), "zh")).await.unwrap();
+    assert_eq!(*loads.lock().unwrap(), vec!["marian-en-zh"]);
+
+    // 没有源语言时，只有唯一候选语言对才能推断
+    let response = registry.translate(request("你好", None, "en")).await.unwrap();
+    assert_eq!(response.translated_text, "[zh-en]你好");
+}
+
+#[tokio::test]
+async fn test_pivot_through_english() {
+    let base_dir = model_base_dir(&["marian-ja-en", "marian-en-zh"], 10);
+    let loads = Arc::new(Mutex::new(Vec::new()));
+    let registry = registry(base_dir.path(), loads);
+
+    let route = registry.route(LanguageCode::Ja, LanguageCode::Zh).unwrap();
+    assert_eq!(route.len(), 2);
+    assert!(registry.route(LanguageCode::Zh, LanguageCode::Ja).is_none());
+
+    let response = registry.translate(request("こんにちは", Some("ja"), "zh")).await.unwrap();
+    assert_eq!(response.translated_text, "[en-zh][ja-en]こんにちは");
+    assert_eq!(response.source_text.as_deref(), Some("こんにちは"));
+    assert_eq!(response.source_language.as_deref(), Some("ja"));
+
+    let deltas: Vec<TranslationDelta> = registry
+        .translate_stream(request("こんにちは", Some("ja"), "zh"))
+        .await
+        .unwrap()
+        .map(|delta| delta.unwrap())
+        .collect()
+        .await;
+    let text: String = deltas.iter().map(|d| d.text.as_str()).collect();
+    assert_eq!(text, "[en-zh][ja-en]こんにちは");
+    assert!(deltas.last().unwrap().is_last);
+
+    assert!(registry.translate(request("hola", Some("es"), "zh")).await.is_err());
+}
+
+#[tokio::test]
+async fn test_lru_eviction_under_memory_budget() {
+    let base_dir = model_base_dir(&["marian-en-zh", "marian-zh-en", "marian-en-ja"], 100);
+    let loads = Arc::new(Mutex::new(Vec::new()));
+    // 预算只够同时保留两个模型
+    let registry = registry(base_dir.path(), Arc::clone(&loads)).with_memory_budget(250);
+
+    registry.translate(request("a", Some("en"), "zh")).await.unwrap();
+    registry.translate(request("b", Some("zh"), "en")).await.unwrap();
+    // 使用 en-zh，使 zh-en 成为最久未使用
+    registry.translate(request("c", Some("en"), "zh")).await.unwrap();
+    registry.translate(request("d", Some("en"), "ja")).await.unwrap();
+
+    let loaded: Vec<String> = registry.loaded_pairs().iter().map(|p| p.to_model_dir_name()).collect();
+    assert_eq!(loaded, vec!["marian-en-zh", "marian-en-ja"]);
+
+    // 被淘汰的模型再次使用时重新加载
+    registry.translate(request("e", Some("zh"), "en")).await.unwrap();
+    assert_eq!(
+        *loads.lock().unwrap(),
+        vec!["marian-en-zh", "marian-zh-en", "marian-en-ja", "marian-zh-en"]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_slow_load_does_not_block_other_pairs() {
+    let base_dir = model_base_dir(&["marian-en-zh", "marian-zh-en"], 10);
+    let loads = Arc::new(Mutex::new(Vec::new()));
+    let (release_tx, release_rx) = mpsc::channel::<()>();
+    let release_rx = Mutex::new(release_rx);
+    let loads_for_loader = Arc::clone(&loads);
+    let loader: MarianModelLoader = Arc::new(move |_dir: &Path, pair: LanguagePair| {
+        loads_for_loader.lock().unwrap().push(pair.to_model_dir_name());
+        if pair.source == LanguageCode::Zh {
+            // zh-en 的加载一直阻塞，直到测试放行
+            release_rx.lock().unwrap().recv_timeout(Duration::from_secs(5)).unwrap();
+        }
+        let model: Arc<dyn NmtIncremental> = Arc::new(TaggingModel { pair });
+        Ok(model)
+    });
+    let registry = Arc::new(MarianModelRegistry::scan(base_dir.path()).unwrap().with_loader(loader));
+
+    // 两个 zh-en 请求等待同一次加载
+    let slow: Vec<_> = (0..2)
+        .map(|idx| {
+            let registry = Arc::clone(&registry);
+            tokio::spawn(async move { registry.translate(request(&format!("慢{}", idx), Some("zh"), "en")).await })
+        })
+        .collect();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // 其他语言对不等待 zh-en 的加载
+    let response = tokio::time::timeout(Duration::from_secs(1), registry.translate(request("fast", Some("en"), "zh")))
+        .await
+        .expect("other pairs should not wait for a loading model")
+        .unwrap();
+    assert_eq!(response.translated_text, "[en-zh]fast");
+
+    release_tx.send(()).unwrap();
+    for task in slow {
+        let response = task.await.unwrap().unwrap();
+        assert!(response.translated_text.starts_with("[zh-en]"), "{}", response.translated_text);
+    }
+    let zh_en_loads = loads.lock().unwrap().iter().filter(|pair| *pair == "marian-zh-en").count();
+    assert_eq!(zh_en_loads, 1);
+    assert_eq!(registry.loaded_pairs().len(), 2);
+}
@@ -0,0 +1,307 @@
+//! 流式翻译 → 逐分句 TTS 流程测试
+//!
+//! 验证启用增量播放后：
+//! - NMT 每输出一个 token 发布一次 NmtPartial 事件
+//! - 译文每凑齐一个分句就开始 TTS 合成，不等整句翻译完成
+//! - 最终翻译结果与流式输出拼接的文本一致
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::{stream, StreamExt};
+use core_engine::asr_streaming::AsrStreamingExt;
+use core_engine::event_bus::{ChannelEventBus, EventTopic};
+use core_engine::vad::BoundaryType;
+use core_engine::*;
+
+const FRAME_MS: u64 = 100;
+
+/// 流式输出的译文（逐 token）
+const DELTAS: [&str; 5] = ["Hello,", " world,", " how", " are", " you?"];
+
+/// 每 3 帧报告一次边界的 VAD
+struct EveryThirdFrameVad;
+
+#[async_trait]
+impl VoiceActivityDetector for EveryThirdFrameVad {
+    async fn detect(&self, frame: AudioFrame) -> EngineResult<DetectionOutcome> {
+        let is_boundary = (frame.timestamp_ms / FRAME_MS) % 3 == 2;
+        Ok(DetectionOutcome {
+            is_boundary,
+            confidence: 1.0,
+            frame,
+            boundary_type: if is_boundary { Some(BoundaryType::NaturalPause) } else { None },
+            speech_event: None,
+        })
+    }
+}
+
+/// 边界时返回固定文本的 ASR
+struct FixedAsr {
+    buffer: Mutex<Vec<AudioFrame>>,
+}
+
+#[async_trait]
+impl AsrStreaming for FixedAsr {
+    async fn initialize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn infer(&self, _request: AsrRequest) -> EngineResult<AsrResult> {
+        Err(EngineError::new("FixedAsr only supports boundary inference"))
+    }
+
+    async fn finalize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    fn as_ext(&self) -> Option<&dyn AsrStreamingExt> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl AsrStreamingExt for FixedAsr {
+    fn accumulate_frame(&self, frame: AudioFrame) -> EngineResult<()> {
+        self.buffer.lock().unwrap().push(frame);
+        Ok(())
+    }
+
+    fn get_accumulated_frames(&self) -> EngineResult<Vec<AudioFrame>> {
+        Ok(self.buffer.lock().unwrap().clone())
+    }
+
+    fn clear_buffer(&self) -> EngineResult<()> {
+        self.buffer.lock().unwrap().clear();
+        Ok(())
+    }
+
+    fn set_language(&self, _language: Option<String>) -> EngineResult<()> {
+        Ok(())
+    }
+
+    fn get_language(&self) -> EngineResult<Option<String>> {
+        Ok(Some("zh".to_string()))
+    }
+
+    async fn infer_on_boundary(&self) -> EngineResult<AsrResult> {
+        self.buffer.lock().unwrap().clear();
+        Ok(AsrResult {
+            partial: None,
+            final_transcript: Some(StableTranscript {
+                text: "你好世界你好吗".to_string(),
+                speaker_id: None,
+                language: "zh".to_string(),
+                segments: Vec::new(),
+                language_probabilities: Vec::new(),
+            }),
+        })
+    }
+
+    fn is_streaming_enabled(&self) -> bool {
+        false
+    }
+
+    async fn infer_partial(&self, _current_timestamp_ms: u64) -> EngineResult<Option<PartialTranscript>> {
+        Ok(None)
+    }
+}
+
+/// 逐 token 输出固定译文的 NMT，记录每个 token 的输出顺序
+struct StreamingNmt {
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl NmtIncremental for StreamingNmt {
+    async fn initialize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn translate(&self, _request: TranslationRequest) -> EngineResult<TranslationResponse> {
+        Err(EngineError::new("StreamingNmt only supports streaming translation"))
+    }
+
+    async fn translate_stream<'a>(&'a self, _request: TranslationRequest) -> EngineResult<TranslationStream<'a>> {
+        let log = Arc::clone(&self.log);
+        let deltas = DELTAS
+            .iter()
+            .enumerate()
+            .map(|(idx, text)| TranslationDelta {
+                text: text.to_string(),
+                token_id: Some(idx as i64),
+                probability: Some(0.9),
+                is_last: false,
+                full_text: None,
+                provider: None,
+            })
+            .chain(std::iter::once(TranslationDelta {
+                text: String::new(),
+                token_id: None,
+                probability: None,
+                is_last: true,
+                full_text: None,
+                provider: None,
+            }));
+        Ok(Box::pin(stream::iter(deltas).then(move |delta| {
+            let log = Arc::clone(&log);
+            async move {
+                tokio::task::yield_now().await;
+                log.lock().unwrap().push(format!("nmt:{}", delta.text));
+                Ok(delta)
+            }
+        })))
+    }
+
+    async fn finalize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+/// 记录合成请求的 TTS
+struct RecordingTts {
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl TtsStreaming for RecordingTts {
+    async fn synthesize(&self, request: TtsRequest) -> EngineResult<TtsStreamChunk> {
+        self.log.lock().unwrap().push(format!("tts:{}", request.text));
+        Ok(TtsStreamChunk {
+            audio: request.text.into_bytes(),
+            format: TtsAudioFormat::pcm16(16000, 1),
+            timestamp_ms: 0,
+            is_last: true,
+        })
+    }
+
+    async fn close(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+struct DummyConfig;
+
+#[async_trait]
+impl ConfigManager for DummyConfig {
+    async fn load(&self) -> EngineResult<EngineConfig> {
+        Ok(EngineConfig {
+            mode: "fast".to_string(),
+            source_language: "zh".to_string(),
+            target_language: "en".to_string(),
+        })
+    }
+
+    async fn current(&self) -> EngineResult<EngineConfig> {
+        self.load().await
+    }
+}
+
+struct DummyCache;
+
+#[async_trait]
+impl CacheManager for DummyCache {
+    async fn warm_up(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn purge(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+struct DummyTelemetry;
+
+#[async_trait]
+impl TelemetrySink for DummyTelemetry {
+    async fn record(&self, _datum: TelemetryDatum) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+fn speech_frame(index: u64) -> AudioFrame {
+    AudioFrame {
+        sample_rate: 16000,
+        channels: 1,
+        data: vec![0.1; (16000 * FRAME_MS / 1000) as usize],
+        timestamp_ms: index * FRAME_MS,
+    }
+}
+
+#[tokio::test]
+async fn test_streaming_translation_starts_tts_per_clause() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let event_bus = Arc::new(ChannelEventBus::new());
+    let mut nmt_partials = event_bus.subscribe_receiver(EventTopic("NmtPartial".to_string()));
+    let mut tts_events = event_bus.subscribe_receiver(EventTopic("Tts".to_string()));
+    // 订阅在后台任务中注册
+    tokio::task::yield_now().await;
+
+    let engine = CoreEngineBuilder::new()
+        .event_bus(event_bus)
+        .vad(Arc::new(EveryThirdFrameVad))
+        .asr(Arc::new(FixedAsr {
+            buffer: Mutex::new(Vec::new()),
+        }))
+        .nmt(Arc::new(StreamingNmt { log: Arc::clone(&log) }))
+        .emotion(Arc::new(EmotionStub::new()))
+        .persona(Arc::new(PersonaStub::new()))
+        .tts(Arc::new(RecordingTts { log: Arc::clone(&log) }))
+        .config(Arc::new(DummyConfig))
+        .cache(Arc::new(DummyCache))
+        .telemetry(Arc::new(DummyTelemetry))
+        .with_tts_incremental_playback(true, 0, 50)
+        .build()
+        .expect("builder should succeed");
+    engine.boot().await.expect("boot should succeed");
+
+    let session = engine.create_session(Some("zh".to_string()), Some("en".to_string())).await;
+    let mut result = None;
+    for index in 0..3 {
+        result = engine
+            .process_audio_frame(&session, speech_frame(index))
+            .await
+            .expect("process_audio_frame should succeed");
+    }
+    let result = result.expect("boundary should produce a result");
+
+    let translation = result.translation.expect("streaming translation should succeed");
+    assert_eq!(translation.translated_text, DELTAS.concat());
+    assert_eq!(translation.source_text.as_deref(), Some("你好世界你好吗"));
+    assert!(result.tts.is_some());
+
+    // 第一个分句在译文完成前就开始合成
+    let log = log.lock().unwrap().clone();
+    let first_tts = log.iter().position(|entry| entry == "tts:Hello,").expect("first clause should be synthesized");
+    let last_token = log.iter().position(|entry| entry == "nmt: you?").expect("last token should be emitted");
+    assert!(first_tts < last_token, "TTS should start before translation finishes: {:?}", log);
+    let tts_texts: Vec<&str> = log.iter().filter_map(|entry| entry.strip_prefix("tts:")).collect();
+    assert_eq!(tts_texts, vec!["Hello,", "world,", "how are you?"]);
+
+    // 每个 token 一个 NmtPartial 事件，外加结束事件
+    let mut partial_texts = Vec::new();
+    loop {
+        let event = tokio::time::timeout(Duration::from_secs(1), nmt_partials.recv())
+            .await
+            .expect("NmtPartial event should arrive")
+            .expect("event bus should stay open");
+        partial_texts.push(event.payload["text"].as_str().unwrap().to_string());
+        if event.payload["is_last"].as_bool().unwrap() {
+            assert_eq!(event.payload["translated_text"], DELTAS.concat());
+            break;
+        }
+    }
+    assert_eq!(partial_texts.len(), DELTAS.len() + 1);
+
+    // 只有最后一个分句标记 is_last
+    let mut tts_is_last = Vec::new();
+    for _ in 0..3 {
+        let event = tokio::time::timeout(Duration::from_secs(1), tts_events.recv())
+            .await
+            .expect("Tts event should arrive")
+            .expect("event bus should stay open");
+        tts_is_last.push(event.payload["is_last"].as_bool().unwrap());
+    }
+    assert_eq!(tts_is_last, vec![false, false, true]);
+}
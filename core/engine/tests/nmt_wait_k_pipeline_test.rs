@@ -0,0 +1,308 @@
+//! wait-k 同传流程测试
+//!
+//! 验证会话启用 wait-k 后：
+//! - 说话过程中每个 ASR 部分结果都会触发部分翻译，已提交前缀按 wait-k 增长
+//! - 已提交前缀会回传给 NMT，句子结束时最终翻译沿用该前缀并清空会话中的前缀
+//! - 未启用 wait-k 时不做部分翻译
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use core_engine::asr_streaming::AsrStreamingExt;
+use core_engine::event_bus::ChannelEventBus;
+use core_engine::nmt_incremental::WaitKPolicy;
+use core_engine::vad::BoundaryType;
+use core_engine::*;
+
+const FRAME_MS: u64 = 100;
+const FRAMES_PER_UTTERANCE: u64 = 5;
+
+/// 每 5 帧报告一次边界的 VAD
+struct EveryFifthFrameVad;
+
+#[async_trait]
+impl VoiceActivityDetector for EveryFifthFrameVad {
+    async fn detect(&self, frame: AudioFrame) -> EngineResult<DetectionOutcome> {
+        let index = frame.timestamp_ms / FRAME_MS;
+        let is_boundary = index % FRAMES_PER_UTTERANCE == FRAMES_PER_UTTERANCE - 1;
+        Ok(DetectionOutcome {
+            is_boundary,
+            confidence: 1.0,
+            frame,
+            boundary_type: if is_boundary { Some(BoundaryType::NaturalPause) } else { None },
+            speech_event: None,
+        })
+    }
+}
+
+/// 每帧识别出一个词的流式 ASR（第 n 帧的部分结果为 "w1 ... wn"）
+struct WordPerFrameAsr {
+    buffer: Mutex<Vec<AudioFrame>>,
+}
+
+impl WordPerFrameAsr {
+    fn words(&self) -> String {
+        let count = self.buffer.lock().unwrap().len();
+        (1..=count).map(|i| format!("w{}", i)).collect::<Vec<_>>().join(" ")
+    }
+}
+
+#[async_trait]
+impl AsrStreaming for WordPerFrameAsr {
+    async fn initialize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn infer(&self, _request: AsrRequest) -> EngineResult<AsrResult> {
+        Err(EngineError::new("WordPerFrameAsr only supports boundary inference"))
+    }
+
+    async fn finalize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    fn as_ext(&self) -> Option<&dyn AsrStreamingExt> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl AsrStreamingExt for WordPerFrameAsr {
+    fn accumulate_frame(&self, frame: AudioFrame) -> EngineResult<()> {
+        self.buffer.lock().unwrap().push(frame);
+        Ok(())
+    }
+
+    fn get_accumulated_frames(&self) -> EngineResult<Vec<AudioFrame>> {
+        Ok(self.buffer.lock().unwrap().clone())
+    }
+
+    fn clear_buffer(&self) -> EngineResult<()> {
+        self.buffer.lock().unwrap().clear();
+        Ok(())
+    }
+
+    fn set_language(&self, _language: Option<String>) -> EngineResult<()> {
+        Ok(())
+    }
+
+    fn get_language(&self) -> EngineResult<Option<String>> {
+        Ok(Some("en".to_string()))
+    }
+
+    async fn infer_on_boundary(&self) -> EngineResult<AsrResult> {
+        let text = self.words();
+        self.buffer.lock().unwrap().clear();
+        Ok(AsrResult {
+            partial: None,
+            final_transcript: Some(StableTranscript {
+                text,
+                speaker_id: None,
+                language: "en".to_string(),
+                segments: Vec::new(),
+                language_probabilities: Vec::new(),
+            }),
+        })
+    }
+
+    fn is_streaming_enabled(&self) -> bool {
+        true
+    }
+
+    async fn infer_partial(&self, _current_timestamp_ms: u64) -> EngineResult<Option<PartialTranscript>> {
+        Ok(Some(PartialTranscript {
+            text: self.words(),
+            confidence: 0.9,
+            is_final: false,
+            stable_prefix: None,
+        }))
+    }
+}
+
+/// 逐词“翻译”（转大写）的前缀翻译 NMT，记录收到的请求
+struct UppercaseWaitKNmt {
+    requests: Mutex<Vec<TranslationRequest>>,
+}
+
+#[async_trait]
+impl NmtIncremental for UppercaseWaitKNmt {
+    async fn initialize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn translate(&self, request: TranslationRequest) -> EngineResult<TranslationResponse> {
+        self.requests.lock().unwrap().push(request.clone());
+
+        let source: Vec<&str> = request.transcript.text.split_whitespace().collect();
+        let forced: Vec<String> = request
+            .committed_prefix
+            .as_deref()
+            .map(|prefix| prefix.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default();
+        let mut target = forced.clone();
+        target.extend(source.iter().skip(forced.len()).map(|word| word.to_uppercase()));
+
+        let committed_len = match WaitKPolicy::from_request(request.wait_k) {
+            Some(policy) => policy.committed_len(source.len(), target.len(), forced.len(), request.transcript.is_final),
+            None => target.len(),
+        };
+
+        Ok(TranslationResponse {
+            translated_text: target.join(" "),
+            is_stable: committed_len == target.len(),
+            speaker_id: request.speaker_id,
+            source_text: Some(request.transcript.text),
+            source_audio_duration_ms: None,
+            source_language: None,
+            quality_metrics: None,
+            committed_text: Some(target[..committed_len].join(" ")),
+            provider: None,
+        })
+    }
+
+    async fn finalize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+struct DummyConfig;
+
+#[async_trait]
+impl ConfigManager for DummyConfig {
+    async fn load(&self) -> EngineResult<EngineConfig> {
+        Ok(EngineConfig {
+            mode: "fast".to_string(),
+            source_language: "en".to_string(),
+            target_language: "zh".to_string(),
+        })
+    }
+
+    async fn current(&self) -> EngineResult<EngineConfig> {
+        self.load().await
+    }
+}
+
+struct DummyCache;
+
+#[async_trait]
+impl CacheManager for DummyCache {
+    async fn warm_up(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn purge(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+struct DummyTelemetry;
+
+#[async_trait]
+impl TelemetrySink for DummyTelemetry {
+    async fn record(&self, _datum: TelemetryDatum) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+fn build_engine(nmt: Arc<UppercaseWaitKNmt>) -> CoreEngine {
+    CoreEngineBuilder::new()
+        .event_bus(Arc::new(ChannelEventBus::new()))
+        .vad(Arc::new(EveryFifthFrameVad))
+        .asr(Arc::new(WordPerFrameAsr {
+            buffer: Mutex::new(Vec::new()),
+        }))
+        .nmt(nmt)
+        .emotion(Arc::new(EmotionStub::new()))
+        .persona(Arc::new(PersonaStub::new()))
+        .tts(Arc::new(TtsStub::new()))
+        .config(Arc::new(DummyConfig))
+        .cache(Arc::new(DummyCache))
+        .telemetry(Arc::new(DummyTelemetry))
+        .build()
+        .expect("builder should succeed")
+}
+
+fn speech_frame(index: u64) -> AudioFrame {
+    AudioFrame {
+        sample_rate: 16000,
+        channels: 1,
+        data: vec![0.1; (16000 * FRAME_MS / 1000) as usize],
+        timestamp_ms: index * FRAME_MS,
+    }
+}
+
+#[tokio::test]
+async fn test_wait_k_publishes_growing_committed_prefix() {
+    let nmt = Arc::new(UppercaseWaitKNmt {
+        requests: Mutex::new(Vec::new()),
+    });
+    let engine = build_engine(nmt.clone());
+    engine.boot().await.expect("boot should succeed");
+
+    let session = engine.create_session(Some("en".to_string()), Some("zh".to_string())).await;
+    session.set_wait_k(Some(2));
+
+    let mut partial_translations = Vec::new();
+    for index in 0..FRAMES_PER_UTTERANCE - 1 {
+        let result = engine
+            .process_audio_frame(&session, speech_frame(index))
+            .await
+            .expect("process_audio_frame should succeed")
+            .expect("streaming ASR should produce a partial result");
+        partial_translations.push(result.translation.expect("wait-k should produce a partial translation"));
+    }
+
+    let committed: Vec<String> = partial_translations
+        .iter()
+        .map(|t| t.committed_text.clone().unwrap())
+        .collect();
+    assert_eq!(committed, vec!["", "W1", "W1 W2", "W1 W2 W3"]);
+    assert!(partial_translations.iter().all(|t| !t.is_stable));
+    assert_eq!(session.committed_translation(), Some("W1 W2 W3".to_string()));
+
+    // 句子结束：最终翻译沿用已提交前缀，并清空会话中的前缀
+    let result = engine
+        .process_audio_frame(&session, speech_frame(FRAMES_PER_UTTERANCE - 1))
+        .await
+        .expect("process_audio_frame should succeed")
+        .expect("boundary should produce a result");
+    let final_translation = result.translation.expect("boundary should produce a translation");
+    assert!(final_translation.is_stable);
+    assert_eq!(final_translation.translated_text, "W1 W2 W3 W4 W5");
+    assert_eq!(session.committed_translation(), None);
+
+    let requests = nmt.requests.lock().unwrap();
+    let final_request = requests.last().unwrap();
+    assert!(final_request.transcript.is_final);
+    assert_eq!(final_request.wait_k, Some(2));
+    assert_eq!(final_request.committed_prefix.as_deref(), Some("W1 W2 W3"));
+}
+
+#[tokio::test]
+async fn test_without_wait_k_only_final_is_translated() {
+    let nmt = Arc::new(UppercaseWaitKNmt {
+        requests: Mutex::new(Vec::new()),
+    });
+    let engine = build_engine(nmt.clone());
+    engine.boot().await.expect("boot should succeed");
+
+    let session = engine.create_session(Some("en".to_string()), Some("zh".to_string())).await;
+
+    for index in 0..FRAMES_PER_UTTERANCE - 1 {
+        let result = engine
+            .process_audio_frame(&session, speech_frame(index))
+            .await
+            .expect("process_audio_frame should succeed")
+            .expect("streaming ASR should produce a partial result");
+        assert!(result.translation.is_none());
+    }
+    engine
+        .process_audio_frame(&session, speech_frame(FRAMES_PER_UTTERANCE - 1))
+        .await
+        .expect("process_audio_frame should succeed");
+
+    let requests = nmt.requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].wait_k, None);
+    assert_eq!(requests[0].committed_prefix, None);
+}
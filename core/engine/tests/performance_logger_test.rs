@@ -1,5 +1,6 @@
 //! 性能日志模块测试
 
+use core_engine::nmt_incremental::TranslationQualityMetrics;
 use core_engine::performance_logger::{PerformanceLog, PerformanceLogger};
 
 #[test]
@@ -93,6 +94,50 @@ fn test_performance_log_normal_translation() {
     assert_eq!(log.suspect_translation, Some(false));
 }
 
+#[test]
+fn test_performance_log_suspect_translation_low_confidence() {
+    let mut log = PerformanceLog::new(
+        "test-id".to_string(),
+        "zh".to_string(),
+        "en".to_string(),
+        100,
+        200,
+        150,
+        450,
+        true,
+    );
+    
+    // 文本本身正常，但解码器置信度很低，应该被标记为可疑
+    let metrics = TranslationQualityMetrics::from_log_probs(&[0.02f32.ln(), 0.01f32.ln()]).unwrap();
+    log.check_suspect_translation_with_metrics("你好", "Hello", Some(&metrics));
+    
+    assert_eq!(log.suspect_translation, Some(true));
+    // 困惑度 = exp(-平均 log 概率) = 1 / sqrt(0.02 * 0.01) ≈ 70.71
+    assert!((log.perplexity.unwrap() - 70.71).abs() < 0.01);
+    assert!(log.to_json().contains("perplexity"));
+}
+
+#[test]
+fn test_performance_log_confident_translation_records_metrics() {
+    let mut log = PerformanceLog::new(
+        "test-id".to_string(),
+        "zh".to_string(),
+        "en".to_string(),
+        100,
+        200,
+        150,
+        450,
+        true,
+    );
+    
+    let metrics = TranslationQualityMetrics::from_log_probs(&[0.9f32.ln(), 0.6f32.ln()]).unwrap();
+    log.check_suspect_translation_with_metrics("你好", "Hello", Some(&metrics));
+    
+    assert_eq!(log.suspect_translation, Some(false));
+    assert!((log.avg_probability.unwrap() - 0.75).abs() < 1e-5);
+    assert!((log.min_probability.unwrap() - 0.6).abs() < 1e-5);
+}
+
 #[test]
 fn test_performance_log_to_json() {
     let log = PerformanceLog::new(
@@ -114,6 +159,25 @@ fn test_performance_log_to_json() {
     assert!(json.contains("200"));
     assert!(json.contains("150"));
     assert!(json.contains("450"));
+    // 未记录 NMT 后端时不输出该字段
+    assert!(!json.contains("nmt_provider"));
+}
+
+#[test]
+fn test_performance_log_records_nmt_provider() {
+    let mut log = PerformanceLog::new(
+        "test-id".to_string(),
+        "zh".to_string(),
+        "en".to_string(),
+        100,
+        200,
+        150,
+        450,
+        true,
+    );
+    log.nmt_provider = Some("remote".to_string());
+
+    assert!(log.to_json().contains(r#""nmt_provider":"remote""#));
 }
 
 #[test]
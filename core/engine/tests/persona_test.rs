@@ -10,6 +10,8 @@ async fn test_persona_stub() {
         text: "Hello, this is a test.".to_string(),
         speaker_id: None,
         language: "en".to_string(),
+        segments: Vec::new(),
+        language_probabilities: Vec::new(),
     };
     
     let context = PersonaContext {
@@ -37,6 +39,8 @@ async fn test_rule_based_formal_chinese() {
         text: "帮我做这个".to_string(),
         speaker_id: None,
         language: "zh".to_string(),
+        segments: Vec::new(),
+        language_probabilities: Vec::new(),
     };
     
     let context = PersonaContext {
@@ -61,6 +65,8 @@ async fn test_rule_based_casual_chinese() {
         text: "请您帮我做这个".to_string(),
         speaker_id: None,
         language: "zh".to_string(),
+        segments: Vec::new(),
+        language_probabilities: Vec::new(),
     };
     
     let context = PersonaContext {
@@ -85,6 +91,8 @@ async fn test_rule_based_friendly_chinese() {
         text: "你好".to_string(),
         speaker_id: None,
         language: "zh".to_string(),
+        segments: Vec::new(),
+        language_probabilities: Vec::new(),
     };
     
     let context = PersonaContext {
@@ -109,6 +117,8 @@ async fn test_rule_based_formal_english() {
         text: "I don't want to do this".to_string(),
         speaker_id: None,
         language: "en".to_string(),
+        segments: Vec::new(),
+        language_probabilities: Vec::new(),
     };
     
     let context = PersonaContext {
@@ -134,6 +144,8 @@ async fn test_rule_based_casual_english() {
         text: "I do not want to do this".to_string(),
         speaker_id: None,
         language: "en".to_string(),
+        segments: Vec::new(),
+        language_probabilities: Vec::new(),
     };
     
     let context = PersonaContext {
@@ -158,6 +170,8 @@ async fn test_rule_based_friendly_english() {
         text: "Hello".to_string(),
         speaker_id: None,
         language: "en".to_string(),
+        segments: Vec::new(),
+        language_probabilities: Vec::new(),
     };
     
     let context = PersonaContext {
@@ -190,6 +204,8 @@ async fn test_rule_based_multiple_combinations() {
             text: input.to_string(),
             speaker_id: None,
             language: culture.to_string(),
+            segments: Vec::new(),
+            language_probabilities: Vec::new(),
         };
         
         let context = PersonaContext {
@@ -0,0 +1,389 @@
+//! 会话隔离测试
+//!
+//! 验证每个 `Session` 拥有独立的语言对和状态，互不干扰。
+//! 两个会话的帧交替送入同一个引擎时，VAD 状态、`asr_buffer` / `audio_buffer` 和发布的事件各自独立。
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+use core_engine::asr_streaming::AsrStreamingExt;
+use core_engine::event_bus::{ChannelEventBus, CoreEvent, EventTopic};
+use core_engine::vad::TimeBasedVad;
+use core_engine::*;
+
+const FRAME_MS: u64 = 100;
+
+/// 每个会话送入的帧数（TimeBasedVad 每 300ms 一个边界：4 + 3 + 3 帧）
+const FRAMES_PER_SESSION: u64 = 10;
+
+struct DummyAsr;
+
+#[async_trait]
+impl AsrStreaming for DummyAsr {
+    async fn initialize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn infer(&self, _request: AsrRequest) -> EngineResult<AsrResult> {
+        Ok(AsrResult {
+            partial: None,
+            final_transcript: None,
+        })
+    }
+
+    async fn finalize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+/// 按样本值标记帧的来源会话（A 的帧全是 0.1，B 的帧全是 0.2）
+fn marker(samples: &[f32]) -> String {
+    let mut markers: Vec<&str> = Vec::new();
+    for &sample in samples {
+        let name = if sample < 0.15 { "alpha" } else { "beta" };
+        if !markers.contains(&name) {
+            markers.push(name);
+        }
+    }
+    format!("{} {}", markers.join("+"), samples.len() / (16000 * FRAME_MS as usize / 1000))
+}
+
+/// 识别结果为"来源标记 + 帧数"的 ASR：混入其他会话的帧时标记变成 "alpha+beta"
+///
+/// 非连续模式下通过 `AsrStreamingExt` 在边界推理，连续模式下通过 `infer` 处理合并后的片段
+struct MarkerAsr {
+    buffer: Mutex<Vec<AudioFrame>>,
+}
+
+impl MarkerAsr {
+    fn result(text: String) -> AsrResult {
+        AsrResult {
+            partial: None,
+            final_transcript: Some(StableTranscript {
+                text,
+                speaker_id: None,
+                language: "en".to_string(),
+                segments: Vec::new(),
+                language_probabilities: Vec::new(),
+            }),
+        }
+    }
+}
+
+#[async_trait]
+impl AsrStreaming for MarkerAsr {
+    async fn initialize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn infer(&self, request: AsrRequest) -> EngineResult<AsrResult> {
+        Ok(Self::result(marker(&request.frame.data)))
+    }
+
+    async fn finalize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    fn as_ext(&self) -> Option<&dyn AsrStreamingExt> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl AsrStreamingExt for MarkerAsr {
+    fn accumulate_frame(&self, frame: AudioFrame) -> EngineResult<()> {
+        self.buffer.lock().unwrap().push(frame);
+        Ok(())
+    }
+
+    fn get_accumulated_frames(&self) -> EngineResult<Vec<AudioFrame>> {
+        Ok(self.buffer.lock().unwrap().clone())
+    }
+
+    fn clear_buffer(&self) -> EngineResult<()> {
+        self.buffer.lock().unwrap().clear();
+        Ok(())
+    }
+
+    fn set_language(&self, _language: Option<String>) -> EngineResult<()> {
+        Ok(())
+    }
+
+    fn get_language(&self) -> EngineResult<Option<String>> {
+        Ok(Some("en".to_string()))
+    }
+
+    async fn infer_on_boundary(&self) -> EngineResult<AsrResult> {
+        let frames = std::mem::take(&mut *self.buffer.lock().unwrap());
+        let samples: Vec<f32> = frames.iter().flat_map(|f| f.data.iter().copied()).collect();
+        Ok(Self::result(marker(&samples)))
+    }
+
+    fn is_streaming_enabled(&self) -> bool {
+        false
+    }
+}
+
+struct DummyNmt;
+
+#[async_trait]
+impl NmtIncremental for DummyNmt {
+    async fn initialize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn translate(&self, request: TranslationRequest) -> EngineResult<TranslationResponse> {
+        Ok(TranslationResponse {
+            translated_text: format!("[{}] {}", request.target_language, request.transcript.text),
+            is_stable: true,
+            speaker_id: request.speaker_id,
+            source_text: Some(request.transcript.text),
+            source_audio_duration_ms: None,
+            source_language: None,
+            quality_metrics: None,
+            committed_text: None,
+            provider: None,
+        })
+    }
+
+    async fn finalize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+struct DummyConfig;
+
+#[async_trait]
+impl ConfigManager for DummyConfig {
+    async fn load(&self) -> EngineResult<EngineConfig> {
+        Ok(EngineConfig {
+            mode: "fast".to_string(),
+            source_language: "en".to_string(),
+            target_language: "zh".to_string(),
+        })
+    }
+
+    async fn current(&self) -> EngineResult<EngineConfig> {
+        self.load().await
+    }
+}
+
+struct DummyCache;
+
+#[async_trait]
+impl CacheManager for DummyCache {
+    async fn warm_up(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn purge(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+struct DummyTelemetry;
+
+#[async_trait]
+impl TelemetrySink for DummyTelemetry {
+    async fn record(&self, _datum: TelemetryDatum) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+fn build_engine() -> CoreEngine {
+    CoreEngineBuilder::new()
+        .event_bus(Arc::new(ChannelEventBus::new()))
+        .vad(Arc::new(TimeBasedVad::new(1000)))
+        .asr(Arc::new(DummyAsr))
+        .nmt(Arc::new(DummyNmt))
+        .emotion(Arc::new(EmotionStub::new()))
+        .persona(Arc::new(PersonaStub::new()))
+        .tts(Arc::new(TtsStub::new()))
+        .config(Arc::new(DummyConfig))
+        .cache(Arc::new(DummyCache))
+        .telemetry(Arc::new(DummyTelemetry))
+        .with_continuous_mode(true, 5000, 200)
+        .build()
+        .expect("builder should succeed")
+}
+
+fn build_marker_engine(event_bus: Arc<ChannelEventBus>, continuous: bool) -> CoreEngine {
+    let builder = CoreEngineBuilder::new()
+        .event_bus(event_bus)
+        .vad(Arc::new(TimeBasedVad::new(300)))
+        .asr(Arc::new(MarkerAsr {
+            buffer: Mutex::new(Vec::new()),
+        }))
+        .nmt(Arc::new(DummyNmt))
+        .emotion(Arc::new(EmotionStub::new()))
+        .persona(Arc::new(PersonaStub::new()))
+        .tts(Arc::new(TtsStub::new()))
+        .config(Arc::new(DummyConfig))
+        .cache(Arc::new(DummyCache))
+        .telemetry(Arc::new(DummyTelemetry));
+    let builder = if continuous {
+        builder.with_continuous_mode(true, 5000, 200)
+    } else {
+        builder
+    };
+    builder.build().expect("builder should succeed")
+}
+
+fn frame(value: f32, timestamp_ms: u64) -> AudioFrame {
+    AudioFrame {
+        sample_rate: 16000,
+        channels: 1,
+        data: vec![value; (16000 * FRAME_MS / 1000) as usize],
+        timestamp_ms,
+    }
+}
+
+/// 送入一个会话的所有帧，每帧之后让出执行权，使两个会话的帧交替处理
+async fn feed(engine: &CoreEngine, session: &Session, value: f32, start_ms: u64) {
+    for index in 0..FRAMES_PER_SESSION {
+        engine
+            .process_audio_frame(session, frame(value, start_ms + index * FRAME_MS))
+            .await
+            .expect("process_audio_frame should succeed");
+        tokio::task::yield_now().await;
+    }
+}
+
+/// 取出已发布的事件，按 session_id 分组
+fn events_by_session(receiver: &mut mpsc::UnboundedReceiver<CoreEvent>) -> HashMap<String, Vec<CoreEvent>> {
+    let mut events: HashMap<String, Vec<CoreEvent>> = HashMap::new();
+    while let Ok(event) = receiver.try_recv() {
+        let session_id = event.payload["session_id"].as_str().expect("event should carry session_id").to_string();
+        events.entry(session_id).or_default().push(event);
+    }
+    events
+}
+
+/// 两个会话并发送帧：A 的时间轴从 0 开始，B 的从 60s 开始
+///
+/// 共享 VAD 状态时 B 的第一帧就会被判为边界；共享缓冲区时识别结果会出现 "alpha+beta"
+async fn check_concurrent_sessions_isolated(continuous: bool) {
+    let event_bus = Arc::new(ChannelEventBus::new());
+    let mut asr_events = event_bus.subscribe_receiver(EventTopic("AsrFinal".to_string()));
+    let mut vad_events = event_bus.subscribe_receiver(EventTopic("VadSpeechStart".to_string()));
+    // 订阅在后台任务中注册
+    tokio::task::yield_now().await;
+
+    let engine = build_marker_engine(event_bus, continuous);
+    engine.boot().await.expect("boot should succeed");
+    let session_a = engine.create_session(None, None).await;
+    let session_b = engine.create_session(None, None).await;
+
+    tokio::join!(
+        feed(&engine, &session_a, 0.1, 0),
+        feed(&engine, &session_b, 0.2, 60_000),
+    );
+
+    let asr_events = events_by_session(&mut asr_events);
+    let vad_events = events_by_session(&mut vad_events);
+    assert_eq!(asr_events.len(), 2, "only the two sessions should publish events");
+    for (session, name, start_ms) in [(&session_a, "alpha", 0), (&session_b, "beta", 60_000)] {
+        let texts: Vec<&str> = asr_events[session.id()]
+            .iter()
+            .map(|event| event.payload["text"].as_str().unwrap())
+            .collect();
+        let expected: Vec<String> = [4, 3, 3].iter().map(|frames| format!("{} {}", name, frames)).collect();
+        assert_eq!(texts, expected, "session {} transcripts", name);
+
+        let starts: Vec<u64> = vad_events[session.id()]
+            .iter()
+            .map(|event| event.payload["start_ms"].as_u64().unwrap())
+            .collect();
+        assert_eq!(starts, vec![start_ms, start_ms + 400, start_ms + 700], "session {} speech starts", name);
+    }
+}
+
+#[tokio::test]
+async fn test_concurrent_sessions_keep_separate_asr_buffers() {
+    check_concurrent_sessions_isolated(false).await;
+}
+
+#[tokio::test]
+async fn test_concurrent_sessions_keep_separate_audio_buffers() {
+    check_concurrent_sessions_isolated(true).await;
+}
+
+#[tokio::test]
+async fn test_session_defaults_from_config() {
+    let engine = build_engine();
+    let session = engine.create_session(None, None).await;
+
+    assert_eq!(session.source_language(), Some("en".to_string()));
+    assert_eq!(session.target_language(), "zh");
+}
+
+#[tokio::test]
+async fn test_sessions_have_independent_languages() {
+    let engine = build_engine();
+    let session_a = engine.create_session(Some("en".to_string()), Some("zh".to_string())).await;
+    let session_b = engine.create_session(Some("zh".to_string()), Some("en".to_string())).await;
+
+    assert_ne!(session_a.id(), session_b.id());
+
+    // 修改会话 A 的语言对不应影响会话 B
+    session_a.set_languages(Some("ja".to_string()), "de".to_string());
+
+    assert_eq!(
+        session_a.languages(),
+        SessionLanguages {
+            source_language: Some("ja".to_string()),
+            target_language: "de".to_string(),
+        }
+    );
+    assert_eq!(
+        session_b.languages(),
+        SessionLanguages {
+            source_language: Some("zh".to_string()),
+            target_language: "en".to_string(),
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_session_reset_keeps_languages() {
+    let engine = build_engine();
+    let session = engine.create_session(Some("zh".to_string()), Some("en".to_string())).await;
+
+    session.reset().await.expect("reset should succeed");
+
+    assert_eq!(session.source_language(), Some("zh".to_string()));
+    assert_eq!(session.target_language(), "en");
+}
+
+#[tokio::test]
+async fn test_user_session_keeps_user_id() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let engine = CoreEngineBuilder::new()
+        .event_bus(Arc::new(ChannelEventBus::new()))
+        .vad(Arc::new(TimeBasedVad::new(1000)))
+        .asr(Arc::new(DummyAsr))
+        .nmt(Arc::new(DummyNmt))
+        .emotion(Arc::new(EmotionStub::new()))
+        .persona(Arc::new(PersonaStub::new()))
+        .tts(Arc::new(TtsStub::new()))
+        .config(Arc::new(DummyConfig))
+        .cache(Arc::new(DummyCache))
+        .telemetry(Arc::new(DummyTelemetry))
+        .with_vad_state_persistence(dir.path())
+        .build()
+        .expect("builder should succeed");
+
+    let session = engine
+        .create_session_for_user(None, None, Some("alice".to_string()))
+        .await;
+    assert_eq!(session.user_id(), Some("alice"));
+    // TimeBasedVad 没有自适应状态：保存是空操作
+    engine.save_session_vad_state(&session).expect("save should succeed");
+    assert!(std::fs::read_dir(dir.path()).unwrap().next().is_none());
+
+    let anonymous = engine.create_session_for_user(None, None, Some(String::new())).await;
+    assert_eq!(anonymous.user_id(), None);
+    assert_eq!(engine.create_session(None, None).await.user_id(), None);
+}
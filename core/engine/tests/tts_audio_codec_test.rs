@@ -0,0 +1,197 @@
+//! TTS 输出编码测试
+//!
+//! 验证：
+//! - 编码名称（客户端在 config 消息 / S2S 请求中使用）和 MIME 类型
+//! - `pcm16` / `wav` 在进程内编码，不依赖 ffmpeg
+//! - ffmpeg 不可用时只提供 `pcm16` / `wav`，请求 Opus 返回错误
+//! - 流式编码：同一次合成的音频块拼接后是一个完整的文件（只有一个文件头）
+//! - （需要 ffmpeg，`available_codecs` 不包含时跳过）Ogg Opus / WebM Opus / MP3 的一次性和流式编码
+
+use core_engine::tts_streaming::{
+    AudioCodec, AudioCodecConfig, AudioEncoding, PcmAudio, TtsAudioEncoder, TtsAudioFormat, TtsStreamChunk,
+};
+
+/// 200ms 440Hz 正弦波，22.05kHz 单声道 WAV
+fn wav_chunk() -> TtsStreamChunk {
+    let samples = (0..4410)
+        .map(|i| ((2.0 * std::f32::consts::PI * 440.0 * i as f32 / 22050.0).sin() * 8000.0) as i16)
+        .collect();
+    let audio = PcmAudio { samples, sample_rate: 22050, channels: 1 };
+    TtsStreamChunk {
+        audio: audio.encode(AudioEncoding::Wav),
+        format: TtsAudioFormat::wav16(22050, 1),
+        timestamp_ms: 0,
+        is_last: true,
+    }
+}
+
+fn encoder_without_ffmpeg() -> TtsAudioEncoder {
+    TtsAudioEncoder::new(AudioCodecConfig {
+        ffmpeg_path: "/nonexistent/ffmpeg".to_string(),
+        ..AudioCodecConfig::default()
+    })
+}
+
+#[test]
+fn test_codec_names_and_mime_types() {
+    let codec: AudioCodec = serde_json::from_str(r#""ogg_opus""#).unwrap();
+    assert_eq!(codec, AudioCodec::OggOpus);
+    assert_eq!(codec.mime_type(), "audio/ogg; codecs=opus");
+    assert!(codec.requires_ffmpeg());
+    assert_eq!(serde_json::to_string(&AudioCodec::Pcm16).unwrap(), r#""pcm16""#);
+    assert_eq!(AudioCodec::Mp3.mime_type(), "audio/mpeg");
+    assert!(!AudioCodec::Wav.requires_ffmpeg());
+    assert!(serde_json::from_str::<AudioCodec>(r#""aac""#).is_err());
+
+    let config: AudioCodecConfig = toml::from_str("opus_bitrate_kbps = 32").unwrap();
+    assert_eq!(config.opus_bitrate_kbps, 32);
+    assert_eq!(config.ffmpeg_path, "ffmpeg");
+}
+
+#[tokio::test]
+async fn test_pcm16_and_wav_encoded_in_process() {
+    let encoder = encoder_without_ffmpeg();
+    let chunk = wav_chunk();
+
+    // WAV -> 裸 PCM：去掉文件头，采样率不变
+    let pcm = encoder.encode(&chunk, AudioCodec::Pcm16).await.unwrap();
+    assert_eq!(pcm.codec, AudioCodec::Pcm16);
+    assert_eq!(pcm.sample_rate, 22050);
+    assert_eq!(pcm.data.len(), 4410 * 2);
+
+    let wav = encoder.encode(&chunk, AudioCodec::Wav).await.unwrap();
+    assert_eq!(wav.data, chunk.audio);
+
+    let empty = TtsStreamChunk { audio: Vec::new(), ..wav_chunk() };
+    assert!(encoder.encode(&empty, AudioCodec::OggOpus).await.unwrap().data.is_empty());
+}
+
+#[tokio::test]
+async fn test_opus_unavailable_without_ffmpeg() {
+    let encoder = encoder_without_ffmpeg();
+    assert_eq!(encoder.available_codecs().await, vec![AudioCodec::Pcm16, AudioCodec::Wav]);
+
+    let error = encoder.encode(&wav_chunk(), AudioCodec::OggOpus).await.unwrap_err().to_string();
+    assert!(error.contains("ffmpeg"), "{}", error);
+}
+
+/// 把 600ms 正弦波切成 3 块流式音频：第一块是流式 WAV，后续块是裸 PCM
+fn stream_chunks() -> Vec<TtsStreamChunk> {
+    (0..3)
+        .map(|idx| {
+            let samples = (idx * 4410..(idx + 1) * 4410)
+                .map(|i| ((2.0 * std::f32::consts::PI * 440.0 * i as f32 / 22050.0).sin() * 8000.0) as i16)
+                .collect();
+            let audio = PcmAudio { samples, sample_rate: 22050, channels: 1 };
+            let (audio, format) = if idx == 0 {
+                let mut wav = audio.encode(AudioEncoding::Wav);
+                wav[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+                wav[40..44].copy_from_slice(&u32::MAX.to_le_bytes());
+                (wav, TtsAudioFormat::wav16(22050, 1))
+            } else {
+                (audio.encode(AudioEncoding::Pcm), TtsAudioFormat::pcm16(22050, 1))
+            };
+            TtsStreamChunk { audio, format, timestamp_ms: idx as u64 * 200, is_last: idx == 2 }
+        })
+        .collect()
+}
+
+/// 流式编码所有块，返回拼接后的数据
+async fn encode_stream(encoder: &TtsAudioEncoder, codec: AudioCodec, chunks: &[TtsStreamChunk]) -> Vec<u8> {
+    let mut stream_encoder = encoder.stream_encoder(codec);
+    let mut data = Vec::new();
+    for chunk in chunks {
+        let encoded = stream_encoder.push(chunk).await.unwrap();
+        assert_eq!(encoded.codec, codec);
+        data.extend(encoded.data);
+    }
+    data
+}
+
+fn count_occurrences(data: &[u8], pattern: &[u8]) -> usize {
+    data.windows(pattern.len()).filter(|window| *window == pattern).count()
+}
+
+/// 带所需编码器的 ffmpeg（没有时打印原因并返回 None）
+async fn ffmpeg_encoder(codec: AudioCodec) -> Option<TtsAudioEncoder> {
+    let encoder = TtsAudioEncoder::default();
+    if encoder.available_codecs().await.contains(&codec) {
+        Some(encoder)
+    } else {
+        eprintln!("⚠️  跳过测试: ffmpeg 不可用或不支持 {:?}", codec);
+        None
+    }
+}
+
+#[tokio::test]
+async fn test_pcm16_and_wav_stream_encoding() {
+    let encoder = encoder_without_ffmpeg();
+    let chunks = stream_chunks();
+
+    let pcm = encode_stream(&encoder, AudioCodec::Pcm16, &chunks).await;
+    assert_eq!(pcm.len(), 3 * 4410 * 2);
+
+    // 只有第一块带 WAV 头，拼接后是一个完整的 WAV
+    let wav = encode_stream(&encoder, AudioCodec::Wav, &chunks).await;
+    assert_eq!(count_occurrences(&wav, b"RIFF"), 1);
+    let decoded = PcmAudio::decode(&wav, &TtsAudioFormat::wav16(22050, 1)).unwrap();
+    assert_eq!(decoded.samples.len(), 3 * 4410);
+
+    // 下一次合成重新带文件头
+    let mut stream_encoder = encoder.stream_encoder(AudioCodec::Wav);
+    for chunk in chunks.iter().chain(&chunks) {
+        let encoded = stream_encoder.push(chunk).await.unwrap();
+        assert_eq!(encoded.data.starts_with(b"RIFF"), chunk.timestamp_ms == 0);
+    }
+}
+
+#[tokio::test]
+async fn test_ogg_opus_encoding_with_ffmpeg() {
+    let Some(encoder) = ffmpeg_encoder(AudioCodec::OggOpus).await else {
+        return;
+    };
+
+    let chunk = wav_chunk();
+    let encoded = encoder.encode(&chunk, AudioCodec::OggOpus).await.unwrap();
+    assert_eq!(&encoded.data[0..4], b"OggS");
+    assert_eq!(encoded.sample_rate, 48000);
+    assert!(encoded.data.len() < chunk.audio.len());
+
+    // 流式：一个 Ogg 流（只有一个 OpusHead），而不是每块一个文件
+    let data = encode_stream(&encoder, AudioCodec::OggOpus, &stream_chunks()).await;
+    assert_eq!(&data[0..4], b"OggS");
+    assert_eq!(count_occurrences(&data, b"OpusHead"), 1);
+}
+
+#[tokio::test]
+async fn test_webm_opus_encoding_with_ffmpeg() {
+    let Some(encoder) = ffmpeg_encoder(AudioCodec::WebmOpus).await else {
+        return;
+    };
+    const EBML_MAGIC: &[u8] = &[0x1A, 0x45, 0xDF, 0xA3];
+
+    let encoded = encoder.encode(&wav_chunk(), AudioCodec::WebmOpus).await.unwrap();
+    assert_eq!(&encoded.data[0..4], EBML_MAGIC);
+    assert_eq!(encoded.sample_rate, 48000);
+
+    let data = encode_stream(&encoder, AudioCodec::WebmOpus, &stream_chunks()).await;
+    assert_eq!(&data[0..4], EBML_MAGIC);
+    assert_eq!(count_occurrences(&data, EBML_MAGIC), 1);
+}
+
+#[tokio::test]
+async fn test_mp3_encoding_with_ffmpeg() {
+    let Some(encoder) = ffmpeg_encoder(AudioCodec::Mp3).await else {
+        return;
+    };
+
+    let chunk = wav_chunk();
+    let encoded = encoder.encode(&chunk, AudioCodec::Mp3).await.unwrap();
+    assert_eq!(encoded.sample_rate, 22050);
+    assert!(!encoded.data.is_empty() && encoded.data.len() < chunk.audio.len());
+
+    // 流式：只有一个 ID3 标签（ffmpeg 的 mp3 封装在文件开头写入）
+    let data = encode_stream(&encoder, AudioCodec::Mp3, &stream_chunks()).await;
+    assert!(data.starts_with(b"ID3"));
+    assert_eq!(count_occurrences(&data, b"ID3"), 1);
+}
@@ -1,6 +1,7 @@
 //! TTS 音频增强模块单元测试
 
 use core_engine::tts_audio_enhancement::{AudioEnhancer, AudioEnhancementConfig};
+use core_engine::tts_streaming::{PcmAudio, TtsAudioFormat};
 
 #[test]
 fn test_audio_enhancement_config_default() {
@@ -8,30 +9,47 @@ fn test_audio_enhancement_config_default() {
     assert!(config.enable_fade);
     assert_eq!(config.fade_duration_ms, 20);
     assert!(config.enable_pause);
-    assert_eq!(config.pause_duration_ms, 100);
-    assert_eq!(config.sample_rate, 22050);
-    assert_eq!(config.channels, 1);
+    assert_eq!(config.sentence_end_pause_ms, 250);
+    assert_eq!(config.comma_pause_ms, 150);
 }
 
 #[tokio::test]
 async fn test_enhance_audio_empty() {
     let enhancer = AudioEnhancer::default();
-    
-    // 空音频应该返回错误或空结果
-    let result = enhancer.enhance_audio(&[], true, true, true).await;
+
+    // 空的 WAV 数据应该返回错误
+    let result = enhancer.enhance_audio(&[], &TtsAudioFormat::wav16(22050, 1), true, true, true).await;
     assert!(result.is_err());
 }
 
 #[tokio::test]
 async fn test_enhance_audio_invalid_wav() {
     let enhancer = AudioEnhancer::default();
-    
+
     // 无效的 WAV 数据
     let invalid_data = vec![0u8; 10];
-    let result = enhancer.enhance_audio(&invalid_data, true, true, true).await;
+    let result = enhancer.enhance_audio(&invalid_data, &TtsAudioFormat::wav16(22050, 1), true, true, true).await;
     assert!(result.is_err());
 }
 
-// 注意：完整的 WAV 文件测试需要实际的 WAV 数据
-// 这里只测试配置和基本逻辑
+#[tokio::test]
+async fn test_pause_length_follows_chunk_sample_rate() {
+    let enhancer = AudioEnhancer::default();
+    let samples = vec![1000i16; 1600];
+
+    // 裸 PCM（16kHz）：输出仍是裸 PCM，句末停顿 250ms = 4000 个样本
+    let pcm = PcmAudio { samples: samples.clone(), sample_rate: 16000, channels: 1 };
+    let format = TtsAudioFormat::pcm16(16000, 1);
+    let enhanced = enhancer.enhance_audio(&pcm.encode(format.encoding), &format, true, true, true).await.unwrap();
+    assert_eq!(enhanced.len(), (1600 + 4000) * 2);
 
+    // WAV（22.05kHz）：输出仍是 WAV，停顿按 WAV 头中的采样率计算
+    let wav = PcmAudio { samples, sample_rate: 22050, channels: 1 };
+    let format = TtsAudioFormat::wav16(22050, 1);
+    let enhanced = enhancer.enhance_audio(&wav.encode(format.encoding), &format, true, true, true).await.unwrap();
+    let decoded = PcmAudio::decode(&enhanced, &format).unwrap();
+    assert_eq!(decoded.sample_rate, 22050);
+    assert_eq!(decoded.samples.len(), 1600 + 5512);
+    // fade in：第一个样本被压到 0
+    assert_eq!(decoded.samples[0], 0);
+}
@@ -0,0 +1,151 @@
+//! TTS 音频格式转换测试
+//!
+//! 验证：
+//! - 不同后端的音频（WAV / 裸 PCM、不同采样率和声道数）转换为统一的输出格式
+//! - 格式已经一致时原样返回
+//! - 多个不同格式的音频块合并为一个只带一个 WAV 头的音频块
+//! - 流式合成的分块逐块转换：重采样器跨块保留状态，拼接后与一次性转换相同，只有第一块带 WAV 头
+//! - 客户端请求的格式从 JSON 解析（位深默认 16），无效格式被拒绝
+
+use core_engine::tts_streaming::{
+    AudioEncoding, PcmAudio, TtsAudioConverter, TtsAudioFormat, TtsStreamChunk, TtsStreamConverter,
+};
+
+/// 1kHz 正弦波（多声道时每个声道相同）
+fn tone(sample_rate: u32, channels: u16, ms: u32) -> PcmAudio {
+    let frames = (sample_rate * ms / 1000) as usize;
+    let samples = (0..frames)
+        .map(|i| ((2.0 * std::f32::consts::PI * 1000.0 * i as f32 / sample_rate as f32).sin() * 8000.0) as i16)
+        .flat_map(|sample| std::iter::repeat_n(sample, channels as usize))
+        .collect();
+    PcmAudio { samples, sample_rate, channels }
+}
+
+fn chunk(audio: &PcmAudio, encoding: AudioEncoding, timestamp_ms: u64, is_last: bool) -> TtsStreamChunk {
+    TtsStreamChunk {
+        audio: audio.encode(encoding),
+        format: TtsAudioFormat {
+            encoding,
+            sample_rate: audio.sample_rate,
+            channels: audio.channels,
+            bits_per_sample: 16,
+        },
+        timestamp_ms,
+        is_last,
+    }
+}
+
+fn rms(samples: &[i16]) -> f32 {
+    (samples.iter().map(|&s| (s as f32).powi(2)).sum::<f32>() / samples.len().max(1) as f32).sqrt()
+}
+
+#[test]
+fn test_piper_wav_converted_to_client_pcm() {
+    let converter = TtsAudioConverter::new(TtsAudioFormat::pcm16(16000, 1)).unwrap();
+    let piper = chunk(&tone(22050, 2, 1000), AudioEncoding::Wav, 300, true);
+
+    let converted = converter.convert(&piper).unwrap();
+    assert_eq!(converted.format, TtsAudioFormat::pcm16(16000, 1));
+    assert_eq!(converted.timestamp_ms, 300);
+    assert!(converted.is_last);
+    // 裸 PCM 没有文件头：1 秒 16kHz 单声道 = 16000 个样本
+    assert_eq!(converted.audio.len(), 16000 * 2);
+
+    // 1kHz 在通带内，幅度保持（8000 / √2 ≈ 5657）
+    let decoded = PcmAudio::decode(&converted.audio, &converted.format).unwrap();
+    assert_eq!(decoded.duration_ms(), 1000);
+    assert!((rms(&decoded.samples[500..15500]) - 5657.0).abs() < 100.0);
+}
+
+#[test]
+fn test_matching_format_is_passed_through() {
+    let converter = TtsAudioConverter::new(TtsAudioFormat::pcm16(16000, 1)).unwrap();
+    let onnx = chunk(&tone(16000, 1, 200), AudioEncoding::Pcm, 0, false);
+    let converted = converter.convert(&onnx).unwrap();
+    assert_eq!(converted.audio, onnx.audio);
+    assert_eq!(converted.format, onnx.format);
+
+    let empty = TtsStreamChunk {
+        audio: Vec::new(),
+        format: TtsAudioFormat::wav16(22050, 1),
+        timestamp_ms: 0,
+        is_last: true,
+    };
+    let converted = converter.convert(&empty).unwrap();
+    assert!(converted.audio.is_empty());
+    assert_eq!(converted.format, TtsAudioFormat::pcm16(16000, 1));
+}
+
+#[test]
+fn test_merge_mixed_backends_into_single_wav() {
+    let converter = TtsAudioConverter::new(TtsAudioFormat::wav16(16000, 1)).unwrap();
+    let chunks = vec![
+        chunk(&tone(22050, 1, 500), AudioEncoding::Wav, 100, false),  // Piper
+        chunk(&tone(16000, 1, 250), AudioEncoding::Pcm, 600, true),   // ONNX 回退
+    ];
+
+    let merged = converter.merge(&chunks).unwrap();
+    assert_eq!(merged.format, TtsAudioFormat::wav16(16000, 1));
+    assert_eq!(merged.timestamp_ms, 100);
+    assert!(merged.is_last);
+    assert_eq!(merged.audio.windows(4).filter(|window| window == b"RIFF").count(), 1);
+    let decoded = PcmAudio::decode(&merged.audio, &merged.format).unwrap();
+    assert_eq!(decoded.samples.len(), 8000 + 4000);
+
+    assert!(converter.merge(&[]).is_err());
+}
+
+#[test]
+fn test_stream_chunks_converted_with_shared_resampler() {
+    let source = tone(22050, 2, 1000);
+    let one_shot = source.clone().convert(16000, 1).unwrap();
+
+    // Piper 流式返回：第一块带 WAV 头，后续块是裸 PCM（按样本帧切分）
+    let wav = source.encode(AudioEncoding::Wav);
+    let boundaries = [44 + 4 * 1000, 44 + 4 * 7000, 44 + 4 * 15000, wav.len()];
+    let mut converter = TtsStreamConverter::new(TtsAudioFormat::wav16(16000, 1)).unwrap();
+    let mut output = Vec::new();
+    let mut start = 0;
+    for (idx, &end) in boundaries.iter().enumerate() {
+        let encoding = if idx == 0 { AudioEncoding::Wav } else { AudioEncoding::Pcm };
+        let input = TtsStreamChunk {
+            audio: wav[start..end].to_vec(),
+            format: TtsAudioFormat { encoding, ..TtsAudioFormat::pcm16(22050, 2) },
+            timestamp_ms: idx as u64 * 100,
+            is_last: end == wav.len(),
+        };
+        let converted = converter.push(&input).unwrap();
+        assert_eq!(converted.format.encoding, encoding);
+        assert_eq!(converted.format.sample_rate, 16000);
+        assert_eq!(converted.timestamp_ms, input.timestamp_ms);
+        assert_eq!(converted.is_last, input.is_last);
+        output.extend(converted.audio);
+        start = end;
+    }
+
+    assert_eq!(output.windows(4).filter(|window| window == b"RIFF").count(), 1);
+    let decoded = PcmAudio::decode(&output, &TtsAudioFormat::wav16(16000, 1)).unwrap();
+    assert_eq!(decoded, one_shot);
+}
+
+#[test]
+fn test_single_stream_chunk_gets_complete_wav_header() {
+    let mut converter = TtsStreamConverter::new(TtsAudioFormat::wav16(16000, 1)).unwrap();
+    let converted = converter.push(&chunk(&tone(16000, 1, 100), AudioEncoding::Pcm, 0, true)).unwrap();
+    assert_eq!(converted.format, TtsAudioFormat::wav16(16000, 1));
+    assert_eq!(converted.audio, tone(16000, 1, 100).encode(AudioEncoding::Wav));
+}
+
+#[test]
+fn test_client_format_from_json() {
+    let format: TtsAudioFormat =
+        serde_json::from_str(r#"{"encoding": "wav", "sample_rate": 24000, "channels": 2}"#).unwrap();
+    assert_eq!(format, TtsAudioFormat::wav16(24000, 2));
+    assert!(TtsAudioConverter::new(format).is_ok());
+
+    let format: TtsAudioFormat =
+        serde_json::from_str(r#"{"encoding": "pcm", "sample_rate": 16000, "channels": 1, "bits_per_sample": 24}"#).unwrap();
+    assert!(TtsAudioConverter::new(format).is_err());
+    assert!(TtsAudioConverter::new(TtsAudioFormat::pcm16(0, 1)).is_err());
+    assert!(serde_json::from_str::<TtsAudioFormat>(r#"{"encoding": "mp3", "sample_rate": 16000, "channels": 1}"#).is_err());
+}
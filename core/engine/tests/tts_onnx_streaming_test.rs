@@ -0,0 +1,106 @@
+//! ONNX TTS 引擎（FastSpeech2、VITS）流式合成测试（需要模型文件，模型不存在时跳过）
+//!
+//! 验证：
+//! - 音频块按顺序返回：时间戳等于之前所有块的时长，只有最后一块 `is_last`
+//! - FastSpeech2 的分块拼接后与一次性合成的长度相同（同一个 mel-spectrogram 按窗口送入声码器）
+//! - VITS 按分句合成，拼接后的长度与一次性合成接近（时长预测带随机噪声，不要求完全相同）
+//! - 所有样本都在 ±1.0 以内，且没有被削波（流式限幅，不靠 PCM 转换时截断）
+
+use std::path::PathBuf;
+
+use futures::StreamExt;
+use core_engine::tts_streaming::{
+    FastSpeech2TtsEngine, PcmAudio, TtsRequest, TtsStreamChunk, TtsStreaming, VitsTtsEngine,
+};
+
+fn request(text: &str, locale: &str) -> TtsRequest {
+    TtsRequest {
+        text: text.to_string(),
+        voice: "default".to_string(),
+        locale: locale.to_string(),
+        speaker_id: None,
+        reference_audio: None,
+        voice_embedding: None,
+        speaker: None,
+        speech_rate: None,
+    }
+}
+
+/// 检查分块的顺序和时间戳，返回所有块拼接后的样本
+fn check_stream_chunks(chunks: &[TtsStreamChunk]) -> Vec<i16> {
+    assert!(!chunks.is_empty(), "stream should return at least one chunk");
+    let sample_rate = chunks[0].format.sample_rate as u64;
+    let mut samples = Vec::new();
+    for (idx, chunk) in chunks.iter().enumerate() {
+        assert_eq!(chunk.format, chunks[0].format, "chunk {} format changed", idx);
+        assert_eq!(chunk.timestamp_ms, samples.len() as u64 * 1000 / sample_rate, "chunk {} timestamp", idx);
+        assert_eq!(chunk.is_last, idx + 1 == chunks.len(), "only the last chunk should be marked is_last");
+        samples.extend(PcmAudio::decode(&chunk.audio, &chunk.format).unwrap().samples);
+    }
+    samples
+}
+
+/// 样本不超过 ±1.0 且没有削波（削波的样本会正好等于 ±32767）
+fn assert_not_clipped(samples: &[i16]) {
+    let clipped = samples.iter().filter(|&&s| s.unsigned_abs() >= i16::MAX as u16).count();
+    assert_eq!(clipped, 0, "{} of {} samples are clipped", clipped, samples.len());
+}
+
+async fn collect_stream(tts: &dyn TtsStreaming, request: TtsRequest) -> Vec<TtsStreamChunk> {
+    tts.synthesize_stream(request)
+        .map(|chunk| chunk.expect("stream chunk should succeed"))
+        .collect()
+        .await
+}
+
+#[tokio::test]
+async fn test_fastspeech2_stream_matches_one_shot_length() {
+    let model_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("models/tts");
+    if !model_dir.exists() {
+        eprintln!("⚠️  跳过测试: 模型目录不存在 {}", model_dir.display());
+        return;
+    }
+    let engine = match FastSpeech2TtsEngine::new_from_dir(&model_dir) {
+        Ok(engine) => engine,
+        Err(e) => {
+            eprintln!("⚠️  跳过测试: 加载模型失败: {}", e);
+            return;
+        }
+    };
+
+    let text = "今天天气很好，我们一起去公园散步吧。";
+    let one_shot = engine.synthesize(request(text, "zh")).await.expect("one-shot synthesis should succeed");
+    let chunks = collect_stream(&engine, request(text, "zh")).await;
+    assert!(chunks.len() > 1, "a sentence this long should span several vocoder windows");
+
+    let samples = check_stream_chunks(&chunks);
+    assert_eq!(samples.len() * 2, one_shot.audio.len());
+    assert_not_clipped(&samples);
+}
+
+#[tokio::test]
+async fn test_vits_stream_chunks_in_order() {
+    let model_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("models/tts/mms-tts-eng");
+    if !model_dir.exists() {
+        eprintln!("⚠️  跳过测试: 模型目录不存在 {}", model_dir.display());
+        return;
+    }
+    let engine = match VitsTtsEngine::new_from_dir(&model_dir) {
+        Ok(engine) => engine,
+        Err(e) => {
+            eprintln!("⚠️  跳过测试: 加载模型失败: {}", e);
+            return;
+        }
+    };
+
+    let text = "Hello from Lingua, this is a streaming test, and this is the last clause.";
+    let one_shot = engine.synthesize(request(text, "en")).await.expect("one-shot synthesis should succeed");
+    let chunks = collect_stream(&engine, request(text, "en")).await;
+    assert_eq!(chunks.len(), 3, "one chunk per clause");
+
+    let samples = check_stream_chunks(&chunks);
+    let one_shot_samples = one_shot.audio.len() / 2;
+    let ratio = samples.len() as f32 / one_shot_samples as f32;
+    assert!((0.75..1.25).contains(&ratio), "stream {} samples vs one-shot {}", samples.len(), one_shot_samples);
+    assert_not_clipped(&samples);
+}
@@ -0,0 +1,256 @@
+//! SSML 子集测试
+//!
+//! 验证：
+//! - `<break>`、`<prosody>`（嵌套叠加）、`<say-as>`、`<sub>` 的解析和降级
+//! - XML 结构错误返回错误，无效属性值被忽略
+//! - `SsmlTts` 包装任意后端：普通文本原样透传，SSML 逐段合成后插入静音、应用语速和音量
+//! - `<phoneme>`：接受音素的后端按音素合成，其余后端读内容文字
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use core_engine::error::EngineResult;
+use core_engine::tts_streaming::{
+    is_ssml, lower_ssml, parse_ssml, AudioEncoding, PcmAudio, Prosody, SsmlPart, SsmlPhoneme, SsmlSegment,
+    SsmlTts, TtsAudioFormat, TtsRequest, TtsStreamChunk, TtsStreaming,
+};
+
+fn request(text: &str, locale: &str) -> TtsRequest {
+    TtsRequest {
+        text: text.to_string(),
+        voice: "zh_CN-huayan-medium".to_string(),
+        locale: locale.to_string(),
+        speaker_id: None,
+        reference_audio: None,
+        voice_embedding: None,
+        speaker: None,
+        speech_rate: Some(4.0),
+    }
+}
+
+fn text(text: &str) -> SsmlPart {
+    SsmlPart::Text { text: text.to_string(), prosody: Prosody::default() }
+}
+
+#[test]
+fn test_plain_text_is_not_ssml() {
+    assert!(!is_ssml("你好，世界。"));
+    assert!(is_ssml("  <speak>你好</speak>"));
+    assert!(is_ssml(r#"<?xml version="1.0"?><speak version="1.1">hi</speak>"#));
+}
+
+#[test]
+fn test_break_and_prosody() {
+    let parts = parse_ssml(
+        r#"<speak>你好<break time="500ms"/>欢迎<break strength="weak"/><break time="1.5s"/>
+           <prosody rate="150%" volume="-6dB">请稍等<prosody pitch="+2st" rate="slow">一下</prosody></prosody>。</speak>"#,
+        "zh",
+    )
+    .unwrap();
+    assert_eq!(parts, vec![
+        text("你好"),
+        SsmlPart::Break { duration_ms: 500 },
+        text("欢迎"),
+        SsmlPart::Break { duration_ms: 1750 },
+        SsmlPart::Text { text: "请稍等".to_string(), prosody: Prosody { rate: 1.5, pitch_semitones: 0.0, volume_db: -6.0 } },
+        SsmlPart::Text { text: "一下".to_string(), prosody: Prosody { rate: 1.125, pitch_semitones: 2.0, volume_db: -6.0 } },
+        text("。"),
+    ]);
+
+    // 无效属性值被忽略
+    let parts = parse_ssml(r#"<speak><prosody rate="very-fast">Hello</prosody></speak>"#, "en").unwrap();
+    assert_eq!(parts, vec![text("Hello")]);
+}
+
+#[test]
+fn test_say_as_and_sub() {
+    let parts = parse_ssml(
+        r#"<speak>编号<say-as interpret-as="characters">A12</say-as>，共<say-as interpret-as="cardinal">10,086</say-as>人，
+           <say-as interpret-as="date" format="ymd">2025-03-05</say-as>，电话<say-as interpret-as="telephone">110-1234</say-as>，
+           <sub alias="世界卫生组织">WHO</sub>重庆</speak>"#,
+        "zh-CN",
+    )
+    .unwrap();
+    assert_eq!(parts, vec![text(
+        "编号A 一 二，共一万零八十六人， 二零二五年三月五日，电话幺幺零，幺二三四， 世界卫生组织重庆"
+    )]);
+
+    let parts = parse_ssml(
+        r#"<speak>On <say-as interpret-as="date" format="mdy">3/21/2025</say-as>, call
+           <say-as interpret-as="telephone">+1 555-0100</say-as> &amp; ask for <say-as interpret-as="cardinal">-3.5</say-as>.</speak>"#,
+        "en",
+    )
+    .unwrap();
+    assert_eq!(parts, vec![text(
+        "On March twenty-first, twenty twenty-five, call plus one, five five five, zero one zero zero & ask for minus three point five."
+    )]);
+}
+
+#[test]
+fn test_malformed_ssml_is_rejected() {
+    for input in [
+        "<speak>你好",
+        "<speak>你好</prosody></speak>",
+        "<speak><prosody>你好</speak></prosody>",
+        "<prosody>你好</prosody>",
+        "<speak>你好</speak>多余的文字",
+        r#"<speak><say-as interpret-as="cardinal"><break/>1</say-as></speak>"#,
+        "<speak><sub>WHO</sub></speak>",
+        "<speak>A &nbsp; B</speak>",
+    ] {
+        assert!(parse_ssml(input, "zh").is_err(), "should reject: {}", input);
+    }
+}
+
+#[test]
+fn test_phoneme_parsing() {
+    let parts = parse_ssml(r#"<speak><phoneme alphabet="pinyin" ph="chong2">重</phoneme>庆</speak>"#, "zh").unwrap();
+    assert_eq!(parts, vec![
+        SsmlPart::Phoneme {
+            text: "重".to_string(),
+            phoneme: SsmlPhoneme { alphabet: "pinyin".to_string(), ph: "chong2".to_string() },
+            prosody: Prosody::default(),
+        },
+        text("庆"),
+    ]);
+
+    // 自闭合的 <phoneme/> 没有内容；缺少 ph 时只保留文字
+    assert_eq!(parse_ssml(r#"<speak>番茄<phoneme ph="təˈmeɪtoʊ"/></speak>"#, "zh").unwrap(), vec![text("番茄")]);
+    assert_eq!(parse_ssml("<speak><phoneme>tomato</phoneme> soup</speak>", "en").unwrap(), vec![text("tomato soup")]);
+}
+
+#[test]
+fn test_lower_ssml_keeps_request_parameters() {
+    let segments = lower_ssml(&request(r#"<speak>第一句<break time="300ms"/>第二句</speak>"#, "zh")).unwrap();
+    assert_eq!(segments.len(), 3);
+    match &segments[0] {
+        SsmlSegment::Speech { request, prosody } => {
+            assert_eq!(request.text, "第一句");
+            assert_eq!(request.voice, "zh_CN-huayan-medium");
+            assert_eq!(request.speech_rate, Some(4.0));
+            assert!(prosody.is_neutral());
+        }
+        other => panic!("unexpected segment: {:?}", other),
+    }
+    assert!(matches!(segments[1], SsmlSegment::Silence { duration_ms: 300 }));
+}
+
+/// 每个字合成 100ms 的 16kHz 正弦波（WAV），记录收到的文本
+struct ToneTts {
+    requests: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl TtsStreaming for ToneTts {
+    async fn synthesize(&self, request: TtsRequest) -> EngineResult<TtsStreamChunk> {
+        self.requests.lock().unwrap().push(request.text.clone());
+        let frames = request.text.chars().count() * 1600;
+        let samples = (0..frames)
+            .map(|i| ((2.0 * std::f32::consts::PI * 440.0 * i as f32 / 16000.0).sin() * 8000.0) as i16)
+            .collect();
+        let audio = PcmAudio { samples, sample_rate: 16000, channels: 1 };
+        Ok(TtsStreamChunk {
+            audio: audio.encode(AudioEncoding::Wav),
+            format: TtsAudioFormat::wav16(16000, 1),
+            timestamp_ms: 0,
+            is_last: true,
+        })
+    }
+
+    async fn close(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+fn rms(samples: &[i16]) -> f32 {
+    (samples.iter().map(|&s| (s as f32).powi(2)).sum::<f32>() / samples.len().max(1) as f32).sqrt()
+}
+
+#[tokio::test]
+async fn test_ssml_tts_wraps_any_backend() {
+    let backend = Arc::new(ToneTts { requests: Mutex::new(Vec::new()) });
+    let tts = SsmlTts::new(backend.clone());
+
+    // 普通文本原样透传
+    let chunk = tts.synthesize(request("你好", "zh")).await.unwrap();
+    assert_eq!(PcmAudio::decode(&chunk.audio, &chunk.format).unwrap().duration_ms(), 200);
+
+    // 1s 语音 + 500ms 静音 + 1s 语音以 2 倍速、-6dB 播放（500ms）
+    let chunk = tts
+        .synthesize(request(
+            r#"<speak>一二三四五六七八九十<break time="500ms"/><prosody rate="200%" volume="-6dB">一二三四五六七八九十</prosody></speak>"#,
+            "zh",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(chunk.format, TtsAudioFormat::wav16(16000, 1));
+    assert!(chunk.is_last);
+    let pcm = PcmAudio::decode(&chunk.audio, &chunk.format).unwrap();
+    assert_eq!(pcm.duration_ms(), 2000);
+    assert_eq!(backend.requests.lock().unwrap()[1..], ["一二三四五六七八九十", "一二三四五六七八九十"]);
+
+    let normal = rms(&pcm.samples[1600..14400]);
+    let silence = rms(&pcm.samples[16100..23900]);
+    let quiet = rms(&pcm.samples[25600..30400]);
+    assert!((normal - 5657.0).abs() < 100.0, "normal rms {}", normal);
+    assert_eq!(silence, 0.0);
+    assert!((quiet / normal - 0.5).abs() < 0.05, "quiet/normal {}", quiet / normal);
+}
+
+/// 接受拼音音素的后端：记录按音素合成的请求
+struct PinyinTts {
+    tone: ToneTts,
+    phonemes: Mutex<Vec<(String, SsmlPhoneme)>>,
+}
+
+#[async_trait]
+impl TtsStreaming for PinyinTts {
+    async fn synthesize(&self, request: TtsRequest) -> EngineResult<TtsStreamChunk> {
+        self.tone.synthesize(request).await
+    }
+
+    fn accepts_phonemes(&self, alphabet: &str) -> bool {
+        alphabet == "pinyin"
+    }
+
+    async fn synthesize_phonemes(&self, request: TtsRequest, phoneme: SsmlPhoneme) -> EngineResult<TtsStreamChunk> {
+        self.phonemes.lock().unwrap().push((request.text.clone(), phoneme));
+        self.tone.synthesize(request).await
+    }
+
+    async fn close(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_phoneme_speaks_content_text_without_backend_support() {
+    let backend = Arc::new(ToneTts { requests: Mutex::new(Vec::new()) });
+    let tts = SsmlTts::new(backend.clone());
+
+    let input = r#"<speak>我在<phoneme alphabet="pinyin" ph="chong2">重</phoneme>庆<phoneme ph="x"/></speak>"#;
+    for _ in 0..2 {
+        let chunk = tts.synthesize(request(input, "zh")).await.expect("phoneme should not fail synthesis");
+        assert_eq!(PcmAudio::decode(&chunk.audio, &chunk.format).unwrap().duration_ms(), 400);
+    }
+    // 内容文字与相邻文字合并为一个请求
+    assert_eq!(*backend.requests.lock().unwrap(), ["我在重庆", "我在重庆"]);
+}
+
+#[tokio::test]
+async fn test_phoneme_passthrough_to_accepting_backend() {
+    let backend = Arc::new(PinyinTts {
+        tone: ToneTts { requests: Mutex::new(Vec::new()) },
+        phonemes: Mutex::new(Vec::new()),
+    });
+    let tts = SsmlTts::new(backend.clone());
+
+    let input = r#"<speak>我在<phoneme alphabet="pinyin" ph="chong2">重</phoneme>庆，<phoneme alphabet="ipa" ph="x">了</phoneme></speak>"#;
+    let chunk = tts.synthesize(request(input, "zh")).await.unwrap();
+    assert_eq!(PcmAudio::decode(&chunk.audio, &chunk.format).unwrap().duration_ms(), 600);
+
+    // 拼音按音素合成，后端不接受的 IPA 读内容文字
+    let phonemes = backend.phonemes.lock().unwrap().clone();
+    assert_eq!(phonemes, vec![("重".to_string(), SsmlPhoneme { alphabet: "pinyin".to_string(), ph: "chong2".to_string() })]);
+    assert_eq!(*backend.tone.requests.lock().unwrap(), ["我在", "重", "庆，了"]);
+}
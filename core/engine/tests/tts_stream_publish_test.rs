@@ -0,0 +1,316 @@
+//! 引擎流式发布 TTS 音频测试
+//!
+//! 验证一次性合成路径（未启用增量播放）使用 `synthesize_stream`：
+//! - 每收到一块音频立即发布 Tts 事件，不等整句合成完成
+//! - 音频块逐块转换为输出格式（只有第一块带 WAV 头），事件时间戳严格递增，只有最后一块 `is_last`
+//! - 返回的结果是所有块按顺序拼接后的完整音频
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::stream;
+use tokio::sync::mpsc;
+use core_engine::asr_streaming::AsrStreamingExt;
+use core_engine::event_bus::{ChannelEventBus, CoreEvent, EventTopic};
+use core_engine::tts_streaming::{AudioEncoding, PcmAudio, TtsChunkStream};
+use core_engine::vad::BoundaryType;
+use core_engine::*;
+
+const FRAME_MS: u64 = 100;
+
+/// 流式 TTS 输出的块数（每块 100ms）
+const CHUNKS: usize = 3;
+
+/// TTS 后端的采样率（与输出格式不同，需要跨块重采样）
+const TTS_SAMPLE_RATE: u32 = 22050;
+
+/// 每 3 帧报告一次边界的 VAD
+struct EveryThirdFrameVad;
+
+#[async_trait]
+impl VoiceActivityDetector for EveryThirdFrameVad {
+    async fn detect(&self, frame: AudioFrame) -> EngineResult<DetectionOutcome> {
+        let is_boundary = (frame.timestamp_ms / FRAME_MS) % 3 == 2;
+        Ok(DetectionOutcome {
+            is_boundary,
+            confidence: 1.0,
+            frame,
+            boundary_type: if is_boundary { Some(BoundaryType::NaturalPause) } else { None },
+            speech_event: None,
+        })
+    }
+}
+
+/// 边界时返回固定文本的 ASR
+struct FixedAsr {
+    buffer: Mutex<Vec<AudioFrame>>,
+}
+
+#[async_trait]
+impl AsrStreaming for FixedAsr {
+    async fn initialize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn infer(&self, _request: AsrRequest) -> EngineResult<AsrResult> {
+        Err(EngineError::new("FixedAsr only supports boundary inference"))
+    }
+
+    async fn finalize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    fn as_ext(&self) -> Option<&dyn AsrStreamingExt> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl AsrStreamingExt for FixedAsr {
+    fn accumulate_frame(&self, frame: AudioFrame) -> EngineResult<()> {
+        self.buffer.lock().unwrap().push(frame);
+        Ok(())
+    }
+
+    fn get_accumulated_frames(&self) -> EngineResult<Vec<AudioFrame>> {
+        Ok(self.buffer.lock().unwrap().clone())
+    }
+
+    fn clear_buffer(&self) -> EngineResult<()> {
+        self.buffer.lock().unwrap().clear();
+        Ok(())
+    }
+
+    fn set_language(&self, _language: Option<String>) -> EngineResult<()> {
+        Ok(())
+    }
+
+    fn get_language(&self) -> EngineResult<Option<String>> {
+        Ok(Some("zh".to_string()))
+    }
+
+    async fn infer_on_boundary(&self) -> EngineResult<AsrResult> {
+        self.buffer.lock().unwrap().clear();
+        Ok(AsrResult {
+            partial: None,
+            final_transcript: Some(StableTranscript {
+                text: "你好世界".to_string(),
+                speaker_id: None,
+                language: "zh".to_string(),
+                segments: Vec::new(),
+                language_probabilities: Vec::new(),
+            }),
+        })
+    }
+
+    fn is_streaming_enabled(&self) -> bool {
+        false
+    }
+
+    async fn infer_partial(&self, _current_timestamp_ms: u64) -> EngineResult<Option<PartialTranscript>> {
+        Ok(None)
+    }
+}
+
+/// 返回固定译文的 NMT
+struct FixedNmt;
+
+#[async_trait]
+impl NmtIncremental for FixedNmt {
+    async fn initialize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn translate(&self, request: TranslationRequest) -> EngineResult<TranslationResponse> {
+        Ok(TranslationResponse {
+            translated_text: "Hello world.".to_string(),
+            is_stable: true,
+            speaker_id: request.speaker_id,
+            source_text: Some(request.transcript.text),
+            source_audio_duration_ms: None,
+            source_language: None,
+            quality_metrics: None,
+            committed_text: None,
+            provider: None,
+        })
+    }
+
+    async fn finalize(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+/// 分块返回 22.05kHz 音频的流式 TTS（第一块带流式 WAV 头，后续块是裸 PCM）
+///
+/// 产出下一块之前先等待上一块的 Tts 事件：引擎等整句合成完才发布时，等待超时，流返回错误
+struct ChunkedTts {
+    tts_events: tokio::sync::Mutex<mpsc::UnboundedReceiver<CoreEvent>>,
+    published: Mutex<Vec<CoreEvent>>,
+}
+
+impl ChunkedTts {
+    fn chunk(idx: usize) -> TtsStreamChunk {
+        let audio = PcmAudio {
+            samples: vec![1000; TTS_SAMPLE_RATE as usize / 10],
+            sample_rate: TTS_SAMPLE_RATE,
+            channels: 1,
+        };
+        let (audio, format) = if idx == 0 {
+            let mut wav = audio.encode(AudioEncoding::Wav);
+            wav[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+            wav[40..44].copy_from_slice(&u32::MAX.to_le_bytes());
+            (wav, TtsAudioFormat::wav16(TTS_SAMPLE_RATE, 1))
+        } else {
+            (audio.encode(AudioEncoding::Pcm), TtsAudioFormat::pcm16(TTS_SAMPLE_RATE, 1))
+        };
+        TtsStreamChunk {
+            audio,
+            format,
+            timestamp_ms: idx as u64 * 100,
+            is_last: idx + 1 == CHUNKS,
+        }
+    }
+}
+
+#[async_trait]
+impl TtsStreaming for ChunkedTts {
+    async fn synthesize(&self, _request: TtsRequest) -> EngineResult<TtsStreamChunk> {
+        Err(EngineError::new("ChunkedTts only supports streaming synthesis"))
+    }
+
+    fn synthesize_stream(&self, _request: TtsRequest) -> TtsChunkStream<'_> {
+        Box::pin(stream::unfold(0usize, move |idx| async move {
+            if idx == CHUNKS {
+                return None;
+            }
+            if idx > 0 {
+                let event = tokio::time::timeout(Duration::from_secs(1), self.tts_events.lock().await.recv()).await;
+                match event {
+                    Ok(Some(event)) => self.published.lock().unwrap().push(event),
+                    _ => {
+                        let error = EngineError::new(format!("chunk {} was not published before synthesis continued", idx - 1));
+                        return Some((Err(error), CHUNKS));
+                    }
+                }
+            }
+            Some((Ok(Self::chunk(idx)), idx + 1))
+        }))
+    }
+
+    async fn close(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+struct DummyConfig;
+
+#[async_trait]
+impl ConfigManager for DummyConfig {
+    async fn load(&self) -> EngineResult<EngineConfig> {
+        Ok(EngineConfig {
+            mode: "fast".to_string(),
+            source_language: "zh".to_string(),
+            target_language: "en".to_string(),
+        })
+    }
+
+    async fn current(&self) -> EngineResult<EngineConfig> {
+        self.load().await
+    }
+}
+
+struct DummyCache;
+
+#[async_trait]
+impl CacheManager for DummyCache {
+    async fn warm_up(&self) -> EngineResult<()> {
+        Ok(())
+    }
+
+    async fn purge(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+struct DummyTelemetry;
+
+#[async_trait]
+impl TelemetrySink for DummyTelemetry {
+    async fn record(&self, _datum: TelemetryDatum) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+fn speech_frame(index: u64) -> AudioFrame {
+    AudioFrame {
+        sample_rate: 16000,
+        channels: 1,
+        data: vec![0.1; (16000 * FRAME_MS / 1000) as usize],
+        timestamp_ms: index * FRAME_MS,
+    }
+}
+
+#[tokio::test]
+async fn test_stream_chunks_published_as_they_arrive() {
+    let event_bus = Arc::new(ChannelEventBus::new());
+    let tts_events = event_bus.subscribe_receiver(EventTopic("Tts".to_string()));
+    // 订阅在后台任务中注册
+    tokio::task::yield_now().await;
+
+    let tts = Arc::new(ChunkedTts {
+        tts_events: tokio::sync::Mutex::new(tts_events),
+        published: Mutex::new(Vec::new()),
+    });
+    let engine = CoreEngineBuilder::new()
+        .event_bus(event_bus)
+        .vad(Arc::new(EveryThirdFrameVad))
+        .asr(Arc::new(FixedAsr {
+            buffer: Mutex::new(Vec::new()),
+        }))
+        .nmt(Arc::new(FixedNmt))
+        .emotion(Arc::new(EmotionStub::new()))
+        .persona(Arc::new(PersonaStub::new()))
+        .tts(tts.clone())
+        .config(Arc::new(DummyConfig))
+        .cache(Arc::new(DummyCache))
+        .telemetry(Arc::new(DummyTelemetry))
+        .with_tts_output_format(TtsAudioFormat::wav16(16000, 1))
+        .build()
+        .expect("builder should succeed");
+    engine.boot().await.expect("boot should succeed");
+
+    let session = engine.create_session(Some("zh".to_string()), Some("en".to_string())).await;
+    let mut result = None;
+    for index in 0..3 {
+        result = engine
+            .process_audio_frame(&session, speech_frame(index))
+            .await
+            .expect("process_audio_frame should succeed");
+    }
+    let result = result.expect("boundary should produce a result");
+
+    // 前面的块在合成继续之前已经发布，最后一块在合成结束后发布
+    let mut events = tts.published.lock().unwrap().clone();
+    assert_eq!(events.len(), CHUNKS - 1);
+    let last = tokio::time::timeout(Duration::from_secs(1), tts.tts_events.lock().await.recv())
+        .await
+        .expect("last Tts event should arrive")
+        .expect("event bus should stay open");
+    events.push(last);
+
+    let timestamps: Vec<u64> = events.iter().map(|event| event.timestamp_ms).collect();
+    assert_eq!(timestamps, vec![200, 300, 400]);
+    let is_last: Vec<bool> = events.iter().map(|event| event.payload["is_last"].as_bool().unwrap()).collect();
+    assert_eq!(is_last, vec![false, false, true]);
+    let encodings: Vec<&str> = events.iter().map(|event| event.payload["format"]["encoding"].as_str().unwrap()).collect();
+    assert_eq!(encodings, vec!["wav", "pcm", "pcm"]);
+    assert!(events.iter().all(|event| event.payload["format"]["sample_rate"] == 16000));
+
+    // 返回的音频是所有块拼接后的 16kHz WAV（3 × 100ms）
+    let tts_chunk = result.tts.expect("TTS should succeed");
+    assert_eq!(tts_chunk.format, TtsAudioFormat::wav16(16000, 1));
+    assert!(tts_chunk.is_last);
+    let decoded = PcmAudio::decode(&tts_chunk.audio, &tts_chunk.format).unwrap();
+    assert_eq!(decoded.samples.len(), 4800);
+}
@@ -0,0 +1,152 @@
+//! TTS 流式合成测试
+//!
+//! 验证：
+//! - `synthesize_stream` 的默认实现把一次性合成的结果作为唯一的一块返回（错误同样作为一项返回）
+//! - `PiperHttpTts` 按 HTTP 响应体分块到达的顺序返回音频块：第一块带 WAV 头（格式为 WAV，后续块为裸 PCM），
+//!   拼接后与服务返回的 WAV 完全相同，时间戳递增，最后一块 `is_last`
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use axum::{body::{Body, Bytes}, http::StatusCode, response::IntoResponse, routing::post, Router};
+use futures::StreamExt;
+use core_engine::error::{EngineError, EngineResult};
+use core_engine::tts_streaming::{PiperHttpConfig, PiperHttpTts, TtsAudioFormat, TtsRequest, TtsStreamChunk, TtsStreaming};
+
+fn request(text: &str) -> TtsRequest {
+    TtsRequest {
+        text: text.to_string(),
+        voice: "zh_CN-huayan-medium".to_string(),
+        locale: "zh".to_string(),
+        speaker_id: None,
+        reference_audio: None,
+        voice_embedding: None,
+        speaker: None,
+        speech_rate: None,
+    }
+}
+
+/// 只实现一次性合成的 TTS
+struct OneShotTts {
+    fail: bool,
+}
+
+#[async_trait]
+impl TtsStreaming for OneShotTts {
+    async fn synthesize(&self, request: TtsRequest) -> EngineResult<TtsStreamChunk> {
+        if self.fail {
+            return Err(EngineError::new("synthesis failed"));
+        }
+        Ok(TtsStreamChunk {
+            audio: request.text.into_bytes(),
+            format: TtsAudioFormat::pcm16(16000, 1),
+            timestamp_ms: 0,
+            is_last: true,
+        })
+    }
+
+    async fn close(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_default_stream_wraps_one_shot_synthesis() {
+    let tts = OneShotTts { fail: false };
+    let chunks: Vec<_> = tts.synthesize_stream(request("你好")).collect().await;
+    assert_eq!(chunks.len(), 1);
+    let chunk = chunks[0].as_ref().unwrap();
+    assert_eq!(chunk.audio, "你好".as_bytes());
+    assert!(chunk.is_last);
+
+    let tts = OneShotTts { fail: true };
+    let chunks: Vec<_> = tts.synthesize_stream(request("你好")).collect().await;
+    assert_eq!(chunks.len(), 1);
+    assert!(chunks[0].is_err());
+}
+
+/// 22050Hz 单声道 16-bit WAV（流式响应：RIFF/data 长度未知，写为 0xFFFFFFFF）
+fn streaming_wav(samples: usize) -> Vec<u8> {
+    let mut wav = Vec::new();
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&u32::MAX.to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&22050u32.to_le_bytes());
+    wav.extend_from_slice(&44100u32.to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&u32::MAX.to_le_bytes());
+    wav.extend((0..samples).flat_map(|i| ((i % 2000) as i16 - 1000).to_le_bytes()));
+    wav
+}
+
+/// 启动一个模拟的 Piper 服务，返回 TTS 端点 URL
+async fn spawn_piper_server(app: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}/tts", addr)
+}
+
+fn piper_client(endpoint: String) -> PiperHttpTts {
+    PiperHttpTts::new(PiperHttpConfig {
+        endpoint,
+        default_voice: "zh_CN-huayan-medium".to_string(),
+        timeout_ms: 5000,
+    })
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_piper_stream_follows_chunked_body() {
+    let wav = streaming_wav(22050);
+    // 按奇数字节切分，每块之间间隔一段时间（模拟边合成边发送）
+    let pieces: Vec<Vec<u8>> = wav.chunks(9999).map(|piece| piece.to_vec()).collect();
+    let app = Router::new().route("/tts", post(move || {
+        let pieces = pieces.clone();
+        async move {
+            let body = futures::stream::iter(pieces).then(|piece| async move {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                Ok::<_, std::io::Error>(Bytes::from(piece))
+            });
+            Body::from_stream(body)
+        }
+    }));
+    let tts = piper_client(spawn_piper_server(app).await);
+
+    let chunks: Vec<TtsStreamChunk> = tts
+        .synthesize_stream(request("你好，欢迎使用语音翻译系统。"))
+        .map(|chunk| chunk.unwrap())
+        .collect()
+        .await;
+
+    assert!(chunks.len() >= 4, "expected one chunk per body piece, got {}", chunks.len());
+    assert_eq!(&chunks[0].audio[0..4], b"RIFF");
+    assert_eq!(chunks[0].format, TtsAudioFormat::wav16(22050, 1));
+    assert!(chunks[1..].iter().all(|chunk| chunk.format == TtsAudioFormat::pcm16(22050, 1)));
+    assert_eq!(chunks[0].timestamp_ms, 0);
+    assert!(chunks.windows(2).all(|pair| pair[0].timestamp_ms <= pair[1].timestamp_ms));
+    assert!(chunks.last().unwrap().is_last);
+    assert_eq!(chunks.iter().filter(|chunk| chunk.is_last).count(), 1);
+    let joined: Vec<u8> = chunks.into_iter().flat_map(|chunk| chunk.audio).collect();
+    assert_eq!(joined, wav);
+}
+
+#[tokio::test]
+async fn test_piper_stream_reports_http_errors() {
+    let app = Router::new().route("/tts", post(|| async {
+        (StatusCode::INTERNAL_SERVER_ERROR, "voice not found").into_response()
+    }));
+    let tts = piper_client(spawn_piper_server(app).await);
+
+    let chunks: Vec<_> = tts.synthesize_stream(request("你好")).collect().await;
+    assert_eq!(chunks.len(), 1);
+    let error = chunks[0].as_ref().unwrap_err().to_string();
+    assert!(error.contains("500"), "{}", error);
+}
@@ -0,0 +1,200 @@
+//! TTS 文本规范化测试
+//!
+//! 验证：
+//! - 中文 / 英文的基数、序数、百分数、货币、日期、时间、电话号码、单位和缩写读法（表驱动）
+//! - 规范化是幂等的，不支持的语言原样返回
+//! - `NormalizedTts` 包装任意后端：普通文本在合成前规范化，SSML 原样透传
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use core_engine::error::EngineResult;
+use core_engine::tts_streaming::{
+    normalize_for_tts, NormalizationLanguage, NormalizedTts, TextNormalizer, TtsAudioFormat, TtsRequest,
+    TtsStreamChunk, TtsStreaming,
+};
+
+/// （输入，期望输出）
+const ZH_CASES: &[(&str, &str)] = &[
+    // 基数
+    ("共123人", "共一百二十三人"),
+    ("10,086", "一万零八十六"),
+    ("20000", "两万"),
+    ("2万人", "两万人"),
+    ("2345", "两千三百四十五"),
+    ("3.14", "三点一四"),
+    ("气温-5度", "气温负五度"),
+    ("编号007", "编号零零七"),
+    ("第3-5名", "第三到五名"),
+    ("192.168.1.1", "一百九十二点一百六十八点一点一"),
+    // 百分数
+    ("增长了3.5%", "增长了百分之三点五"),
+    ("50％", "百分之五十"),
+    // 货币
+    ("$3.5M", "三点五百万美元"),
+    ("¥12.50", "十二元五角"),
+    ("￥100", "一百元"),
+    ("€20", "二十欧元"),
+    ("$2亿", "两亿美元"),
+    // 日期
+    ("2025年3月5日", "二零二五年三月五日"),
+    ("2025-03-05", "二零二五年三月五日"),
+    ("2020-2025年", "二零二零到二零二五年"),
+    // 时间
+    ("10:30", "十点三十分"),
+    ("2:05", "两点零五分"),
+    ("8:00", "八点整"),
+    ("10:30:15", "十点三十分十五秒"),
+    // 电话号码
+    ("13812345678", "幺三八幺二三四五六七八"),
+    ("138-1234-5678", "幺三八，幺二三四，五六七八"),
+    ("010-12345678", "零幺零，幺二三四五六七八"),
+    ("+86 138 1234 5678", "加八六，幺三八，幺二三四，五六七八"),
+    // 单位
+    ("5km", "五公里"),
+    ("5 km", "五公里"),
+    ("36.5℃", "三十六点五摄氏度"),
+    ("-10°C", "零下十摄氏度"),
+    ("120km/h", "一百二十公里每小时"),
+    ("500ml", "五百毫升"),
+    ("3 min", "三分钟"),
+    // 缩写和符号
+    ("A&B", "A和B"),
+    ("苹果vs.三星", "苹果对三星"),
+];
+
+const EN_CASES: &[(&str, &str)] = &[
+    // 基数
+    ("I have 3 apples.", "I have three apples."),
+    ("1,234,567", "one million two hundred thirty-four thousand five hundred sixty-seven"),
+    ("3.14", "three point one four"),
+    ("-7", "minus seven"),
+    ("pages 3-5", "pages three to five"),
+    ("in 2025", "in twenty twenty-five"),
+    ("version 2.0.1", "version two point zero point one"),
+    // 序数
+    ("the 1st and 22nd", "the first and twenty-second"),
+    ("3rd place", "third place"),
+    // 百分数
+    ("up 12%", "up twelve percent"),
+    // 货币
+    ("$3.5M", "three point five million dollars"),
+    ("$3.50", "three dollars and fifty cents"),
+    ("$1", "one dollar"),
+    ("£2.01", "two pounds and one penny"),
+    ("€10K", "ten thousand euros"),
+    // 日期
+    ("2025-03-05", "March fifth, twenty twenty-five"),
+    ("3/21/2025", "March twenty-first, twenty twenty-five"),
+    // 时间
+    ("10:30", "ten thirty"),
+    ("9:05 pm", "nine oh five p m"),
+    ("at 10:30am", "at ten thirty a m"),
+    ("7:00", "seven o'clock"),
+    ("7:00 AM", "seven a m"),
+    // 电话号码
+    ("555-123-4567", "five five five, one two three, four five six seven"),
+    ("(555) 123-4567", "five five five, one two three, four five six seven"),
+    ("+1 555-0100", "plus one, five five five, zero one zero zero"),
+    // 单位
+    ("5km", "five kilometers"),
+    ("1 kg", "one kilogram"),
+    ("2.5 GB", "two point five gigabytes"),
+    ("6ft", "six feet"),
+    ("60mph", "sixty miles per hour"),
+    ("3 m", "three meters"),
+    // 年代
+    ("1990s", "nineteen nineties"),
+    ("90s", "nineties"),
+    // 缩写和符号
+    ("Dr. Smith", "Doctor Smith"),
+    ("Mr. and Mrs. Lee", "Mister and Missus Lee"),
+    ("cats vs. dogs", "cats versus dogs"),
+    ("apples, pears, etc.", "apples, pears, et cetera."),
+    ("R&D", "R and D"),
+];
+
+#[test]
+fn test_chinese_normalization() {
+    let normalizer = TextNormalizer::new(NormalizationLanguage::Chinese);
+    for &(input, expected) in ZH_CASES {
+        assert_eq!(normalizer.normalize(input), expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_english_normalization() {
+    let normalizer = TextNormalizer::new(NormalizationLanguage::English);
+    for &(input, expected) in EN_CASES {
+        assert_eq!(normalizer.normalize(input), expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_normalization_is_idempotent() {
+    for (locale, cases) in [("zh-CN", ZH_CASES), ("en-US", EN_CASES)] {
+        for &(_, expected) in cases {
+            assert_eq!(normalize_for_tts(expected, locale), expected, "locale: {}", locale);
+        }
+    }
+}
+
+#[test]
+fn test_locale_selection() {
+    assert_eq!(NormalizationLanguage::from_locale("zh-CN"), Some(NormalizationLanguage::Chinese));
+    assert_eq!(NormalizationLanguage::from_locale("english"), Some(NormalizationLanguage::English));
+    assert_eq!(NormalizationLanguage::from_locale("zh-Hant-TW"), Some(NormalizationLanguage::Chinese));
+    assert_eq!(NormalizationLanguage::from_locale("中文"), Some(NormalizationLanguage::Chinese));
+    assert_eq!(NormalizationLanguage::from_locale("en_GB"), Some(NormalizationLanguage::English));
+    assert_eq!(NormalizationLanguage::from_locale("ja"), None);
+    assert_eq!(NormalizationLanguage::from_locale("eno"), None);
+    assert_eq!(normalize_for_tts("10:30", "ja"), "10:30");
+    assert_eq!(normalize_for_tts("10:30", "zh"), "十点三十分");
+}
+
+/// 记录收到的文本
+struct RecordingTts {
+    requests: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl TtsStreaming for RecordingTts {
+    async fn synthesize(&self, request: TtsRequest) -> EngineResult<TtsStreamChunk> {
+        self.requests.lock().unwrap().push(request.text);
+        Ok(TtsStreamChunk {
+            audio: Vec::new(),
+            format: TtsAudioFormat::wav16(16000, 1),
+            timestamp_ms: 0,
+            is_last: true,
+        })
+    }
+
+    async fn close(&self) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_normalized_tts_wraps_any_backend() {
+    let backend = Arc::new(RecordingTts { requests: Mutex::new(Vec::new()) });
+    let tts = NormalizedTts::new(backend.clone());
+    let request = |text: &str, locale: &str| TtsRequest {
+        text: text.to_string(),
+        voice: String::new(),
+        locale: locale.to_string(),
+        speaker_id: None,
+        reference_audio: None,
+        voice_embedding: None,
+        speaker: None,
+        speech_rate: None,
+    };
+
+    tts.synthesize(request("会议在10:30开始", "zh")).await.unwrap();
+    tts.synthesize(request("It costs $5.", "en")).await.unwrap();
+    tts.synthesize(request(r#"<speak>等待<break time="1.5s"/>3秒</speak>"#, "zh")).await.unwrap();
+    assert_eq!(*backend.requests.lock().unwrap(), [
+        "会议在十点三十分开始",
+        "It costs five dollars.",
+        r#"<speak>等待<break time="1.5s"/>3秒</speak>"#,
+    ]);
+}
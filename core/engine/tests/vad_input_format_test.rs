@@ -0,0 +1,80 @@
+//! VAD 输入格式适配测试
+//!
+//! 验证：
+//! - 44.1/48kHz 立体声帧被混成单声道并重采样到 16kHz，时间戳保持原始值
+//! - 逐帧转换的总样本数与音频时长一致
+//! - TimeBasedVad 接受任意采样率/声道数的帧，边界时间与原始时间戳一致
+
+use core_engine::types::AudioFrame;
+use core_engine::vad::{TimeBasedVad, VadInputAdapter, VoiceActivityDetector};
+
+/// 生成一秒内的第 `index` 个 10ms 立体声帧（左声道正弦波，右声道静音）
+fn stereo_frame(sample_rate: u32, index: u64) -> AudioFrame {
+    let samples_per_frame = (sample_rate / 100) as u64;
+    let data = (0..samples_per_frame)
+        .flat_map(|i| {
+            let t = (index * samples_per_frame + i) as f32 / sample_rate as f32;
+            [(2.0 * std::f32::consts::PI * 440.0 * t).sin() * 0.8, 0.0]
+        })
+        .collect();
+    AudioFrame {
+        sample_rate,
+        channels: 2,
+        data,
+        timestamp_ms: index * 10,
+    }
+}
+
+#[test]
+fn test_adapter_downmixes_and_resamples_browser_audio() {
+    for sample_rate in [44100, 48000] {
+        let adapter = VadInputAdapter::new(16000);
+        let mut total = 0;
+        let mut peak = 0.0f32;
+        for index in 0..100 {
+            let converted = adapter.convert(&stereo_frame(sample_rate, index)).unwrap();
+            assert_eq!(converted.sample_rate, 16000);
+            assert_eq!(converted.channels, 1);
+            assert_eq!(converted.timestamp_ms, index * 10);
+            total += converted.data.len();
+            peak = converted.data.iter().fold(peak, |max, x| max.max(x.abs()));
+        }
+        // 一秒音频约 16000 个样本（重采样器有不到 1ms 的滞后）
+        assert!((15950..=16000).contains(&total), "{}Hz -> {} samples", sample_rate, total);
+        // 左右声道取平均，幅度减半
+        assert!((peak - 0.4).abs() < 0.02, "{}Hz peak {}", sample_rate, peak);
+    }
+}
+
+#[test]
+fn test_adapter_passes_through_model_format_and_rejects_invalid_frames() {
+    let adapter = VadInputAdapter::new(16000);
+    let frame = AudioFrame {
+        sample_rate: 16000,
+        channels: 1,
+        data: vec![0.1, -0.2, 0.3],
+        timestamp_ms: 42,
+    };
+    assert_eq!(adapter.convert(&frame).unwrap().data, frame.data);
+
+    let invalid = AudioFrame { channels: 0, ..frame };
+    assert!(adapter.convert(&invalid).is_err());
+}
+
+#[tokio::test]
+async fn test_time_based_vad_accepts_any_input_format() {
+    let vad = TimeBasedVad::new(300);
+    let mut boundaries = Vec::new();
+    for index in 0..100 {
+        let frame = stereo_frame(48000, index);
+        let outcome = vad.detect(frame.clone()).await.unwrap();
+        // 返回原始帧，不改变格式和时间戳
+        assert_eq!(outcome.frame.sample_rate, 48000);
+        assert_eq!(outcome.frame.channels, 2);
+        assert_eq!(outcome.frame.data.len(), frame.data.len());
+        if outcome.is_boundary {
+            boundaries.push(outcome.frame.timestamp_ms);
+        }
+    }
+    assert_eq!(boundaries, vec![300, 600, 900]);
+}
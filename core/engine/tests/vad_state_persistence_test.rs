@@ -0,0 +1,114 @@
+//! VAD 自适应状态持久化测试
+//!
+//! 验证：
+//! - `SpeakerAdaptiveState` 按用户保存为 JSON，读取后与保存前一致
+//! - 没有保存过的用户返回 None，损坏的文件返回错误
+//! - 不同用户的状态互不覆盖
+//! - 同一用户的并发保存不会互相破坏，也不会留下临时文件
+//! - SileroVad 恢复状态后立即使用保存的阈值（需要模型文件）
+
+use std::path::Path;
+
+use core_engine::vad::{AdaptiveStateStore, SileroVad, SpeakerAdaptiveState, VadFeedbackType};
+use tempfile::TempDir;
+
+fn state(base_threshold_ms: u64, delta_ms: i64) -> SpeakerAdaptiveState {
+    serde_json::from_value(serde_json::json!({
+        "speech_rate_history": [8.0, 9.5],
+        "base_threshold_ms": base_threshold_ms,
+        "delta_ms": delta_ms,
+        "sample_count": 2,
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_save_and_load_roundtrip() {
+    let dir = TempDir::new().unwrap();
+    let store = AdaptiveStateStore::new(dir.path().join("vad_state")).unwrap();
+
+    assert!(store.load("alice").unwrap().is_none());
+
+    let saved = state(350, -100);
+    store.save("alice", &saved).unwrap();
+    assert!(store.path_for("alice").exists());
+
+    let loaded = store.load("alice").unwrap().unwrap();
+    assert_eq!(loaded, saved);
+    assert_eq!(loaded.base_threshold_ms(), 350);
+    assert_eq!(loaded.delta_ms(), -100);
+    assert_eq!(loaded.sample_count(), 2);
+}
+
+#[test]
+fn test_users_are_stored_separately() {
+    let dir = TempDir::new().unwrap();
+    let store = AdaptiveStateStore::new(dir.path()).unwrap();
+
+    store.save("alice", &state(300, 0)).unwrap();
+    store.save("bob", &state(500, 150)).unwrap();
+    store.save("alice", &state(250, -50)).unwrap();
+
+    assert_eq!(store.load("alice").unwrap().unwrap().base_threshold_ms(), 250);
+    assert_eq!(store.load("bob").unwrap().unwrap().base_threshold_ms(), 500);
+    // 用户 ID 中的路径分隔符被转义，状态文件始终在存储目录内
+    store.save("../bob", &state(400, 0)).unwrap();
+    assert_eq!(store.path_for("../bob").parent(), Some(dir.path()));
+    assert_eq!(store.load("bob").unwrap().unwrap().base_threshold_ms(), 500);
+}
+
+#[test]
+fn test_concurrent_saves_for_same_user() {
+    let dir = TempDir::new().unwrap();
+    let store = AdaptiveStateStore::new(dir.path()).unwrap();
+
+    std::thread::scope(|scope| {
+        for threshold in 0..8u64 {
+            let store = &store;
+            scope.spawn(move || {
+                for _ in 0..20 {
+                    store.save("alice", &state(300 + threshold, 0)).unwrap();
+                }
+            });
+        }
+    });
+
+    // 最终文件是某一次完整的保存
+    let threshold = store.load("alice").unwrap().unwrap().base_threshold_ms();
+    assert!((300..308).contains(&threshold), "unexpected threshold {}", threshold);
+    let files: Vec<_> = std::fs::read_dir(dir.path()).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+    assert_eq!(files, vec![std::ffi::OsString::from("alice.json")]);
+}
+
+#[test]
+fn test_corrupt_file_is_an_error() {
+    let dir = TempDir::new().unwrap();
+    let store = AdaptiveStateStore::new(dir.path()).unwrap();
+    std::fs::write(store.path_for("alice"), "{ not json").unwrap();
+
+    assert!(store.load("alice").is_err());
+}
+
+#[test]
+fn test_silero_vad_restores_saved_threshold() {
+    let model_path = "models/vad/silero/silero_vad_official.onnx";
+    if !Path::new(model_path).exists() {
+        eprintln!("Skipping test: model file not found at {}", model_path);
+        return;
+    }
+
+    // 第一次连接：通过反馈学到更短的阈值并保存
+    let dir = TempDir::new().unwrap();
+    let store = AdaptiveStateStore::new(dir.path()).unwrap();
+    let vad = SileroVad::new(model_path).unwrap();
+    vad.update_speech_rate("this is a fairly quick sentence", 2000);
+    vad.adjust_delta_by_feedback(VadFeedbackType::BoundaryTooLong, 150);
+    let learned = vad.get_adjusted_duration_ms();
+    store.save("alice", &vad.adaptive_state()).unwrap();
+
+    // 第二次连接：新实例从默认值开始，恢复后立即使用保存的阈值
+    let returning = SileroVad::new(model_path).unwrap();
+    assert_ne!(returning.get_adjusted_duration_ms(), learned);
+    returning.restore_adaptive_state(store.load("alice").unwrap().unwrap());
+    assert_eq!(returning.get_adjusted_duration_ms(), learned);
+}
@@ -0,0 +1,99 @@
+//! 词表纠错模块测试
+
+use core_engine::vocabulary_correction::{PinyinTable, VocabularyCorrector, DEFAULT_MAX_EDIT_RATIO};
+use std::fs;
+use tempfile::TempDir;
+
+fn vocabulary(words: &[&str]) -> Vec<String> {
+    words.iter().map(|word| word.to_string()).collect()
+}
+
+fn pinyin_table(entries: &[(char, &str)]) -> PinyinTable {
+    let mut table = PinyinTable::new();
+    for &(ch, pinyin) in entries {
+        table.insert(ch, pinyin);
+    }
+    table
+}
+
+#[test]
+fn test_corrects_misspelled_latin_terms() {
+    let corrector = VocabularyCorrector::default();
+    let words = vocabulary(&["Kubernetes", "Lingua"]);
+
+    assert_eq!(
+        corrector.correct("we deploy it on cubernetes with lingo", &words),
+        "we deploy it on Kubernetes with Lingua"
+    );
+    // 多个单词被听成一个词、或一个词被拆成多个单词
+    assert_eq!(corrector.correct("open ling gua now", &words), "open Lingua now");
+}
+
+#[test]
+fn test_leaves_unrelated_and_short_words_alone() {
+    let corrector = VocabularyCorrector::default();
+
+    let text = "the language is fine, isn't it";
+    assert_eq!(corrector.correct(text, &vocabulary(&["Lingua"])), text);
+    // 短词不做模糊匹配，也不改大小写
+    assert_eq!(corrector.correct("is it done", &vocabulary(&["IT"])), "is it done");
+    // 空词表直接返回原文
+    assert_eq!(corrector.correct("cubernetes", &[]), "cubernetes");
+}
+
+#[test]
+fn test_does_not_match_across_punctuation() {
+    let corrector = VocabularyCorrector::default();
+    assert_eq!(corrector.correct("kuber, netes", &vocabulary(&["Kubernetes"])), "kuber, netes");
+    assert_eq!(corrector.correct("kuber netes", &vocabulary(&["Kubernetes"])), "Kubernetes");
+}
+
+#[test]
+fn test_corrects_chinese_homophones_with_pinyin() {
+    let table = pinyin_table(&[
+        ('灵', "ling"), ('零', "ling"), ('果', "guo"), ('锅', "guo"), ('瓜', "gua"),
+        ('打', "da"), ('开', "kai"),
+    ]);
+    let corrector = VocabularyCorrector::new(table, DEFAULT_MAX_EDIT_RATIO);
+
+    // 同音字
+    assert_eq!(corrector.correct("打开零锅", &vocabulary(&["灵果"])), "打开灵果");
+    // 中文读音接近英文产品名
+    assert_eq!(corrector.correct("打开灵瓜", &vocabulary(&["Lingua"])), "打开Lingua");
+
+    // 没有拼音表时无法识别同音字
+    let plain = VocabularyCorrector::default();
+    assert_eq!(plain.correct("打开零锅", &vocabulary(&["灵果"])), "打开零锅");
+}
+
+#[test]
+fn test_pinyin_table_from_lexicon() {
+    let temp_dir = TempDir::new().unwrap();
+    let lexicon = temp_dir.path().join("lexicon.txt");
+    fs::write(&lexicon, "# 注释\n一 ^ i1 #0\n灵 l ing2 #0\n好 hao3\n行 x ing2 #0\n行 h ang2 #0\n\n无效行\n").unwrap();
+
+    let table = PinyinTable::from_lexicon(&lexicon).unwrap();
+    assert_eq!(table.get('一'), Some("i"));
+    assert_eq!(table.get('灵'), Some("ling"));
+    assert_eq!(table.get('好'), Some("hao"));
+    // 多音字取第一个读音
+    assert_eq!(table.get('行'), Some("xing"));
+    assert_eq!(table.len(), 4);
+
+    assert!(PinyinTable::from_lexicon(&temp_dir.path().join("missing.txt")).is_err());
+}
+
+#[test]
+fn test_bundled_pinyin_lexicon() {
+    let lexicon = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("config/pinyin_lexicon.txt");
+    let table = PinyinTable::from_lexicon(&lexicon).unwrap();
+    // GB2312 一、二级汉字
+    assert_eq!(table.len(), 6763);
+    assert_eq!(table.get('灵'), Some("ling"));
+    assert_eq!(table.get('零'), Some("ling"));
+    assert_eq!(table.get('女'), Some("nv"));
+    assert_eq!(table.get('的'), Some("de"));
+
+    let corrector = VocabularyCorrector::new(table, DEFAULT_MAX_EDIT_RATIO);
+    assert_eq!(corrector.correct("打开零锅", &vocabulary(&["灵果"])), "打开灵果");
+}